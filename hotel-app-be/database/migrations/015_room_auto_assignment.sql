@@ -0,0 +1,18 @@
+-- ============================================================================
+-- MIGRATION 015: ROOM AUTO-ASSIGNMENT
+-- ============================================================================
+-- Type-level bookings reserve a room type; `room_id` holds a provisional room
+-- of that type until a concrete room is assigned (at check-in or by the night
+-- audit pre-assignment step). See services::room_assignment.
+
+ALTER TABLE bookings
+    ADD COLUMN IF NOT EXISTS room_type_id BIGINT REFERENCES room_types(id),
+    ADD COLUMN IF NOT EXISTS room_assigned BOOLEAN NOT NULL DEFAULT true;
+
+CREATE INDEX IF NOT EXISTS idx_bookings_unassigned_arrivals
+    ON bookings(check_in_date) WHERE room_assigned = false;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('auto_assign_rooms', 'false', 'boolean', 'booking',
+        'Automatically assign a concrete room to type-level bookings at check-in and during night audit')
+ON CONFLICT (key) DO NOTHING;
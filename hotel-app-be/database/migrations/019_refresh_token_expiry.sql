@@ -0,0 +1,19 @@
+-- ============================================================================
+-- MIGRATION 019: CONFIGURABLE REFRESH-TOKEN EXPIRY
+-- ============================================================================
+-- Refresh tokens record the start of their session so rotation can either
+-- slide the expiry (up to an absolute cap) or keep a fixed session end.
+-- See services::sessions.
+
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS session_started_at TIMESTAMP WITH TIME ZONE;
+UPDATE refresh_tokens SET session_started_at = created_at WHERE session_started_at IS NULL;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('refresh_token_lifetime_days', '30', 'number', 'security',
+     'Days a refresh token stays valid; in fixed mode, the length of the whole session'),
+    ('refresh_token_expiry_mode', 'sliding', 'string', 'security',
+     'sliding: each refresh extends the session up to the maximum lifetime; fixed: sessions end at a hard cap'),
+    ('refresh_token_max_lifetime_days', '90', 'number', 'security',
+     'Absolute session length in sliding mode, counted from login')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,39 @@
+-- ============================================================================
+-- MIGRATION 023: BOOKING ARCHIVE
+-- ============================================================================
+-- Long-past checked-out and cancelled bookings can be moved out of `bookings`
+-- into `bookings_archive`, together with their status history and
+-- modification log, so day-to-day queries only scan live stays. Reports read
+-- `bookings_all`, which unions both tables. `booking_archive_after_days`
+-- sets the retention the night audit applies; 0 leaves archiving off.
+-- See services::booking_archive.
+--
+-- The archive tables copy the live column layout, so a migration that adds a
+-- column to `bookings` must add it to `bookings_archive` (before
+-- `archived_at`) and recreate `bookings_all`.
+
+CREATE TABLE IF NOT EXISTS bookings_archive (LIKE bookings);
+ALTER TABLE bookings_archive
+    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bookings_archive_id ON bookings_archive(id);
+CREATE INDEX IF NOT EXISTS idx_bookings_archive_dates
+    ON bookings_archive(check_in_date, check_out_date);
+CREATE INDEX IF NOT EXISTS idx_bookings_archive_guest ON bookings_archive(guest_id);
+
+CREATE TABLE IF NOT EXISTS booking_history_archive (LIKE booking_history);
+CREATE INDEX IF NOT EXISTS idx_booking_history_archive_booking
+    ON booking_history_archive(booking_id);
+
+CREATE TABLE IF NOT EXISTS booking_modifications_archive (LIKE booking_modifications);
+CREATE INDEX IF NOT EXISTS idx_booking_modifications_archive_booking
+    ON booking_modifications_archive(booking_id);
+
+CREATE OR REPLACE VIEW bookings_all AS
+    SELECT b.*, NULL::TIMESTAMP WITH TIME ZONE AS archived_at FROM bookings b
+    UNION ALL
+    SELECT * FROM bookings_archive;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('booking_archive_after_days', '0', 'number', 'night_audit',
+        'Night audit archives checked-out and cancelled bookings this many days after check-out (0 disables)')
+ON CONFLICT (key) DO NOTHING;
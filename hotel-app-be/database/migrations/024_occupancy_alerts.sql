@@ -0,0 +1,32 @@
+-- ============================================================================
+-- MIGRATION 024: OCCUPANCY ALERTS
+-- ============================================================================
+-- When enabled, the night audit forecasts occupancy for the coming days and
+-- raises an `occupancy.threshold_crossed` outbox event whenever a date moves
+-- above the high or below the low threshold. `occupancy_alerts` keeps each
+-- change of level per date, so a date is only alerted again after it has
+-- gone back to normal. See services::occupancy_alerts.
+
+CREATE TABLE IF NOT EXISTS occupancy_alerts (
+    id BIGSERIAL PRIMARY KEY,
+    stay_date DATE NOT NULL,
+    level VARCHAR(10) NOT NULL CHECK (level IN ('low', 'normal', 'high')),
+    occupancy_pct DECIMAL(5,2) NOT NULL,
+    rooms_sold INTEGER NOT NULL,
+    rooms_available INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_occupancy_alerts_date ON occupancy_alerts(stay_date, id);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('occupancy_alerts_enabled', 'false', 'boolean', 'night_audit',
+     'Night audit raises an event when forecast occupancy crosses the high or low threshold'),
+    ('occupancy_alert_high_pct', '90', 'number', 'night_audit',
+     'Forecast occupancy (%) at or above which a date is alerted as high'),
+    ('occupancy_alert_low_pct', '30', 'number', 'night_audit',
+     'Forecast occupancy (%) at or below which a date is alerted as low'),
+    ('occupancy_alert_horizon_days', '14', 'number', 'night_audit',
+     'How many days ahead the night audit checks forecast occupancy')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,19 @@
+-- ============================================================================
+-- MIGRATION 026: REVIEW POINTS
+-- ============================================================================
+-- When enabled, a verified guest review earns the guest's membership a
+-- configurable points bonus, recorded as an `earn` transaction referencing
+-- the reviewed booking. The unique index allows one review award per
+-- booking, so edited or resubmitted reviews don't pay out again. See
+-- services::review_points.
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_points_transactions_review_once
+    ON points_transactions(reference_id) WHERE reference_type = 'review';
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('review_points_enabled', 'false', 'boolean', 'loyalty',
+     'Award loyalty points for a verified guest review'),
+    ('review_points_bonus', '100', 'number', 'loyalty',
+     'Points awarded once per booking for a verified review')
+ON CONFLICT (key) DO NOTHING;
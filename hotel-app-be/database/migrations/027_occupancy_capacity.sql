@@ -0,0 +1,11 @@
+-- ============================================================================
+-- MIGRATION 027: BOOKING CAPACITY
+-- ============================================================================
+-- Bookings are checked against the room type's max_occupancy: adults and
+-- children always count, infants only when this setting is on. See
+-- services::booking::check_guest_capacity.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('occupancy_count_infants', 'false', 'boolean', 'booking',
+        'Count infants towards a room''s maximum occupancy')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- MIGRATION 028: FOLIO SPLIT
+-- ============================================================================
+-- Routes a booking's charge categories (room, extra_bed, late_checkout,
+-- tourism_tax, services) to the guest folio or the company's city ledger.
+-- Categories without a row stay on the guest folio. Kept out of `bookings`
+-- so the archive layout is unaffected. See services::folio_split.
+
+CREATE TABLE IF NOT EXISTS booking_folio_routes (
+    booking_id BIGINT NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    charge_category VARCHAR(30) NOT NULL
+        CHECK (charge_category IN ('room', 'extra_bed', 'late_checkout', 'tourism_tax', 'services')),
+    payer VARCHAR(20) NOT NULL CHECK (payer IN ('guest', 'company')),
+    updated_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (booking_id, charge_category)
+);
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- MIGRATION 031: GUEST COMMUNICATION PREFERENCES
+-- ============================================================================
+-- Per-guest consent to email and SMS. NULL means the guest has not chosen,
+-- and the matching *_opt_in_default setting applies. Guest messages are only
+-- queued for guests who consent; see services::communication.
+
+ALTER TABLE guests ADD COLUMN IF NOT EXISTS email_opt_in BOOLEAN;
+ALTER TABLE guests ADD COLUMN IF NOT EXISTS sms_opt_in BOOLEAN;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('guest_email_opt_in_default', 'true', 'boolean', 'guests',
+     'Whether guests who have not set a preference receive email'),
+    ('guest_sms_opt_in_default', 'false', 'boolean', 'guests',
+     'Whether guests who have not set a preference receive SMS')
+ON CONFLICT (key) DO NOTHING;
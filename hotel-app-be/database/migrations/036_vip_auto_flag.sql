@@ -0,0 +1,23 @@
+-- ============================================================================
+-- MIGRATION 036: AUTOMATIC VIP FLAGGING
+-- ============================================================================
+-- `is_vip` marks guests staff should prioritise. The night audit sets it for
+-- guests whose checked-out stays reach `vip_lifetime_nights` nights or
+-- `vip_lifetime_spend` in booking totals, when `vip_auto_flag_enabled` is
+-- on. See services::vip. Guests with a `vip_status` are VIP already.
+
+ALTER TABLE guests ADD COLUMN IF NOT EXISTS is_vip BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE guests SET is_vip = true WHERE COALESCE(vip_status, '') <> '';
+
+CREATE INDEX IF NOT EXISTS idx_guests_is_vip ON guests(is_vip) WHERE is_vip;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('vip_auto_flag_enabled', 'false', 'boolean', 'guests',
+     'Flag guests as VIP from their stay history during the night audit'),
+    ('vip_lifetime_nights', '30', 'number', 'guests',
+     'Checked-out nights that make a guest VIP (0 turns this criterion off)'),
+    ('vip_lifetime_spend', '10000', 'number', 'guests',
+     'Lifetime booking spend that makes a guest VIP (0 turns this criterion off)')
+ON CONFLICT (key) DO NOTHING;
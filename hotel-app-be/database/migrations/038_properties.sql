@@ -0,0 +1,66 @@
+-- ============================================================================
+-- MIGRATION 038: PROPERTIES
+-- ============================================================================
+-- One install can run several properties. Rooms and bookings carry a
+-- `property_id`; a booking always belongs to its room's property, which a
+-- trigger keeps in step. Staff are given properties in `user_properties`;
+-- a user with none works in property 1, seeded here for existing installs.
+-- See core::property_scope.
+
+CREATE TABLE IF NOT EXISTS properties (
+    id BIGSERIAL PRIMARY KEY,
+    code VARCHAR(20) UNIQUE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO properties (id, code, name)
+VALUES (1, 'MAIN', COALESCE(
+    (SELECT value FROM system_settings WHERE key = 'hotel_name'), 'Main Property'))
+ON CONFLICT (id) DO NOTHING;
+SELECT setval(pg_get_serial_sequence('properties', 'id'), (SELECT MAX(id) FROM properties));
+
+CREATE TABLE IF NOT EXISTS user_properties (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    property_id BIGINT NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    is_default BOOLEAN NOT NULL DEFAULT false,
+    PRIMARY KEY (user_id, property_id)
+);
+
+ALTER TABLE rooms
+    ADD COLUMN IF NOT EXISTS property_id BIGINT NOT NULL DEFAULT 1 REFERENCES properties(id);
+CREATE INDEX IF NOT EXISTS idx_rooms_property ON rooms(property_id);
+
+ALTER TABLE bookings
+    ADD COLUMN IF NOT EXISTS property_id BIGINT NOT NULL DEFAULT 1 REFERENCES properties(id);
+CREATE INDEX IF NOT EXISTS idx_bookings_property ON bookings(property_id);
+
+CREATE OR REPLACE FUNCTION set_booking_property()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.property_id = COALESCE(
+        (SELECT property_id FROM rooms WHERE id = NEW.room_id), NEW.property_id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS set_bookings_property ON bookings;
+CREATE TRIGGER set_bookings_property
+    BEFORE INSERT OR UPDATE OF room_id ON bookings
+    FOR EACH ROW EXECUTE FUNCTION set_booking_property();
+
+-- The archive keeps the live column layout with `archived_at` last, so the
+-- new column goes in before it (see migration 023).
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN IF NOT EXISTS property_id BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive
+    ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE OR REPLACE VIEW bookings_all AS
+    SELECT b.*, NULL::TIMESTAMP WITH TIME ZONE AS archived_at FROM bookings b
+    UNION ALL
+    SELECT * FROM bookings_archive;
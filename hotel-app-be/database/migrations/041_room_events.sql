@@ -0,0 +1,21 @@
+-- ============================================================================
+-- MIGRATION 041: ROOM EVENTS
+-- ============================================================================
+-- Room status changes, check-ins and check-outs log an event per room, shown
+-- on the room's detail view. The table was queried and written to but never
+-- created, so events were silently dropped.
+
+CREATE TABLE IF NOT EXISTS room_events (
+    id BIGSERIAL PRIMARY KEY,
+    room_id BIGINT NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    status VARCHAR(30),
+    priority VARCHAR(20) DEFAULT 'normal',
+    notes TEXT,
+    scheduled_date TIMESTAMP WITH TIME ZONE,
+    created_by BIGINT REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_events_room ON room_events(room_id, created_at DESC);
@@ -0,0 +1,24 @@
+-- ============================================================================
+-- MIGRATION 042: GROUP BOOKINGS
+-- ============================================================================
+-- Rooms booked together in one request share a `group_booking_number`, so
+-- the group can be confirmed and looked up as one. Single-room bookings leave
+-- it NULL.
+
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS group_booking_number VARCHAR(60);
+CREATE INDEX IF NOT EXISTS idx_bookings_group_booking_number
+    ON bookings (group_booking_number) WHERE group_booking_number IS NOT NULL;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN IF NOT EXISTS group_booking_number VARCHAR(60);
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive
+    ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE OR REPLACE VIEW bookings_all AS
+    SELECT b.*, NULL::TIMESTAMP WITH TIME ZONE AS archived_at FROM bookings b
+    UNION ALL
+    SELECT * FROM bookings_archive;
@@ -0,0 +1,29 @@
+-- ============================================================================
+-- MIGRATION 044: CANCELLATION AND NO-SHOW FEES
+-- ============================================================================
+-- Late cancellation and no-show fees are charged once per booking and fee
+-- type, recorded here for the revenue and payment-status reports and posted
+-- to the customer ledger (`ledger_id`). No-shows are charged
+-- `no_show_fee_nights` nights of the stay. See services::booking_fees.
+
+CREATE TABLE IF NOT EXISTS booking_fees (
+    id BIGSERIAL PRIMARY KEY,
+    booking_id BIGINT NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    fee_type VARCHAR(20) NOT NULL CHECK (fee_type IN ('cancellation', 'no_show')),
+    amount DECIMAL(12,2) NOT NULL CHECK (amount > 0),
+    billed_to VARCHAR(20) NOT NULL DEFAULT 'guest' CHECK (billed_to IN ('guest', 'company')),
+    company_name VARCHAR(255),
+    ledger_id BIGINT REFERENCES customer_ledgers(id) ON DELETE SET NULL,
+    fee_date DATE NOT NULL,
+    charged_by BIGINT REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (booking_id, fee_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_fees_fee_date ON booking_fees(fee_date);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('no_show_fee_nights', '1', 'number', 'booking',
+     'Nights of the stay charged when a guest does not arrive; 0 charges nothing')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,15 @@
+-- ============================================================================
+-- MIGRATION 047: MANUAL POINTS ADJUSTMENT AUDIT
+-- ============================================================================
+-- Staff points additions and redemptions need `loyalty:manage`, record the
+-- acting user in `performed_by` and are capped per adjustment by
+-- `loyalty_max_points_adjustment`. See services::loyalty.
+
+ALTER TABLE points_transactions
+    ADD COLUMN IF NOT EXISTS performed_by BIGINT REFERENCES users(id) ON DELETE SET NULL;
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('loyalty_max_points_adjustment', '100000', 'number', 'loyalty',
+     'Largest number of points staff may add or redeem in one adjustment')
+ON CONFLICT (key) DO NOTHING;
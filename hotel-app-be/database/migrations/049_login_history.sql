@@ -0,0 +1,19 @@
+-- ============================================================================
+-- MIGRATION 049: LOGIN HISTORY
+-- ============================================================================
+-- Every password and passkey login for an existing account is recorded with
+-- its outcome, client IP and user agent, so users can review their recent
+-- logins at GET /auth/login-history. Attempts on unknown usernames are not
+-- recorded. See services::login_history.
+
+CREATE TABLE IF NOT EXISTS login_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    method VARCHAR(20) NOT NULL CHECK (method IN ('password', 'passkey')),
+    outcome VARCHAR(20) NOT NULL CHECK (outcome IN ('success', 'failure', 'rate_limited')),
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_history_user ON login_history(user_id, created_at DESC);
@@ -0,0 +1,16 @@
+-- ============================================================================
+-- MIGRATION 053: CHECK-IN/CHECK-OUT TIMES AND LATE-CHECKOUT FEE
+-- ============================================================================
+-- The standard times were only in the seed data; installs without it now
+-- get them too. A guest in house past the check-out time on their
+-- departure day is charged late_checkout_fee_per_hour per hour or part
+-- hour, up to one night, as the booking's late_checkout_penalty. See
+-- services::stay_policy.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('check_in_time', '15:00', 'string', 'general', 'Standard check-in time'),
+    ('check_out_time', '11:00', 'string', 'general', 'Standard check-out time'),
+    ('late_checkout_fee_per_hour', '0', 'number', 'booking',
+     'Charged per hour or part hour a guest stays past the check-out time, up to one night; 0 charges nothing')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,24 @@
+-- ============================================================================
+-- MIGRATION 054: ROOM BLOCKS
+-- ============================================================================
+-- Holds that keep a room off sale for a date range for reasons other than
+-- maintenance or cleaning: owner use, staff housing, renovation staging.
+-- They are kept apart from rooms.status, which describes the room today. A
+-- block covers the nights start_date up to, but not including, end_date,
+-- the way a stay does. See services::room_blocks.
+
+CREATE TABLE IF NOT EXISTS room_blocks (
+    id BIGSERIAL PRIMARY KEY,
+    room_id BIGINT NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    reason VARCHAR(30) NOT NULL
+        CHECK (reason IN ('owner_use', 'staff_housing', 'renovation', 'other')),
+    notes TEXT,
+    created_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (end_date > start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_blocks_room_dates
+    ON room_blocks(room_id, start_date, end_date);
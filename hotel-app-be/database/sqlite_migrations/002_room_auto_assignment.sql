@@ -0,0 +1,11 @@
+-- Room auto-assignment for type-level bookings (mirrors PostgreSQL migration 015).
+-- `bookings.room_type_id` already exists in the SQLite schema.
+
+ALTER TABLE bookings ADD COLUMN room_assigned INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_bookings_unassigned_arrivals
+    ON bookings(check_in_date) WHERE room_assigned = 0;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('auto_assign_rooms', 'false', 'boolean', 'booking',
+        'Automatically assign a concrete room to type-level bookings at check-in and during night audit');
@@ -0,0 +1,15 @@
+-- Configurable booking number format (mirrors PostgreSQL migration 016).
+
+CREATE TABLE IF NOT EXISTS booking_number_sequences (
+    scope TEXT PRIMARY KEY,
+    last_value INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('booking_number_format', '', 'string', 'booking',
+     'Booking number template using {year}, {month}, {day}, {date}, {property} and {seq} or {seq:N}; empty keeps the default random format'),
+    ('booking_number_check_digit', 'false', 'boolean', 'booking',
+     'Append a Luhn check digit to templated booking numbers'),
+    ('property_code', '', 'string', 'general',
+     'Short property code (letters/digits, max 10) used by the {property} booking number token');
@@ -0,0 +1,8 @@
+-- Automatic release of cleaned rooms (mirrors PostgreSQL migration 017).
+
+CREATE INDEX IF NOT EXISTS idx_rooms_cleaning_end_date
+    ON rooms(cleaning_end_date) WHERE status IN ('cleaning', 'dirty');
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('auto_release_cleaned_rooms', 'false', 'boolean', 'housekeeping',
+        'Return cleaning/dirty rooms to available once their cleaning end date passes and no guest is in house');
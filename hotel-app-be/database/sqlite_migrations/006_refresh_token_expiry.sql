@@ -0,0 +1,13 @@
+-- Configurable refresh-token expiry (mirrors PostgreSQL migration 019).
+
+ALTER TABLE refresh_tokens ADD COLUMN session_started_at TEXT;
+UPDATE refresh_tokens SET session_started_at = created_at WHERE session_started_at IS NULL;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('refresh_token_lifetime_days', '30', 'number', 'security',
+     'Days a refresh token stays valid; in fixed mode, the length of the whole session'),
+    ('refresh_token_expiry_mode', 'sliding', 'string', 'security',
+     'sliding: each refresh extends the session up to the maximum lifetime; fixed: sessions end at a hard cap'),
+    ('refresh_token_max_lifetime_days', '90', 'number', 'security',
+     'Absolute session length in sliding mode, counted from login');
@@ -0,0 +1,18 @@
+-- No-show deposit forfeiture (mirrors PostgreSQL migration 022).
+
+CREATE TABLE IF NOT EXISTS deposit_forfeitures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    booking_id INTEGER NOT NULL UNIQUE REFERENCES bookings(id) ON DELETE CASCADE,
+    amount REAL NOT NULL CHECK (amount > 0),
+    category TEXT NOT NULL DEFAULT 'no_show_forfeit',
+    audit_date TEXT NOT NULL,
+    forfeited_by INTEGER REFERENCES users(id),
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_deposit_forfeitures_audit_date
+    ON deposit_forfeitures(audit_date);
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('forfeit_deposit_on_no_show', 'false', 'boolean', 'night_audit',
+        'Night audit forfeits the deposit on no-show bookings to revenue instead of leaving it refundable');
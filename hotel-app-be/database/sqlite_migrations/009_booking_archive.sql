@@ -0,0 +1,27 @@
+-- Booking archive (mirrors PostgreSQL migration 023).
+
+CREATE TABLE IF NOT EXISTS bookings_archive AS
+    SELECT *, datetime('now') AS archived_at FROM bookings WHERE 0;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bookings_archive_id ON bookings_archive(id);
+CREATE INDEX IF NOT EXISTS idx_bookings_archive_dates
+    ON bookings_archive(check_in_date, check_out_date);
+CREATE INDEX IF NOT EXISTS idx_bookings_archive_guest ON bookings_archive(guest_id);
+
+CREATE TABLE IF NOT EXISTS booking_history_archive AS
+    SELECT * FROM booking_history WHERE 0;
+CREATE INDEX IF NOT EXISTS idx_booking_history_archive_booking
+    ON booking_history_archive(booking_id);
+
+CREATE TABLE IF NOT EXISTS booking_modifications_archive AS
+    SELECT * FROM booking_modifications WHERE 0;
+CREATE INDEX IF NOT EXISTS idx_booking_modifications_archive_booking
+    ON booking_modifications_archive(booking_id);
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('booking_archive_after_days', '0', 'number', 'night_audit',
+        'Night audit archives checked-out and cancelled bookings this many days after check-out (0 disables)');
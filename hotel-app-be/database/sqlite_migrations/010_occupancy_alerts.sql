@@ -0,0 +1,24 @@
+-- Occupancy alerts (mirrors PostgreSQL migration 024).
+
+CREATE TABLE IF NOT EXISTS occupancy_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stay_date TEXT NOT NULL,
+    level TEXT NOT NULL CHECK (level IN ('low', 'normal', 'high')),
+    occupancy_pct REAL NOT NULL,
+    rooms_sold INTEGER NOT NULL,
+    rooms_available INTEGER NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_occupancy_alerts_date ON occupancy_alerts(stay_date, id);
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('occupancy_alerts_enabled', 'false', 'boolean', 'night_audit',
+     'Night audit raises an event when forecast occupancy crosses the high or low threshold'),
+    ('occupancy_alert_high_pct', '90', 'number', 'night_audit',
+     'Forecast occupancy (%) at or above which a date is alerted as high'),
+    ('occupancy_alert_low_pct', '30', 'number', 'night_audit',
+     'Forecast occupancy (%) at or below which a date is alerted as low'),
+    ('occupancy_alert_horizon_days', '14', 'number', 'night_audit',
+     'How many days ahead the night audit checks forecast occupancy');
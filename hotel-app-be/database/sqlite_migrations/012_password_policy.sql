@@ -0,0 +1,20 @@
+-- Password policy settings (mirrors PostgreSQL migration 025).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('password_min_length', '8', 'number', 'security',
+     'Minimum password length'),
+    ('password_max_length', '128', 'number', 'security',
+     'Maximum password length'),
+    ('password_require_uppercase', 'true', 'boolean', 'security',
+     'Passwords must contain an uppercase letter'),
+    ('password_require_lowercase', 'true', 'boolean', 'security',
+     'Passwords must contain a lowercase letter'),
+    ('password_require_digit', 'true', 'boolean', 'security',
+     'Passwords must contain a digit'),
+    ('password_require_symbol', 'true', 'boolean', 'security',
+     'Passwords must contain a special character'),
+    ('password_breached_check', 'false', 'boolean', 'security',
+     'Reject passwords found in the breached-password list'),
+    ('password_breached_list_path', '', 'string', 'security',
+     'Path to a local file of breached passwords, one per line');
@@ -0,0 +1,5 @@
+-- Booking capacity setting (mirrors PostgreSQL migration 027).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('occupancy_count_infants', 'false', 'boolean', 'booking',
+        'Count infants towards a room''s maximum occupancy');
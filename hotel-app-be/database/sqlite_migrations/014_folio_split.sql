@@ -0,0 +1,11 @@
+-- Folio split routes per booking (mirrors PostgreSQL migration 028).
+
+CREATE TABLE IF NOT EXISTS booking_folio_routes (
+    booking_id INTEGER NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    charge_category TEXT NOT NULL
+        CHECK (charge_category IN ('room', 'extra_bed', 'late_checkout', 'tourism_tax', 'services')),
+    payer TEXT NOT NULL CHECK (payer IN ('guest', 'company')),
+    updated_by INTEGER REFERENCES users(id),
+    updated_at TEXT DEFAULT (datetime('now')),
+    PRIMARY KEY (booking_id, charge_category)
+);
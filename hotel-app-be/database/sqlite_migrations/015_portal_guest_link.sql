@@ -0,0 +1,12 @@
+-- Portal guest link setting and backfill (mirrors PostgreSQL migration 029).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('portal_auto_create_guest', 'true', 'boolean', 'guests',
+        'Create and link a guest profile when a portal user signs up');
+
+INSERT OR IGNORE INTO user_guests
+    (user_id, guest_id, relationship_type, can_book_for, can_view_bookings, can_modify, linked_by)
+SELECT u.id, u.guest_id, 'self', 1, 1, 1, u.id
+FROM users u
+JOIN guests g ON g.id = u.guest_id
+WHERE u.user_type = 'guest';
@@ -0,0 +1,10 @@
+-- External call retry and circuit breaker settings (mirrors PostgreSQL migration 030).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('external_call_max_retries', '2', 'number', 'integrations',
+     'Times a failed outbound call is retried before giving up (0-5)'),
+    ('external_call_failure_threshold', '5', 'number', 'integrations',
+     'Consecutive failures that open an endpoint''s circuit breaker (1-100)'),
+    ('external_call_cooldown_secs', '60', 'number', 'integrations',
+     'Seconds an open circuit breaker skips calls before probing again (1-3600)');
@@ -0,0 +1,12 @@
+-- Guest communication preferences (mirrors PostgreSQL migration 031).
+-- NULL means the guest has not chosen and the *_opt_in_default setting applies.
+
+ALTER TABLE guests ADD COLUMN email_opt_in INTEGER;
+ALTER TABLE guests ADD COLUMN sms_opt_in INTEGER;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('guest_email_opt_in_default', 'true', 'boolean', 'guests',
+     'Whether guests who have not set a preference receive email'),
+    ('guest_sms_opt_in_default', 'false', 'boolean', 'guests',
+     'Whether guests who have not set a preference receive SMS');
@@ -0,0 +1,8 @@
+-- Orphaned upload cleanup (mirrors PostgreSQL migration 032).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('upload_cleanup_enabled', 'false', 'boolean', 'maintenance',
+     'Night audit removes uploaded files that no record refers to'),
+    ('upload_cleanup_grace_hours', '24', 'number', 'maintenance',
+     'Unreferenced uploads newer than this many hours are kept');
@@ -0,0 +1,5 @@
+-- Day-use rate (mirrors PostgreSQL migration 033).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('day_use_rate_percent', '100', 'number', 'booking',
+        'Percentage of the nightly rate charged for same-day (day-use) bookings');
@@ -0,0 +1,3 @@
+-- Room notes (mirrors PostgreSQL migration 006).
+
+ALTER TABLE rooms ADD COLUMN notes TEXT;
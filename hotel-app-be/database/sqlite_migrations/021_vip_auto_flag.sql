@@ -0,0 +1,11 @@
+-- Automatic VIP flagging settings (mirrors PostgreSQL migration 036).
+-- guests.is_vip is part of the initial SQLite schema.
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('vip_auto_flag_enabled', 'false', 'boolean', 'guests',
+     'Flag guests as VIP from their stay history during the night audit'),
+    ('vip_lifetime_nights', '30', 'number', 'guests',
+     'Checked-out nights that make a guest VIP (0 turns this criterion off)'),
+    ('vip_lifetime_spend', '10000', 'number', 'guests',
+     'Lifetime booking spend that makes a guest VIP (0 turns this criterion off)');
@@ -0,0 +1,17 @@
+-- Login attempt lockout (mirrors PostgreSQL migration 037).
+
+CREATE TABLE IF NOT EXISTS login_attempts (
+    username TEXT NOT NULL,
+    ip_address TEXT NOT NULL,
+    failed_count INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TEXT,
+    locked_until TEXT,
+    PRIMARY KEY (username, ip_address)
+);
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('max_login_attempts', '5', 'number', 'security',
+     'Maximum failed login attempts before lockout'),
+    ('login_lockout_minutes', '30', 'number', 'security',
+     'Minutes a username is locked out from an IP after too many failed logins');
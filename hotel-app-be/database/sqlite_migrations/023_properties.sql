@@ -0,0 +1,57 @@
+-- Properties (mirrors PostgreSQL migration 038).
+-- SQLite cannot add a column with both REFERENCES and a non-NULL default, so
+-- the property_id columns are plain integers here.
+
+CREATE TABLE IF NOT EXISTS properties (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT UNIQUE NOT NULL,
+    name TEXT NOT NULL,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO properties (id, code, name)
+VALUES (1, 'MAIN', COALESCE(
+    (SELECT value FROM system_settings WHERE key = 'hotel_name'), 'Main Property'));
+
+CREATE TABLE IF NOT EXISTS user_properties (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    property_id INTEGER NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    is_default INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, property_id)
+);
+
+ALTER TABLE rooms ADD COLUMN property_id INTEGER NOT NULL DEFAULT 1;
+CREATE INDEX IF NOT EXISTS idx_rooms_property ON rooms(property_id);
+
+ALTER TABLE bookings ADD COLUMN property_id INTEGER NOT NULL DEFAULT 1;
+CREATE INDEX IF NOT EXISTS idx_bookings_property ON bookings(property_id);
+
+CREATE TRIGGER IF NOT EXISTS set_bookings_property_on_insert
+AFTER INSERT ON bookings
+BEGIN
+    UPDATE bookings
+    SET property_id = COALESCE((SELECT property_id FROM rooms WHERE id = NEW.room_id), property_id)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS set_bookings_property_on_room_change
+AFTER UPDATE OF room_id ON bookings
+BEGIN
+    UPDATE bookings
+    SET property_id = COALESCE((SELECT property_id FROM rooms WHERE id = NEW.room_id), property_id)
+    WHERE id = NEW.id;
+END;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN property_id INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive ADD COLUMN archived_at TEXT;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
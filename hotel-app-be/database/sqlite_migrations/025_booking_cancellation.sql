@@ -0,0 +1,25 @@
+-- Booking cancellation policy (mirrors PostgreSQL migration 040).
+-- SQLite's bookings table has no status CHECK, so only the fee column it
+-- was missing is added.
+
+ALTER TABLE bookings ADD COLUMN cancellation_fee REAL;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN cancellation_fee REAL;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive ADD COLUMN archived_at TEXT;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('cancellation_free_days', '2', 'number', 'booking',
+     'Bookings cancelled more than this many days before check-in are not charged'),
+    ('cancellation_fee_percent', '50', 'number', 'booking',
+     'Percentage of the booking total charged for later cancellations');
@@ -0,0 +1,31 @@
+-- Room history and events (mirrors PostgreSQL migrations 006 and 041).
+
+CREATE TABLE IF NOT EXISTS room_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room_id INTEGER NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    notes TEXT,
+    start_date TEXT,
+    end_date TEXT,
+    changed_by INTEGER REFERENCES users(id),
+    is_auto_generated INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_history_room ON room_history(room_id);
+
+CREATE TABLE IF NOT EXISTS room_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room_id INTEGER NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    status TEXT,
+    priority TEXT DEFAULT 'normal',
+    notes TEXT,
+    scheduled_date TEXT,
+    created_by INTEGER REFERENCES users(id),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_events_room ON room_events(room_id, created_at);
@@ -0,0 +1,50 @@
+-- Booking pricing and billing columns (mirrors the bookings table in
+-- PostgreSQL migration 008). Booking modifications write these, so they are
+-- needed to change a stay's dates or room. company_id is a plain integer here.
+
+ALTER TABLE bookings ADD COLUMN room_rate REAL;
+ALTER TABLE bookings ADD COLUMN subtotal REAL;
+ALTER TABLE bookings ADD COLUMN daily_rates TEXT;
+ALTER TABLE bookings ADD COLUMN rate_override_weekday REAL;
+ALTER TABLE bookings ADD COLUMN rate_override_weekend REAL;
+ALTER TABLE bookings ADD COLUMN deposit_paid INTEGER DEFAULT 0;
+ALTER TABLE bookings ADD COLUMN deposit_paid_at TEXT;
+ALTER TABLE bookings ADD COLUMN company_id INTEGER;
+ALTER TABLE bookings ADD COLUMN company_name TEXT;
+ALTER TABLE bookings ADD COLUMN payment_note TEXT;
+ALTER TABLE bookings ADD COLUMN remarks TEXT;
+ALTER TABLE bookings ADD COLUMN is_tourist INTEGER DEFAULT 0;
+ALTER TABLE bookings ADD COLUMN tourism_tax_amount REAL DEFAULT 0;
+ALTER TABLE bookings ADD COLUMN extra_bed_count INTEGER DEFAULT 0;
+ALTER TABLE bookings ADD COLUMN extra_bed_charge REAL DEFAULT 0;
+
+-- Prices are tax-inclusive, so existing stays are priced at their total.
+UPDATE bookings SET room_rate = rate_per_night, subtotal = total_amount;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN room_rate REAL;
+ALTER TABLE bookings_archive ADD COLUMN subtotal REAL;
+ALTER TABLE bookings_archive ADD COLUMN daily_rates TEXT;
+ALTER TABLE bookings_archive ADD COLUMN rate_override_weekday REAL;
+ALTER TABLE bookings_archive ADD COLUMN rate_override_weekend REAL;
+ALTER TABLE bookings_archive ADD COLUMN deposit_paid INTEGER DEFAULT 0;
+ALTER TABLE bookings_archive ADD COLUMN deposit_paid_at TEXT;
+ALTER TABLE bookings_archive ADD COLUMN company_id INTEGER;
+ALTER TABLE bookings_archive ADD COLUMN company_name TEXT;
+ALTER TABLE bookings_archive ADD COLUMN payment_note TEXT;
+ALTER TABLE bookings_archive ADD COLUMN remarks TEXT;
+ALTER TABLE bookings_archive ADD COLUMN is_tourist INTEGER DEFAULT 0;
+ALTER TABLE bookings_archive ADD COLUMN tourism_tax_amount REAL DEFAULT 0;
+ALTER TABLE bookings_archive ADD COLUMN extra_bed_count INTEGER DEFAULT 0;
+ALTER TABLE bookings_archive ADD COLUMN extra_bed_charge REAL DEFAULT 0;
+UPDATE bookings_archive SET room_rate = rate_per_night, subtotal = total_amount;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive ADD COLUMN archived_at TEXT;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
@@ -0,0 +1,18 @@
+-- Group bookings (mirrors PostgreSQL migration 042).
+
+ALTER TABLE bookings ADD COLUMN group_booking_number TEXT;
+CREATE INDEX IF NOT EXISTS idx_bookings_group_booking_number
+    ON bookings (group_booking_number) WHERE group_booking_number IS NOT NULL;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN group_booking_number TEXT;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive ADD COLUMN archived_at TEXT;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
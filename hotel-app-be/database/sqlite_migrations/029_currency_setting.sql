@@ -0,0 +1,5 @@
+-- Hotel currency (mirrors PostgreSQL migration 043).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('currency', 'USD', 'string', 'general', 'Default currency code');
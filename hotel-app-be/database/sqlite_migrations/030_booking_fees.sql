@@ -0,0 +1,24 @@
+-- Cancellation and no-show fees (mirrors PostgreSQL migration 044).
+-- Fees are not posted to SQLite's customer_ledgers, so ledger_id stays
+-- empty here.
+
+CREATE TABLE IF NOT EXISTS booking_fees (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    booking_id INTEGER NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    fee_type TEXT NOT NULL CHECK (fee_type IN ('cancellation', 'no_show')),
+    amount REAL NOT NULL CHECK (amount > 0),
+    billed_to TEXT NOT NULL DEFAULT 'guest' CHECK (billed_to IN ('guest', 'company')),
+    company_name TEXT,
+    ledger_id INTEGER,
+    fee_date TEXT NOT NULL,
+    charged_by INTEGER REFERENCES users(id),
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE (booking_id, fee_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_fees_fee_date ON booking_fees(fee_date);
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('no_show_fee_nights', '1', 'number', 'booking',
+     'Nights of the stay charged when a guest does not arrive; 0 charges nothing');
@@ -0,0 +1,3 @@
+-- Avatar thumbnails (mirrors PostgreSQL migration 048).
+
+ALTER TABLE users ADD COLUMN avatar_thumbnail_url TEXT;
@@ -0,0 +1,13 @@
+-- Login history (mirrors PostgreSQL migration 049).
+
+CREATE TABLE IF NOT EXISTS login_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    method TEXT NOT NULL CHECK (method IN ('password', 'passkey')),
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failure', 'rate_limited')),
+    ip_address TEXT,
+    user_agent TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_history_user ON login_history(user_id, created_at);
@@ -0,0 +1,3 @@
+-- Forced password change (mirrors PostgreSQL migration 050).
+
+ALTER TABLE users ADD COLUMN must_change_password INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+-- Guest marketing consent and consent history (mirrors PostgreSQL migration 051).
+
+ALTER TABLE guests ADD COLUMN marketing_opt_in INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE guests ADD COLUMN consent_timestamp TEXT;
+
+CREATE TABLE IF NOT EXISTS guest_consent_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guest_id INTEGER NOT NULL REFERENCES guests(id) ON DELETE CASCADE,
+    channel TEXT NOT NULL CHECK (channel IN ('email', 'sms', 'marketing')),
+    opted_in INTEGER NOT NULL,
+    changed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_guest_consent_history_guest
+    ON guest_consent_history(guest_id, created_at);
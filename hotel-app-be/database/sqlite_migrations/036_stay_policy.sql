@@ -0,0 +1,25 @@
+-- Check-in/check-out times and late-checkout fee (mirrors PostgreSQL
+-- migration 053). The SQLite bookings table also gains the late-checkout
+-- penalty the fee is kept in.
+
+ALTER TABLE bookings ADD COLUMN late_checkout_penalty REAL DEFAULT 0;
+
+-- Keep archived_at as the archive's last column.
+DROP VIEW IF EXISTS bookings_all;
+ALTER TABLE bookings_archive ADD COLUMN late_checkout_penalty REAL DEFAULT 0;
+ALTER TABLE bookings_archive RENAME COLUMN archived_at TO archived_at_old;
+ALTER TABLE bookings_archive ADD COLUMN archived_at TEXT;
+UPDATE bookings_archive SET archived_at = archived_at_old;
+ALTER TABLE bookings_archive DROP COLUMN archived_at_old;
+
+CREATE VIEW IF NOT EXISTS bookings_all AS
+    SELECT *, NULL AS archived_at FROM bookings
+    UNION ALL
+    SELECT * FROM bookings_archive;
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('check_in_time', '15:00', 'string', 'general', 'Standard check-in time'),
+    ('check_out_time', '11:00', 'string', 'general', 'Standard check-out time'),
+    ('late_checkout_fee_per_hour', '0', 'number', 'booking',
+     'Charged per hour or part hour a guest stays past the check-out time, up to one night; 0 charges nothing');
@@ -0,0 +1,17 @@
+-- Room blocks (mirrors PostgreSQL migration 054).
+
+CREATE TABLE IF NOT EXISTS room_blocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room_id INTEGER NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    reason TEXT NOT NULL
+        CHECK (reason IN ('owner_use', 'staff_housing', 'renovation', 'other')),
+    notes TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    CHECK (end_date > start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_blocks_room_dates
+    ON room_blocks(room_id, start_date, end_date);
@@ -0,0 +1,12 @@
+-- Booking sources (mirrors PostgreSQL migration 056).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('booking_sources',
+     '["walk_in","direct","phone","website","mobile","online","agent","corporate","booking_com","expedia","agoda"]',
+     'json', 'booking', 'Channels a booking may come from'),
+    ('default_booking_source', 'walk_in', 'string', 'booking',
+     'Source given to bookings made without one');
+
+UPDATE bookings SET source = 'walk_in' WHERE source IS NULL OR TRIM(source) = '';
+UPDATE bookings_archive SET source = 'walk_in' WHERE source IS NULL OR TRIM(source) = '';
@@ -0,0 +1,4 @@
+-- Hotel timezone (mirrors PostgreSQL migration 057).
+
+INSERT OR IGNORE INTO system_settings (key, value, value_type, category, description)
+VALUES ('timezone', 'UTC', 'string', 'general', 'Hotel timezone (IANA name, e.g. Asia/Kuala_Lumpur)');
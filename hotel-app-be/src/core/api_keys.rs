@@ -0,0 +1,308 @@
+//! Per-user API keys for integrations
+//!
+//! A key is sent in the `X-Api-Key` header and authenticates as the user who
+//! created it, limited to the permission names in its `scopes`. Only a
+//! SHA-256 hash is stored; the plaintext is handed out once at creation.
+//! Keys are honoured wherever a handler requires a permission through
+//! `require_permission_helper`; session-only endpoints still need a JWT.
+
+use super::db::{DbPool, DbRow};
+use super::error::ApiError;
+use crate::models::ApiKey;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+
+/// Request header carrying an API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Marks a string as one of our keys so it is recognisable in logs and
+/// secret scanners.
+const KEY_PREFIX: &str = "hak_";
+
+/// Characters of the key kept in clear for display.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Upper bound for `expires_in_days` on creation.
+pub const MAX_EXPIRY_DAYS: i64 = 3650;
+
+const API_KEY_COLUMNS: &str = "id, user_id, name, key_prefix, scopes, last_used_at, expires_at, \
+                               revoked_at, created_at";
+
+/// A key resolved from a request header.
+#[derive(Debug, Clone)]
+pub struct ApiKeyPrincipal {
+    pub user_id: i64,
+    pub scopes: Vec<String>,
+}
+
+/// Generates a new plaintext key: the `hak_` marker plus 32 random bytes in hex.
+pub fn generate_key() -> String {
+    let mut rng = rand::rng();
+    let key_bytes: [u8; 32] = rng.random();
+    format!("{KEY_PREFIX}{}", hex::encode(key_bytes))
+}
+
+/// Hashes a key for storage and lookup.
+pub fn hash_key(key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Whether `scopes` cover `permission`. As with role permissions,
+/// `<resource>:manage` covers every action on that resource.
+pub fn scope_allows(scopes: &[String], permission: &str) -> bool {
+    let manage_permission = permission
+        .split_once(':')
+        .map(|(resource, _)| format!("{resource}:manage"));
+
+    scopes
+        .iter()
+        .any(|scope| scope == permission || Some(scope) == manage_permission.as_ref())
+}
+
+/// Trims, validates and de-duplicates requested scopes. Each scope must be a
+/// `<resource>:<action>` permission name.
+pub fn normalize_scopes(scopes: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for scope in scopes {
+        let scope = scope.trim().to_lowercase();
+        let valid = scope.split_once(':').is_some_and(|(resource, action)| {
+            !resource.is_empty()
+                && !action.is_empty()
+                && format!("{resource}{action}")
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c == '_')
+        });
+        if !valid {
+            return Err(format!(
+                "Invalid scope '{scope}'; expected <resource>:<action>"
+            ));
+        }
+        if !normalized.contains(&scope) {
+            normalized.push(scope);
+        }
+    }
+
+    if normalized.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+
+    Ok(normalized)
+}
+
+/// Reads the API key header, if present.
+pub fn api_key_from_headers(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+fn scopes_to_db(scopes: &[String]) -> String {
+    super::db::array_to_json(scopes)
+}
+
+#[cfg(not(all(feature = "sqlite", not(feature = "postgres"))))]
+fn scopes_to_db(scopes: &[String]) -> Vec<String> {
+    scopes.to_vec()
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+fn scopes_from_row(row: &DbRow) -> Vec<String> {
+    row.try_get::<String, _>("scopes")
+        .map(|json| super::db::json_to_array(&json))
+        .unwrap_or_default()
+}
+
+#[cfg(not(all(feature = "sqlite", not(feature = "postgres"))))]
+fn scopes_from_row(row: &DbRow) -> Vec<String> {
+    row.try_get("scopes").unwrap_or_default()
+}
+
+fn api_key_from_row(row: &DbRow) -> ApiKey {
+    ApiKey {
+        id: row.get("id"),
+        name: row.get("name"),
+        key_prefix: row.get("key_prefix"),
+        scopes: scopes_from_row(row),
+        last_used_at: row.try_get("last_used_at").ok().flatten(),
+        expires_at: row.try_get("expires_at").ok().flatten(),
+        revoked_at: row.try_get("revoked_at").ok().flatten(),
+        created_at: row.try_get("created_at").ok().flatten(),
+    }
+}
+
+/// Whether a key may still be used at `now`.
+pub fn is_key_usable(key: &ApiKey, now: DateTime<Utc>) -> bool {
+    key.revoked_at.is_none() && key.expires_at.is_none_or(|expires_at| expires_at > now)
+}
+
+/// Creates a key for `user_id` and returns the plaintext alongside its record.
+/// Scopes must already be normalized and held by the user.
+pub async fn create_api_key(
+    pool: &DbPool,
+    user_id: i64,
+    name: &str,
+    scopes: &[String],
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(String, ApiKey), ApiError> {
+    let key = generate_key();
+    let key_prefix: String = key.chars().take(DISPLAY_PREFIX_LEN).collect();
+
+    let id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO api_keys (user_id, name, key_prefix, key_hash, scopes, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(&key_prefix)
+    .bind(hash_key(&key))
+    .bind(scopes_to_db(scopes))
+    .bind(expires_at)
+    .bind(Utc::now())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let row = sqlx::query(&format!(
+        "SELECT {API_KEY_COLUMNS} FROM api_keys WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok((key, api_key_from_row(&row)))
+}
+
+/// Lists a user's keys, newest first, including revoked ones.
+pub async fn list_api_keys(pool: &DbPool, user_id: i64) -> Result<Vec<ApiKey>, ApiError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {API_KEY_COLUMNS} FROM api_keys WHERE user_id = $1 ORDER BY id DESC"
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows.iter().map(api_key_from_row).collect())
+}
+
+/// Revokes one of the user's keys. Returns `NotFound` for unknown, foreign
+/// or already-revoked keys.
+pub async fn revoke_api_key(pool: &DbPool, user_id: i64, key_id: i64) -> Result<(), ApiError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE api_keys
+        SET revoked_at = $1
+        WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(key_id)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("API key not found".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Resolves a plaintext key to its owner, rejecting unknown, revoked and
+/// expired keys and keys of inactive users. Records the use.
+pub async fn authenticate(pool: &DbPool, key: &str) -> Result<ApiKeyPrincipal, ApiError> {
+    let invalid = || ApiError::Unauthorized("Invalid or expired API key".to_string());
+
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT {API_KEY_COLUMNS}
+        FROM api_keys
+        WHERE key_hash = $1
+          AND EXISTS(
+              SELECT 1 FROM users u
+              WHERE u.id = api_keys.user_id
+                AND u.is_active = true
+                AND u.deleted_at IS NULL
+          )
+        "#
+    ))
+    .bind(hash_key(key))
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(invalid)?;
+
+    let api_key = api_key_from_row(&row);
+    let now = Utc::now();
+    if !is_key_usable(&api_key, now) {
+        return Err(invalid());
+    }
+
+    // Best effort: a failed timestamp update must not fail the request.
+    let _ = sqlx::query("UPDATE api_keys SET last_used_at = $1 WHERE id = $2")
+        .bind(now)
+        .bind(api_key.id)
+        .execute(pool)
+        .await;
+
+    Ok(ApiKeyPrincipal {
+        user_id: row.get("user_id"),
+        scopes: api_key.scopes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn generated_keys_are_marked_and_hashed_deterministically() {
+        let key = generate_key();
+
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(key.len(), KEY_PREFIX.len() + 64);
+        assert_eq!(hash_key(&key), hash_key(&key));
+        assert_ne!(hash_key(&key), key);
+        assert_ne!(generate_key(), key);
+    }
+
+    #[test]
+    fn scopes_match_exact_permission_or_resource_manage() {
+        let granted = scopes(&["rooms:read", "bookings:manage"]);
+
+        assert!(scope_allows(&granted, "rooms:read"));
+        assert!(scope_allows(&granted, "bookings:update"));
+        assert!(!scope_allows(&granted, "rooms:update"));
+        assert!(!scope_allows(&granted, "settings:read"));
+        assert!(!scope_allows(&[], "rooms:read"));
+    }
+
+    #[test]
+    fn normalize_scopes_trims_dedupes_and_rejects_malformed() {
+        assert_eq!(
+            normalize_scopes(&scopes(&[" Rooms:Read ", "rooms:read", "night_audit:read"])),
+            Ok(scopes(&["rooms:read", "night_audit:read"]))
+        );
+        assert!(normalize_scopes(&[]).is_err());
+        assert!(normalize_scopes(&scopes(&["rooms"])).is_err());
+        assert!(normalize_scopes(&scopes(&["rooms:"])).is_err());
+        assert!(normalize_scopes(&scopes(&["rooms:read;drop"])).is_err());
+    }
+}
@@ -1,6 +1,7 @@
-use super::db::{DbPool, array_to_json};
+use super::config;
+use super::db::{DbPool, array_to_json, generate_uuid};
 use bcrypt::{DEFAULT_COST, hash, verify};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use hex;
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use rand::Rng;
@@ -8,7 +9,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::Row;
-use std::env;
+use std::collections::HashSet;
 use std::sync::OnceLock;
 use totp_rs::{Algorithm, Secret, TOTP};
 
@@ -19,6 +20,17 @@ pub struct Claims {
     pub exp: usize,
     pub iat: usize,
     pub roles: Vec<String>,
+    /// Token id, used to revoke the token. Empty in tokens issued before
+    /// revocation existed.
+    #[serde(default)]
+    pub jti: String,
+}
+
+/// A valid refresh token's owner and the start of its session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshSession {
+    pub user_id: i64,
+    pub session_started_at: DateTime<Utc>,
 }
 
 pub struct AuthService;
@@ -59,15 +71,154 @@ const WEAK_PASSWORDS: &[&str] = &[
     "monkey123",
 ];
 
+/// Rules a new password must satisfy. The default is the built-in policy;
+/// `services::password_policy` builds one from the system settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    /// Upper bound, mainly to keep hashing cheap
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Known-breached passwords, lowercased; matched case-insensitively
+    pub breached_passwords: HashSet<String>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            breached_passwords: HashSet::new(),
+        }
+    }
+}
+
+/// A rule a password failed to meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordRequirement {
+    MinLength(usize),
+    MaxLength(usize),
+    Uppercase,
+    Lowercase,
+    Digit,
+    Symbol,
+    /// Not on, or built around, the built-in weak-password list
+    NotCommon,
+    /// Not in the configured breached-password list
+    NotBreached,
+}
+
+impl PasswordRequirement {
+    /// Stable identifier for clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MinLength(_) => "min_length",
+            Self::MaxLength(_) => "max_length",
+            Self::Uppercase => "uppercase",
+            Self::Lowercase => "lowercase",
+            Self::Digit => "digit",
+            Self::Symbol => "symbol",
+            Self::NotCommon => "common",
+            Self::NotBreached => "breached",
+        }
+    }
+
+    /// What the password must do, completing "Password must ...".
+    fn requirement(&self) -> String {
+        match self {
+            Self::MinLength(n) => format!("be at least {} characters long", n),
+            Self::MaxLength(n) => format!("not exceed {} characters", n),
+            Self::Uppercase => "contain at least one uppercase letter".to_string(),
+            Self::Lowercase => "contain at least one lowercase letter".to_string(),
+            Self::Digit => "contain at least one number".to_string(),
+            Self::Symbol => "contain at least one special character".to_string(),
+            Self::NotCommon => "not be a common or weak password".to_string(),
+            Self::NotBreached => "not appear in a list of breached passwords".to_string(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        format!("Password must {}", self.requirement())
+    }
+
+    /// One sentence naming every requirement in `unmet`, e.g. "Password
+    /// must contain at least one number and contain at least one special
+    /// character".
+    pub fn summary(unmet: &[PasswordRequirement]) -> String {
+        let parts: Vec<String> = unmet.iter().map(Self::requirement).collect();
+        match parts.split_last() {
+            Some((last, rest)) if !rest.is_empty() => {
+                format!("Password must {} and {}", rest.join(", "), last)
+            }
+            Some((last, _)) => format!("Password must {}", last),
+            None => String::new(),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Returns every requirement `password` does not meet.
+    pub fn validate(&self, password: &str) -> Result<(), Vec<PasswordRequirement>> {
+        let mut unmet = Vec::new();
+
+        if password.len() < self.min_length {
+            unmet.push(PasswordRequirement::MinLength(self.min_length));
+        }
+
+        if password.len() > self.max_length {
+            unmet.push(PasswordRequirement::MaxLength(self.max_length));
+        }
+
+        if self.require_uppercase && !uppercase_regex().is_match(password) {
+            unmet.push(PasswordRequirement::Uppercase);
+        }
+
+        if self.require_lowercase && !lowercase_regex().is_match(password) {
+            unmet.push(PasswordRequirement::Lowercase);
+        }
+
+        if self.require_digit && !digit_regex().is_match(password) {
+            unmet.push(PasswordRequirement::Digit);
+        }
+
+        if self.require_symbol && !special_character_regex().is_match(password) {
+            unmet.push(PasswordRequirement::Symbol);
+        }
+
+        // Check for common weak passwords
+        let lowercase_pwd = password.to_lowercase();
+        if WEAK_PASSWORDS
+            .iter()
+            .any(|weak| lowercase_pwd.contains(weak))
+        {
+            unmet.push(PasswordRequirement::NotCommon);
+        }
+
+        if self.breached_passwords.contains(&lowercase_pwd) {
+            unmet.push(PasswordRequirement::NotBreached);
+        }
+
+        if unmet.is_empty() { Ok(()) } else { Err(unmet) }
+    }
+}
+
 impl AuthService {
     pub fn generate_jwt(
         user_id: i64,
         username: String,
         roles: Vec<String>,
     ) -> Result<String, jsonwebtoken::errors::Error> {
-        let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+        let config = config::get();
+        let secret = &config.jwt_secret;
         let now = Utc::now();
-        let exp = (now + Duration::hours(24)).timestamp() as usize;
+        let exp = (now + Duration::minutes(config.access_token_ttl_minutes)).timestamp() as usize;
         let iat = now.timestamp() as usize;
 
         let claims = Claims {
@@ -76,6 +227,7 @@ impl AuthService {
             exp,
             iat,
             roles,
+            jti: generate_uuid(),
         };
 
         encode(
@@ -86,7 +238,7 @@ impl AuthService {
     }
 
     pub fn verify_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+        let secret = &config::get().jwt_secret;
         decode::<Claims>(
             token,
             &DecodingKey::from_secret(secret.as_ref()),
@@ -103,53 +255,6 @@ impl AuthService {
         verify(password, hash)
     }
 
-    /// Validates password complexity and returns an error message if invalid
-    pub fn validate_password(password: &str) -> Result<(), String> {
-        // Minimum length check
-        if password.len() < 8 {
-            return Err("Password must be at least 8 characters long".to_string());
-        }
-
-        // Maximum length check (prevent DOS attacks with extremely long passwords)
-        if password.len() > 128 {
-            return Err("Password must not exceed 128 characters".to_string());
-        }
-
-        // Check for at least one uppercase letter
-        let has_uppercase = uppercase_regex().is_match(password);
-        if !has_uppercase {
-            return Err("Password must contain at least one uppercase letter".to_string());
-        }
-
-        // Check for at least one lowercase letter
-        let has_lowercase = lowercase_regex().is_match(password);
-        if !has_lowercase {
-            return Err("Password must contain at least one lowercase letter".to_string());
-        }
-
-        // Check for at least one digit
-        let has_digit = digit_regex().is_match(password);
-        if !has_digit {
-            return Err("Password must contain at least one number".to_string());
-        }
-
-        // Check for at least one special character
-        let has_special = special_character_regex().is_match(password);
-        if !has_special {
-            return Err("Password must contain at least one special character".to_string());
-        }
-
-        // Check for common weak passwords
-        let lowercase_pwd = password.to_lowercase();
-        for weak in WEAK_PASSWORDS {
-            if lowercase_pwd.contains(weak) {
-                return Err("Password is too common or weak".to_string());
-            }
-        }
-
-        Ok(())
-    }
-
     /// Generates a cryptographically secure refresh token
     pub fn generate_refresh_token() -> String {
         let mut rng = rand::rng();
@@ -164,24 +269,26 @@ impl AuthService {
         hex::encode(hasher.finalize())
     }
 
-    /// Stores a refresh token in the database
+    /// Stores a refresh token in the database. `session_started_at` is the
+    /// login that began the token's session; rotated tokens carry it forward.
     pub async fn store_refresh_token(
         pool: &DbPool,
         user_id: i64,
         token: &str,
-        expires_in_days: i64,
+        session_started_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
     ) -> Result<(), sqlx::Error> {
         let token_hash = Self::hash_refresh_token(token);
-        let expires_at = Utc::now() + Duration::days(expires_in_days);
 
         sqlx::query(
             r#"
-            INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
-            VALUES ($1, $2, $3)
+            INSERT INTO refresh_tokens (user_id, token_hash, session_started_at, expires_at)
+            VALUES ($1, $2, $3, $4)
             "#,
         )
         .bind(user_id)
         .bind(token_hash)
+        .bind(session_started_at)
         .bind(expires_at)
         .execute(pool)
         .await?;
@@ -189,19 +296,18 @@ impl AuthService {
         Ok(())
     }
 
-    /// Validates a refresh token and returns the user_id if valid
+    /// Validates a refresh token and returns its session if valid
     pub async fn validate_refresh_token(
         pool: &DbPool,
         token: &str,
-    ) -> Result<Option<i64>, sqlx::Error> {
+    ) -> Result<Option<RefreshSession>, sqlx::Error> {
         let token_hash = Self::hash_refresh_token(token);
 
-        let result = sqlx::query_scalar::<_, i64>(
+        let row = sqlx::query(
             r#"
-            SELECT user_id
+            SELECT user_id, session_started_at, created_at, expires_at
             FROM refresh_tokens
             WHERE token_hash = $1
-              AND expires_at > NOW()
               AND revoked_at IS NULL
             "#,
         )
@@ -209,7 +315,28 @@ impl AuthService {
         .fetch_optional(pool)
         .await?;
 
-        Ok(result)
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        // Compared here rather than in SQL so both databases agree on it.
+        let expires_at: DateTime<Utc> = row.try_get("expires_at")?;
+        if expires_at <= Utc::now() {
+            return Ok(None);
+        }
+
+        let created_at: Option<DateTime<Utc>> = row.try_get("created_at").ok().flatten();
+        let session_started_at = row
+            .try_get::<Option<DateTime<Utc>>, _>("session_started_at")
+            .ok()
+            .flatten()
+            .or(created_at)
+            .unwrap_or_else(Utc::now);
+
+        Ok(Some(RefreshSession {
+            user_id: row.try_get("user_id")?,
+            session_started_at,
+        }))
     }
 
     /// Revokes a refresh token
@@ -639,42 +766,64 @@ impl AuthService {
 
 #[cfg(test)]
 mod tests {
-    use super::AuthService;
+    use super::{AuthService, PasswordPolicy, PasswordRequirement};
 
     #[test]
     fn validate_password_accepts_strong_password() {
-        assert!(AuthService::validate_password("S3cure_Rooms!").is_ok());
+        assert!(PasswordPolicy::default().validate("S3cure_Rooms!").is_ok());
     }
 
     #[test]
     fn validate_password_rejects_each_complexity_gap() {
         let cases = [
-            ("Short1!", "at least 8 characters"),
-            ("lowercase1!", "uppercase"),
-            ("UPPERCASE1!", "lowercase"),
-            ("NoDigits!", "number"),
-            ("NoSpecial1", "special character"),
-            ("Password123!", "too common"),
+            ("Short1!", PasswordRequirement::MinLength(8)),
+            ("lowercase1!", PasswordRequirement::Uppercase),
+            ("UPPERCASE1!", PasswordRequirement::Lowercase),
+            ("NoDigits!", PasswordRequirement::Digit),
+            ("NoSpecial1", PasswordRequirement::Symbol),
+            ("Password123!", PasswordRequirement::NotCommon),
         ];
 
-        for (password, expected_message) in cases {
-            let error = AuthService::validate_password(password)
+        for (password, expected) in cases {
+            let unmet = PasswordPolicy::default()
+                .validate(password)
                 .expect_err("weak password should be rejected");
 
-            assert!(
-                error.contains(expected_message),
-                "expected '{error}' to contain '{expected_message}'"
-            );
+            assert_eq!(unmet, [expected], "{password}");
         }
     }
 
+    #[test]
+    fn validate_password_reports_every_unmet_requirement() {
+        let unmet = PasswordPolicy::default().validate("short").unwrap_err();
+
+        assert_eq!(
+            unmet,
+            [
+                PasswordRequirement::MinLength(8),
+                PasswordRequirement::Uppercase,
+                PasswordRequirement::Digit,
+                PasswordRequirement::Symbol,
+            ]
+        );
+        assert_eq!(
+            PasswordRequirement::summary(&unmet[2..]),
+            "Password must contain at least one number and contain at least one special character"
+        );
+    }
+
     #[test]
     fn validate_password_rejects_excessively_long_passwords() {
         let password = format!("A1!{}", "a".repeat(126));
-        let error = AuthService::validate_password(&password)
+        let unmet = PasswordPolicy::default()
+            .validate(&password)
             .expect_err("password longer than 128 chars should be rejected");
 
-        assert!(error.contains("must not exceed 128 characters"));
+        assert_eq!(unmet, [PasswordRequirement::MaxLength(128)]);
+        assert_eq!(
+            unmet[0].message(),
+            "Password must not exceed 128 characters"
+        );
     }
 
     #[test]
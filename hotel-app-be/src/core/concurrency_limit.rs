@@ -0,0 +1,66 @@
+//! Server-wide cap on in-flight requests
+//!
+//! Under a burst, requests queued behind an exhausted connection pool only
+//! time out later and drag the rest of the server with them. Requests beyond
+//! the cap are shed straight away with 503 so clients can back off and retry.
+//! `/health` is exempt so probes still reach a busy server.
+//!
+//! The cap comes from `MAX_CONCURRENT_REQUESTS` (default 256) via
+//! [`AppConfig`](super::config::AppConfig).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tokio::sync::Semaphore;
+
+use crate::core::error::ApiError;
+
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+/// Paths that are never shed.
+const EXEMPT_PATHS: &[&str] = &["/health"];
+
+/// Shared permit pool; clone it into the middleware state.
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    permits: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_in_flight.max(1))),
+        }
+    }
+}
+
+/// Middleware: run the request if a permit is free, otherwise answer 503.
+/// The permit is held until the handler has produced its response.
+pub async fn limit_concurrency(
+    State(limit): State<ConcurrencyLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if EXEMPT_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match limit.permits.try_acquire() {
+        Ok(_permit) => next.run(request).await,
+        Err(_) => {
+            log::warn!(
+                "Shedding {} {}: server busy",
+                request.method(),
+                request.uri().path()
+            );
+            ApiError::ServiceUnavailable(
+                "The server is busy right now. Please try again shortly.".to_string(),
+            )
+            .into_response()
+        }
+    }
+}
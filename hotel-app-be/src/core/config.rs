@@ -0,0 +1,236 @@
+//! Process configuration
+//!
+//! [`AppConfig`] is read from the environment once at startup and validated
+//! there, so a missing or malformed variable stops the server with a clear
+//! message instead of surfacing on the first request that needs it. `main`
+//! passes it to [`create_pool`](super::db::create_pool) and
+//! [`create_router`](crate::routes::create_router) and installs it with
+//! [`install`]; code without access to either reads it through [`get`].
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use super::concurrency_limit::DEFAULT_MAX_IN_FLIGHT;
+use super::security_headers::{AllowedOrigins, DeploymentMode};
+
+pub const DEFAULT_BACKEND_PORT: u16 = 3030;
+pub const DEFAULT_DATABASE_PATH: &str = "./hotel_data.db";
+pub const DEFAULT_PASSKEY_RP_ID: &str = "localhost";
+/// Origin of the local web app, where passkeys for `localhost` are used.
+pub const DEFAULT_PASSKEY_ORIGIN: &str = "http://localhost:3000";
+pub const DEFAULT_MAX_UPLOAD_MB: usize = 10;
+pub const DEFAULT_ACCESS_TOKEN_TTL_MINUTES: i64 = 24 * 60;
+/// Upper bound for `ACCESS_TOKEN_TTL_MINUTES`: 30 days.
+pub const MAX_ACCESS_TOKEN_TTL_MINUTES: i64 = 30 * 24 * 60;
+/// Upper bound for `REFRESH_TOKEN_TTL_DAYS` and the refresh-token lifetime
+/// settings.
+pub const MAX_REFRESH_TOKEN_TTL_DAYS: i64 = 3650;
+
+/// Why the environment could not be turned into an [`AppConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A required variable is unset or blank
+    Missing(&'static str),
+    /// A variable is set to something that can't be used
+    Invalid {
+        var: &'static str,
+        value: String,
+        reason: &'static str,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Missing(var) => write!(f, "{} must be set", var),
+            ConfigError::Invalid { var, value, reason } => {
+                write!(f, "{} has invalid value {:?}: {}", var, value, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppConfig {
+    /// `HOTEL_DESKTOP_MODE`: bundled desktop app, bound to localhost
+    pub desktop_mode: bool,
+    /// `DEPLOYMENT_MODE`, defaulting to desktop when `HOTEL_DESKTOP_MODE` is
+    /// set and web otherwise
+    pub deployment_mode: DeploymentMode,
+    /// `DATABASE_URL`; required for PostgreSQL builds
+    pub database_url: Option<String>,
+    /// `DATABASE_PATH`: SQLite database file
+    pub database_path: String,
+    /// `JWT_SECRET`: signs access tokens; required
+    pub jwt_secret: String,
+    /// `ALLOWED_ORIGINS`: comma-separated CORS origins, or `*`. Web
+    /// deployments need at least one valid origin; malformed entries are
+    /// dropped with a warning when the router is built.
+    pub allowed_origins: String,
+    /// `BACKEND_PORT`
+    pub backend_port: u16,
+    /// `MAX_CONCURRENT_REQUESTS`
+    pub max_concurrent_requests: usize,
+    /// `MAX_UPLOAD_MB`: caps the file size every upload endpoint accepts
+    pub max_upload_mb: usize,
+    /// `PASSKEY_RP_ID`: WebAuthn relying-party id
+    pub passkey_rp_id: String,
+    /// `PASSKEY_ORIGIN`: origin passkey logins must come from; defaults to
+    /// `https://` and the relying-party id, or the local web app for
+    /// `localhost`
+    pub passkey_origin: String,
+    /// `ACCESS_TOKEN_TTL_MINUTES`: how long an access token (JWT) is valid
+    pub access_token_ttl_minutes: i64,
+    /// `REFRESH_TOKEN_TTL_DAYS`: when set, the refresh-token lifetime,
+    /// overriding the `refresh_token_lifetime_days` setting
+    pub refresh_token_ttl_days: Option<i64>,
+    /// `SKIP_EMAIL_VERIFICATION`: let unverified users log in (development)
+    pub skip_email_verification: bool,
+    /// `HOTEL_LOG_DIR`: overrides where log files are written
+    pub log_dir: Option<PathBuf>,
+    /// `RUST_LOG`
+    pub log_level: Option<String>,
+}
+
+impl AppConfig {
+    /// Read the process environment.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Build from `lookup`, which returns a variable's value when set. Blank
+    /// values count as unset.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let var = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+        let required = |key: &'static str| var(key).ok_or(ConfigError::Missing(key));
+
+        let desktop_mode = var("HOTEL_DESKTOP_MODE").is_some();
+        let deployment_mode = match var("DEPLOYMENT_MODE") {
+            Some(value) => DeploymentMode::parse(&value).ok_or(ConfigError::Invalid {
+                var: "DEPLOYMENT_MODE",
+                value,
+                reason: "expected web or desktop",
+            })?,
+            None if desktop_mode => DeploymentMode::Desktop,
+            None => DeploymentMode::Web,
+        };
+
+        #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+        let database_url = Some(required("DATABASE_URL")?);
+        #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+        let database_url = var("DATABASE_URL");
+
+        let allowed_origins = var("ALLOWED_ORIGINS")
+            .unwrap_or_else(|| deployment_mode.default_allowed_origins().to_string());
+        if deployment_mode == DeploymentMode::Web
+            && matches!(
+                AllowedOrigins::parse(&allowed_origins),
+                AllowedOrigins::List { ref origins, .. } if origins.is_empty()
+            )
+        {
+            return Err(ConfigError::Invalid {
+                var: "ALLOWED_ORIGINS",
+                value: allowed_origins,
+                reason: "no valid origins such as https://hotel.example.com",
+            });
+        }
+
+        let passkey_rp_id =
+            var("PASSKEY_RP_ID").unwrap_or_else(|| DEFAULT_PASSKEY_RP_ID.to_string());
+        let passkey_origin = match var("PASSKEY_ORIGIN") {
+            Some(origin) => origin.trim().trim_end_matches('/').to_string(),
+            None if passkey_rp_id == DEFAULT_PASSKEY_RP_ID => DEFAULT_PASSKEY_ORIGIN.to_string(),
+            None => format!("https://{}", passkey_rp_id),
+        };
+
+        Ok(Self {
+            desktop_mode,
+            deployment_mode,
+            database_url,
+            database_path: var("DATABASE_PATH")
+                .unwrap_or_else(|| DEFAULT_DATABASE_PATH.to_string()),
+            jwt_secret: required("JWT_SECRET")?,
+            allowed_origins,
+            backend_port: parse_var(
+                var("BACKEND_PORT"),
+                "BACKEND_PORT",
+                "expected a port number",
+                DEFAULT_BACKEND_PORT,
+                |port| *port > 0,
+            )?,
+            max_concurrent_requests: parse_var(
+                var("MAX_CONCURRENT_REQUESTS"),
+                "MAX_CONCURRENT_REQUESTS",
+                "expected a positive number",
+                DEFAULT_MAX_IN_FLIGHT,
+                |n| *n > 0,
+            )?,
+            max_upload_mb: parse_var(
+                var("MAX_UPLOAD_MB"),
+                "MAX_UPLOAD_MB",
+                "expected a whole number of megabytes between 1 and 100",
+                DEFAULT_MAX_UPLOAD_MB,
+                |mb| (1..=100).contains(mb),
+            )?,
+            passkey_rp_id,
+            passkey_origin,
+            access_token_ttl_minutes: parse_var(
+                var("ACCESS_TOKEN_TTL_MINUTES"),
+                "ACCESS_TOKEN_TTL_MINUTES",
+                "expected a whole number of minutes between 1 and 43200",
+                DEFAULT_ACCESS_TOKEN_TTL_MINUTES,
+                |minutes| (1..=MAX_ACCESS_TOKEN_TTL_MINUTES).contains(minutes),
+            )?,
+            refresh_token_ttl_days: var("REFRESH_TOKEN_TTL_DAYS")
+                .map(|value| {
+                    parse_var(
+                        Some(value),
+                        "REFRESH_TOKEN_TTL_DAYS",
+                        "expected a whole number of days between 1 and 3650",
+                        0,
+                        |days| (1..=MAX_REFRESH_TOKEN_TTL_DAYS).contains(days),
+                    )
+                })
+                .transpose()?,
+            skip_email_verification: var("SKIP_EMAIL_VERIFICATION")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("true")),
+            log_dir: var("HOTEL_LOG_DIR").map(PathBuf::from),
+            log_level: var("RUST_LOG"),
+        })
+    }
+}
+
+/// Parse an optional variable, using `default` when it is unset.
+fn parse_var<T: std::str::FromStr>(
+    value: Option<String>,
+    var: &'static str,
+    reason: &'static str,
+    default: T,
+    valid: fn(&T) -> bool,
+) -> Result<T, ConfigError> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    match value.trim().parse() {
+        Ok(parsed) if valid(&parsed) => Ok(parsed),
+        _ => Err(ConfigError::Invalid { var, value, reason }),
+    }
+}
+
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+/// Make `config` the process-wide configuration. Only the first call has an
+/// effect.
+pub fn install(config: AppConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// The installed configuration. When nothing was installed (tests, tools)
+/// it is read from the environment on first use, panicking if invalid.
+pub fn get() -> &'static AppConfig {
+    CONFIG.get_or_init(|| {
+        AppConfig::from_env().unwrap_or_else(|e| panic!("Invalid configuration: {}", e))
+    })
+}
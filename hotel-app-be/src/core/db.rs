@@ -1,4 +1,4 @@
-use std::env;
+use super::config::AppConfig;
 
 // Type aliases for database pool based on feature flags
 // Use mutual exclusion to ensure only one database type is active
@@ -12,6 +12,27 @@ pub type DbPool = sqlx::Pool<sqlx::Postgres>;
 #[cfg(all(feature = "sqlite", feature = "postgres"))]
 pub type DbPool = sqlx::Pool<sqlx::Postgres>;
 
+// Transaction on the active pool, for helpers that write inside a caller's transaction
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub type DbTransaction<'a> = sqlx::Transaction<'a, sqlx::Sqlite>;
+
+#[cfg(all(feature = "postgres", not(feature = "sqlite")))]
+pub type DbTransaction<'a> = sqlx::Transaction<'a, sqlx::Postgres>;
+
+#[cfg(all(feature = "sqlite", feature = "postgres"))]
+pub type DbTransaction<'a> = sqlx::Transaction<'a, sqlx::Postgres>;
+
+// Connection on the active database, for helpers that run on either a pooled
+// connection or a caller's transaction
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub type DbConnection = sqlx::SqliteConnection;
+
+#[cfg(all(feature = "postgres", not(feature = "sqlite")))]
+pub type DbConnection = sqlx::PgConnection;
+
+#[cfg(all(feature = "sqlite", feature = "postgres"))]
+pub type DbConnection = sqlx::PgConnection;
+
 // Re-export the correct Row type
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 pub type DbRow = sqlx::sqlite::SqliteRow;
@@ -23,10 +44,10 @@ pub type DbRow = sqlx::postgres::PgRow;
 pub type DbRow = sqlx::postgres::PgRow;
 
 /// Creates a database connection pool based on the enabled feature
-pub async fn create_pool() -> Result<DbPool, sqlx::Error> {
+pub async fn create_pool(config: &AppConfig) -> Result<DbPool, sqlx::Error> {
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     {
-        let db_path = env::var("DATABASE_PATH").unwrap_or_else(|_| "./hotel_data.db".to_string());
+        let db_path = &config.database_path;
 
         // Create database file if it doesn't exist
         let db_url = format!("sqlite:{}?mode=rwc", db_path);
@@ -50,8 +71,10 @@ pub async fn create_pool() -> Result<DbPool, sqlx::Error> {
         use sqlx::Executor;
         use sqlx::postgres::PgPoolOptions;
 
-        let database_url =
-            env::var("DATABASE_URL").expect("DATABASE_URL must be set in environment variables");
+        let database_url = config
+            .database_url
+            .clone()
+            .ok_or_else(|| sqlx::Error::Configuration("DATABASE_URL must be set".into()))?;
 
         log::info!("Connecting to PostgreSQL database");
 
@@ -1,11 +1,14 @@
 //! Unified error types for the hotel API
 //!
-//! This module contains the common error type used across all handlers.
+//! This module contains the common error type used across all handlers, and
+//! [`RepoError`], which repositories return so a database failure reaches the
+//! client with the status code it deserves.
 
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Json, Response},
 };
+use serde::Serialize;
 
 /// API Error type used across all handlers
 #[derive(Debug)]
@@ -18,6 +21,8 @@ pub enum ApiError {
     Forbidden(String),
     /// Invalid request data
     BadRequest(String),
+    /// Invalid request data, with each problem listed for the client
+    Validation(String, Vec<ValidationIssue>),
     /// Resource not found
     NotFound(String),
     /// Resource already exists (conflict)
@@ -28,6 +33,18 @@ pub enum ApiError {
     TooManyRequests(String),
     /// Rate limit exceeded with Retry-After header
     TooManyRequestsRetryAfter(String, u64),
+    /// Server is overloaded and shed the request
+    ServiceUnavailable(String),
+    /// The user must change their password before doing anything else
+    PasswordChangeRequired,
+}
+
+/// One reason a request failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// Stable identifier, e.g. "min_length"
+    pub code: &'static str,
+    pub message: String,
 }
 
 impl std::fmt::Display for ApiError {
@@ -37,6 +54,7 @@ impl std::fmt::Display for ApiError {
             ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             ApiError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            ApiError::Validation(msg, _) => write!(f, "Bad request: {}", msg),
             ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
             ApiError::Conflict(msg) => write!(f, "Conflict: {}", msg),
             ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
@@ -44,6 +62,8 @@ impl std::fmt::Display for ApiError {
             ApiError::TooManyRequestsRetryAfter(msg, secs) => {
                 write!(f, "Too many requests (retry after {}s): {}", secs, msg)
             }
+            ApiError::ServiceUnavailable(msg) => write!(f, "Service unavailable: {}", msg),
+            ApiError::PasswordChangeRequired => write!(f, "Password change required"),
         }
     }
 }
@@ -140,6 +160,28 @@ impl IntoResponse for ApiError {
                 StatusCode::TOO_MANY_REQUESTS,
                 polish_message(msg, "Too many requests. Please slow down and try again."),
             ),
+            ApiError::ServiceUnavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                polish_message(
+                    msg,
+                    "The server is busy right now. Please try again shortly.",
+                ),
+            ),
+            ApiError::Validation(msg, issues) => {
+                let body = Json(serde_json::json!({
+                    "error": polish_message(msg, "That request couldn't be processed."),
+                    "details": issues
+                }));
+                return (StatusCode::BAD_REQUEST, body).into_response();
+            }
+            ApiError::PasswordChangeRequired => {
+                // A code the client can recognise without parsing the message
+                let body = Json(serde_json::json!({
+                    "error": "Please change your password to continue.",
+                    "code": "password_change_required"
+                }));
+                return (StatusCode::FORBIDDEN, body).into_response();
+            }
         };
 
         let body = Json(serde_json::json!({
@@ -173,3 +215,59 @@ impl From<std::io::Error> for ApiError {
         ApiError::Internal(err.to_string())
     }
 }
+
+/// Error returned by repositories. Converting a `sqlx::Error` sorts it into
+/// a missing row, a unique-constraint clash or a genuine database failure,
+/// and each becomes the matching [`ApiError`] when a handler uses `?`.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum RepoError {
+    /// The query expected a row and found none
+    NotFound(String),
+    /// A unique constraint rejected the write
+    Conflict(String),
+    /// Any other database failure
+    Database(String),
+}
+
+impl std::fmt::Display for RepoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            RepoError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            RepoError::Database(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RepoError {}
+
+impl From<sqlx::Error> for RepoError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => RepoError::NotFound(err.to_string()),
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                RepoError::Conflict(db_err.message().to_string())
+            }
+            _ => RepoError::Database(err.to_string()),
+        }
+    }
+}
+
+// Driver messages name tables and constraints, so only database failures
+// carry them through (ApiError::Database logs and hides its message).
+impl From<RepoError> for ApiError {
+    fn from(err: RepoError) -> Self {
+        match err {
+            RepoError::NotFound(msg) => {
+                log::debug!("Repository lookup found no row: {}", msg);
+                ApiError::NotFound("The requested record was not found".to_string())
+            }
+            RepoError::Conflict(msg) => {
+                log::debug!("Repository write hit a unique constraint: {}", msg);
+                ApiError::Conflict("A record with the same details already exists".to_string())
+            }
+            RepoError::Database(msg) => ApiError::Database(msg),
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use super::api_keys;
 use super::auth::{AuthService, Claims};
 use super::db::DbPool;
 use super::error::ApiError;
+use super::revoked_tokens;
+use axum::extract::FromRequestParts;
 use axum::http::header::HeaderMap;
+use axum::http::request::Parts;
 
 // Extract JWT token from Authorization header
 pub async fn extract_claims(headers: &HeaderMap) -> Result<Claims, ApiError> {
@@ -17,8 +21,12 @@ pub async fn extract_claims(headers: &HeaderMap) -> Result<Claims, ApiError> {
     }
 
     let token = auth_header.strip_prefix("Bearer ").unwrap();
-    AuthService::verify_jwt(token)
-        .map_err(|_| ApiError::Unauthorized("Invalid or expired token".to_string()))
+    let claims = AuthService::verify_jwt(token)
+        .map_err(|_| ApiError::Unauthorized("Invalid or expired token".to_string()))?;
+    if revoked_tokens::is_revoked(&claims.jti) {
+        return Err(ApiError::Unauthorized("Token has been revoked".to_string()));
+    }
+    Ok(claims)
 }
 
 // Extract user ID from claims
@@ -65,16 +73,63 @@ pub async fn check_admin_role(pool: &DbPool, user_id: i64) -> Result<(), ApiErro
 
 // Helper function to create authenticated user from request
 pub async fn require_auth(headers: &HeaderMap) -> Result<i64, ApiError> {
-    let claims = extract_claims(headers).await?;
-    extract_user_id(&claims)
+    Ok(AuthUser::from_headers(headers).await?.user_id)
+}
+
+/// The user a request's bearer token belongs to. Taking it as a handler
+/// argument rejects requests without a valid token with 401, so a route
+/// cannot forget to authenticate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthUser {
+    pub user_id: i64,
+    /// Role names as issued in the token
+    pub roles: Vec<String>,
+}
+
+impl AuthUser {
+    pub async fn from_headers(headers: &HeaderMap) -> Result<Self, ApiError> {
+        let claims = extract_claims(headers).await?;
+        Ok(AuthUser {
+            user_id: extract_user_id(&claims)?,
+            roles: claims.roles,
+        })
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        AuthUser::from_headers(&parts.headers).await
+    }
 }
 
 // Helper function to require permission
+// Without an Authorization header, an X-Api-Key is accepted instead: the key
+// must be scoped for the permission and its owner must still hold it.
 pub async fn require_permission_helper(
     pool: &DbPool,
     headers: &HeaderMap,
     permission: &str,
 ) -> Result<i64, ApiError> {
+    if !headers.contains_key("authorization")
+        && let Some(key) = api_keys::api_key_from_headers(headers)
+    {
+        let principal = api_keys::authenticate(pool, key).await?;
+        if !api_keys::scope_allows(&principal.scopes, permission) {
+            return Err(ApiError::Forbidden(format!(
+                "API key is not scoped for: {}",
+                permission
+            )));
+        }
+        check_permission(pool, principal.user_id, permission).await?;
+        return Ok(principal.user_id);
+    }
+
     let user_id = require_auth(headers).await?;
     check_permission(pool, user_id, permission).await?;
     Ok(user_id)
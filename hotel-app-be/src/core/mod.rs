@@ -1,28 +1,50 @@
 //! Core infrastructure modules
 //!
 //! This module contains foundational components used across the application:
+//! - `api_keys`: Per-user API keys for integrations (`X-Api-Key`)
 //! - `auth`: Authentication service (JWT, password hashing, 2FA, refresh tokens)
+//! - `concurrency_limit`: Server-wide in-flight request cap (load shedding)
+//! - `config`: Environment configuration, validated at startup
 //! - `db`: Database connection pool
-//! - `error`: Unified API error types
+//! - `error`: Unified API and repository error types
 //! - `middleware`: Request authentication and authorization middleware
+//! - `password_change`: Refusing requests until a forced password change
+//! - `property_scope`: Selecting the property (tenant) a request works in
+//! - `revoked_tokens`: Denylist of access tokens revoked at logout
+//! - `security_headers`: Security-header and CORS profiles per deployment mode
 //! - `sql_compat`: SQL compatibility helpers for PostgreSQL/SQLite
+//! - `uploads`: Size and file-type limits for upload endpoints
+//! - `webauthn`: Passkey attestation parsing and assertion verification
 
+pub mod api_keys;
 pub mod auth;
+pub mod concurrency_limit;
+pub mod config;
 #[allow(dead_code)]
 pub mod db;
 pub mod error;
 pub mod middleware;
+pub mod password_change;
+pub mod property_scope;
 pub mod rate_limiter;
+pub mod revoked_tokens;
+pub mod security_headers;
 #[allow(dead_code)]
 pub mod sql_compat;
+pub mod uploads;
+pub mod webauthn;
 
 // Re-export commonly used types
 #[allow(unused_imports)]
 pub use auth::{AuthService, Claims};
+pub use config::AppConfig;
 pub use db::create_pool;
 #[allow(unused_imports)]
-pub use error::ApiError;
+pub use error::{ApiError, RepoError};
 #[allow(unused_imports)]
 pub use middleware::{
-    require_admin_helper, require_auth, require_permission_helper, require_super_admin_helper,
+    AuthUser, require_admin_helper, require_auth, require_permission_helper,
+    require_super_admin_helper,
 };
+#[allow(unused_imports)]
+pub use property_scope::{PropertyScope, require_property_scope};
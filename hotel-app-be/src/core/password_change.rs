@@ -0,0 +1,70 @@
+//! Forced password change
+//!
+//! Accounts an administrator creates are flagged `must_change_password`.
+//! Until the owner changes their password, [`require_password_change`]
+//! refuses their authenticated requests with
+//! [`ApiError::PasswordChangeRequired`], so ignoring the prompt after login
+//! leaves nothing but the password change itself (and signing out) usable.
+//! `update_password_handler` clears the flag.
+//!
+//! Requests without a valid bearer token pass through untouched; the route
+//! itself decides whether it needs one.
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::db::DbPool;
+use super::error::ApiError;
+use super::middleware::{extract_claims, extract_user_id};
+
+/// Requests a user who must change their password may still make.
+const EXEMPT_ROUTES: &[(Method, &str)] = &[
+    (Method::POST, "/profile/password"),
+    (Method::POST, "/auth/logout"),
+    (Method::POST, "/auth/refresh"),
+];
+
+/// Whether `method` and `path` remain available before the password is
+/// changed.
+pub fn is_exempt(method: &Method, path: &str) -> bool {
+    EXEMPT_ROUTES.iter().any(|(m, p)| m == method && *p == path)
+}
+
+/// Whether `user_id` must change their password before doing anything else.
+pub async fn is_required(pool: &DbPool, user_id: i64) -> Result<bool, ApiError> {
+    let required: Option<bool> =
+        sqlx::query_scalar("SELECT must_change_password FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(required.unwrap_or(false))
+}
+
+/// Middleware: refuse authenticated requests from users who must change
+/// their password, other than the exempt ones.
+pub async fn require_password_change(
+    State(pool): State<DbPool>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_exempt(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Ok(user_id) = extract_claims(request.headers())
+        .await
+        .and_then(|claims| extract_user_id(&claims))
+    else {
+        return next.run(request).await;
+    };
+
+    match is_required(&pool, user_id).await {
+        Ok(false) => next.run(request).await,
+        Ok(true) => ApiError::PasswordChangeRequired.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,129 @@
+//! Property (tenant) scoping
+//!
+//! One install can run several properties. Rooms and bookings carry a
+//! `property_id`, and staff routes work in one property at a time: the one
+//! named in the `X-Property-Id` header, or the user's default without it.
+//! Users are given properties in `user_properties`; a user with none works
+//! in the seeded default property, so single-property installs need no setup.
+
+use super::db::DbPool;
+use super::error::ApiError;
+use axum::http::HeaderMap;
+
+/// Request header selecting the property to work in.
+pub const PROPERTY_HEADER: &str = "x-property-id";
+
+/// Property seeded for existing installs.
+pub const DEFAULT_PROPERTY_ID: i64 = 1;
+
+/// The property a request works in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyScope {
+    pub property_id: i64,
+}
+
+impl Default for PropertyScope {
+    fn default() -> Self {
+        Self {
+            property_id: DEFAULT_PROPERTY_ID,
+        }
+    }
+}
+
+impl PropertyScope {
+    /// Fail with not found unless room `room_id` is in this property, so
+    /// rooms elsewhere look the same as rooms that do not exist.
+    pub async fn require_room(&self, pool: &DbPool, room_id: i64) -> Result<(), ApiError> {
+        let found: Option<i32> =
+            sqlx::query_scalar("SELECT 1 FROM rooms WHERE id = $1 AND property_id = $2")
+                .bind(room_id)
+                .bind(self.property_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+        found
+            .map(|_| ())
+            .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))
+    }
+
+    /// Fail with not found unless booking `booking_id` is in this property.
+    pub async fn require_booking(&self, pool: &DbPool, booking_id: i64) -> Result<(), ApiError> {
+        let found: Option<i32> =
+            sqlx::query_scalar("SELECT 1 FROM bookings WHERE id = $1 AND property_id = $2")
+                .bind(booking_id)
+                .bind(self.property_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+        found
+            .map(|_| ())
+            .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))
+    }
+}
+
+/// The property id named in the request header, if any.
+pub fn requested_property(headers: &HeaderMap) -> Result<Option<i64>, ApiError> {
+    headers
+        .get(PROPERTY_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .ok_or_else(|| ApiError::BadRequest("Invalid X-Property-Id header".to_string()))
+        })
+        .transpose()
+}
+
+/// Pick the property to work in from those the user may access, the first
+/// being their default.
+pub fn select_property(
+    accessible: &[i64],
+    requested: Option<i64>,
+) -> Result<PropertyScope, ApiError> {
+    let property_id = match requested {
+        Some(id) if accessible.contains(&id) => id,
+        Some(_) => {
+            return Err(ApiError::Forbidden(
+                "You don't have access to this property".to_string(),
+            ));
+        }
+        None => accessible.first().copied().unwrap_or(DEFAULT_PROPERTY_ID),
+    };
+    Ok(PropertyScope { property_id })
+}
+
+/// Active properties `user_id` may work in, default first. Users without
+/// assignments get the default property.
+pub async fn accessible_properties(pool: &DbPool, user_id: i64) -> Result<Vec<i64>, ApiError> {
+    let assigned: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT up.property_id
+        FROM user_properties up
+        INNER JOIN properties p ON p.id = up.property_id AND p.is_active
+        WHERE up.user_id = $1
+        ORDER BY up.is_default DESC, up.property_id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if assigned.is_empty() {
+        Ok(vec![DEFAULT_PROPERTY_ID])
+    } else {
+        Ok(assigned)
+    }
+}
+
+/// Resolve the property an authenticated request works in.
+pub async fn require_property_scope(
+    pool: &DbPool,
+    headers: &HeaderMap,
+    user_id: i64,
+) -> Result<PropertyScope, ApiError> {
+    let requested = requested_property(headers)?;
+    let accessible = accessible_properties(pool, user_id).await?;
+    select_property(&accessible, requested)
+}
@@ -0,0 +1,118 @@
+//! Access token revocation
+//!
+//! Access tokens are stateless JWTs, so logging out cannot take one back.
+//! Instead the token's `jti` is denylisted until the token would have expired
+//! anyway. The list lives in `revoked_tokens` and is mirrored in memory so
+//! `extract_claims` can check it on every request without a query. The sweep
+//! started by [`spawn_sweeper`] purges expired entries and reloads the table,
+//! picking up revocations made by other server instances.
+
+use super::db::DbPool;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+/// How often expired entries are purged and the table reloaded.
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Entries are kept this long past expiry, covering the leeway token
+/// validation allows on `exp`.
+const PURGE_GRACE_MINUTES: i64 = 5;
+
+/// Revoked token ids and when each token expires.
+fn denylist() -> &'static RwLock<HashMap<String, DateTime<Utc>>> {
+    static DENYLIST: OnceLock<RwLock<HashMap<String, DateTime<Utc>>>> = OnceLock::new();
+    DENYLIST.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Whether the token with id `jti` has been revoked.
+pub fn is_revoked(jti: &str) -> bool {
+    !jti.is_empty()
+        && denylist()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_key(jti)
+}
+
+/// Revoke the access token `jti` belonging to `user_id` until it expires.
+pub async fn revoke(
+    pool: &DbPool,
+    jti: &str,
+    user_id: i64,
+    expires_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO revoked_tokens (jti, user_id, expires_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (jti) DO NOTHING
+        "#,
+    )
+    .bind(jti)
+    .bind(user_id)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+
+    denylist()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(jti.to_string(), expires_at);
+    Ok(())
+}
+
+/// Add every revocation in the table to the in-memory list. Entries are
+/// only ever dropped by [`purge_expired`], so a reload racing a
+/// [`revoke`] cannot lose it. Returns the number of rows loaded.
+pub async fn reload(pool: &DbPool) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query("SELECT jti, expires_at FROM revoked_tokens")
+        .fetch_all(pool)
+        .await?;
+    let loaded = rows
+        .iter()
+        .map(|row| Ok((row.try_get("jti")?, row.try_get("expires_at")?)))
+        .collect::<Result<Vec<(String, DateTime<Utc>)>, sqlx::Error>>()?;
+
+    let count = loaded.len();
+    denylist()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .extend(loaded);
+    Ok(count)
+}
+
+/// Drop revocations for tokens that expired before `now`, less a grace
+/// period. Returns the number of rows deleted.
+pub async fn purge_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let cutoff = now - Duration::minutes(PURGE_GRACE_MINUTES);
+    let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at < $1")
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+
+    denylist()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|_, expires_at| *expires_at >= cutoff);
+    Ok(result.rows_affected())
+}
+
+/// Purge and reload the denylist for the life of the process. Call
+/// [`reload`] before serving so revocations apply from the first request.
+pub fn spawn_sweeper(pool: DbPool) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SWEEP_INTERVAL).await;
+
+            match purge_expired(&pool, Utc::now()).await {
+                Ok(0) => {}
+                Ok(n) => log::debug!("Purged {} expired token revocation(s)", n),
+                Err(e) => log::warn!("Token revocation purge failed: {}", e),
+            }
+            if let Err(e) = reload(&pool).await {
+                log::warn!("Token revocation reload failed: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,151 @@
+//! Security-header and CORS profiles per deployment
+//!
+//! `DEPLOYMENT_MODE` selects the profile: `web` (the default) sends the
+//! strict set, including HSTS. `desktop` is the bundled app talking to a
+//! backend on `http://127.0.0.1`, where HSTS is meaningless and, once cached
+//! by the webview, can break other local services on the same host; it omits
+//! HSTS and lets the CSP connect to local ports. When `DEPLOYMENT_MODE` is
+//! unset, `HOTEL_DESKTOP_MODE` implies `desktop`; see
+//! [`AppConfig`](super::config::AppConfig).
+
+use std::net::Ipv6Addr;
+
+use axum::http::{HeaderName, HeaderValue, header};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeploymentMode {
+    Desktop,
+    Web,
+}
+
+const WEB_CSP: &str = "default-src 'self'; \
+    script-src 'self'; \
+    style-src 'self'; \
+    img-src 'self' data: https:; \
+    font-src 'self' data:; \
+    connect-src 'self'; \
+    frame-ancestors 'none';";
+
+const DESKTOP_CSP: &str = "default-src 'self'; \
+    script-src 'self'; \
+    style-src 'self'; \
+    img-src 'self' data: https:; \
+    font-src 'self' data:; \
+    connect-src 'self' http://127.0.0.1:* http://localhost:* ws://127.0.0.1:* ws://localhost:*; \
+    frame-ancestors 'none';";
+
+const WEB_ALLOWED_ORIGINS: &str = "http://localhost:3000,http://localhost:5173";
+
+impl DeploymentMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "desktop" => Some(Self::Desktop),
+            "web" => Some(Self::Web),
+            _ => None,
+        }
+    }
+
+    /// `ALLOWED_ORIGINS` when it isn't set: any origin for the desktop
+    /// webview, the dev servers for web.
+    pub fn default_allowed_origins(self) -> &'static str {
+        match self {
+            Self::Desktop => "*",
+            Self::Web => WEB_ALLOWED_ORIGINS,
+        }
+    }
+}
+
+/// `ALLOWED_ORIGINS` after checking each entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// `*`: any origin, without credentials
+    Any,
+    List {
+        origins: Vec<HeaderValue>,
+        /// Entries that are not an origin and are left out
+        rejected: Vec<String>,
+    },
+}
+
+impl AllowedOrigins {
+    /// Split a comma-separated `ALLOWED_ORIGINS` value. Blank entries are
+    /// skipped; anything that isn't a bare `http(s)://host[:port]` origin,
+    /// including one with a path or trailing slash that no browser would
+    /// send, is rejected.
+    pub fn parse(value: &str) -> Self {
+        if value.trim() == "*" {
+            return Self::Any;
+        }
+        let mut origins = Vec::new();
+        let mut rejected = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match HeaderValue::from_str(entry) {
+                Ok(origin) if is_origin(entry) => origins.push(origin),
+                _ => rejected.push(entry.to_string()),
+            }
+        }
+        Self::List { origins, rejected }
+    }
+}
+
+fn is_origin(entry: &str) -> bool {
+    let Some(authority) = entry
+        .strip_prefix("https://")
+        .or_else(|| entry.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let (host_ok, port) = match authority.strip_prefix('[') {
+        // An IPv6 literal such as [::1]:5173
+        Some(rest) => match rest.split_once(']') {
+            Some((ip, port)) => (ip.parse::<Ipv6Addr>().is_ok(), port),
+            None => return false,
+        },
+        None => {
+            let (host, port) = authority.split_at(authority.find(':').unwrap_or(authority.len()));
+            let host_ok = !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+            (host_ok, port)
+        }
+    };
+    let port_ok = port.is_empty()
+        || port
+            .strip_prefix(':')
+            .is_some_and(|p| p.parse::<u16>().is_ok_and(|p| p > 0));
+    host_ok && port_ok
+}
+
+/// Response-header layers for `mode`. Each only sets its header when the
+/// handler hasn't.
+pub fn security_header_layers(mode: DeploymentMode) -> Vec<SetResponseHeaderLayer<HeaderValue>> {
+    let mut headers: Vec<(HeaderName, &'static str)> = Vec::new();
+    if mode == DeploymentMode::Web {
+        headers.push((
+            header::STRICT_TRANSPORT_SECURITY,
+            "max-age=31536000; includeSubDomains",
+        ));
+    }
+    headers.extend([
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        (header::X_FRAME_OPTIONS, "DENY"),
+        (header::X_XSS_PROTECTION, "1; mode=block"),
+        (
+            header::CONTENT_SECURITY_POLICY,
+            match mode {
+                DeploymentMode::Desktop => DESKTOP_CSP,
+                DeploymentMode::Web => WEB_CSP,
+            },
+        ),
+        (header::REFERRER_POLICY, "strict-origin-when-cross-origin"),
+    ]);
+
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            SetResponseHeaderLayer::if_not_present(name, HeaderValue::from_static(value))
+        })
+        .collect()
+}
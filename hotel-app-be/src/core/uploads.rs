@@ -0,0 +1,201 @@
+//! Upload size and file-type limits
+//!
+//! Each upload endpoint declares an [`UploadPolicy`]: what it accepts, the
+//! largest file it takes and the file types allowed. A file's declared
+//! content type must be on the policy's allow-list, and its leading bytes
+//! must match that type; the declared type alone is never trusted. Multipart
+//! files are read chunk by chunk through [`read_file`], so a disallowed type
+//! is refused before any of it is read and an oversized file as soon as it
+//! passes the limit rather than after it has been buffered.
+//!
+//! `MAX_UPLOAD_MB` (see [`AppConfig`](super::config::AppConfig)) caps every
+//! policy's limit, letting a deployment accept less than an endpoint would.
+
+use axum::extract::multipart::Field;
+
+use super::config;
+use super::error::ApiError;
+
+/// Room left for multipart boundaries and headers in route body limits.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// A file type recognised by its leading bytes.
+#[derive(Debug)]
+pub struct FileType {
+    pub name: &'static str,
+    pub content_types: &'static [&'static str],
+    /// Extension to store the file with
+    pub extension: &'static str,
+    signature: fn(&[u8]) -> bool,
+}
+
+impl FileType {
+    /// Whether `data` starts like a file of this type.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        (self.signature)(data)
+    }
+}
+
+pub static JPEG: FileType = FileType {
+    name: "JPEG",
+    content_types: &["image/jpeg", "image/jpg"],
+    extension: "jpg",
+    signature: |data| data.starts_with(&[0xFF, 0xD8, 0xFF]),
+};
+
+pub static PNG: FileType = FileType {
+    name: "PNG",
+    content_types: &["image/png"],
+    extension: "png",
+    signature: |data| data.starts_with(b"\x89PNG\r\n\x1a\n"),
+};
+
+pub static WEBP: FileType = FileType {
+    name: "WebP",
+    content_types: &["image/webp"],
+    extension: "webp",
+    signature: |data| data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP".as_slice()),
+};
+
+pub static PDF: FileType = FileType {
+    name: "PDF",
+    content_types: &["application/pdf"],
+    extension: "pdf",
+    signature: |data| data.starts_with(b"%PDF-"),
+};
+
+/// What an upload endpoint accepts.
+#[derive(Debug, Clone, Copy)]
+pub struct UploadPolicy {
+    /// What is uploaded, for error messages, e.g. "Avatar"
+    pub what: &'static str,
+    /// Largest accepted file in bytes, before the `MAX_UPLOAD_MB` cap
+    pub max_bytes: usize,
+    pub file_types: &'static [&'static FileType],
+}
+
+impl UploadPolicy {
+    /// This policy with its limit lowered to `MAX_UPLOAD_MB` where that is
+    /// smaller.
+    pub fn capped(&self) -> Self {
+        Self {
+            max_bytes: self
+                .max_bytes
+                .min(config::get().max_upload_mb * 1024 * 1024),
+            ..*self
+        }
+    }
+
+    /// Request body limit for a route taking one such file as multipart.
+    pub fn body_limit(&self) -> usize {
+        self.max_bytes + MULTIPART_OVERHEAD
+    }
+
+    /// The allowed file type declared as `content_type`.
+    pub fn declared_type(&self, content_type: &str) -> Result<&'static FileType, ApiError> {
+        let content_type = content_type.trim().to_ascii_lowercase();
+        self.file_types
+            .iter()
+            .copied()
+            .find(|t| t.content_types.contains(&content_type.as_str()))
+            .ok_or_else(|| self.disallowed())
+    }
+
+    /// Check a complete file declared as `content_type`: its size, that the
+    /// type is allowed, and that its contents are of that type.
+    pub fn check(&self, content_type: &str, data: &[u8]) -> Result<&'static FileType, ApiError> {
+        let file_type = self.declared_type(content_type)?;
+        self.check_size(data.len())?;
+        if !file_type.matches(data) {
+            return Err(ApiError::BadRequest(format!(
+                "{} contents are not a valid {} file",
+                self.what, file_type.name
+            )));
+        }
+        Ok(file_type)
+    }
+
+    /// Check a complete file with no declared type, such as a base64 image
+    /// in a JSON body, by its contents alone.
+    pub fn sniff(&self, data: &[u8]) -> Result<&'static FileType, ApiError> {
+        self.check_size(data.len())?;
+        self.file_types
+            .iter()
+            .copied()
+            .find(|t| t.matches(data))
+            .ok_or_else(|| self.disallowed())
+    }
+
+    fn check_size(&self, len: usize) -> Result<(), ApiError> {
+        if len == 0 {
+            return Err(ApiError::BadRequest(format!("{} file is empty", self.what)));
+        }
+        if len > self.max_bytes {
+            return Err(self.too_large());
+        }
+        Ok(())
+    }
+
+    fn disallowed(&self) -> ApiError {
+        ApiError::BadRequest(format!(
+            "{} must be a {} file",
+            self.what,
+            self.type_names()
+        ))
+    }
+
+    fn too_large(&self) -> ApiError {
+        let limit = if self.max_bytes.is_multiple_of(1024 * 1024) {
+            format!("{} MB", self.max_bytes / (1024 * 1024))
+        } else {
+            format!("{} KB", self.max_bytes.div_ceil(1024))
+        };
+        ApiError::BadRequest(format!("{} must be at most {}", self.what, limit))
+    }
+
+    /// "JPEG, PNG or WebP"
+    fn type_names(&self) -> String {
+        let names: Vec<_> = self.file_types.iter().map(|t| t.name).collect();
+        match names.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+            _ => names.concat(),
+        }
+    }
+}
+
+/// A multipart file that passed its policy.
+#[derive(Debug)]
+pub struct Upload {
+    /// The declared content type
+    pub content_type: String,
+    pub file_type: &'static FileType,
+    pub data: Vec<u8>,
+}
+
+/// Read a multipart file field under `policy`, capped by `MAX_UPLOAD_MB`.
+/// A disallowed declared type is refused before the file is read, and an
+/// oversized one as soon as it passes the limit.
+pub async fn read_file(mut field: Field<'_>, policy: &UploadPolicy) -> Result<Upload, ApiError> {
+    let policy = policy.capped();
+    let content_type = field.content_type().unwrap_or("").to_string();
+    policy.declared_type(&content_type)?;
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?
+    {
+        if data.len() + chunk.len() > policy.max_bytes {
+            return Err(policy.too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    let file_type = policy.check(&content_type, &data)?;
+    Ok(Upload {
+        content_type,
+        file_type,
+        data,
+    })
+}
@@ -0,0 +1,412 @@
+//! WebAuthn credential parsing and assertion verification
+//!
+//! Registration keeps the credential's COSE public key from the attestation
+//! object (the attestation statement itself is not checked). Login verifies
+//! the assertion signature over `authenticatorData || SHA-256(clientDataJSON)`
+//! with that key, checks the client data, origin and relying party, and
+//! requires the signature counter to move forward. ES256 and RS256 keys are supported.
+
+use super::error::ApiError;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::{Algorithm, DecodingKey};
+use sha2::{Digest, Sha256};
+
+/// User present flag in authenticator data.
+const FLAG_USER_PRESENT: u8 = 0x01;
+/// Attested credential data included flag.
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// rpIdHash (32) + flags (1) + signCount (4).
+const AUTH_DATA_HEADER_LEN: usize = 37;
+
+/// Deepest CBOR nesting accepted; COSE keys and attestation objects need 3.
+const MAX_CBOR_DEPTH: usize = 8;
+
+const COSE_KTY_EC2: i128 = 2;
+const COSE_KTY_RSA: i128 = 3;
+const COSE_ALG_ES256: i128 = -7;
+const COSE_ALG_RS256: i128 = -257;
+const COSE_CRV_P256: i128 = 1;
+
+/// A decoded CBOR item. Only what WebAuthn structures use is kept.
+#[derive(Debug, Clone, PartialEq)]
+enum Cbor {
+    Int(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Simple,
+}
+
+impl Cbor {
+    fn get(&self, key: &Cbor) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn get_int_key(&self, key: i128) -> Option<&Cbor> {
+        self.get(&Cbor::Int(key))
+    }
+
+    fn get_text_key(&self, key: &str) -> Option<&Cbor> {
+        self.get(&Cbor::Text(key.to_string()))
+    }
+
+    fn as_int(&self) -> Option<i128> {
+        match self {
+            Cbor::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+/// Reads definite-length CBOR items from the front of a buffer.
+struct CborReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let slice = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    fn argument(&mut self, info: u8) -> Option<u64> {
+        match info {
+            0..=23 => Some(info as u64),
+            24 => self.take(1).map(|b| b[0] as u64),
+            25 => self
+                .take(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as u64),
+            26 => self
+                .take(4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as u64),
+            27 => self
+                .take(8)
+                .map(|b| u64::from_be_bytes(b.try_into().unwrap())),
+            // Indefinite lengths and reserved values are not used by WebAuthn.
+            _ => None,
+        }
+    }
+
+    fn read(&mut self, depth: usize) -> Option<Cbor> {
+        if depth > MAX_CBOR_DEPTH {
+            return None;
+        }
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        if major == 7 {
+            // false, true, null, undefined and floats carry no data we need.
+            return match info {
+                20..=23 => Some(Cbor::Simple),
+                25 => self.take(2).map(|_| Cbor::Simple),
+                26 => self.take(4).map(|_| Cbor::Simple),
+                27 => self.take(8).map(|_| Cbor::Simple),
+                _ => None,
+            };
+        }
+
+        let arg = self.argument(info)?;
+        match major {
+            0 => Some(Cbor::Int(arg as i128)),
+            1 => Some(Cbor::Int(-1 - arg as i128)),
+            2 => {
+                let len = usize::try_from(arg).ok()?;
+                self.take(len).map(|b| Cbor::Bytes(b.to_vec()))
+            }
+            3 => {
+                let len = usize::try_from(arg).ok()?;
+                let text = std::str::from_utf8(self.take(len)?).ok()?;
+                Some(Cbor::Text(text.to_string()))
+            }
+            4 => {
+                // Every item takes at least one byte, so the count is bounded
+                // by the input as items are read.
+                let mut items = Vec::new();
+                for _ in 0..arg {
+                    items.push(self.read(depth + 1)?);
+                }
+                Some(Cbor::Array(items))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                for _ in 0..arg {
+                    let key = self.read(depth + 1)?;
+                    let value = self.read(depth + 1)?;
+                    entries.push((key, value));
+                }
+                Some(Cbor::Map(entries))
+            }
+            // Tags: the tagged item stands in for the tag.
+            6 => self.read(depth + 1),
+            _ => None,
+        }
+    }
+}
+
+fn decode_cbor(data: &[u8]) -> Option<(Cbor, usize)> {
+    let mut reader = CborReader::new(data);
+    let item = reader.read(0)?;
+    Some((item, reader.pos))
+}
+
+/// Credential created at registration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key bytes as sent by the authenticator.
+    pub public_key: Vec<u8>,
+}
+
+/// Parsed authenticator data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+}
+
+fn malformed(what: &str) -> ApiError {
+    ApiError::BadRequest(format!("Malformed {}", what))
+}
+
+/// Parses the binary authenticator data structure.
+pub fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData, ApiError> {
+    if data.len() < AUTH_DATA_HEADER_LEN {
+        return Err(malformed("authenticator data"));
+    }
+    let mut rp_id_hash = [0u8; 32];
+    rp_id_hash.copy_from_slice(&data[..32]);
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+    let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+        // aaguid (16) + credentialIdLength (2) + credentialId + COSE key.
+        let rest = &data[AUTH_DATA_HEADER_LEN..];
+        let id_len_bytes = rest
+            .get(16..18)
+            .ok_or_else(|| malformed("authenticator data"))?;
+        let id_len = u16::from_be_bytes([id_len_bytes[0], id_len_bytes[1]]) as usize;
+        let credential_id = rest
+            .get(18..18 + id_len)
+            .ok_or_else(|| malformed("authenticator data"))?
+            .to_vec();
+        let key_bytes = &rest[18 + id_len..];
+        let (_, key_len) = decode_cbor(key_bytes).ok_or_else(|| malformed("credential key"))?;
+        Some(AttestedCredential {
+            credential_id,
+            public_key: key_bytes[..key_len].to_vec(),
+        })
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags,
+        sign_count,
+        attested_credential,
+    })
+}
+
+/// Reads the new credential out of a registration attestation object.
+///
+/// The key must be one `verify_assertion` can use, so unsupported
+/// algorithms are turned away at registration rather than at login.
+pub fn parse_attestation_object(data: &[u8]) -> Result<AuthenticatorData, ApiError> {
+    let (object, _) = decode_cbor(data).ok_or_else(|| malformed("attestation object"))?;
+    let auth_data = object
+        .get_text_key("authData")
+        .and_then(Cbor::as_bytes)
+        .ok_or_else(|| malformed("attestation object"))?;
+    let parsed = parse_authenticator_data(auth_data)?;
+
+    let credential = parsed
+        .attested_credential
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Attestation has no credential".to_string()))?;
+    public_key_from_cose(&credential.public_key)?;
+    Ok(parsed)
+}
+
+/// Turns a COSE key into a verification key and its algorithm.
+fn public_key_from_cose(cose: &[u8]) -> Result<(DecodingKey, Algorithm), ApiError> {
+    let unsupported = || ApiError::BadRequest("Unsupported passkey algorithm".to_string());
+    let (key, _) = decode_cbor(cose).ok_or_else(|| malformed("credential key"))?;
+    let kty = key.get_int_key(1).and_then(Cbor::as_int);
+    let alg = key.get_int_key(3).and_then(Cbor::as_int);
+
+    match (kty, alg) {
+        (Some(COSE_KTY_EC2), Some(COSE_ALG_ES256)) => {
+            if key.get_int_key(-1).and_then(Cbor::as_int) != Some(COSE_CRV_P256) {
+                return Err(unsupported());
+            }
+            let x = key.get_int_key(-2).and_then(Cbor::as_bytes);
+            let y = key.get_int_key(-3).and_then(Cbor::as_bytes);
+            match (x, y) {
+                (Some(x), Some(y)) if x.len() == 32 && y.len() == 32 => {
+                    let mut point = Vec::with_capacity(65);
+                    point.push(0x04);
+                    point.extend_from_slice(x);
+                    point.extend_from_slice(y);
+                    Ok((DecodingKey::from_ec_der(&point), Algorithm::ES256))
+                }
+                _ => Err(malformed("credential key")),
+            }
+        }
+        (Some(COSE_KTY_RSA), Some(COSE_ALG_RS256)) => {
+            let n = key.get_int_key(-1).and_then(Cbor::as_bytes);
+            let e = key.get_int_key(-2).and_then(Cbor::as_bytes);
+            match (n, e) {
+                (Some(n), Some(e)) => {
+                    Ok((DecodingKey::from_rsa_raw_components(n, e), Algorithm::RS256))
+                }
+                _ => Err(malformed("credential key")),
+            }
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Converts an ASN.1 DER ECDSA signature into the fixed `r || s` form.
+fn ecdsa_der_to_fixed(der: &[u8]) -> Option<[u8; 64]> {
+    fn integer(input: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (&tag, rest) = input.split_first()?;
+        let (&len, rest) = rest.split_first()?;
+        if tag != 0x02 || len as usize > rest.len() {
+            return None;
+        }
+        Some(rest.split_at(len as usize))
+    }
+
+    let (&tag, rest) = der.split_first()?;
+    let (&len, body) = rest.split_first()?;
+    if tag != 0x30 || len as usize != body.len() {
+        return None;
+    }
+    let (r, rest) = integer(body)?;
+    let (s, rest) = integer(rest)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let mut fixed = [0u8; 64];
+    let (r_out, s_out) = fixed.split_at_mut(32);
+    for (value, out) in [(r, r_out), (s, s_out)] {
+        let trimmed = match value.iter().position(|&b| b != 0) {
+            Some(start) => &value[start..],
+            None => &[][..],
+        };
+        if trimmed.len() > 32 {
+            return None;
+        }
+        out[32 - trimmed.len()..].copy_from_slice(trimmed);
+    }
+    Some(fixed)
+}
+
+/// The three parts of an authenticator's assertion response.
+#[derive(Debug, Clone, Copy)]
+pub struct Assertion<'a> {
+    pub authenticator_data: &'a [u8],
+    pub client_data_json: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+/// Verifies a login assertion against a stored credential.
+///
+/// `challenge` is the one issued for this login and `stored_counter` the
+/// signature counter saved from the last use. `rp_id` and `origin` are the
+/// relying party's id and the origin its pages are served from. Returns the new counter to
+/// store. A counter that does not increase means the credential may have
+/// been cloned; authenticators that keep no counter report 0 every time
+/// and are let through.
+pub fn verify_assertion(
+    assertion: &Assertion,
+    public_key: &[u8],
+    stored_counter: u32,
+    rp_id: &str,
+    origin: &str,
+    challenge: &[u8],
+) -> Result<u32, ApiError> {
+    let rejected = |reason: &str| ApiError::Unauthorized(format!("Passkey rejected: {}", reason));
+
+    let client_data: serde_json::Value =
+        serde_json::from_slice(assertion.client_data_json).map_err(|_| malformed("client data"))?;
+    if client_data["type"].as_str() != Some("webauthn.get") {
+        return Err(rejected("wrong client data type"));
+    }
+    // An assertion made on another site must not log in here.
+    if client_data["origin"].as_str() != Some(origin) {
+        return Err(rejected("origin mismatch"));
+    }
+    let signed_challenge = client_data["challenge"]
+        .as_str()
+        .and_then(|c| URL_SAFE_NO_PAD.decode(c.trim_end_matches('=')).ok());
+    if signed_challenge.as_deref() != Some(challenge) {
+        return Err(rejected("challenge mismatch"));
+    }
+
+    let auth_data = parse_authenticator_data(assertion.authenticator_data)?;
+    if auth_data.rp_id_hash[..] != Sha256::digest(rp_id.as_bytes())[..] {
+        return Err(rejected("relying party mismatch"));
+    }
+    if !auth_data.user_present() {
+        return Err(rejected("user not present"));
+    }
+
+    let (key, algorithm) = public_key_from_cose(public_key)
+        .map_err(|_| rejected("stored key is unusable, register the passkey again"))?;
+    let signature = match algorithm {
+        Algorithm::ES256 => ecdsa_der_to_fixed(assertion.signature)
+            .ok_or_else(|| malformed("signature"))?
+            .to_vec(),
+        _ => assertion.signature.to_vec(),
+    };
+    let mut message = assertion.authenticator_data.to_vec();
+    message.extend_from_slice(&Sha256::digest(assertion.client_data_json));
+
+    let valid = jsonwebtoken::crypto::verify(
+        &URL_SAFE_NO_PAD.encode(signature),
+        &message,
+        &key,
+        algorithm,
+    )
+    .unwrap_or(false);
+    if !valid {
+        return Err(rejected("invalid signature"));
+    }
+
+    let counter = auth_data.sign_count;
+    if (counter != 0 || stored_counter != 0) && counter <= stored_counter {
+        return Err(rejected("signature counter did not increase"));
+    }
+    Ok(counter)
+}
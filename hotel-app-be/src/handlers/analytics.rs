@@ -6,9 +6,16 @@ use crate::core::auth::AuthService;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::{require_auth, require_permission_helper};
-use crate::models::ReportQuery;
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 use crate::models::row_mappers;
+use crate::models::{ChannelQuery, ChannelReport, PaceQuery, PaceReport, ReportQuery};
+use crate::services::booking_sources;
+use crate::services::booking_trends;
+use crate::services::company_bookings;
+use crate::services::currency;
+use crate::services::hotel_time;
+use crate::services::occupancy;
+use crate::services::pace;
 use axum::{
     extract::{Query, State},
     http::HeaderMap,
@@ -39,15 +46,18 @@ pub async fn get_occupancy_report_handler(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    let today = hotel_time::hotel_today(&pool).await?;
+
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     let occupied_rooms: i64 = sqlx::query_scalar(
         r#"
         SELECT COUNT(DISTINCT room_id) FROM bookings
         WHERE status NOT IN ('voided')
-        AND check_in_date <= date('now')
-        AND check_out_date > date('now')
+        AND check_in_date <= ?1
+        AND check_out_date > ?1
         "#,
     )
+    .bind(today)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -57,10 +67,11 @@ pub async fn get_occupancy_report_handler(
         r#"
         SELECT COUNT(DISTINCT room_id) FROM bookings
         WHERE status NOT IN ('voided')
-        AND check_in_date <= CURRENT_DATE
-        AND check_out_date > CURRENT_DATE
+        AND check_in_date <= $1
+        AND check_out_date > $1
         "#,
     )
+    .bind(today)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -93,10 +104,11 @@ pub async fn get_occupancy_report_handler(
         r#"
         SELECT COALESCE(CAST(SUM(total_amount) AS TEXT), '0') as revenue FROM bookings
         WHERE status NOT IN ('voided')
-        AND check_in_date <= date('now')
-        AND check_out_date > date('now')
+        AND check_in_date <= ?1
+        AND check_out_date > ?1
         "#,
     )
+    .bind(today)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -109,10 +121,11 @@ pub async fn get_occupancy_report_handler(
         r#"
         SELECT COALESCE(SUM(total_amount), 0) FROM bookings
         WHERE status NOT IN ('voided')
-        AND check_in_date <= CURRENT_DATE
-        AND check_out_date > CURRENT_DATE
+        AND check_in_date <= $1
+        AND check_out_date > $1
         "#,
     )
+    .bind(today)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -123,7 +136,8 @@ pub async fn get_occupancy_report_handler(
         "occupancyRate": occupancy_rate,
         "availableRooms": available_rooms,
         "utilization": occupancy_rate,
-        "revenue": revenue.to_string().parse::<f64>().unwrap_or(0.0)
+        "revenue": revenue.to_string().parse::<f64>().unwrap_or(0.0),
+        "currency": currency::hotel_currency(&pool).await
     })))
 }
 
@@ -190,23 +204,58 @@ pub async fn get_booking_analytics_handler(
         })
         .collect();
 
-    // Monthly trends (simplified - last 6 months)
-    let monthly_trends = vec![serde_json::json!({
-        "month": "Current Month",
-        "bookings": total_bookings,
-        "revenue": total_revenue.to_string().parse::<f64>().unwrap_or(0.0)
-    })];
+    let today = hotel_time::hotel_today(&pool).await?;
+    let monthly_trends: Vec<serde_json::Value> = booking_trends::booking_trends(&pool, today)
+        .await?
+        .into_iter()
+        .map(|trend| {
+            serde_json::json!({
+                "month": trend.month.format("%b %Y").to_string(),
+                "bookings": trend.bookings,
+                "revenue": trend.revenue.to_string().parse::<f64>().unwrap_or(0.0)
+            })
+        })
+        .collect();
 
     Ok(Json(serde_json::json!({
         "totalBookings": total_bookings,
         "averageBookingValue": average_booking_value.to_string().parse::<f64>().unwrap_or(0.0),
         "totalRevenue": total_revenue.to_string().parse::<f64>().unwrap_or(0.0),
+        "currency": currency::hotel_currency(&pool).await,
         "bookingsByRoomType": bookings_by_room_type,
         "peakBookingHours": [9, 10, 11, 14, 15, 16],
         "monthlyTrends": monthly_trends
     })))
 }
 
+/// Room-nights and room revenue on the books for each date in a range
+pub async fn get_pace_handler(
+    State(pool): State<DbPool>,
+    Query(query): Query<PaceQuery>,
+) -> Result<Json<PaceReport>, ApiError> {
+    let start = NaiveDate::parse_from_str(&query.start, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid start date. Use YYYY-MM-DD".to_string()))?;
+    let end = NaiveDate::parse_from_str(&query.end, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid end date. Use YYYY-MM-DD".to_string()))?;
+
+    Ok(Json(pace::pace_report(&pool, start, end).await?))
+}
+
+/// Bookings, room-nights, revenue and ADR per booking source
+pub async fn get_channel_report_handler(
+    State(pool): State<DbPool>,
+    Query(query): Query<ChannelQuery>,
+) -> Result<Json<ChannelReport>, ApiError> {
+    let start = NaiveDate::parse_from_str(&query.start, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid start date. Use YYYY-MM-DD".to_string()))?;
+    let end = NaiveDate::parse_from_str(&query.end, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid end date. Use YYYY-MM-DD".to_string()))?;
+
+    Ok(Json(
+        booking_sources::channel_report(&pool, start, end).await?,
+    ))
+}
+
 // Personalized report handler - generates reports tailored to user role and context
 pub async fn get_personalized_report_handler(
     State(pool): State<DbPool>,
@@ -244,8 +293,9 @@ pub async fn get_personalized_report_handler(
 
             // Get occupancy
             let occupied_rooms: i64 = sqlx::query_scalar(
-            "SELECT COUNT(DISTINCT room_id) FROM bookings WHERE status NOT IN ('voided') AND check_in_date <= CURRENT_DATE AND check_out_date > CURRENT_DATE"
+            "SELECT COUNT(DISTINCT room_id) FROM bookings WHERE status NOT IN ('voided') AND check_in_date <= $1 AND check_out_date > $1"
         )
+        .bind(hotel_time::hotel_today(&pool).await?)
         .fetch_one(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -322,6 +372,7 @@ pub async fn get_personalized_report_handler(
             "totalRevenue": total_revenue.to_string().parse::<f64>().unwrap_or(0.0),
             "averageBookingValue": if total_bookings > 0 { total_revenue.to_string().parse::<f64>().unwrap_or(0.0) / total_bookings as f64 } else { 0.0 }
         },
+        "currency": currency::hotel_currency(&pool).await,
         "recentBookings": recent_bookings,
         "insights": insights,
         "generatedAt": chrono::Utc::now().to_rfc3339()
@@ -354,7 +405,7 @@ pub async fn generate_report_handler(
     let end_date = parse_date_flexible(&params.end_date)
         .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
 
-    let report_data = match params.report_type.as_str() {
+    let mut report_data = match params.report_type.as_str() {
         // Legacy accounting reports
         "balance_sheet" => generate_balance_sheet(&pool, start_date, end_date).await?,
         "journal_by_type" => generate_journal_by_type(&pool, start_date, end_date).await?,
@@ -394,6 +445,12 @@ pub async fn generate_report_handler(
             )));
         }
     };
+    if let Some(report) = report_data.as_object_mut() {
+        report.insert(
+            "currency".to_string(),
+            serde_json::Value::String(currency::hotel_currency(&pool).await),
+        );
+    }
 
     Ok(Json(report_data))
 }
@@ -404,9 +461,9 @@ async fn generate_balance_sheet(
     start_date: NaiveDate,
     end_date: NaiveDate,
 ) -> Result<serde_json::Value, ApiError> {
-    // Get total room revenue
-    let room_revenue: Decimal = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(total_amount), 0) FROM bookings
+    // Get total room revenue and the service tax included in it
+    let (room_revenue, service_tax): (Decimal, Decimal) = sqlx::query_as(
+        "SELECT COALESCE(SUM(total_amount), 0), COALESCE(SUM(tax_amount), 0) FROM bookings
          WHERE check_in_date >= $1 AND check_in_date <= $2 AND status IN ('confirmed', 'checked_in', 'checked_out')"
     )
     .bind(start_date)
@@ -426,24 +483,8 @@ async fn generate_balance_sheet(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Read service tax rate from system_settings (default 8%)
-    let tax_rate_pct: Decimal = {
-        let raw = sqlx::query_scalar::<_, String>(
-            "SELECT value FROM system_settings WHERE key = 'service_tax_rate'",
-        )
-        .fetch_optional(pool)
-        .await
-        .unwrap_or(None)
-        .and_then(|v| v.parse::<Decimal>().ok())
-        .unwrap_or(Decimal::ZERO);
-        if raw > Decimal::ZERO {
-            raw
-        } else {
-            Decimal::new(8, 0)
-        }
-    };
-    let tax_rate = tax_rate_pct / Decimal::new(100, 0);
-    let service_tax = room_revenue * tax_rate;
+    // Room prices include service tax, so revenue is what remains after it
+    let net_revenue = room_revenue - service_tax;
 
     let accounts = vec![
         serde_json::json!({
@@ -461,8 +502,8 @@ async fn generate_balance_sheet(
         serde_json::json!({
             "name": "Room Revenue",
             "debit": 0,
-            "credit": room_revenue,
-            "balance": -room_revenue
+            "credit": net_revenue,
+            "balance": -net_revenue
         }),
         serde_json::json!({
             "name": "Sales Tax Payable",
@@ -473,7 +514,7 @@ async fn generate_balance_sheet(
     ];
 
     let total_debit = room_revenue;
-    let total_credit = room_revenue + deposits + service_tax;
+    let total_credit = net_revenue + deposits + service_tax;
     let total_balance = total_debit - total_credit;
 
     Ok(serde_json::json!({
@@ -490,24 +531,6 @@ async fn generate_journal_by_type(
     start_date: NaiveDate,
     end_date: NaiveDate,
 ) -> Result<serde_json::Value, ApiError> {
-    // Read service tax rate from system_settings (default 8%)
-    let tax_rate_pct: Decimal = {
-        let raw = sqlx::query_scalar::<_, String>(
-            "SELECT value FROM system_settings WHERE key = 'service_tax_rate'",
-        )
-        .fetch_optional(pool)
-        .await
-        .unwrap_or(None)
-        .and_then(|v| v.parse::<Decimal>().ok())
-        .unwrap_or(Decimal::ZERO);
-        if raw > Decimal::ZERO {
-            raw
-        } else {
-            Decimal::new(8, 0)
-        }
-    };
-    let tax_rate = tax_rate_pct / Decimal::new(100, 0);
-
     let rows = sqlx::query(
         "SELECT
             b.id,
@@ -515,6 +538,7 @@ async fn generate_journal_by_type(
             b.booking_number as folio,
             r.room_number as room,
             b.total_amount,
+            COALESCE(b.tax_amount, 0) as tax_amount,
             b.status,
             g.full_name as guest_name
          FROM bookings b
@@ -551,8 +575,8 @@ async fn generate_journal_by_type(
         }));
         total_debit += amount;
 
-        // Credit entry (Service Tax)
-        let service_tax = amount * tax_rate;
+        // Credit entry (Service Tax), as stored on the booking
+        let service_tax: Decimal = row.get("tax_amount");
         transactions.push(serde_json::json!({
             "date": date.and_hms_opt(8, 0, 0).unwrap().and_utc().to_rfc3339(),
             "folio": folio.unwrap_or_default(),
@@ -706,7 +730,7 @@ async fn generate_rooms_sold_report(
             r.room_number,
             rt.name as room_type,
             g.full_name as guest_name
-         FROM bookings b
+         FROM bookings_all b
          JOIN rooms r ON b.room_id = r.id
          JOIN room_types rt ON r.room_type_id = rt.id
          LEFT JOIN guests g ON b.guest_id = g.id
@@ -761,7 +785,7 @@ async fn generate_general_journal(
             b.check_in_date as date,
             b.booking_number as folio,
             b.total_amount,
-            b.room_rate,
+            COALESCE(b.subtotal, b.total_amount) as subtotal,
             COALESCE(b.tax_amount, 0) as tax_amount,
             COALESCE(b.tourism_tax_amount, 0) as tourism_tax_amount,
             b.payment_status,
@@ -805,7 +829,7 @@ async fn generate_general_journal(
     for row in rows {
         let date: NaiveDate = row.get("date");
         let _total_amount: Decimal = row.get("total_amount");
-        let room_rate: Decimal = row.get("room_rate");
+        let subtotal: Decimal = row.get("subtotal");
         let tax_amount: Decimal = row.get("tax_amount");
         let tourism_tax_amount: Decimal = row.get("tourism_tax_amount");
         let payment_status: Option<String> = row.get("payment_status");
@@ -814,10 +838,11 @@ async fn generate_general_journal(
         let room_number: String = row.get("room_number");
         let date_str = date.format("%d/%m/%Y").to_string();
 
-        // Use actual values from booking instead of hardcoded calculations
+        // The subtotal is tax-inclusive: split it using the service tax stored
+        // on the booking so the journal agrees with the folio
+        let room_charge = subtotal - tax_amount;
         let service_tax = tax_amount;
         let tourism_tax = tourism_tax_amount;
-        let room_charge = room_rate;
 
         // Use actual deposit amount from booking
         let deposit_amount = deposit_amount_val;
@@ -1048,9 +1073,10 @@ async fn generate_daily_operations_report(
     date: NaiveDate,
 ) -> Result<serde_json::Value, ApiError> {
     // Today's arrivals (expected check-ins)
-    let arrivals: Vec<(i64, String, String, String, Option<String>)> = sqlx::query_as(
+    let arrivals: Vec<(i64, String, String, String, Option<String>, bool)> = sqlx::query_as(
         r#"
-        SELECT b.id, b.booking_number, g.full_name, r.room_number, b.payment_status
+        SELECT b.id, b.booking_number, g.full_name, r.room_number, b.payment_status,
+               COALESCE(g.is_vip, false)
         FROM bookings b
         JOIN guests g ON b.guest_id = g.id
         JOIN rooms r ON b.room_id = r.id
@@ -1130,16 +1156,38 @@ async fn generate_daily_operations_report(
         0.0
     };
 
+    // Tonight's occupancy per room type, on the same terms as the totals
+    let by_room_type: Vec<(String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT rt.name, COUNT(r.id)::bigint, COUNT(sold.room_id)::bigint
+        FROM rooms r
+        JOIN room_types rt ON r.room_type_id = rt.id
+        LEFT JOIN (
+            SELECT DISTINCT room_id FROM bookings
+            WHERE check_in_date <= $1 AND check_out_date > $1
+            AND status NOT IN ('voided')
+        ) sold ON sold.room_id = r.id
+        WHERE r.is_active = true
+        GROUP BY rt.name
+        ORDER BY rt.name
+        "#,
+    )
+    .bind(date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
     let arrivals_json: Vec<serde_json::Value> = arrivals
         .into_iter()
         .map(
-            |(id, booking_number, guest_name, room_number, payment_status)| {
+            |(id, booking_number, guest_name, room_number, payment_status, is_vip)| {
                 serde_json::json!({
                     "id": id,
                     "booking_number": booking_number,
                     "guest_name": guest_name,
                     "room_number": room_number,
-                    "payment_status": payment_status
+                    "payment_status": payment_status,
+                    "is_vip": is_vip
                 })
             },
         )
@@ -1181,6 +1229,19 @@ async fn generate_daily_operations_report(
         .map(|(status, count)| (status, serde_json::Value::Number(count.into())))
         .collect();
 
+    let by_room_type_json: Vec<serde_json::Value> = by_room_type
+        .into_iter()
+        .map(|(room_type, rooms, sold)| {
+            serde_json::json!({
+                "room_type": room_type,
+                "total_rooms": rooms,
+                "sold": sold,
+                "available": rooms - sold,
+                "occupancy_rate": if rooms > 0 { (sold as f64 / rooms as f64) * 100.0 } else { 0.0 }
+            })
+        })
+        .collect();
+
     Ok(serde_json::json!({
         "date": date.to_string(),
         "arrivals": arrivals_json,
@@ -1192,7 +1253,8 @@ async fn generate_daily_operations_report(
         "room_status": room_status_map,
         "total_rooms": total_rooms,
         "tonight_occupied": tonight_occupied,
-        "occupancy_rate": occupancy_rate
+        "occupancy_rate": occupancy_rate,
+        "by_room_type": by_room_type_json
     }))
 }
 
@@ -1214,7 +1276,7 @@ async fn generate_occupancy_report(
     let stats: (Option<i64>, Option<Decimal>) = sqlx::query_as(
         r#"
         SELECT COUNT(*)::bigint, SUM(total_amount)
-        FROM bookings
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
         AND status NOT IN ('voided')
         "#,
@@ -1245,17 +1307,17 @@ async fn generate_occupancy_report(
         Decimal::ZERO
     };
 
-    let occupancy_rate = if available_room_nights > 0 {
-        (rooms_sold as f64 / available_room_nights as f64) * 100.0
-    } else {
-        0.0
-    };
+    // Occupancy compares room-nights with room-nights: a five-night stay in
+    // the range fills five of them, not one.
+    let nights = occupancy::occupancy_by_night(pool, start_date, end_date, total_rooms).await?;
+    let occupied_room_nights = occupancy::occupied_room_nights(&nights);
+    let occupancy_rate = occupancy::occupancy_rate(occupied_room_nights, available_room_nights);
 
     // Occupancy by room type
     let by_room_type: Vec<(String, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT rt.name, COUNT(*), SUM(b.total_amount)
-        FROM bookings b
+        FROM bookings_all b
         JOIN rooms r ON b.room_id = r.id
         JOIN room_types rt ON r.room_type_id = rt.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
@@ -1280,30 +1342,16 @@ async fn generate_occupancy_report(
         })
         .collect();
 
-    // Daily breakdown
-    let daily_data: Vec<(NaiveDate, i64, Option<Decimal>)> = sqlx::query_as(
-        r#"
-        SELECT check_in_date, COUNT(*), SUM(total_amount)
-        FROM bookings
-        WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
-        GROUP BY check_in_date
-        ORDER BY check_in_date
-        "#,
-    )
-    .bind(start_date)
-    .bind(end_date)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    let daily_json: Vec<serde_json::Value> = daily_data.into_iter()
-        .map(|(date, count, revenue)| {
+    // Daily breakdown: every night a stay covers, not just its arrival day
+    let daily_json: Vec<serde_json::Value> = nights
+        .into_iter()
+        .map(|night| {
             serde_json::json!({
-                "date": date.to_string(),
-                "bookings": count,
-                "revenue": revenue.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0),
-                "occupancy_rate": if total_rooms > 0 { (count as f64 / total_rooms as f64) * 100.0 } else { 0.0 }
+                "date": night.date.to_string(),
+                "bookings": night.arrivals,
+                "rooms_occupied": night.rooms_occupied,
+                "revenue": night.revenue.to_string().parse::<f64>().unwrap_or(0.0),
+                "occupancy_rate": night.occupancy_rate
             })
         })
         .collect();
@@ -1318,6 +1366,7 @@ async fn generate_occupancy_report(
             "total_rooms": total_rooms,
             "rooms_sold": rooms_sold,
             "available_room_nights": available_room_nights,
+            "occupied_room_nights": occupied_room_nights,
             "occupancy_rate": occupancy_rate,
             "total_revenue": total_revenue.to_string().parse::<f64>().unwrap_or(0.0),
             "adr": adr.to_string().parse::<f64>().unwrap_or(0.0),
@@ -1334,12 +1383,12 @@ async fn generate_revenue_report(
     start_date: NaiveDate,
     end_date: NaiveDate,
 ) -> Result<serde_json::Value, ApiError> {
-    // Total revenue
+    // Room revenue; cancelled and no-show stays earn only their fees
     let total_revenue: Option<Decimal> = sqlx::query_scalar(
         r#"
-        SELECT SUM(total_amount) FROM bookings
+        SELECT SUM(total_amount) FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
         "#,
     )
     .bind(start_date)
@@ -1348,17 +1397,36 @@ async fn generate_revenue_report(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    let total = total_revenue.unwrap_or(Decimal::ZERO);
+    let room_revenue = total_revenue.unwrap_or(Decimal::ZERO);
+
+    // Cancellation and no-show fees charged in the period
+    let fees: Vec<(String, i64, Option<Decimal>)> = sqlx::query_as(
+        r#"
+        SELECT fee_type, COUNT(*), SUM(amount)
+        FROM booking_fees
+        WHERE fee_date >= $1 AND fee_date <= $2
+        GROUP BY fee_type
+        ORDER BY fee_type
+        "#,
+    )
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let fee_revenue: Decimal = fees.iter().filter_map(|(_, _, amount)| *amount).sum();
+    let total = room_revenue + fee_revenue;
 
     // Revenue by room type
     let by_room_type: Vec<(String, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT rt.name, COUNT(*), SUM(b.total_amount)
-        FROM bookings b
+        FROM bookings_all b
         JOIN rooms r ON b.room_id = r.id
         JOIN room_types rt ON r.room_type_id = rt.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
-        AND b.status NOT IN ('voided')
+        AND b.status NOT IN ('voided', 'cancelled', 'no_show')
         GROUP BY rt.name
         ORDER BY SUM(b.total_amount) DESC
         "#,
@@ -1369,19 +1437,21 @@ async fn generate_revenue_report(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Revenue by source
+    // Revenue by source; bookings without one count under the default source
+    let default_source = booking_sources::configured(pool).await.default_source;
     let by_source: Vec<(Option<String>, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
-        SELECT source, COUNT(*), SUM(total_amount)
-        FROM bookings
+        SELECT COALESCE(NULLIF(TRIM(source), ''), $3), COUNT(*), SUM(total_amount)
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
-        GROUP BY source
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
+        GROUP BY 1
         ORDER BY SUM(total_amount) DESC
         "#,
     )
     .bind(start_date)
     .bind(end_date)
+    .bind(&default_source)
     .fetch_all(pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1390,9 +1460,9 @@ async fn generate_revenue_report(
     let by_payment_status: Vec<(Option<String>, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT payment_status, COUNT(*), SUM(total_amount)
-        FROM bookings
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
         GROUP BY payment_status
         ORDER BY SUM(total_amount) DESC
         "#,
@@ -1407,9 +1477,9 @@ async fn generate_revenue_report(
     let daily_data: Vec<(NaiveDate, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT check_in_date, COUNT(*), SUM(total_amount)
-        FROM bookings
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
         GROUP BY check_in_date
         ORDER BY check_in_date
         "#,
@@ -1420,6 +1490,9 @@ async fn generate_revenue_report(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    // Revenue by company, for bookings attributed to one
+    let by_company = company_bookings::revenue_by_company(pool, start_date, end_date).await?;
+
     let by_room_type_json: Vec<serde_json::Value> = by_room_type.into_iter()
         .map(|(room_type, count, revenue)| {
             serde_json::json!({
@@ -1433,7 +1506,7 @@ async fn generate_revenue_report(
     let by_source_json: Vec<serde_json::Value> = by_source.into_iter()
         .map(|(source, count, revenue)| {
             serde_json::json!({
-                "source": source.unwrap_or_else(|| "unknown".to_string()),
+                "source": source.unwrap_or_else(|| default_source.clone()),
                 "bookings": count,
                 "revenue": revenue.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0)
             })
@@ -1450,6 +1523,29 @@ async fn generate_revenue_report(
         })
         .collect();
 
+    let by_company_json: Vec<serde_json::Value> = by_company
+        .into_iter()
+        .map(|company| {
+            serde_json::json!({
+                "company_id": company.company_id,
+                "company_name": company.company_name,
+                "bookings": company.bookings,
+                "revenue": company.revenue.to_string().parse::<f64>().unwrap_or(0.0)
+            })
+        })
+        .collect();
+
+    let fees_json: Vec<serde_json::Value> = fees
+        .into_iter()
+        .map(|(fee_type, count, amount)| {
+            serde_json::json!({
+                "fee_type": fee_type,
+                "bookings": count,
+                "revenue": amount.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0)
+            })
+        })
+        .collect();
+
     let daily_json: Vec<serde_json::Value> = daily_data.into_iter()
         .map(|(date, count, revenue)| {
             serde_json::json!({
@@ -1466,9 +1562,13 @@ async fn generate_revenue_report(
             "end": end_date.to_string()
         },
         "total_revenue": total.to_string().parse::<f64>().unwrap_or(0.0),
+        "room_revenue": room_revenue.to_string().parse::<f64>().unwrap_or(0.0),
+        "fee_revenue": fee_revenue.to_string().parse::<f64>().unwrap_or(0.0),
+        "fees": fees_json,
         "by_room_type": by_room_type_json,
         "by_source": by_source_json,
         "by_payment_status": by_payment_status_json,
+        "by_company": by_company_json,
         "daily": daily_json
     }))
 }
@@ -1483,9 +1583,9 @@ async fn generate_payment_status_report(
     let by_status: Vec<(Option<String>, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT payment_status, COUNT(*), SUM(total_amount)
-        FROM bookings
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
         GROUP BY payment_status
         ORDER BY COUNT(*) DESC
         "#,
@@ -1496,12 +1596,12 @@ async fn generate_payment_status_report(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Calculate outstanding balance (unpaid bookings)
+    // Calculate outstanding balance (unpaid stays)
     let outstanding: Option<Decimal> = sqlx::query_scalar(
         r#"
-        SELECT SUM(total_amount) FROM bookings
+        SELECT SUM(total_amount) FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
-        AND status NOT IN ('voided')
+        AND status NOT IN ('voided', 'cancelled', 'no_show')
         AND payment_status IN ('unpaid', 'unpaid_deposit', 'partial')
         "#,
     )
@@ -1511,21 +1611,49 @@ async fn generate_payment_status_report(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    // Fees charged in the period, and what the booking's payments leave unpaid
+    let fees: Vec<(String, i64, Option<Decimal>, Option<Decimal>)> = sqlx::query_as(
+        r#"
+        SELECT bf.fee_type, COUNT(*), SUM(bf.amount),
+               SUM(CASE WHEN bf.amount > paid.amount THEN bf.amount - paid.amount ELSE 0 END)
+        FROM booking_fees bf
+        JOIN (
+            SELECT b.id AS booking_id,
+                   COALESCE(SUM(CASE WHEN COALESCE(p.payment_type, 'booking') = 'refund'
+                                     THEN -p.amount ELSE p.amount END), 0) AS amount
+            FROM bookings_all b
+            LEFT JOIN payments p ON p.booking_id = b.id AND p.status = 'completed'
+            GROUP BY b.id
+        ) paid ON paid.booking_id = bf.booking_id
+        WHERE bf.fee_date >= $1 AND bf.fee_date <= $2
+        GROUP BY bf.fee_type
+        ORDER BY bf.fee_type
+        "#,
+    )
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let fees_outstanding: Decimal = fees.iter().filter_map(|(_, _, _, unpaid)| *unpaid).sum();
+
     // Overdue payments (past check-out with unpaid status)
     #[allow(clippy::type_complexity)]
     let overdue: Vec<(i64, String, String, String, Decimal, NaiveDate, Option<String>)> = sqlx::query_as(
         r#"
         SELECT b.id, b.booking_number, g.full_name, r.room_number, b.total_amount, b.check_out_date, b.payment_status
-        FROM bookings b
+        FROM bookings_all b
         JOIN guests g ON b.guest_id = g.id
         JOIN rooms r ON b.room_id = r.id
-        WHERE b.check_out_date < CURRENT_DATE
-        AND b.status NOT IN ('voided')
+        WHERE b.check_out_date < $1
+        AND b.status NOT IN ('voided', 'cancelled', 'no_show')
         AND b.payment_status IN ('unpaid', 'unpaid_deposit', 'partial')
         ORDER BY b.check_out_date DESC
         LIMIT 50
         "#
     )
+    .bind(hotel_time::hotel_today(pool).await?)
     .fetch_all(pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1540,6 +1668,18 @@ async fn generate_payment_status_report(
         })
         .collect();
 
+    let fees_json: Vec<serde_json::Value> = fees
+        .into_iter()
+        .map(|(fee_type, count, amount, unpaid)| {
+            serde_json::json!({
+                "fee_type": fee_type,
+                "count": count,
+                "total_amount": amount.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0),
+                "outstanding": unpaid.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0)
+            })
+        })
+        .collect();
+
     let overdue_json: Vec<serde_json::Value> = overdue
         .into_iter()
         .map(
@@ -1563,7 +1703,9 @@ async fn generate_payment_status_report(
             "end": end_date.to_string()
         },
         "by_status": by_status_json,
-        "outstanding_balance": outstanding.unwrap_or(Decimal::ZERO).to_string().parse::<f64>().unwrap_or(0.0),
+        "outstanding_balance": (outstanding.unwrap_or(Decimal::ZERO) + fees_outstanding).to_string().parse::<f64>().unwrap_or(0.0),
+        "fees": fees_json,
+        "fees_outstanding": fees_outstanding.to_string().parse::<f64>().unwrap_or(0.0),
         "overdue": overdue_json,
         "overdue_count": overdue_json.len()
     }))
@@ -1599,7 +1741,7 @@ async fn generate_complimentary_report(
                b.is_complimentary, b.complimentary_reason,
                b.complimentary_start_date, b.complimentary_end_date,
                b.original_total_amount, b.total_amount, b.complimentary_nights, b.status
-        FROM bookings b
+        FROM bookings_all b
         JOIN guests g ON b.guest_id = g.id
         JOIN rooms r ON b.room_id = r.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
@@ -1692,7 +1834,7 @@ async fn generate_guest_statistics_report(
     // Total unique guests in period
     let unique_guests: Option<i64> = sqlx::query_scalar(
         r#"
-        SELECT COUNT(DISTINCT guest_id)::bigint FROM bookings
+        SELECT COUNT(DISTINCT guest_id)::bigint FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
         AND status NOT IN ('voided')
         "#,
@@ -1708,11 +1850,11 @@ async fn generate_guest_statistics_report(
     let new_guests: Option<i64> = sqlx::query_scalar(
         r#"
         SELECT COUNT(DISTINCT b.guest_id)::bigint
-        FROM bookings b
+        FROM bookings_all b
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
         AND b.status NOT IN ('voided')
         AND NOT EXISTS (
-            SELECT 1 FROM bookings prev
+            SELECT 1 FROM bookings_all prev
             WHERE prev.guest_id = b.guest_id
             AND prev.check_in_date < $1
             AND prev.status NOT IN ('voided')
@@ -1732,7 +1874,7 @@ async fn generate_guest_statistics_report(
     let tourist_stats: Vec<(Option<bool>, i64)> = sqlx::query_as(
         r#"
         SELECT b.is_tourist, COUNT(DISTINCT b.guest_id)::bigint
-        FROM bookings b
+        FROM bookings_all b
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
         AND b.status NOT IN ('voided')
         GROUP BY b.is_tourist
@@ -1758,7 +1900,7 @@ async fn generate_guest_statistics_report(
     let avg_stay: Option<f64> = sqlx::query_scalar(
         r#"
         SELECT AVG(check_out_date - check_in_date)::float
-        FROM bookings
+        FROM bookings_all
         WHERE check_in_date >= $1 AND check_in_date <= $2
         AND status NOT IN ('voided')
         "#,
@@ -1773,7 +1915,7 @@ async fn generate_guest_statistics_report(
     let by_nationality: Vec<(Option<String>, i64)> = sqlx::query_as(
         r#"
         SELECT g.nationality, COUNT(DISTINCT b.guest_id)
-        FROM bookings b
+        FROM bookings_all b
         JOIN guests g ON b.guest_id = g.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
         AND b.status NOT IN ('voided')
@@ -1803,7 +1945,7 @@ async fn generate_guest_statistics_report(
     let top_guests: Vec<(i64, String, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT g.id, g.full_name, COUNT(*) as booking_count, SUM(b.total_amount)
-        FROM bookings b
+        FROM bookings_all b
         JOIN guests g ON b.guest_id = g.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
         AND b.status NOT IN ('voided')
@@ -1857,7 +1999,7 @@ async fn generate_room_performance_report(
     let by_room: Vec<(String, String, i64, Option<Decimal>)> = sqlx::query_as(
         r#"
         SELECT r.room_number, rt.name as room_type, COUNT(*), SUM(b.total_amount)
-        FROM bookings b
+        FROM bookings_all b
         JOIN rooms r ON b.room_id = r.id
         JOIN room_types rt ON r.room_type_id = rt.id
         WHERE b.check_in_date >= $1 AND b.check_in_date <= $2
@@ -1878,7 +2020,7 @@ async fn generate_room_performance_report(
         SELECT rt.name, COUNT(DISTINCT r.id) as room_count, COUNT(b.id), SUM(b.total_amount)
         FROM rooms r
         JOIN room_types rt ON r.room_type_id = rt.id
-        LEFT JOIN bookings b ON b.room_id = r.id
+        LEFT JOIN bookings_all b ON b.room_id = r.id
             AND b.check_in_date >= $1 AND b.check_in_date <= $2
             AND b.status NOT IN ('voided')
         WHERE r.is_active = true
@@ -1898,7 +2040,7 @@ async fn generate_room_performance_report(
         SELECT r.room_number, rt.name, COUNT(b.id)
         FROM rooms r
         JOIN room_types rt ON r.room_type_id = rt.id
-        LEFT JOIN bookings b ON b.room_id = r.id
+        LEFT JOIN bookings_all b ON b.room_id = r.id
             AND b.check_in_date >= $1 AND b.check_in_date <= $2
             AND b.status NOT IN ('voided')
         WHERE r.is_active = true
@@ -2073,7 +2215,7 @@ async fn generate_company_ledger_statement(
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
     // Calculate aging buckets based on invoice_date or created_at
-    let today = chrono::Utc::now().date_naive();
+    let today = hotel_time::hotel_today(pool).await?;
     let mut open_balance = Decimal::ZERO;
     let mut days_31_60 = Decimal::ZERO;
     let mut days_61_90 = Decimal::ZERO;
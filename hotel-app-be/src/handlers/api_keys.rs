@@ -0,0 +1,106 @@
+//! API key handlers
+//!
+//! Lets a signed-in user create, list and revoke their own integration keys.
+
+use crate::core::api_keys::{self, MAX_EXPIRY_DAYS};
+use crate::core::auth::AuthService;
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::*;
+use crate::services::audit::AuditLog;
+use crate::utils::sanitization::Sanitizer;
+use axum::{
+    extract::{Extension, Path, State},
+    response::Json,
+};
+use chrono::{Duration, Utc};
+
+pub async fn list_api_keys_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    Ok(Json(api_keys::list_api_keys(&pool, user_id).await?))
+}
+
+pub async fn create_api_key_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, ApiError> {
+    let name = Sanitizer::sanitize_text(input.name.trim());
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(ApiError::BadRequest(
+            "API key name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+
+    let scopes = api_keys::normalize_scopes(&input.scopes).map_err(ApiError::BadRequest)?;
+
+    // A key can never do more than its owner.
+    for scope in &scopes {
+        let held = AuthService::check_permission(&pool, user_id, scope)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        if !held {
+            return Err(ApiError::Forbidden(format!(
+                "Cannot grant scope you do not hold: {}",
+                scope
+            )));
+        }
+    }
+
+    let expires_at = match input.expires_in_days {
+        Some(days) if !(1..=MAX_EXPIRY_DAYS).contains(&days) => {
+            return Err(ApiError::BadRequest(format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_EXPIRY_DAYS
+            )));
+        }
+        Some(days) => Some(Utc::now() + Duration::days(days)),
+        None => None,
+    };
+
+    let (key, api_key) =
+        api_keys::create_api_key(&pool, user_id, &name, &scopes, expires_at).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "api_key_created",
+        "api_key",
+        Some(api_key.id),
+        Some(serde_json::json!({
+            "name": api_key.name,
+            "scopes": api_key.scopes,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(CreateApiKeyResponse { key, api_key }))
+}
+
+pub async fn revoke_api_key_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Path(key_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    api_keys::revoke_api_key(&pool, user_id, key_id).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "api_key_revoked",
+        "api_key",
+        Some(key_id),
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(
+        serde_json::json!({"message": "API key revoked successfully"}),
+    ))
+}
@@ -3,16 +3,84 @@
 //! Handles login, logout, registration, and token management.
 
 use crate::core::auth::AuthService;
+use crate::core::config;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
+use crate::core::middleware::{extract_claims, extract_user_id};
+use crate::core::password_change;
+use crate::core::revoked_tokens;
 use crate::models::*;
 use crate::services::audit::AuditLog;
-use axum::{extract::State, response::Json};
+use crate::services::login_attempts;
+use crate::services::login_history;
+use crate::services::password_policy;
+use crate::services::portal_guest::{self, PortalGuestDetails};
+use crate::services::sessions;
+use axum::{
+    extract::{Extension, Query, State},
+    http::HeaderMap,
+    response::Json,
+};
+use std::net::IpAddr;
+use std::sync::LazyLock;
+
+/// Bcrypt hash checked when the username is unknown, so that failure takes
+/// as long as a wrong password.
+static UNKNOWN_USER_HASH: LazyLock<String> = LazyLock::new(|| {
+    bcrypt::hash("unknown-user-placeholder", bcrypt::DEFAULT_COST)
+        .expect("bcrypt hash of a constant")
+});
+
+fn locked_out(minutes: i64) -> ApiError {
+    ApiError::TooManyRequests(format!(
+        "Too many failed login attempts. Try again in {} minute(s).",
+        minutes
+    ))
+}
 
+/// Log in with a password, recording the attempt in the user's login
+/// history.
 pub async fn login_handler(
     State(pool): State<DbPool>,
+    client_ip: IpAddr,
+    user_agent: Option<String>,
+    Json(req): Json<LoginRequest>,
+) -> Result<Json<AuthResponse>, ApiError> {
+    let username = req.username.clone();
+    let result = password_login(State(pool.clone()), client_ip, Json(req)).await;
+    login_history::record(
+        &pool,
+        result.as_ref().ok().map(|r| r.user.id),
+        &username,
+        login_history::PASSWORD_LOGIN,
+        login_history::outcome(&result),
+        &client_ip.to_string(),
+        user_agent.as_deref(),
+    )
+    .await;
+    result
+}
+
+async fn password_login(
+    State(pool): State<DbPool>,
+    client_ip: IpAddr,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, ApiError> {
+    let ip = client_ip.to_string();
+
+    // Lockouts are per username and IP, and apply to unknown usernames too
+    if let Some(minutes) = login_attempts::locked_for(&pool, &req.username, &ip).await? {
+        let _ = AuditLog::log_login_failure(
+            &pool,
+            &req.username,
+            "Account locked",
+            Some(ip.clone()),
+            None,
+        )
+        .await;
+        return Err(locked_out(minutes));
+    }
+
     let user = sqlx::query_as::<_, User>(
         "SELECT id, username, email, full_name, phone, is_active, is_verified, user_type, two_factor_enabled, two_factor_secret, two_factor_recovery_codes, created_at, updated_at FROM users WHERE (username = $1 OR email = $1) AND deleted_at IS NULL"
     )
@@ -21,141 +89,67 @@ pub async fn login_handler(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    let user = match user {
-        Some(u) if u.is_active => u,
-        Some(_) => {
-            let _ = AuditLog::log_login_failure(
-                &pool,
-                &req.username,
-                "Account is inactive",
-                None,
-                None,
-            )
-            .await;
-            return Err(ApiError::Unauthorized("Account is inactive".to_string()));
-        }
-        None => {
-            let _ = AuditLog::log_login_failure(&pool, &req.username, "User not found", None, None)
-                .await;
-            return Err(ApiError::Unauthorized("Invalid credentials".to_string()));
-        }
+    // Get password hash
+    let password_hash: Option<String> = match &user {
+        Some(u) => sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+            .bind(u.id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?,
+        None => None,
     };
 
-    // Check account lockout
-    let (is_locked, locked_until, failed_attempts): (
-        Option<bool>,
-        Option<chrono::NaiveDateTime>,
-        Option<i32>,
-    ) = sqlx::query_as(
-        "SELECT is_locked, locked_until, failed_login_attempts FROM users WHERE id = $1",
+    // Verify password; unknown users are checked against a placeholder hash
+    // so the response takes the same time
+    let valid = AuthService::verify_password(
+        &req.password,
+        password_hash.as_deref().unwrap_or(&UNKNOWN_USER_HASH),
     )
-    .bind(user.id)
-    .fetch_one(&pool)
     .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+    .map_err(|_| ApiError::Internal("Password verification failed".to_string()))?
+        && password_hash.is_some();
 
-    if is_locked.unwrap_or(false)
-        && let Some(until) = locked_until
-    {
-        let now = chrono::Utc::now().naive_utc();
-        if now < until {
-            let remaining_mins = (until - now).num_minutes() + 1;
-            let _ = AuditLog::log_login_failure(&pool, &req.username, "Account locked", None, None)
-                .await;
-            return Err(ApiError::TooManyRequests(format!(
-                "Account is locked due to too many failed attempts. Try again in {} minute(s).",
-                remaining_mins
-            )));
+    let user = match user {
+        Some(u) if valid => u,
+        user => {
+            let reason = if user.is_some() {
+                "Invalid password"
+            } else {
+                "User not found"
+            };
+            let _ =
+                AuditLog::log_login_failure(&pool, &req.username, reason, Some(ip.clone()), None)
+                    .await;
+
+            let policy = login_attempts::configured_policy(&pool).await;
+            if let Some(minutes) =
+                login_attempts::record_failure(&pool, &req.username, &ip, &policy).await?
+            {
+                return Err(locked_out(minutes));
+            }
+            return Err(ApiError::Unauthorized("Invalid credentials".to_string()));
         }
-        // Lock expired - unlock the account
-        sqlx::query("UPDATE users SET is_locked = false, locked_until = NULL, failed_login_attempts = 0 WHERE id = $1")
-                .bind(user.id)
-                .execute(&pool)
-                .await
-                .ok();
+    };
+
+    if !user.is_active {
+        let _ = AuditLog::log_login_failure(
+            &pool,
+            &req.username,
+            "Account is inactive",
+            Some(ip.clone()),
+            None,
+        )
+        .await;
+        return Err(ApiError::Unauthorized("Account is inactive".to_string()));
     }
 
     // Check email verification (can be disabled in development with SKIP_EMAIL_VERIFICATION env var)
-    let skip_email_verification = std::env::var("SKIP_EMAIL_VERIFICATION")
-        .unwrap_or_else(|_| "false".to_string())
-        .to_lowercase()
-        == "true";
-
-    if !skip_email_verification && !user.is_verified {
+    if !config::get().skip_email_verification && !user.is_verified {
         return Err(ApiError::Unauthorized(
             "Please verify your email address before logging in. Check your email for the verification link.".to_string()
         ));
     }
 
-    // Get password hash
-    let password_hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
-        .bind(user.id)
-        .fetch_one(&pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    // Max login attempts before lockout (default 5)
-    let max_attempts: i32 = sqlx::query_scalar::<_, Option<String>>(
-        "SELECT value FROM system_settings WHERE key = 'max_login_attempts'",
-    )
-    .fetch_optional(&pool)
-    .await
-    .ok()
-    .flatten()
-    .flatten()
-    .and_then(|v| v.parse().ok())
-    .unwrap_or(5);
-
-    // Verify password
-    let valid = AuthService::verify_password(&req.password, &password_hash)
-        .await
-        .map_err(|_| ApiError::Internal("Password verification failed".to_string()))?;
-
-    if !valid {
-        // Increment failed login attempts
-        let new_attempts = failed_attempts.unwrap_or(0) + 1;
-        let should_lock = new_attempts >= max_attempts;
-
-        if should_lock {
-            sqlx::query(
-                "UPDATE users SET failed_login_attempts = $1, is_locked = true, locked_until = $2 WHERE id = $3"
-            )
-            .bind(new_attempts)
-            .bind(chrono::Utc::now().naive_utc() + chrono::Duration::minutes(30))
-            .bind(user.id)
-            .execute(&pool)
-            .await
-            .ok();
-
-            let _ = AuditLog::log_login_failure(
-                &pool,
-                &req.username,
-                "Account locked after max attempts",
-                None,
-                None,
-            )
-            .await;
-            return Err(ApiError::TooManyRequests(
-                "Account locked due to too many failed login attempts. Try again in 30 minutes."
-                    .to_string(),
-            ));
-        } else {
-            sqlx::query("UPDATE users SET failed_login_attempts = $1 WHERE id = $2")
-                .bind(new_attempts)
-                .execute(&pool)
-                .await
-                .ok();
-        }
-
-        let remaining = max_attempts - new_attempts;
-        let _ =
-            AuditLog::log_login_failure(&pool, &req.username, "Invalid password", None, None).await;
-        return Err(ApiError::Unauthorized(format!(
-            "Invalid credentials. {} attempt(s) remaining before account lockout.",
-            remaining
-        )));
-    }
-
     // Get user 2FA status and check if 2FA code is required
     let (two_factor_enabled, two_factor_secret): (Option<bool>, Option<String>) =
         sqlx::query_as("SELECT two_factor_enabled, two_factor_secret FROM users WHERE id = $1")
@@ -200,12 +194,8 @@ pub async fn login_handler(
         }
     }
 
-    // Reset failed login attempts on successful login
-    sqlx::query("UPDATE users SET failed_login_attempts = 0, is_locked = false, locked_until = NULL WHERE id = $1")
-        .bind(user.id)
-        .execute(&pool)
-        .await
-        .ok();
+    // Reset failed login attempts only once every factor has passed
+    login_attempts::clear(&pool, &req.username, &ip).await?;
 
     // Get roles and permissions
     let roles = AuthService::get_user_roles(&pool, user.id)
@@ -219,9 +209,6 @@ pub async fn login_handler(
     let access_token = AuthService::generate_jwt(user.id, user.username.clone(), roles.clone())
         .map_err(|e| ApiError::Internal(format!("Token generation failed: {}", e)))?;
 
-    // Generate secure refresh token
-    let refresh_token = AuthService::generate_refresh_token();
-
     // Check if this is the first login
     let is_first_login: bool =
         sqlx::query_scalar("SELECT last_login_at IS NULL FROM users WHERE id = $1")
@@ -229,11 +216,10 @@ pub async fn login_handler(
             .fetch_one(&pool)
             .await
             .unwrap_or(false);
+    let must_change_password = password_change::is_required(&pool, user.id).await?;
 
-    // Store refresh token (expires in 30 days)
-    AuthService::store_refresh_token(&pool, user.id, &refresh_token, 30)
-        .await
-        .map_err(|e| ApiError::Database(format!("Failed to store refresh token: {}", e)))?;
+    // Issue a refresh token for a new session
+    let refresh_token = sessions::issue_refresh_token(&pool, user.id, None).await?;
 
     // Update last login
     sqlx::query("UPDATE users SET last_login_at = CURRENT_TIMESTAMP WHERE id = $1")
@@ -257,20 +243,33 @@ pub async fn login_handler(
         roles,
         permissions,
         is_first_login,
+        must_change_password,
     };
 
     Ok(Json(response))
 }
 
+/// The authenticated user's recent logins, newest first.
+pub async fn get_login_history_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Query(query): Query<LoginHistoryQuery>,
+) -> Result<Json<PaginatedResponse<Vec<LoginHistoryEntry>>>, ApiError> {
+    Ok(Json(
+        login_history::login_history(&pool, user_id, query.page, query.page_size).await?,
+    ))
+}
+
 pub async fn refresh_token_handler(
     State(pool): State<DbPool>,
     Json(req): Json<RefreshTokenRequest>,
 ) -> Result<Json<RefreshTokenResponse>, ApiError> {
     // Validate refresh token
-    let user_id = AuthService::validate_refresh_token(&pool, &req.refresh_token)
+    let session = AuthService::validate_refresh_token(&pool, &req.refresh_token)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
         .ok_or_else(|| ApiError::Unauthorized("Invalid or expired refresh token".to_string()))?;
+    let user_id = session.user_id;
 
     // Get user info
     let user = sqlx::query_as::<_, User>(
@@ -295,18 +294,14 @@ pub async fn refresh_token_handler(
     let access_token = AuthService::generate_jwt(user.id, user.username.clone(), roles.clone())
         .map_err(|e| ApiError::Internal(format!("Token generation failed: {}", e)))?;
 
-    // Generate new refresh token
-    let new_refresh_token = AuthService::generate_refresh_token();
-
     // Revoke old refresh token
     AuthService::revoke_refresh_token(&pool, &req.refresh_token)
         .await
         .map_err(|e| ApiError::Database(format!("Failed to revoke old token: {}", e)))?;
 
-    // Store new refresh token (expires in 30 days)
-    AuthService::store_refresh_token(&pool, user.id, &new_refresh_token, 30)
-        .await
-        .map_err(|e| ApiError::Database(format!("Failed to store refresh token: {}", e)))?;
+    // Rotate within the same session; the expiry mode decides whether it extends
+    let new_refresh_token =
+        sessions::issue_refresh_token(&pool, user.id, Some(session.session_started_at)).await?;
 
     let response = RefreshTokenResponse {
         access_token,
@@ -318,6 +313,7 @@ pub async fn refresh_token_handler(
 
 pub async fn logout_handler(
     State(pool): State<DbPool>,
+    headers: HeaderMap,
     Json(req): Json<RefreshTokenRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Revoke the refresh token
@@ -325,6 +321,19 @@ pub async fn logout_handler(
         .await
         .map_err(|e| ApiError::Database(format!("Failed to revoke token: {}", e)))?;
 
+    // And the access token sent with the request, which would otherwise stay
+    // valid until it expires. Missing or already invalid tokens need nothing.
+    if let Ok(claims) = extract_claims(&headers).await
+        && !claims.jti.is_empty()
+    {
+        let user_id = extract_user_id(&claims)?;
+        let expires_at =
+            chrono::DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or_else(chrono::Utc::now);
+        revoked_tokens::revoke(&pool, &claims.jti, user_id, expires_at)
+            .await
+            .map_err(|e| ApiError::Database(format!("Failed to revoke token: {}", e)))?;
+    }
+
     Ok(Json(
         serde_json::json!({"message": "Logged out successfully"}),
     ))
@@ -335,7 +344,7 @@ pub async fn register_handler(
     Json(req): Json<RegisterRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Validate password
-    AuthService::validate_password(&req.password).map_err(ApiError::BadRequest)?;
+    password_policy::validate_password(&pool, &req.password).await?;
 
     // Validate email format
     let email_regex =
@@ -359,44 +368,25 @@ pub async fn register_handler(
         ));
     }
 
-    // Start transaction for atomic guest + user creation
+    let password_hash = AuthService::hash_password(&req.password)
+        .await
+        .map_err(|_| ApiError::Internal("Password hashing failed".to_string()))?;
+    let auto_create_guest = portal_guest::auto_create_enabled(&pool).await;
+
+    // Start transaction for atomic user + guest creation
     let mut tx = pool
         .begin()
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // 1. Create guest profile FIRST
-    let guest: Guest = sqlx::query_as(
-        r#"
-        INSERT INTO guests (
-            first_name, last_name, full_name, email, phone, is_active, created_at
-        )
-        VALUES ($1, $2, $3, $4, $5, true, CURRENT_TIMESTAMP)
-        RETURNING *
-        "#,
-    )
-    .bind(&req.first_name)
-    .bind(&req.last_name)
-    .bind(format!("{} {}", req.first_name, req.last_name))
-    .bind(&req.email)
-    .bind(&req.phone)
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    // 2. Hash password
-    let password_hash = AuthService::hash_password(&req.password)
-        .await
-        .map_err(|_| ApiError::Internal("Password hashing failed".to_string()))?;
-
-    // 3. Create user account linked to guest
+    // 1. Create user account
     let user = sqlx::query_as::<_, User>(
         r#"
         INSERT INTO users (
             username, email, password_hash, full_name, phone,
-            user_type, guest_id, is_active, is_verified, created_at
+            user_type, is_active, is_verified, created_at
         )
-        VALUES ($1, $2, $3, $4, $5, 'guest', $6, true, false, CURRENT_TIMESTAMP)
+        VALUES ($1, $2, $3, $4, $5, 'guest', true, false, CURRENT_TIMESTAMP)
         RETURNING id, username, email, full_name, phone, is_active, is_verified, user_type, two_factor_enabled, two_factor_secret, two_factor_recovery_codes, created_at, updated_at
         "#
     )
@@ -405,17 +395,24 @@ pub async fn register_handler(
     .bind(&password_hash)
     .bind(format!("{} {}", req.first_name, req.last_name))
     .bind(&req.phone)
-    .bind(guest.id)
     .fetch_one(&mut *tx)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // 4. Generate and store email verification token
-    let verification_token = AuthService::create_email_verification_token(&pool, user.id)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    // 2. Create the guest profile and link it so the first booking finds it
+    let guest_id = if auto_create_guest {
+        let details = PortalGuestDetails {
+            first_name: req.first_name.clone(),
+            last_name: req.last_name.clone(),
+            email: req.email.clone(),
+            phone: req.phone.clone(),
+        };
+        Some(portal_guest::create_linked_guest(&mut tx, user.id, &details).await?)
+    } else {
+        None
+    };
 
-    // 5. Automatically assign "guest" role
+    // 3. Automatically assign "guest" role
     let guest_role_id: i64 =
         sqlx::query_scalar("SELECT id FROM roles WHERE name = 'guest' LIMIT 1")
             .fetch_one(&mut *tx)
@@ -429,37 +426,45 @@ pub async fn register_handler(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // 6. Create loyalty membership using guest.id directly
-    let loyalty_program_id: Option<i64> = sqlx::query_scalar(
-        "SELECT id FROM loyalty_programs WHERE tier_level = 1 ORDER BY created_at LIMIT 1",
-    )
-    .fetch_optional(&mut *tx)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    if let Some(program_id) = loyalty_program_id {
-        sqlx::query(
-            r#"
-            INSERT INTO loyalty_memberships (
-                guest_id, program_id, membership_number,
-                points_balance, lifetime_points, tier_level, status, enrolled_date
-            )
-            VALUES ($1, $2, $3, 0, 0, 1, 'active', CURRENT_DATE)
-            "#,
+    // 4. Create loyalty membership for the guest profile
+    if let Some(guest_id) = guest_id {
+        let loyalty_program_id: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM loyalty_programs WHERE tier_level = 1 ORDER BY created_at LIMIT 1",
         )
-        .bind(guest.id)
-        .bind(program_id)
-        .bind(format!("LM-{:08}", guest.id))
-        .execute(&mut *tx)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        if let Some(program_id) = loyalty_program_id {
+            sqlx::query(
+                r#"
+                INSERT INTO loyalty_memberships (
+                    guest_id, program_id, membership_number,
+                    points_balance, lifetime_points, tier_level, status, enrolled_date
+                )
+                VALUES ($1, $2, $3, 0, 0, 1, 'active', CURRENT_DATE)
+                "#,
+            )
+            .bind(guest_id)
+            .bind(program_id)
+            .bind(format!("LM-{:08}", guest_id))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        }
     }
 
-    // 7. Commit transaction
+    // 5. Commit transaction
     tx.commit()
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    // 6. Generate and store email verification token, now that the user row
+    // is visible outside the transaction
+    let verification_token = AuthService::create_email_verification_token(&pool, user.id)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
     Ok(Json(serde_json::json!({
         "message": "Registration successful! Please check your email to verify your account.",
         "user": {
@@ -470,7 +475,7 @@ pub async fn register_handler(
             "user_type": user.user_type,
             "is_verified": user.is_verified,
         },
-        "guest_id": guest.id,
+        "guest_id": guest_id,
         "verification_token": verification_token
     })))
 }
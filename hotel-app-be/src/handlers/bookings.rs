@@ -3,18 +3,41 @@
 //! Handles booking CRUD, check-in/out, and pre-check-in.
 
 use crate::core::auth::AuthService;
-use crate::core::db::DbPool;
+use crate::core::db::{DbPool, decimal_to_db};
 use crate::core::error::ApiError;
 use crate::core::middleware::require_auth;
+use crate::core::property_scope::PropertyScope;
+use crate::core::uploads;
 use crate::handlers::bookings_queries::*;
+use crate::handlers::rooms_queries::{INSERT_ROOM_EVENT, INSERT_ROOM_HISTORY};
 use crate::models::*;
+use crate::repositories::booking::{
+    BookingRepository, CONFLICTING_BOOKINGS_QUERY, SqlBookingRepository, lock_room,
+};
 use crate::services::audit::AuditLog;
 use crate::services::booking as booking_svc;
+use crate::services::booking_attachments;
+use crate::services::booking_fees;
+use crate::services::booking_numbers;
+use crate::services::booking_sources;
+use crate::services::cancellation;
+use crate::services::communication;
+use crate::services::company_bookings;
+use crate::services::currency;
+use crate::services::folio_split;
+use crate::services::guest_balance;
+use crate::services::hotel_time;
+use crate::services::outbox;
+use crate::services::room_assignment;
+use crate::services::tax::{self, TaxContext, TaxMode};
+use crate::services::walk_in;
 use crate::utils::sanitization::Sanitizer;
+use crate::utils::sort::SortSpec;
 use axum::{
-    extract::{Extension, Path, Query, State},
-    http::HeaderMap,
-    response::Json,
+    body::Body,
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{Json, Response},
 };
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
@@ -34,6 +57,18 @@ fn like_operator() -> &'static str {
     return "ILIKE";
 }
 
+/// `value` with LIKE wildcards escaped, for a pattern matched with `ESCAPE '\'`.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 fn date_cast(col: &str) -> String {
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     return format!("date({})", col);
@@ -109,8 +144,10 @@ async fn record_booking_history(
 pub async fn get_booking_timeline_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<Vec<BookingTimelineEntry>>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     let booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
 
     let has_booking_access = AuthService::check_permission(&pool, user_id, "bookings:read")
@@ -278,9 +315,245 @@ pub async fn get_booking_timeline_handler(
     Ok(Json(timeline))
 }
 
-/// Auto-create a `customer_ledgers` room-charge row for a company-billing
-/// booking on checkout. Idempotent: returns Ok(()) without inserting if a
-/// non-reversal `room_charge` row already exists for the booking.
+/// The booking's folio split and the charges billed to each payer.
+pub async fn get_folio_split_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+) -> Result<Json<BookingFolioSplit>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let split = folio_split::booking_folio(&pool, booking_id).await?;
+    Ok(Json(split))
+}
+
+/// Route the booking's charge categories to the guest folio or the company
+/// ledger. Applies to charges posted from now on, i.e. at checkout.
+pub async fn update_folio_split_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+    Json(input): Json<FolioSplitInput>,
+) -> Result<Json<BookingFolioSplit>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let split = folio_split::set_routes(&pool, booking_id, &input.routes, user_id).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "folio_split_updated",
+        "booking",
+        Some(booking_id),
+        Some(serde_json::json!({ "routes": split.routes })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(split))
+}
+
+/// POST /bookings/:id/attachments
+/// Attach the multipart `file` field to the booking
+pub async fn upload_booking_attachment_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Json<BookingAttachment>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().map(str::to_string);
+        let file = uploads::read_file(field, &booking_attachments::ATTACHMENT_UPLOAD).await?;
+        let attachment = booking_attachments::save_attachment(
+            &pool,
+            booking_id,
+            file_name.as_deref(),
+            &file,
+            user_id,
+        )
+        .await?;
+
+        let _ = AuditLog::log_event(
+            &pool,
+            Some(user_id),
+            "booking_attachment_added",
+            "booking",
+            Some(booking_id),
+            Some(serde_json::json!({
+                "attachment_id": attachment.id,
+                "file_name": attachment.file_name,
+            })),
+            None,
+            None,
+        )
+        .await;
+
+        return Ok(Json(attachment));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+/// GET /bookings/:id/attachments
+pub async fn list_booking_attachments_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+) -> Result<Json<Vec<BookingAttachment>>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let attachments = booking_attachments::list_attachments(&pool, booking_id).await?;
+    Ok(Json(attachments))
+}
+
+/// GET /bookings/:id/attachments/:attachment_id
+/// Download an attachment's file
+pub async fn download_booking_attachment_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path((booking_id, attachment_id)): Path<(i64, i64)>,
+) -> Result<Response, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let (attachment, data) =
+        booking_attachments::read_attachment(&pool, booking_id, attachment_id).await?;
+    let file_name = attachment.file_name.replace(['"', '\\'], "_");
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", attachment.content_type)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", file_name),
+        )
+        .header("X-Content-Type-Options", "nosniff")
+        .body(Body::from(data))
+        .unwrap())
+}
+
+/// DELETE /bookings/:id/attachments/:attachment_id
+pub async fn delete_booking_attachment_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path((booking_id, attachment_id)): Path<(i64, i64)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let attachment =
+        booking_attachments::delete_attachment(&pool, booking_id, attachment_id).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "booking_attachment_removed",
+        "booking",
+        Some(booking_id),
+        Some(serde_json::json!({
+            "attachment_id": attachment.id,
+            "file_name": attachment.file_name,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Attachment {} removed", attachment.file_name)
+    })))
+}
+
+/// Invoice a booking that has just checked out and post its company-billed
+/// charges to the city ledger. Best-effort: failures are logged and never
+/// block the checkout itself.
+async fn post_checkout_charges(pool: &DbPool, booking: &Booking, user_id: i64) {
+    let booking_id = booking.id;
+    if let Err(e) =
+        crate::handlers::payments::ensure_invoice_for_booking(pool, booking_id, user_id).await
+    {
+        log::warn!(
+            "Failed to create invoice for checked-out booking {}: {}",
+            booking_id,
+            e
+        );
+    }
+
+    // Auto-post company room charges to customer_ledgers on checkout.
+    //
+    // Why: when a booking with company billing transitions to
+    // checked_out, the receivable must land on the city ledger so
+    // it shows on the company's account. Doing this server-side
+    // ensures every checkout path (Bookings page, Rooms grid,
+    // future paths) gets the same behavior — prior to this only
+    // the Rooms-grid frontend handler created the row, so checkouts
+    // initiated from the Bookings page silently skipped it.
+    //
+    // Idempotent: skip if a non-reversal room_charge row already
+    // exists for this booking. Skip silently when company info is
+    // missing or total_amount is non-positive.
+    if let Some(co_name) = booking.company_name.as_deref()
+        && !co_name.trim().is_empty()
+        && booking.total_amount > Decimal::ZERO
+        && let Err(e) = auto_post_company_ledger(
+            pool,
+            booking,
+            co_name,
+            booking.check_in_date,
+            booking.check_out_date,
+            user_id,
+        )
+        .await
+    {
+        log::warn!(
+            "Failed to auto-post company ledger for booking {}: {}",
+            booking_id,
+            e
+        );
+    }
+}
+
+/// Record a guest moving into or out of a room in `room_history` and
+/// `room_events`, as a manual room status change does.
+async fn record_room_transition(
+    pool: &DbPool,
+    room_id: i64,
+    from_status: Option<&str>,
+    to_status: &str,
+    user_id: i64,
+    notes: &str,
+) {
+    if let Err(e) = sqlx::query(INSERT_ROOM_HISTORY)
+        .bind(room_id)
+        .bind(from_status)
+        .bind(to_status)
+        .bind(None::<chrono::DateTime<chrono::Utc>>)
+        .bind(None::<chrono::DateTime<chrono::Utc>>)
+        .bind(user_id)
+        .bind(notes)
+        .execute(pool)
+        .await
+    {
+        log::warn!("Failed to record room {} history: {}", room_id, e);
+    }
+
+    let _ = sqlx::query(INSERT_ROOM_EVENT)
+        .bind(room_id)
+        .bind(format!("Status changed to: {}", to_status))
+        .bind(user_id)
+        .execute(pool)
+        .await;
+}
+
+/// Auto-create `customer_ledgers` rows for a company-billing booking on
+/// checkout: the room charge, or under a folio split the charges routed to
+/// the company. Idempotent per charge: one that already has a non-reversal
+/// row for the booking is not posted again.
 async fn auto_post_company_ledger(
     pool: &DbPool,
     booking: &Booking,
@@ -291,29 +564,46 @@ async fn auto_post_company_ledger(
 ) -> Result<(), ApiError> {
     let booking_id = booking.id;
 
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let exists: bool = sqlx::query_scalar(
-        "SELECT EXISTS(SELECT 1 FROM customer_ledgers \
-         WHERE booking_id = $1 AND post_type = 'room_charge' \
-         AND COALESCE(is_reversal, false) = false)",
-    )
-    .bind(booking_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false);
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let exists: bool = sqlx::query_scalar::<_, i32>(
-        "SELECT EXISTS(SELECT 1 FROM customer_ledgers \
-         WHERE booking_id = ?1 AND post_type = 'room_charge' \
-         AND COALESCE(is_reversal, 0) = 0)",
-    )
-    .bind(booking_id)
-    .fetch_one(pool)
-    .await
-    .map(|v| v != 0)
-    .unwrap_or(false);
+    let routed =
+        folio_split::company_ledger_charges(pool, booking_id, booking.total_amount).await?;
+    let mut charges = Vec::new();
+    for charge in routed {
+        let (post_type, expense_type) = folio_split::ledger_codes(&charge.category);
+
+        #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM customer_ledgers \
+             WHERE booking_id = $1 AND post_type = $2 \
+             AND (post_type = 'room_charge' OR expense_type = $3) \
+             AND COALESCE(is_reversal, false) = false)",
+        )
+        .bind(booking_id)
+        .bind(post_type)
+        .bind(expense_type)
+        .fetch_one(pool)
+        .await
+        .unwrap_or(false);
+        #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+        let exists: bool = sqlx::query_scalar::<_, i32>(
+            "SELECT EXISTS(SELECT 1 FROM customer_ledgers \
+             WHERE booking_id = ?1 AND post_type = ?2 \
+             AND (post_type = 'room_charge' OR expense_type = ?3) \
+             AND COALESCE(is_reversal, 0) = 0)",
+        )
+        .bind(booking_id)
+        .bind(post_type)
+        .bind(expense_type)
+        .fetch_one(pool)
+        .await
+        .map(|v| v != 0)
+        .unwrap_or(false);
+
+        if !exists {
+            charges.push(charge);
+        }
+    }
 
-    if exists {
+    if charges.is_empty() {
         return Ok(());
     }
 
@@ -367,7 +657,7 @@ async fn auto_post_company_ledger(
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     let terms_days: i64 = 30;
 
-    let today = chrono::Local::now().date_naive();
+    let today = hotel_time::hotel_today(pool).await?;
     let due_date = today + chrono::Duration::days(terms_days);
 
     // Reuse the booking's existing invoice number when one already exists,
@@ -403,79 +693,118 @@ async fn auto_post_company_ledger(
             .ok(),
     };
 
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    sqlx::query(
-        r#"
-        INSERT INTO customer_ledgers (
-            company_name, description, expense_type, amount,
-            booking_id, post_type, posting_date, transaction_date,
-            invoice_date, due_date, room_number,
-            folio_type, transaction_type,
-            created_by, updated_by, cashier_id,
-            invoice_number
+    for charge in &charges {
+        let (post_type, expense_type) = folio_split::ledger_codes(&charge.category);
+        let line_description = if charge.category == folio_split::ROOM {
+            description.clone()
+        } else {
+            format!(
+                "{} - {}",
+                folio_split::category_label(&charge.category),
+                description
+            )
+        };
+
+        #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+        sqlx::query(
+            r#"
+            INSERT INTO customer_ledgers (
+                company_name, description, expense_type, amount,
+                booking_id, post_type, posting_date, transaction_date,
+                invoice_date, due_date, room_number,
+                folio_type, transaction_type,
+                created_by, updated_by, cashier_id,
+                invoice_number
+            )
+            VALUES ($1, $2, $9, $3,
+                    $4, $10, $11, $11,
+                    $11, $5, $6,
+                    'city_ledger', 'debit',
+                    $7, $7, $7,
+                    $8)
+            "#,
         )
-        VALUES ($1, $2, 'accommodation', $3,
-                $4, 'room_charge', CURRENT_DATE, CURRENT_DATE,
-                CURRENT_DATE, $5, $6,
-                'city_ledger', 'debit',
-                $7, $7, $7,
-                $8)
-        "#,
-    )
-    .bind(company_name)
-    .bind(&description)
-    .bind(booking.total_amount)
-    .bind(booking_id)
-    .bind(due_date)
-    .bind(&room_number)
-    .bind(user_id)
-    .bind(&invoice_number)
-    .execute(pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+        .bind(company_name)
+        .bind(&line_description)
+        .bind(charge.amount)
+        .bind(booking_id)
+        .bind(due_date)
+        .bind(&room_number)
+        .bind(user_id)
+        .bind(&invoice_number)
+        .bind(expense_type)
+        .bind(post_type)
+        .bind(today)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    sqlx::query(
-        r#"
-        INSERT INTO customer_ledgers (
-            company_name, description, expense_type, amount,
-            booking_id, post_type, posting_date, transaction_date,
-            invoice_date, due_date, room_number,
-            folio_type, transaction_type,
-            created_by, updated_by, cashier_id,
-            invoice_number
+        #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+        sqlx::query(
+            r#"
+            INSERT INTO customer_ledgers (
+                company_name, description, expense_type, amount,
+                booking_id, post_type, posting_date, transaction_date,
+                invoice_date, due_date, room_number,
+                folio_type, transaction_type,
+                created_by, updated_by, cashier_id,
+                invoice_number
+            )
+            VALUES (?1, ?2, ?9, ?3,
+                    ?4, ?10, ?11, ?11,
+                    ?11, ?5, ?6,
+                    'city_ledger', 'debit',
+                    ?7, ?7, ?7,
+                    ?8)
+            "#,
         )
-        VALUES (?1, ?2, 'accommodation', ?3,
-                ?4, 'room_charge', date('now'), date('now'),
-                date('now'), ?5, ?6,
-                'city_ledger', 'debit',
-                ?7, ?7, ?7,
-                ?8)
-        "#,
-    )
-    .bind(company_name)
-    .bind(&description)
-    .bind(booking.total_amount.to_string())
-    .bind(booking_id)
-    .bind(due_date.to_string())
-    .bind(&room_number)
-    .bind(user_id)
-    .bind(&invoice_number)
-    .execute(pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+        .bind(company_name)
+        .bind(&line_description)
+        .bind(charge.amount.to_string())
+        .bind(booking_id)
+        .bind(due_date.to_string())
+        .bind(&room_number)
+        .bind(user_id)
+        .bind(&invoice_number)
+        .bind(expense_type)
+        .bind(post_type)
+        .bind(today.to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    log::info!(
-        "Auto-posted company ledger for booking {} ({}, amount {})",
-        booking_id,
-        company_name,
-        booking.total_amount
-    );
+        log::info!(
+            "Auto-posted company ledger for booking {} ({}, {} {})",
+            booking_id,
+            company_name,
+            charge.category,
+            charge.amount
+        );
+    }
     Ok(())
 }
 
+/// Sortable columns for the bookings list.
+pub const BOOKING_SORT: SortSpec = SortSpec {
+    columns: &[
+        ("created_at", "b.created_at"),
+        ("check_in_date", "b.check_in_date"),
+        ("check_out_date", "b.check_out_date"),
+        ("guest_name", "g.full_name"),
+        ("room_number", "r.room_number"),
+        ("status", "b.status"),
+        ("invoice_number", "invoice_number"),
+        ("folio_number", "b.booking_number"),
+        ("booking_number", "b.booking_number"),
+    ],
+    default_sort: "created_at",
+    default_descending: true,
+    tiebreaker: "b.id",
+};
+
 pub async fn get_bookings_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
     Query(params): Query<BookingPaginationParams>,
 ) -> Result<Json<PaginatedResponse<Vec<BookingWithDetails>>>, ApiError> {
     let page = params.page.unwrap_or(1).max(1);
@@ -500,6 +829,10 @@ pub async fn get_bookings_handler(
     let mut bind_check_in_from: Option<NaiveDate> = None;
     let mut bind_check_in_to: Option<NaiveDate> = None;
 
+    // 0. Only the caller's property
+    param_idx += 1;
+    conditions.push(format!("b.property_id = {}", param_placeholder(param_idx)));
+
     // 1. Status: explicit filter, "all" to include every status, or default exclude voided
     if let Some(s) = status {
         if s.eq_ignore_ascii_case("all") {
@@ -585,26 +918,10 @@ pub async fn get_bookings_handler(
         }
     }
 
-    let where_clause = if conditions.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", conditions.join(" AND "))
-    };
+    let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
-    let sort_col = match params.sort_by.as_deref() {
-        Some("check_in_date") => "b.check_in_date",
-        Some("check_out_date") => "b.check_out_date",
-        Some("guest_name") => "g.full_name",
-        Some("room_number") => "r.room_number",
-        Some("status") => "b.status",
-        Some("invoice_number") => "invoice_number",
-        Some("folio_number") | Some("booking_number") => "b.booking_number",
-        _ => "b.created_at",
-    };
-    let sort_dir = match params.sort_order.as_deref() {
-        Some("asc") => "ASC",
-        _ => "DESC",
-    };
+    let order_by =
+        BOOKING_SORT.order_by(params.sort_by.as_deref(), params.sort_order.as_deref())?;
 
     let count_sql = format!(
         "SELECT COUNT(*) FROM bookings b \
@@ -613,13 +930,13 @@ pub async fn get_bookings_handler(
         where_clause
     );
     let main_sql = format!(
-        "{}{} ORDER BY {} {} LIMIT {} OFFSET {}",
-        GET_BOOKINGS_BASE_QUERY, where_clause, sort_col, sort_dir, page_size, offset
+        "{}{} ORDER BY {} LIMIT {} OFFSET {}",
+        GET_BOOKINGS_BASE_QUERY, where_clause, order_by, page_size, offset
     );
 
     macro_rules! apply_binds {
         ($q:expr) => {{
-            let q = $q;
+            let q = $q.bind(scope.property_id);
             let q = if let Some(ref v) = bind_status {
                 q.bind(v.as_str())
             } else {
@@ -677,89 +994,491 @@ pub async fn get_bookings_handler(
     }))
 }
 
-pub async fn get_booking_stats_handler(
-    State(pool): State<DbPool>,
-) -> Result<Json<BookingStats>, ApiError> {
-    let today = chrono::Local::now().date_naive();
+/// Shortest query the booking search will run.
+pub const MIN_SEARCH_LENGTH: usize = 2;
 
-    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bookings WHERE status != 'voided'")
-        .fetch_one(&pool)
-        .await
-        .unwrap_or(0);
+/// Phone digits must be at least this long before matching ignores punctuation.
+const MIN_PHONE_DIGITS: usize = 3;
 
-    let checked_in: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM bookings WHERE status = 'checked_in'")
-            .fetch_one(&pool)
-            .await
-            .unwrap_or(0);
+/// `COALESCE(g.phone, '')` with common separators stripped.
+const GUEST_PHONE_DIGITS: &str = "REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(COALESCE(g.phone, ''), \
+    ' ', ''), '-', ''), '+', ''), '(', ''), ')', '')";
 
-    let confirmed: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM bookings WHERE status = 'confirmed'")
-            .fetch_one(&pool)
-            .await
-            .unwrap_or(0);
+/// Front-desk search across guest name/phone/email, booking number, and room number.
+///
+/// Exact booking or room numbers rank first, then booking-number prefixes,
+/// phone or email matches, guest-name prefixes, and finally any partial match.
+/// Phone matching ignores spaces, dashes, `+`, and parentheses.
+pub async fn search_bookings_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Query(params): Query<BookingSearchParams>,
+) -> Result<Json<PaginatedResponse<Vec<BookingWithDetails>>>, ApiError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * page_size;
 
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let today_check_ins: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM bookings WHERE status IN ('pending', 'confirmed') AND date(check_in_date) = ?"
-    ).bind(today).fetch_one(&pool).await.unwrap_or(0);
+    let q = params.q.unwrap_or_default().trim().to_string();
+    if q.chars().count() < MIN_SEARCH_LENGTH {
+        return Ok(Json(PaginatedResponse {
+            data: vec![],
+            total: 0,
+            page,
+            page_size,
+        }));
+    }
 
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let today_check_ins: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM bookings WHERE status IN ('pending', 'confirmed') AND check_in_date::date = $1"
-    ).bind(today).fetch_one(&pool).await.unwrap_or(0);
+    let digits: String = q.chars().filter(char::is_ascii_digit).collect();
+    let phone_pattern = (digits.len() >= MIN_PHONE_DIGITS).then(|| format!("%{}%", digits));
 
-    Ok(Json(BookingStats {
-        total,
-        checked_in,
-        confirmed,
-        today_check_ins,
-    }))
-}
+    let like_op = like_operator();
+    // The count query only uses the first three parameters.
+    let (p_like, p_phone, p_property, p_exact, p_prefix) = (
+        param_placeholder(1),
+        param_placeholder(2),
+        param_placeholder(3),
+        param_placeholder(4),
+        param_placeholder(5),
+    );
 
-pub async fn get_my_bookings_handler(
-    State(pool): State<DbPool>,
-    headers: HeaderMap,
-) -> Result<Json<Vec<BookingWithDetails>>, ApiError> {
-    let user_id = require_auth(&headers).await?;
+    let where_clause = format!(
+        "WHERE b.status != 'voided' AND b.property_id = {p_property} AND ( \
+            b.booking_number {like_op} {p_like} ESCAPE '\\' \
+            OR g.full_name {like_op} {p_like} ESCAPE '\\' \
+            OR COALESCE(g.email, '') {like_op} {p_like} ESCAPE '\\' \
+            OR COALESCE(g.phone, '') {like_op} {p_like} ESCAPE '\\' \
+            OR r.room_number {like_op} {p_like} ESCAPE '\\' \
+            OR (CAST({p_phone} AS TEXT) IS NOT NULL AND {GUEST_PHONE_DIGITS} LIKE {p_phone}))"
+    );
+    let rank = format!(
+        "CASE \
+            WHEN LOWER(b.booking_number) = {p_exact} THEN 0 \
+            WHEN LOWER(r.room_number) = {p_exact} THEN 1 \
+            WHEN LOWER(b.booking_number) LIKE {p_prefix} ESCAPE '\\' THEN 2 \
+            WHEN (CAST({p_phone} AS TEXT) IS NOT NULL AND {GUEST_PHONE_DIGITS} LIKE {p_phone}) \
+              OR LOWER(COALESCE(g.email, '')) = {p_exact} THEN 3 \
+            WHEN LOWER(g.full_name) LIKE {p_prefix} ESCAPE '\\' THEN 4 \
+            ELSE 5 \
+        END"
+    );
 
-    let user_email: String = sqlx::query_scalar(GET_USER_EMAIL_QUERY)
-        .bind(user_id)
+    let count_sql = format!(
+        "SELECT COUNT(*) FROM bookings b \
+         INNER JOIN guests g ON b.guest_id = g.id \
+         INNER JOIN rooms r ON b.room_id = r.id {}",
+        where_clause
+    );
+    let main_sql = format!(
+        "{}{} ORDER BY {}, b.check_in_date DESC, b.id DESC LIMIT {} OFFSET {}",
+        GET_BOOKINGS_BASE_QUERY, where_clause, rank, page_size, offset
+    );
+
+    // Wildcards in the query are matched literally.
+    let pattern = format!("%{}%", escape_like(&q));
+    let exact = q.to_lowercase();
+    let prefix = format!("{}%", escape_like(&exact));
+
+    let total: i64 = sqlx::query_scalar::<_, i64>(&count_sql)
+        .bind(&pattern)
+        .bind(phone_pattern.as_deref())
+        .bind(scope.property_id)
         .fetch_one(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    let rows = sqlx::query(GET_USER_BOOKINGS_QUERY)
-        .bind(&user_email)
+    let rows = sqlx::query(&main_sql)
+        .bind(&pattern)
+        .bind(phone_pattern.as_deref())
+        .bind(scope.property_id)
+        .bind(&exact)
+        .bind(&prefix)
         .fetch_all(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    let bookings: Vec<BookingWithDetails> = rows
-        .iter()
-        .map(row_mappers::row_to_booking_with_details)
-        .collect();
-
-    Ok(Json(bookings))
+    Ok(Json(PaginatedResponse {
+        data: rows
+            .iter()
+            .map(row_mappers::row_to_booking_with_details)
+            .collect(),
+        total,
+        page,
+        page_size,
+    }))
 }
 
-pub async fn create_booking_handler(
+/// Preview whether a room is free for a stay without creating anything.
+/// Uses the same overlap rule as `create_booking_handler`.
+pub async fn check_booking_conflict_handler(
     State(pool): State<DbPool>,
-    Extension(user_id): Extension<i64>,
-    Json(input): Json<BookingInput>,
-) -> Result<Json<Booking>, ApiError> {
-    let check_in = parse_date_flexible(&input.check_in_date)
+    Extension(scope): Extension<PropertyScope>,
+    Query(params): Query<BookingConflictParams>,
+) -> Result<Json<BookingConflictCheck>, ApiError> {
+    let check_in = parse_date_flexible(&params.check_in)
         .map_err(|_| ApiError::BadRequest("Invalid check-in date. Use YYYY-MM-DD".to_string()))?;
-    let check_out = parse_date_flexible(&input.check_out_date)
+    let check_out = parse_date_flexible(&params.check_out)
         .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
 
-    if check_out < check_in {
-        return Err(ApiError::BadRequest(
-            "Check-out date must be on or after check-in date".to_string(),
-        ));
-    }
+    scope.require_room(&pool, params.room_id).await?;
 
-    // Start a transaction to prevent race conditions:
+    let repo = SqlBookingRepository::new(&pool);
+    let check = booking_svc::check_conflict(&repo, params.room_id, check_in, check_out).await?;
+    Ok(Json(check))
+}
+
+/// Whether a booking can stay on in its room until a later check-out: the
+/// added nights are checked against the room's other bookings and priced
+/// with the booking's rate override, or the room's rates without one.
+pub async fn can_extend_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+    Query(params): Query<StayExtensionParams>,
+) -> Result<Json<StayExtensionCheck>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let check_out = parse_date_flexible(&params.check_out_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT b.room_id, b.check_out_date, b.status,
+               CAST(b.rate_override_weekday AS TEXT),
+               CAST(COALESCE(r.custom_price, rt.base_price) AS TEXT)
+        FROM bookings b
+        INNER JOIN rooms r ON b.room_id = r.id
+        INNER JOIN room_types rt ON r.room_type_id = rt.id
+        WHERE b.id = $1
+        "#,
+    )
+    .bind(booking_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+
+    let room_id: i64 = row.get(0);
+    let current_check_out: NaiveDate = row.get(1);
+    let status: String = row.get(2);
+    let rate_override: Option<f64> = row
+        .get::<Option<String>, _>(3)
+        .and_then(|rate| rate.parse().ok());
+    let room_price: Decimal = row.get::<String, _>(4).parse().unwrap_or_default();
+
+    if !matches!(
+        status.as_str(),
+        "pending" | "confirmed" | "checked_in" | "auto_checked_in"
+    ) {
+        return Err(ApiError::BadRequest(format!(
+            "A {} booking cannot be extended",
+            status.replace("_", " ")
+        )));
+    }
+    if check_out <= current_check_out {
+        return Err(ApiError::BadRequest(format!(
+            "Check-out date must be after the current check-out date {}",
+            current_check_out
+        )));
+    }
+
+    let repo = SqlBookingRepository::new(&pool);
+    let conflict =
+        booking_svc::check_conflict(&repo, room_id, current_check_out, check_out).await?;
+
+    let nightly_rates = booking_svc::plan_rates_for_stay(
+        &pool,
+        room_id,
+        rate_override,
+        None,
+        current_check_out,
+        check_out,
+    )
+    .await?;
+    let price = booking_svc::price_new_booking(
+        &pool,
+        current_check_out,
+        check_out,
+        room_price,
+        nightly_rates,
+        rate_override,
+        None,
+    )
+    .await;
+
+    Ok(Json(StayExtensionCheck {
+        booking_id,
+        room_id,
+        current_check_out_date: current_check_out,
+        check_out_date: check_out,
+        available: conflict.available,
+        conflicting_booking_numbers: conflict.conflicting_booking_numbers,
+        nightly_rates: price.nights,
+        additional_amount: price.taxes.gross,
+        service_tax: price.taxes.service_tax,
+        currency: currency::hotel_currency(&pool).await,
+    }))
+}
+
+/// Whether tourism tax applies to a quote. As on booking creation, a guest's
+/// tourism type decides; without a guest, the request's flag does.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+async fn quote_is_tourist(pool: &DbPool, input: &BookingQuoteInput) -> bool {
+    match input.guest_id {
+        Some(guest_id) => {
+            let tourism_type: Option<String> =
+                sqlx::query_scalar("SELECT tourism_type::text FROM guests WHERE id = $1")
+                    .bind(guest_id)
+                    .fetch_optional(pool)
+                    .await
+                    .unwrap_or(None);
+            tourism_type.as_deref() == Some("foreign")
+        }
+        None => input.is_tourist.unwrap_or(false),
+    }
+}
+
+/// The SQLite schema has no guest tourism type, so the request's flag applies.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+async fn quote_is_tourist(_pool: &DbPool, input: &BookingQuoteInput) -> bool {
+    input.is_tourist.unwrap_or(false)
+}
+
+/// Price a stay the way `create_booking_handler` would and report whether the
+/// room is free for it, without writing anything.
+pub async fn quote_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Json(input): Json<BookingQuoteInput>,
+) -> Result<Json<BookingQuote>, ApiError> {
+    let check_in = parse_date_flexible(&input.check_in_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-in date. Use YYYY-MM-DD".to_string()))?;
+    let check_out = parse_date_flexible(&input.check_out_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
+
+    if check_out < check_in {
+        return Err(ApiError::BadRequest(
+            "Check-out date must be on or after check-in date".to_string(),
+        ));
+    }
+
+    let nightly_rates = booking_svc::plan_rates_for_stay(
+        &pool,
+        input.room_id,
+        input.room_rate_override,
+        input.daily_rates.as_ref(),
+        check_in,
+        check_out,
+    )
+    .await?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT r.room_number, rt.name,
+               CAST(COALESCE(r.custom_price, rt.base_price) AS TEXT),
+               rt.max_occupancy, r.status
+        FROM rooms r
+        INNER JOIN room_types rt ON r.room_type_id = rt.id
+        WHERE r.id = $1 AND r.is_active = true AND r.property_id = $2
+        "#,
+    )
+    .bind(input.room_id)
+    .bind(scope.property_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))?;
+
+    let room_number: String = row.get(0);
+    let room_type: String = row.get(1);
+    let room_price: Decimal = row.get::<String, _>(2).parse().unwrap_or_default();
+    let max_occupancy: i32 = row.get(3);
+    let room_status: Option<String> = row.get(4);
+
+    booking_svc::check_guest_capacity(
+        input.adults.unwrap_or(1),
+        input.children.unwrap_or(0),
+        input.infants.unwrap_or(0),
+        max_occupancy,
+        booking_svc::infants_count_towards_occupancy(&pool).await,
+    )?;
+
+    let repo = SqlBookingRepository::new(&pool);
+    let conflict = booking_svc::check_conflict(&repo, input.room_id, check_in, check_out).await?;
+    // Same rules, and messages, as booking creation
+    let room_status = room_status.as_deref().unwrap_or("available");
+    let unavailable_reason = if room_status == "maintenance" || room_status == "out_of_order" {
+        Some(format!(
+            "Room is not available - currently {}",
+            room_status.replace("_", " ")
+        ))
+    } else if !conflict.available {
+        Some("Room is already booked for these dates".to_string())
+    } else {
+        None
+    };
+
+    let price = booking_svc::price_new_booking(
+        &pool,
+        check_in,
+        check_out,
+        room_price,
+        nightly_rates,
+        input.room_rate_override,
+        input.daily_rates.as_ref(),
+    )
+    .await;
+    let (taxes, fees) = booking_svc::quote_charges(
+        &price,
+        quote_is_tourist(&pool, &input).await,
+        input.tourism_tax_amount,
+        input.extra_bed_count,
+        input.extra_bed_charge,
+    );
+    let charged_on_top: Decimal = taxes
+        .iter()
+        .chain(&fees)
+        .filter(|charge| !charge.included)
+        .map(|charge| charge.amount)
+        .sum();
+
+    Ok(Json(BookingQuote {
+        room_id: input.room_id,
+        room_number,
+        room_type,
+        check_in_date: check_in,
+        check_out_date: check_out,
+        is_hourly: check_out == check_in,
+        nightly_rates: price.nights,
+        subtotal: price.subtotal,
+        taxes,
+        fees,
+        total_amount: price.taxes.gross,
+        amount_due: price.taxes.gross + charged_on_top,
+        currency: currency::hotel_currency(&pool).await,
+        available: unavailable_reason.is_none(),
+        unavailable_reason,
+        conflicting_booking_numbers: conflict.conflicting_booking_numbers,
+    }))
+}
+
+pub async fn get_booking_stats_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+) -> Result<Json<BookingStats>, ApiError> {
+    let today = hotel_time::hotel_today(&pool).await?;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE status != 'voided' AND property_id = $1",
+    )
+    .bind(scope.property_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let checked_in: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE status = 'checked_in' AND property_id = $1",
+    )
+    .bind(scope.property_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let confirmed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE status = 'confirmed' AND property_id = $1",
+    )
+    .bind(scope.property_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let today_check_ins: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE status IN ('pending', 'confirmed') AND date(check_in_date) = ?1 AND property_id = ?2"
+    ).bind(today).bind(scope.property_id).fetch_one(&pool).await.unwrap_or(0);
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let today_check_ins: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE status IN ('pending', 'confirmed') AND check_in_date::date = $1 AND property_id = $2"
+    ).bind(today).bind(scope.property_id).fetch_one(&pool).await.unwrap_or(0);
+
+    Ok(Json(BookingStats {
+        total,
+        checked_in,
+        confirmed,
+        today_check_ins,
+    }))
+}
+
+pub async fn get_my_bookings_handler(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<BookingWithDetails>>, ApiError> {
+    let user_id = require_auth(&headers).await?;
+
+    let user_email: String = sqlx::query_scalar(GET_USER_EMAIL_QUERY)
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let rows = sqlx::query(GET_USER_BOOKINGS_QUERY)
+        .bind(&user_email)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let bookings: Vec<BookingWithDetails> = rows
+        .iter()
+        .map(row_mappers::row_to_booking_with_details)
+        .collect();
+
+    Ok(Json(bookings))
+}
+
+pub async fn create_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Json(input): Json<BookingInput>,
+) -> Result<Json<Booking>, ApiError> {
+    let check_in = parse_date_flexible(&input.check_in_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-in date. Use YYYY-MM-DD".to_string()))?;
+    let check_out = parse_date_flexible(&input.check_out_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
+
+    if check_out < check_in {
+        return Err(ApiError::BadRequest(
+            "Check-out date must be on or after check-in date".to_string(),
+        ));
+    }
+
+    // Without an explicit override, nights are priced by the shared rate resolver
+    // (rate plans, then base price). A room-level custom price still wins.
+    let nightly_rates = booking_svc::plan_rates_for_stay(
+        &pool,
+        input.room_id,
+        input.room_rate_override,
+        input.daily_rates.as_ref(),
+        check_in,
+        check_out,
+    )
+    .await?;
+
+    let adults = input.adults.unwrap_or(1);
+    let children = input.children.unwrap_or(0);
+    let infants = input.infants.unwrap_or(0);
+    let count_infants = booking_svc::infants_count_towards_occupancy(&pool).await;
+
+    let source = booking_sources::configured(&pool)
+        .await
+        .resolve(input.source.as_deref())?;
+
+    // Attribute the booking to its company, keeping the name for the ledger
+    let company_name = match input.company_id {
+        Some(id) => Some(company_bookings::bookable_company_name(&pool, id).await?),
+        None => None,
+    };
+
+    // Start a transaction to prevent race conditions:
     // The FOR UPDATE lock on the room row + conflict check + insert must be atomic
     let mut tx = pool
         .begin()
@@ -774,11 +1493,12 @@ pub async fn create_booking_handler(
                rt.description, rt.max_occupancy, r.status, r.created_at, r.updated_at
         FROM rooms r
         INNER JOIN room_types rt ON r.room_type_id = rt.id
-        WHERE r.id = $1 AND r.is_active = true
+        WHERE r.id = $1 AND r.is_active = true AND r.property_id = $2
         FOR UPDATE OF r
         "#,
     )
     .bind(input.room_id)
+    .bind(scope.property_id)
     .fetch_optional(&mut *tx)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?
@@ -807,51 +1527,24 @@ pub async fn create_booking_handler(
         )));
     }
 
-    // Only check for ACTIVE bookings that would conflict
-    // Active statuses: reserved, confirmed, checked_in, pending
-    // Inactive statuses (don't block): voided, checked_out, completed
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let conflict_query = r#"
-        SELECT EXISTS(
-            SELECT 1 FROM bookings
-            WHERE room_id = ?1 AND status IN ('reserved', 'confirmed', 'checked_in', 'pending') AND status != 'voided'
-            AND ((check_in_date <= ?2 AND check_out_date > ?2)
-                OR (check_in_date < ?3 AND check_out_date >= ?3)
-                OR (check_in_date >= ?2 AND check_out_date <= ?3))
-        )
-    "#;
-
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let conflict_query = r#"
-        SELECT EXISTS(
-            SELECT 1 FROM bookings
-            WHERE room_id = $1 AND status IN ('reserved', 'confirmed', 'checked_in', 'pending') AND status != 'voided'
-            AND ((check_in_date <= $2 AND check_out_date > $2)
-                OR (check_in_date < $3 AND check_out_date >= $3)
-                OR (check_in_date >= $2 AND check_out_date <= $3))
-        )
-    "#;
+    booking_svc::check_guest_capacity(
+        adults,
+        children,
+        infants,
+        room.max_occupancy,
+        count_infants,
+    )?;
 
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let conflict: bool = sqlx::query_scalar::<_, i32>(conflict_query)
+    // Only ACTIVE bookings block the room; runs inside the tx under the room lock
+    let conflicts: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
         .bind(input.room_id)
         .bind(check_in)
         .bind(check_out)
-        .fetch_one(&mut *tx)
-        .await
-        .map(|v| v != 0)
-        .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let conflict: bool = sqlx::query_scalar::<_, bool>(conflict_query)
-        .bind(input.room_id)
-        .bind(check_in)
-        .bind(check_out)
-        .fetch_one(&mut *tx)
+        .fetch_all(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    if conflict {
+    if !conflicts.is_empty() {
         return Err(ApiError::BadRequest(
             "Room is already booked for these dates".to_string(),
         ));
@@ -867,58 +1560,33 @@ pub async fn create_booking_handler(
             .unwrap_or(None);
     let is_tourist = guest_tourism_type.as_deref() == Some("foreign");
 
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let hotel_today: NaiveDate = {
-        let today_str: String = sqlx::query_scalar("SELECT date('now', 'localtime')")
-            .fetch_one(&mut *tx)
-            .await
-            .map_err(|e| ApiError::Database(e.to_string()))?;
-        NaiveDate::parse_from_str(&today_str, "%Y-%m-%d")
-            .map_err(|e| ApiError::Database(e.to_string()))?
-    };
-
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let hotel_today: NaiveDate = sqlx::query_scalar("SELECT CURRENT_DATE")
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let hotel_today = hotel_time::hotel_today(&pool).await?;
 
-    let nights = (check_out - check_in).num_days() as i32;
-    let is_hourly = nights == 0; // Same-day check-in/check-out = hourly booking
-    let billable_nights = if is_hourly { 1 } else { nights }; // Charge 1 night for hourly
-    let room_rate = input
-        .room_rate_override
-        .map(|r| Decimal::from_f64_retain(r).unwrap_or(room.price_per_night))
-        .unwrap_or(room.price_per_night);
-    // The configured room price is tax-inclusive (final price)
-    // Store total_amount as the configured price × nights without adding additional tax
-    // For hourly bookings (same-day), charge 1 night at the standard rate
-    // If daily_rates provided, sum them for subtotal; otherwise use room_rate * nights
-    let subtotal = if let Some(ref daily_rates) = input.daily_rates {
-        if let Some(obj) = daily_rates.as_object() {
-            let sum: f64 = obj.values().filter_map(|v| v.as_f64()).sum();
-            Decimal::from_f64_retain(sum).unwrap_or(room_rate * Decimal::from(billable_nights))
-        } else {
-            room_rate * Decimal::from(billable_nights)
-        }
-    } else {
-        room_rate * Decimal::from(billable_nights)
-    };
-    let tax_amount = Decimal::ZERO; // Tax is calculated on frontend using hotel settings rate
-    let total_amount = subtotal; // Configured price is the final price
-    let daily_rates_json = input.daily_rates.clone();
+    let is_hourly = check_out == check_in; // Same-day check-in/check-out = hourly booking
+    // The configured room price is tax-inclusive (final price); hourly
+    // bookings are charged the configured day-use share of one night.
+    let price = booking_svc::price_new_booking(
+        &pool,
+        check_in,
+        check_out,
+        room.price_per_night,
+        nightly_rates,
+        input.room_rate_override,
+        input.daily_rates.as_ref(),
+    )
+    .await;
+    let room_rate = price.room_rate;
+    let subtotal = price.subtotal;
+    let tax_amount = price.taxes.service_tax;
+    let total_amount = price.taxes.gross;
+    let daily_rates_json = price.daily_rates;
 
     // Use provided booking_number for online bookings, or auto-generate for walk-ins
     let booking_number = match &input.booking_number {
         Some(bn) if !bn.trim().is_empty() => bn.trim().to_string(),
-        _ => booking_svc::generate_booking_number_for_date(hotel_today),
+        _ => booking_numbers::next_booking_number(&pool, hotel_today).await?,
     };
 
-    let source = input
-        .source
-        .clone()
-        .unwrap_or_else(|| "walk_in".to_string());
-
     // Sanitize user-provided text fields
     let booking_remarks = input
         .booking_remarks
@@ -948,9 +1616,11 @@ pub async fn create_booking_handler(
             INSERT INTO bookings (
                 booking_number, guest_id, room_id, check_in_date, check_out_date,
                 room_rate, subtotal, tax_amount, total_amount, status, payment_status, payment_method, remarks, created_by, adults, source,
-                deposit_paid, deposit_amount, deposit_paid_at, rate_override_weekday, rate_override_weekend, special_requests, post_type, daily_rates
+                deposit_paid, deposit_amount, deposit_paid_at, rate_override_weekday, rate_override_weekend, special_requests, post_type, daily_rates,
+                children, infants, company_id, company_name
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'confirmed', ?10, ?11, ?12, ?13, 1, ?14, ?15, ?16, CASE WHEN ?15 THEN datetime('now') ELSE NULL END, ?17, ?17, ?18, ?19, ?20)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'confirmed', ?10, ?11, ?12, ?13, ?21, ?14, ?15, ?16, CASE WHEN ?15 THEN datetime('now') ELSE NULL END, ?17, ?17, ?18, ?19, ?20,
+                ?22, ?23, ?24, ?25)
             "#
         )
         .bind(&booking_number)
@@ -973,6 +1643,11 @@ pub async fn create_booking_handler(
         .bind(special_requests.as_deref())
         .bind(if is_hourly { Some("hourly") } else { None::<&str> })
         .bind(daily_rates_json.as_ref().map(|v| v.to_string()))
+        .bind(adults)
+        .bind(children)
+        .bind(infants)
+        .bind(input.company_id)
+        .bind(company_name.as_deref())
         .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -999,10 +1674,11 @@ pub async fn create_booking_handler(
                 booking_number, guest_id, room_id, check_in_date, check_out_date,
                 room_rate, subtotal, tax_amount, total_amount, status, payment_status, payment_method, remarks, created_by, adults, source,
                 deposit_paid, deposit_amount, deposit_paid_at, rate_override_weekday, rate_override_weekend, special_requests,
-                is_tourist, tourism_tax_amount, extra_bed_count, extra_bed_charge, post_type, daily_rates
+                is_tourist, tourism_tax_amount, extra_bed_count, extra_bed_charge, post_type, daily_rates,
+                children, infants, company_id, company_name
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'confirmed', $10, $11, $12, $13, 1, $14, $15, $16, CASE WHEN $15 THEN CURRENT_TIMESTAMP ELSE NULL END, $17, $17, $18,
-                $19, $20, $21, $22, $23, $24)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'confirmed', $10, $11, $12, $13, $25, $14, $15, $16, CASE WHEN $15 THEN CURRENT_TIMESTAMP ELSE NULL END, $17, $17, $18,
+                $19, $20, $21, $22, $23, $24, $26, $27, $28, $29)
             RETURNING id, booking_number, guest_id, room_id, check_in_date, check_out_date, room_rate, subtotal, tax_amount, discount_amount, total_amount, status, payment_status, payment_method, adults, children, special_requests, remarks, source, market_code, discount_percentage, rate_override_weekday, rate_override_weekend, pre_checkin_completed, pre_checkin_completed_at, pre_checkin_token, pre_checkin_token_expires_at, created_by, is_complimentary, complimentary_reason, complimentary_start_date, complimentary_end_date, original_total_amount, complimentary_nights, deposit_paid, deposit_amount, deposit_paid_at, company_id, company_name, payment_note, daily_rates, created_at, updated_at, post_type
             "#
         )
@@ -1030,6 +1706,11 @@ pub async fn create_booking_handler(
         .bind(input.extra_bed_charge.map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO)))
         .bind(if is_hourly { Some("hourly") } else { None::<&str> })
         .bind(&daily_rates_json)
+        .bind(adults)
+        .bind(children)
+        .bind(infants)
+        .bind(input.company_id)
+        .bind(company_name.as_deref())
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
@@ -1064,6 +1745,22 @@ pub async fn create_booking_handler(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    // Type-level booking: keep the room as a provisional hold until one is assigned.
+    if input.room_type_only.unwrap_or(false) {
+        sqlx::query(
+            r#"
+            UPDATE bookings
+            SET room_assigned = false,
+                room_type_id = (SELECT room_type_id FROM rooms WHERE rooms.id = bookings.room_id)
+            WHERE id = $1
+            "#,
+        )
+        .bind(booking.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
     // Record payment if deposit was paid during booking creation
     if let Some(amount_paid) = input.amount_paid
         && amount_paid > 0.0
@@ -1084,6 +1781,26 @@ pub async fn create_booking_handler(
             .await;
     }
 
+    outbox::enqueue(
+        &mut tx,
+        outbox::BOOKING_CREATED,
+        "booking",
+        booking.id,
+        &serde_json::json!({
+            "booking_id": booking.id,
+            "booking_number": &booking.booking_number,
+            "guest_id": booking.guest_id,
+            "room_id": booking.room_id,
+            "check_in_date": booking.check_in_date.to_string(),
+            "check_out_date": booking.check_out_date.to_string(),
+            "total_amount": booking.total_amount.to_string(),
+            "status": &booking.status,
+            "source": &booking.source,
+        }),
+    )
+    .await?;
+    communication::queue_booking_confirmation(&mut tx, &booking).await?;
+
     // Commit the transaction - all conflict check + insert + room update are now atomic
     tx.commit()
         .await
@@ -1118,16 +1835,574 @@ pub async fn create_booking_handler(
     )
     .await;
 
-    Ok(Json(booking))
+    Ok(Json(booking))
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const INSERT_GROUP_BOOKING: &str = r#"
+    INSERT INTO bookings (
+        booking_number, group_booking_number, guest_id, room_id, check_in_date, check_out_date,
+        rate_per_night, room_rate, subtotal, tax_amount, total_amount, daily_rates,
+        status, payment_status, payment_method, source, remarks, special_requests,
+        adults, children, infants, rate_override_weekday, rate_override_weekend, post_type,
+        created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $7, $8, $9, $10, $11, 'confirmed', 'unpaid', $12, $13,
+            $14, $15, $16, $17, $18, $19, $19, $20, $21)
+    RETURNING id
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const INSERT_GROUP_BOOKING: &str = r#"
+    INSERT INTO bookings (
+        booking_number, group_booking_number, guest_id, room_id, check_in_date, check_out_date,
+        room_rate, subtotal, tax_amount, total_amount, daily_rates,
+        status, payment_status, payment_method, source, remarks, special_requests,
+        adults, children, infants, rate_override_weekday, rate_override_weekend, post_type,
+        created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CAST($11 AS JSONB), 'confirmed', 'unpaid',
+            $12, $13, $14, $15, $16, $17, $18, $19, $19, $20, $21)
+    RETURNING id
+"#;
+
+/// Book several rooms for one guest and stay. Each room is priced like a
+/// single booking, and if any room cannot be booked none are.
+pub async fn create_group_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Json(input): Json<GroupBookingInput>,
+) -> Result<Json<GroupBooking>, ApiError> {
+    let check_in = parse_date_flexible(&input.check_in_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-in date. Use YYYY-MM-DD".to_string()))?;
+    let check_out = parse_date_flexible(&input.check_out_date)
+        .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
+
+    if check_out < check_in {
+        return Err(ApiError::BadRequest(
+            "Check-out date must be on or after check-in date".to_string(),
+        ));
+    }
+
+    // Rooms are locked in id order so two overlapping groups cannot deadlock.
+    let mut room_ids = input.room_ids.clone();
+    room_ids.sort_unstable();
+    room_ids.dedup();
+    if room_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A group booking needs at least one room".to_string(),
+        ));
+    }
+    if room_ids.len() != input.room_ids.len() {
+        return Err(ApiError::BadRequest(
+            "Each room can only be booked once in a group".to_string(),
+        ));
+    }
+
+    let adults = input.adults.unwrap_or(1);
+    let children = input.children.unwrap_or(0);
+    let infants = input.infants.unwrap_or(0);
+    let count_infants = booking_svc::infants_count_towards_occupancy(&pool).await;
+    let is_hourly = check_out == check_in;
+    let hotel_today = hotel_time::hotel_today(&pool).await?;
+
+    // Price each room and allocate its number before the transaction; a
+    // number left unused by a rollback is only a gap in the sequence.
+    let mut rooms = Vec::with_capacity(room_ids.len());
+    for &room_id in &room_ids {
+        let row = sqlx::query(
+            r#"
+            SELECT r.room_number, CAST(COALESCE(r.custom_price, rt.base_price) AS TEXT),
+                   rt.max_occupancy, r.status
+            FROM rooms r
+            INNER JOIN room_types rt ON r.room_type_id = rt.id
+            WHERE r.id = $1 AND r.is_active = true AND r.property_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(scope.property_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Room {} not found", room_id)))?;
+
+        let room_number: String = row.get(0);
+        let room_price: Decimal = row.get::<String, _>(1).parse().unwrap_or_default();
+        let max_occupancy: i32 = row.get(2);
+        let room_status: Option<String> = row.get(3);
+
+        let room_status = room_status.as_deref().unwrap_or("available");
+        if room_status == "maintenance" || room_status == "out_of_order" {
+            return Err(ApiError::BadRequest(format!(
+                "Room {} is not available - currently {}",
+                room_number,
+                room_status.replace("_", " ")
+            )));
+        }
+        booking_svc::check_guest_capacity(adults, children, infants, max_occupancy, count_infants)?;
+
+        let nightly_rates = booking_svc::plan_rates_for_stay(
+            &pool,
+            room_id,
+            input.room_rate_override,
+            None,
+            check_in,
+            check_out,
+        )
+        .await?;
+        let price = booking_svc::price_new_booking(
+            &pool,
+            check_in,
+            check_out,
+            room_price,
+            nightly_rates,
+            input.room_rate_override,
+            None,
+        )
+        .await;
+        let booking_number = booking_numbers::next_booking_number(&pool, hotel_today).await?;
+        rooms.push((room_id, room_number, price, booking_number));
+    }
+    let group_booking_number = booking_numbers::group_booking_number(&rooms[0].3);
+
+    let source = booking_sources::configured(&pool)
+        .await
+        .resolve(input.source.as_deref())?;
+    let booking_remarks = input
+        .booking_remarks
+        .as_deref()
+        .map(Sanitizer::sanitize_notes);
+    let special_requests = input
+        .special_requests
+        .as_deref()
+        .map(Sanitizer::sanitize_notes);
+    let rate_override = input
+        .room_rate_override
+        .and_then(Decimal::from_f64_retain)
+        .map(decimal_to_db);
+    let post_type = is_hourly.then_some("hourly");
+    let room_status = if check_in == hotel_today {
+        "occupied"
+    } else {
+        "reserved"
+    };
+
+    // Every room's lock, conflict check and insert share one transaction, so
+    // a conflict on any room books none of them.
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut bookings = Vec::with_capacity(rooms.len());
+    for (room_id, room_number, price, booking_number) in &rooms {
+        lock_room(&mut tx, *room_id).await?;
+
+        let conflicts: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
+            .bind(room_id)
+            .bind(check_in)
+            .bind(check_out)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        if !conflicts.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Room {} is already booked for these dates",
+                room_number
+            )));
+        }
+
+        let booking_id: i64 = sqlx::query_scalar(INSERT_GROUP_BOOKING)
+            .bind(booking_number)
+            .bind(&group_booking_number)
+            .bind(input.guest_id)
+            .bind(room_id)
+            .bind(check_in)
+            .bind(check_out)
+            .bind(decimal_to_db(price.room_rate))
+            .bind(decimal_to_db(price.subtotal))
+            .bind(decimal_to_db(price.taxes.service_tax))
+            .bind(decimal_to_db(price.taxes.gross))
+            .bind(price.daily_rates.as_ref().map(|v| v.to_string()))
+            .bind(input.payment_method.as_deref())
+            .bind(&source)
+            .bind(booking_remarks.as_deref())
+            .bind(special_requests.as_deref())
+            .bind(adults)
+            .bind(children)
+            .bind(infants)
+            .bind(rate_override.clone())
+            .bind(post_type)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        sqlx::query("UPDATE rooms SET status = $1, status_notes = $2 WHERE id = $3")
+            .bind(room_status)
+            .bind(format!(
+                "Booking #{} - Group {}",
+                booking_number, group_booking_number
+            ))
+            .bind(room_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        let row = sqlx::query("SELECT * FROM bookings WHERE id = $1")
+            .bind(booking_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        let booking = row_mappers::row_to_booking(&row);
+
+        outbox::enqueue(
+            &mut tx,
+            outbox::BOOKING_CREATED,
+            "booking",
+            booking.id,
+            &serde_json::json!({
+                "booking_id": booking.id,
+                "booking_number": &booking.booking_number,
+                "group_booking_number": &group_booking_number,
+                "guest_id": booking.guest_id,
+                "room_id": booking.room_id,
+                "check_in_date": booking.check_in_date.to_string(),
+                "check_out_date": booking.check_out_date.to_string(),
+                "total_amount": booking.total_amount.to_string(),
+                "status": &booking.status,
+                "source": &booking.source,
+            }),
+        )
+        .await?;
+        bookings.push(booking);
+    }
+
+    let group = GroupBooking {
+        group_booking_number,
+        total_amount: rooms.iter().map(|(_, _, price, _)| price.taxes.gross).sum(),
+        bookings,
+    };
+    // The guest gets one confirmation for the whole group.
+    communication::queue_group_booking_confirmation(&mut tx, &group).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for booking in &group.bookings {
+        let _ = AuditLog::log_booking_created(
+            &pool,
+            user_id,
+            booking.id,
+            booking.guest_id,
+            booking.room_id,
+        )
+        .await;
+        record_booking_history(
+            &pool,
+            booking.id,
+            None,
+            &booking.status,
+            Some(user_id),
+            Some("Group booking created"),
+            serde_json::json!({
+                "group_booking_number": &group.group_booking_number,
+                "guest_id": booking.guest_id,
+                "room_id": booking.room_id,
+                "check_in_date": booking.check_in_date.to_string(),
+                "check_out_date": booking.check_out_date.to_string(),
+                "total_amount": booking.total_amount.to_string(),
+                "source": &booking.source,
+            }),
+        )
+        .await;
+    }
+
+    Ok(Json(group))
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const INSERT_WALK_IN_BOOKING: &str = r#"
+    INSERT INTO bookings (
+        booking_number, guest_id, room_id, check_in_date, check_out_date,
+        rate_per_night, room_rate, subtotal, tax_amount, total_amount, daily_rates,
+        status, payment_status, payment_method, source, remarks, special_requests,
+        adults, children, infants, rate_override_weekday, rate_override_weekend, post_type,
+        actual_check_in, created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, $9, $10, 'checked_in', 'unpaid', $11, 'walk_in',
+            $12, $13, $14, $15, $16, $17, $17, $18, CURRENT_TIMESTAMP, $19)
+    RETURNING id
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const INSERT_WALK_IN_BOOKING: &str = r#"
+    INSERT INTO bookings (
+        booking_number, guest_id, room_id, check_in_date, check_out_date,
+        room_rate, subtotal, tax_amount, total_amount, daily_rates,
+        status, payment_status, payment_method, source, remarks, special_requests,
+        adults, children, infants, rate_override_weekday, rate_override_weekend, post_type,
+        actual_check_in, created_by
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CAST($10 AS JSONB), 'checked_in', 'unpaid', $11,
+            'walk_in', $12, $13, $14, $15, $16, $17, $17, $18, CURRENT_TIMESTAMP, $19)
+    RETURNING id
+"#;
+
+/// Check in a guest who arrives without a booking. The guest is created if
+/// new, and the booking from today, its check-in and the room turning
+/// occupied commit together, so a conflict or a bad guest leaves nothing
+/// behind.
+pub async fn walk_in_checkin_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Json(input): Json<WalkInInput>,
+) -> Result<Json<WalkIn>, ApiError> {
+    let walk_in_guest = walk_in::walk_in_guest(&input)?;
+    let check_in = hotel_time::hotel_today(&pool).await?;
+    let check_out = input.check_out_date;
+    walk_in::check_walk_in_stay(check_out, check_in)?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT r.room_number, CAST(COALESCE(r.custom_price, rt.base_price) AS TEXT),
+               rt.max_occupancy, r.status
+        FROM rooms r
+        INNER JOIN room_types rt ON r.room_type_id = rt.id
+        WHERE r.id = $1 AND r.is_active = true AND r.property_id = $2
+        "#,
+    )
+    .bind(input.room_id)
+    .bind(scope.property_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))?;
+
+    let room_price: Decimal = row.get::<String, _>(1).parse().unwrap_or_default();
+    let max_occupancy: i32 = row.get(2);
+    let previous_room_status: Option<String> = row.get(3);
+
+    let room_status = previous_room_status.as_deref().unwrap_or("available");
+    if room_status == "maintenance" || room_status == "out_of_order" {
+        return Err(ApiError::BadRequest(format!(
+            "Room is not available - currently {}",
+            room_status.replace("_", " ")
+        )));
+    }
+
+    let adults = input.adults.unwrap_or(1);
+    let children = input.children.unwrap_or(0);
+    let infants = input.infants.unwrap_or(0);
+    let count_infants = booking_svc::infants_count_towards_occupancy(&pool).await;
+    booking_svc::check_guest_capacity(adults, children, infants, max_occupancy, count_infants)?;
+
+    let nightly_rates = booking_svc::plan_rates_for_stay(
+        &pool,
+        input.room_id,
+        input.room_rate_override,
+        None,
+        check_in,
+        check_out,
+    )
+    .await?;
+    let price = booking_svc::price_new_booking(
+        &pool,
+        check_in,
+        check_out,
+        room_price,
+        nightly_rates,
+        input.room_rate_override,
+        None,
+    )
+    .await;
+    // Allocated before the transaction; a rollback only leaves a gap.
+    let booking_number = booking_numbers::next_booking_number(&pool, check_in).await?;
+
+    let booking_remarks = input
+        .booking_remarks
+        .as_deref()
+        .map(Sanitizer::sanitize_notes);
+    let special_requests = input
+        .special_requests
+        .as_deref()
+        .map(Sanitizer::sanitize_notes);
+    let rate_override = input
+        .room_rate_override
+        .and_then(Decimal::from_f64_retain)
+        .map(decimal_to_db);
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let guest = match walk_in_guest {
+        walk_in::WalkInGuest::Existing(guest_id) => {
+            crate::handlers::guests::fetch_guest(&mut tx, guest_id).await?
+        }
+        walk_in::WalkInGuest::New(guest_input) => {
+            crate::handlers::guests::insert_guest(&mut tx, guest_input, user_id).await?
+        }
+    };
+
+    lock_room(&mut tx, input.room_id).await?;
+    let conflicts: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
+        .bind(input.room_id)
+        .bind(check_in)
+        .bind(check_out)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if !conflicts.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Room is already booked for these dates".to_string(),
+        ));
+    }
+
+    let booking_id: i64 = sqlx::query_scalar(INSERT_WALK_IN_BOOKING)
+        .bind(&booking_number)
+        .bind(guest.id)
+        .bind(input.room_id)
+        .bind(check_in)
+        .bind(check_out)
+        .bind(decimal_to_db(price.room_rate))
+        .bind(decimal_to_db(price.subtotal))
+        .bind(decimal_to_db(price.taxes.service_tax))
+        .bind(decimal_to_db(price.taxes.gross))
+        .bind(price.daily_rates.as_ref().map(|v| v.to_string()))
+        .bind(input.payment_method.as_deref())
+        .bind(booking_remarks.as_deref())
+        .bind(special_requests.as_deref())
+        .bind(adults)
+        .bind(children)
+        .bind(infants)
+        .bind(rate_override)
+        .bind((check_out == check_in).then_some("hourly"))
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    sqlx::query("UPDATE rooms SET status = 'occupied', status_notes = $1 WHERE id = $2")
+        .bind(format!("Booking #{} - Walk-in checked in", booking_number))
+        .bind(input.room_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let row = sqlx::query("SELECT * FROM bookings WHERE id = $1")
+        .bind(booking_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let booking = row_mappers::row_to_booking(&row);
+
+    outbox::enqueue(
+        &mut tx,
+        outbox::BOOKING_CREATED,
+        "booking",
+        booking.id,
+        &serde_json::json!({
+            "booking_id": booking.id,
+            "booking_number": &booking.booking_number,
+            "guest_id": booking.guest_id,
+            "room_id": booking.room_id,
+            "check_in_date": booking.check_in_date.to_string(),
+            "check_out_date": booking.check_out_date.to_string(),
+            "total_amount": booking.total_amount.to_string(),
+            "status": &booking.status,
+            "source": &booking.source,
+        }),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if matches!(walk_in_guest, walk_in::WalkInGuest::New(_)) {
+        let _ = AuditLog::log_event(
+            &pool,
+            Some(user_id),
+            "guest_created",
+            "guest",
+            Some(guest.id),
+            Some(serde_json::json!({"name": &guest.full_name, "email": &guest.email})),
+            None,
+            None,
+        )
+        .await;
+    }
+    let _ =
+        AuditLog::log_booking_created(&pool, user_id, booking.id, guest.id, booking.room_id).await;
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "booking_checkin",
+        "booking",
+        Some(booking.id),
+        Some(
+            serde_json::json!({"guest_id": guest.id, "room_id": booking.room_id, "walk_in": true}),
+        ),
+        None,
+        None,
+    )
+    .await;
+    record_booking_history(
+        &pool,
+        booking.id,
+        None,
+        "checked_in",
+        Some(user_id),
+        Some("Walk-in checked in"),
+        serde_json::json!({
+            "guest_id": guest.id,
+            "room_id": booking.room_id,
+            "check_in_date": booking.check_in_date.to_string(),
+            "check_out_date": booking.check_out_date.to_string(),
+            "total_amount": booking.total_amount.to_string(),
+        }),
+    )
+    .await;
+    record_room_transition(
+        &pool,
+        booking.room_id,
+        previous_room_status.as_deref(),
+        "occupied",
+        user_id,
+        &format!("Walk-in check-in: booking {}", booking.booking_number),
+    )
+    .await;
+
+    // As for any same-day check-in, post nights whose audit already closed.
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    if let Err(e) =
+        crate::services::night_audit::backfill_booking_posted_nights(&pool, booking.id, user_id)
+            .await
+    {
+        log::warn!(
+            "Failed to backfill posted nights for booking {}: {}",
+            booking.id,
+            e
+        );
+    }
+
+    Ok(Json(WalkIn { booking, guest }))
 }
 
 pub async fn get_booking_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<BookingWithDetails>, ApiError> {
     let row = sqlx::query(GET_BOOKING_BY_ID_QUERY)
         .bind(booking_id)
+        .bind(scope.property_id)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
@@ -1160,12 +2435,70 @@ pub async fn get_booking_handler(
     Ok(Json(booking))
 }
 
+/// Fetch several bookings of the property in one query. They come back in
+/// request order, each once; ids with no booking in the property are
+/// reported in `not_found`.
+pub async fn get_bookings_batch_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Json(input): Json<BookingBatchRequest>,
+) -> Result<Json<BookingBatchResponse>, ApiError> {
+    let ids = booking_svc::batch_ids(&input.ids)?;
+    if ids.is_empty() {
+        return Ok(Json(BookingBatchResponse {
+            bookings: Vec::new(),
+            not_found: Vec::new(),
+        }));
+    }
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let rows = sqlx::query(&format!(
+        "{} WHERE b.property_id = $1 AND b.id = ANY($2)",
+        GET_BOOKINGS_BASE_QUERY
+    ))
+    .bind(scope.property_id)
+    .bind(&ids)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let rows = {
+        let placeholders: Vec<String> = (2..).take(ids.len()).map(param_placeholder).collect();
+        let sql = format!(
+            "{} WHERE b.property_id = ?1 AND b.id IN ({})",
+            GET_BOOKINGS_BASE_QUERY,
+            placeholders.join(", ")
+        );
+        ids.iter()
+            .fold(sqlx::query(&sql).bind(scope.property_id), |q, id| {
+                q.bind(*id)
+            })
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+    };
+
+    let found: Vec<BookingWithDetails> = rows
+        .iter()
+        .map(row_mappers::row_to_booking_with_details)
+        .collect();
+    let (bookings, not_found) = booking_svc::in_request_order(&ids, found, |b| b.id);
+
+    Ok(Json(BookingBatchResponse {
+        bookings,
+        not_found,
+    }))
+}
+
 pub async fn update_booking_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
     Json(input): Json<BookingUpdateInput>,
 ) -> Result<Json<Booking>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     let existing_booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
 
     let has_booking_update = AuthService::check_permission(&pool, user_id, "bookings:update")
@@ -1212,6 +2545,10 @@ pub async fn update_booking_handler(
     } else {
         existing_booking.room_id
     };
+    // Moving to a room in another property would move the booking there too.
+    if new_room_id != existing_booking.room_id {
+        scope.require_room(&pool, new_room_id).await?;
+    }
 
     let new_status = input
         .status
@@ -1239,6 +2576,29 @@ pub async fn update_booking_handler(
         ));
     }
 
+    // A changed source must be a configured one; an unchanged one is kept
+    // even if it has since been taken off the list.
+    let source = match input.source.as_deref() {
+        Some(source) if Some(source.trim()) != existing_booking.source.as_deref() => Some(
+            booking_sources::configured(&pool)
+                .await
+                .resolve(Some(source))?,
+        ),
+        _ => input.source.clone(),
+    };
+
+    // Repriced stays are charged like new bookings: prices include the
+    // service tax, and a stay moved onto a single day is charged day use.
+    let tax_ctx = TaxContext::load(&pool, TaxMode::Inclusive).await;
+    let day_use_percent = booking_svc::day_use_percent(&pool).await;
+
+    // The room lock, conflict check and update share a transaction, so two
+    // edits cannot both move overlapping stays into the same room.
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
     // Check for room conflicts when room or dates change (skip for non-active statuses)
     let room_changed = input.room_id.is_some() && new_room_id != existing_booking.room_id;
     let dates_changed = input.check_in_date.is_some() || input.check_out_date.is_some();
@@ -1247,12 +2607,14 @@ pub async fn update_booking_handler(
         "voided" | "checked_out" | "late_checkout"
     );
     if (room_changed || dates_changed) && !is_inactive_status {
+        lock_room(&mut tx, new_room_id).await?;
+
         #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
         let conflict_query = r#"
             SELECT EXISTS(
                 SELECT 1 FROM bookings
                 WHERE room_id = ?1 AND id != ?4
-                AND status IN ('reserved', 'confirmed', 'checked_in', 'pending') AND status != 'voided'
+                AND status IN ('reserved', 'confirmed', 'checked_in', 'auto_checked_in', 'pending')
                 AND ((check_in_date <= ?2 AND check_out_date > ?2)
                     OR (check_in_date < ?3 AND check_out_date >= ?3)
                     OR (check_in_date >= ?2 AND check_out_date <= ?3))
@@ -1264,7 +2626,7 @@ pub async fn update_booking_handler(
             SELECT EXISTS(
                 SELECT 1 FROM bookings
                 WHERE room_id = $1 AND id != $4
-                AND status IN ('reserved', 'confirmed', 'checked_in', 'pending') AND status != 'voided'
+                AND status IN ('reserved', 'confirmed', 'checked_in', 'auto_checked_in', 'pending')
                 AND ((check_in_date <= $2 AND check_out_date > $2)
                     OR (check_in_date < $3 AND check_out_date >= $3)
                     OR (check_in_date >= $2 AND check_out_date <= $3))
@@ -1277,7 +2639,7 @@ pub async fn update_booking_handler(
             .bind(check_in)
             .bind(check_out)
             .bind(booking_id)
-            .fetch_one(&pool)
+            .fetch_one(&mut *tx)
             .await
             .map(|v| v != 0)
             .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1288,7 +2650,7 @@ pub async fn update_booking_handler(
             .bind(check_in)
             .bind(check_out)
             .bind(booking_id)
-            .fetch_one(&pool)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
 
@@ -1361,7 +2723,7 @@ pub async fn update_booking_handler(
         daily_rates_json = Some(serde_json::Value::Object(new_dr));
     }
 
-    let (new_room_rate, new_subtotal, new_total_amount) = if let Some(ref dr) = daily_rates_json {
+    let (new_room_rate, new_subtotal) = if let Some(ref dr) = daily_rates_json {
         // Daily rates available (caller-supplied or rebuilt) - sum them for subtotal
         if let Some(obj) = dr.as_object() {
             let sum: f64 = obj
@@ -1377,27 +2739,35 @@ pub async fn update_booking_handler(
             } else {
                 existing_booking.room_rate
             };
-            (Some(room_rate), Some(subtotal), Some(subtotal))
+            (Some(room_rate), Some(subtotal))
         } else {
-            (None, None, None)
+            (None, None)
+        }
+    } else if input.room_rate_override.is_some() || dates_changed {
+        // An override reprices every night; a date change alone keeps the
+        // booking's own rate.
+        let mut price = booking_svc::price_stay(
+            check_in,
+            check_out,
+            existing_booking.room_rate,
+            None,
+            input.room_rate_override,
+            None,
+            &tax_ctx,
+        );
+        if check_out == check_in {
+            price = booking_svc::apply_day_use(price, day_use_percent, &tax_ctx);
         }
-    } else if let Some(rate_override) = input.room_rate_override {
-        let nights = std::cmp::max((check_out - check_in).num_days() as i32, 1);
-        let room_rate =
-            Decimal::from_f64_retain(rate_override).unwrap_or(existing_booking.room_rate);
-        let subtotal = room_rate * Decimal::from(nights);
-        let total_amount = subtotal; // Tax is calculated on frontend using hotel settings rate
-        (Some(room_rate), Some(subtotal), Some(total_amount))
-    } else if input.check_out_date.is_some() || input.check_in_date.is_some() {
-        // Dates changed without explicit rate override - recalculate using existing room rate
-        let nights = std::cmp::max((check_out - check_in).num_days() as i32, 1);
-        let room_rate = existing_booking.room_rate;
-        let subtotal = room_rate * Decimal::from(nights);
-        let total_amount = subtotal;
-        (None, Some(subtotal), Some(total_amount))
+        (
+            input.room_rate_override.map(|_| price.room_rate),
+            Some(price.subtotal),
+        )
     } else {
-        (None, None, None)
+        (None, None)
     };
+    let new_taxes = new_subtotal.map(|subtotal| tax::compute_taxes(subtotal, &tax_ctx));
+    let new_tax_amount = new_taxes.as_ref().map(|t| t.service_tax);
+    let new_total_amount = new_taxes.as_ref().map(|t| t.gross);
 
     // SQLite version: UPDATE then SELECT
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
@@ -1427,6 +2797,7 @@ pub async fn update_booking_handler(
                 extra_bed_count = COALESCE(?23, extra_bed_count),
                 extra_bed_charge = COALESCE(?24, extra_bed_charge),
                 daily_rates = COALESCE(?25, daily_rates),
+                tax_amount = COALESCE(?26, tax_amount),
                 actual_check_out = CASE WHEN ?2 = 'checked_out' AND actual_check_out IS NULL THEN datetime('now') ELSE actual_check_out END,
                 updated_at = datetime('now')
             WHERE id = ?7"#
@@ -1444,7 +2815,7 @@ pub async fn update_booking_handler(
         .bind(&input.company_name)
         .bind(&input.payment_note)
         .bind(&input.remarks)
-        .bind(&input.source)
+        .bind(&source)
         .bind(&input.payment_method)
         .bind(new_room_rate.map(|r| r.to_f64().unwrap_or(0.0)))
         .bind(new_subtotal.map(|s| s.to_f64().unwrap_or(0.0)))
@@ -1456,13 +2827,14 @@ pub async fn update_booking_handler(
         .bind(input.extra_bed_count)
         .bind(input.extra_bed_charge)
         .bind(daily_rates_json.as_ref().map(|v| v.to_string()))
-        .execute(&pool)
+        .bind(new_tax_amount.map(|t| t.to_f64().unwrap_or(0.0)))
+        .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
         let row = sqlx::query("SELECT * FROM bookings WHERE id = ?1")
             .bind(booking_id)
-            .fetch_one(&pool)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
 
@@ -1499,6 +2871,7 @@ pub async fn update_booking_handler(
                 extra_bed_count = COALESCE($23, extra_bed_count),
                 extra_bed_charge = COALESCE($24, extra_bed_charge),
                 daily_rates = COALESCE($25, daily_rates),
+                tax_amount = COALESCE($26, tax_amount),
                 actual_check_out = CASE WHEN $2 = 'checked_out' AND actual_check_out IS NULL THEN CURRENT_TIMESTAMP ELSE actual_check_out END,
                 updated_at = CURRENT_TIMESTAMP
             WHERE id = $7
@@ -1517,7 +2890,7 @@ pub async fn update_booking_handler(
         .bind(&input.company_name)
         .bind(&input.payment_note)
         .bind(&input.remarks)
-        .bind(&input.source)
+        .bind(&source)
         .bind(&input.payment_method)
         .bind(new_room_rate)
         .bind(new_subtotal)
@@ -1529,24 +2902,40 @@ pub async fn update_booking_handler(
         .bind(input.extra_bed_count)
         .bind(input.extra_bed_charge.map(|v| Decimal::from_f64_retain(v).unwrap_or(Decimal::ZERO)))
         .bind(&daily_rates_json)
-        .fetch_one(&pool)
+        .bind(new_tax_amount)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
     };
 
+    // Choosing a room explicitly turns a type-level booking into an assigned one.
+    if input.room_id.is_some() {
+        sqlx::query("UPDATE bookings SET room_assigned = true WHERE id = $1")
+            .bind(booking_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
     let old_status = existing_booking.status.as_str();
     let updated_status = booking.status.as_str();
+    let today = hotel_time::hotel_today(&pool).await?;
 
     if new_room_id != existing_booking.room_id {
         #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-        let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = ?1 AND id != ?2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > date('now'))"#;
+        let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = ?1 AND id != ?2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > ?3)"#;
         #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-        let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = $1 AND id != $2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > CURRENT_DATE)"#;
+        let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = $1 AND id != $2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > $3)"#;
 
         #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
         let has_other_bookings: bool = sqlx::query_scalar::<_, i32>(has_other_query)
             .bind(existing_booking.room_id)
             .bind(booking_id)
+            .bind(today)
             .fetch_one(&pool)
             .await
             .map(|v| v != 0)
@@ -1556,6 +2945,7 @@ pub async fn update_booking_handler(
         let has_other_bookings: bool = sqlx::query_scalar(has_other_query)
             .bind(existing_booking.room_id)
             .bind(booking_id)
+            .bind(today)
             .fetch_one(&pool)
             .await
             .unwrap_or(false);
@@ -1575,7 +2965,6 @@ pub async fn update_booking_handler(
 
         if updated_status == "confirmed" || updated_status == "pending" {
             // Set room status based on check-in date
-            let today = chrono::Local::now().date_naive();
             let room_status = if check_in == today {
                 "occupied"
             } else {
@@ -1615,14 +3004,15 @@ pub async fn update_booking_handler(
         match updated_status {
             "voided" => {
                 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-                let has_other_query2 = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = ?1 AND id != ?2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > date('now'))"#;
+                let has_other_query2 = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = ?1 AND id != ?2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > ?3)"#;
                 #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-                let has_other_query2 = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = $1 AND id != $2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > CURRENT_DATE)"#;
+                let has_other_query2 = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = $1 AND id != $2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > $3)"#;
 
                 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
                 let has_other_bookings: bool = sqlx::query_scalar::<_, i32>(has_other_query2)
                     .bind(new_room_id)
                     .bind(booking_id)
+                    .bind(today)
                     .fetch_one(&pool)
                     .await
                     .map(|v| v != 0)
@@ -1632,6 +3022,7 @@ pub async fn update_booking_handler(
                 let has_other_bookings: bool = sqlx::query_scalar(has_other_query2)
                     .bind(new_room_id)
                     .bind(booking_id)
+                    .bind(today)
                     .fetch_one(&pool)
                     .await
                     .unwrap_or(false);
@@ -1704,47 +3095,7 @@ pub async fn update_booking_handler(
                     Err(e) => log::error!("Failed to set room {} to dirty: {}", new_room_id, e),
                 }
 
-                // Generate an invoice number for this checked-out booking. Best-effort:
-                // failure here must not block the checkout itself.
-                if let Err(e) = crate::handlers::payments::ensure_invoice_for_booking(
-                    &pool, booking_id, user_id,
-                )
-                .await
-                {
-                    log::warn!(
-                        "Failed to create invoice for checked-out booking {}: {}",
-                        booking_id,
-                        e
-                    );
-                }
-
-                // Auto-post company room charges to customer_ledgers on checkout.
-                //
-                // Why: when a booking with company billing transitions to
-                // checked_out, the receivable must land on the city ledger so
-                // it shows on the company's account. Doing this server-side
-                // ensures every checkout path (Bookings page, Rooms grid,
-                // future paths) gets the same behavior — prior to this only
-                // the Rooms-grid frontend handler created the row, so checkouts
-                // initiated from the Bookings page silently skipped it.
-                //
-                // Idempotent: skip if a non-reversal room_charge row already
-                // exists for this booking. Skip silently when company info is
-                // missing or total_amount is non-positive.
-                if let Some(co_name) = booking.company_name.as_deref()
-                    && !co_name.trim().is_empty()
-                    && booking.total_amount > rust_decimal::Decimal::ZERO
-                    && let Err(e) = auto_post_company_ledger(
-                        &pool, &booking, co_name, check_in, check_out, user_id,
-                    )
-                    .await
-                {
-                    log::warn!(
-                        "Failed to auto-post company ledger for booking {}: {}",
-                        booking_id,
-                        e
-                    );
-                }
+                post_checkout_charges(&pool, &booking, user_id).await;
             }
             "checked_in" | "auto_checked_in" => {
                 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
@@ -1890,7 +3241,7 @@ pub async fn update_booking_handler(
     .bind(modification_type)
     .bind(&old_value)
     .bind(&new_value)
-    .bind(price_adj)
+    .bind(decimal_to_db(price_adj))
     .bind(user_id)
     .execute(&pool)
     .await
@@ -1907,8 +3258,10 @@ pub async fn update_booking_handler(
 pub async fn delete_booking_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     let booking_row = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
 
     let guest_id: i64 = booking_row.guest_id;
@@ -2026,55 +3379,423 @@ pub async fn delete_booking_handler(
             .bind(nights)
             .execute(&pool)
             .await
-            .ok();
-            nights_credited = nights;
+            .ok();
+            nights_credited = nights;
+        }
+    }
+
+    // Log booking cancellation
+    let _ = AuditLog::log_booking_cancelled(&pool, user_id, booking_id).await;
+    record_booking_history(
+        &pool,
+        booking_id,
+        Some(&status),
+        "voided",
+        Some(user_id),
+        Some("Booking voided"),
+        serde_json::json!({
+            "room_id": room_id,
+            "guest_id": guest_id,
+            "check_in_date": check_in_date.to_string(),
+            "check_out_date": check_out_date.to_string(),
+        }),
+    )
+    .await;
+
+    // Record in booking_modifications audit trail
+    sqlx::query(
+        "INSERT INTO booking_modifications (booking_id, modification_type, old_value, new_value, modified_by) VALUES ($1, $2, $3, $4, $5)"
+    )
+    .bind(booking_id)
+    .bind("voided")
+    .bind(serde_json::json!({"status": &status, "check_in_date": check_in_date.to_string(), "check_out_date": check_out_date.to_string()}))
+    .bind(serde_json::json!({"status": "voided"}))
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .ok();
+
+    Ok(Json(serde_json::json!({
+        "message": "Booking voided successfully",
+        "booking_id": booking_id,
+        "complimentary_nights_credited": nights_credited
+    })))
+}
+
+/// Completed payments taken for `booking_id`, less refunds already made.
+async fn amount_paid(pool: &DbPool, booking_id: i64) -> Result<Decimal, ApiError> {
+    let sql = r#"
+        SELECT COALESCE(SUM(CASE WHEN COALESCE(payment_type, 'booking') = 'refund'
+                                 THEN -amount ELSE amount END), 0.0)
+        FROM payments
+        WHERE booking_id = $1 AND status = 'completed'
+    "#;
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let paid = sqlx::query_scalar::<_, f64>(sql)
+        .bind(booking_id)
+        .fetch_one(pool)
+        .await
+        .map(|v| Decimal::from_f64_retain(v).unwrap_or_default().round_dp(2));
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let paid = sqlx::query_scalar::<_, Decimal>(sql)
+        .bind(booking_id)
+        .fetch_one(pool)
+        .await;
+
+    paid.map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// Cancel a pending or confirmed booking under the cancellation policy.
+/// The fee is recorded on the booking and charged, and the room released;
+/// the response says what the guest owes or is owed back.
+pub async fn cancel_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+    Json(input): Json<CancelBookingInput>,
+) -> Result<Json<BookingCancellation>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
+
+    if !cancellation::CANCELLABLE_STATUSES.contains(&booking.status.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "Cannot cancel a booking with status: {}",
+            booking.status
+        )));
+    }
+
+    let reason = input
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    let policy = cancellation::configured_policy(&pool).await;
+    let today = hotel_time::hotel_today(&pool).await?;
+    let charge = cancellation::cancellation_charge(
+        policy,
+        booking.total_amount,
+        amount_paid(&pool, booking_id).await?,
+        booking.check_in_date,
+        today,
+    );
+
+    // Only from the status just checked, so a check-in racing the
+    // cancellation wins rather than being overwritten.
+    let result = sqlx::query(
+        r#"
+        UPDATE bookings
+        SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP, cancelled_by = $2,
+            cancellation_reason = $3, cancellation_fee = $4, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status = $5
+        "#,
+    )
+    .bind(booking_id)
+    .bind(user_id)
+    .bind(reason);
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let result = result.bind(charge.cancellation_fee.to_string());
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let result = result.bind(charge.cancellation_fee);
+    let result = result
+        .bind(&booking.status)
+        .execute(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::Conflict(
+            "Booking was changed while cancelling; please retry".to_string(),
+        ));
+    }
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = ?1 AND id != ?2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > ?3)"#;
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let has_other_query = r#"SELECT EXISTS(SELECT 1 FROM bookings WHERE room_id = $1 AND id != $2 AND status IN ('confirmed', 'checked_in', 'auto_checked_in') AND check_out_date > $3)"#;
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let has_other_bookings: bool = sqlx::query_scalar::<_, i32>(has_other_query)
+        .bind(booking.room_id)
+        .bind(booking_id)
+        .bind(today)
+        .fetch_one(&pool)
+        .await
+        .map(|v| v != 0)
+        .unwrap_or(true);
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let has_other_bookings: bool = sqlx::query_scalar(has_other_query)
+        .bind(booking.room_id)
+        .bind(booking_id)
+        .bind(today)
+        .fetch_one(&pool)
+        .await
+        .unwrap_or(true);
+
+    if !has_other_bookings {
+        if let Err(e) = sqlx::query(
+            "UPDATE rooms SET status = 'available' WHERE id = $1 AND status = 'reserved'",
+        )
+        .bind(booking.room_id)
+        .execute(&pool)
+        .await
+        {
+            log::warn!(
+                "Failed to release room {} after cancelling booking {}: {}",
+                booking.room_id,
+                booking_id,
+                e
+            );
+        }
+    }
+
+    let _ = AuditLog::log_booking_cancelled(&pool, user_id, booking_id).await;
+    record_booking_history(
+        &pool,
+        booking_id,
+        Some(&booking.status),
+        "cancelled",
+        Some(user_id),
+        reason.or(Some("Booking cancelled")),
+        serde_json::json!({
+            "room_id": booking.room_id,
+            "guest_id": booking.guest_id,
+            "check_in_date": booking.check_in_date.to_string(),
+            "days_before_check_in": charge.days_before_check_in,
+            "cancellation_fee": charge.cancellation_fee.to_string(),
+            "refund_amount": charge.refund_amount.to_string(),
+        }),
+    )
+    .await;
+
+    if let Err(e) = booking_fees::charge_fee(
+        &pool,
+        booking_id,
+        booking_fees::CANCELLATION_FEE,
+        charge.cancellation_fee,
+        today,
+        user_id,
+    )
+    .await
+    {
+        log::error!(
+            "Failed to charge the cancellation fee on booking {}: {}",
+            booking_id,
+            e
+        );
+    }
+
+    Ok(Json(BookingCancellation {
+        booking_id,
+        booking_number: booking.booking_number,
+        status: "cancelled".to_string(),
+        days_before_check_in: charge.days_before_check_in,
+        free_cancellation: charge.free_cancellation,
+        cancellation_fee: charge.cancellation_fee,
+        amount_paid: charge.amount_paid,
+        refund_amount: charge.refund_amount,
+        currency: currency::hotel_currency(&pool).await,
+    }))
+}
+
+/// Cancel every booking of a group under the cancellation policy. Bookings
+/// already cancelled are skipped, so repeating the request is harmless; if
+/// any other booking has started the group is left untouched.
+pub async fn cancel_group_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(group_booking_number): Path<String>,
+    Json(input): Json<CancelBookingInput>,
+) -> Result<Json<GroupCancellation>, ApiError> {
+    let booking_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT b.id FROM bookings b
+        INNER JOIN rooms r ON b.room_id = r.id
+        WHERE b.group_booking_number = $1 AND r.property_id = $2
+        ORDER BY b.id
+        "#,
+    )
+    .bind(&group_booking_number)
+    .bind(scope.property_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if booking_ids.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Group booking {} not found",
+            group_booking_number
+        )));
+    }
+
+    let reason = input
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    let policy = cancellation::configured_policy(&pool).await;
+    let today = hotel_time::hotel_today(&pool).await?;
+
+    // Price every cancellation before the transaction, and refuse the whole
+    // group if any stay has already started.
+    let mut to_cancel = Vec::with_capacity(booking_ids.len());
+    let mut already_cancelled = Vec::new();
+    for booking_id in booking_ids {
+        let booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
+        if booking.status == "cancelled" {
+            already_cancelled.push(booking.booking_number);
+            continue;
+        }
+        if !cancellation::CANCELLABLE_STATUSES.contains(&booking.status.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Cannot cancel booking {} with status: {}",
+                booking.booking_number, booking.status
+            )));
+        }
+        let charge = cancellation::cancellation_charge(
+            policy,
+            booking.total_amount,
+            amount_paid(&pool, booking_id).await?,
+            booking.check_in_date,
+            today,
+        );
+        to_cancel.push((booking, charge));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut fees = Vec::new();
+    for (booking, charge) in &to_cancel {
+        let result = sqlx::query(
+            r#"
+            UPDATE bookings
+            SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP, cancelled_by = $2,
+                cancellation_reason = $3, cancellation_fee = $4, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND status = $5
+            "#,
+        )
+        .bind(booking.id)
+        .bind(user_id)
+        .bind(reason);
+        #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+        let result = result.bind(charge.cancellation_fee.to_string());
+        #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+        let result = result.bind(charge.cancellation_fee);
+        let result = result
+            .bind(&booking.status)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::Conflict(format!(
+                "Booking {} was changed while cancelling; please retry",
+                booking.booking_number
+            )));
+        }
+
+        // Release the room unless another stay still holds it.
+        sqlx::query(
+            r#"
+            UPDATE rooms SET status = 'available'
+            WHERE id = $1 AND status = 'reserved'
+              AND NOT EXISTS (
+                  SELECT 1 FROM bookings
+                  WHERE room_id = $1
+                    AND status IN ('confirmed', 'checked_in', 'auto_checked_in')
+                    AND check_out_date > $2
+              )
+            "#,
+        )
+        .bind(booking.room_id)
+        .bind(today)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        // Post the fee with the cancellation so the group is never left
+        // cancelled without it.
+        if let Some(fee) = booking_fees::charge_fee_in(
+            &mut tx,
+            booking.id,
+            booking_fees::CANCELLATION_FEE,
+            charge.cancellation_fee,
+            today,
+            user_id,
+        )
+        .await?
+        {
+            fees.push(fee);
         }
     }
 
-    // Log booking cancellation
-    let _ = AuditLog::log_booking_cancelled(&pool, user_id, booking_id).await;
-    record_booking_history(
-        &pool,
-        booking_id,
-        Some(&status),
-        "voided",
-        Some(user_id),
-        Some("Booking voided"),
-        serde_json::json!({
-            "room_id": room_id,
-            "guest_id": guest_id,
-            "check_in_date": check_in_date.to_string(),
-            "check_out_date": check_out_date.to_string(),
-        }),
-    )
-    .await;
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    for fee in &fees {
+        booking_fees::record_fee_charged(&pool, fee, today, user_id).await;
+    }
 
-    // Record in booking_modifications audit trail
-    sqlx::query(
-        "INSERT INTO booking_modifications (booking_id, modification_type, old_value, new_value, modified_by) VALUES ($1, $2, $3, $4, $5)"
-    )
-    .bind(booking_id)
-    .bind("voided")
-    .bind(serde_json::json!({"status": &status, "check_in_date": check_in_date.to_string(), "check_out_date": check_out_date.to_string()}))
-    .bind(serde_json::json!({"status": "voided"}))
-    .bind(user_id)
-    .execute(&pool)
-    .await
-    .ok();
+    let hotel_currency = currency::hotel_currency(&pool).await;
+    let mut cancelled = Vec::with_capacity(to_cancel.len());
+    for (booking, charge) in to_cancel {
+        let _ = AuditLog::log_booking_cancelled(&pool, user_id, booking.id).await;
+        record_booking_history(
+            &pool,
+            booking.id,
+            Some(&booking.status),
+            "cancelled",
+            Some(user_id),
+            reason.or(Some("Group cancelled")),
+            serde_json::json!({
+                "group_booking_number": &group_booking_number,
+                "room_id": booking.room_id,
+                "guest_id": booking.guest_id,
+                "check_in_date": booking.check_in_date.to_string(),
+                "days_before_check_in": charge.days_before_check_in,
+                "cancellation_fee": charge.cancellation_fee.to_string(),
+                "refund_amount": charge.refund_amount.to_string(),
+            }),
+        )
+        .await;
 
-    Ok(Json(serde_json::json!({
-        "message": "Booking voided successfully",
-        "booking_id": booking_id,
-        "complimentary_nights_credited": nights_credited
-    })))
+        cancelled.push(BookingCancellation {
+            booking_id: booking.id,
+            booking_number: booking.booking_number,
+            status: "cancelled".to_string(),
+            days_before_check_in: charge.days_before_check_in,
+            free_cancellation: charge.free_cancellation,
+            cancellation_fee: charge.cancellation_fee,
+            amount_paid: charge.amount_paid,
+            refund_amount: charge.refund_amount,
+            currency: hotel_currency.clone(),
+        });
+    }
+
+    Ok(Json(GroupCancellation {
+        group_booking_number,
+        total_cancellation_fee: cancelled.iter().map(|c| c.cancellation_fee).sum(),
+        total_refund_amount: cancelled.iter().map(|c| c.refund_amount).sum(),
+        cancelled,
+        already_cancelled,
+        currency: hotel_currency,
+    }))
 }
 
 pub async fn manual_checkin_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
     Json(checkin_data): Json<Option<CheckInRequest>>,
 ) -> Result<Json<Booking>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     let booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
 
     let has_checkin_permission = AuthService::check_permission(&pool, user_id, "bookings:update")
@@ -2099,6 +3820,19 @@ pub async fn manual_checkin_handler(
         )));
     }
 
+    // Type-level bookings need a concrete room before the guest can check in.
+    let booking = if room_assignment::needs_assignment(&pool, booking_id).await? {
+        if !room_assignment::auto_assign_enabled(&pool).await {
+            return Err(ApiError::BadRequest(
+                "Cannot check in - no room has been assigned to this booking yet.".to_string(),
+            ));
+        }
+        room_assignment::auto_assign_room(&pool, booking_id).await?;
+        booking_svc::fetch_booking_by_id(&pool, booking_id).await?
+    } else {
+        booking
+    };
+
     // Check if room is ready for check-in (only block maintenance/out_of_order)
     // Note: Dirty/cleaning rooms are allowed for check-in - room will be set to occupied
     let room_status: Option<String> = sqlx::query_scalar("SELECT status FROM rooms WHERE id = $1")
@@ -2107,7 +3841,7 @@ pub async fn manual_checkin_handler(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    if let Some(status) = room_status
+    if let Some(status) = room_status.as_deref()
         && (status == "maintenance" || status == "out_of_order")
     {
         return Err(ApiError::BadRequest(format!(
@@ -2291,18 +4025,30 @@ pub async fn manual_checkin_handler(
     }
 
     // Only update room status for current/future bookings (skip back-dated)
-    let today = chrono::Local::now().date_naive();
-    if booking.check_out_date >= today
-        && let Err(e) = sqlx::query("UPDATE rooms SET status = 'occupied' WHERE id = $1")
+    let today = hotel_time::hotel_today(&pool).await?;
+    if booking.check_out_date >= today {
+        match sqlx::query("UPDATE rooms SET status = 'occupied' WHERE id = $1")
             .bind(booking.room_id)
             .execute(&pool)
             .await
-    {
-        log::warn!(
-            "Failed to update room {} to occupied during check-in: {}",
-            booking.room_id,
-            e
-        );
+        {
+            Ok(_) => {
+                record_room_transition(
+                    &pool,
+                    booking.room_id,
+                    room_status.as_deref(),
+                    "occupied",
+                    user_id,
+                    &format!("Check-in: booking {}", booking.booking_number),
+                )
+                .await
+            }
+            Err(e) => log::warn!(
+                "Failed to update room {} to occupied during check-in: {}",
+                booking.room_id,
+                e
+            ),
+        }
     }
 
     // Back-fill night audit postings for any past nights whose audit already closed.
@@ -2368,6 +4114,163 @@ pub async fn manual_checkin_handler(
     Ok(Json(updated_booking))
 }
 
+/// Check a guest out. The stay must be checked in and, unless
+/// `override_balance` is set, paid in full by the guest; company-billed
+/// charges go to the city ledger instead. The room is left dirty for
+/// housekeeping, and a loyalty member earns the stay's points.
+pub async fn checkout_booking_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(booking_id): Path<i64>,
+    Json(checkout_data): Json<Option<CheckOutRequest>>,
+) -> Result<Json<Booking>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
+    let checkout = checkout_data.unwrap_or_default();
+    let booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
+
+    if booking.status != "checked_in" && booking.status != "auto_checked_in" {
+        return Err(ApiError::BadRequest(format!(
+            "Cannot check out booking with status: {}",
+            booking.status
+        )));
+    }
+
+    let balance_due = guest_balance::booking_charges(&pool, booking_id)
+        .await?
+        .map(|charges| charges.guest_balance_due())
+        .unwrap_or_default();
+    if balance_due > Decimal::ZERO && !checkout.override_balance {
+        return Err(ApiError::BadRequest(format!(
+            "Cannot check out - the guest has an outstanding balance of {}.",
+            balance_due
+        )));
+    }
+
+    let result = sqlx::query(
+        r#"
+        UPDATE bookings
+        SET status = 'checked_out', actual_check_out = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status = $2
+        "#,
+    )
+    .bind(booking_id)
+    .bind(&booking.status)
+    .execute(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::Conflict(
+            "Booking was changed while checking out; please retry".to_string(),
+        ));
+    }
+
+    let room_status: Option<String> = sqlx::query_scalar("SELECT status FROM rooms WHERE id = $1")
+        .bind(booking.room_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    match sqlx::query("UPDATE rooms SET status = 'dirty' WHERE id = $1")
+        .bind(booking.room_id)
+        .execute(&pool)
+        .await
+    {
+        Ok(_) => {
+            record_room_transition(
+                &pool,
+                booking.room_id,
+                room_status.as_deref(),
+                "dirty",
+                user_id,
+                &format!("Check-out: booking {}", booking.booking_number),
+            )
+            .await
+        }
+        Err(e) => log::warn!(
+            "Failed to update room {} to dirty during check-out: {}",
+            booking.room_id,
+            e
+        ),
+    }
+
+    let updated_booking = booking_svc::fetch_booking_by_id(&pool, booking_id).await?;
+    post_checkout_charges(&pool, &updated_booking, user_id).await;
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    if let Err(e) =
+        crate::services::night_audit::backfill_booking_posted_nights(&pool, booking_id, user_id)
+            .await
+    {
+        log::warn!(
+            "Failed to backfill posted nights for booking {}: {}",
+            booking_id,
+            e
+        );
+    }
+
+    // The loyalty tables exist only in the PostgreSQL schema.
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    match crate::services::loyalty::award_stay_points(&pool, &updated_booking, user_id).await {
+        Ok(Some(points)) => log::info!(
+            "Awarded {} loyalty points for booking {}",
+            points,
+            booking_id
+        ),
+        Ok(None) => {}
+        Err(e) => log::warn!(
+            "Failed to award loyalty points for booking {}: {}",
+            booking_id,
+            e
+        ),
+    }
+
+    let details = serde_json::json!({
+        "guest_id": booking.guest_id,
+        "room_id": booking.room_id,
+        "balance_due": balance_due.to_string(),
+        "balance_overridden": balance_due > Decimal::ZERO,
+    });
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "booking_checkout",
+        "booking",
+        Some(booking_id),
+        Some(details.clone()),
+        None,
+        None,
+    )
+    .await;
+    record_booking_history(
+        &pool,
+        booking_id,
+        Some(&booking.status),
+        "checked_out",
+        Some(user_id),
+        Some(checkout.notes.as_deref().unwrap_or("Guest checked out")),
+        details,
+    )
+    .await;
+
+    if let Err(e) = sqlx::query(
+        "INSERT INTO booking_modifications (booking_id, modification_type, old_value, new_value, modified_by) VALUES ($1, $2, $3, $4, $5)"
+    )
+    .bind(booking_id)
+    .bind("check_out")
+    .bind(serde_json::json!({"status": &booking.status, "room_id": booking.room_id}))
+    .bind(serde_json::json!({"status": "checked_out", "room_id": booking.room_id}))
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    {
+        log::warn!("Failed to record check-out audit trail for booking {}: {}", booking_id, e);
+    }
+
+    Ok(Json(updated_booking))
+}
+
 pub async fn pre_checkin_update_handler(
     State(pool): State<DbPool>,
     Path(booking_id): Path<i64>,
@@ -2454,9 +4357,11 @@ pub async fn pre_checkin_update_handler(
 pub async fn mark_complimentary_handler(
     State(pool): State<DbPool>,
     Extension(_user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
     Json(input): Json<MarkComplimentaryRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     // Check if booking exists and is in a valid state, get room and rate info
     let booking_row = sqlx::query(
         r#"
@@ -2483,7 +4388,6 @@ pub async fn mark_complimentary_handler(
     let room_rate: Decimal = booking_row.get(6);
     let original_total: Decimal = booking_row.get(7);
     let _subtotal: Decimal = booking_row.get(8);
-    let tax_amount: Option<Decimal> = booking_row.get(9);
     let room_type_id: i64 = booking_row.get(10);
     let room_type_name: String = booking_row.get(11);
 
@@ -2541,15 +4445,14 @@ pub async fn mark_complimentary_handler(
     };
 
     // Calculate new pricing
-    let new_subtotal = room_rate * Decimal::from(paid_nights);
-    // Calculate tax proportionally (if there was tax before)
-    let tax_rate = if original_total > Decimal::ZERO && total_nights > 0 {
-        tax_amount.unwrap_or(Decimal::ZERO) / (room_rate * Decimal::from(total_nights))
-    } else {
-        Decimal::new(10, 2) // Default 10% if we can't calculate
-    };
-    let new_tax = new_subtotal * tax_rate;
-    let new_total = new_subtotal + new_tax;
+    let taxes = booking_svc::price_paid_nights(
+        room_rate,
+        paid_nights,
+        &TaxContext::load(&pool, TaxMode::Inclusive).await,
+    );
+    let new_subtotal = taxes.gross;
+    let new_tax = taxes.service_tax;
+    let new_total = taxes.gross;
 
     // Determine payment status
     let payment_status = if complimentary_nights == total_nights {
@@ -2659,8 +4562,10 @@ pub async fn mark_complimentary_handler(
 pub async fn convert_complimentary_to_credits_handler(
     State(pool): State<DbPool>,
     Extension(_user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     // Get booking details with room info
     let booking_row = sqlx::query(
         r#"
@@ -2828,39 +4733,21 @@ pub async fn book_with_credits_handler(
         )));
     }
 
-    // Check room availability
-    let room_available: bool = sqlx::query_scalar(
-        r#"
-        SELECT NOT EXISTS(
-            SELECT 1 FROM bookings
-            WHERE room_id = $1
-              AND status NOT IN ('checked_out', 'voided')
-              AND check_in_date < $3
-              AND check_out_date > $2
-        )
-        "#,
-    )
-    .bind(input.room_id)
-    .bind(check_in)
-    .bind(check_out)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    if !room_available {
-        return Err(ApiError::BadRequest(
-            "Room is not available for the selected dates".to_string(),
-        ));
-    }
-
     // Calculate charges for non-complimentary nights
     let paid_nights = total_nights - complimentary_nights;
-    let subtotal = room_rate * Decimal::from(paid_nights);
-    let tax_amount = subtotal * Decimal::from_str_exact("0.10").unwrap_or_default();
-    let total_amount = subtotal + tax_amount;
+    let taxes = booking_svc::price_paid_nights(
+        room_rate,
+        paid_nights,
+        &TaxContext::load(&pool, TaxMode::Inclusive).await,
+    );
+    let subtotal = taxes.gross;
+    let tax_amount = taxes.service_tax;
+    let total_amount = taxes.gross;
 
-    // Generate booking number
-    let booking_number = format!("COMP-{}", chrono::Utc::now().format("%Y%m%d%H%M%S"));
+    // Allocated like any other booking number; a timestamp is not unique
+    // when two credits bookings land in the same second.
+    let booking_number =
+        booking_numbers::next_booking_number(&pool, hotel_time::hotel_today(&pool).await?).await?;
 
     // Format complimentary dates for storage
     let complimentary_dates_str: Vec<String> = complimentary_dates
@@ -2887,6 +4774,28 @@ pub async fn book_with_credits_handler(
         )
     };
 
+    // Availability check, insert and credit deduction are atomic under the
+    // room lock.
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    lock_room(&mut tx, input.room_id).await?;
+
+    let conflicts: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
+        .bind(input.room_id)
+        .bind(check_in)
+        .bind(check_out)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if !conflicts.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Room is not available for the selected dates".to_string(),
+        ));
+    }
+
     // Create the booking
     let booking_id: i64 = sqlx::query_scalar(
         r#"
@@ -2920,25 +4829,37 @@ pub async fn book_with_credits_handler(
     .bind(&input.special_requests)
     .bind(&complimentary_reason)
     .bind(user_id)
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Deduct credits from room-type specific credits
-    sqlx::query(
-        "UPDATE guest_complimentary_credits SET nights_available = nights_available - $1, updated_at = CURRENT_TIMESTAMP WHERE guest_id = $2 AND room_type_id = $3"
+    // Deduct credits from room-type specific credits, unless a concurrent
+    // booking spent them first
+    let deducted = sqlx::query(
+        "UPDATE guest_complimentary_credits SET nights_available = nights_available - $1, updated_at = CURRENT_TIMESTAMP WHERE guest_id = $2 AND room_type_id = $3 AND nights_available >= $1"
     )
     .bind(complimentary_nights)
     .bind(input.guest_id)
     .bind(room_type_id)
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
-    .ok();
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if deducted.rows_affected() == 0 {
+        return Err(ApiError::BadRequest(format!(
+            "Insufficient complimentary credits for {}",
+            room_type_name
+        )));
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
 
     // Update room status based on check-in date:
     // - If check-in is today: set to 'occupied' (guest arriving today)
     // - If check-in is in the future: set to 'reserved'
-    let today = chrono::Local::now().date_naive();
+    let today = hotel_time::hotel_today(&pool).await?;
     let room_status = if check_in == today {
         "occupied"
     } else {
@@ -2969,6 +4890,7 @@ pub async fn book_with_credits_handler(
 /// Get all complimentary bookings
 pub async fn get_complimentary_bookings_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
 ) -> Result<Json<Vec<BookingWithDetails>>, ApiError> {
     let bookings: Vec<BookingWithDetails> = sqlx::query_as(
         r#"
@@ -2988,11 +4910,13 @@ pub async fn get_complimentary_bookings_handler(
         INNER JOIN guests g ON b.guest_id = g.id
         INNER JOIN rooms r ON b.room_id = r.id
         INNER JOIN room_types rt ON r.room_type_id = rt.id
-        WHERE b.is_complimentary = true
-           OR b.status IN ('partial_complimentary', 'fully_complimentary')
+        WHERE (b.is_complimentary = true
+               OR b.status IN ('partial_complimentary', 'fully_complimentary'))
+          AND b.property_id = $1
         ORDER BY b.created_at DESC
         "#
     )
+    .bind(scope.property_id)
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -3003,19 +4927,22 @@ pub async fn get_complimentary_bookings_handler(
 /// Get complimentary statistics summary
 pub async fn get_complimentary_summary_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Total complimentary bookings
     let total_bookings: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM bookings WHERE is_complimentary = true OR status IN ('partial_complimentary', 'fully_complimentary')"
+        "SELECT COUNT(*) FROM bookings WHERE (is_complimentary = true OR status IN ('partial_complimentary', 'fully_complimentary')) AND property_id = $1"
     )
+    .bind(scope.property_id)
     .fetch_one(&pool)
     .await
     .unwrap_or(0);
 
     // Total complimentary nights
     let total_nights: i64 = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(complimentary_nights), 0) FROM bookings WHERE is_complimentary = true OR status IN ('partial_complimentary', 'fully_complimentary')"
+        "SELECT COALESCE(SUM(complimentary_nights), 0) FROM bookings WHERE (is_complimentary = true OR status IN ('partial_complimentary', 'fully_complimentary')) AND property_id = $1"
     )
+    .bind(scope.property_id)
     .fetch_one(&pool)
     .await
     .unwrap_or(0);
@@ -3030,8 +4957,9 @@ pub async fn get_complimentary_summary_handler(
 
     // Value of complimentary nights (sum of original amounts - adjusted amounts)
     let value_given: Decimal = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(original_total_amount - total_amount), 0) FROM bookings WHERE is_complimentary = true AND original_total_amount IS NOT NULL"
+        "SELECT COALESCE(SUM(original_total_amount - total_amount), 0) FROM bookings WHERE is_complimentary = true AND original_total_amount IS NOT NULL AND property_id = $1"
     )
+    .bind(scope.property_id)
     .fetch_one(&pool)
     .await
     .unwrap_or(Decimal::ZERO);
@@ -3048,9 +4976,11 @@ pub async fn get_complimentary_summary_handler(
 pub async fn update_complimentary_handler(
     State(pool): State<DbPool>,
     Extension(_user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
     Json(input): Json<UpdateComplimentaryRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     // Get current booking
     let booking_row = sqlx::query(
         "SELECT id, is_complimentary, check_in_date, check_out_date, room_rate, total_amount FROM bookings WHERE id = $1"
@@ -3119,10 +5049,14 @@ pub async fn update_complimentary_handler(
             "partial_complimentary"
         };
 
-        let new_subtotal = room_rate * Decimal::from(paid_nights);
-        let tax_rate = Decimal::from_str_exact("0.10").unwrap_or_default();
-        let new_tax = new_subtotal * tax_rate;
-        let new_total = new_subtotal + new_tax;
+        let taxes = booking_svc::price_paid_nights(
+            room_rate,
+            paid_nights,
+            &TaxContext::load(&pool, TaxMode::Inclusive).await,
+        );
+        let new_subtotal = taxes.gross;
+        let new_tax = taxes.service_tax;
+        let new_total = taxes.gross;
 
         sqlx::query(
             r#"
@@ -3209,8 +5143,10 @@ pub async fn update_complimentary_handler(
 pub async fn remove_complimentary_handler(
     State(pool): State<DbPool>,
     Extension(_user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_booking(&pool, booking_id).await?;
     // Get current booking
     let booking_row = sqlx::query(
         "SELECT id, guest_id, is_complimentary, original_total_amount, complimentary_nights, status FROM bookings WHERE id = $1"
@@ -3584,23 +5520,21 @@ pub async fn delete_guest_credits_handler(
 pub async fn reactivate_booking_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
     Path(booking_id): Path<i64>,
 ) -> Result<Json<Booking>, ApiError> {
-    // Get the booking
-    let existing_row = sqlx::query(
-        "SELECT id, guest_id, room_id, status, check_in_date, check_out_date FROM bookings WHERE id = $1"
-    )
-    .bind(booking_id)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?
-    .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+    scope.require_booking(&pool, booking_id).await?;
+    let repo = SqlBookingRepository::new(&pool);
+    let existing = repo
+        .find(booking_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
 
-    let guest_id: i64 = existing_row.get("guest_id");
-    let room_id: i64 = existing_row.get("room_id");
-    let status: String = existing_row.get("status");
-    let check_in: NaiveDate = existing_row.get("check_in_date");
-    let check_out: NaiveDate = existing_row.get("check_out_date");
+    let guest_id = existing.guest_id;
+    let room_id = existing.room_id;
+    let status = existing.status;
+    let check_in = existing.check_in_date;
+    let check_out = existing.check_out_date;
 
     // Only allow reactivation for voided bookings
     if status != "voided" {
@@ -3624,30 +5558,23 @@ pub async fn reactivate_booking_handler(
         ));
     }
 
-    // Check for conflicting bookings (same logic as create_booking)
-    let conflict_query = r#"
-        SELECT EXISTS(
-            SELECT 1 FROM bookings
-            WHERE room_id = $1 
-              AND status IN ('reserved', 'confirmed', 'checked_in', 'pending') 
-              AND status != 'voided'
-              AND id != $4
-              AND ((check_in_date <= $2 AND check_out_date > $2)
-                  OR (check_in_date < $3 AND check_out_date >= $3)
-                  OR (check_in_date >= $2 AND check_out_date <= $3))
-        )
-    "#;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    lock_room(&mut tx, room_id).await?;
 
-    let conflict: bool = sqlx::query_scalar::<_, bool>(conflict_query)
+    // Check for conflicting bookings (same logic as create_booking); the
+    // booking itself is voided, so it never conflicts with itself.
+    let conflicts: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
         .bind(room_id)
         .bind(check_in)
         .bind(check_out)
-        .bind(booking_id)
-        .fetch_one(&pool)
+        .fetch_all(&mut *tx)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    if conflict {
+    if !conflicts.is_empty() {
         return Err(ApiError::BadRequest(
             "Cannot reactivate booking - room is already booked for these dates".to_string(),
         ));
@@ -3665,12 +5592,16 @@ pub async fn reactivate_booking_handler(
         "#
     )
     .bind(booking_id)
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
     // Update room status based on check-in date
-    let today = chrono::Local::now().date_naive();
+    let today = hotel_time::hotel_today(&pool).await?;
     let room_status = if check_in == today {
         "occupied"
     } else {
@@ -12,10 +12,13 @@ use rust_decimal::Decimal;
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::require_auth;
+use crate::core::middleware::{require_auth, require_permission_helper};
 use crate::models::{
-    Company, CompanyCreateRequest, CompanyListQuery, CompanyUpdateRequest, row_mappers,
+    Company, CompanyBookings, CompanyBookingsQuery, CompanyCreateRequest, CompanyListQuery,
+    CompanyPaymentRequest, CompanyPaymentResult, CompanyUpdateRequest, row_mappers,
 };
+use crate::services::audit::AuditLog;
+use crate::services::{company_bookings, company_payments};
 
 /// List all companies with optional filters
 pub async fn list_companies_handler(
@@ -479,3 +482,73 @@ pub async fn delete_company_handler(
         "message": "Company deleted successfully"
     })))
 }
+
+/// Post one payment across the company's open invoices
+pub async fn post_company_payment_handler(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Path(company_id): Path<i64>,
+    Json(input): Json<CompanyPaymentRequest>,
+) -> Result<Json<CompanyPaymentResult>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "payments:create").await?;
+
+    let result = company_payments::post_company_payment(&pool, company_id, user_id, &input).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "company_payment_posted",
+        "company",
+        Some(company_id),
+        Some(serde_json::json!({
+            "amount": result.amount,
+            "credit_amount": result.credit_amount,
+            "ledger_ids": result.applied.iter().map(|a| a.ledger_id).collect::<Vec<_>>(),
+            "payment_reference": input.payment_reference,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(result))
+}
+
+/// List the bookings attributed to a company, with the revenue of its stays
+pub async fn get_company_bookings_handler(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Path(company_id): Path<i64>,
+    Query(query): Query<CompanyBookingsQuery>,
+) -> Result<Json<CompanyBookings>, ApiError> {
+    require_permission_helper(&pool, &headers, "bookings:read").await?;
+
+    if let (Some(start), Some(end)) = (query.start_date, query.end_date)
+        && end < start
+    {
+        return Err(ApiError::BadRequest(
+            "end_date must be on or after start_date".to_string(),
+        ));
+    }
+
+    let company_name: String =
+        sqlx::query_scalar("SELECT company_name FROM companies WHERE id = $1")
+            .bind(company_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound("Company not found".to_string()))?;
+
+    let bookings =
+        company_bookings::company_bookings(&pool, company_id, query.start_date, query.end_date)
+            .await?;
+    let (stays, revenue) = company_bookings::stay_totals(&bookings);
+
+    Ok(Json(CompanyBookings {
+        company_id,
+        company_name,
+        stays,
+        revenue,
+        bookings,
+    }))
+}
@@ -17,11 +17,19 @@ use crate::constants::EkycStatus;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::require_auth;
+use crate::core::uploads::{self, JPEG, PNG, UploadPolicy};
 use crate::models::{
     EkycStatusResponse, EkycSubmissionRequest, EkycVerification, EkycVerificationUpdate,
     SelfCheckinEvent, SelfCheckinRequest,
 };
 
+/// Identity documents and selfies, uploaded or sent as base64.
+pub const EKYC_IMAGE: UploadPolicy = UploadPolicy {
+    what: "Document image",
+    max_bytes: 10 * 1024 * 1024,
+    file_types: &[&JPEG, &PNG],
+};
+
 /// Helper function to save base64 image to file system
 fn save_base64_image(
     base64_data: &str,
@@ -45,10 +53,14 @@ fn save_base64_image(
     let bytes = general_purpose::STANDARD
         .decode(data)
         .map_err(|e| ApiError::BadRequest(format!("Invalid base64 data: {}", e)))?;
+    let file_type = EKYC_IMAGE.capped().sniff(&bytes)?;
 
     // Generate unique filename
     let timestamp = Utc::now().timestamp();
-    let filename = format!("{}_{}_{}.jpg", user_id, image_type, timestamp);
+    let filename = format!(
+        "{}_{}_{}.{}",
+        user_id, image_type, timestamp, file_type.extension
+    );
     let file_path = upload_dir.join(&filename);
 
     // Save file
@@ -88,51 +100,24 @@ pub async fn upload_document_handler(
                 ApiError::BadRequest(format!("Failed to read document type: {}", e))
             })?;
         } else if field_name == "file" {
-            let content_type = field.content_type().unwrap_or("").to_string();
-
-            // Validate file type
-            if !content_type.starts_with("image/") {
-                return Err(ApiError::BadRequest(
-                    "Only image files are allowed".to_string(),
-                ));
-            }
-
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
-
-            // Validate file size (max 10MB)
-            if data.len() > 10 * 1024 * 1024 {
-                return Err(ApiError::BadRequest(
-                    "File size must be less than 10MB".to_string(),
-                ));
-            }
+            let upload = uploads::read_file(field, &EKYC_IMAGE).await?;
 
             // Generate unique filename
             let timestamp = Utc::now().timestamp();
-            let extension = if content_type.contains("jpeg") || content_type.contains("jpg") {
-                "jpg"
-            } else if content_type.contains("png") {
-                "png"
-            } else {
-                "jpg"
-            };
-
             let filename = format!(
                 "{}_{}_{}_{}.{}",
                 user_id,
                 document_type,
                 timestamp,
                 uuid::Uuid::new_v4(),
-                extension
+                upload.file_type.extension
             );
             let full_path = upload_dir.join(&filename);
 
             // Save file
             let mut file = fs::File::create(&full_path)
                 .map_err(|e| ApiError::Internal(format!("Failed to create file: {}", e)))?;
-            file.write_all(&data)
+            file.write_all(&upload.data)
                 .map_err(|e| ApiError::Internal(format!("Failed to write file: {}", e)))?;
 
             file_path = format!("uploads/ekyc/{}", filename);
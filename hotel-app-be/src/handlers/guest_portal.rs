@@ -1,10 +1,11 @@
 //! Guest portal handlers
 //!
-//! Handles guest self-service features including pre-check-in.
+//! Handles guest self-service features including pre-check-in and reviews
+//! of past stays.
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Extension, Path, State},
 };
 use chrono::{Duration, Utc};
 use uuid::Uuid;
@@ -12,9 +13,13 @@ use uuid::Uuid;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::models::{
-    Booking, Guest, GuestPortalBookingResponse, GuestPortalVerifyRequest,
-    GuestPortalVerifyResponse, PreCheckInUpdateRequest,
+    Booking, CommunicationPreferences, CommunicationPreferencesUpdate, Guest,
+    GuestPortalBookingResponse, GuestPortalProfile, GuestPortalProfileUpdate,
+    GuestPortalVerifyRequest, GuestPortalVerifyResponse, GuestReviewInput, GuestReviewSubmitted,
+    PreCheckInUpdateRequest,
 };
+use crate::services::{communication, hotel_time, portal_guest, review_points};
+use crate::utils::sanitization::Sanitizer;
 
 /// Generate a secure random token for pre-checkin
 fn generate_precheckin_token() -> String {
@@ -57,7 +62,7 @@ pub async fn verify_guest_booking(
 
     // Check if booking is eligible for pre-checkin (check-in date within next 7 days)
     let check_in_date = booking.check_in_date;
-    let today = Utc::now().date_naive();
+    let today = hotel_time::hotel_today(&pool).await?;
     let days_until_checkin = (check_in_date - today).num_days();
 
     if days_until_checkin < 0 {
@@ -316,3 +321,315 @@ pub async fn submit_precheckin_update(
         guest: updated_guest,
     }))
 }
+
+// ---------------------------------------------------------------------------
+// Signed-in guest profile
+// ---------------------------------------------------------------------------
+
+/// Guest columns that are named differently in the two schemas:
+/// `(address line 1, address line 2, state)`.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const ADDRESS_COLUMNS: (&str, &str, &str) = ("address_line_1", "address_line_2", "state");
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const ADDRESS_COLUMNS: (&str, &str, &str) = ("address_line1", "address_line2", "state_province");
+
+/// Excludes soft-deleted guests where the schema has them.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const LIVE_GUEST: &str = "AND g.deleted_at IS NULL";
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const LIVE_GUEST: &str = "";
+
+/// Trim and strip control characters, rejecting values over `max_len`.
+fn clean_field(value: &str, field: &str, max_len: usize) -> Result<String, ApiError> {
+    let cleaned = Sanitizer::sanitize_text(value).trim().to_string();
+    if cleaned.chars().count() > max_len {
+        return Err(ApiError::BadRequest(format!(
+            "{} must be at most {} characters",
+            field, max_len
+        )));
+    }
+    Ok(cleaned)
+}
+
+fn clean_phone(value: &str, field: &str) -> Result<String, ApiError> {
+    let phone = Sanitizer::sanitize_phone(value);
+    let phone_regex = regex::Regex::new(r"^\+?[1-9]\d{7,14}$").unwrap();
+    if value.trim().is_empty() || phone_regex.is_match(&phone) {
+        Ok(phone)
+    } else {
+        Err(ApiError::BadRequest(format!("Invalid {}", field)))
+    }
+}
+
+/// Sanitize and validate a portal profile update. Present fields come back
+/// trimmed, with an empty string meaning the field is to be cleared.
+pub fn sanitize_profile_update(
+    input: GuestPortalProfileUpdate,
+) -> Result<GuestPortalProfileUpdate, ApiError> {
+    let email = match input.email {
+        Some(email) => {
+            let email = Sanitizer::sanitize_email(&Sanitizer::sanitize_text(&email));
+            let email_regex =
+                regex::Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
+            if !email.is_empty() && (email.len() > 255 || !email_regex.is_match(&email)) {
+                return Err(ApiError::BadRequest("Invalid email format".to_string()));
+            }
+            Some(email)
+        }
+        None => None,
+    };
+    let text = |value: Option<String>, field: &str, max_len: usize| {
+        value.map(|v| clean_field(&v, field, max_len)).transpose()
+    };
+
+    Ok(GuestPortalProfileUpdate {
+        email,
+        phone: input
+            .phone
+            .map(|v| clean_phone(&v, "phone number"))
+            .transpose()?,
+        alt_phone: input
+            .alt_phone
+            .map(|v| clean_phone(&v, "alternate phone number"))
+            .transpose()?,
+        address_line1: text(input.address_line1, "address_line1", 255)?,
+        address_line2: text(input.address_line2, "address_line2", 255)?,
+        city: text(input.city, "city", 100)?,
+        state_province: text(input.state_province, "state_province", 100)?,
+        postal_code: text(input.postal_code, "postal_code", 20)?,
+        country: text(input.country, "country", 100)?,
+    })
+}
+
+/// The guest record the user may edit. A user linked to several guests
+/// (family members, say) gets the earliest link they can modify.
+async fn own_guest_id(pool: &DbPool, user_id: i64) -> Result<i64, ApiError> {
+    portal_guest::ensure_portal_guest(pool, user_id).await?;
+    sqlx::query_scalar::<_, i64>(&format!(
+        r#"
+        SELECT ug.guest_id
+        FROM user_guests ug
+        JOIN guests g ON g.id = ug.guest_id
+        WHERE ug.user_id = $1 AND ug.can_modify = true {}
+        ORDER BY ug.id
+        LIMIT 1
+        "#,
+        LIVE_GUEST
+    ))
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::Forbidden("No guest profile is linked to this account".to_string()))
+}
+
+async fn fetch_guest_profile(pool: &DbPool, guest_id: i64) -> Result<GuestPortalProfile, ApiError> {
+    let (address_line1, address_line2, state) = ADDRESS_COLUMNS;
+    sqlx::query_as::<_, GuestPortalProfile>(&format!(
+        r#"
+        SELECT id AS guest_id,
+               COALESCE(full_name, TRIM(COALESCE(first_name, '') || ' ' || COALESCE(last_name, ''))) AS full_name,
+               email, phone, alt_phone,
+               {} AS address_line1, {} AS address_line2, city, {} AS state_province,
+               postal_code, country
+        FROM guests
+        WHERE id = $1
+        "#,
+        address_line1, address_line2, state
+    ))
+    .bind(guest_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(format!("Failed to fetch guest: {}", e)))
+}
+
+/// GET /portal/guest-profile
+/// The signed-in user's own guest record
+pub async fn get_own_guest_profile(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+) -> Result<Json<GuestPortalProfile>, ApiError> {
+    let guest_id = own_guest_id(&pool, user_id).await?;
+    Ok(Json(fetch_guest_profile(&pool, guest_id).await?))
+}
+
+/// PATCH /portal/guest-profile
+/// Updates contact details on the signed-in user's own guest record
+pub async fn update_own_guest_profile(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<GuestPortalProfileUpdate>,
+) -> Result<Json<GuestPortalProfile>, ApiError> {
+    let guest_id = own_guest_id(&pool, user_id).await?;
+    let update = sanitize_profile_update(input)?;
+
+    let (address_line1, address_line2, state) = ADDRESS_COLUMNS;
+    let fields = [
+        ("email", update.email),
+        ("phone", update.phone),
+        ("alt_phone", update.alt_phone),
+        (address_line1, update.address_line1),
+        (address_line2, update.address_line2),
+        ("city", update.city),
+        (state, update.state_province),
+        ("postal_code", update.postal_code),
+        ("country", update.country),
+    ];
+
+    let mut query_parts = vec![];
+    let mut values: Vec<Option<String>> = vec![];
+    for (column, value) in fields {
+        if let Some(value) = value {
+            query_parts.push(format!("{} = ${}", column, values.len() + 1));
+            values.push(Some(value).filter(|v| !v.is_empty()));
+        }
+    }
+
+    if !query_parts.is_empty() {
+        let query = format!(
+            "UPDATE guests SET {}, updated_at = CURRENT_TIMESTAMP WHERE id = ${}",
+            query_parts.join(", "),
+            values.len() + 1
+        );
+
+        let mut sqlx_query = sqlx::query(&query);
+        for value in values {
+            sqlx_query = sqlx_query.bind(value);
+        }
+        sqlx_query
+            .bind(guest_id)
+            .execute(&pool)
+            .await
+            .map_err(|e| ApiError::Database(format!("Failed to update guest: {}", e)))?;
+    }
+
+    Ok(Json(fetch_guest_profile(&pool, guest_id).await?))
+}
+
+/// GET /portal/communication-preferences
+/// Whether the signed-in user's guest receives email, SMS and marketing
+pub async fn get_own_communication_preferences(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+) -> Result<Json<CommunicationPreferences>, ApiError> {
+    let guest_id = own_guest_id(&pool, user_id).await?;
+    Ok(Json(
+        communication::guest_preferences(&pool, guest_id).await?,
+    ))
+}
+
+/// PUT /portal/communication-preferences
+/// Opts the signed-in user's guest in to or out of email, SMS and marketing
+pub async fn update_own_communication_preferences(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<CommunicationPreferencesUpdate>,
+) -> Result<Json<CommunicationPreferences>, ApiError> {
+    let guest_id = own_guest_id(&pool, user_id).await?;
+    Ok(Json(
+        communication::update_preferences(&pool, guest_id, input, Some(user_id)).await?,
+    ))
+}
+
+/// Sanitize and validate a review. Ratings must be between 1 and 5; text
+/// comes back trimmed, with blank fields dropped.
+pub fn validate_review(input: GuestReviewInput) -> Result<GuestReviewInput, ApiError> {
+    let ratings = [
+        ("overall_rating", Some(input.overall_rating)),
+        ("cleanliness_rating", input.cleanliness_rating),
+        ("service_rating", input.service_rating),
+        ("comfort_rating", input.comfort_rating),
+        ("location_rating", input.location_rating),
+        ("value_rating", input.value_rating),
+    ];
+    for (field, rating) in ratings {
+        if let Some(rating) = rating
+            && !(1.0..=5.0).contains(&rating)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "{} must be between 1 and 5",
+                field
+            )));
+        }
+    }
+    let text = |value: Option<String>, field: &str, max_len: usize| {
+        value
+            .map(|v| clean_field(&v, field, max_len))
+            .transpose()
+            .map(|v| v.filter(|v| !v.is_empty()))
+    };
+
+    Ok(GuestReviewInput {
+        title: text(input.title, "title", 255)?,
+        content: text(input.content, "content", 5000)?,
+        pros: text(input.pros, "pros", 2000)?,
+        cons: text(input.cons, "cons", 2000)?,
+        ..input
+    })
+}
+
+/// POST /portal/bookings/{id}/review
+/// Saves the signed-in user's guest's review of their stay and awards the
+/// review loyalty bonus in the same transaction
+pub async fn submit_own_review(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Path(booking_id): Path<i64>,
+    Json(input): Json<GuestReviewInput>,
+) -> Result<Json<GuestReviewSubmitted>, ApiError> {
+    let guest_id = own_guest_id(&pool, user_id).await?;
+    let review = validate_review(input)?;
+
+    let booking_guest: Option<i64> =
+        sqlx::query_scalar("SELECT guest_id FROM bookings WHERE id = $1")
+            .bind(booking_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| ApiError::Database(format!("Failed to fetch booking: {}", e)))?;
+    if booking_guest != Some(guest_id) {
+        return Err(ApiError::NotFound("Booking not found".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let review_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO guest_reviews
+            (guest_id, booking_id, overall_rating, cleanliness_rating, service_rating,
+             comfort_rating, location_rating, value_rating, title, content, pros, cons)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id
+        "#,
+    )
+    .bind(guest_id)
+    .bind(booking_id)
+    .bind(review.overall_rating)
+    .bind(review.cleanliness_rating)
+    .bind(review.service_rating)
+    .bind(review.comfort_rating)
+    .bind(review.location_rating)
+    .bind(review.value_rating)
+    .bind(&review.title)
+    .bind(&review.content)
+    .bind(&review.pros)
+    .bind(&review.cons)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(format!("Failed to save review: {}", e)))?;
+
+    let points_awarded =
+        review_points::award_review_points(&pool, &mut tx, guest_id, booking_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(GuestReviewSubmitted {
+        review_id,
+        booking_id,
+        points_awarded,
+    }))
+}
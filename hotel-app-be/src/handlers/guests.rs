@@ -4,12 +4,17 @@
 
 use crate::constants::{GuestType, TourismType};
 use crate::core::auth::AuthService;
-use crate::core::db::DbPool;
+use crate::core::db::{DbConnection, DbPool};
 use crate::core::error::ApiError;
 use crate::core::middleware::require_auth;
 use crate::models::*;
 use crate::services::audit::AuditLog;
+use crate::services::{
+    communication, guest_balance, guest_stays, hotel_time, password_policy, portal_guest,
+    pre_arrival,
+};
 use crate::utils::sanitization::Sanitizer;
+use crate::utils::sort::SortSpec;
 use axum::{
     extract::{Extension, Path, Query, State},
     http::HeaderMap,
@@ -18,6 +23,20 @@ use axum::{
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 
+/// Sortable columns for the guests list.
+pub const GUEST_SORT: SortSpec = SortSpec {
+    columns: &[
+        ("full_name", "full_name"),
+        ("email", "email"),
+        ("created_at", "created_at"),
+        ("bookings_count", "bookings_count"),
+        ("last_stay_date", "last_stay_date"),
+    ],
+    default_sort: "full_name",
+    default_descending: false,
+    tiebreaker: "id",
+};
+
 pub async fn get_guests_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -45,6 +64,7 @@ pub async fn get_guests_handler(
     let page = params.page.unwrap_or(1).max(1);
     let page_size = params.page_size.unwrap_or(100).min(500);
     let offset = (page - 1) * page_size;
+    let order_by = GUEST_SORT.order_by(params.sort_by.as_deref(), params.sort_order.as_deref())?;
 
     let search = params.search.as_deref().filter(|s| !s.trim().is_empty());
     let guest_type_filter = params
@@ -75,7 +95,8 @@ pub async fn get_guests_handler(
         guest_type, tourism_type,
         COALESCE(discount_percentage, 0) as discount_percentage, company_name,
         COALESCE(complimentary_nights_credit, 0) as complimentary_nights_credit,
-        created_at, updated_at,
+        created_at, updated_at, COALESCE(is_vip, false) AS is_vip,
+        email_opt_in, COALESCE(marketing_opt_in, false) AS marketing_opt_in, consent_timestamp,
         (SELECT COUNT(*) FROM bookings b
             WHERE b.guest_id = guests.id AND b.status != 'voided') AS bookings_count,
         (SELECT MAX(b.check_in_date) FROM bookings b
@@ -99,7 +120,7 @@ pub async fn get_guests_handler(
             "SELECT {select_cols} FROM guests \
              WHERE deleted_at IS NULL{type_clause} AND \
              (full_name {like_op} {p1} OR email {like_op} {p2} OR phone {like_op} {p3}) \
-             ORDER BY full_name LIMIT {p_limit} OFFSET {p_offset}"
+             ORDER BY {order_by} LIMIT {p_limit} OFFSET {p_offset}"
         );
 
         let total: i64 = sqlx::query_scalar(&count_sql)
@@ -123,7 +144,7 @@ pub async fn get_guests_handler(
         let data_sql = format!(
             "SELECT {select_cols} FROM guests \
              WHERE deleted_at IS NULL{type_clause} \
-             ORDER BY full_name \
+             ORDER BY {order_by} \
              LIMIT $1 OFFSET $2"
         );
 
@@ -150,6 +171,14 @@ pub async fn get_guests_handler(
     }))
 }
 
+/// Guest columns in the shape of [`Guest`], for statements that return a guest.
+const GUEST_RETURNING: &str = r#"id, full_name, email, phone, ic_number, nationality,
+    address_line_1 as address_line1, city, state as state_province, postal_code, country,
+    NULL::TEXT as title, NULL::TEXT as alt_phone, true as is_active,
+    guest_type, tourism_type, COALESCE(discount_percentage, 0) as discount_percentage,
+    company_name, COALESCE(complimentary_nights_credit, 0) as complimentary_nights_credit,
+    created_at, updated_at"#;
+
 pub async fn create_guest_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -157,6 +186,37 @@ pub async fn create_guest_handler(
 ) -> Result<Json<Guest>, ApiError> {
     let user_id = require_auth(&headers).await?;
 
+    let guest = {
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        insert_guest(&mut conn, &input, user_id).await?
+    };
+
+    // Log guest creation
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "guest_created",
+        "guest",
+        Some(guest.id),
+        Some(serde_json::json!({"name": &guest.full_name, "email": &guest.email})),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(guest))
+}
+
+/// Create a guest from `input` on `conn`, which may be a caller's
+/// transaction. Names are required and a guest's full name must be new.
+pub(crate) async fn insert_guest(
+    conn: &mut DbConnection,
+    input: &GuestInput,
+    user_id: i64,
+) -> Result<Guest, ApiError> {
     if input.first_name.trim().is_empty() {
         return Err(ApiError::BadRequest(
             "First name cannot be empty".to_string(),
@@ -202,7 +262,7 @@ pub async fn create_guest_handler(
         "SELECT id FROM guests WHERE LOWER(TRIM(full_name)) = LOWER($1) AND deleted_at IS NULL LIMIT 1"
     )
     .bind(&full_name)
-    .fetch_optional(&pool)
+    .fetch_optional(&mut *conn)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
@@ -213,21 +273,14 @@ pub async fn create_guest_handler(
         )));
     }
 
-    let guest = sqlx::query_as::<_, Guest>(
+    sqlx::query_as::<_, Guest>(&format!(
         r#"
         INSERT INTO guests (full_name, first_name, last_name, email, phone, ic_number, nationality, address_line_1, city, state, postal_code, country, guest_type, tourism_type, discount_percentage, company_name, created_by)
         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
-        RETURNING id, full_name, email, phone, ic_number, nationality, address_line_1 as address_line1, city, state as state_province, postal_code, country,
-                  NULL::TEXT as title, NULL::TEXT as alt_phone,
-                  true as is_active,
-                  guest_type,
-                  tourism_type,
-                  COALESCE(discount_percentage, 0) as discount_percentage,
-                  company_name,
-                  COALESCE(complimentary_nights_credit, 0) as complimentary_nights_credit,
-                  created_at, updated_at
-        "#
-    )
+        RETURNING {}
+        "#,
+        GUEST_RETURNING
+    ))
     .bind(&full_name)
     .bind(&first_name)
     .bind(&last_name)
@@ -245,24 +298,22 @@ pub async fn create_guest_handler(
     .bind(discount_percentage)
     .bind(input.company_name.as_deref().map(Sanitizer::sanitize_text))
     .bind(user_id)
-    .fetch_one(&pool)
+    .fetch_one(&mut *conn)
     .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    // Log guest creation
-    let _ = AuditLog::log_event(
-        &pool,
-        Some(user_id),
-        "guest_created",
-        "guest",
-        Some(guest.id),
-        Some(serde_json::json!({"name": &guest.full_name, "email": &guest.email})),
-        None,
-        None,
-    )
-    .await;
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
 
-    Ok(Json(guest))
+/// Fetch a live guest on `conn`, which may be a caller's transaction.
+pub(crate) async fn fetch_guest(conn: &mut DbConnection, guest_id: i64) -> Result<Guest, ApiError> {
+    sqlx::query_as::<_, Guest>(&format!(
+        "SELECT {} FROM guests WHERE id = $1 AND deleted_at IS NULL",
+        GUEST_RETURNING
+    ))
+    .bind(guest_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Guest not found".to_string()))
 }
 
 pub async fn update_guest_handler(
@@ -569,6 +620,93 @@ pub async fn get_guest_bookings_handler(
     Ok(Json(result))
 }
 
+/// Guests with a confirmed arrival in the next `within_days` days, one row per
+/// guest at their earliest arrival.
+pub async fn get_arriving_guests_handler(
+    State(pool): State<DbPool>,
+    Query(params): Query<ArrivingGuestsParams>,
+) -> Result<Json<Vec<ArrivingGuest>>, ApiError> {
+    let within_days = params
+        .within_days
+        .unwrap_or(pre_arrival::DEFAULT_WITHIN_DAYS);
+    let today = hotel_time::hotel_today(&pool).await?;
+    Ok(Json(
+        pre_arrival::arriving_guests(&pool, today, within_days).await?,
+    ))
+}
+
+/// Outstanding balance across the guest's bookings, for collections.
+pub async fn get_guest_balance_handler(
+    State(pool): State<DbPool>,
+    Path(guest_id): Path<i64>,
+) -> Result<Json<GuestBalance>, ApiError> {
+    Ok(Json(guest_balance::guest_balance(&pool, guest_id).await?))
+}
+
+/// The guest's email, SMS and marketing consent.
+pub async fn get_guest_communication_preferences_handler(
+    State(pool): State<DbPool>,
+    Path(guest_id): Path<i64>,
+) -> Result<Json<CommunicationPreferences>, ApiError> {
+    Ok(Json(
+        communication::guest_preferences(&pool, guest_id).await?,
+    ))
+}
+
+/// Record consent the guest gave or revoked through staff, e.g. at the
+/// front desk.
+pub async fn update_guest_communication_preferences_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Path(guest_id): Path<i64>,
+    Json(input): Json<CommunicationPreferencesUpdate>,
+) -> Result<Json<CommunicationPreferences>, ApiError> {
+    Ok(Json(
+        communication::update_preferences(&pool, guest_id, input, Some(user_id)).await?,
+    ))
+}
+
+/// Every consent the guest has given or revoked, newest first.
+pub async fn get_guest_consent_history_handler(
+    State(pool): State<DbPool>,
+    Path(guest_id): Path<i64>,
+) -> Result<Json<Vec<ConsentChange>>, ApiError> {
+    Ok(Json(communication::consent_history(&pool, guest_id).await?))
+}
+
+/// The guest's stays, newest first, with their reviews. Staff with
+/// `guests:read` see any guest; other users only guests linked to them.
+pub async fn get_guest_stays_handler(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Path(guest_id): Path<i64>,
+    Query(params): Query<GuestStaysParams>,
+) -> Result<Json<PaginatedResponse<Vec<GuestStay>>>, ApiError> {
+    let user_id = require_auth(&headers).await?;
+
+    let linked: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM user_guests WHERE user_id = $1 AND guest_id = $2")
+            .bind(user_id)
+            .bind(guest_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let has_guest_permission = AuthService::check_permission(&pool, user_id, "guests:read")
+        .await
+        .unwrap_or(false);
+
+    if linked == 0 && !has_guest_permission {
+        return Err(ApiError::Forbidden(
+            "You don't have access to this guest's stays".to_string(),
+        ));
+    }
+
+    Ok(Json(
+        guest_stays::guest_stays(&pool, guest_id, params.page, params.page_size).await?,
+    ))
+}
+
 pub async fn link_guest_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -647,6 +785,8 @@ pub async fn get_my_guests_handler(
     headers: HeaderMap,
 ) -> Result<Json<Vec<Guest>>, ApiError> {
     let user_id = require_auth(&headers).await?;
+    // A portal account's own guest is linked on first use
+    portal_guest::ensure_portal_guest(&pool, user_id).await?;
 
     let guests = sqlx::query_as::<_, Guest>(
         r#"
@@ -692,6 +832,8 @@ pub async fn upgrade_guest_to_user_handler(
         ));
     }
 
+    password_policy::validate_password(&pool, &input.password).await?;
+
     let password_hash = AuthService::hash_password(&input.password)
         .await
         .map_err(|_| ApiError::Internal("Password hashing failed".to_string()))?;
@@ -853,6 +995,8 @@ pub async fn get_my_guests_with_credits_handler(
     headers: HeaderMap,
 ) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
     let user_id = require_auth(&headers).await?;
+    // A portal account's own guest is linked on first use
+    portal_guest::ensure_portal_guest(&pool, user_id).await?;
 
     // Get all linked guests
     let guests: Vec<(i64, String, String, i32)> = sqlx::query_as(
@@ -20,6 +20,7 @@ use crate::models::row_mappers::{
     get_decimal, row_to_customer_ledger, row_to_customer_ledger_payment,
 };
 use crate::models::*;
+use crate::services::hotel_time;
 
 // Common SELECT fields for CustomerLedger.
 const LEDGER_SELECT_FIELDS: &str = r#"
@@ -355,7 +356,10 @@ pub async fn create_customer_ledger_handler(
             .flatten()
             .flatten()
             .unwrap_or(30);
-            let base = posting_date.unwrap_or_else(|| chrono::Local::now().date_naive());
+            let base = match posting_date {
+                Some(date) => date,
+                None => hotel_time::hotel_today(&pool).await?,
+            };
             Some(base + chrono::Duration::days(terms_days))
         }
     };
@@ -533,7 +537,10 @@ pub async fn create_customer_ledger_handler(
             .flatten()
             .flatten()
             .unwrap_or(30);
-            let base = posting_date.unwrap_or_else(|| chrono::Local::now().date_naive());
+            let base = match posting_date {
+                Some(date) => date,
+                None => hotel_time::hotel_today(&pool).await?,
+            };
             Some(base + chrono::Duration::days(terms_days as i64))
         }
     };
@@ -1695,7 +1702,7 @@ pub async fn create_ledger_reversal_handler(
         )
         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, 'paid', ?13,
                 ?15, ?16, ?31, ?17, ?18, ?19, ?19, ?19,
-                ?20, ?21, ?22, ?23, ?24, ?25, date('now'), date('now'),
+                ?20, ?21, ?22, ?23, ?24, ?25, ?32, ?32,
                 ?26, ?27, ?28, 1, ?29, ?30)
         "#,
     )
@@ -1730,6 +1737,7 @@ pub async fn create_ledger_reversal_handler(
     .bind(ledger_id)
     .bind(&request.reason)
     .bind(&invoice_number)
+    .bind(hotel_time::hotel_today(&pool).await?)
     .execute(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1802,7 +1810,7 @@ pub async fn create_ledger_reversal_handler(
         )
         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, 'paid', $13,
                 $15, $16, $31, $17, $18, $19, $19, $19,
-                $20, $21, $22, $23, $24, $25, CURRENT_DATE, CURRENT_DATE,
+                $20, $21, $22, $23, $24, $25, $32, $32,
                 $26, $27, $28, TRUE, $29, $30)
         RETURNING {}
         "#,
@@ -1841,6 +1849,7 @@ pub async fn create_ledger_reversal_handler(
         .bind(ledger_id)
         .bind(&request.reason)
         .bind(&invoice_number)
+        .bind(hotel_time::hotel_today(&pool).await?)
         .fetch_one(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
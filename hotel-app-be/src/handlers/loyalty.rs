@@ -6,10 +6,16 @@ use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::models::row_mappers;
 use crate::models::*;
+use crate::services::hotel_time;
 use crate::services::loyalty as svc;
+use crate::services::membership_expiry;
+use crate::utils::pagination::ListPage;
+use crate::utils::sort::SortSpec;
 use axum::{
+    body::Body,
     extract::{Extension, Path, Query, State},
-    response::Json,
+    http::StatusCode,
+    response::{Json, Response},
 };
 use sqlx::Row;
 use std::collections::HashMap;
@@ -88,10 +94,34 @@ pub async fn get_loyalty_programs_handler(
     Ok(Json(programs))
 }
 
+/// Sortable columns for the memberships list.
+pub const MEMBERSHIP_SORT: SortSpec = SortSpec {
+    columns: &[
+        ("guest_name", "g.full_name"),
+        ("membership_number", "lm.membership_number"),
+        ("points_balance", "lm.points_balance"),
+        ("lifetime_points", "lm.lifetime_points"),
+        ("tier_level", "lm.tier_level"),
+        ("enrolled_date", "lm.enrolled_date"),
+    ],
+    default_sort: "lifetime_points",
+    default_descending: true,
+    tiebreaker: "lm.id",
+};
+
 pub async fn get_loyalty_memberships_handler(
     State(pool): State<DbPool>,
-) -> Result<Json<Vec<LoyaltyMembershipWithDetails>>, ApiError> {
-    let memberships = sqlx::query_as::<_, LoyaltyMembershipWithDetails>(
+    Query(params): Query<ListParams>,
+) -> Result<Json<PaginatedResponse<Vec<LoyaltyMembershipWithDetails>>>, ApiError> {
+    let page = ListPage::resolve(&params, &MEMBERSHIP_SORT)?;
+
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM loyalty_memberships WHERE status = 'active'")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let memberships = sqlx::query_as::<_, LoyaltyMembershipWithDetails>(&format!(
         r#"
         SELECT
             lm.id,
@@ -112,14 +142,23 @@ pub async fn get_loyalty_memberships_handler(
         JOIN guests g ON lm.guest_id = g.id
         JOIN loyalty_programs lp ON lm.program_id = lp.id
         WHERE lm.status = 'active'
-        ORDER BY lm.lifetime_points DESC
+        ORDER BY {}
+        LIMIT $1 OFFSET $2
         "#,
-    )
+        page.order_by
+    ))
+    .bind(page.per_page)
+    .bind(page.offset())
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    Ok(Json(memberships))
+    Ok(Json(PaginatedResponse {
+        data: memberships,
+        total,
+        page: page.page,
+        page_size: page.per_page,
+    }))
 }
 
 pub async fn get_loyalty_statistics_handler(
@@ -218,6 +257,7 @@ pub async fn get_loyalty_statistics_handler(
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
     // Get membership growth (last 30 days)
+    let today = hotel_time::hotel_today(&pool).await?;
     let membership_growth = sqlx::query_as::<_, MembershipGrowth>(
         r#"
         SELECT
@@ -226,8 +266,8 @@ pub async fn get_loyalty_statistics_handler(
             (SELECT COUNT(*)::bigint FROM loyalty_memberships
              WHERE enrolled_date <= date_series) as total_members
         FROM generate_series(
-            CURRENT_DATE - INTERVAL '30 days',
-            CURRENT_DATE,
+            $1::date - INTERVAL '30 days',
+            $1::date,
             INTERVAL '1 day'
         ) AS date_series
         LEFT JOIN loyalty_memberships lm ON lm.enrolled_date = date_series::date
@@ -235,6 +275,7 @@ pub async fn get_loyalty_statistics_handler(
         ORDER BY date_series
         "#,
     )
+    .bind(today)
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -247,8 +288,8 @@ pub async fn get_loyalty_statistics_handler(
             COALESCE(SUM(CASE WHEN transaction_type = 'earn' THEN points_amount ELSE 0 END), 0)::bigint as points_earned,
             COALESCE(SUM(CASE WHEN transaction_type = 'redeem' THEN ABS(points_amount) ELSE 0 END), 0)::bigint as points_redeemed
         FROM generate_series(
-            CURRENT_DATE - INTERVAL '30 days',
-            CURRENT_DATE,
+            $1::date - INTERVAL '30 days',
+            $1::date,
             INTERVAL '1 day'
         ) AS date_series
         LEFT JOIN points_transactions pt ON DATE(pt.created_at) = date_series::date
@@ -256,6 +297,7 @@ pub async fn get_loyalty_statistics_handler(
         ORDER BY date_series
         "#
     )
+    .bind(today)
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -277,35 +319,122 @@ pub async fn get_loyalty_statistics_handler(
     Ok(Json(statistics))
 }
 
+/// Download the loyalty statistics as JSON or, with `format=csv`, as CSV
+/// sections for members by tier, top members and points activity.
+pub async fn export_loyalty_statistics_handler(
+    State(pool): State<DbPool>,
+    Query(params): Query<LoyaltyStatisticsExportParams>,
+) -> Result<Response, ApiError> {
+    let format = svc::parse_export_format(params.format.as_deref())?;
+    let Json(statistics) = get_loyalty_statistics_handler(State(pool)).await?;
+
+    let stamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let (content_type, filename, body) = match format {
+        svc::StatisticsExportFormat::Json => (
+            "application/json",
+            format!("loyalty_statistics_{}.json", stamp),
+            serde_json::to_string_pretty(&statistics)
+                .map_err(|e| ApiError::Internal(e.to_string()))?,
+        ),
+        svc::StatisticsExportFormat::Csv => (
+            "text/csv; charset=utf-8",
+            format!("loyalty_statistics_{}.csv", stamp),
+            svc::statistics_csv(&statistics),
+        ),
+    };
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from(body))
+        .unwrap())
+}
+
 pub async fn add_points_handler(
     State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
     Path(membership_id): Path<i64>,
     Json(input): Json<AddPointsInput>,
 ) -> Result<Json<PointsTransaction>, ApiError> {
-    if input.points <= 0 {
-        return Err(ApiError::BadRequest("Points must be positive".to_string()));
-    }
-    let transaction =
-        svc::adjust_membership_points(&pool, membership_id, input.points, true, input.description)
-            .await?;
+    let transaction = svc::adjust_membership_points(
+        &pool,
+        membership_id,
+        input.points,
+        true,
+        input.description,
+        user_id,
+    )
+    .await?;
     Ok(Json(transaction))
 }
 
 pub async fn redeem_points_handler(
     State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
     Path(membership_id): Path<i64>,
     Json(input): Json<AddPointsInput>,
 ) -> Result<Json<PointsTransaction>, ApiError> {
-    if input.points <= 0 {
-        return Err(ApiError::BadRequest("Points must be positive".to_string()));
-    }
-    let transaction =
-        svc::adjust_membership_points(&pool, membership_id, input.points, false, input.description)
-            .await?;
+    let transaction = svc::adjust_membership_points(
+        &pool,
+        membership_id,
+        input.points,
+        false,
+        input.description,
+        user_id,
+    )
+    .await?;
     Ok(Json(transaction))
 }
 
-// Get user's own loyalty membership with full details
+/// Recompute a membership's balances from its transaction history
+pub async fn recompute_points_handler(
+    State(pool): State<DbPool>,
+    Extension(admin_id): Extension<i64>,
+    Path(membership_id): Path<i64>,
+) -> Result<Json<PointsRecomputeResult>, ApiError> {
+    let result = svc::recompute_membership_points(&pool, admin_id, membership_id).await?;
+    Ok(Json(result))
+}
+
+// Renew an active or expired membership (staff)
+pub async fn renew_membership_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Path(membership_id): Path<i64>,
+    Json(input): Json<Option<RenewMembershipInput>>,
+) -> Result<Json<MembershipRenewal>, ApiError> {
+    let input = input.unwrap_or_default();
+    let renewal =
+        membership_expiry::renew_membership(&pool, user_id, membership_id, input.months).await?;
+    Ok(Json(renewal))
+}
+
+// Move points between memberships (staff)
+pub async fn transfer_points_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<TransferPointsInput>,
+) -> Result<Json<PointsTransferResult>, ApiError> {
+    let result = svc::transfer_points(&pool, user_id, &input).await?;
+    Ok(Json(result))
+}
+
+// Add points to every active membership matching a filter (staff)
+pub async fn bulk_points_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<BulkPointsInput>,
+) -> Result<Json<BulkPointsResult>, ApiError> {
+    let result = svc::bulk_adjust_points(&pool, user_id, &input).await?;
+    Ok(Json(result))
+}
+
+// Get user's own loyalty membership with full details. Without an active
+// membership, an expired one is returned with status "expired".
 pub async fn get_user_loyalty_membership_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
@@ -314,7 +443,12 @@ pub async fn get_user_loyalty_membership_handler(
 
     // Get the membership
     let membership = sqlx::query_as::<_, LoyaltyMembership>(
-        "SELECT * FROM loyalty_memberships WHERE guest_id = $1 AND status = 'active'",
+        r#"
+        SELECT * FROM loyalty_memberships
+        WHERE guest_id = $1 AND status IN ('active', 'expired')
+        ORDER BY status = 'active' DESC, id DESC
+        LIMIT 1
+        "#,
     )
     .bind(guest_id)
     .fetch_optional(&pool)
@@ -339,14 +473,8 @@ pub async fn get_user_loyalty_membership_handler(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Get benefits from current program (benefits is a JSONB array)
-    let benefits: Vec<String> = sqlx::query_scalar(
-        "SELECT jsonb_array_elements_text(benefits) FROM loyalty_programs WHERE id = $1",
-    )
-    .bind(membership.program_id)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+    // Benefits of the current tier (benefits is a JSONB array)
+    let benefits = svc::program_benefits(&pool, membership.program_id).await?;
 
     // Get recent transactions
     let recent_transactions = sqlx::query_as::<_, PointsTransaction>(
@@ -357,21 +485,24 @@ pub async fn get_user_loyalty_membership_handler(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Build next tier info
-    let next_tier_info = next_tier.map(|tier| {
-        let tier_benefits: Vec<String> = vec![];
-        TierInfo {
+    // Build next tier info, with what the guest unlocks there
+    let next_tier_info = match next_tier {
+        Some(tier) => Some(TierInfo {
+            benefits: svc::program_benefits(&pool, tier.id).await?,
             tier_level: tier.tier_level,
             tier_name: tier.name,
             minimum_points: tier.minimum_points_required,
-            benefits: tier_benefits,
             points_multiplier: tier.points_multiplier,
-        }
-    });
+        }),
+        None => None,
+    };
 
-    let points_to_next_tier = next_tier_info
-        .as_ref()
-        .map(|tier| (tier.minimum_points - membership.lifetime_points).max(0));
+    // Progress within the current tier; the top tier has no next tier
+    let progress = svc::tier_progress(
+        membership.lifetime_points,
+        current_program.minimum_points_required,
+        next_tier_info.as_ref().map(|tier| tier.minimum_points),
+    );
 
     Ok(Json(UserLoyaltyMembership {
         id: membership.id,
@@ -385,11 +516,25 @@ pub async fn get_user_loyalty_membership_handler(
         expiry_date: membership.expiry_date,
         next_tier: next_tier_info,
         current_tier_benefits: benefits,
-        points_to_next_tier,
+        points_to_next_tier: progress.points_to_next_tier,
+        current_tier_minimum: current_program.minimum_points_required,
+        progress_percent: progress.progress_percent,
+        is_top_tier: progress.points_to_next_tier.is_none(),
         recent_transactions,
     }))
 }
 
+// Get the user's own points history, optionally filtered by type
+pub async fn get_user_points_transactions_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Query(params): Query<PointsTransactionsParams>,
+) -> Result<Json<PaginatedResponse<Vec<PointsTransaction>>>, ApiError> {
+    Ok(Json(
+        svc::user_points_transactions(&pool, user_id, &params).await?,
+    ))
+}
+
 // Get available loyalty rewards filtered by user's tier
 pub async fn get_loyalty_rewards_handler(
     State(pool): State<DbPool>,
@@ -608,22 +753,7 @@ pub async fn create_reward_handler(
     State(pool): State<DbPool>,
     Json(input): Json<RewardInput>,
 ) -> Result<Json<LoyaltyReward>, ApiError> {
-    // Validate category
-    let valid_categories = [
-        "room_upgrade",
-        "service",
-        "discount",
-        "gift",
-        "dining",
-        "spa",
-        "experience",
-    ];
-    if !valid_categories.contains(&input.category.as_str()) {
-        return Err(ApiError::BadRequest(format!(
-            "Invalid category. Must be one of: {}",
-            valid_categories.join(", ")
-        )));
-    }
+    let category: RewardCategory = input.category.parse().map_err(ApiError::BadRequest)?;
 
     // Validate tier level (1-4)
     if input.minimum_tier_level < 1 || input.minimum_tier_level > 4 {
@@ -654,7 +784,7 @@ pub async fn create_reward_handler(
     )
     .bind(&input.name)
     .bind(&input.description)
-    .bind(&input.category)
+    .bind(category)
     .bind(input.points_cost)
     .bind(monetary_value)
     .bind(input.minimum_tier_level)
@@ -683,25 +813,17 @@ pub async fn update_reward_handler(
             .map_err(|e| ApiError::Database(e.to_string()))?
             .ok_or_else(|| ApiError::NotFound("Reward not found".to_string()))?;
 
-    // Validate category if provided
-    if let Some(ref category) = input.category {
-        let valid_categories = [
-            "room_upgrade",
-            "service",
-            "discount",
-            "gift",
-            "dining",
-            "spa",
-            "experience",
-        ];
-        if !valid_categories.contains(&category.as_str()) {
-            return Err(ApiError::BadRequest(format!(
-                "Invalid category. Must be one of: {}",
-                valid_categories.join(", ")
-            )));
-        }
+    if input.is_empty() {
+        return Ok(Json(existing));
     }
 
+    let category: Option<RewardCategory> = input
+        .category
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+
     // Validate tier level if provided
     if let Some(tier_level) = input.minimum_tier_level
         && (!(1..=4).contains(&tier_level))
@@ -723,7 +845,7 @@ pub async fn update_reward_handler(
     // Use provided values or keep existing ones
     let name = input.name.as_ref().unwrap_or(&existing.name);
     let description = input.description.as_ref().or(existing.description.as_ref());
-    let category = input.category.as_ref().unwrap_or(&existing.category);
+    let category = category.map_or(existing.category.clone(), |c| c.to_string());
     let points_cost = input.points_cost.unwrap_or(existing.points_cost);
     let monetary_value = if input.monetary_value.is_some() {
         input
@@ -767,7 +889,7 @@ pub async fn update_reward_handler(
     )
     .bind(name)
     .bind(description)
-    .bind(category)
+    .bind(&category)
     .bind(points_cost)
     .bind(monetary_value)
     .bind(minimum_tier_level)
@@ -806,11 +928,159 @@ pub async fn delete_reward_handler(
     })))
 }
 
+/// Every points-earning rule, active or not (admin only).
+pub async fn get_points_rules_handler(
+    State(pool): State<DbPool>,
+) -> Result<Json<Vec<PointsRule>>, ApiError> {
+    let rules = sqlx::query_as::<_, PointsRule>("SELECT * FROM points_rules ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(rules))
+}
+
+/// Create a points-earning rule (admin only).
+pub async fn create_points_rule_handler(
+    State(pool): State<DbPool>,
+    Json(input): Json<PointsRuleInput>,
+) -> Result<Json<PointsRule>, ApiError> {
+    let rule_type = svc::validate_points_rule(&input)?;
+    // Each rule type keeps only the value it uses.
+    let is_multiplier = rule_type == PointsRuleType::Multiplier;
+    let bonus_points = input.bonus_points.filter(|_| !is_multiplier);
+    let multiplier = input
+        .multiplier
+        .filter(|_| is_multiplier)
+        .map(|v| rust_decimal::Decimal::from_f64_retain(v).unwrap_or_default());
+
+    let rule = sqlx::query_as::<_, PointsRule>(
+        r#"
+        INSERT INTO points_rules
+        (name, rule_type, room_type_id, bonus_points, multiplier, starts_on, ends_on, is_active)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(input.name.trim())
+    .bind(rule_type)
+    .bind(input.room_type_id)
+    .bind(bonus_points)
+    .bind(multiplier)
+    .bind(input.starts_on)
+    .bind(input.ends_on)
+    .bind(input.is_active.unwrap_or(true))
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(rule))
+}
+
+/// Replace a points-earning rule (admin only).
+pub async fn update_points_rule_handler(
+    State(pool): State<DbPool>,
+    Path(rule_id): Path<i64>,
+    Json(input): Json<PointsRuleInput>,
+) -> Result<Json<PointsRule>, ApiError> {
+    let rule_type = svc::validate_points_rule(&input)?;
+    // Each rule type keeps only the value it uses.
+    let is_multiplier = rule_type == PointsRuleType::Multiplier;
+    let bonus_points = input.bonus_points.filter(|_| !is_multiplier);
+    let multiplier = input
+        .multiplier
+        .filter(|_| is_multiplier)
+        .map(|v| rust_decimal::Decimal::from_f64_retain(v).unwrap_or_default());
+
+    let rule = sqlx::query_as::<_, PointsRule>(
+        r#"
+        UPDATE points_rules
+        SET name = $1,
+            rule_type = $2,
+            room_type_id = $3,
+            bonus_points = $4,
+            multiplier = $5,
+            starts_on = $6,
+            ends_on = $7,
+            is_active = $8,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $9
+        RETURNING *
+        "#,
+    )
+    .bind(input.name.trim())
+    .bind(rule_type)
+    .bind(input.room_type_id)
+    .bind(bonus_points)
+    .bind(multiplier)
+    .bind(input.starts_on)
+    .bind(input.ends_on)
+    .bind(input.is_active.unwrap_or(true))
+    .bind(rule_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Points rule not found".to_string()))?;
+
+    Ok(Json(rule))
+}
+
+/// Delete a points-earning rule (admin only). Points already awarded stand.
+pub async fn delete_points_rule_handler(
+    State(pool): State<DbPool>,
+    Path(rule_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let result = sqlx::query("DELETE FROM points_rules WHERE id = $1")
+        .bind(rule_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Points rule not found".to_string()));
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "Points rule deleted successfully"
+    })))
+}
+
+/// Sortable columns for the redemption history.
+pub const REDEMPTION_SORT: SortSpec = SortSpec {
+    columns: &[
+        ("created_at", "rr.created_at"),
+        ("redeemed_at", "rr.redeemed_at"),
+        ("points_spent", "rr.points_spent"),
+        ("status", "rr.status"),
+        ("guest_name", "g.full_name"),
+        ("reward_name", "lr.name"),
+    ],
+    default_sort: "created_at",
+    default_descending: true,
+    tiebreaker: "rr.id",
+};
+
 // Get reward redemption history (admin only)
 pub async fn get_reward_redemptions_handler(
     State(pool): State<DbPool>,
-) -> Result<Json<Vec<RewardRedemptionWithDetails>>, ApiError> {
-    let redemptions = sqlx::query_as::<_, RewardRedemptionWithDetails>(
+    Query(params): Query<ListParams>,
+) -> Result<Json<PaginatedResponse<Vec<RewardRedemptionWithDetails>>>, ApiError> {
+    let page = ListPage::resolve(&params, &REDEMPTION_SORT)?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM reward_redemptions rr
+        INNER JOIN loyalty_memberships lm ON rr.membership_id = lm.id
+        INNER JOIN guests g ON lm.guest_id = g.id
+        INNER JOIN loyalty_rewards lr ON rr.reward_id = lr.id
+        "#,
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let redemptions = sqlx::query_as::<_, RewardRedemptionWithDetails>(&format!(
         r#"
         SELECT
             rr.id,
@@ -830,14 +1100,23 @@ pub async fn get_reward_redemptions_handler(
         INNER JOIN loyalty_memberships lm ON rr.membership_id = lm.id
         INNER JOIN guests g ON lm.guest_id = g.id
         INNER JOIN loyalty_rewards lr ON rr.reward_id = lr.id
-        ORDER BY rr.created_at DESC
+        ORDER BY {}
+        LIMIT $1 OFFSET $2
         "#,
-    )
+        page.order_by
+    ))
+    .bind(page.per_page)
+    .bind(page.offset())
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    Ok(Json(redemptions))
+    Ok(Json(PaginatedResponse {
+        data: redemptions,
+        total,
+        page: page.page,
+        page_size: page.per_page,
+    }))
 }
 
 // Redeem reward for user (user-facing endpoint with path parameter)
@@ -4,6 +4,7 @@
 //! Handlers are organized by feature area for better maintainability.
 
 pub mod analytics;
+pub mod api_keys;
 #[allow(dead_code)]
 pub mod audit;
 pub mod auth;
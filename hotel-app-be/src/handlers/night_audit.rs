@@ -18,7 +18,18 @@ use crate::models::{
     RunNightAuditRequest, UnpostedBooking,
 };
 use crate::services::audit::AuditLog;
+use crate::services::booking_archive;
+use crate::services::booking_fees;
+use crate::services::deposit_forfeiture;
+use crate::services::hotel_time;
+use crate::services::housekeeping;
 use crate::services::night_audit as svc;
+use crate::services::occupancy_alerts;
+use crate::services::room_assignment;
+use crate::services::room_status;
+use crate::services::tier_review;
+use crate::services::upload_cleanup;
+use crate::services::vip;
 use std::collections::HashMap;
 
 /// Get preview of what will be posted for a given date
@@ -37,12 +48,12 @@ pub async fn get_night_audit_preview(
         }
     };
 
-    let audit_date_str = params
-        .get("date")
-        .ok_or_else(|| ApiError::BadRequest("Date is required".to_string()))?;
-
-    let audit_date = NaiveDate::parse_from_str(audit_date_str, "%Y-%m-%d")
-        .map_err(|_| ApiError::BadRequest("Invalid date. Use YYYY-MM-DD".to_string()))?;
+    // Without a date, preview the hotel's current business day.
+    let audit_date = match params.get("date") {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::BadRequest("Invalid date. Use YYYY-MM-DD".to_string()))?,
+        None => hotel_time::hotel_today(&pool).await?,
+    };
 
     log::info!("Checking if audit already run for date: {}", audit_date);
 
@@ -260,6 +271,15 @@ pub async fn run_night_audit(
         ))
     })?;
 
+    // A night can only be audited once it has started at the hotel.
+    let hotel_today = hotel_time::hotel_today(&pool).await?;
+    if audit_date > hotel_today {
+        return Err(ApiError::BadRequest(format!(
+            "Cannot run the night audit for {}; it is still {} at the hotel",
+            audit_date, hotel_today
+        )));
+    }
+
     let already_run = svc::is_audit_completed(&pool, audit_date).await;
     log::info!(
         "Checking if audit already run for {}: {}",
@@ -299,6 +319,141 @@ pub async fn run_night_audit(
         Err(e) => log::warn!("Night audit invoice backfill failed: {}", e),
     }
 
+    // Forfeit deposits held on no-shows to revenue.
+    if deposit_forfeiture::forfeiture_enabled(&pool).await {
+        match deposit_forfeiture::forfeit_no_show_deposits(&pool, audit_date, user_id).await {
+            Ok(forfeited) if forfeited.is_empty() => {}
+            Ok(forfeited) => log::info!(
+                "Night audit forfeited deposits on {} no-show booking(s)",
+                forfeited.len()
+            ),
+            Err(e) => log::warn!("Night audit deposit forfeiture failed: {}", e),
+        }
+    }
+
+    // Charge the no-show fee.
+    match booking_fees::charge_no_show_fees(&pool, audit_date, user_id).await {
+        Ok(charged) if charged.is_empty() => {}
+        Ok(charged) => log::info!(
+            "Night audit charged no-show fees on {} booking(s)",
+            charged.len()
+        ),
+        Err(e) => log::warn!("Night audit no-show fees failed: {}", e),
+    }
+
+    // Pre-assign rooms to tomorrow's type-level arrivals.
+    if room_assignment::auto_assign_enabled(&pool).await
+        && let Some(next_day) = audit_date.succ_opt()
+    {
+        match room_assignment::pre_assign_arrivals(&pool, next_day).await {
+            Ok(0) => {}
+            Ok(n) => log::info!("Night audit pre-assigned rooms for {} arrival(s)", n),
+            Err(e) => log::warn!("Night audit room pre-assignment failed: {}", e),
+        }
+    }
+
+    // Release rooms whose cleaning window has ended.
+    if housekeeping::auto_release_enabled(&pool).await {
+        match housekeeping::release_cleaned_rooms(&pool, chrono::Utc::now()).await {
+            Ok(released) if released.is_empty() => {}
+            Ok(released) => log::info!("Night audit released {} cleaned room(s)", released.len()),
+            Err(e) => log::warn!("Night audit cleaned-room release failed: {}", e),
+        }
+    }
+
+    // Correct stored room statuses that drifted from the rooms' bookings.
+    match room_status::reconcile_room_statuses(&pool, None, None).await {
+        Ok(corrected) if corrected.is_empty() => {}
+        Ok(corrected) => log::info!(
+            "Night audit reconciled the status of {} room(s)",
+            corrected.len()
+        ),
+        Err(e) => log::warn!("Night audit room status reconciliation failed: {}", e),
+    }
+
+    // Expire lapsed loyalty memberships; the loyalty tables exist only in
+    // the PostgreSQL schema.
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    match crate::services::membership_expiry::expire_memberships(&pool, chrono::Utc::now()).await {
+        Ok(expired) if expired.is_empty() => {}
+        Ok(expired) => log::info!(
+            "Night audit expired {} loyalty membership(s), {} downgraded",
+            expired.len(),
+            expired
+                .iter()
+                .filter(|m| m.to_tier_id != m.from_tier_id)
+                .count()
+        ),
+        Err(e) => log::warn!("Night audit membership expiry failed: {}", e),
+    }
+
+    // Periodic loyalty tier review, when demotion is enabled and one is due.
+    if tier_review::demotion_enabled(&pool).await
+        && tier_review::review_due(&pool, chrono::Utc::now())
+            .await
+            .unwrap_or(false)
+    {
+        let window_months = tier_review::configured_window_months(&pool).await;
+        match tier_review::review_tiers(&pool, window_months).await {
+            Ok(summary) => log::info!(
+                "Night audit tier review {}: {} member(s) reviewed, {} downgraded, {} in grace",
+                summary.review_id,
+                summary.reviewed,
+                summary.downgraded.len(),
+                summary.in_grace
+            ),
+            Err(e) => log::warn!("Night audit tier review failed: {}", e),
+        }
+    }
+
+    // Alert on upcoming nights whose forecast occupancy crossed a threshold.
+    if occupancy_alerts::alerts_enabled(&pool).await
+        && let Some(next_day) = audit_date.succ_opt()
+    {
+        let thresholds = occupancy_alerts::configured_thresholds(&pool).await;
+        let days = occupancy_alerts::configured_horizon_days(&pool).await;
+        match occupancy_alerts::check_occupancy_alerts(&pool, next_day, days, &thresholds).await {
+            Ok(changes) => {
+                let alerts = changes.iter().filter(|c| c.is_alert()).count();
+                if alerts > 0 {
+                    log::info!("Night audit raised {} occupancy alert(s)", alerts);
+                }
+            }
+            Err(e) => log::warn!("Night audit occupancy alerts failed: {}", e),
+        }
+    }
+
+    // Move long-past stays out of the live bookings table.
+    if let Some(days) = booking_archive::configured_archive_days(&pool).await {
+        match booking_archive::archive_old_bookings(&pool, days).await {
+            Ok(0) => {}
+            Ok(n) => log::info!("Night audit archived {} booking(s)", n),
+            Err(e) => log::warn!("Night audit booking archive failed: {}", e),
+        }
+    }
+
+    // Remove uploaded files no record refers to any more.
+    if upload_cleanup::cleanup_enabled(&pool).await {
+        match upload_cleanup::cleanup_orphaned_uploads(&pool).await {
+            Ok(summary) if summary.removed.is_empty() => {}
+            Ok(summary) => log::info!(
+                "Night audit removed {} orphaned upload(s)",
+                summary.removed.len()
+            ),
+            Err(e) => log::warn!("Night audit upload cleanup failed: {}", e),
+        }
+    }
+
+    // Flag guests whose stay history makes them VIP.
+    if vip::auto_flag_enabled(&pool).await {
+        let config = vip::configured(&pool).await;
+        match vip::flag_vip_guests(&pool, &config).await {
+            Ok(flagged) if flagged.is_empty() => {}
+            Ok(flagged) => log::info!("Night audit flagged {} guest(s) as VIP", flagged.len()),
+            Err(e) => log::warn!("Night audit VIP flagging failed: {}", e),
+        }
+    }
+
     if let Some(notes) = &input.notes {
         let _ = sqlx::query("UPDATE night_audit_runs SET notes = $1 WHERE id = $2")
             .bind(notes)
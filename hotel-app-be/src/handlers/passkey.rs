@@ -3,16 +3,23 @@
 //! Handles passkey registration and authentication.
 
 use crate::core::auth::AuthService;
+use crate::core::config;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
+use crate::core::password_change;
+use crate::core::webauthn::{self, Assertion};
 use crate::models::*;
+use crate::services::login_history;
+use crate::services::sessions;
+use crate::utils::user_agent;
 use axum::{
     extract::{Extension, Path, State},
+    http::{HeaderMap, header},
     response::Json,
 };
 use base64::Engine;
 use base64::engine::general_purpose;
-use std::env;
+use std::net::IpAddr;
 
 // Helper function to decode base64url (WebAuthn format)
 fn decode_base64url(input: &str) -> Result<Vec<u8>, String> {
@@ -32,6 +39,26 @@ fn decode_base64url(input: &str) -> Result<Vec<u8>, String> {
         .map_err(|e| format!("Base64 decode error: {}", e))
 }
 
+// Binary fields of a serialized credential arrive as byte arrays or base64url strings
+fn credential_bytes(value: &serde_json::Value) -> Option<Vec<u8>> {
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect(),
+        serde_json::Value::String(s) => decode_base64url(s).ok(),
+        _ => None,
+    }
+}
+
+// Assertion fields are sent as standard base64; accept base64url as well
+fn decode_assertion_field(input: &str, name: &str) -> Result<Vec<u8>, ApiError> {
+    general_purpose::STANDARD
+        .decode(input)
+        .or_else(|_| decode_base64url(input))
+        .map_err(|_| ApiError::BadRequest(format!("Invalid {} encoding", name)))
+}
+
 pub async fn list_passkeys_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
@@ -101,7 +128,7 @@ pub async fn delete_passkey_handler(
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
     if result.rows_affected() == 0 {
-        return Err(ApiError::NotFound("Passkey not found".to_string()));
+        return Err(passkey_unchanged_error(&pool, passkey_id).await);
     }
 
     Ok(Json(
@@ -124,7 +151,7 @@ pub async fn update_passkey_handler(
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
     if result.rows_affected() == 0 {
-        return Err(ApiError::NotFound("Passkey not found".to_string()));
+        return Err(passkey_unchanged_error(&pool, passkey_id).await);
     }
 
     Ok(Json(
@@ -132,6 +159,20 @@ pub async fn update_passkey_handler(
     ))
 }
 
+/// Why a change scoped to the user's own passkeys matched nothing: the
+/// passkey belongs to another user, or it does not exist.
+async fn passkey_unchanged_error(pool: &DbPool, passkey_id: uuid::Uuid) -> ApiError {
+    let exists = sqlx::query("SELECT 1 FROM passkeys WHERE id = $1")
+        .bind(passkey_id)
+        .fetch_optional(pool)
+        .await;
+    match exists {
+        Ok(Some(_)) => ApiError::Forbidden("Passkey belongs to another user".to_string()),
+        Ok(None) => ApiError::NotFound("Passkey not found".to_string()),
+        Err(e) => ApiError::Database(e.to_string()),
+    }
+}
+
 pub async fn passkey_register_start_handler(
     State(pool): State<DbPool>,
     Json(req): Json<PasskeyRegistrationStart>,
@@ -184,7 +225,7 @@ pub async fn passkey_register_start_handler(
         "challenge": challenge_b64,
         "rp": {
             "name": "Hotel Management System",
-            "id": env::var("PASSKEY_RP_ID").unwrap_or_else(|_| "localhost".to_string()),
+            "id": config::get().passkey_rp_id,
         },
         "user": {
             "id": general_purpose::STANDARD.encode(user.id.to_string()),
@@ -196,6 +237,7 @@ pub async fn passkey_register_start_handler(
 
 pub async fn passkey_register_finish_handler(
     State(pool): State<DbPool>,
+    headers: HeaderMap,
     Json(req): Json<PasskeyRegistrationFinish>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Get user
@@ -236,24 +278,42 @@ pub async fn passkey_register_finish_handler(
     let credential_id_bytes = decode_base64url(credential_id_str)
         .map_err(|e| ApiError::BadRequest(format!("Invalid credential ID format: {}", e)))?;
 
-    // Extract public key (simplified)
-    let public_key = vec![0u8; 64]; // In production, parse from attestationObject
+    // Extract the public key and initial counter from the attestation object
+    let attestation_object = credential_bytes(&credential["response"]["attestationObject"])
+        .ok_or_else(|| ApiError::BadRequest("Missing attestation object".to_string()))?;
+    let auth_data = webauthn::parse_attestation_object(&attestation_object)?;
+    let attested = auth_data
+        .attested_credential
+        .ok_or_else(|| ApiError::BadRequest("Attestation has no credential".to_string()))?;
+    if attested.credential_id != credential_id_bytes {
+        return Err(ApiError::BadRequest(
+            "Credential ID does not match attestation".to_string(),
+        ));
+    }
 
-    // Store passkey
+    // Store passkey, named after the browser and OS unless the client named it
     let device_name = req
         .device_name
         .clone()
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| {
+            headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .and_then(user_agent::device_name)
+        })
         .unwrap_or_else(|| format!("Passkey {}", chrono::Utc::now().format("%Y-%m-%d")));
 
     sqlx::query(
         r#"
         INSERT INTO passkeys (user_id, credential_id, public_key, counter, device_name)
-        VALUES ($1, $2, $3, 0, $4)
+        VALUES ($1, $2, $3, $4, $5)
         "#,
     )
     .bind(user.id)
     .bind(&credential_id_bytes[..])
-    .bind(&public_key)
+    .bind(&attested.public_key)
+    .bind(auth_data.sign_count as i64)
     .bind(device_name)
     .execute(&pool)
     .await
@@ -348,7 +408,30 @@ pub async fn passkey_login_start_handler(
     })))
 }
 
+/// Log in with a passkey, recording the attempt in the user's login
+/// history.
 pub async fn passkey_login_finish_handler(
+    State(pool): State<DbPool>,
+    client_ip: IpAddr,
+    user_agent: Option<String>,
+    Json(req): Json<PasskeyLoginFinish>,
+) -> Result<Json<AuthResponse>, ApiError> {
+    let username = req.username.clone();
+    let result = passkey_login(State(pool.clone()), Json(req)).await;
+    login_history::record(
+        &pool,
+        result.as_ref().ok().map(|r| r.user.id),
+        &username,
+        login_history::PASSKEY_LOGIN,
+        login_history::outcome(&result),
+        &client_ip.to_string(),
+        user_agent.as_deref(),
+    )
+    .await;
+    result
+}
+
+async fn passkey_login(
     State(pool): State<DbPool>,
     Json(req): Json<PasskeyLoginFinish>,
 ) -> Result<Json<AuthResponse>, ApiError> {
@@ -363,11 +446,14 @@ pub async fn passkey_login_finish_handler(
     .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
 
     // Verify challenge
+    let challenge = general_purpose::STANDARD
+        .decode(&req.challenge)
+        .map_err(|_| ApiError::BadRequest("Invalid challenge".to_string()))?;
     let challenge_exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM passkey_challenges WHERE user_id = $1 AND challenge = $2 AND expires_at > NOW())"
     )
     .bind(user.id)
-    .bind(general_purpose::STANDARD.decode(&req.challenge).map_err(|_| ApiError::BadRequest("Invalid challenge".to_string()))?)
+    .bind(&challenge)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -393,15 +479,39 @@ pub async fn passkey_login_finish_handler(
     .map_err(|e| ApiError::Database(e.to_string()))?
     .ok_or_else(|| ApiError::Unauthorized("Invalid passkey".to_string()))?;
 
-    // In production, verify the signature here using the public_key
-    // For now, we'll trust the credential_id match
+    // Verify the assertion signature, client data and counter
+    let authenticator_data = decode_assertion_field(&req.authenticator_data, "authenticator data")?;
+    let client_data_json = decode_assertion_field(&req.client_data_json, "client data")?;
+    let signature = decode_assertion_field(&req.signature, "signature")?;
+    let counter = webauthn::verify_assertion(
+        &Assertion {
+            authenticator_data: &authenticator_data,
+            client_data_json: &client_data_json,
+            signature: &signature,
+        },
+        &passkey.public_key,
+        u32::try_from(passkey.counter).unwrap_or(u32::MAX),
+        &config::get().passkey_rp_id,
+        &config::get().passkey_origin,
+        &challenge,
+    )?;
+
+    // Store the new counter; a concurrent login with the same counter loses
+    let updated = sqlx::query(
+        "UPDATE passkeys SET counter = $1, last_used_at = NOW() WHERE id = $2 AND counter = $3",
+    )
+    .bind(counter as i64)
+    .bind(passkey.id)
+    .bind(passkey.counter)
+    .execute(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // Update last used
-    sqlx::query("UPDATE passkeys SET last_used_at = NOW() WHERE id = $1")
-        .bind(passkey.id)
-        .execute(&pool)
-        .await
-        .ok();
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::Unauthorized(
+            "Passkey rejected: signature counter did not increase".to_string(),
+        ));
+    }
 
     // Delete used challenge
     sqlx::query("DELETE FROM passkey_challenges WHERE user_id = $1")
@@ -422,8 +532,6 @@ pub async fn passkey_login_finish_handler(
     let access_token = AuthService::generate_jwt(user.id, user.username.clone(), roles.clone())
         .map_err(|e| ApiError::Internal(format!("Token generation failed: {}", e)))?;
 
-    let refresh_token = AuthService::generate_refresh_token();
-
     // Check if this is the first login
     let is_first_login: bool =
         sqlx::query_scalar("SELECT last_login_at IS NULL FROM users WHERE id = $1")
@@ -431,11 +539,10 @@ pub async fn passkey_login_finish_handler(
             .fetch_one(&pool)
             .await
             .unwrap_or(false);
+    let must_change_password = password_change::is_required(&pool, user.id).await?;
 
-    // Store refresh token
-    AuthService::store_refresh_token(&pool, user.id, &refresh_token, 30)
-        .await
-        .map_err(|e| ApiError::Database(format!("Failed to store refresh token: {}", e)))?;
+    // Issue a refresh token for a new session
+    let refresh_token = sessions::issue_refresh_token(&pool, user.id, None).await?;
 
     // Update last login
     sqlx::query("UPDATE users SET last_login_at = CURRENT_TIMESTAMP WHERE id = $1")
@@ -451,5 +558,6 @@ pub async fn passkey_login_finish_handler(
         roles,
         permissions,
         is_first_login,
+        must_change_password,
     }))
 }
@@ -17,6 +17,7 @@ use crate::core::error::ApiError;
 use crate::core::middleware::require_auth;
 use crate::models::row_mappers;
 use crate::models::*;
+use crate::services::{currency, deposit_forfeiture, outbox};
 
 /// Recompute and persist `bookings.payment_status` for a single booking,
 /// bringing the stored column back in sync with the live sum of completed
@@ -236,6 +237,20 @@ pub async fn create_payment_handler(
         .map_err(|e| ApiError::Database(e.to_string()))?;
     }
 
+    outbox::enqueue(
+        &mut tx,
+        outbox::PAYMENT_POSTED,
+        "payment",
+        payment.id,
+        &serde_json::json!({
+            "payment_id": payment.id,
+            "booking_id": booking_id,
+            "payment_method": request.payment_method.to_string(),
+            "amount": total.to_string(),
+        }),
+    )
+    .await?;
+
     // Commit the transaction - all inserts succeed or none do
     tx.commit()
         .await
@@ -310,6 +325,22 @@ pub async fn record_payment_handler(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    let payment_id: i64 = row.get("id");
+    outbox::enqueue(
+        &mut tx,
+        outbox::PAYMENT_POSTED,
+        "payment",
+        payment_id,
+        &serde_json::json!({
+            "payment_id": payment_id,
+            "booking_id": request.booking_id,
+            "payment_method": &request.payment_method,
+            "payment_type": payment_type,
+            "amount": amount.to_string(),
+        }),
+    )
+    .await?;
+
     // Commit the transaction
     tx.commit()
         .await
@@ -319,7 +350,7 @@ pub async fn record_payment_handler(
     recompute_payment_status(&pool, request.booking_id).await?;
 
     let payment = serde_json::json!({
-        "id": row.get::<i64, _>("id"),
+        "id": payment_id,
         "booking_id": row.get::<i64, _>("booking_id"),
         "total_amount": row.get::<String, _>("amount"),
         "payment_method": row.get::<String, _>("payment_method"),
@@ -447,13 +478,17 @@ pub async fn get_payment_workflow_summary_handler(
     let booking_status: String = row.get("booking_status");
     let payment_status: String = row.get("payment_status");
     let has_failed_payment = row_mappers::get_bool(&row, "has_failed_payment");
+    let currency = currency::hotel_currency(&pool).await;
 
     let mut warnings = Vec::new();
     if has_failed_payment {
         warnings.push("One or more payments failed and need review".to_string());
     }
     if balance_due > Decimal::ZERO {
-        warnings.push(format!("Outstanding balance: {}", balance_due));
+        warnings.push(format!(
+            "Outstanding balance: {}",
+            currency::format_money(balance_due, &currency)
+        ));
     }
     if deposit_collected > deposit_refunded
         && matches!(booking_status.as_str(), "checked_out" | "completed")
@@ -488,6 +523,7 @@ pub async fn get_payment_workflow_summary_handler(
         balance_due,
         deposit_collected,
         deposit_refunded,
+        currency,
         has_failed_payment,
         next_action,
         warnings,
@@ -520,6 +556,12 @@ pub async fn refund_deposit_handler(
         ));
     }
 
+    if deposit_forfeiture::is_forfeited(&pool, booking_id).await? {
+        return Err(ApiError::BadRequest(
+            "Deposit was forfeited on no-show and cannot be refunded".to_string(),
+        ));
+    }
+
     // Start a transaction to prevent double-refunding (check + insert must be atomic)
     let mut tx = pool
         .begin()
@@ -1128,6 +1170,10 @@ pub async fn ensure_invoice_for_booking(
         None => crate::services::invoice_numbers::next_invoice_number(pool).await?,
     };
 
+    // Under a folio split the invoice covers only the guest folio; the
+    // company's share is billed through the city ledger.
+    let guest_folio = crate::services::folio_split::guest_invoice_folio(pool, booking_id).await?;
+
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     {
         sqlx::query(
@@ -1136,7 +1182,8 @@ pub async fn ensure_invoice_for_booking(
                 invoice_number, booking_id, invoice_type,
                 subtotal, total_amount, status, created_by
             )
-            SELECT ?1, b.id, 'checkout', b.total_amount, b.total_amount, 'issued', ?2
+            SELECT ?1, b.id, 'checkout',
+                   COALESCE(?4, b.total_amount), COALESCE(?4, b.total_amount), 'issued', ?2
             FROM bookings b
             WHERE b.id = ?3
             "#,
@@ -1144,6 +1191,7 @@ pub async fn ensure_invoice_for_booking(
         .bind(&invoice_number)
         .bind(user_id)
         .bind(booking_id)
+        .bind(guest_folio.as_ref().map(|f| f.guest_total.to_string()))
         .execute(pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1160,9 +1208,9 @@ pub async fn ensure_invoice_for_booking(
             SELECT $1, b.id,
                    COALESCE(g.full_name, ''),
                    g.email,
-                   b.total_amount,
-                   b.total_amount,
-                   '[]'::jsonb,
+                   COALESCE($4, b.total_amount),
+                   COALESCE($4, b.total_amount),
+                   COALESCE($5, '[]'::jsonb),
                    'issued',
                    'booking',
                    $2
@@ -1174,6 +1222,12 @@ pub async fn ensure_invoice_for_booking(
         .bind(&invoice_number)
         .bind(user_id)
         .bind(booking_id)
+        .bind(guest_folio.as_ref().map(|f| f.guest_total))
+        .bind(
+            guest_folio
+                .as_ref()
+                .map(|f| serde_json::json!(f.guest_folio)),
+        )
         .execute(pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -5,9 +5,13 @@
 use crate::core::auth::AuthService;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
+use crate::core::uploads;
 use crate::models::*;
+use crate::services::audit::{AuditLog, USER_ACTIVITY_ACTIONS};
+use crate::services::avatars;
+use crate::services::password_policy;
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Multipart, Query, State},
     response::Json,
 };
 
@@ -19,7 +23,7 @@ pub async fn get_user_profile_handler(
         r#"
         SELECT
             id, username, email, full_name, phone,
-            avatar_url, created_at, updated_at, last_login_at
+            avatar_url, avatar_thumbnail_url, created_at, updated_at, last_login_at
         FROM users
         WHERE id = $1 AND is_active = true
         "#,
@@ -33,11 +37,72 @@ pub async fn get_user_profile_handler(
     Ok(Json(profile))
 }
 
+/// Audit columns that are named differently in the two schemas.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const ACTIVITY_COLUMNS: &str = "id, action, resource_type, resource_id, created_at";
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const ACTIVITY_COLUMNS: &str = "id, action, entity_type AS resource_type, \
+     CAST(entity_id AS INTEGER) AS resource_id, created_at";
+
+/// GET /profile/activity
+/// The user's own recent actions, newest first, limited to
+/// [`USER_ACTIVITY_ACTIONS`]
+pub async fn get_user_activity_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Query(params): Query<UserActivityQuery>,
+) -> Result<Json<UserActivityResponse>, ApiError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(25).clamp(1, 100);
+    let offset = (page - 1) * page_size;
+
+    let actions = USER_ACTIVITY_ACTIONS
+        .iter()
+        .map(|a| format!("'{}'", a))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let where_clause = format!("WHERE user_id = $1 AND action IN ({})", actions);
+
+    let total: i64 =
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM audit_logs {}", where_clause))
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let data = sqlx::query_as::<_, UserActivityEntry>(&format!(
+        r#"
+        SELECT {}
+        FROM audit_logs
+        {}
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        ACTIVITY_COLUMNS, where_clause
+    ))
+    .bind(user_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(UserActivityResponse {
+        data,
+        total,
+        page,
+        page_size,
+        total_pages: (total as f64 / page_size as f64).ceil() as i64,
+    }))
+}
+
 pub async fn update_user_profile_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
     Json(input): Json<UserProfileUpdate>,
 ) -> Result<Json<UserProfile>, ApiError> {
+    let mut changed = vec![];
+
     // Use separate UPDATE statements for each field - safer than dynamic SQL construction
     if let Some(full_name) = input.full_name {
         sqlx::query(
@@ -48,6 +113,7 @@ pub async fn update_user_profile_handler(
         .execute(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
+        changed.push("full_name");
     }
 
     if let Some(email) = input.email {
@@ -57,6 +123,7 @@ pub async fn update_user_profile_handler(
             .execute(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
+        changed.push("email");
     }
 
     if let Some(phone) = input.phone {
@@ -66,23 +133,57 @@ pub async fn update_user_profile_handler(
             .execute(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
+        changed.push("phone");
     }
 
+    // A new avatar URL no longer matches an uploaded avatar's thumbnail.
     if let Some(avatar_url) = input.avatar_url {
         sqlx::query(
-            "UPDATE users SET avatar_url = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+            r#"
+            UPDATE users
+            SET avatar_url = $1, avatar_thumbnail_url = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2
+            "#,
         )
         .bind(avatar_url)
         .bind(user_id)
         .execute(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
+        changed.push("avatar_url");
+    }
+
+    if !changed.is_empty() {
+        let _ = AuditLog::log_profile_updated(&pool, user_id, &changed).await;
     }
 
     // Fetch updated profile
     get_user_profile_handler(State(pool), Extension(user_id)).await
 }
 
+/// POST /profile/avatar
+/// Upload an image as the user's avatar from the multipart `file` field
+pub async fn upload_avatar_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    mut multipart: Multipart,
+) -> Result<Json<AvatarUpload>, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file = uploads::read_file(field, &avatars::AVATAR_UPLOAD).await?;
+        let upload = avatars::save_avatar(&pool, user_id, &file.content_type, &file.data).await?;
+        return Ok(Json(upload));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
 pub async fn update_password_handler(
     State(pool): State<DbPool>,
     Extension(user_id): Extension<i64>,
@@ -106,6 +207,8 @@ pub async fn update_password_handler(
         ));
     }
 
+    password_policy::validate_password(&pool, &input.new_password).await?;
+
     // Hash new password
     let new_hash = AuthService::hash_password(&input.new_password)
         .await
@@ -115,7 +218,7 @@ pub async fn update_password_handler(
     sqlx::query(
         r#"
         UPDATE users
-        SET password_hash = $1, updated_at = CURRENT_TIMESTAMP
+        SET password_hash = $1, must_change_password = false, updated_at = CURRENT_TIMESTAMP
         WHERE id = $2
         "#,
     )
@@ -125,6 +228,8 @@ pub async fn update_password_handler(
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    let _ = AuditLog::log_password_changed(&pool, user_id).await;
+
     Ok(Json(
         serde_json::json!({"message": "Password updated successfully"}),
     ))
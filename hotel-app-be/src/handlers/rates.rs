@@ -7,7 +7,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Json, Response},
 };
-use chrono::{Datelike, NaiveDate};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde_json::json;
 
@@ -15,10 +15,12 @@ use crate::core::db::{DbPool, DbRow};
 use crate::core::error::ApiError;
 use crate::models::row_mappers;
 use crate::models::{
-    ApplicableRateQuery, RatePlan, RatePlanInput, RatePlanUpdateInput, RatePlanWithRates, RoomRate,
-    RoomRateInput, RoomRateUpdateInput, RoomRateWithDetails, RoomType,
+    ApplicableRateQuery, RateCalendar, RateCalendarQuery, RatePlan, RatePlanInput,
+    RatePlanUpdateInput, RatePlanWithRates, RoomRate, RoomRateInput, RoomRateUpdateInput,
+    RoomRateWithDetails, RoomType,
 };
 use crate::services::audit::AuditLog;
+use crate::services::rates;
 use sqlx::Row;
 
 /// Rate-specific error type
@@ -853,6 +855,41 @@ pub async fn get_room_types_for_rates(
     Ok(Json(room_types))
 }
 
+/// Longest range (in days) the rate calendar will return
+pub const MAX_CALENDAR_DAYS: i64 = 92;
+
+/// Get the resolved nightly rate for each date in a range
+pub async fn get_rate_calendar(
+    State(pool): State<DbPool>,
+    Query(query): Query<RateCalendarQuery>,
+) -> Result<Json<RateCalendar>, RateError> {
+    let start = NaiveDate::parse_from_str(&query.start, "%Y-%m-%d")
+        .map_err(|_| RateError::BadRequest("Invalid start date. Use YYYY-MM-DD".to_string()))?;
+    let end = NaiveDate::parse_from_str(&query.end, "%Y-%m-%d")
+        .map_err(|_| RateError::BadRequest("Invalid end date. Use YYYY-MM-DD".to_string()))?;
+
+    if end < start {
+        return Err(RateError::BadRequest(
+            "End date must be on or after start date".to_string(),
+        ));
+    }
+    if (end - start).num_days() >= MAX_CALENDAR_DAYS {
+        return Err(RateError::BadRequest(format!(
+            "Calendar range cannot exceed {} days",
+            MAX_CALENDAR_DAYS
+        )));
+    }
+
+    let days = rates::resolve_rates(&pool, query.room_type_id, start, end).await?;
+
+    Ok(Json(RateCalendar {
+        room_type_id: query.room_type_id,
+        start_date: start,
+        end_date: end,
+        days,
+    }))
+}
+
 /// Get applicable rate for a room type on a specific date
 pub async fn get_applicable_rate(
     State(pool): State<DbPool>,
@@ -861,50 +898,7 @@ pub async fn get_applicable_rate(
     let date = NaiveDate::parse_from_str(&query.date, "%Y-%m-%d")
         .map_err(|_| RateError::BadRequest("Invalid date format. Use YYYY-MM-DD".to_string()))?;
 
-    let day_of_week = date.weekday().num_days_from_monday();
-
-    let rate = sqlx::query_as::<_, RoomRateWithDetails>(
-        r#"
-        SELECT
-            rr.id,
-            rr.rate_plan_id,
-            rp.name as rate_plan_name,
-            rp.code as rate_plan_code,
-            rp.description as rate_plan_description,
-            rr.room_type_id,
-            rt.name as room_type_name,
-            rt.code as room_type_code,
-            rr.price,
-            rr.effective_from,
-            rr.effective_to,
-            rr.created_at
-        FROM room_rates rr
-        JOIN rate_plans rp ON rr.rate_plan_id = rp.id
-        JOIN room_types rt ON rr.room_type_id = rt.id
-        WHERE rr.room_type_id = $1
-          AND rp.is_active = true
-          AND rr.effective_from <= $2
-          AND (rr.effective_to IS NULL OR rr.effective_to >= $2)
-          AND (rp.valid_from IS NULL OR rp.valid_from <= $2)
-          AND (rp.valid_to IS NULL OR rp.valid_to >= $2)
-          AND (
-              ($3 = 0 AND rp.applies_monday = true) OR
-              ($3 = 1 AND rp.applies_tuesday = true) OR
-              ($3 = 2 AND rp.applies_wednesday = true) OR
-              ($3 = 3 AND rp.applies_thursday = true) OR
-              ($3 = 4 AND rp.applies_friday = true) OR
-              ($3 = 5 AND rp.applies_saturday = true) OR
-              ($3 = 6 AND rp.applies_sunday = true)
-          )
-        ORDER BY rp.priority DESC
-        LIMIT 1
-        "#,
-    )
-    .bind(query.room_type_id)
-    .bind(date)
-    .bind(day_of_week as i32)
-    .fetch_optional(&pool)
-    .await?;
+    let rate = rates::applicable_room_rate(&pool, query.room_type_id, date).await?;
 
     if let Some(rate) = rate {
         return Ok(Json(json!(rate)));
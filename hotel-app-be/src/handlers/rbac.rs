@@ -7,6 +7,7 @@ use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::models::*;
 use crate::services::audit::AuditLog;
+use crate::services::password_policy;
 use axum::{
     extract::{Extension, Path, State},
     response::Json,
@@ -111,29 +112,79 @@ pub async fn create_permission_handler(
     }))
 }
 
+/// Roles only a super admin may hand out.
+const PRIVILEGED_ROLES: &[&str] = &["admin", "super_admin"];
+
+/// Assign a role to a user. Assigning a role the user already holds
+/// succeeds with `created: false`.
 pub async fn assign_role_to_user_handler(
     State(pool): State<DbPool>,
+    Extension(admin_user_id): Extension<i64>,
     Json(input): Json<AssignRoleInput>,
-) -> Result<Json<serde_json::Value>, ApiError> {
-    sqlx::query(
+) -> Result<Json<RoleAssignmentResult>, ApiError> {
+    let user_exists: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM users WHERE id = $1 AND deleted_at IS NULL")
+            .bind(input.user_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    if user_exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "User with id {} not found",
+            input.user_id
+        )));
+    }
+
+    let role_name: String = sqlx::query_scalar("SELECT name FROM roles WHERE id = $1")
+        .bind(input.role_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Role with id {} not found", input.role_id)))?;
+
+    if PRIVILEGED_ROLES.contains(&role_name.as_str()) {
+        let is_super_admin = AuthService::check_role(&pool, admin_user_id, "super_admin")
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        if !is_super_admin {
+            return Err(ApiError::Forbidden(
+                "Only super admins can assign admin or super_admin roles".to_string(),
+            ));
+        }
+    }
+
+    let created = sqlx::query(
         r#"
-        INSERT INTO user_roles (user_id, role_id)
-        VALUES ($1, $2)
+        INSERT INTO user_roles (user_id, role_id, assigned_by)
+        VALUES ($1, $2, $3)
         ON CONFLICT (user_id, role_id) DO NOTHING
         "#,
     )
     .bind(input.user_id)
     .bind(input.role_id)
+    .bind(admin_user_id)
     .execute(&pool)
     .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .rows_affected()
+        > 0;
 
-    // Log role assignment (no admin_id available in this handler)
-    let _ = AuditLog::log_role_assignment(&pool, 0, input.user_id, input.role_id).await;
+    if created {
+        let _ =
+            AuditLog::log_role_assignment(&pool, admin_user_id, input.user_id, input.role_id).await;
+    }
 
-    Ok(Json(
-        serde_json::json!({"message": "Role assigned successfully"}),
-    ))
+    Ok(Json(RoleAssignmentResult {
+        user_id: input.user_id,
+        role_id: input.role_id,
+        role_name,
+        created,
+        message: if created {
+            "Role assigned successfully".to_string()
+        } else {
+            "Role already assigned".to_string()
+        },
+    }))
 }
 
 pub async fn remove_role_from_user_handler(
@@ -177,6 +228,105 @@ pub async fn assign_permission_to_role_handler(
     ))
 }
 
+/// Grant several permissions to several roles in one transaction. Every
+/// role and permission id is checked first; an unknown id rejects the whole
+/// batch. Grants a role already holds are left as they are.
+pub async fn batch_grant_permissions_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Json(input): Json<Vec<RoleGrantInput>>,
+) -> Result<Json<Vec<RoleGrantResult>>, ApiError> {
+    if input.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one role grant is required".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut role_ids: Vec<i64> = input.iter().map(|g| g.role_id).collect();
+    role_ids.sort_unstable();
+    role_ids.dedup();
+    let mut permission_ids: Vec<i64> = input
+        .iter()
+        .flat_map(|g| g.permission_ids.iter().copied())
+        .collect();
+    permission_ids.sort_unstable();
+    permission_ids.dedup();
+
+    let mut unknown = Vec::new();
+    for (table, label, ids) in [
+        ("roles", "role", &role_ids),
+        ("permissions", "permission", &permission_ids),
+    ] {
+        let mut missing = Vec::new();
+        for id in ids {
+            let found: Option<i64> =
+                sqlx::query_scalar(&format!("SELECT id FROM {} WHERE id = $1", table))
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(|e| ApiError::Database(e.to_string()))?;
+            if found.is_none() {
+                missing.push(id.to_string());
+            }
+        }
+        if !missing.is_empty() {
+            unknown.push(format!("unknown {} ids: {}", label, missing.join(", ")));
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(ApiError::BadRequest(unknown.join("; ")));
+    }
+
+    let mut results = Vec::new();
+    for grant in &input {
+        let mut granted = Vec::new();
+        for permission_id in &grant.permission_ids {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO role_permissions (role_id, permission_id, granted_by)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (role_id, permission_id) DO NOTHING
+                "#,
+            )
+            .bind(grant.role_id)
+            .bind(permission_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .rows_affected();
+            if inserted > 0 {
+                granted.push(*permission_id);
+            }
+        }
+
+        let held: Vec<i64> = sqlx::query_scalar(
+            "SELECT permission_id FROM role_permissions WHERE role_id = $1 ORDER BY permission_id",
+        )
+        .bind(grant.role_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        results.push(RoleGrantResult {
+            role_id: grant.role_id,
+            permission_ids: held,
+            granted,
+        });
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(results))
+}
+
 pub async fn remove_permission_from_role_handler(
     State(pool): State<DbPool>,
     Path((role_id, permission_id)): Path<(i64, i64)>,
@@ -269,7 +419,7 @@ pub async fn create_user_handler(
         ));
     }
 
-    AuthService::validate_password(&input.password).map_err(ApiError::BadRequest)?;
+    password_policy::validate_password(&pool, &input.password).await?;
 
     let password_hash = AuthService::hash_password(&input.password)
         .await
@@ -296,8 +446,8 @@ pub async fn create_user_handler(
 
     let user = sqlx::query_as::<_, User>(
         r#"
-        INSERT INTO users (username, email, password_hash, full_name, phone, is_active, is_verified)
-        VALUES ($1, $2, $3, $4, $5, true, true)
+        INSERT INTO users (username, email, password_hash, full_name, phone, is_active, is_verified, must_change_password)
+        VALUES ($1, $2, $3, $4, $5, true, true, true)
         RETURNING id, username, email, full_name, phone, is_active, is_verified, user_type, two_factor_enabled, two_factor_secret, two_factor_recovery_codes, created_at, updated_at
         "#
     )
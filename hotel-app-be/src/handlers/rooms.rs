@@ -5,12 +5,17 @@
 use crate::core::db::{DbPool, DbRow, opt_decimal_to_db};
 use crate::core::error::ApiError;
 use crate::core::middleware::{require_auth, require_permission_helper};
+use crate::core::property_scope::{PropertyScope, require_property_scope};
 use crate::handlers::rooms_queries::*;
 use crate::models::row_mappers::{get_decimal, get_opt_decimal};
 use crate::models::*;
 use crate::services::audit::AuditLog;
+use crate::services::booking as booking_svc;
+use crate::services::tax::{TaxContext, TaxMode};
+use crate::services::{currency, hotel_time, room_blocks, room_status};
+use crate::utils::sort::SortSpec;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::HeaderMap,
     response::Json,
 };
@@ -70,21 +75,49 @@ fn row_to_room_type(row: &DbRow) -> RoomType {
     }
 }
 
+/// Sortable columns for the rooms list.
+pub const ROOM_SORT: SortSpec = SortSpec {
+    columns: &[
+        ("room_number", "r.room_number"),
+        ("room_type", "rt.name"),
+        ("price_per_night", "COALESCE(r.custom_price, rt.base_price)"),
+        ("max_occupancy", "rt.max_occupancy"),
+        ("created_at", "r.created_at"),
+    ],
+    default_sort: "room_number",
+    default_descending: false,
+    tiebreaker: "r.id",
+};
+
 pub async fn get_rooms_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Query(params): Query<RoomListParams>,
 ) -> Result<Json<Vec<RoomWithRating>>, ApiError> {
+    let order_by = ROOM_SORT.order_by(params.sort_by.as_deref(), params.sort_order.as_deref())?;
+    let wanted = room_status::parse_status_filter(params.status.as_deref().unwrap_or_default())?;
+
     // Use database-specific query
-    let rows = sqlx::query(GET_ROOMS_QUERY)
+    let today = hotel_time::hotel_today(&pool).await?;
+    let rows = sqlx::query(&format!("{} ORDER BY {}", GET_ROOMS_QUERY, order_by))
+        .bind(scope.property_id)
+        .bind(today)
         .fetch_all(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
+    let mut current_status = room_status::current_room_status(&pool).await?;
 
     let mut rooms = Vec::new();
     for row in rows {
         // Read average_rating as f64 (works for both PostgreSQL and SQLite NULL)
         let average_rating: Option<f64> = row.try_get(9).ok();
         let review_count: Option<i64> = row.try_get(10).ok();
-        let status: Option<String> = row.try_get(11).ok();
+        let id: i64 = row.get(0);
+        let status: Option<String> = current_status.remove(&id).or_else(|| row.try_get(11).ok());
+        // Filter on the computed status, which SQL alone does not know.
+        if !wanted.is_empty() && !status.as_deref().is_some_and(|s| wanted.contains(&s)) {
+            continue;
+        }
         let maintenance_start_date: Option<DateTime<Utc>> = row.try_get(12).ok();
         let maintenance_end_date: Option<DateTime<Utc>> = row.try_get(13).ok();
         let cleaning_start_date: Option<DateTime<Utc>> = row.try_get(14).ok();
@@ -102,7 +135,7 @@ pub async fn get_rooms_handler(
         let available: bool = row.get(4);
 
         rooms.push(RoomWithRating {
-            id: row.get(0),
+            id,
             room_number: row.get(1),
             room_type: row.get(2),
             price_per_night: row.get::<String, _>(3).parse().unwrap_or_default(),
@@ -129,6 +162,7 @@ pub async fn get_rooms_handler(
 
 pub async fn search_rooms_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
     Query(query): Query<SearchQuery>,
 ) -> Result<Json<Vec<RoomWithRating>>, ApiError> {
     // Parse date range if provided for availability check
@@ -142,6 +176,13 @@ pub async fn search_rooms_handler(
         .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
     let room_type = query.room_type.as_deref().filter(|s| !s.trim().is_empty());
     let max_price = query.max_price;
+    if query.guests.is_some_and(|guests| guests < 1) {
+        return Err(ApiError::BadRequest(
+            "guests must be at least 1".to_string(),
+        ));
+    }
+
+    let today = hotel_time::hotel_today(&pool).await?;
 
     // Use database-specific queries
     let rows = if let (Some(ci), Some(co)) = (check_in, check_out) {
@@ -151,12 +192,17 @@ pub async fn search_rooms_handler(
             .bind(query.exclude_booking_id)
             .bind(room_type.map(str::trim))
             .bind(max_price)
+            .bind(query.guests)
+            .bind(scope.property_id)
             .fetch_all(&pool)
             .await
     } else {
         sqlx::query(SEARCH_ROOMS_NO_DATES_QUERY)
             .bind(room_type.map(str::trim))
             .bind(max_price)
+            .bind(query.guests)
+            .bind(today)
+            .bind(scope.property_id)
             .fetch_all(&pool)
             .await
     }
@@ -209,11 +255,126 @@ pub async fn search_rooms_handler(
     Ok(Json(rooms))
 }
 
+/// Longest stay the priced search will price
+pub const MAX_PRICED_SEARCH_NIGHTS: i64 = 30;
+
+/// Rooms free for a stay with the stay's total price, for the booking funnel.
+/// Availability is `search_rooms_handler`'s; each room is priced the way
+/// booking creation prices it.
+pub async fn search_priced_rooms_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Query(query): Query<PricedRoomSearchQuery>,
+) -> Result<Json<Vec<PricedRoom>>, ApiError> {
+    let check_in = NaiveDate::parse_from_str(&query.check_in, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid check-in date. Use YYYY-MM-DD".to_string()))?;
+    let check_out = NaiveDate::parse_from_str(&query.check_out, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid check-out date. Use YYYY-MM-DD".to_string()))?;
+
+    if check_out <= check_in {
+        return Err(ApiError::BadRequest(
+            "Check-out date must be after check-in date".to_string(),
+        ));
+    }
+    if (check_out - check_in).num_days() > MAX_PRICED_SEARCH_NIGHTS {
+        return Err(ApiError::BadRequest(format!(
+            "Stay cannot exceed {} nights",
+            MAX_PRICED_SEARCH_NIGHTS
+        )));
+    }
+
+    let Json(rooms) = search_rooms_handler(
+        State(pool.clone()),
+        Extension(scope),
+        Query(SearchQuery {
+            room_type: query.room_type,
+            max_price: None,
+            check_in_date: Some(query.check_in),
+            check_out_date: Some(query.check_out),
+            exclude_booking_id: None,
+            guests: query.guests,
+        }),
+    )
+    .await?;
+
+    let tax_ctx = TaxContext::load(&pool, TaxMode::Inclusive).await;
+    let currency = currency::hotel_currency(&pool).await;
+    let mut priced = Vec::with_capacity(rooms.len());
+    for room in rooms {
+        let nightly_rates =
+            booking_svc::plan_rates_for_stay(&pool, room.id, None, None, check_in, check_out)
+                .await?;
+        let price = booking_svc::price_stay(
+            check_in,
+            check_out,
+            room.price_per_night,
+            nightly_rates,
+            None,
+            None,
+            &tax_ctx,
+        );
+        priced.push(PricedRoom {
+            room,
+            nights: price.billable_nights,
+            nightly_breakdown: price.nights,
+            service_tax: price.taxes.service_tax,
+            total_price: price.taxes.gross,
+            currency: currency.clone(),
+        });
+    }
+
+    Ok(Json(priced))
+}
+
+/// Load a room as the API returns it.
+async fn fetch_room(pool: &DbPool, room_id: i64) -> Result<Room, ApiError> {
+    let row = sqlx::query(GET_ROOM_BY_ID_QUERY)
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))?;
+
+    // Handle available field - SQLite returns 0/1, PostgreSQL returns bool
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let available: bool = row.get::<i32, _>(4) != 0;
+    #[cfg(any(
+        all(feature = "postgres", not(feature = "sqlite")),
+        all(feature = "sqlite", feature = "postgres")
+    ))]
+    let available: bool = row.get(4);
+
+    Ok(Room {
+        id: row.get(0),
+        room_number: row.get(1),
+        room_type: row.get(2),
+        price_per_night: row.get::<String, _>(3).parse().unwrap_or_default(),
+        available,
+        description: row.get(5),
+        max_occupancy: row.get(6),
+        status: row.get(7),
+        created_at: row.get(8),
+        updated_at: row.get(9),
+        notes: row.get(10),
+    })
+}
+
+/// Update a room. Unset fields keep their current value. `room_type` moves
+/// the room to the type with that name or code; `description` and
+/// `max_occupancy` belong to the room type, so they change it for every
+/// room of that type.
 pub async fn update_room_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
     Path(room_id): Path<i64>,
     Json(input): Json<RoomUpdateInput>,
 ) -> Result<Json<Room>, ApiError> {
+    scope.require_room(&pool, room_id).await?;
+
+    if input.is_empty() {
+        return fetch_room(&pool, room_id).await.map(Json);
+    }
+
     // Check if room exists and get current values with JOIN to room_types
     let existing_row = sqlx::query(GET_EXISTING_ROOM_FOR_UPDATE)
         .bind(room_id)
@@ -223,48 +384,35 @@ pub async fn update_room_handler(
         .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))?;
 
     let current_room_number: String = existing_row.get(1);
-    let current_room_type: String = existing_row.get(2);
-    let current_price: Decimal = existing_row.get::<String, _>(3).parse().unwrap_or_default();
-
-    // Handle available field - SQLite returns 0/1, PostgreSQL returns bool
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let current_available: bool = existing_row.get::<i32, _>(4) != 0;
-    #[cfg(any(
-        all(feature = "postgres", not(feature = "sqlite")),
-        all(feature = "sqlite", feature = "postgres")
-    ))]
-    let current_available: bool = existing_row.get(4);
-
-    let current_description: Option<String> = existing_row.get(5);
-    let current_max_occupancy: i32 = existing_row.get(6);
-    let current_status: Option<String> = existing_row.get(7);
-    let current_notes: Option<String> = existing_row.try_get(11).ok();
-
-    // Check if anything actually changed
-    if input.room_number.is_none()
-        && input.price_per_night.is_none()
-        && input.available.is_none()
-        && input.notes.is_none()
+    let current_custom_price: Option<Decimal> = existing_row
+        .get::<Option<String>, _>(10)
+        .and_then(|p| p.parse().ok());
+    let current_notes: Option<String> = existing_row.try_get(11).ok().flatten();
+    let current_room_type_id: i64 = existing_row.get(12);
+
+    if let Some(max_occupancy) = input.max_occupancy
+        && max_occupancy < 1
     {
-        return Ok(Json(Room {
-            id: existing_row.get(0),
-            room_number: current_room_number,
-            room_type: current_room_type,
-            price_per_night: current_price,
-            available: current_available,
-            description: current_description,
-            max_occupancy: current_max_occupancy,
-            status: current_status,
-            created_at: existing_row.get(8),
-            updated_at: existing_row.get(9),
-            notes: current_notes,
-        }));
+        return Err(ApiError::BadRequest(
+            "Max occupancy must be at least 1".to_string(),
+        ));
     }
 
+    let room_type_id = match &input.room_type {
+        Some(room_type) => sqlx::query_scalar::<_, i64>(FIND_ROOM_TYPE_BY_NAME)
+            .bind(room_type.trim())
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::BadRequest("Invalid room type".to_string()))?,
+        None => current_room_type_id,
+    };
+
     let room_number = input.room_number.as_ref().unwrap_or(&current_room_number);
     let custom_price = input
         .price_per_night
-        .map(|p| rust_decimal::Decimal::from_f64_retain(p).unwrap_or_default());
+        .map(|p| rust_decimal::Decimal::from_f64_retain(p).unwrap_or_default())
+        .or(current_custom_price);
     let notes = if input.notes.is_some() {
         input.notes.clone()
     } else {
@@ -285,6 +433,7 @@ pub async fn update_room_handler(
     if new_status == Some("available") {
         let active_booking: Option<i64> = sqlx::query_scalar(CHECK_ACTIVE_BOOKING)
             .bind(room_id)
+            .bind(hotel_time::hotel_today(&pool).await?)
             .fetch_optional(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -302,6 +451,7 @@ pub async fn update_room_handler(
             .bind(opt_decimal_to_db(custom_price))
             .bind(status)
             .bind(&notes)
+            .bind(room_type_id)
             .bind(room_id)
             .execute(&pool)
             .await
@@ -311,44 +461,29 @@ pub async fn update_room_handler(
             .bind(room_number)
             .bind(opt_decimal_to_db(custom_price))
             .bind(&notes)
+            .bind(room_type_id)
             .bind(room_id)
             .execute(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
     }
 
-    let row = sqlx::query(GET_ROOM_BY_ID_QUERY)
-        .bind(room_id)
-        .fetch_one(&pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    // Handle available field - SQLite returns 0/1, PostgreSQL returns bool
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let available: bool = row.get::<i32, _>(4) != 0;
-    #[cfg(any(
-        all(feature = "postgres", not(feature = "sqlite")),
-        all(feature = "sqlite", feature = "postgres")
-    ))]
-    let available: bool = row.get(4);
+    if input.description.is_some() || input.max_occupancy.is_some() {
+        sqlx::query(UPDATE_ROOM_TYPE_DETAILS_QUERY)
+            .bind(&input.description)
+            .bind(input.max_occupancy)
+            .bind(room_type_id)
+            .execute(&pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
 
-    Ok(Json(Room {
-        id: row.get(0),
-        room_number: row.get(1),
-        room_type: row.get(2),
-        price_per_night: row.get::<String, _>(3).parse().unwrap_or_default(),
-        available,
-        description: row.get(5),
-        max_occupancy: row.get(6),
-        status: row.get(7),
-        created_at: row.get(8),
-        updated_at: row.get(9),
-        notes: row.get(10),
-    }))
+    fetch_room(&pool, room_id).await.map(Json)
 }
 
 pub async fn create_room_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
     Json(input): Json<RoomCreateInput>,
 ) -> Result<Json<Room>, ApiError> {
     let existing: Option<i64> = sqlx::query_scalar(CHECK_ROOM_NUMBER_EXISTS)
@@ -392,6 +527,7 @@ pub async fn create_room_handler(
             } else {
                 0i32
             })
+            .bind(scope.property_id)
             .execute(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -413,6 +549,7 @@ pub async fn create_room_handler(
         .bind(&input.building)
         .bind(opt_decimal_to_db(custom_price_decimal))
         .bind(input.is_accessible.unwrap_or(false))
+        .bind(scope.property_id)
         .fetch_one(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -449,17 +586,10 @@ pub async fn create_room_handler(
 
 pub async fn delete_room_handler(
     State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
     Path(room_id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let room_exists: Option<i64> = sqlx::query_scalar(CHECK_ROOM_EXISTS_BY_ID)
-        .bind(room_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
-
-    if room_exists.is_none() {
-        return Err(ApiError::NotFound("Room not found".to_string()));
-    }
+    scope.require_room(&pool, room_id).await?;
 
     // Only block deletion if there are currently checked-in guests
     let has_active_booking: Option<i64> = sqlx::query_scalar(CHECK_ROOM_HAS_ACTIVE_BOOKING)
@@ -875,6 +1005,10 @@ pub async fn update_room_status_handler(
     Json(input): Json<RoomStatusUpdateInput>,
 ) -> Result<Json<Room>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let valid_statuses = vec![
         "available",
@@ -931,6 +1065,7 @@ pub async fn update_room_status_handler(
     if target_status == "available" {
         let active_booking: Option<i64> = sqlx::query_scalar(CHECK_ACTIVE_BOOKING)
             .bind(room_id)
+            .bind(hotel_time::hotel_today(&pool).await?)
             .fetch_optional(&pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1092,6 +1227,10 @@ pub async fn end_maintenance_handler(
     headers: HeaderMap,
 ) -> Result<Json<Room>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let current_status: Option<String> = sqlx::query_scalar(GET_ROOM_STATUS)
         .bind(room_id)
@@ -1215,6 +1354,10 @@ pub async fn end_cleaning_handler(
     headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let current_status: Option<String> = sqlx::query_scalar(GET_ROOM_STATUS)
         .bind(room_id)
@@ -1235,6 +1378,7 @@ pub async fn end_cleaning_handler(
 
     let next_status: String = sqlx::query_scalar(GET_NEXT_ROOM_STATUS)
         .bind(room_id)
+        .bind(hotel_time::hotel_today(&pool).await?)
         .fetch_one(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1286,39 +1430,54 @@ pub async fn end_cleaning_handler(
     })))
 }
 
+/// Bring the stored statuses of the property's rooms in line with their
+/// bookings; the reconciliation `reconcile_room_statuses_handler` runs.
 pub async fn sync_room_statuses_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let _user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
-    let rows = sqlx::query("SELECT * FROM sync_all_room_statuses()")
-        .fetch_all(&pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let corrections =
+        room_status::reconcile_room_statuses(&pool, Some(scope.property_id), Some(user_id)).await?;
 
-    let mut changes = Vec::new();
-    for row in &rows {
-        changes.push(serde_json::json!({
-            "room_id": row.get::<i64, _>("room_id"),
-            "room_number": row.get::<String, _>("room_number"),
-            "old_status": row.get::<String, _>("old_status"),
-            "new_status": row.get::<String, _>("new_status"),
-        }));
-    }
+    let changes: Vec<serde_json::Value> = corrections
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "room_id": c.room_id,
+                "room_number": c.room_number,
+                "old_status": c.from_status,
+                "new_status": c.to_status,
+            })
+        })
+        .collect();
 
     Ok(Json(serde_json::json!({
         "success": true,
-        "synced_count": rows.len(),
-        "changes": changes,
-        "message": if !rows.is_empty() {
-            format!("Successfully synchronized {} room(s)", rows.len())
+        "synced_count": changes.len(),
+        "message": if !changes.is_empty() {
+            format!("Successfully synchronized {} room(s)", changes.len())
         } else {
             "All room statuses are already consistent".to_string()
-        }
+        },
+        "changes": changes,
     })))
 }
 
+/// Correct stored room statuses that have drifted from the rooms' bookings,
+/// for the rooms in the scoped property.
+pub async fn reconcile_room_statuses_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+) -> Result<Json<Vec<RoomStatusCorrection>>, ApiError> {
+    let corrections =
+        room_status::reconcile_room_statuses(&pool, Some(scope.property_id), Some(user_id)).await?;
+    Ok(Json(corrections))
+}
+
 pub async fn execute_room_change_handler(
     State(pool): State<DbPool>,
     Path(room_id): Path<i64>,
@@ -1326,6 +1485,8 @@ pub async fn execute_room_change_handler(
     Json(input): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    scope.require_room(&pool, room_id).await?;
 
     let target_id: i64 = input
         .get("target_room_id")
@@ -1350,8 +1511,10 @@ pub async fn execute_room_change_handler(
 
     // Find the currently active booking for this room
     // Priority: checked_in first, then confirmed bookings that are currently active
+    let today = hotel_time::hotel_today(&pool).await?;
     let booking: Option<(i64, i64)> = sqlx::query_as(GET_ACTIVE_BOOKING_FOR_ROOM)
         .bind(room_id)
+        .bind(today)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1362,8 +1525,10 @@ pub async fn execute_room_change_handler(
 
     // Check target room exists and is available using dynamic status computation
     // This matches the logic used in get_rooms_handler for consistency
+    scope.require_room(&pool, target_id).await?;
     let target_room: Option<(String, bool, bool)> = sqlx::query_as(GET_TARGET_ROOM_STATUS)
         .bind(target_id)
+        .bind(today)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1516,7 +1681,8 @@ pub async fn get_room_change_history_handler(
     headers: HeaderMap,
     Query(params): Query<std::collections::HashMap<String, String>>,
 ) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
-    require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
     let booking_id = params.get("booking_id").and_then(|v| v.parse::<i64>().ok());
     let guest_id = params.get("guest_id").and_then(|v| v.parse::<i64>().ok());
@@ -1531,6 +1697,7 @@ pub async fn get_room_change_history_handler(
         .bind(guest_id)
         .bind(room_id)
         .bind(limit)
+        .bind(scope.property_id)
         .fetch_all(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1576,6 +1743,10 @@ pub async fn create_room_event_handler(
     Json(input): Json<RoomEventInput>,
 ) -> Result<Json<RoomEvent>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let valid_types = vec!["reserved", "maintenance"];
     if !valid_types.contains(&input.event_type.as_str()) {
@@ -1700,7 +1871,11 @@ pub async fn get_room_detailed_status_handler(
     Path(room_id): Path<i64>,
     headers: HeaderMap,
 ) -> Result<Json<RoomDetailedStatus>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let room_row = sqlx::query(GET_ROOM_DETAILED_STATUS)
         .bind(room_id)
@@ -1732,8 +1907,10 @@ pub async fn get_room_detailed_status_handler(
     let target_room_id: Option<i64> = room_row.try_get(14).ok();
     let status_notes: Option<String> = room_row.try_get(15).ok();
 
+    let today = hotel_time::hotel_today(&pool).await?;
     let current_booking = sqlx::query(GET_CURRENT_BOOKING_FOR_ROOM)
         .bind(room_id)
+        .bind(today)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
@@ -1741,6 +1918,7 @@ pub async fn get_room_detailed_status_handler(
 
     let next_booking = sqlx::query(GET_NEXT_BOOKING_FOR_ROOM)
         .bind(room_id)
+        .bind(today)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?
@@ -1783,7 +1961,11 @@ pub async fn get_room_history_handler(
     Path(room_id): Path<i64>,
     headers: HeaderMap,
 ) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     let history = match sqlx::query(GET_ROOM_HISTORY)
         .bind(room_id)
@@ -1853,7 +2035,8 @@ pub async fn get_all_room_occupancy_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<Vec<RoomCurrentOccupancy>>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
     let rows = sqlx::query(
         r#"
@@ -1876,9 +2059,11 @@ pub async fn get_all_room_occupancy_handler(
             check_out_date,
             is_occupied
         FROM room_current_occupancy
+        WHERE room_id IN (SELECT id FROM rooms WHERE property_id = $1)
         ORDER BY room_number
         "#,
     )
+    .bind(scope.property_id)
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1896,7 +2081,11 @@ pub async fn get_room_occupancy_handler(
     Path(room_id): Path<i64>,
     headers: HeaderMap,
 ) -> Result<Json<RoomCurrentOccupancy>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    require_property_scope(&pool, &headers, user_id)
+        .await?
+        .require_room(&pool, room_id)
+        .await?;
 
     #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     let query = r#"
@@ -1961,24 +2150,28 @@ pub async fn get_hotel_occupancy_summary_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<HotelOccupancySummary>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
+    // The `hotel_occupancy_summary` view's totals, over the property's rooms.
     let row = sqlx::query(
         r#"
         SELECT
-            total_rooms,
-            occupied_rooms,
-            available_rooms,
-            occupancy_rate,
-            total_adults,
-            total_children,
-            total_infants,
-            total_guests,
-            total_capacity,
-            guest_occupancy_rate
-        FROM hotel_occupancy_summary
+            COUNT(*)::BIGINT AS total_rooms,
+            COUNT(*) FILTER (WHERE is_occupied = TRUE)::BIGINT AS occupied_rooms,
+            COUNT(*) FILTER (WHERE is_occupied = FALSE)::BIGINT AS available_rooms,
+            ROUND(COUNT(*) FILTER (WHERE is_occupied = TRUE)::numeric / NULLIF(COUNT(*), 0) * 100, 1) AS occupancy_rate,
+            COALESCE(SUM(current_adults), 0)::BIGINT AS total_adults,
+            COALESCE(SUM(current_children), 0)::BIGINT AS total_children,
+            COALESCE(SUM(current_infants), 0)::BIGINT AS total_infants,
+            COALESCE(SUM(current_total_guests), 0)::BIGINT AS total_guests,
+            COALESCE(SUM(max_occupancy), 0)::BIGINT AS total_capacity,
+            ROUND(COALESCE(SUM(current_total_guests), 0)::NUMERIC / NULLIF(SUM(max_occupancy), 0) * 100, 1) AS guest_occupancy_rate
+        FROM room_current_occupancy
+        WHERE room_id IN (SELECT id FROM rooms WHERE property_id = $1)
         "#,
     )
+    .bind(scope.property_id)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -1991,24 +2184,34 @@ pub async fn get_occupancy_by_room_type_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<Vec<OccupancyByRoomType>>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
+    // The `occupancy_by_room_type` view, counting only the property's rooms.
     let rows = sqlx::query(
         r#"
         SELECT
-            room_type_id,
-            room_type_name,
-            capacity_per_room,
-            total_rooms,
-            occupied_rooms,
-            room_occupancy_rate,
-            total_guests,
-            total_capacity,
-            guest_occupancy_rate
-        FROM occupancy_by_room_type
-        ORDER BY room_type_name
+            rt.id AS room_type_id,
+            rt.name AS room_type_name,
+            rt.max_occupancy AS capacity_per_room,
+            COUNT(r.id)::BIGINT AS total_rooms,
+            COUNT(r.id) FILTER (WHERE b.id IS NOT NULL)::BIGINT AS occupied_rooms,
+            ROUND(COUNT(r.id) FILTER (WHERE b.id IS NOT NULL)::NUMERIC / NULLIF(COUNT(r.id), 0) * 100, 1) AS room_occupancy_rate,
+            COALESCE(SUM(COALESCE(b.adults, 0) + COALESCE(b.children, 0) + COALESCE(b.infants, 0)), 0)::BIGINT AS total_guests,
+            (COUNT(r.id) * rt.max_occupancy)::BIGINT AS total_capacity,
+            ROUND(COALESCE(SUM(COALESCE(b.adults, 0) + COALESCE(b.children, 0) + COALESCE(b.infants, 0)), 0)::NUMERIC
+                  / NULLIF(COUNT(r.id) * rt.max_occupancy, 0) * 100, 1) AS guest_occupancy_rate
+        FROM room_types rt
+        LEFT JOIN rooms r ON r.room_type_id = rt.id AND r.is_active = TRUE AND r.property_id = $1
+        LEFT JOIN bookings b ON r.id = b.room_id AND b.status = 'checked_in'
+            AND $2 >= b.check_in_date AND $2 <= b.check_out_date
+        WHERE rt.is_active = TRUE
+        GROUP BY rt.id, rt.name, rt.max_occupancy
+        ORDER BY rt.name
         "#,
     )
+    .bind(scope.property_id)
+    .bind(hotel_time::hotel_today(&pool).await?)
     .fetch_all(&pool)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -2026,9 +2229,11 @@ pub async fn get_rooms_with_occupancy_handler(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<Vec<RoomWithOccupancy>>, ApiError> {
-    let _user_id = require_auth(&headers).await?;
+    let user_id = require_auth(&headers).await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
 
     let rows = sqlx::query(GET_ROOMS_WITH_OCCUPANCY)
+        .bind(scope.property_id)
         .fetch_all(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -2081,3 +2286,297 @@ pub async fn get_rooms_with_occupancy_handler(
 
     Ok(Json(rooms_with_occupancy))
 }
+
+/// Longest range (in days) the reservation timeline will return
+pub const MAX_TIMELINE_DAYS: i64 = 92;
+
+/// Get bookings per room across a date range, alongside each room's current status
+pub async fn get_reservation_timeline_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Query(query): Query<ReservationTimelineQuery>,
+) -> Result<Json<ReservationTimeline>, ApiError> {
+    let start = NaiveDate::parse_from_str(&query.start, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid start date. Use YYYY-MM-DD".to_string()))?;
+    let end = NaiveDate::parse_from_str(&query.end, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest("Invalid end date. Use YYYY-MM-DD".to_string()))?;
+
+    if end < start {
+        return Err(ApiError::BadRequest(
+            "End date must be on or after start date".to_string(),
+        ));
+    }
+    if (end - start).num_days() >= MAX_TIMELINE_DAYS {
+        return Err(ApiError::BadRequest(format!(
+            "Timeline range cannot exceed {} days",
+            MAX_TIMELINE_DAYS
+        )));
+    }
+
+    let room_rows = sqlx::query(
+        r#"
+        SELECT r.id, r.room_number, rt.name
+        FROM rooms r
+        INNER JOIN room_types rt ON r.room_type_id = rt.id
+        WHERE r.is_active = true AND r.property_id = $1
+        ORDER BY r.room_number
+        "#,
+    )
+    .bind(scope.property_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    // A booking appears on every day from check-in up to (not including) check-out;
+    // same-day bookings occupy their check-in day.
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let booking_sql = r#"
+        SELECT b.id, b.booking_number, b.guest_id, b.room_id,
+               COALESCE(g.full_name, g.first_name || ' ' || g.last_name) as guest_name,
+               b.check_in_date, b.check_out_date, b.status
+        FROM bookings b
+        LEFT JOIN guests g ON b.guest_id = g.id
+        WHERE b.status NOT IN ('cancelled', 'voided')
+          AND b.property_id = ?3
+          AND b.check_in_date <= ?2
+          AND (b.check_out_date > ?1 OR (b.check_out_date = b.check_in_date AND b.check_in_date >= ?1))
+        ORDER BY b.room_id, b.check_in_date
+    "#;
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let booking_sql = r#"
+        SELECT b.id, b.booking_number, b.guest_id, b.room_id,
+               COALESCE(g.full_name, g.first_name || ' ' || g.last_name) as guest_name,
+               b.check_in_date, b.check_out_date, b.status
+        FROM bookings b
+        LEFT JOIN guests g ON b.guest_id = g.id
+        WHERE b.status NOT IN ('cancelled', 'voided')
+          AND b.property_id = $3
+          AND b.check_in_date <= $2
+          AND (b.check_out_date > $1 OR (b.check_out_date = b.check_in_date AND b.check_in_date >= $1))
+        ORDER BY b.room_id, b.check_in_date
+    "#;
+
+    let booking_rows = sqlx::query(booking_sql)
+        .bind(start)
+        .bind(end)
+        .bind(scope.property_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut bookings_by_room: std::collections::HashMap<i64, Vec<TimelineBooking>> =
+        std::collections::HashMap::new();
+    for row in booking_rows {
+        let room_id: i64 = row.get("room_id");
+        bookings_by_room
+            .entry(room_id)
+            .or_default()
+            .push(TimelineBooking {
+                booking_id: row.get("id"),
+                booking_number: row.get("booking_number"),
+                guest_id: row.get("guest_id"),
+                guest_name: row.try_get("guest_name").ok().flatten(),
+                check_in_date: row.get("check_in_date"),
+                check_out_date: row.get("check_out_date"),
+                status: row.get("status"),
+            });
+    }
+
+    let mut current_status = room_status::current_room_status(&pool).await?;
+    let rooms = room_rows
+        .into_iter()
+        .map(|row| {
+            let room_id: i64 = row.get(0);
+            TimelineRoom {
+                room_id,
+                room_number: row.get(1),
+                room_type: row.get(2),
+                current_status: current_status
+                    .remove(&room_id)
+                    .unwrap_or_else(|| "available".to_string()),
+                bookings: bookings_by_room.remove(&room_id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(Json(ReservationTimeline {
+        start_date: start,
+        end_date: end,
+        rooms,
+    }))
+}
+
+/// Days a room schedule covers when no `to` date is given
+pub const DEFAULT_ROOM_SCHEDULE_DAYS: i64 = 30;
+
+/// Get one room's non-cancelled bookings across a date range, ordered by check-in
+pub async fn get_room_bookings_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(room_id): Path<i64>,
+    Query(query): Query<RoomBookingsQuery>,
+) -> Result<Json<RoomSchedule>, ApiError> {
+    scope.require_room(&pool, room_id).await?;
+
+    let from = match query.from.as_deref() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::BadRequest("Invalid from date. Use YYYY-MM-DD".to_string()))?,
+        None => hotel_time::hotel_today(&pool).await?,
+    };
+    let to = match query.to.as_deref() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::BadRequest("Invalid to date. Use YYYY-MM-DD".to_string()))?,
+        None => from + chrono::Duration::days(DEFAULT_ROOM_SCHEDULE_DAYS),
+    };
+
+    if to < from {
+        return Err(ApiError::BadRequest(
+            "To date must be on or after from date".to_string(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_TIMELINE_DAYS {
+        return Err(ApiError::BadRequest(format!(
+            "Schedule range cannot exceed {} days",
+            MAX_TIMELINE_DAYS
+        )));
+    }
+
+    let room_number: String = sqlx::query_scalar("SELECT room_number FROM rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))?;
+
+    // Same overlap rule as the reservation timeline.
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let booking_sql = r#"
+        SELECT b.id, b.booking_number, b.guest_id,
+               COALESCE(g.full_name, g.first_name || ' ' || g.last_name) as guest_name,
+               b.check_in_date, b.check_out_date, b.status
+        FROM bookings b
+        LEFT JOIN guests g ON b.guest_id = g.id
+        WHERE b.room_id = ?3
+          AND b.status NOT IN ('cancelled', 'voided')
+          AND b.check_in_date <= ?2
+          AND (b.check_out_date > ?1 OR (b.check_out_date = b.check_in_date AND b.check_in_date >= ?1))
+        ORDER BY b.check_in_date, b.id
+    "#;
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let booking_sql = r#"
+        SELECT b.id, b.booking_number, b.guest_id,
+               COALESCE(g.full_name, g.first_name || ' ' || g.last_name) as guest_name,
+               b.check_in_date, b.check_out_date, b.status
+        FROM bookings b
+        LEFT JOIN guests g ON b.guest_id = g.id
+        WHERE b.room_id = $3
+          AND b.status NOT IN ('cancelled', 'voided')
+          AND b.check_in_date <= $2
+          AND (b.check_out_date > $1 OR (b.check_out_date = b.check_in_date AND b.check_in_date >= $1))
+        ORDER BY b.check_in_date, b.id
+    "#;
+
+    let bookings = sqlx::query(booking_sql)
+        .bind(from)
+        .bind(to)
+        .bind(room_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .iter()
+        .map(|row| TimelineBooking {
+            booking_id: row.get("id"),
+            booking_number: row.get("booking_number"),
+            guest_id: row.get("guest_id"),
+            guest_name: row.try_get("guest_name").ok().flatten(),
+            check_in_date: row.get("check_in_date"),
+            check_out_date: row.get("check_out_date"),
+            status: row.get("status"),
+        })
+        .collect();
+
+    Ok(Json(RoomSchedule {
+        room_id,
+        room_number,
+        from_date: from,
+        to_date: to,
+        bookings,
+    }))
+}
+
+/// POST /rooms/:id/block
+/// Keep the room off sale for a date range, for owner use, staff housing or
+/// renovation
+pub async fn create_room_block_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(room_id): Path<i64>,
+    Json(input): Json<RoomBlockInput>,
+) -> Result<Json<RoomBlock>, ApiError> {
+    scope.require_room(&pool, room_id).await?;
+    let block = room_blocks::create_block(&pool, room_id, &input, user_id).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "room_blocked",
+        "room",
+        Some(room_id),
+        Some(serde_json::json!({
+            "block_id": block.id,
+            "start_date": block.start_date,
+            "end_date": block.end_date,
+            "reason": block.reason,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(block))
+}
+
+/// GET /rooms/:id/block
+pub async fn list_room_blocks_handler(
+    State(pool): State<DbPool>,
+    Extension(scope): Extension<PropertyScope>,
+    Path(room_id): Path<i64>,
+) -> Result<Json<Vec<RoomBlock>>, ApiError> {
+    scope.require_room(&pool, room_id).await?;
+    let blocks = room_blocks::list_blocks(&pool, room_id).await?;
+    Ok(Json(blocks))
+}
+
+/// DELETE /rooms/:id/block/:block_id
+pub async fn delete_room_block_handler(
+    State(pool): State<DbPool>,
+    Extension(user_id): Extension<i64>,
+    Extension(scope): Extension<PropertyScope>,
+    Path((room_id, block_id)): Path<(i64, i64)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    scope.require_room(&pool, room_id).await?;
+    let block = room_blocks::delete_block(&pool, room_id, block_id).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "room_unblocked",
+        "room",
+        Some(room_id),
+        Some(serde_json::json!({
+            "block_id": block.id,
+            "start_date": block.start_date,
+            "end_date": block.end_date,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "Room block removed"
+    })))
+}
@@ -3,6 +3,10 @@
 //! This module provides query strings that work with both PostgreSQL and SQLite.
 
 /// Get rooms query - PostgreSQL version
+///
+/// `status` is the stored room status; `get_rooms_handler` replaces it with the
+/// dynamic value from `services::room_status::current_room_status`. The
+/// handler appends the `ORDER BY`.
 #[cfg(any(
     all(feature = "postgres", not(feature = "sqlite")),
     all(feature = "sqlite", feature = "postgres")
@@ -16,13 +20,13 @@ WITH current_bookings AS (
         check_out_date
     FROM bookings
     WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-      AND check_out_date >= CURRENT_DATE
+      AND check_out_date >= $2
     ORDER BY room_id,
         CASE
             WHEN status IN ('checked_in', 'auto_checked_in') THEN 1
-            WHEN status = 'confirmed' AND check_in_date <= CURRENT_DATE THEN 2
+            WHEN status = 'confirmed' AND check_in_date <= $2 THEN 2
             WHEN status = 'confirmed' THEN 3
-            WHEN status = 'pending' AND check_in_date <= CURRENT_DATE THEN 4
+            WHEN status = 'pending' AND check_in_date <= $2 THEN 4
             ELSE 5
         END,
         check_in_date
@@ -35,7 +39,7 @@ SELECT
     CASE
         WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN false
         WHEN cb.booking_status IN ('confirmed', 'pending') THEN false
-        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN false
+        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty', 'cleaning') THEN false
         ELSE true
     END as available,
     rt.description,
@@ -44,12 +48,7 @@ SELECT
     r.updated_at,
     NULL::DECIMAL as average_rating,
     NULL::BIGINT as review_count,
-    CASE
-        WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN 'occupied'
-        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN r.status
-        WHEN cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= CURRENT_DATE THEN 'reserved'
-        ELSE 'available'
-    END as status,
+    r.status,
     r.maintenance_start_date,
     r.maintenance_end_date,
     r.cleaning_start_date,
@@ -60,11 +59,12 @@ SELECT
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 LEFT JOIN current_bookings cb ON cb.room_id = r.id
-WHERE r.is_active = true
-ORDER BY r.room_number
+WHERE r.is_active = true AND r.property_id = $1
 "#;
 
 /// Get rooms query - SQLite version
+///
+/// See the PostgreSQL version for how `status` is resolved.
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 pub const GET_ROOMS_QUERY: &str = r#"
 WITH current_bookings AS (
@@ -75,18 +75,18 @@ WITH current_bookings AS (
         check_out_date
     FROM bookings b1
     WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-      AND check_out_date >= date('now')
+      AND check_out_date >= $2
       AND b1.id = (
           SELECT b2.id FROM bookings b2
           WHERE b2.room_id = b1.room_id
             AND b2.status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-            AND b2.check_out_date >= date('now')
+            AND b2.check_out_date >= $2
           ORDER BY
               CASE
                   WHEN b2.status IN ('checked_in', 'auto_checked_in') THEN 1
-                  WHEN b2.status = 'confirmed' AND b2.check_in_date <= date('now') THEN 2
+                  WHEN b2.status = 'confirmed' AND b2.check_in_date <= $2 THEN 2
                   WHEN b2.status = 'confirmed' THEN 3
-                  WHEN b2.status = 'pending' AND b2.check_in_date <= date('now') THEN 4
+                  WHEN b2.status = 'pending' AND b2.check_in_date <= $2 THEN 4
                   ELSE 5
               END,
               b2.check_in_date
@@ -101,7 +101,7 @@ SELECT
     CASE
         WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN 0
         WHEN cb.booking_status IN ('confirmed', 'pending') THEN 0
-        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN 0
+        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty', 'cleaning') THEN 0
         ELSE 1
     END as available,
     rt.description,
@@ -110,12 +110,7 @@ SELECT
     r.updated_at,
     NULL as average_rating,
     NULL as review_count,
-    CASE
-        WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN 'occupied'
-        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN r.status
-        WHEN cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= date('now') THEN 'reserved'
-        ELSE 'available'
-    END as status,
+    r.status,
     r.maintenance_start_date,
     r.maintenance_end_date,
     r.cleaning_start_date,
@@ -126,8 +121,7 @@ SELECT
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 LEFT JOIN current_bookings cb ON cb.room_id = r.id
-WHERE r.is_active = 1
-ORDER BY r.room_number
+WHERE r.is_active = 1 AND r.property_id = $1
 "#;
 
 /// Search rooms with date range - PostgreSQL version
@@ -169,8 +163,14 @@ LEFT JOIN conflicting_bookings cb ON cb.room_id = r.id
 WHERE r.is_active = true
   AND r.status NOT IN ('maintenance', 'out_of_order')
   AND cb.room_id IS NULL
+  AND NOT EXISTS (
+      SELECT 1 FROM room_blocks rb
+      WHERE rb.room_id = r.id AND rb.start_date < $2 AND rb.end_date > $1
+  )
   AND ($4::text IS NULL OR LOWER(rt.name) = LOWER($4) OR LOWER(rt.code) = LOWER($4))
   AND ($5::DOUBLE PRECISION IS NULL OR COALESCE(r.custom_price, rt.base_price) <= $5)
+  AND ($6::INTEGER IS NULL OR rt.max_occupancy >= $6)
+  AND r.property_id = $7
 ORDER BY COALESCE(r.custom_price, rt.base_price)
 "#;
 
@@ -210,8 +210,14 @@ LEFT JOIN conflicting_bookings cb ON cb.room_id = r.id
 WHERE r.is_active = 1
   AND r.status NOT IN ('maintenance', 'out_of_order')
   AND cb.room_id IS NULL
+  AND NOT EXISTS (
+      SELECT 1 FROM room_blocks rb
+      WHERE rb.room_id = r.id AND rb.start_date < ?2 AND rb.end_date > ?1
+  )
   AND (?4 IS NULL OR LOWER(rt.name) = LOWER(?4) OR LOWER(rt.code) = LOWER(?4))
   AND (?5 IS NULL OR COALESCE(r.custom_price, rt.base_price) <= ?5)
+  AND (?6 IS NULL OR rt.max_occupancy >= ?6)
+  AND r.property_id = ?7
 ORDER BY COALESCE(r.custom_price, rt.base_price)
 "#;
 
@@ -228,11 +234,11 @@ WITH current_bookings AS (
         check_in_date
     FROM bookings
     WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-      AND check_out_date >= CURRENT_DATE
+      AND check_out_date >= $4
     ORDER BY room_id,
         CASE
             WHEN status IN ('checked_in', 'auto_checked_in') THEN 1
-            WHEN status = 'confirmed' AND check_in_date <= CURRENT_DATE THEN 2
+            WHEN status = 'confirmed' AND check_in_date <= $4 THEN 2
             ELSE 3
         END,
         check_in_date
@@ -264,10 +270,12 @@ WHERE r.is_active = true
   AND r.status NOT IN ('maintenance', 'out_of_order')
   AND (cb.room_id IS NULL OR NOT (
       cb.booking_status IN ('checked_in', 'auto_checked_in') OR
-      (cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= CURRENT_DATE)
+      (cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= $4)
   ))
   AND ($1::text IS NULL OR LOWER(rt.name) = LOWER($1) OR LOWER(rt.code) = LOWER($1))
   AND ($2::DOUBLE PRECISION IS NULL OR COALESCE(r.custom_price, rt.base_price) <= $2)
+  AND ($3::INTEGER IS NULL OR rt.max_occupancy >= $3)
+  AND r.property_id = $5
 ORDER BY COALESCE(r.custom_price, rt.base_price)
 "#;
 
@@ -281,16 +289,16 @@ WITH current_bookings AS (
         check_in_date
     FROM bookings b1
     WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-      AND check_out_date >= date('now')
+      AND check_out_date >= ?4
       AND b1.id = (
           SELECT b2.id FROM bookings b2
           WHERE b2.room_id = b1.room_id
             AND b2.status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
-            AND b2.check_out_date >= date('now')
+            AND b2.check_out_date >= ?4
           ORDER BY
               CASE
                   WHEN b2.status IN ('checked_in', 'auto_checked_in') THEN 1
-                  WHEN b2.status = 'confirmed' AND b2.check_in_date <= date('now') THEN 2
+                  WHEN b2.status = 'confirmed' AND b2.check_in_date <= ?4 THEN 2
                   ELSE 3
               END,
               b2.check_in_date
@@ -324,10 +332,12 @@ WHERE r.is_active = 1
   AND r.status NOT IN ('maintenance', 'out_of_order')
   AND (cb.room_id IS NULL OR NOT (
       cb.booking_status IN ('checked_in', 'auto_checked_in') OR
-      (cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= date('now'))
+      (cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= ?4)
   ))
   AND (?1 IS NULL OR LOWER(rt.name) = LOWER(?1) OR LOWER(rt.code) = LOWER(?1))
   AND (?2 IS NULL OR COALESCE(r.custom_price, rt.base_price) <= ?2)
+  AND (?3 IS NULL OR rt.max_occupancy >= ?3)
+  AND r.property_id = ?5
 ORDER BY COALESCE(r.custom_price, rt.base_price)
 "#;
 
@@ -369,8 +379,9 @@ SET room_number = $1,
     custom_price = $2,
     status = $3,
     notes = $4,
+    room_type_id = $5,
     updated_at = CURRENT_TIMESTAMP
-WHERE id = $5
+WHERE id = $6
 "#;
 
 /// Update room - SQLite version
@@ -381,8 +392,9 @@ SET room_number = ?1,
     custom_price = ?2,
     status = ?3,
     notes = ?4,
+    room_type_id = ?5,
     updated_at = datetime('now')
-WHERE id = ?5
+WHERE id = ?6
 "#;
 
 /// Update room without status - PostgreSQL version
@@ -395,8 +407,9 @@ UPDATE rooms
 SET room_number = $1,
     custom_price = $2,
     notes = $3,
+    room_type_id = $4,
     updated_at = CURRENT_TIMESTAMP
-WHERE id = $4
+WHERE id = $5
 "#;
 
 /// Update room without status - SQLite version
@@ -406,8 +419,9 @@ UPDATE rooms
 SET room_number = ?1,
     custom_price = ?2,
     notes = ?3,
+    room_type_id = ?4,
     updated_at = datetime('now')
-WHERE id = ?4
+WHERE id = ?5
 "#;
 
 /// Check room exists - PostgreSQL version
@@ -432,34 +446,59 @@ pub const CHECK_ROOM_TYPE_EXISTS: &str = "SELECT id FROM room_types WHERE id = $
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 pub const CHECK_ROOM_TYPE_EXISTS: &str = "SELECT id FROM room_types WHERE id = ?1";
 
-/// Insert room - PostgreSQL version
+/// Find room type by name or code - PostgreSQL version
 #[cfg(any(
     all(feature = "postgres", not(feature = "sqlite")),
     all(feature = "sqlite", feature = "postgres")
 ))]
-pub const INSERT_ROOM_QUERY: &str = r#"
-INSERT INTO rooms (room_number, room_type_id, floor, building, custom_price, is_accessible, status, is_active)
-VALUES ($1, $2, $3, $4, $5, $6, 'available', true)
-RETURNING id
+pub const FIND_ROOM_TYPE_BY_NAME: &str =
+    "SELECT id FROM room_types WHERE LOWER(name) = LOWER($1) OR LOWER(code) = LOWER($1)";
+
+/// Find room type by name or code - SQLite version
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub const FIND_ROOM_TYPE_BY_NAME: &str =
+    "SELECT id FROM room_types WHERE LOWER(name) = LOWER(?1) OR LOWER(code) = LOWER(?1)";
+
+/// Update the description and capacity of a room's type - PostgreSQL version
+#[cfg(any(
+    all(feature = "postgres", not(feature = "sqlite")),
+    all(feature = "sqlite", feature = "postgres")
+))]
+pub const UPDATE_ROOM_TYPE_DETAILS_QUERY: &str = r#"
+UPDATE room_types
+SET description = COALESCE($1, description),
+    max_occupancy = COALESCE($2, max_occupancy),
+    updated_at = CURRENT_TIMESTAMP
+WHERE id = $3
 "#;
 
-/// Insert room - SQLite version (no RETURNING, use last_insert_rowid)
+/// Update the description and capacity of a room's type - SQLite version
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-pub const INSERT_ROOM_QUERY: &str = r#"
-INSERT INTO rooms (room_number, room_type_id, floor, building, custom_price, is_accessible, status, is_active)
-VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'available', 1)
+pub const UPDATE_ROOM_TYPE_DETAILS_QUERY: &str = r#"
+UPDATE room_types
+SET description = COALESCE(?1, description),
+    max_occupancy = COALESCE(?2, max_occupancy),
+    updated_at = datetime('now')
+WHERE id = ?3
 "#;
 
-/// Check room exists by ID - PostgreSQL version
+/// Insert room - PostgreSQL version
 #[cfg(any(
     all(feature = "postgres", not(feature = "sqlite")),
     all(feature = "sqlite", feature = "postgres")
 ))]
-pub const CHECK_ROOM_EXISTS_BY_ID: &str = "SELECT id FROM rooms WHERE id = $1";
+pub const INSERT_ROOM_QUERY: &str = r#"
+INSERT INTO rooms (room_number, room_type_id, floor, building, custom_price, is_accessible, status, is_active, property_id)
+VALUES ($1, $2, $3, $4, $5, $6, 'available', true, $7)
+RETURNING id
+"#;
 
-/// Check room exists by ID - SQLite version
+/// Insert room - SQLite version (no RETURNING, use last_insert_rowid)
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-pub const CHECK_ROOM_EXISTS_BY_ID: &str = "SELECT id FROM rooms WHERE id = ?1";
+pub const INSERT_ROOM_QUERY: &str = r#"
+INSERT INTO rooms (room_number, room_type_id, floor, building, custom_price, is_accessible, status, is_active, property_id)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'available', 1, ?7)
+"#;
 
 /// Check room has active booking (currently checked in) - PostgreSQL version
 /// Only blocks deletion if there's a guest currently checked in
@@ -528,8 +567,8 @@ pub const CHECK_ACTIVE_BOOKING: &str = r#"
 SELECT id FROM bookings
 WHERE room_id = $1
 AND status IN ('checked_in', 'auto_checked_in')
-AND check_in_date <= CURRENT_DATE
-AND check_out_date >= CURRENT_DATE
+AND check_in_date <= $2
+AND check_out_date >= $2
 LIMIT 1
 "#;
 
@@ -539,8 +578,8 @@ pub const CHECK_ACTIVE_BOOKING: &str = r#"
 SELECT id FROM bookings
 WHERE room_id = ?1
 AND status IN ('checked_in', 'auto_checked_in')
-AND check_in_date <= date('now')
-AND check_out_date >= date('now')
+AND check_in_date <= ?2
+AND check_out_date >= ?2
 LIMIT 1
 "#;
 
@@ -575,7 +614,7 @@ pub const GET_EXISTING_ROOM_FOR_UPDATE: &str = r#"
 SELECT r.id, r.room_number, rt.name as room_type,
        COALESCE(r.custom_price, rt.base_price)::text as price_per_night,
        CASE WHEN r.status = 'available' THEN true ELSE false END as available,
-       rt.description, rt.max_occupancy, r.status, r.created_at, r.updated_at, r.custom_price::text, r.notes
+       rt.description, rt.max_occupancy, r.status, r.created_at, r.updated_at, r.custom_price::text, r.notes, r.room_type_id
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 WHERE r.id = $1
@@ -587,7 +626,7 @@ pub const GET_EXISTING_ROOM_FOR_UPDATE: &str = r#"
 SELECT r.id, r.room_number, rt.name as room_type,
        CAST(COALESCE(r.custom_price, rt.base_price) AS TEXT) as price_per_night,
        CASE WHEN r.status = 'available' THEN 1 ELSE 0 END as available,
-       rt.description, rt.max_occupancy, r.status, r.created_at, r.updated_at, CAST(r.custom_price AS TEXT), r.notes
+       rt.description, rt.max_occupancy, r.status, r.created_at, r.updated_at, CAST(r.custom_price AS TEXT), r.notes, r.room_type_id
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 WHERE r.id = ?1
@@ -943,6 +982,7 @@ LEFT JOIN users u ON rc.changed_by = u.id
 WHERE ($1::BIGINT IS NULL OR rc.booking_id = $1)
   AND ($2::BIGINT IS NULL OR rc.guest_id = $2)
   AND ($3::BIGINT IS NULL OR rc.from_room_id = $3 OR rc.to_room_id = $3)
+  AND b.property_id = $5
 ORDER BY rc.changed_at DESC
 LIMIT $4
 "#;
@@ -977,6 +1017,7 @@ LEFT JOIN users u ON rc.changed_by = u.id
 WHERE (?1 IS NULL OR rc.booking_id = ?1)
   AND (?2 IS NULL OR rc.guest_id = ?2)
   AND (?3 IS NULL OR rc.from_room_id = ?3 OR rc.to_room_id = ?3)
+  AND b.property_id = ?5
 ORDER BY rc.changed_at DESC
 LIMIT ?4
 "#;
@@ -1076,8 +1117,8 @@ JOIN rooms r ON b.room_id = r.id
 JOIN room_types rt ON r.room_type_id = rt.id
 WHERE b.room_id = $1
   AND b.status NOT IN ('checked_out', 'voided')
-  AND b.check_in_date <= CURRENT_DATE
-  AND b.check_out_date > CURRENT_DATE
+  AND b.check_in_date <= $2
+  AND b.check_out_date > $2
 ORDER BY b.check_in_date DESC
 LIMIT 1
 "#;
@@ -1095,8 +1136,8 @@ JOIN rooms r ON b.room_id = r.id
 JOIN room_types rt ON r.room_type_id = rt.id
 WHERE b.room_id = ?1
   AND b.status NOT IN ('checked_out', 'voided')
-  AND b.check_in_date <= date('now')
-  AND b.check_out_date > date('now')
+  AND b.check_in_date <= ?2
+  AND b.check_out_date > ?2
 ORDER BY b.check_in_date DESC
 LIMIT 1
 "#;
@@ -1117,7 +1158,7 @@ JOIN rooms r ON b.room_id = r.id
 JOIN room_types rt ON r.room_type_id = rt.id
 WHERE b.room_id = $1
   AND b.status = 'confirmed'
-  AND b.check_in_date > CURRENT_DATE
+  AND b.check_in_date > $2
 ORDER BY b.check_in_date ASC
 LIMIT 1
 "#;
@@ -1135,7 +1176,7 @@ JOIN rooms r ON b.room_id = r.id
 JOIN room_types rt ON r.room_type_id = rt.id
 WHERE b.room_id = ?1
   AND b.status = 'confirmed'
-  AND b.check_in_date > date('now')
+  AND b.check_in_date > ?2
 ORDER BY b.check_in_date ASC
 LIMIT 1
 "#;
@@ -1300,7 +1341,7 @@ SELECT
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 LEFT JOIN room_current_occupancy rco ON r.id = rco.room_id
-WHERE r.is_active = true
+WHERE r.is_active = true AND r.property_id = $1
 ORDER BY r.room_number
 "#;
 
@@ -1328,7 +1369,7 @@ SELECT
 FROM rooms r
 INNER JOIN room_types rt ON r.room_type_id = rt.id
 LEFT JOIN room_current_occupancy rco ON r.id = rco.room_id
-WHERE r.is_active = 1
+WHERE r.is_active = 1 AND r.property_id = ?1
 ORDER BY r.room_number
 "#;
 
@@ -1341,8 +1382,8 @@ pub const GET_ACTIVE_BOOKING_FOR_ROOM: &str = r#"
 SELECT id, guest_id FROM bookings
 WHERE room_id = $1
   AND status IN ('confirmed', 'checked_in')
-  AND check_in_date <= CURRENT_DATE
-  AND check_out_date >= CURRENT_DATE
+  AND check_in_date <= $2
+  AND check_out_date >= $2
 ORDER BY
     CASE WHEN status = 'checked_in' THEN 0 ELSE 1 END,
     check_in_date
@@ -1355,8 +1396,8 @@ pub const GET_ACTIVE_BOOKING_FOR_ROOM: &str = r#"
 SELECT id, guest_id FROM bookings
 WHERE room_id = ?1
   AND status IN ('confirmed', 'checked_in')
-  AND check_in_date <= date('now')
-  AND check_out_date >= date('now')
+  AND check_in_date <= ?2
+  AND check_out_date >= ?2
 ORDER BY
     CASE WHEN status = 'checked_in' THEN 0 ELSE 1 END,
     check_in_date
@@ -1375,15 +1416,15 @@ SELECT
             SELECT 1 FROM bookings
             WHERE room_id = r.id
             AND status = 'checked_in'
-            AND check_out_date >= CURRENT_DATE
+            AND check_out_date >= $2
         ) THEN 'occupied'
         WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN r.status
         WHEN EXISTS (
             SELECT 1 FROM bookings
             WHERE room_id = r.id
             AND status IN ('confirmed', 'pending')
-            AND check_in_date <= CURRENT_DATE
-            AND check_out_date >= CURRENT_DATE
+            AND check_in_date <= $2
+            AND check_out_date >= $2
         ) THEN 'reserved'
         ELSE 'available'
     END as computed_status,
@@ -1402,15 +1443,15 @@ SELECT
             SELECT 1 FROM bookings
             WHERE room_id = r.id
             AND status = 'checked_in'
-            AND check_out_date >= date('now')
+            AND check_out_date >= ?2
         ) THEN 'occupied'
         WHEN r.status IN ('maintenance', 'out_of_order', 'dirty') THEN r.status
         WHEN EXISTS (
             SELECT 1 FROM bookings
             WHERE room_id = r.id
             AND status IN ('confirmed', 'pending')
-            AND check_in_date <= date('now')
-            AND check_out_date >= date('now')
+            AND check_in_date <= ?2
+            AND check_out_date >= ?2
         ) THEN 'reserved'
         ELSE 'available'
     END as computed_status,
@@ -1524,14 +1565,26 @@ pub const DELETE_ROOM_TYPE: &str = "DELETE FROM room_types WHERE id = $1";
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 pub const DELETE_ROOM_TYPE: &str = "DELETE FROM room_types WHERE id = ?1";
 
-/// Get next room status - PostgreSQL version (function call)
+/// Get next room status after cleaning: reserved when a booking arrives on
+/// or after the hotel's today (`$2`) - PostgreSQL version
 #[cfg(any(
     all(feature = "postgres", not(feature = "sqlite")),
     all(feature = "sqlite", feature = "postgres")
 ))]
-pub const GET_NEXT_ROOM_STATUS: &str = "SELECT get_room_next_status($1)";
+pub const GET_NEXT_ROOM_STATUS: &str = r#"
+SELECT
+    CASE
+        WHEN EXISTS (
+            SELECT 1 FROM bookings
+            WHERE room_id = $1
+            AND status IN ('confirmed', 'pending')
+            AND check_in_date >= $2
+        ) THEN 'reserved'
+        ELSE 'available'
+    END
+"#;
 
-/// Get next room status - SQLite version (inline logic since no stored function)
+/// Get next room status after cleaning - SQLite version
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 pub const GET_NEXT_ROOM_STATUS: &str = r#"
 SELECT
@@ -1540,7 +1593,7 @@ SELECT
             SELECT 1 FROM bookings
             WHERE room_id = ?1
             AND status IN ('confirmed', 'pending')
-            AND check_in_date >= date('now')
+            AND check_in_date >= ?2
         ) THEN 'reserved'
         ELSE 'available'
     END
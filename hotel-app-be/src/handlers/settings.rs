@@ -6,6 +6,30 @@ use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::require_permission_helper;
 use crate::models::*;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+use crate::services::booking as booking_svc;
+use crate::services::booking_archive;
+use crate::services::booking_fees;
+use crate::services::booking_numbers::{self, BookingNumberFormat};
+use crate::services::booking_sources;
+use crate::services::cancellation;
+use crate::services::circuit_breaker;
+use crate::services::currency;
+use crate::services::hotel_time;
+use crate::services::housekeeping;
+use crate::services::login_attempts;
+use crate::services::loyalty;
+use crate::services::membership_expiry;
+use crate::services::occupancy_alerts;
+use crate::services::outbox;
+use crate::services::password_policy;
+use crate::services::review_points;
+use crate::services::sessions;
+use crate::services::stay_policy;
+use crate::services::tier_review;
+use crate::services::upload_cleanup;
+use crate::services::vip;
 use axum::{
     extract::{Path, State},
     http::HeaderMap,
@@ -40,6 +64,30 @@ pub async fn update_system_setting_handler(
     // Require admin permission
     let user_id = require_permission_helper(&pool, &headers, "settings:update").await?;
 
+    if key == booking_numbers::FORMAT_SETTING && !input.value.trim().is_empty() {
+        BookingNumberFormat::parse(input.value.trim(), false).map_err(ApiError::BadRequest)?;
+    }
+    sessions::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    tier_review::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    outbox::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    circuit_breaker::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    cancellation::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    currency::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    booking_archive::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    booking_fees::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    booking_svc::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    booking_sources::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    hotel_time::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    login_attempts::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    loyalty::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    membership_expiry::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    occupancy_alerts::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    password_policy::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    review_points::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    stay_policy::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    upload_cleanup::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+    vip::validate_setting(&key, &input.value).map_err(ApiError::BadRequest)?;
+
     let updated = sqlx::query_as::<_, SystemSetting>(
         r#"
         UPDATE system_settings
@@ -81,33 +129,41 @@ pub async fn get_market_codes_handler(
     Ok(Json(MarketCodesResponse { market_codes }))
 }
 
+/// Get the booking sources (channels) bookings may use
+pub async fn get_booking_sources_handler(
+    State(pool): State<DbPool>,
+) -> Result<Json<BookingSourcesResponse>, ApiError> {
+    Ok(Json(booking_sources::configured(&pool).await.response()))
+}
+
 /// Process auto check-in and late checkout based on system settings
 pub async fn process_auto_checkin_checkout_handler(
     State(pool): State<DbPool>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Get settings
-    let auto_checkin_enabled = get_setting_value(&pool, "auto_checkin_enabled").await?;
-    let late_checkout_enabled = get_setting_value(&pool, "late_checkout_enabled").await?;
-    let check_in_time = get_setting_value(&pool, "check_in_time").await?;
-    let check_out_time = get_setting_value(&pool, "check_out_time").await?;
+    let auto_checkin_enabled =
+        SettingsRepository::get_bool(&pool, "auto_checkin_enabled", false).await;
+    let late_checkout_enabled =
+        SettingsRepository::get_bool(&pool, "late_checkout_enabled", false).await;
+    let policy = stay_policy::configured(&pool).await;
+    let now = hotel_time::hotel_now(&pool).await;
+    let today = now.date();
 
     let mut checked_in = 0;
-    let mut marked_late = 0;
+    let mut late_checkouts = Vec::new();
 
-    // Auto check-in: update booking status and mark rooms as occupied
-    if auto_checkin_enabled == "true" {
+    // Auto check-in from the check-in time: update booking status and mark
+    // rooms as occupied
+    if auto_checkin_enabled && now.time() >= policy.check_in_time {
         let result = sqlx::query(
             r#"
             UPDATE bookings
             SET status = 'auto_checked_in', updated_at = CURRENT_TIMESTAMP
             WHERE status = 'confirmed'
-              AND check_in_date = CURRENT_DATE
-              AND CURRENT_TIME >= $1::TIME
-              AND CURRENT_TIME < $2::TIME
+              AND check_in_date = $1
             "#,
         )
-        .bind(&check_in_time)
-        .bind(&check_out_time)
+        .bind(today)
         .execute(&pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -123,40 +179,74 @@ pub async fn process_auto_checkin_checkout_handler(
                 WHERE id IN (
                     SELECT room_id FROM bookings
                     WHERE status = 'auto_checked_in'
-                      AND check_in_date = CURRENT_DATE
+                      AND check_in_date = $1
                 )
                 "#,
             )
+            .bind(today)
             .execute(&pool)
             .await;
         }
     }
 
-    // Mark late checkouts
-    if late_checkout_enabled == "true" {
-        let result = sqlx::query(
-            r#"
-            UPDATE bookings
-            SET status = 'late_checkout', updated_at = CURRENT_TIMESTAMP
-            WHERE status IN ('checked_in', 'auto_checked_in')
-              AND check_out_date = CURRENT_DATE
-              AND CURRENT_TIME > $1::TIME
-            "#,
-        )
-        .bind(&check_out_time)
-        .execute(&pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    // Mark late checkouts and charge their fees
+    if late_checkout_enabled {
+        late_checkouts = stay_policy::mark_late_checkouts(&pool, &policy, now).await?;
+    }
+    let marked_late = late_checkouts.iter().filter(|l| l.newly_marked).count();
 
-        marked_late = result.rows_affected() as i32;
+    // Return rooms whose cleaning window has ended to available
+    let mut released_rooms = 0;
+    if housekeeping::auto_release_enabled(&pool).await {
+        released_rooms = housekeeping::release_cleaned_rooms(&pool, chrono::Utc::now())
+            .await?
+            .len();
     }
 
     Ok(Json(serde_json::json!({
         "checked_in": checked_in,
-        "marked_late": marked_late
+        "marked_late": marked_late,
+        "late_checkouts": late_checkouts,
+        "released_rooms": released_rooms,
+        "stay_policy": policy.response()
     })))
 }
 
+/// The hotel's check-in and check-out times and late-checkout fee
+pub async fn get_stay_policy_handler(
+    State(pool): State<DbPool>,
+) -> Result<Json<StayPolicyResponse>, ApiError> {
+    Ok(Json(stay_policy::configured(&pool).await.response()))
+}
+
+/// Remove uploaded files that no record refers to
+pub async fn cleanup_orphaned_uploads_handler(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+) -> Result<Json<upload_cleanup::UploadCleanupSummary>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "settings:update").await?;
+
+    let summary = upload_cleanup::cleanup_orphaned_uploads(&pool).await?;
+
+    let _ = AuditLog::log_event(
+        &pool,
+        Some(user_id),
+        "uploads_cleaned",
+        "uploads",
+        None,
+        Some(serde_json::json!({
+            "scanned": summary.scanned,
+            "removed": &summary.removed,
+            "failed": &summary.failed,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(summary))
+}
+
 /// Helper function to get a setting value by key
 pub async fn get_setting_value(pool: &DbPool, key: &str) -> Result<String, ApiError> {
     let row = sqlx::query("SELECT value FROM system_settings WHERE key = $1")
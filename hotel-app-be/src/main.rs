@@ -11,27 +11,23 @@ mod routes;
 mod services;
 mod utils;
 
+use core::config::{self, AppConfig};
 use core::create_pool;
 use routes::create_router;
 use std::io::Write;
 use std::net::TcpListener as StdTcpListener;
 use std::path::PathBuf;
 
-/// Check if we're running in desktop mode
-fn is_desktop_mode() -> bool {
-    std::env::var("HOTEL_DESKTOP_MODE").is_ok()
-}
-
 /// Resolve the directory log files should be written to.
 ///
 /// Order: `HOTEL_LOG_DIR` env override → desktop data dir (`HotelApp/logs/`) →
 /// fallback `./logs/`. The desktop UI's `get_logs` Tauri command reads from
 /// `<data_local>/HotelApp/logs/`, so writing there makes logs visible in-app.
-fn resolve_log_dir() -> PathBuf {
-    if let Ok(dir) = std::env::var("HOTEL_LOG_DIR") {
-        return PathBuf::from(dir);
+fn resolve_log_dir(config: &AppConfig) -> PathBuf {
+    if let Some(dir) = &config.log_dir {
+        return dir.clone();
     }
-    if is_desktop_mode()
+    if config.desktop_mode
         && let Some(base) = dirs::data_local_dir()
     {
         return base.join("HotelApp").join("logs");
@@ -43,13 +39,13 @@ fn resolve_log_dir() -> PathBuf {
 ///
 /// Falls back to stderr-only if the log dir / file can't be created so a
 /// permission issue never prevents the process from starting.
-fn init_logging() {
+fn init_logging(config: &AppConfig) {
     use simplelog::{
         ColorChoice, CombinedLogger, ConfigBuilder, LevelFilter, TermLogger, TerminalMode,
         WriteLogger,
     };
 
-    let level = match std::env::var("RUST_LOG").ok().as_deref() {
+    let level = match config.log_level.as_deref() {
         Some("trace") => LevelFilter::Trace,
         Some("debug") => LevelFilter::Debug,
         Some("warn") => LevelFilter::Warn,
@@ -69,7 +65,7 @@ fn init_logging() {
         ColorChoice::Auto,
     );
 
-    let log_dir = resolve_log_dir();
+    let log_dir = resolve_log_dir(config);
     let mut loggers: Vec<Box<dyn simplelog::SharedLogger>> = vec![term_logger];
 
     if let Err(e) = std::fs::create_dir_all(&log_dir) {
@@ -128,7 +124,17 @@ async fn main() {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
-    let desktop_mode = is_desktop_mode();
+    // Read and check the configuration before anything else so a missing
+    // variable stops startup with a clear message.
+    let config = match AppConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("FATAL: Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    config::install(config.clone());
+    let desktop_mode = config.desktop_mode;
 
     // Print immediately to stdout and stderr
     println!("=== Hotel Management Backend Starting ===");
@@ -143,7 +149,7 @@ async fn main() {
     // AND to a per-day file under the resolved log dir, so warn/error events
     // from swallowed Result paths (e.g. ensure_invoice_for_booking) survive
     // a process exit.
-    init_logging();
+    init_logging(&config);
 
     log::info!("Starting Hotel Management API server...");
     if desktop_mode {
@@ -151,20 +157,27 @@ async fn main() {
     }
 
     // Initialize database pool
-    let pool = match create_pool().await {
+    let pool = match create_pool(&config).await {
         Ok(pool) => {
             log::info!("✓ Database connection established");
             pool
         }
         Err(e) => {
             log::error!("✗ Failed to create database pool: {}", e);
-            let db_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "not set".to_string());
+            let db_url = config.database_url.as_deref().unwrap_or("not set");
             log::error!("DATABASE_URL: {}", db_url);
             eprintln!("FATAL: Database connection failed: {}", e);
             std::process::exit(1);
         }
     };
 
+    // Refuse to start with a booking number format that cannot be rendered.
+    if let Err(e) = services::booking_numbers::validate_configured_format(&pool).await {
+        log::error!("✗ Invalid booking number format: {}", e);
+        eprintln!("FATAL: Invalid booking number format: {}", e);
+        std::process::exit(1);
+    }
+
     // One-shot backfill: ensure every booking has an invoice row.
     match hotel_app_be::services::invoice_numbers::backfill_missing_booking_invoices(&pool).await {
         Ok(0) => {}
@@ -179,14 +192,20 @@ async fn main() {
         Err(e) => log::warn!("Ledger due_date backfill failed: {}", e),
     }
 
+    // Load revoked access tokens before serving, then keep the list current.
+    if let Err(e) = hotel_app_be::core::revoked_tokens::reload(&pool).await {
+        log::warn!("Failed to load revoked tokens: {}", e);
+    }
+    let token_sweeper = hotel_app_be::core::revoked_tokens::spawn_sweeper(pool.clone());
+
+    // Deliver outbox events (booking/payment webhooks) in the background.
+    let outbox_dispatcher = hotel_app_be::services::outbox::spawn_dispatcher(pool.clone());
+
     // Create router with all routes and middleware
-    let app = create_router(pool);
+    let app = create_router(pool.clone(), &config);
 
     // Determine bind address and port
-    let preferred_port: u16 = std::env::var("BACKEND_PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(3030);
+    let preferred_port = config.backend_port;
 
     let (bind_address, port) = if desktop_mode {
         // In desktop mode, bind to localhost only and find available port
@@ -220,6 +239,13 @@ async fn main() {
         .await
         .unwrap();
 
+    // Send events committed since the dispatcher's last poll, then close
+    // the pool so in-flight queries finish before exit.
+    outbox_dispatcher.abort();
+    token_sweeper.abort();
+    hotel_app_be::services::outbox::flush_on_shutdown(&pool).await;
+    pool.close().await;
+
     log::info!("Server shutdown complete");
     println!("Server shutdown complete");
 }
@@ -10,3 +10,72 @@ pub struct ReportQuery {
     pub drawer: Option<String>,
     pub company_name: Option<String>,
 }
+
+/// Query parameters for the booking pace report (dates inclusive).
+#[derive(Debug, serde::Deserialize)]
+pub struct PaceQuery {
+    pub start: String,
+    pub end: String,
+}
+
+/// Business on the books for one stay date.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PaceDay {
+    pub date: chrono::NaiveDate,
+    pub room_nights: i64,
+    pub revenue: rust_decimal::Decimal,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PaceReport {
+    pub start: chrono::NaiveDate,
+    pub end: chrono::NaiveDate,
+    pub total_room_nights: i64,
+    pub total_revenue: rust_decimal::Decimal,
+    /// ISO 4217 code of the revenue figures
+    pub currency: String,
+    pub days: Vec<PaceDay>,
+}
+
+/// Query parameters for the channel report (check-in dates inclusive).
+#[derive(Debug, serde::Deserialize)]
+pub struct ChannelQuery {
+    pub start: String,
+    pub end: String,
+}
+
+/// Bookings and revenue from one booking source.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ChannelSummary {
+    pub source: String,
+    pub bookings: i64,
+    pub room_nights: i64,
+    pub revenue: rust_decimal::Decimal,
+    /// Average daily rate: revenue per room-night
+    pub adr: rust_decimal::Decimal,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChannelReport {
+    pub start: chrono::NaiveDate,
+    pub end: chrono::NaiveDate,
+    pub total_bookings: i64,
+    pub total_room_nights: i64,
+    pub total_revenue: rust_decimal::Decimal,
+    pub adr: rust_decimal::Decimal,
+    /// ISO 4217 code of the revenue figures
+    pub currency: String,
+    pub channels: Vec<ChannelSummary>,
+}
+
+/// Rooms occupied on one night of an occupancy report.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct OccupancyNight {
+    pub date: chrono::NaiveDate,
+    /// Rooms whose stay covers the night
+    pub rooms_occupied: i64,
+    /// Stays arriving that day
+    pub arrivals: i64,
+    pub revenue: rust_decimal::Decimal,
+    pub occupancy_rate: f64,
+}
@@ -0,0 +1,35 @@
+//! API key models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An API key as shown to its owner. Never carries the key itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    /// Leading characters of the key, to tell keys apart in a list
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Create API key request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Permission names the key may use, e.g. `rooms:read`
+    pub scopes: Vec<String>,
+    /// Days until the key expires; omitted means it never expires
+    pub expires_in_days: Option<i64>,
+}
+
+/// Response to key creation: the only time the plaintext key is returned
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    pub api_key: ApiKey,
+}
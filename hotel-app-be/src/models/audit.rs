@@ -59,3 +59,31 @@ pub struct AuditLogResponse {
     pub page_size: i64,
     pub total_pages: i64,
 }
+
+/// Query parameters for a user's own activity feed.
+#[derive(Debug, Default, Deserialize)]
+pub struct UserActivityQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// One entry in a user's activity feed. Details, IP address and user agent
+/// stay in the audit log.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UserActivityEntry {
+    pub id: i64,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response for a page of a user's activity feed.
+#[derive(Debug, Serialize)]
+pub struct UserActivityResponse {
+    pub data: Vec<UserActivityEntry>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub total_pages: i64,
+}
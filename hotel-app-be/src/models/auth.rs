@@ -24,6 +24,8 @@ pub struct AuthResponse {
     pub roles: Vec<String>,
     pub permissions: Vec<String>,
     pub is_first_login: bool,
+    /// Other requests are refused until the password is changed
+    pub must_change_password: bool,
 }
 
 /// Refresh token request
@@ -63,6 +65,26 @@ pub struct ResendVerificationRequest {
     pub email: String,
 }
 
+/// Query parameters for a user's own login history.
+#[derive(Debug, Default, Deserialize)]
+pub struct LoginHistoryQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// One login to the user's account
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoginHistoryEntry {
+    pub id: i64,
+    /// password | passkey
+    pub method: String,
+    /// success | failure | rate_limited
+    pub outcome: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 // Two-Factor Authentication models
 
 /// Request to setup 2FA
@@ -1,11 +1,14 @@
 //! Booking-related models
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-use super::guest::GuestUpdateInput;
+use super::guest::{Guest, GuestInput, GuestUpdateInput};
+use super::rate::NightlyRate;
 
 /// Pagination and filter query parameters for bookings.
 #[derive(Debug, Deserialize)]
@@ -32,6 +35,68 @@ pub struct BookingPaginationParams {
     pub sort_order: Option<String>,
 }
 
+/// Query parameters for the front-desk booking search.
+#[derive(Debug, Deserialize)]
+pub struct BookingSearchParams {
+    /// Guest name, phone, or email; booking number; or room number.
+    pub q: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// Bookings to fetch in one request, in the order they should come back.
+#[derive(Debug, Deserialize)]
+pub struct BookingBatchRequest {
+    pub ids: Vec<i64>,
+}
+
+/// The requested bookings in request order, and the ids that matched none.
+#[derive(Debug, Serialize)]
+pub struct BookingBatchResponse {
+    pub bookings: Vec<BookingWithDetails>,
+    pub not_found: Vec<i64>,
+}
+
+/// Query parameters for the booking conflict preview.
+#[derive(Debug, Deserialize)]
+pub struct BookingConflictParams {
+    pub room_id: i64,
+    pub check_in: String,
+    pub check_out: String,
+}
+
+/// Whether a room is free for a date range, and which bookings block it.
+#[derive(Debug, Serialize)]
+pub struct BookingConflictCheck {
+    pub available: bool,
+    pub conflicting_booking_numbers: Vec<String>,
+}
+
+/// Query parameters for the stay extension check.
+#[derive(Debug, Deserialize)]
+pub struct StayExtensionParams {
+    pub check_out_date: String,
+}
+
+/// Whether a booking's room is free to extend the stay to a later check-out,
+/// and what the added nights cost.
+#[derive(Debug, Serialize)]
+pub struct StayExtensionCheck {
+    pub booking_id: i64,
+    pub room_id: i64,
+    pub current_check_out_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub available: bool,
+    pub conflicting_booking_numbers: Vec<String>,
+    /// The added nights, priced as a booking for them would be
+    pub nightly_rates: Vec<NightlyRate>,
+    /// Price of the added nights, taxes included
+    pub additional_amount: Decimal,
+    /// Service tax contained in `additional_amount`
+    pub service_tax: Decimal,
+    pub currency: String,
+}
+
 /// Lightweight booking statistics.
 #[derive(Debug, Serialize)]
 pub struct BookingStats {
@@ -51,7 +116,7 @@ pub struct PaginatedResponse<T: Serialize> {
 }
 
 /// Core booking entity
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
 pub struct Booking {
     pub id: i64,
     pub booking_number: String,
@@ -105,6 +170,10 @@ pub struct BookingInput {
     pub room_id: i64,
     pub check_in_date: String,
     pub check_out_date: String,
+    /// Party size; defaults to one adult and no children or infants.
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
+    pub infants: Option<i32>,
     pub post_type: Option<String>,
     pub rate_code: Option<String>,
     pub booking_remarks: Option<String>,
@@ -123,6 +192,117 @@ pub struct BookingInput {
     pub room_rate_override: Option<f64>,
     pub special_requests: Option<String>,
     pub daily_rates: Option<serde_json::Value>,
+    /// Reserve the room's type only; `room_id` is provisional until a room is
+    /// assigned at check-in or by the night audit.
+    pub room_type_only: Option<bool>,
+    /// Company the booking is made for; it must be active.
+    pub company_id: Option<i64>,
+}
+
+/// Input for booking several rooms for one guest and stay in one request.
+/// The party size and pricing fields apply to each room.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupBookingInput {
+    pub guest_id: i64,
+    pub room_ids: Vec<i64>,
+    pub check_in_date: String,
+    pub check_out_date: String,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
+    pub infants: Option<i32>,
+    pub payment_method: Option<String>,
+    pub source: Option<String>,
+    pub booking_remarks: Option<String>,
+    pub special_requests: Option<String>,
+    pub room_rate_override: Option<f64>,
+}
+
+/// The bookings created by a group booking and what they cost together.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupBooking {
+    pub group_booking_number: String,
+    pub bookings: Vec<Booking>,
+    pub total_amount: Decimal,
+}
+
+/// Input for checking in a guest who arrives without a booking. The stay
+/// starts today; give `guest_id` for a known guest or `guest` to create one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalkInInput {
+    pub guest_id: Option<i64>,
+    pub guest: Option<GuestInput>,
+    pub room_id: i64,
+    pub check_out_date: NaiveDate,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
+    pub infants: Option<i32>,
+    pub payment_method: Option<String>,
+    pub booking_remarks: Option<String>,
+    pub special_requests: Option<String>,
+    pub room_rate_override: Option<f64>,
+}
+
+/// A walk-in's booking, already checked in, with its guest.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalkIn {
+    pub booking: Booking,
+    pub guest: Guest,
+}
+
+/// Input for pricing a stay without booking it: the pricing fields of
+/// [`BookingInput`]. `guest_id` is optional; when given, the guest's tourism
+/// type decides whether tourism tax applies, as it does for a booking.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BookingQuoteInput {
+    pub guest_id: Option<i64>,
+    pub room_id: i64,
+    pub check_in_date: String,
+    pub check_out_date: String,
+    pub adults: Option<i32>,
+    pub children: Option<i32>,
+    pub infants: Option<i32>,
+    pub is_tourist: Option<bool>,
+    pub tourism_tax_amount: Option<f64>,
+    pub extra_bed_count: Option<i32>,
+    pub extra_bed_charge: Option<f64>,
+    pub room_rate_override: Option<f64>,
+    pub daily_rates: Option<serde_json::Value>,
+}
+
+/// A tax or fee line of a quote.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuoteCharge {
+    pub code: String,
+    pub description: String,
+    pub amount: Decimal,
+    /// Already part of `subtotal` (service tax in tax-inclusive room prices)
+    /// rather than charged on top.
+    pub included: bool,
+}
+
+/// The price of a hypothetical stay and whether the room is free for it.
+#[derive(Debug, Serialize)]
+pub struct BookingQuote {
+    pub room_id: i64,
+    pub room_number: String,
+    pub room_type: String,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub is_hourly: bool,
+    pub nightly_rates: Vec<NightlyRate>,
+    /// Room charges for the stay, tax-inclusive.
+    pub subtotal: Decimal,
+    pub taxes: Vec<QuoteCharge>,
+    pub fees: Vec<QuoteCharge>,
+    /// The booking's `total_amount` for the same inputs.
+    pub total_amount: Decimal,
+    /// `total_amount` plus the taxes and fees posted on top of it.
+    pub amount_due: Decimal,
+    /// ISO 4217 code of the amounts
+    pub currency: String,
+    pub available: bool,
+    pub unavailable_reason: Option<String>,
+    pub conflicting_booking_numbers: Vec<String>,
 }
 
 /// Input for cancelling a booking
@@ -132,6 +312,39 @@ pub struct BookingCancellationRequest {
     pub reason: Option<String>,
 }
 
+/// Input for cancelling a booking under the cancellation policy
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CancelBookingInput {
+    pub reason: Option<String>,
+}
+
+/// Outcome of cancelling a booking: the fee charged and what is refundable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingCancellation {
+    pub booking_id: i64,
+    pub booking_number: String,
+    pub status: String,
+    pub days_before_check_in: i64,
+    pub free_cancellation: bool,
+    pub cancellation_fee: Decimal,
+    pub amount_paid: Decimal,
+    pub refund_amount: Decimal,
+    pub currency: String,
+}
+
+/// Outcome of cancelling a group: each booking cancelled now, the bookings
+/// skipped because they were already cancelled, and the combined fee and
+/// refund
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupCancellation {
+    pub group_booking_number: String,
+    pub cancelled: Vec<BookingCancellation>,
+    pub already_cancelled: Vec<String>,
+    pub total_cancellation_fee: Decimal,
+    pub total_refund_amount: Decimal,
+    pub currency: String,
+}
+
 /// Input for updating a booking
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BookingUpdateInput {
@@ -184,6 +397,15 @@ pub struct CheckInRequest {
     pub payment_record: Option<CheckInPaymentRecord>,
 }
 
+/// Request for checking out a guest
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CheckOutRequest {
+    /// Check out even though the guest still owes money.
+    #[serde(default)]
+    pub override_balance: bool,
+    pub notes: Option<String>,
+}
+
 /// Request for pre-check-in update
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PreCheckInUpdateRequest {
@@ -315,3 +537,45 @@ pub struct BookingTimelineEntry {
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
+
+/// A file attached to a booking, such as a scanned registration card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct BookingAttachment {
+    pub id: i64,
+    pub booking_id: i64,
+    /// Name of the file as uploaded
+    pub file_name: String,
+    pub file_url: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub uploaded_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for setting a booking's folio split: charge category (`room`,
+/// `extra_bed`, `late_checkout`, `tourism_tax`, `services`) to payer
+/// (`guest` or `company`). Categories left out stay on the guest folio; an
+/// empty map removes the split.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolioSplitInput {
+    pub routes: BTreeMap<String, String>,
+}
+
+/// One charge category's amount on a folio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolioLine {
+    pub category: String,
+    pub amount: Decimal,
+}
+
+/// A booking's folio split and the charges billed to each payer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookingFolioSplit {
+    pub booking_id: i64,
+    pub company_name: Option<String>,
+    pub routes: BTreeMap<String, String>,
+    pub guest_folio: Vec<FolioLine>,
+    pub company_ledger: Vec<FolioLine>,
+    pub guest_total: Decimal,
+    pub company_total: Decimal,
+}
@@ -10,6 +10,8 @@ pub struct SearchQuery {
     pub check_in_date: Option<String>,
     pub check_out_date: Option<String>,
     pub exclude_booking_id: Option<i64>,
+    /// Only return rooms whose type holds at least this many guests.
+    pub guests: Option<i32>,
 }
 
 /// Pagination parameters
@@ -28,3 +30,25 @@ impl PaginationParams {
         self.offset.unwrap_or(0) as i64
     }
 }
+
+/// Page and sort parameters for list endpoints with a sort whitelist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListParams {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Column to sort by; must be one the endpoint allows.
+    pub sort: Option<String>,
+    /// Sort direction: asc | desc.
+    pub order: Option<String>,
+}
+
+/// An update input whose fields are all optional, where an unset field keeps
+/// the current value.
+///
+/// Implementations destructure the input without `..`, so a field added
+/// later cannot be left out of `is_empty`.
+pub trait PartialUpdate {
+    /// True when no field is set, so the update can return the current
+    /// record without writing.
+    fn is_empty(&self) -> bool;
+}
@@ -1,6 +1,7 @@
 //! Company models for direct billing
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -72,3 +73,47 @@ pub struct CompanyListQuery {
     pub limit: Option<i32>,
     pub offset: Option<i32>,
 }
+
+/// Query parameters for a company's bookings; both dates bound the check-in
+/// date and are optional.
+#[derive(Debug, Deserialize)]
+pub struct CompanyBookingsQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// A booking attributed to a company
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompanyBooking {
+    pub id: i64,
+    pub booking_number: String,
+    pub guest_name: Option<String>,
+    pub room_number: Option<String>,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub status: Option<String>,
+    pub total_amount: Decimal,
+    /// Moved to `bookings_archive`
+    pub archived: bool,
+}
+
+/// A company's bookings with their revenue
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanyBookings {
+    pub company_id: i64,
+    pub company_name: String,
+    /// Bookings that were not voided, cancelled or no-shows
+    pub stays: i64,
+    /// Total of those stays
+    pub revenue: Decimal,
+    pub bookings: Vec<CompanyBooking>,
+}
+
+/// One company's line in the revenue report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompanyRevenue {
+    pub company_id: i64,
+    pub company_name: Option<String>,
+    pub bookings: i64,
+    pub revenue: Decimal,
+}
@@ -38,6 +38,23 @@ pub struct Guest {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[sqlx(default)]
     pub last_stay_date: Option<chrono::NaiveDate>,
+    /// Flagged as a VIP, by staff or by stay history (see `services::vip`)
+    #[serde(default)]
+    #[sqlx(default)]
+    pub is_vip: bool,
+    /// Consent to email as chosen; `None` until the guest chooses, when the
+    /// default setting applies (see `services::communication`)
+    #[serde(default)]
+    #[sqlx(default)]
+    pub email_opt_in: Option<bool>,
+    /// Consent to promotional messages; off until the guest opts in
+    #[serde(default)]
+    #[sqlx(default)]
+    pub marketing_opt_in: bool,
+    /// When the guest's consent last changed
+    #[serde(default)]
+    #[sqlx(default)]
+    pub consent_timestamp: Option<DateTime<Utc>>,
 }
 
 /// Input for creating a guest
@@ -145,6 +162,10 @@ pub struct GuestPaginationParams {
     pub search: Option<String>,
     /// Filter by guest type: "member" or "non_member".
     pub guest_type: Option<String>,
+    /// Column to sort by.
+    pub sort_by: Option<String>,
+    /// Sort direction: asc | desc.
+    pub sort_order: Option<String>,
 }
 
 /// Paginated guest list response.
@@ -155,3 +176,58 @@ pub struct GuestPaginatedResponse {
     pub page: i64,
     pub page_size: i64,
 }
+
+/// Query parameters for the upcoming-arrivals list.
+#[derive(Debug, Deserialize)]
+pub struct ArrivingGuestsParams {
+    /// Days ahead of the hotel's today to include (default 7).
+    pub within_days: Option<i64>,
+}
+
+/// A guest with a confirmed stay starting soon, for pre-arrival outreach.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArrivingGuest {
+    pub guest_id: i64,
+    pub full_name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub arrival_date: chrono::NaiveDate,
+    pub room_number: Option<String>,
+    pub is_vip: bool,
+}
+
+/// Pagination for a guest's stay history.
+#[derive(Debug, Deserialize)]
+pub struct GuestStaysParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// One of a guest's past or upcoming stays, with what they thought of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestStay {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub room_number: String,
+    pub room_type: Option<String>,
+    pub check_in_date: chrono::NaiveDate,
+    pub check_out_date: chrono::NaiveDate,
+    pub nights: i64,
+    pub total_amount: rust_decimal::Decimal,
+    pub status: String,
+    pub reviews: Vec<GuestStayReview>,
+}
+
+/// A review the guest left for a stay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestStayReview {
+    pub id: i64,
+    pub overall_rating: rust_decimal::Decimal,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    /// The hotel's reply, if any
+    pub response: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,6 +1,8 @@
 //! Guest portal API models.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
 use super::{Booking, Guest};
 
@@ -25,3 +27,92 @@ pub struct GuestPortalBookingResponse {
     pub booking: Booking,
     pub guest: Guest,
 }
+
+/// The signed-in user's own guest record, as shown in the portal.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct GuestPortalProfile {
+    pub guest_id: i64,
+    pub full_name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub alt_phone: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    pub state_province: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Contact details a guest may change from the portal. Omitted fields are
+/// left as they are; blank ones are cleared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct GuestPortalProfileUpdate {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub alt_phone: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    pub state_province: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Whether a guest may be contacted on each channel, after applying the
+/// default settings to channels they have not chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CommunicationPreferences {
+    pub email_opt_in: bool,
+    pub sms_opt_in: bool,
+    /// Promotional messages on any channel; off until the guest opts in
+    pub marketing_opt_in: bool,
+    /// When any of the above last changed; `None` if it never has
+    pub consent_timestamp: Option<DateTime<Utc>>,
+}
+
+/// Consent changes from the portal or staff. Omitted channels are left as
+/// they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct CommunicationPreferencesUpdate {
+    pub email_opt_in: Option<bool>,
+    pub sms_opt_in: Option<bool>,
+    pub marketing_opt_in: Option<bool>,
+}
+
+/// One consent given or revoked, from the guest's consent history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromRow)]
+pub struct ConsentChange {
+    pub id: i64,
+    /// email | sms | marketing
+    pub channel: String,
+    pub opted_in: bool,
+    /// Staff member or portal account that made the change
+    pub changed_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A review of one of the guest's own stays. Ratings run from 1 to 5.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct GuestReviewInput {
+    pub overall_rating: f64,
+    pub cleanliness_rating: Option<f64>,
+    pub service_rating: Option<f64>,
+    pub comfort_rating: Option<f64>,
+    pub location_rating: Option<f64>,
+    pub value_rating: Option<f64>,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub pros: Option<String>,
+    pub cons: Option<String>,
+}
+
+/// A saved review and the loyalty points it earned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GuestReviewSubmitted {
+    pub review_id: i64,
+    pub booking_id: i64,
+    /// `None` when the review earned no points, including a repeat review
+    /// of a stay that was already rewarded
+    pub points_awarded: Option<i32>,
+}
@@ -224,3 +224,47 @@ pub struct LedgerPaginatedResponse {
     pub page: i64,
     pub page_size: i64,
 }
+
+/// One invoice's share of a company payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanyPaymentAllocation {
+    pub ledger_id: i64,
+    pub amount: f64,
+}
+
+/// Input for posting one payment across a company's open invoices.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanyPaymentRequest {
+    pub amount: f64,
+    pub payment_method: String,
+    pub payment_reference: Option<String>,
+    pub notes: Option<String>,
+    pub payment_date: Option<String>,
+    /// Explicit split across invoices. When omitted the amount is applied to
+    /// the oldest open invoices first.
+    pub allocations: Option<Vec<CompanyPaymentAllocation>>,
+}
+
+/// A ledger entry after a company payment was applied to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedLedgerPayment {
+    pub ledger_id: i64,
+    pub payment_id: i64,
+    pub invoice_number: Option<String>,
+    pub amount_applied: Decimal,
+    pub balance_due: Decimal,
+    pub status: String,
+}
+
+/// Result of posting a company payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanyPaymentResult {
+    pub company_id: i64,
+    pub company_name: String,
+    pub amount: Decimal,
+    pub amount_applied: Decimal,
+    /// Unapplied remainder, recorded as a credit entry on the company ledger
+    pub credit_amount: Decimal,
+    pub credit_ledger_id: Option<i64>,
+    pub applied: Vec<AppliedLedgerPayment>,
+}
@@ -69,6 +69,97 @@ pub struct PointsTransaction {
     pub created_at: DateTime<Utc>,
 }
 
+/// Pagination and filter for a member's own points history.
+#[derive(Debug, Default, Deserialize)]
+pub struct PointsTransactionsParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    /// earn | redeem | transfer | expire
+    #[serde(rename = "type")]
+    pub transaction_type: Option<String>,
+}
+
+/// Query for downloading the loyalty statistics.
+#[derive(Debug, Default, Deserialize)]
+pub struct LoyaltyStatisticsExportParams {
+    /// json (default) | csv
+    pub format: Option<String>,
+}
+
+/// Result of recomputing a membership's balances from its transactions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PointsRecomputeResult {
+    pub membership_id: i64,
+    pub previous_points_balance: i32,
+    pub previous_lifetime_points: i32,
+    pub points_balance: i32,
+    pub lifetime_points: i32,
+    /// Whether the stored balances were wrong and have been corrected
+    pub corrected: bool,
+}
+
+/// Input for adding points to every active membership matching a filter,
+/// such as a promotion for one tier
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkPointsInput {
+    /// Only memberships at this tier level; all tiers when absent
+    pub tier_level: Option<i32>,
+    /// Only memberships enrolled before this date
+    pub enrolled_before: Option<NaiveDate>,
+    pub points: i32,
+    pub description: String,
+}
+
+/// Result of a bulk points adjustment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulkPointsResult {
+    pub points: i32,
+    /// Memberships credited, each with its own `earn` transaction
+    pub memberships_adjusted: i64,
+}
+
+/// Input for renewing a membership
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RenewMembershipInput {
+    /// Term to add; defaults to `loyalty_membership_term_months`
+    pub months: Option<u32>,
+}
+
+/// Result of renewing a membership
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MembershipRenewal {
+    pub membership_id: i64,
+    pub previous_status: String,
+    pub previous_expires_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Input for moving points from one membership to another
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferPointsInput {
+    pub from_membership_id: i64,
+    pub to_membership_id: i64,
+    pub points: i32,
+    pub reason: String,
+}
+
+/// Result of a points transfer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PointsTransferResult {
+    pub from_membership_id: i64,
+    pub to_membership_id: i64,
+    pub points: i32,
+    /// Source balance after the transfer
+    pub from_points_balance: i32,
+    /// Destination balance after the transfer
+    pub to_points_balance: i32,
+    /// The source's `transfer` transaction (negative points)
+    pub debit_transaction_id: i64,
+    /// The destination's `transfer` transaction (positive points)
+    pub credit_transaction_id: i64,
+}
+
 /// Statistics by tier
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct TierStatistics {
@@ -161,6 +252,11 @@ pub struct UserLoyaltyMembership {
     pub next_tier: Option<TierInfo>,
     pub current_tier_benefits: Vec<String>,
     pub points_to_next_tier: Option<i32>,
+    /// Lifetime points the current tier starts at
+    pub current_tier_minimum: i32,
+    /// Percent of the way from the current tier to the next (100 at the top)
+    pub progress_percent: f64,
+    pub is_top_tier: bool,
     pub recent_transactions: Vec<PointsTransaction>,
 }
 
@@ -173,3 +269,84 @@ pub struct TierInfo {
     pub benefits: Vec<String>,
     pub points_multiplier: Decimal,
 }
+
+/// Kinds of points-earning rule. The snake_case name is what the API
+/// accepts and what `points_rules.rule_type` stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PointsRuleType {
+    /// Adds `bonus_points` to the stay
+    Bonus,
+    /// Multiplies the stay's base points by `multiplier`
+    Multiplier,
+    /// Guarantees a paid stay at least `bonus_points`
+    Floor,
+}
+
+impl PointsRuleType {
+    pub const ALL: [PointsRuleType; 3] = [
+        PointsRuleType::Bonus,
+        PointsRuleType::Multiplier,
+        PointsRuleType::Floor,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PointsRuleType::Bonus => "bonus",
+            PointsRuleType::Multiplier => "multiplier",
+            PointsRuleType::Floor => "floor",
+        }
+    }
+}
+
+impl std::fmt::Display for PointsRuleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PointsRuleType {
+    type Err = String;
+
+    /// The error names the `rule_type` field and lists the valid values.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        PointsRuleType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == value)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = PointsRuleType::ALL.iter().map(|t| t.as_str()).collect();
+                format!("rule_type must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// A points-earning rule. Rules limited to a room type or a window of
+/// check-in dates apply only to stays that match.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PointsRule {
+    pub id: i64,
+    pub name: String,
+    pub rule_type: String,
+    pub room_type_id: Option<i64>,
+    pub bonus_points: Option<i32>,
+    pub multiplier: Option<Decimal>,
+    pub starts_on: Option<NaiveDate>,
+    pub ends_on: Option<NaiveDate>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for creating or replacing a points-earning rule
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PointsRuleInput {
+    pub name: String,
+    pub rule_type: String,
+    pub room_type_id: Option<i64>,
+    pub bonus_points: Option<i32>,
+    pub multiplier: Option<f64>,
+    pub starts_on: Option<NaiveDate>,
+    pub ends_on: Option<NaiveDate>,
+    pub is_active: Option<bool>,
+}
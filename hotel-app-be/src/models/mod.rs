@@ -3,6 +3,7 @@
 //! Each module contains models for a specific domain of the application.
 
 pub mod analytics;
+pub mod api_key;
 pub mod audit;
 pub mod auth;
 pub mod booking;
@@ -34,6 +35,7 @@ pub mod user;
 
 // Re-export all models for convenience
 pub use analytics::*;
+pub use api_key::*;
 pub use audit::*;
 pub use auth::*;
 pub use booking::*;
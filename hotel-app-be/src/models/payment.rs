@@ -69,11 +69,41 @@ pub struct PaymentWorkflowSummary {
     pub balance_due: Decimal,
     pub deposit_collected: Decimal,
     pub deposit_refunded: Decimal,
+    pub currency: String,
     pub has_failed_payment: bool,
     pub next_action: String,
     pub warnings: Vec<String>,
 }
 
+/// Outstanding amount on one booking of a guest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookingBalance {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub status: String,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub total_amount: Decimal,
+    pub total_paid: Decimal,
+    /// `"guest"`, or `"company"` for company-billed stays
+    pub billed_to: String,
+    pub company_name: Option<String>,
+    pub balance_due: Decimal,
+}
+
+/// A guest's outstanding balance across bookings, for collections.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestBalance {
+    pub guest_id: i64,
+    /// Owed by the guest directly
+    pub guest_outstanding: Decimal,
+    /// Owed by companies for the guest's company-billed stays
+    pub company_outstanding: Decimal,
+    pub total_outstanding: Decimal,
+    /// Bookings with a balance due, oldest stay first
+    pub bookings: Vec<BookingBalance>,
+}
+
 /// Invoice record
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Invoice {
@@ -145,3 +145,29 @@ pub struct ApplicableRateQuery {
     pub room_type_id: i64,
     pub date: String,
 }
+
+/// Query for the rate calendar (`start` and `end` inclusive, YYYY-MM-DD).
+#[derive(Debug, Deserialize)]
+pub struct RateCalendarQuery {
+    pub room_type_id: i64,
+    pub start: String,
+    pub end: String,
+}
+
+/// The resolved price of one night and the plan it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NightlyRate {
+    pub date: NaiveDate,
+    pub price: Decimal,
+    pub rate_plan_code: String,
+    pub is_base_rate: bool,
+}
+
+/// Nightly rates for a room type across a date range.
+#[derive(Debug, Serialize)]
+pub struct RateCalendar {
+    pub room_type_id: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub days: Vec<NightlyRate>,
+}
@@ -49,6 +49,17 @@ pub struct AssignRoleInput {
     pub role_id: i64,
 }
 
+/// Outcome of assigning a role to a user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleAssignmentResult {
+    pub user_id: i64,
+    pub role_id: i64,
+    pub role_name: String,
+    /// False when the user already held the role
+    pub created: bool,
+    pub message: String,
+}
+
 /// Input for assigning a permission to a role
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AssignPermissionInput {
@@ -56,6 +67,23 @@ pub struct AssignPermissionInput {
     pub permission_id: i64,
 }
 
+/// One row of a batch grant: permissions to add to a role
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleGrantInput {
+    pub role_id: i64,
+    pub permission_ids: Vec<i64>,
+}
+
+/// A role's permissions after a batch grant
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleGrantResult {
+    pub role_id: i64,
+    /// All permissions the role now holds
+    pub permission_ids: Vec<i64>,
+    /// Permissions this request added; ones already held are skipped
+    pub granted: Vec<i64>,
+}
+
 /// Role with its permissions
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RoleWithPermissions {
@@ -5,6 +5,68 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use super::common::PartialUpdate;
+
+/// Reward catalog categories. The snake_case name is what the API accepts
+/// and what `loyalty_rewards.category` stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RewardCategory {
+    RoomUpgrade,
+    Service,
+    Discount,
+    Gift,
+    Dining,
+    Spa,
+    Experience,
+}
+
+impl RewardCategory {
+    pub const ALL: [RewardCategory; 7] = [
+        RewardCategory::RoomUpgrade,
+        RewardCategory::Service,
+        RewardCategory::Discount,
+        RewardCategory::Gift,
+        RewardCategory::Dining,
+        RewardCategory::Spa,
+        RewardCategory::Experience,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RewardCategory::RoomUpgrade => "room_upgrade",
+            RewardCategory::Service => "service",
+            RewardCategory::Discount => "discount",
+            RewardCategory::Gift => "gift",
+            RewardCategory::Dining => "dining",
+            RewardCategory::Spa => "spa",
+            RewardCategory::Experience => "experience",
+        }
+    }
+}
+
+impl std::fmt::Display for RewardCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RewardCategory {
+    type Err = String;
+
+    /// The error names the `category` field and lists the valid values.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        RewardCategory::ALL
+            .into_iter()
+            .find(|c| c.as_str() == value)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = RewardCategory::ALL.iter().map(|c| c.as_str()).collect();
+                format!("category must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
 /// Loyalty reward in the catalog
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct LoyaltyReward {
@@ -60,6 +122,33 @@ pub struct RewardUpdateInput {
     pub terms_conditions: Option<String>,
 }
 
+impl PartialUpdate for RewardUpdateInput {
+    fn is_empty(&self) -> bool {
+        let Self {
+            name,
+            description,
+            category,
+            points_cost,
+            monetary_value,
+            minimum_tier_level,
+            is_active,
+            stock_quantity,
+            image_url,
+            terms_conditions,
+        } = self;
+        name.is_none()
+            && description.is_none()
+            && category.is_none()
+            && points_cost.is_none()
+            && monetary_value.is_none()
+            && minimum_tier_level.is_none()
+            && is_active.is_none()
+            && stock_quantity.is_none()
+            && image_url.is_none()
+            && terms_conditions.is_none()
+    }
+}
+
 /// Reward redemption record
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct RewardRedemption {
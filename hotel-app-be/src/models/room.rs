@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 use super::booking::BookingWithDetails;
+use super::common::PartialUpdate;
+use super::rate::NightlyRate;
 
 /// Core room entity - Note: This struct is used for manual construction
 /// The actual DB columns differ but handlers construct this for API responses
@@ -62,6 +64,27 @@ pub struct RoomUpdateInput {
     pub notes: Option<String>,
 }
 
+impl PartialUpdate for RoomUpdateInput {
+    fn is_empty(&self) -> bool {
+        let Self {
+            room_number,
+            room_type,
+            price_per_night,
+            available,
+            description,
+            max_occupancy,
+            notes,
+        } = self;
+        room_number.is_none()
+            && room_type.is_none()
+            && price_per_night.is_none()
+            && available.is_none()
+            && description.is_none()
+            && max_occupancy.is_none()
+            && notes.is_none()
+    }
+}
+
 /// Input for updating room status
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RoomStatusUpdateInput {
@@ -129,6 +152,18 @@ pub struct RoomEventInput {
     pub priority: Option<String>,
 }
 
+/// Sort and filter parameters for the rooms list.
+#[derive(Debug, Default, Deserialize)]
+pub struct RoomListParams {
+    /// Column to sort by.
+    pub sort_by: Option<String>,
+    /// Sort direction: asc | desc.
+    pub sort_order: Option<String>,
+    /// Only rooms currently in one of these statuses, comma-separated,
+    /// e.g. `dirty,cleaning`.
+    pub status: Option<String>,
+}
+
 /// Room with rating information
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct RoomWithRating {
@@ -300,3 +335,117 @@ pub struct RoomWithOccupancy {
     pub current_booking_id: Option<i64>,
     pub current_guest_id: Option<i64>,
 }
+
+/// Query parameters for the reservation timeline
+#[derive(Debug, Deserialize)]
+pub struct ReservationTimelineQuery {
+    pub start: String,
+    pub end: String,
+}
+
+/// Query parameters for one room's booking schedule. `from` defaults to the
+/// hotel's today and `to` to 30 days after `from`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RoomBookingsQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A booking placed on the reservation timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineBooking {
+    pub booking_id: i64,
+    pub booking_number: String,
+    pub guest_id: i64,
+    pub guest_name: Option<String>,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub status: String,
+}
+
+/// One row of the reservation timeline: a room and its bookings in range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineRoom {
+    pub room_id: i64,
+    pub room_number: String,
+    pub room_type: String,
+    /// Today's dynamic status, identical to the rooms list
+    pub current_status: String,
+    pub bookings: Vec<TimelineBooking>,
+}
+
+/// Reservation timeline across a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationTimeline {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub rooms: Vec<TimelineRoom>,
+}
+
+/// A room's non-cancelled bookings across a date range, by check-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomSchedule {
+    pub room_id: i64,
+    pub room_number: String,
+    pub from_date: NaiveDate,
+    pub to_date: NaiveDate,
+    pub bookings: Vec<TimelineBooking>,
+}
+
+/// Input for blocking a room from sale for the nights `start_date` up to,
+/// but not including, `end_date`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomBlockInput {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// owner_use | staff_housing | renovation | other
+    pub reason: String,
+    pub notes: Option<String>,
+}
+
+/// A hold keeping a room off sale for a date range, other than maintenance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct RoomBlock {
+    pub id: i64,
+    pub room_id: i64,
+    pub start_date: NaiveDate,
+    /// First night the room is free again
+    pub end_date: NaiveDate,
+    pub reason: String,
+    pub notes: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters for the priced room search
+#[derive(Debug, Deserialize)]
+pub struct PricedRoomSearchQuery {
+    pub check_in: String,
+    pub check_out: String,
+    pub guests: Option<i32>,
+    pub room_type: Option<String>,
+}
+
+/// A room free for the whole stay, priced as a booking would be
+#[derive(Debug, Clone, Serialize)]
+pub struct PricedRoom {
+    #[serde(flatten)]
+    pub room: RoomWithRating,
+    pub nights: i64,
+    pub nightly_breakdown: Vec<NightlyRate>,
+    /// Service tax contained in `total_price`
+    pub service_tax: Decimal,
+    /// Price of the stay, taxes included
+    pub total_price: Decimal,
+    /// ISO 4217 code of the prices
+    pub currency: String,
+}
+
+/// A room whose stored status was corrected to match its bookings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoomStatusCorrection {
+    pub room_id: i64,
+    pub room_number: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+}
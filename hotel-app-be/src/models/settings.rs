@@ -1,6 +1,7 @@
 //! System settings models
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -33,3 +34,20 @@ pub struct RateCodesResponse {
 pub struct MarketCodesResponse {
     pub market_codes: Vec<String>,
 }
+
+/// Response containing the booking sources (channels) bookings may use
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BookingSourcesResponse {
+    pub booking_sources: Vec<String>,
+    /// Given to bookings made without a source
+    pub default_source: String,
+}
+
+/// The hotel's standard check-in and check-out times, `HH:MM`, and the
+/// hourly late-checkout fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StayPolicyResponse {
+    pub check_in_time: String,
+    pub check_out_time: String,
+    pub late_checkout_fee_per_hour: Decimal,
+}
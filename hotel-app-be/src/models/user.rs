@@ -72,6 +72,8 @@ pub struct UserProfile {
     pub full_name: Option<String>,
     pub phone: Option<String>,
     pub avatar_url: Option<String>,
+    /// Set for avatars uploaded through `POST /profile/avatar`
+    pub avatar_thumbnail_url: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_login_at: Option<DateTime<Utc>>,
@@ -86,6 +88,13 @@ pub struct UserProfileUpdate {
     pub avatar_url: Option<String>,
 }
 
+/// Where an uploaded avatar was stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvatarUpload {
+    pub avatar_url: String,
+    pub thumbnail_url: String,
+}
+
 /// Input for changing password
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PasswordUpdateInput {
@@ -1,198 +1,266 @@
 //! Booking repository for database operations
+//!
+//! [`BookingRepository`] is the storage interface the booking handlers use
+//! for single-booking reads and writes; [`SqlBookingRepository`] implements
+//! it over the active pool. Work that must hold a room lock inside a larger
+//! transaction (booking creation, moving or reactivating a stay) takes it
+//! with [`lock_room`] and runs [`CONFLICTING_BOOKINGS_QUERY`] on its own
+//! transaction so the rule stays the same.
+
+use std::future::Future;
 
-use crate::core::db::DbPool;
-use crate::core::error::ApiError;
-use crate::models::{Booking, BookingWithDetails};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 
-pub struct BookingRepository;
-
-impl BookingRepository {
-    /// Find all bookings with details
-    pub async fn find_all_with_details(pool: &DbPool) -> Result<Vec<BookingWithDetails>, ApiError> {
-        sqlx::query_as::<_, BookingWithDetails>(
-            r#"
-            SELECT b.id, b.booking_number, b.guest_id, g.full_name as guest_name, g.email as guest_email,
-                   b.room_id, r.room_number, rt.name as room_type, rt.code as room_type_code,
-                   b.check_in_date, b.check_out_date, b.room_rate, b.total_amount, b.status,
-                   b.payment_status, b.payment_method, b.source, b.remarks, b.created_at
-            FROM bookings b
-            JOIN guests g ON b.guest_id = g.id
-            JOIN rooms r ON b.room_id = r.id
-            LEFT JOIN room_types rt ON r.room_type_id = rt.id
-            ORDER BY b.created_at DESC
-            "#
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))
-    }
+use crate::core::db::{DbConnection, DbPool, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::models::{Booking, row_mappers};
+
+/// Statuses that hold a room; voided, checked-out and completed stays don't.
+pub const BLOCKING_STATUSES: &[&str] = &["reserved", "confirmed", "checked_in", "pending"];
+
+/// Whether an existing stay overlaps `[check_in, check_out)`. Arriving on
+/// another guest's departure day is not a conflict. Mirrors the date test in
+/// [`CONFLICTING_BOOKINGS_QUERY`].
+pub fn stays_overlap(
+    existing_in: NaiveDate,
+    existing_out: NaiveDate,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> bool {
+    (existing_in <= check_in && existing_out > check_in)
+        || (existing_in < check_out && existing_out >= check_out)
+        || (existing_in >= check_in && existing_out <= check_out)
+}
 
-    /// Find booking by ID
-    pub async fn find_by_id(pool: &DbPool, id: i64) -> Result<Option<Booking>, ApiError> {
-        sqlx::query_as::<_, Booking>(
-            r#"
-            SELECT id, booking_number, guest_id, room_id, check_in_date, check_out_date,
-                   room_rate, subtotal, tax_amount, discount_amount, total_amount, status,
-                   payment_status, adults, children, special_requests, remarks, source,
-                   market_code, discount_percentage, rate_override_weekday, rate_override_weekend,
-                   pre_checkin_completed, pre_checkin_completed_at, pre_checkin_token,
-                   pre_checkin_token_expires_at, created_by, created_at, updated_at
-            FROM bookings
-            WHERE id = $1
-            "#,
+/// Whether `booking` keeps `room_id` from being booked for the stay.
+pub fn blocks_stay(
+    booking: &Booking,
+    room_id: i64,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> bool {
+    booking.room_id == room_id
+        && BLOCKING_STATUSES.contains(&booking.status.as_str())
+        && stays_overlap(
+            booking.check_in_date,
+            booking.check_out_date,
+            check_in,
+            check_out,
         )
-        .bind(id)
-        .fetch_optional(pool)
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub const CONFLICTING_BOOKINGS_QUERY: &str = r#"
+    SELECT booking_number FROM bookings
+    WHERE room_id = ?1 AND status IN ('reserved', 'confirmed', 'checked_in', 'pending')
+    AND ((check_in_date <= ?2 AND check_out_date > ?2)
+        OR (check_in_date < ?3 AND check_out_date >= ?3)
+        OR (check_in_date >= ?2 AND check_out_date <= ?3))
+    ORDER BY check_in_date, id
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+pub const CONFLICTING_BOOKINGS_QUERY: &str = r#"
+    SELECT booking_number FROM bookings
+    WHERE room_id = $1 AND status IN ('reserved', 'confirmed', 'checked_in', 'pending')
+    AND ((check_in_date <= $2 AND check_out_date > $2)
+        OR (check_in_date < $3 AND check_out_date >= $3)
+        OR (check_in_date >= $2 AND check_out_date <= $3))
+    ORDER BY check_in_date, id
+"#;
+
+/// Lock `room_id` until the transaction ends, so a conflict check and the
+/// write that follows it cannot interleave with another booking of the room.
+/// SQLite has no row locks, so a no-op write takes the database write lock
+/// instead; a second writer waits on the busy timeout and then sees the
+/// first one's booking.
+pub async fn lock_room(conn: &mut DbConnection, room_id: i64) -> Result<(), ApiError> {
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let query = "SELECT id FROM rooms WHERE id = $1 FOR UPDATE";
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let query = "UPDATE rooms SET id = id WHERE id = $1";
+
+    sqlx::query(query)
+        .bind(room_id)
+        .execute(conn)
         .await
-        .map_err(|e| ApiError::Database(e.to_string()))
-    }
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Fields for a new booking row.
+#[derive(Debug, Clone)]
+pub struct NewBooking {
+    pub booking_number: String,
+    pub guest_id: i64,
+    pub room_id: i64,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub room_rate: Decimal,
+    pub total_amount: Decimal,
+    pub status: String,
+    pub created_by: Option<i64>,
+}
+
+/// Filters for [`BookingRepository::list`]; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct BookingFilter {
+    pub guest_id: Option<i64>,
+    pub room_id: Option<i64>,
+    pub status: Option<String>,
+}
+
+pub trait BookingRepository {
+    fn create(
+        &self,
+        booking: &NewBooking,
+    ) -> impl Future<Output = Result<Booking, ApiError>> + Send;
 
-    /// Find booking with details by ID
-    pub async fn find_by_id_with_details(
-        pool: &DbPool,
+    fn find(&self, id: i64) -> impl Future<Output = Result<Option<Booking>, ApiError>> + Send;
+
+    /// Bookings matching `filter`, by check-in date.
+    fn list(
+        &self,
+        filter: &BookingFilter,
+    ) -> impl Future<Output = Result<Vec<Booking>, ApiError>> + Send;
+
+    /// Set the status; `NotFound` when there is no such booking.
+    fn update_status(
+        &self,
         id: i64,
-    ) -> Result<Option<BookingWithDetails>, ApiError> {
-        sqlx::query_as::<_, BookingWithDetails>(
-            r#"
-            SELECT b.id, b.booking_number, b.guest_id, g.full_name as guest_name, g.email as guest_email,
-                   b.room_id, r.room_number, rt.name as room_type, rt.code as room_type_code,
-                   b.check_in_date, b.check_out_date, b.room_rate, b.total_amount, b.status,
-                   b.payment_status, b.payment_method, b.source, b.remarks, b.created_at
-            FROM bookings b
-            JOIN guests g ON b.guest_id = g.id
-            JOIN rooms r ON b.room_id = r.id
-            LEFT JOIN room_types rt ON r.room_type_id = rt.id
-            WHERE b.id = $1
-            "#
-        )
-        .bind(id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))
-    }
+        status: &str,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send;
 
-    /// Find bookings by guest ID
-    pub async fn find_by_guest_id(
-        pool: &DbPool,
-        guest_id: i64,
-    ) -> Result<Vec<BookingWithDetails>, ApiError> {
-        sqlx::query_as::<_, BookingWithDetails>(
-            r#"
-            SELECT b.id, b.booking_number, b.guest_id, g.full_name as guest_name, g.email as guest_email,
-                   b.room_id, r.room_number, rt.name as room_type, rt.code as room_type_code,
-                   b.check_in_date, b.check_out_date, b.room_rate, b.total_amount, b.status,
-                   b.payment_status, b.payment_method, b.source, b.remarks, b.created_at
-            FROM bookings b
-            JOIN guests g ON b.guest_id = g.id
-            JOIN rooms r ON b.room_id = r.id
-            LEFT JOIN room_types rt ON r.room_type_id = rt.id
-            WHERE b.guest_id = $1
-            ORDER BY b.created_at DESC
-            "#
-        )
-        .bind(guest_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))
+    /// Booking numbers of active stays on `room_id` overlapping the range.
+    fn check_conflict(
+        &self,
+        room_id: i64,
+        check_in: NaiveDate,
+        check_out: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<String>, ApiError>> + Send;
+}
+
+pub struct SqlBookingRepository<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> SqlBookingRepository<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
     }
+}
 
-    /// Create a new booking
-    pub async fn create(
-        pool: &DbPool,
-        guest_id: i64,
-        room_id: i64,
-        check_in_date: NaiveDate,
-        check_out_date: NaiveDate,
-        total_amount: Decimal,
-        created_by: i64,
-    ) -> Result<Booking, ApiError> {
-        let booking_number = format!(
-            "BK-{}-{:06}",
-            chrono::Utc::now().format("%Y%m%d"),
-            rand::random::<u32>() % 1000000
-        );
-        sqlx::query_as::<_, Booking>(
-            r#"
-            INSERT INTO bookings (booking_number, guest_id, room_id, check_in_date, check_out_date, room_rate, subtotal, total_amount, status, created_by, adults)
-            VALUES ($1, $2, $3, $4, $5, $6, $6, $6, 'pending', $7, 1)
-            RETURNING id, booking_number, guest_id, room_id, check_in_date, check_out_date,
-                      room_rate, subtotal, tax_amount, discount_amount, total_amount, status,
-                      payment_status, adults, children, special_requests, remarks, source,
-                      market_code, discount_percentage, rate_override_weekday, rate_override_weekend,
-                      pre_checkin_completed, pre_checkin_completed_at, pre_checkin_token,
-                      pre_checkin_token_expires_at, created_by, created_at, updated_at
-            "#
-        )
-        .bind(&booking_number)
-        .bind(guest_id)
-        .bind(room_id)
-        .bind(check_in_date)
-        .bind(check_out_date)
-        .bind(total_amount)
-        .bind(created_by)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const SELECT_BOOKING: &str = "SELECT * FROM bookings";
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const SELECT_BOOKING: &str = "SELECT id, booking_number, guest_id, room_id, check_in_date, check_out_date, \
+    room_rate, subtotal, tax_amount, discount_amount, total_amount, status, payment_status, \
+    payment_method, adults, children, special_requests, remarks, source, market_code, \
+    discount_percentage, rate_override_weekday, rate_override_weekend, pre_checkin_completed, \
+    pre_checkin_completed_at, pre_checkin_token, pre_checkin_token_expires_at, created_by, \
+    is_complimentary, complimentary_reason, complimentary_start_date, complimentary_end_date, \
+    original_total_amount, complimentary_nights, deposit_paid, deposit_amount, deposit_paid_at, \
+    company_id, company_name, payment_note, daily_rates, created_at, updated_at, post_type \
+    FROM bookings";
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const INSERT_BOOKING: &str = r#"
+    INSERT INTO bookings (booking_number, guest_id, room_id, check_in_date, check_out_date,
+                          rate_per_night, room_rate, subtotal, total_amount, status,
+                          created_by, adults)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?7, ?8, ?9, 1)
+    RETURNING id
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const INSERT_BOOKING: &str = r#"
+    INSERT INTO bookings (booking_number, guest_id, room_id, check_in_date, check_out_date,
+                          room_rate, subtotal, total_amount, status, created_by, adults)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $7, $8, $9, 1)
+    RETURNING id
+"#;
+
+impl BookingRepository for SqlBookingRepository<'_> {
+    async fn create(&self, booking: &NewBooking) -> Result<Booking, ApiError> {
+        let id: i64 = sqlx::query_scalar(INSERT_BOOKING)
+            .bind(&booking.booking_number)
+            .bind(booking.guest_id)
+            .bind(booking.room_id)
+            .bind(booking.check_in_date)
+            .bind(booking.check_out_date)
+            .bind(decimal_to_db(booking.room_rate))
+            .bind(decimal_to_db(booking.total_amount))
+            .bind(&booking.status)
+            .bind(booking.created_by)
+            .fetch_one(self.pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        self.find(id)
+            .await?
+            .ok_or_else(|| ApiError::Internal("Created booking could not be read".to_string()))
     }
 
-    /// Update booking status
-    pub async fn update_status(pool: &DbPool, id: i64, status: &str) -> Result<(), ApiError> {
-        sqlx::query(
-            "UPDATE bookings SET status = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
-        )
-        .bind(status)
-        .bind(id)
-        .execute(pool)
-        .await
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    async fn find(&self, id: i64) -> Result<Option<Booking>, ApiError> {
+        let row = sqlx::query(&format!("{} WHERE id = $1", SELECT_BOOKING))
+            .bind(id)
+            .fetch_optional(self.pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
 
-        Ok(())
+        Ok(row.as_ref().map(row_mappers::row_to_booking))
     }
 
-    /// Check in a booking
-    pub async fn check_in(pool: &DbPool, id: i64, _check_in_time: &str) -> Result<(), ApiError> {
-        sqlx::query(
-            r#"
-            UPDATE bookings
-            SET status = 'checked_in', actual_check_in = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
-            WHERE id = $1
-            "#
-        )
-        .bind(id)
-        .execute(pool)
+    async fn list(&self, filter: &BookingFilter) -> Result<Vec<Booking>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE ($1 IS NULL OR guest_id = $1) AND ($2 IS NULL OR room_id = $2) \
+             AND ($3 IS NULL OR status = $3) ORDER BY check_in_date, id",
+            SELECT_BOOKING
+        ))
+        .bind(filter.guest_id)
+        .bind(filter.room_id)
+        .bind(&filter.status)
+        .fetch_all(self.pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-        Ok(())
+        Ok(rows.iter().map(row_mappers::row_to_booking).collect())
     }
 
-    /// Check out a booking
-    pub async fn check_out(pool: &DbPool, id: i64, _check_out_time: &str) -> Result<(), ApiError> {
-        sqlx::query(
-            r#"
-            UPDATE bookings
-            SET status = 'checked_out', actual_check_out = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
-            WHERE id = $1
-            "#
+    async fn update_status(&self, id: i64, status: &str) -> Result<(), ApiError> {
+        let result = sqlx::query(
+            "UPDATE bookings SET status = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
         )
+        .bind(status)
         .bind(id)
-        .execute(pool)
+        .execute(self.pool)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+        if result.rows_affected() == 0 {
+            return Err(ApiError::NotFound("Booking not found".to_string()));
+        }
         Ok(())
     }
 
-    /// Check if booking exists
-    pub async fn exists(pool: &DbPool, id: i64) -> Result<bool, ApiError> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bookings WHERE id = $1")
-            .bind(id)
-            .fetch_one(pool)
+    async fn check_conflict(
+        &self,
+        room_id: i64,
+        check_in: NaiveDate,
+        check_out: NaiveDate,
+    ) -> Result<Vec<String>, ApiError> {
+        let numbers: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
+            .bind(room_id)
+            .bind(check_in)
+            .bind(check_out)
+            .fetch_all(self.pool)
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
 
-        Ok(count > 0)
+        Ok(numbers.into_iter().flatten().collect())
     }
 }
@@ -64,6 +64,23 @@ impl SettingsRepository {
             .map_err(|e| ApiError::Database(e.to_string()))
     }
 
+    /// Interpret a boolean setting value
+    pub fn parse_bool(value: &str) -> Option<bool> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(true),
+            "false" | "0" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Get a boolean setting, falling back to `default` when missing or unparseable
+    pub async fn get_bool(pool: &DbPool, key: &str, default: bool) -> bool {
+        match Self::get_value(pool, key).await {
+            Ok(Some(v)) => Self::parse_bool(&v).unwrap_or(default),
+            _ => default,
+        }
+    }
+
     /// Update setting value
     pub async fn update_value(
         pool: &DbPool,
@@ -48,7 +48,7 @@ impl UserRepository {
     pub async fn get_profile(pool: &DbPool, user_id: i64) -> Result<Option<UserProfile>, ApiError> {
         sqlx::query_as::<_, UserProfile>(
             r#"
-            SELECT id, username, email, full_name, phone, avatar_url,
+            SELECT id, username, email, full_name, phone, avatar_url, avatar_thumbnail_url,
                    created_at, updated_at, last_login_at
             FROM users
             WHERE id = $1 AND deleted_at IS NULL
@@ -23,6 +23,8 @@ pub fn routes() -> Router<DbPool> {
         .route("/analytics/bookings", get(get_booking_analytics))
         .route("/analytics/benchmark", get(get_benchmark))
         .route("/analytics/personalized", get(get_personalized))
+        .route("/analytics/pace", get(get_pace))
+        .route("/analytics/channels", get(get_channels))
         .route("/reports/generate", get(generate_report))
 }
 
@@ -55,6 +57,24 @@ async fn get_personalized(
     handlers::analytics::get_personalized_report_handler(State(pool), headers, query).await
 }
 
+async fn get_pace(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::PaceQuery>,
+) -> Result<Json<models::PaceReport>, ApiError> {
+    require_permission_helper(&pool, &headers, "analytics:read").await?;
+    handlers::analytics::get_pace_handler(State(pool), query).await
+}
+
+async fn get_channels(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::ChannelQuery>,
+) -> Result<Json<models::ChannelReport>, ApiError> {
+    require_permission_helper(&pool, &headers, "analytics:read").await?;
+    handlers::analytics::get_channel_report_handler(State(pool), query).await
+}
+
 async fn generate_report(
     State(pool): State<DbPool>,
     headers: HeaderMap,
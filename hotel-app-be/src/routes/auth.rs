@@ -2,18 +2,19 @@
 //!
 //! 2FA routes are in `routes::two_factor`, passkey routes in `routes::passkey`.
 
-use super::extract_client_ip;
+use super::{extract_client_ip, extract_user_agent};
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
+use crate::core::middleware::AuthUser;
 use crate::core::rate_limiter::RateLimiters;
 use crate::handlers;
 use crate::models;
 use axum::{
     Router,
-    extract::{Extension, State},
+    extract::{Extension, Query, State},
     http::HeaderMap,
     response::Json,
-    routing::post,
+    routing::{get, post},
 };
 
 pub fn routes() -> Router<DbPool> {
@@ -21,6 +22,7 @@ pub fn routes() -> Router<DbPool> {
         .route("/auth/login", post(login))
         .route("/auth/refresh", post(refresh))
         .route("/auth/logout", post(logout))
+        .route("/auth/login-history", get(login_history))
         .route("/auth/register", post(register))
         .route("/auth/verify-email", post(verify_email))
         .route("/auth/resend-verification", post(resend_verification))
@@ -45,7 +47,15 @@ async fn login(
             retry_after,
         ));
     }
-    handlers::auth::login_handler(State(pool), Json(req)).await
+    handlers::auth::login_handler(State(pool), ip, extract_user_agent(&headers), Json(req)).await
+}
+
+async fn login_history(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    query: Query<models::LoginHistoryQuery>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::LoginHistoryEntry>>>, ApiError> {
+    handlers::auth::get_login_history_handler(State(pool), Extension(user_id), query).await
 }
 
 async fn refresh(
@@ -70,9 +80,10 @@ async fn refresh(
 
 async fn logout(
     State(pool): State<DbPool>,
+    headers: HeaderMap,
     Json(req): Json<models::RefreshTokenRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    handlers::auth::logout_handler(State(pool), Json(req)).await
+    handlers::auth::logout_handler(State(pool), headers, Json(req)).await
 }
 
 async fn register(
@@ -5,13 +5,15 @@
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::require_permission_helper;
+use crate::core::property_scope::require_property_scope;
 use crate::handlers;
 use crate::models;
+use crate::services::booking_attachments::ATTACHMENT_UPLOAD;
 use axum::{
     Router,
-    extract::{Extension, Path, Query, State},
+    extract::{DefaultBodyLimit, Extension, Multipart, Path, Query, State},
     http::HeaderMap,
-    response::Json,
+    response::{Json, Response},
     routing::{delete, get, patch, post, put},
 };
 
@@ -23,9 +25,19 @@ pub fn routes() -> Router<DbPool> {
         .route("/bookings", post(create_booking))
         .route("/bookings/my-bookings", get(get_my_bookings))
         .route("/bookings/stats", get(get_booking_stats))
+        .route("/bookings/search", get(search_bookings))
+        .route("/bookings/check-conflict", get(check_booking_conflict))
+        .route("/bookings/quote", post(quote_booking))
+        .route("/bookings/group", post(create_group_booking))
+        .route(
+            "/bookings/group/{reference}/cancel",
+            post(cancel_group_booking),
+        )
+        .route("/bookings/walk-in", post(walk_in_checkin))
         .route("/bookings/complimentary", get(get_complimentary_bookings))
         .route("/bookings/book-with-credits", post(book_with_credits))
         .route("/bookings/void", post(void_booking))
+        .route("/bookings/batch", post(get_bookings_batch))
         // Complimentary management routes (static paths)
         .route("/complimentary/summary", get(get_complimentary_summary))
         .route(
@@ -39,11 +51,31 @@ pub fn routes() -> Router<DbPool> {
         // Code lookup routes
         .route("/rate-codes", get(get_rate_codes))
         .route("/market-codes", get(get_market_codes))
+        .route("/booking-sources", get(get_booking_sources))
         // Specific parameterized routes (MUST come before generic /bookings/:id routes)
         .route("/bookings/{id}/reactivate", post(reactivate_booking))
+        .route("/bookings/{id}/cancel", post(cancel_booking))
         .route("/bookings/{id}/checkin", post(manual_checkin))
+        .route("/bookings/{id}/check-in", post(manual_checkin))
+        .route("/bookings/{id}/check-out", post(checkout_booking))
         .route("/bookings/{id}/timeline", get(get_booking_timeline))
+        .route("/bookings/{id}/can-extend", get(can_extend_booking))
+        .route(
+            "/bookings/{id}/folio-split",
+            get(get_folio_split).put(update_folio_split),
+        )
         .route("/bookings/{id}/pre-checkin", patch(pre_checkin_update))
+        .route("/bookings/{id}/attachments", get(list_booking_attachments))
+        // Attachment upload, with room for multipart framing around the file
+        .route(
+            "/bookings/{id}/attachments",
+            post(upload_booking_attachment)
+                .layer(DefaultBodyLimit::max(ATTACHMENT_UPLOAD.body_limit())),
+        )
+        .route(
+            "/bookings/{id}/attachments/{attachment_id}",
+            get(download_booking_attachment).delete(delete_booking_attachment),
+        )
         .route("/bookings/{id}/complimentary", post(mark_complimentary))
         .route("/bookings/{id}/complimentary", patch(update_complimentary))
         .route("/bookings/{id}/complimentary", delete(remove_complimentary))
@@ -63,8 +95,9 @@ async fn get_bookings(
     headers: HeaderMap,
     query: Query<models::BookingPaginationParams>,
 ) -> Result<Json<models::PaginatedResponse<Vec<models::BookingWithDetails>>>, ApiError> {
-    require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_bookings_handler(State(pool), query).await
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_bookings_handler(State(pool), Extension(scope), query).await
 }
 
 async fn create_booking(
@@ -73,7 +106,64 @@ async fn create_booking(
     Json(input): Json<models::BookingInput>,
 ) -> Result<Json<models::Booking>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:create").await?;
-    handlers::bookings::create_booking_handler(State(pool), Extension(user_id), Json(input)).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::create_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        Json(input),
+    )
+    .await
+}
+
+async fn create_group_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::GroupBookingInput>,
+) -> Result<Json<models::GroupBooking>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:create").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::create_group_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        Json(input),
+    )
+    .await
+}
+
+async fn cancel_group_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<String>,
+    Json(input): Json<models::CancelBookingInput>,
+) -> Result<Json<models::GroupCancellation>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::cancel_group_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(input),
+    )
+    .await
+}
+
+async fn walk_in_checkin(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::WalkInInput>,
+) -> Result<Json<models::WalkIn>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:create").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::walk_in_checkin_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        Json(input),
+    )
+    .await
 }
 
 async fn get_my_bookings(
@@ -84,12 +174,43 @@ async fn get_my_bookings(
     handlers::bookings::get_my_bookings_handler(State(pool), headers).await
 }
 
+async fn search_bookings(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::BookingSearchParams>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::BookingWithDetails>>>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::search_bookings_handler(State(pool), Extension(scope), query).await
+}
+
+async fn check_booking_conflict(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::BookingConflictParams>,
+) -> Result<Json<models::BookingConflictCheck>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::check_booking_conflict_handler(State(pool), Extension(scope), query).await
+}
+
+async fn quote_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::BookingQuoteInput>,
+) -> Result<Json<models::BookingQuote>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::quote_booking_handler(State(pool), Extension(scope), Json(input)).await
+}
+
 async fn get_booking_stats(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<models::BookingStats>, ApiError> {
-    require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_booking_stats_handler(State(pool)).await
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_booking_stats_handler(State(pool), Extension(scope)).await
 }
 
 async fn get_booking(
@@ -98,7 +219,19 @@ async fn get_booking(
     path: Path<i64>,
 ) -> Result<Json<models::BookingWithDetails>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_booking_handler(State(pool), Extension(user_id), path).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_booking_handler(State(pool), Extension(user_id), Extension(scope), path)
+        .await
+}
+
+async fn get_bookings_batch(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::BookingBatchRequest>,
+) -> Result<Json<models::BookingBatchResponse>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_bookings_batch_handler(State(pool), Extension(scope), Json(input)).await
 }
 
 async fn get_booking_timeline(
@@ -107,7 +240,108 @@ async fn get_booking_timeline(
     path: Path<i64>,
 ) -> Result<Json<Vec<models::BookingTimelineEntry>>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_booking_timeline_handler(State(pool), Extension(user_id), path).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_booking_timeline_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
+}
+
+async fn can_extend_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    query: Query<models::StayExtensionParams>,
+) -> Result<Json<models::StayExtensionCheck>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::can_extend_booking_handler(State(pool), Extension(scope), path, query).await
+}
+
+async fn get_folio_split(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<models::BookingFolioSplit>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_folio_split_handler(State(pool), Extension(scope), path).await
+}
+
+async fn update_folio_split(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<models::FolioSplitInput>,
+) -> Result<Json<models::BookingFolioSplit>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::update_folio_split_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(input),
+    )
+    .await
+}
+
+async fn list_booking_attachments(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<Vec<models::BookingAttachment>>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::list_booking_attachments_handler(State(pool), Extension(scope), path).await
+}
+
+async fn upload_booking_attachment(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    multipart: Multipart,
+) -> Result<Json<models::BookingAttachment>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::upload_booking_attachment_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        multipart,
+    )
+    .await
+}
+
+async fn download_booking_attachment(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<(i64, i64)>,
+) -> Result<Response, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::download_booking_attachment_handler(State(pool), Extension(scope), path)
+        .await
+}
+
+async fn delete_booking_attachment(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<(i64, i64)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::delete_booking_attachment_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
 }
 
 async fn update_booking(
@@ -117,8 +351,15 @@ async fn update_booking(
     Json(input): Json<models::BookingUpdateInput>,
 ) -> Result<Json<models::Booking>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
-    handlers::bookings::update_booking_handler(State(pool), Extension(user_id), path, Json(input))
-        .await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::update_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(input),
+    )
+    .await
 }
 
 async fn delete_booking(
@@ -127,7 +368,14 @@ async fn delete_booking(
     path: Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:delete").await?;
-    handlers::bookings::delete_booking_handler(State(pool), Extension(user_id), path).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::delete_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
 }
 
 async fn void_booking(
@@ -136,14 +384,34 @@ async fn void_booking(
     Json(input): Json<models::BookingCancellationRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
     handlers::bookings::delete_booking_handler(
         State(pool),
         Extension(user_id),
+        Extension(scope),
         Path(input.booking_id),
     )
     .await
 }
 
+async fn cancel_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<models::CancelBookingInput>,
+) -> Result<Json<models::BookingCancellation>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::cancel_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(input),
+    )
+    .await
+}
+
 async fn manual_checkin(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -151,8 +419,33 @@ async fn manual_checkin(
     Json(data): Json<Option<models::CheckInRequest>>,
 ) -> Result<Json<models::Booking>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
-    handlers::bookings::manual_checkin_handler(State(pool), Extension(user_id), path, Json(data))
-        .await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::manual_checkin_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(data),
+    )
+    .await
+}
+
+async fn checkout_booking(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(data): Json<Option<models::CheckOutRequest>>,
+) -> Result<Json<models::Booking>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::checkout_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(data),
+    )
+    .await
 }
 
 async fn pre_checkin_update(
@@ -178,6 +471,13 @@ async fn get_market_codes(
     handlers::settings::get_market_codes_handler(State(pool)).await
 }
 
+async fn get_booking_sources(
+    State(pool): State<DbPool>,
+) -> Result<Json<models::BookingSourcesResponse>, ApiError> {
+    // Public endpoint - no authentication required
+    handlers::settings::get_booking_sources_handler(State(pool)).await
+}
+
 async fn mark_complimentary(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -185,9 +485,11 @@ async fn mark_complimentary(
     Json(input): Json<models::MarkComplimentaryRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
     handlers::bookings::mark_complimentary_handler(
         State(pool),
         Extension(user_id),
+        Extension(scope),
         path,
         Json(input),
     )
@@ -200,9 +502,11 @@ async fn convert_complimentary_to_credits(
     path: Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
     handlers::bookings::convert_complimentary_to_credits_handler(
         State(pool),
         Extension(user_id),
+        Extension(scope),
         path,
     )
     .await
@@ -221,16 +525,18 @@ async fn get_complimentary_bookings(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<Vec<models::BookingWithDetails>>, ApiError> {
-    require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_complimentary_bookings_handler(State(pool)).await
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_complimentary_bookings_handler(State(pool), Extension(scope)).await
 }
 
 async fn get_complimentary_summary(
     State(pool): State<DbPool>,
     headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    require_permission_helper(&pool, &headers, "bookings:read").await?;
-    handlers::bookings::get_complimentary_summary_handler(State(pool)).await
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::get_complimentary_summary_handler(State(pool), Extension(scope)).await
 }
 
 async fn update_complimentary(
@@ -240,9 +546,11 @@ async fn update_complimentary(
     Json(input): Json<models::UpdateComplimentaryRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
     handlers::bookings::update_complimentary_handler(
         State(pool),
         Extension(user_id),
+        Extension(scope),
         path,
         Json(input),
     )
@@ -255,7 +563,14 @@ async fn remove_complimentary(
     path: Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
-    handlers::bookings::remove_complimentary_handler(State(pool), Extension(user_id), path).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::remove_complimentary_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
 }
 
 async fn get_guests_with_credits(
@@ -300,5 +615,12 @@ async fn reactivate_booking(
     path: Path<i64>,
 ) -> Result<Json<models::Booking>, ApiError> {
     let user_id = require_permission_helper(&pool, &headers, "bookings:update").await?;
-    handlers::bookings::reactivate_booking_handler(State(pool), Extension(user_id), path).await
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::bookings::reactivate_booking_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
 }
@@ -16,4 +16,12 @@ pub fn routes() -> Router<DbPool> {
         .route("/companies/{id}", get(get_company_handler))
         .route("/companies/{id}", put(update_company_handler))
         .route("/companies/{id}", delete(delete_company_handler))
+        .route(
+            "/companies/{id}/bookings",
+            get(get_company_bookings_handler),
+        )
+        .route(
+            "/companies/{id}/payments",
+            post(post_company_payment_handler),
+        )
 }
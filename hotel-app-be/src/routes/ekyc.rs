@@ -6,10 +6,11 @@ use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::require_permission_helper;
 use crate::handlers;
+use crate::handlers::ekyc::EKYC_IMAGE;
 use crate::models;
 use axum::{
     Router,
-    extract::{Multipart, Path, State},
+    extract::{DefaultBodyLimit, Multipart, Path, State},
     http::HeaderMap,
     response::Json,
     routing::{get, patch, post},
@@ -19,7 +20,10 @@ use axum::{
 pub fn routes() -> Router<DbPool> {
     Router::new()
         // User eKYC routes
-        .route("/ekyc/upload-document", post(upload_document))
+        .route(
+            "/ekyc/upload-document",
+            post(upload_document).layer(DefaultBodyLimit::max(EKYC_IMAGE.body_limit())),
+        )
         .route("/ekyc/submit", post(submit_ekyc))
         .route("/ekyc/status", get(get_status))
         .route("/ekyc/self-checkin", post(self_checkin))
@@ -1,24 +1,41 @@
 //! Guest portal routes
 //!
-//! Public routes for guest self-service features.
+//! Routes for guest self-service features. The pre-check-in routes are
+//! public and authorised by token; the profile, communication preference
+//! and review routes need a signed-in user.
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
+use crate::core::middleware::AuthUser;
 use crate::handlers;
 use crate::models;
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Extension, Path, State},
     response::Json,
-    routing::{get, post},
+    routing::{get, patch, post, put},
 };
 
-/// Create guest portal routes (no authentication required)
+/// Create guest portal routes
 pub fn routes() -> Router<DbPool> {
     Router::new()
         .route("/guest-portal/verify", post(verify_booking))
         .route("/guest-portal/booking/{token}", get(get_booking))
         .route("/guest-portal/pre-checkin/{token}", post(submit_precheckin))
+        // Signed-in guest's own profile
+        .route("/portal/guest-profile", get(get_guest_profile))
+        .route("/portal/guest-profile", patch(update_guest_profile))
+        // Signed-in guest's email and SMS consent
+        .route(
+            "/portal/communication-preferences",
+            get(get_communication_preferences),
+        )
+        .route(
+            "/portal/communication-preferences",
+            put(update_communication_preferences),
+        )
+        // Signed-in guest's review of a past stay
+        .route("/portal/bookings/{id}/review", post(submit_review))
 }
 
 async fn verify_booking(
@@ -42,3 +59,49 @@ async fn submit_precheckin(
 ) -> Result<Json<models::GuestPortalBookingResponse>, ApiError> {
     handlers::guest_portal::submit_precheckin_update(State(pool), path, Json(input)).await
 }
+
+async fn get_guest_profile(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+) -> Result<Json<models::GuestPortalProfile>, ApiError> {
+    handlers::guest_portal::get_own_guest_profile(State(pool), Extension(user_id)).await
+}
+
+async fn update_guest_profile(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Json(input): Json<models::GuestPortalProfileUpdate>,
+) -> Result<Json<models::GuestPortalProfile>, ApiError> {
+    handlers::guest_portal::update_own_guest_profile(State(pool), Extension(user_id), Json(input))
+        .await
+}
+
+async fn get_communication_preferences(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+) -> Result<Json<models::CommunicationPreferences>, ApiError> {
+    handlers::guest_portal::get_own_communication_preferences(State(pool), Extension(user_id)).await
+}
+
+async fn update_communication_preferences(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Json(input): Json<models::CommunicationPreferencesUpdate>,
+) -> Result<Json<models::CommunicationPreferences>, ApiError> {
+    handlers::guest_portal::update_own_communication_preferences(
+        State(pool),
+        Extension(user_id),
+        Json(input),
+    )
+    .await
+}
+
+async fn submit_review(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    path: Path<i64>,
+    Json(input): Json<models::GuestReviewInput>,
+) -> Result<Json<models::GuestReviewSubmitted>, ApiError> {
+    handlers::guest_portal::submit_own_review(State(pool), Extension(user_id), path, Json(input))
+        .await
+}
@@ -4,7 +4,7 @@
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::{require_auth, require_permission_helper};
+use crate::core::middleware::{AuthUser, require_permission_helper};
 use crate::handlers;
 use crate::models;
 use axum::{
@@ -20,6 +20,7 @@ pub fn routes() -> Router<DbPool> {
     Router::new()
         .route("/guests", get(get_guests))
         .route("/guests", post(create_guest))
+        .route("/guests/arriving", get(get_arriving_guests))
         .route("/guests/my-guests", get(get_my_guests))
         .route(
             "/guests/my-guests-with-credits",
@@ -32,6 +33,13 @@ pub fn routes() -> Router<DbPool> {
         .route("/guests/{id}", delete(delete_guest))
         .route("/guests/{id}/bookings", get(get_guest_bookings))
         .route("/guests/{id}/credits", get(get_guest_credits))
+        .route("/guests/{id}/balance", get(get_guest_balance))
+        .route("/guests/{id}/stays", get(get_guest_stays))
+        .route(
+            "/guests/{id}/communication-preferences",
+            get(get_communication_preferences).patch(update_communication_preferences),
+        )
+        .route("/guests/{id}/consent-history", get(get_consent_history))
 }
 
 async fn get_guests(
@@ -77,10 +85,9 @@ async fn unlink_guest(
 
 async fn upgrade_guest(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     Json(input): Json<models::UpgradeGuestInput>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::guests::upgrade_guest_to_user_handler(State(pool), Extension(user_id), Json(input))
         .await
 }
@@ -113,6 +120,68 @@ async fn get_guest_bookings(
     handlers::guests::get_guest_bookings_handler(State(pool), path).await
 }
 
+async fn get_guest_balance(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<models::GuestBalance>, ApiError> {
+    require_permission_helper(&pool, &headers, "payments:read").await?;
+    handlers::guests::get_guest_balance_handler(State(pool), path).await
+}
+
+async fn get_communication_preferences(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<models::CommunicationPreferences>, ApiError> {
+    require_permission_helper(&pool, &headers, "guests:read").await?;
+    handlers::guests::get_guest_communication_preferences_handler(State(pool), path).await
+}
+
+async fn update_communication_preferences(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<models::CommunicationPreferencesUpdate>,
+) -> Result<Json<models::CommunicationPreferences>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "guests:update").await?;
+    handlers::guests::update_guest_communication_preferences_handler(
+        State(pool),
+        Extension(user_id),
+        path,
+        Json(input),
+    )
+    .await
+}
+
+async fn get_consent_history(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<Vec<models::ConsentChange>>, ApiError> {
+    require_permission_helper(&pool, &headers, "guests:read").await?;
+    handlers::guests::get_guest_consent_history_handler(State(pool), path).await
+}
+
+async fn get_guest_stays(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    query: Query<models::GuestStaysParams>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::GuestStay>>>, ApiError> {
+    // Linked users see their own guests; the handler checks access
+    handlers::guests::get_guest_stays_handler(State(pool), headers, path, query).await
+}
+
+async fn get_arriving_guests(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::ArrivingGuestsParams>,
+) -> Result<Json<Vec<models::ArrivingGuest>>, ApiError> {
+    require_permission_helper(&pool, &headers, "guests:read").await?;
+    handlers::guests::get_arriving_guests_handler(State(pool), query).await
+}
+
 async fn get_guest_credits(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -4,14 +4,14 @@
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::{require_admin_helper, require_auth, require_permission_helper};
+use crate::core::middleware::{AuthUser, require_admin_helper, require_permission_helper};
 use crate::handlers;
 use crate::models;
 use axum::{
     Router,
     extract::{Extension, Path, Query, State},
     http::HeaderMap,
-    response::Json,
+    response::{Json, Response},
     routing::{delete, get, post, put},
 };
 use std::collections::HashMap;
@@ -23,13 +23,26 @@ pub fn routes() -> Router<DbPool> {
         .route("/loyalty/programs", get(get_programs))
         .route("/loyalty/memberships", get(get_memberships))
         .route("/loyalty/statistics", get(get_statistics))
+        .route("/loyalty/statistics/export", get(export_statistics))
         .route("/loyalty/memberships/{id}/points/add", post(add_points))
         .route(
             "/loyalty/memberships/{id}/points/redeem",
             post(redeem_points),
         )
+        .route(
+            "/loyalty/memberships/{id}/recompute",
+            post(recompute_points),
+        )
+        .route("/loyalty/memberships/{id}/renew", post(renew_membership))
+        .route("/loyalty/transfer", post(transfer_points))
+        .route("/loyalty/points/bulk", post(bulk_points))
+        .route("/loyalty/points-rules", get(get_points_rules))
+        .route("/loyalty/points-rules", post(create_points_rule))
+        .route("/loyalty/points-rules/{id}", put(update_points_rule))
+        .route("/loyalty/points-rules/{id}", delete(delete_points_rule))
         // User loyalty routes
         .route("/loyalty/my-membership", get(get_my_membership))
+        .route("/portal/loyalty/transactions", get(get_my_transactions))
         .route("/loyalty/rewards", get(get_rewards))
         .route("/loyalty/rewards/redeem", post(redeem_reward))
         // Admin reward CRUD routes
@@ -55,9 +68,10 @@ async fn get_programs(
 async fn get_memberships(
     State(pool): State<DbPool>,
     headers: HeaderMap,
-) -> Result<Json<Vec<models::LoyaltyMembershipWithDetails>>, ApiError> {
+    query: Query<models::ListParams>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::LoyaltyMembershipWithDetails>>>, ApiError> {
     require_permission_helper(&pool, &headers, "analytics:read").await?;
-    handlers::loyalty::get_loyalty_memberships_handler(State(pool)).await
+    handlers::loyalty::get_loyalty_memberships_handler(State(pool), query).await
 }
 
 async fn get_statistics(
@@ -68,14 +82,23 @@ async fn get_statistics(
     handlers::loyalty::get_loyalty_statistics_handler(State(pool)).await
 }
 
+async fn export_statistics(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::LoyaltyStatisticsExportParams>,
+) -> Result<Response, ApiError> {
+    require_permission_helper(&pool, &headers, "analytics:read").await?;
+    handlers::loyalty::export_loyalty_statistics_handler(State(pool), query).await
+}
+
 async fn add_points(
     State(pool): State<DbPool>,
     headers: HeaderMap,
     path: Path<i64>,
     Json(input): Json<models::AddPointsInput>,
 ) -> Result<Json<models::PointsTransaction>, ApiError> {
-    require_permission_helper(&pool, &headers, "analytics:write").await?;
-    handlers::loyalty::add_points_handler(State(pool), path, Json(input)).await
+    let user_id = require_permission_helper(&pool, &headers, "loyalty:manage").await?;
+    handlers::loyalty::add_points_handler(State(pool), Extension(user_id), path, Json(input)).await
 }
 
 async fn redeem_points(
@@ -84,34 +107,115 @@ async fn redeem_points(
     path: Path<i64>,
     Json(input): Json<models::AddPointsInput>,
 ) -> Result<Json<models::PointsTransaction>, ApiError> {
-    require_permission_helper(&pool, &headers, "analytics:write").await?;
-    handlers::loyalty::redeem_points_handler(State(pool), path, Json(input)).await
+    let user_id = require_permission_helper(&pool, &headers, "loyalty:manage").await?;
+    handlers::loyalty::redeem_points_handler(State(pool), Extension(user_id), path, Json(input))
+        .await
+}
+
+async fn recompute_points(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<models::PointsRecomputeResult>, ApiError> {
+    let admin_id = require_admin_helper(&pool, &headers).await?;
+    handlers::loyalty::recompute_points_handler(State(pool), Extension(admin_id), path).await
+}
+
+async fn renew_membership(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<Option<models::RenewMembershipInput>>,
+) -> Result<Json<models::MembershipRenewal>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "loyalty:manage").await?;
+    handlers::loyalty::renew_membership_handler(State(pool), Extension(user_id), path, Json(input))
+        .await
+}
+
+async fn transfer_points(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::TransferPointsInput>,
+) -> Result<Json<models::PointsTransferResult>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "loyalty:manage").await?;
+    handlers::loyalty::transfer_points_handler(State(pool), Extension(user_id), Json(input)).await
+}
+
+async fn bulk_points(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::BulkPointsInput>,
+) -> Result<Json<models::BulkPointsResult>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "loyalty:manage").await?;
+    handlers::loyalty::bulk_points_handler(State(pool), Extension(user_id), Json(input)).await
+}
+
+async fn get_points_rules(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<models::PointsRule>>, ApiError> {
+    require_admin_helper(&pool, &headers).await?;
+    handlers::loyalty::get_points_rules_handler(State(pool)).await
+}
+
+async fn create_points_rule(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<models::PointsRuleInput>,
+) -> Result<Json<models::PointsRule>, ApiError> {
+    require_admin_helper(&pool, &headers).await?;
+    handlers::loyalty::create_points_rule_handler(State(pool), Json(input)).await
+}
+
+async fn update_points_rule(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<models::PointsRuleInput>,
+) -> Result<Json<models::PointsRule>, ApiError> {
+    require_admin_helper(&pool, &headers).await?;
+    handlers::loyalty::update_points_rule_handler(State(pool), path, Json(input)).await
+}
+
+async fn delete_points_rule(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_helper(&pool, &headers).await?;
+    handlers::loyalty::delete_points_rule_handler(State(pool), path).await
 }
 
 // User loyalty handlers
 
 async fn get_my_membership(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
 ) -> Result<Json<models::UserLoyaltyMembership>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::loyalty::get_user_loyalty_membership_handler(State(pool), Extension(user_id)).await
 }
 
+async fn get_my_transactions(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    query: Query<models::PointsTransactionsParams>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::PointsTransaction>>>, ApiError> {
+    handlers::loyalty::get_user_points_transactions_handler(State(pool), Extension(user_id), query)
+        .await
+}
+
 async fn get_rewards(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
 ) -> Result<Json<Vec<models::LoyaltyReward>>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::loyalty::get_loyalty_rewards_handler(State(pool), Extension(user_id)).await
 }
 
 async fn redeem_reward(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     Json(input): Json<models::RedeemRewardInput>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::loyalty::redeem_reward_handler(State(pool), Extension(user_id), Json(input)).await
 }
 
@@ -166,18 +270,18 @@ async fn delete_reward(
 async fn get_redemptions(
     State(pool): State<DbPool>,
     headers: HeaderMap,
-) -> Result<Json<Vec<models::RewardRedemptionWithDetails>>, ApiError> {
+    query: Query<models::ListParams>,
+) -> Result<Json<models::PaginatedResponse<Vec<models::RewardRedemptionWithDetails>>>, ApiError> {
     require_admin_helper(&pool, &headers).await?;
-    handlers::loyalty::get_reward_redemptions_handler(State(pool)).await
+    handlers::loyalty::get_reward_redemptions_handler(State(pool), query).await
 }
 
 async fn redeem_reward_by_id(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     path: Path<i64>,
     Json(input): Json<models::RedeemRewardInput>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::loyalty::redeem_reward_for_user_handler(
         State(pool),
         Extension(user_id),
@@ -25,13 +25,15 @@ pub mod search;
 pub mod settings;
 pub mod two_factor;
 
+use crate::core::concurrency_limit::{ConcurrencyLimit, limit_concurrency};
+use crate::core::config::AppConfig;
 use crate::core::db::DbPool;
+use crate::core::password_change::require_password_change;
 use crate::core::rate_limiter::RateLimiters;
+use crate::core::security_headers::{AllowedOrigins, security_header_layers};
 use axum::{Router, http::Method, routing::get};
 use tower::ServiceBuilder;
-use tower_http::{
-    cors::CorsLayer, services::ServeDir, set_header::SetResponseHeaderLayer, trace::TraceLayer,
-};
+use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
 
 /// Extract client IP from X-Forwarded-For or X-Real-IP headers, defaulting to localhost.
 pub(crate) fn extract_client_ip(headers: &axum::http::HeaderMap) -> std::net::IpAddr {
@@ -49,6 +51,14 @@ pub(crate) fn extract_client_ip(headers: &axum::http::HeaderMap) -> std::net::Ip
         .unwrap_or(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
 }
 
+/// The User-Agent header, if it is readable text.
+pub(crate) fn extract_user_agent(headers: &axum::http::HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
 /// Health check handler
 async fn health_handler() -> axum::response::Json<serde_json::Value> {
     axum::response::Json(serde_json::json!({"status": "ok"}))
@@ -60,54 +70,70 @@ async fn websocket_status_handler() -> axum::response::Json<serde_json::Value> {
 }
 
 /// Create the complete application router by composing all domain routes
-pub fn create_router(pool: DbPool) -> Router {
-    // Get allowed origins from environment variable
-    let allowed_origins = std::env::var("ALLOWED_ORIGINS")
-        .unwrap_or_else(|_| "http://localhost:3000,http://localhost:5173".to_string());
+pub fn create_router(pool: DbPool, config: &AppConfig) -> Router {
+    let mode = config.deployment_mode;
+    log::info!("Deployment mode: {:?}", mode);
+
+    let allowed_origins = &config.allowed_origins;
 
     log::info!("CORS allowed origins config: {:?}", allowed_origins);
 
     // CORS configuration - use permissive settings for desktop app (when "*" is specified)
-    // or specific origins for web deployment
-    let cors = if allowed_origins.trim() == "*" {
-        log::info!("Using permissive CORS (allow any origin) for desktop mode");
-        CorsLayer::new()
-            .allow_origin(tower_http::cors::Any)
-            .allow_headers(tower_http::cors::Any)
-            .allow_methods([
-                Method::GET,
-                Method::POST,
-                Method::PUT,
-                Method::PATCH,
-                Method::DELETE,
-                Method::OPTIONS,
-            ])
-    } else {
-        let origins: Vec<axum::http::HeaderValue> = allowed_origins
-            .split(',')
-            .filter_map(|s| s.trim().parse().ok())
-            .collect();
-
-        log::info!("CORS allowed origins: {:?}", origins);
+    // or specific origins for web deployment. Credentials are only allowed with
+    // an explicit list, as browsers reject them alongside a wildcard origin.
+    let cors = match AllowedOrigins::parse(allowed_origins) {
+        AllowedOrigins::Any => {
+            log::info!("Using permissive CORS (allow any origin) for desktop mode");
+            CorsLayer::new()
+                .allow_origin(tower_http::cors::Any)
+                .allow_headers(tower_http::cors::Any)
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                    Method::OPTIONS,
+                ])
+        }
+        AllowedOrigins::List { origins, rejected } => {
+            for entry in &rejected {
+                log::warn!(
+                    "Ignoring malformed CORS origin {:?} in ALLOWED_ORIGINS; expected scheme://host[:port]",
+                    entry
+                );
+            }
+            if origins.is_empty() {
+                log::warn!(
+                    "No valid CORS origins configured; cross-origin requests will be refused"
+                );
+            }
+            log::info!("CORS allowed origins: {:?}", origins);
 
-        CorsLayer::new()
-            .allow_origin(origins)
-            .allow_headers([
-                axum::http::header::AUTHORIZATION,
-                axum::http::header::CONTENT_TYPE,
-                axum::http::header::ACCEPT,
-            ])
-            .allow_methods([
-                Method::GET,
-                Method::POST,
-                Method::PUT,
-                Method::PATCH,
-                Method::DELETE,
-                Method::OPTIONS,
-            ])
-            .allow_credentials(true)
+            CorsLayer::new()
+                .allow_origin(origins)
+                .allow_headers([
+                    axum::http::header::AUTHORIZATION,
+                    axum::http::header::CONTENT_TYPE,
+                    axum::http::header::ACCEPT,
+                ])
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                    Method::OPTIONS,
+                ])
+                .allow_credentials(true)
+        }
     };
 
+    log::info!(
+        "Max concurrent requests: {}",
+        config.max_concurrent_requests
+    );
+
     // Initialize rate limiters
     let rate_limiters = RateLimiters::new();
 
@@ -140,46 +166,27 @@ pub fn create_router(pool: DbPool) -> Router {
         .merge(data_transfer::routes())
         .merge(passkey::routes())
         .merge(two_factor::routes())
-        .with_state(pool)
-        .layer(axum::Extension(rate_limiters));
+        .with_state(pool.clone())
+        // Users who must change their password can do little else until then.
+        .layer(axum::middleware::from_fn_with_state(
+            pool,
+            require_password_change,
+        ))
+        .layer(axum::Extension(rate_limiters))
+        // Shed requests beyond the in-flight cap with 503 instead of queuing.
+        .layer(axum::middleware::from_fn_with_state(
+            ConcurrencyLimit::new(config.max_concurrent_requests),
+            limit_concurrency,
+        ));
 
-    // Add middleware layers
+    // Security headers for the deployment profile, then CORS and tracing
+    // outermost
+    let app = security_header_layers(mode)
+        .into_iter()
+        .fold(app, |app, layer| app.layer(layer));
     app.layer(
         ServiceBuilder::new()
             .layer(TraceLayer::new_for_http())
-            .layer(cors)
-            // Security headers
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::STRICT_TRANSPORT_SECURITY,
-                axum::http::HeaderValue::from_static("max-age=31536000; includeSubDomains"),
-            ))
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::X_CONTENT_TYPE_OPTIONS,
-                axum::http::HeaderValue::from_static("nosniff"),
-            ))
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::X_FRAME_OPTIONS,
-                axum::http::HeaderValue::from_static("DENY"),
-            ))
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::X_XSS_PROTECTION,
-                axum::http::HeaderValue::from_static("1; mode=block"),
-            ))
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::CONTENT_SECURITY_POLICY,
-                axum::http::HeaderValue::from_static(
-                    "default-src 'self'; \
-                     script-src 'self'; \
-                     style-src 'self'; \
-                     img-src 'self' data: https:; \
-                     font-src 'self' data:; \
-                     connect-src 'self'; \
-                     frame-ancestors 'none';",
-                ),
-            ))
-            .layer(SetResponseHeaderLayer::if_not_present(
-                axum::http::header::REFERRER_POLICY,
-                axum::http::HeaderValue::from_static("strict-origin-when-cross-origin"),
-            )),
+            .layer(cors),
     )
 }
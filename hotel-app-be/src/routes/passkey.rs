@@ -1,6 +1,6 @@
 //! Passkey (WebAuthn) authentication routes
 
-use super::extract_client_ip;
+use super::{extract_client_ip, extract_user_agent};
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::rate_limiter::RateLimiters;
@@ -59,7 +59,7 @@ async fn register_finish(
             retry_after,
         ));
     }
-    handlers::passkey::passkey_register_finish_handler(State(pool), Json(req)).await
+    handlers::passkey::passkey_register_finish_handler(State(pool), headers, Json(req)).await
 }
 
 async fn login_start(
@@ -99,5 +99,11 @@ async fn login_finish(
             retry_after,
         ));
     }
-    handlers::passkey::passkey_login_finish_handler(State(pool), Json(req)).await
+    handlers::passkey::passkey_login_finish_handler(
+        State(pool),
+        ip,
+        extract_user_agent(&headers),
+        Json(req),
+    )
+    .await
 }
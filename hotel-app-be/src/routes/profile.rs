@@ -1,16 +1,17 @@
 //! User profile routes
 //!
-//! Routes for user profile management, 2FA, and passkeys.
+//! Routes for user profile management, 2FA, passkeys, and API keys.
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::require_auth;
+use crate::core::middleware::AuthUser;
 use crate::core::rate_limiter::RateLimiters;
 use crate::handlers;
 use crate::models;
+use crate::services::avatars::AVATAR_UPLOAD;
 use axum::{
     Router,
-    extract::{Extension, Path, State},
+    extract::{DefaultBodyLimit, Extension, Multipart, Path, Query, State},
     http::HeaderMap,
     response::Json,
     routing::{delete, get, patch, post},
@@ -23,10 +24,20 @@ pub fn routes() -> Router<DbPool> {
         .route("/profile", get(get_profile))
         .route("/profile", patch(update_profile))
         .route("/profile/password", post(update_password))
+        // Avatar upload, with room for multipart framing around the image
+        .route(
+            "/profile/avatar",
+            post(upload_avatar).layer(DefaultBodyLimit::max(AVATAR_UPLOAD.body_limit())),
+        )
+        .route("/profile/activity", get(get_activity))
         // Passkey management
         .route("/profile/passkeys", get(list_passkeys))
         .route("/profile/passkeys/{id}", delete(delete_passkey))
         .route("/profile/passkeys/{id}", patch(update_passkey))
+        // API key management
+        .route("/profile/api-keys", get(list_api_keys))
+        .route("/profile/api-keys", post(create_api_key))
+        .route("/profile/api-keys/{id}", delete(revoke_api_key))
         // 2FA management (profile-specific endpoints)
         .route("/profile/2fa/setup", post(setup_2fa))
         .route("/profile/2fa/enable", post(enable_2fa))
@@ -39,22 +50,36 @@ pub fn routes() -> Router<DbPool> {
 
 async fn get_profile(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
 ) -> Result<Json<models::UserProfile>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::profile::get_user_profile_handler(State(pool), Extension(user_id)).await
 }
 
 async fn update_profile(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     Json(input): Json<models::UserProfileUpdate>,
 ) -> Result<Json<models::UserProfile>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::profile::update_user_profile_handler(State(pool), Extension(user_id), Json(input))
         .await
 }
 
+async fn upload_avatar(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    multipart: Multipart,
+) -> Result<Json<models::AvatarUpload>, ApiError> {
+    handlers::profile::upload_avatar_handler(State(pool), Extension(user_id), multipart).await
+}
+
+async fn get_activity(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    query: Query<models::UserActivityQuery>,
+) -> Result<Json<models::UserActivityResponse>, ApiError> {
+    handlers::profile::get_user_activity_handler(State(pool), Extension(user_id), query).await
+}
+
 async fn update_password(
     State(pool): State<DbPool>,
     Extension(limiters): Extension<RateLimiters>,
@@ -72,7 +97,8 @@ async fn update_password(
             retry_after,
         ));
     }
-    let user_id = require_auth(&headers).await?;
+    // Throttle before authenticating so bad tokens count against the limit.
+    let AuthUser { user_id, .. } = AuthUser::from_headers(&headers).await?;
     handlers::profile::update_password_handler(State(pool), Extension(user_id), Json(input)).await
 }
 
@@ -80,32 +106,67 @@ async fn update_password(
 
 async fn list_passkeys(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
 ) -> Result<Json<Vec<models::PasskeyInfo>>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::passkey::list_passkeys_handler(State(pool), Extension(user_id)).await
 }
 
 async fn delete_passkey(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     path: Path<uuid::Uuid>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::passkey::delete_passkey_handler(State(pool), Extension(user_id), path).await
 }
 
 async fn update_passkey(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    AuthUser { user_id, .. }: AuthUser,
     path: Path<uuid::Uuid>,
     Json(input): Json<models::PasskeyUpdateInput>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let user_id = require_auth(&headers).await?;
     handlers::passkey::update_passkey_handler(State(pool), Extension(user_id), path, Json(input))
         .await
 }
 
+// API key handlers
+
+async fn list_api_keys(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+) -> Result<Json<Vec<models::ApiKey>>, ApiError> {
+    handlers::api_keys::list_api_keys_handler(State(pool), Extension(user_id)).await
+}
+
+async fn create_api_key(
+    State(pool): State<DbPool>,
+    Extension(limiters): Extension<RateLimiters>,
+    headers: HeaderMap,
+    Json(input): Json<models::CreateApiKeyRequest>,
+) -> Result<Json<models::CreateApiKeyResponse>, ApiError> {
+    let ip = extract_client_ip(&headers);
+    let (allowed, retry_after) = limiters.sensitive.check_with_retry(ip).await;
+    if !allowed {
+        return Err(ApiError::TooManyRequestsRetryAfter(
+            format!(
+                "Too many requests. Please try again in {} seconds.",
+                retry_after
+            ),
+            retry_after,
+        ));
+    }
+    let AuthUser { user_id, .. } = AuthUser::from_headers(&headers).await?;
+    handlers::api_keys::create_api_key_handler(State(pool), Extension(user_id), Json(input)).await
+}
+
+async fn revoke_api_key(
+    State(pool): State<DbPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    path: Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    handlers::api_keys::revoke_api_key_handler(State(pool), Extension(user_id), path).await
+}
+
 // 2FA handlers (profile context)
 
 async fn setup_2fa(
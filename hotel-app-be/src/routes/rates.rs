@@ -35,6 +35,7 @@ pub fn routes() -> Router<DbPool> {
         .route("/room-rates/{id}", patch(update_room_rate))
         .route("/room-rates/{id}", delete(delete_room_rate))
         .route("/room-rates/applicable", get(get_applicable_rate))
+        .route("/rates/calendar", get(get_rate_calendar))
         // Room types for rate management
         .route("/rate-management/room-types", get(get_room_types_for_rates))
 }
@@ -160,6 +161,15 @@ async fn get_applicable_rate(
     handlers::rates::get_applicable_rate(State(pool), query).await
 }
 
+async fn get_rate_calendar(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::RateCalendarQuery>,
+) -> Result<impl IntoResponse, handlers::rates::RateError> {
+    require_permission_helper(&pool, &headers, "rooms:read").await?;
+    handlers::rates::get_rate_calendar(State(pool), query).await
+}
+
 async fn get_room_types_for_rates(
     State(pool): State<DbPool>,
     headers: HeaderMap,
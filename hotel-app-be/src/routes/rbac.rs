@@ -4,7 +4,7 @@
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::{require_admin_helper, require_auth};
+use crate::core::middleware::require_admin_helper;
 use crate::handlers;
 use crate::models;
 use axum::{
@@ -40,6 +40,7 @@ pub fn routes() -> Router<DbPool> {
         .route("/rbac/users/{user_id}/roles/{role_id}", delete(remove_role))
         // Role-permission assignments
         .route("/rbac/roles/permissions", post(assign_permission))
+        .route("/rbac/grants", post(batch_grant_permissions))
         .route(
             "/rbac/roles/{role_id}/permissions/{permission_id}",
             delete(remove_permission),
@@ -97,9 +98,9 @@ async fn assign_role(
     State(pool): State<DbPool>,
     headers: HeaderMap,
     Json(input): Json<models::AssignRoleInput>,
-) -> Result<Json<serde_json::Value>, ApiError> {
-    require_admin_helper(&pool, &headers).await?;
-    handlers::rbac::assign_role_to_user_handler(State(pool), Json(input)).await
+) -> Result<Json<models::RoleAssignmentResult>, ApiError> {
+    let user_id = require_admin_helper(&pool, &headers).await?;
+    handlers::rbac::assign_role_to_user_handler(State(pool), Extension(user_id), Json(input)).await
 }
 
 async fn remove_role(
@@ -120,6 +121,16 @@ async fn assign_permission(
     handlers::rbac::assign_permission_to_role_handler(State(pool), Json(input)).await
 }
 
+async fn batch_grant_permissions(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    Json(input): Json<Vec<models::RoleGrantInput>>,
+) -> Result<Json<Vec<models::RoleGrantResult>>, ApiError> {
+    let user_id = require_admin_helper(&pool, &headers).await?;
+    handlers::rbac::batch_grant_permissions_handler(State(pool), Extension(user_id), Json(input))
+        .await
+}
+
 async fn remove_permission(
     State(pool): State<DbPool>,
     headers: HeaderMap,
@@ -142,8 +153,7 @@ async fn create_user(
     headers: HeaderMap,
     Json(input): Json<models::UserCreateInput>,
 ) -> Result<Json<models::UserResponse>, ApiError> {
-    require_admin_helper(&pool, &headers).await?;
-    let user_id = require_auth(&headers).await?;
+    let user_id = require_admin_helper(&pool, &headers).await?;
     handlers::rbac::create_user_handler(State(pool), Extension(user_id), Json(input)).await
 }
 
@@ -5,11 +5,12 @@
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::core::middleware::require_permission_helper;
+use crate::core::property_scope::require_property_scope;
 use crate::handlers;
 use crate::models;
 use axum::{
     Router,
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::HeaderMap,
     response::Json,
     routing::{delete, get, patch, post, put},
@@ -22,6 +23,7 @@ pub fn routes() -> Router<DbPool> {
         .route("/rooms", get(get_rooms))
         .route("/rooms", post(create_room))
         .route("/rooms/available", get(search_rooms))
+        .route("/rooms/search-priced", get(search_priced_rooms))
         .route("/rooms/{id}", patch(update_room))
         .route("/rooms/{id}", delete(delete_room_handler))
         // Room types CRUD
@@ -37,9 +39,16 @@ pub fn routes() -> Router<DbPool> {
         .route("/rooms/{id}/events", post(create_room_event))
         .route("/rooms/{id}/detailed", get(get_room_detailed))
         .route("/rooms/{id}/history", get(get_room_history))
+        .route("/rooms/{id}/bookings", get(get_room_bookings))
         .route("/rooms/{id}/end-maintenance", post(end_maintenance))
         .route("/rooms/{id}/end-cleaning", post(end_cleaning))
+        .route(
+            "/rooms/{id}/block",
+            get(list_room_blocks).post(create_room_block),
+        )
+        .route("/rooms/{id}/block/{block_id}", delete(delete_room_block))
         .route("/rooms/sync-statuses", post(sync_room_statuses))
+        .route("/rooms/reconcile-status", post(reconcile_room_statuses))
         .route("/rooms/{id}/execute-change", post(execute_room_change))
         .route("/rooms/change-history", get(get_room_change_history))
         // Occupancy endpoints (automatic - derived from bookings)
@@ -48,14 +57,18 @@ pub fn routes() -> Router<DbPool> {
         .route("/rooms/occupancy/by-type", get(get_occupancy_by_room_type))
         .route("/rooms/with-occupancy", get(get_rooms_with_occupancy))
         .route("/rooms/{id}/occupancy", get(get_room_occupancy))
+        // Reservation timeline (shares room status logic with /rooms)
+        .route("/reservations/timeline", get(get_reservation_timeline))
 }
 
 async fn get_rooms(
     State(pool): State<DbPool>,
     headers: HeaderMap,
+    query: Query<models::RoomListParams>,
 ) -> Result<Json<Vec<models::RoomWithRating>>, ApiError> {
-    require_permission_helper(&pool, &headers, "rooms:read").await?;
-    handlers::rooms::get_rooms_handler(State(pool)).await
+    let user_id = require_permission_helper(&pool, &headers, "rooms:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::get_rooms_handler(State(pool), Extension(scope), query).await
 }
 
 async fn search_rooms(
@@ -63,8 +76,19 @@ async fn search_rooms(
     headers: HeaderMap,
     query: Query<models::SearchQuery>,
 ) -> Result<Json<Vec<models::RoomWithRating>>, ApiError> {
-    require_permission_helper(&pool, &headers, "rooms:read").await?;
-    handlers::rooms::search_rooms_handler(State(pool), query).await
+    let user_id = require_permission_helper(&pool, &headers, "rooms:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::search_rooms_handler(State(pool), Extension(scope), query).await
+}
+
+async fn search_priced_rooms(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::PricedRoomSearchQuery>,
+) -> Result<Json<Vec<models::PricedRoom>>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "rooms:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::search_priced_rooms_handler(State(pool), Extension(scope), query).await
 }
 
 async fn create_room(
@@ -72,8 +96,9 @@ async fn create_room(
     headers: HeaderMap,
     Json(input): Json<models::RoomCreateInput>,
 ) -> Result<Json<models::Room>, ApiError> {
-    require_permission_helper(&pool, &headers, "rooms:write").await?;
-    handlers::rooms::create_room_handler(State(pool), Json(input)).await
+    let user_id = require_permission_helper(&pool, &headers, "rooms:write").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::create_room_handler(State(pool), Extension(scope), Json(input)).await
 }
 
 async fn update_room(
@@ -82,8 +107,9 @@ async fn update_room(
     path: Path<i64>,
     Json(input): Json<models::RoomUpdateInput>,
 ) -> Result<Json<models::Room>, ApiError> {
-    require_permission_helper(&pool, &headers, "rooms:update").await?;
-    handlers::rooms::update_room_handler(State(pool), path, Json(input)).await
+    let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::update_room_handler(State(pool), Extension(scope), path, Json(input)).await
 }
 
 async fn delete_room_handler(
@@ -91,8 +117,9 @@ async fn delete_room_handler(
     headers: HeaderMap,
     path: Path<i64>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    require_permission_helper(&pool, &headers, "rooms:write").await?;
-    handlers::rooms::delete_room_handler(State(pool), path).await
+    let user_id = require_permission_helper(&pool, &headers, "rooms:write").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::delete_room_handler(State(pool), Extension(scope), path).await
 }
 
 async fn get_room_types(
@@ -186,6 +213,17 @@ async fn get_room_history(
     handlers::rooms::get_room_history_handler(State(pool), path, headers).await
 }
 
+async fn get_room_bookings(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    query: Query<models::RoomBookingsQuery>,
+) -> Result<Json<models::RoomSchedule>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::get_room_bookings_handler(State(pool), Extension(scope), path, query).await
+}
+
 async fn end_maintenance(
     State(pool): State<DbPool>,
     path: Path<i64>,
@@ -194,6 +232,50 @@ async fn end_maintenance(
     handlers::rooms::end_maintenance_handler(State(pool), path, headers).await
 }
 
+async fn create_room_block(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+    Json(input): Json<models::RoomBlockInput>,
+) -> Result<Json<models::RoomBlock>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::create_room_block_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+        Json(input),
+    )
+    .await
+}
+
+async fn list_room_blocks(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<i64>,
+) -> Result<Json<Vec<models::RoomBlock>>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "rooms:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::list_room_blocks_handler(State(pool), Extension(scope), path).await
+}
+
+async fn delete_room_block(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    path: Path<(i64, i64)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::delete_room_block_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+        path,
+    )
+    .await
+}
+
 async fn end_cleaning(
     State(pool): State<DbPool>,
     path: Path<i64>,
@@ -209,6 +291,20 @@ async fn sync_room_statuses(
     handlers::rooms::sync_room_statuses_handler(State(pool), headers).await
 }
 
+async fn reconcile_room_statuses(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<models::RoomStatusCorrection>>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "rooms:update").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::reconcile_room_statuses_handler(
+        State(pool),
+        Extension(user_id),
+        Extension(scope),
+    )
+    .await
+}
+
 async fn execute_room_change(
     State(pool): State<DbPool>,
     path: Path<i64>,
@@ -264,3 +360,13 @@ async fn get_rooms_with_occupancy(
 ) -> Result<Json<Vec<models::RoomWithOccupancy>>, ApiError> {
     handlers::rooms::get_rooms_with_occupancy_handler(State(pool), headers).await
 }
+
+async fn get_reservation_timeline(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+    query: Query<models::ReservationTimelineQuery>,
+) -> Result<Json<models::ReservationTimeline>, ApiError> {
+    let user_id = require_permission_helper(&pool, &headers, "bookings:read").await?;
+    let scope = require_property_scope(&pool, &headers, user_id).await?;
+    handlers::rooms::get_reservation_timeline_handler(State(pool), Extension(scope), query).await
+}
@@ -4,7 +4,7 @@
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::core::middleware::require_auth;
+use crate::core::middleware::AuthUser;
 use crate::handlers;
 use crate::models;
 use axum::{
@@ -19,8 +19,10 @@ use axum::{
 pub fn routes() -> Router<DbPool> {
     Router::new()
         .route("/settings", get(get_settings))
+        .route("/settings/stay-policy", get(get_stay_policy))
         .route("/settings/{key}", patch(update_setting))
         .route("/system/process-checkins", post(process_checkins))
+        .route("/system/cleanup-uploads", post(cleanup_uploads))
 }
 
 async fn get_settings(
@@ -30,6 +32,13 @@ async fn get_settings(
     handlers::settings::get_system_settings_handler(State(pool), headers).await
 }
 
+async fn get_stay_policy(
+    State(pool): State<DbPool>,
+    _: AuthUser,
+) -> Result<Json<models::StayPolicyResponse>, ApiError> {
+    handlers::settings::get_stay_policy_handler(State(pool)).await
+}
+
 async fn update_setting(
     State(pool): State<DbPool>,
     path: Path<String>,
@@ -41,9 +50,15 @@ async fn update_setting(
 
 async fn process_checkins(
     State(pool): State<DbPool>,
-    headers: HeaderMap,
+    _: AuthUser,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     // Require authentication - only authenticated users can trigger auto check-in/checkout
-    let _user_id = require_auth(&headers).await?;
     handlers::settings::process_auto_checkin_checkout_handler(State(pool)).await
 }
+
+async fn cleanup_uploads(
+    State(pool): State<DbPool>,
+    headers: HeaderMap,
+) -> Result<Json<crate::services::upload_cleanup::UploadCleanupSummary>, ApiError> {
+    handlers::settings::cleanup_orphaned_uploads_handler(State(pool), headers).await
+}
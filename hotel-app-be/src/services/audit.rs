@@ -2,6 +2,22 @@ use crate::core::db::DbPool;
 use chrono::Utc;
 use serde_json::Value;
 
+/// Actions shown to a user in their own activity feed. Everything else
+/// (failed logins, role and settings changes, staff operations) is only
+/// visible through the audit log.
+pub const USER_ACTIVITY_ACTIONS: &[&str] = &[
+    "login_success",
+    "password_changed",
+    "profile_updated",
+    "booking_created",
+    "booking_updated",
+    "booking_cancelled",
+    "booking_checkin",
+    "booking_reactivated",
+    "api_key_created",
+    "api_key_revoked",
+];
+
 /// Audit logging service for tracking sensitive operations
 pub struct AuditLog;
 
@@ -303,6 +319,25 @@ impl AuditLog {
         .await
     }
 
+    /// Log a user updating their own profile
+    pub async fn log_profile_updated(
+        pool: &DbPool,
+        user_id: i64,
+        fields: &[&str],
+    ) -> Result<(), sqlx::Error> {
+        Self::log_event(
+            pool,
+            Some(user_id),
+            "profile_updated",
+            "user",
+            Some(user_id),
+            Some(serde_json::json!({ "fields": fields })),
+            None,
+            None,
+        )
+        .await
+    }
+
     /// Log system settings change
     pub async fn log_settings_changed(
         pool: &DbPool,
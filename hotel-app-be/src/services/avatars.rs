@@ -0,0 +1,149 @@
+//! User avatar uploads
+//!
+//! An uploaded avatar must be a JPEG, PNG or WebP image of at most
+//! [`MAX_AVATAR_BYTES`]. It is stored under `uploads/avatars` with a square
+//! PNG thumbnail beside it and set as the user's `avatar_url` and
+//! `avatar_thumbnail_url`. The files of the avatar it replaces are removed;
+//! avatars hosted elsewhere are left alone. Files that are never attached,
+//! such as those of a failed update, are left to the upload cleanup.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use image::ImageFormat;
+use image::imageops::FilterType;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::core::uploads::{JPEG, PNG, UploadPolicy, WEBP};
+use crate::models::AvatarUpload;
+use crate::services::audit::AuditLog;
+use crate::services::upload_cleanup::{UPLOAD_ROOT, upload_path};
+
+/// Directory under the upload root avatars are stored in.
+pub const AVATAR_DIR: &str = "avatars";
+
+pub const MAX_AVATAR_BYTES: usize = 5 * 1024 * 1024;
+
+pub const AVATAR_UPLOAD: UploadPolicy = UploadPolicy {
+    what: "Avatar",
+    max_bytes: MAX_AVATAR_BYTES,
+    file_types: &[&JPEG, &PNG, &WEBP],
+};
+
+/// Width and height of avatar thumbnails, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Check an upload declared as `content_type` is an avatar we accept, and
+/// that its contents match the declared type. Returns the image format and
+/// the extension to store it with.
+pub fn avatar_format(
+    content_type: &str,
+    data: &[u8],
+) -> Result<(ImageFormat, &'static str), ApiError> {
+    let file_type = AVATAR_UPLOAD.check(content_type, data)?;
+    let format = ImageFormat::from_extension(file_type.extension)
+        .ok_or_else(|| ApiError::Internal(format!("No image format for {}", file_type.name)))?;
+    Ok((format, file_type.extension))
+}
+
+/// A [`THUMBNAIL_SIZE`] square PNG of the image, cropped to its centre.
+pub fn make_thumbnail(data: &[u8], format: ImageFormat) -> Result<Vec<u8>, ApiError> {
+    let image = image::load_from_memory_with_format(data, format)
+        .map_err(|e| ApiError::BadRequest(format!("Avatar image could not be read: {}", e)))?;
+
+    let mut thumbnail = Vec::new();
+    image
+        .resize_to_fill(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle)
+        .write_to(&mut Cursor::new(&mut thumbnail), ImageFormat::Png)
+        .map_err(|e| ApiError::Internal(format!("Failed to create avatar thumbnail: {}", e)))?;
+    Ok(thumbnail)
+}
+
+/// The path under the upload root of an avatar stored for `user_id`, or
+/// `None` for anything else, including avatars hosted elsewhere.
+pub fn stored_avatar_path(reference: &str, user_id: i64) -> Option<String> {
+    let prefix = format!("{}_", user_id);
+    upload_path(reference).filter(|path| {
+        path.strip_prefix(AVATAR_DIR)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|name| !name.contains('/') && name.starts_with(&prefix))
+    })
+}
+
+/// Store `data` as the user's avatar under the upload root `root` and
+/// remove the files of the one it replaces.
+pub async fn save_avatar_in(
+    pool: &DbPool,
+    root: &Path,
+    user_id: i64,
+    content_type: &str,
+    data: &[u8],
+) -> Result<AvatarUpload, ApiError> {
+    let (format, extension) = avatar_format(content_type, data)?;
+    let thumbnail = make_thumbnail(data, format)?;
+
+    let previous: (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT avatar_url, avatar_thumbnail_url FROM users WHERE id = $1 AND is_active = true",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    let dir = root.join(AVATAR_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| ApiError::Internal(format!("Failed to create avatar directory: {}", e)))?;
+    let stem = format!("{}_{}", user_id, uuid::Uuid::new_v4());
+    let file_name = format!("{}.{}", stem, extension);
+    let thumbnail_name = format!("{}_thumb.png", stem);
+    std::fs::write(dir.join(&file_name), data)
+        .map_err(|e| ApiError::Internal(format!("Failed to save avatar: {}", e)))?;
+    std::fs::write(dir.join(&thumbnail_name), &thumbnail)
+        .map_err(|e| ApiError::Internal(format!("Failed to save avatar thumbnail: {}", e)))?;
+
+    let upload = AvatarUpload {
+        avatar_url: format!("/{}/{}/{}", UPLOAD_ROOT, AVATAR_DIR, file_name),
+        thumbnail_url: format!("/{}/{}/{}", UPLOAD_ROOT, AVATAR_DIR, thumbnail_name),
+    };
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET avatar_url = $1, avatar_thumbnail_url = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $3
+        "#,
+    )
+    .bind(&upload.avatar_url)
+    .bind(&upload.thumbnail_url)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for reference in [previous.0, previous.1].into_iter().flatten() {
+        let Some(path) = stored_avatar_path(&reference, user_id) else {
+            continue;
+        };
+        match std::fs::remove_file(root.join(&path)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("Failed to remove replaced avatar {}: {}", path, e),
+        }
+    }
+
+    let _ = AuditLog::log_profile_updated(pool, user_id, &["avatar_url"]).await;
+    log::info!("User {} uploaded avatar {}", user_id, upload.avatar_url);
+
+    Ok(upload)
+}
+
+/// Store `data` as the user's avatar and remove the one it replaces.
+pub async fn save_avatar(
+    pool: &DbPool,
+    user_id: i64,
+    content_type: &str,
+    data: &[u8],
+) -> Result<AvatarUpload, ApiError> {
+    save_avatar_in(pool, Path::new(UPLOAD_ROOT), user_id, content_type, data).await
+}
@@ -1,11 +1,26 @@
 //! Booking business logic
 
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::models::{Booking, row_mappers};
+use crate::models::{Booking, BookingConflictCheck, NightlyRate, QuoteCharge};
+use crate::repositories::booking::{BookingRepository, SqlBookingRepository};
+use crate::repositories::settings::SettingsRepository;
+use crate::services::rates;
+use crate::services::tax::{self, TaxBreakdown, TaxContext, TaxMode};
+
+/// Whether infants count towards a room type's `max_occupancy`.
+pub const INFANTS_COUNT_SETTING: &str = "occupancy_count_infants";
+
+/// Share of the nightly rate, in percent, charged for a same-day (day-use)
+/// stay.
+pub const DAY_USE_PERCENT_SETTING: &str = "day_use_rate_percent";
+pub const DEFAULT_DAY_USE_PERCENT: u32 = 100;
 
 /// Generate a unique booking number using the provided hotel-local date.
 pub fn generate_booking_number_for_date(date: NaiveDate) -> String {
@@ -16,28 +31,392 @@ pub fn generate_booking_number_for_date(date: NaiveDate) -> String {
     )
 }
 
+/// Most bookings one batch request may ask for.
+pub const MAX_BOOKING_BATCH: usize = 100;
+
+/// The distinct ids of a batch request in request order, or an error when
+/// it asks for more than [`MAX_BOOKING_BATCH`].
+pub fn batch_ids(requested: &[i64]) -> Result<Vec<i64>, ApiError> {
+    if requested.len() > MAX_BOOKING_BATCH {
+        return Err(ApiError::BadRequest(format!(
+            "At most {} bookings can be fetched at once",
+            MAX_BOOKING_BATCH
+        )));
+    }
+    let mut ids = Vec::with_capacity(requested.len());
+    for &id in requested {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Put `found` into the order of `ids`, returning it with the ids nothing
+/// was found for.
+pub fn in_request_order<T>(
+    ids: &[i64],
+    found: Vec<T>,
+    id_of: impl Fn(&T) -> i64,
+) -> (Vec<T>, Vec<i64>) {
+    let mut by_id: HashMap<i64, T> = found.into_iter().map(|item| (id_of(&item), item)).collect();
+    let mut ordered = Vec::with_capacity(by_id.len());
+    let mut missing = Vec::new();
+    for &id in ids {
+        match by_id.remove(&id) {
+            Some(item) => ordered.push(item),
+            None => missing.push(id),
+        }
+    }
+    (ordered, missing)
+}
+
 /// Fetch a single booking row by ID, returning a fully-mapped `Booking`.
 pub async fn fetch_booking_by_id(pool: &DbPool, booking_id: i64) -> Result<Booking, ApiError> {
-    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
-    let query = "SELECT * FROM bookings WHERE id = ?1";
-
-    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
-    let query = "SELECT id, booking_number, guest_id, room_id, check_in_date, check_out_date, \
-        room_rate, subtotal, tax_amount, discount_amount, total_amount, status, payment_status, \
-        payment_method, adults, children, special_requests, remarks, source, market_code, \
-        discount_percentage, rate_override_weekday, rate_override_weekend, pre_checkin_completed, \
-        pre_checkin_completed_at, pre_checkin_token, pre_checkin_token_expires_at, created_by, \
-        is_complimentary, complimentary_reason, complimentary_start_date, complimentary_end_date, \
-        original_total_amount, complimentary_nights, deposit_paid, deposit_amount, deposit_paid_at, \
-        company_id, company_name, payment_note, daily_rates, created_at, updated_at, post_type \
-        FROM bookings WHERE id = $1";
-
-    let row = sqlx::query(query)
-        .bind(booking_id)
-        .fetch_optional(pool)
+    SqlBookingRepository::new(pool)
+        .find(booking_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))
+}
+
+/// Whether `room_id` is free for the stay, listing the bookings that block it.
+pub async fn check_conflict<R: BookingRepository + Sync>(
+    repo: &R,
+    room_id: i64,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> Result<BookingConflictCheck, ApiError> {
+    if check_out < check_in {
+        return Err(ApiError::BadRequest(
+            "Check-out date must be on or after check-in date".to_string(),
+        ));
+    }
+
+    let conflicting_booking_numbers = repo.check_conflict(room_id, check_in, check_out).await?;
+    Ok(BookingConflictCheck {
+        available: conflicting_booking_numbers.is_empty(),
+        conflicting_booking_numbers,
+    })
+}
+
+/// Whether infants take up room capacity; off by default, as cots don't.
+pub async fn infants_count_towards_occupancy(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, INFANTS_COUNT_SETTING, false).await
+}
+
+/// Check a booking's party against the room type's `max_occupancy`. Adults
+/// and children always count; infants only when `count_infants` is set.
+pub fn check_guest_capacity(
+    adults: i32,
+    children: i32,
+    infants: i32,
+    max_occupancy: i32,
+    count_infants: bool,
+) -> Result<(), ApiError> {
+    if adults < 1 {
+        return Err(ApiError::BadRequest(
+            "A booking needs at least one adult".to_string(),
+        ));
+    }
+    if children < 0 || infants < 0 {
+        return Err(ApiError::BadRequest(
+            "Guest counts cannot be negative".to_string(),
+        ));
+    }
+
+    let guests = adults + children + if count_infants { infants } else { 0 };
+    if guests > max_occupancy {
+        return Err(ApiError::BadRequest(format!(
+            "This room holds at most {} guests, but {} were requested",
+            max_occupancy, guests
+        )));
+    }
+    Ok(())
+}
+
+/// Rate plan code of nights priced by the request (an override or explicit
+/// daily rates) rather than by a plan.
+pub const MANUAL_RATE_CODE: &str = "MANUAL";
+
+/// Plan-resolved rates for each billable night, or `None` when the request
+/// sets its own prices or the room has a custom price.
+pub async fn plan_rates_for_stay(
+    pool: &DbPool,
+    room_id: i64,
+    room_rate_override: Option<f64>,
+    daily_rates: Option<&serde_json::Value>,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> Result<Option<Vec<NightlyRate>>, ApiError> {
+    if room_rate_override.is_some() || daily_rates.is_some() {
+        return Ok(None);
+    }
+
+    let room_pricing: Option<(i64, bool)> =
+        sqlx::query_as("SELECT room_type_id, custom_price IS NOT NULL FROM rooms WHERE id = $1")
+            .bind(room_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    match room_pricing {
+        Some((room_type_id, false)) => Ok(Some(
+            rates::resolve_stay(pool, room_type_id, check_in, check_out).await?,
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// A stay priced the way booking creation records it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StayPrice {
+    /// Price of each billable night.
+    pub nights: Vec<NightlyRate>,
+    /// Nights charged; a same-day stay counts as one.
+    pub billable_nights: i64,
+    pub room_rate: Decimal,
+    pub subtotal: Decimal,
+    /// Service tax contained in the subtotal; `gross` is the booking total.
+    pub taxes: TaxBreakdown,
+    /// Per-night prices for `bookings.daily_rates`.
+    pub daily_rates: Option<serde_json::Value>,
+}
+
+fn nights_at(
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    price: Decimal,
+    manual: bool,
+) -> Vec<NightlyRate> {
+    let (first, last) = rates::billable_nights(check_in, check_out);
+    let code = if manual {
+        MANUAL_RATE_CODE
+    } else {
+        rates::BASE_RATE_CODE
+    };
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| NightlyRate {
+            date,
+            price,
+            rate_plan_code: code.to_string(),
+            is_base_rate: !manual,
+        })
+        .collect()
+}
+
+/// Price a stay. `room_price` is the room's configured (tax-inclusive) price
+/// and `nightly_rates` the result of [`plan_rates_for_stay`]. Plan rates win,
+/// then explicit daily rates, then the override or room price for each night;
+/// a same-day stay is charged one night.
+pub fn price_stay(
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    room_price: Decimal,
+    nightly_rates: Option<Vec<NightlyRate>>,
+    room_rate_override: Option<f64>,
+    daily_rates: Option<&serde_json::Value>,
+    tax_ctx: &TaxContext,
+) -> StayPrice {
+    let billable_nights = (check_out - check_in).num_days().max(1);
+    let room_rate = match nightly_rates.as_deref() {
+        Some([first, ..]) => first.price,
+        _ => room_rate_override
+            .map(|r| Decimal::from_f64_retain(r).unwrap_or(room_price))
+            .unwrap_or(room_price),
+    };
+    let fixed_subtotal = room_rate * Decimal::from(billable_nights);
+    // Record per-night prices when a rate plan priced any night, so the night
+    // audit posts what was actually charged.
+    let stored_daily_rates = match nightly_rates {
+        Some(ref resolved) if resolved.iter().any(|r| !r.is_base_rate) => {
+            Some(rates::daily_rates_json(resolved))
+        }
+        _ => daily_rates.cloned(),
+    };
+
+    let (nights, subtotal) = if let Some(resolved) = nightly_rates {
+        let subtotal = resolved.iter().map(|r| r.price).sum();
+        (resolved, subtotal)
+    } else if let Some(obj) = daily_rates.and_then(|v| v.as_object()) {
+        let sum: f64 = obj.values().filter_map(|v| v.as_f64()).sum();
+        let mut nights: Vec<NightlyRate> = obj
+            .iter()
+            .filter_map(|(date, price)| {
+                Some(NightlyRate {
+                    date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+                    price: Decimal::from_f64_retain(price.as_f64()?)?,
+                    rate_plan_code: MANUAL_RATE_CODE.to_string(),
+                    is_base_rate: false,
+                })
+            })
+            .collect();
+        nights.sort_by_key(|night| night.date);
+        (
+            nights,
+            Decimal::from_f64_retain(sum).unwrap_or(fixed_subtotal),
+        )
+    } else {
+        (
+            nights_at(check_in, check_out, room_rate, room_rate_override.is_some()),
+            fixed_subtotal,
+        )
+    };
+
+    StayPrice {
+        nights,
+        billable_nights,
+        room_rate,
+        subtotal,
+        // Configured prices are final; record the service tax they contain.
+        taxes: tax::compute_taxes(subtotal, tax_ctx),
+        daily_rates: stored_daily_rates,
+    }
+}
+
+fn parse_day_use_percent(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|p| (1..=100).contains(p))
+}
+
+/// Configured day-use percentage; the default charges a full night.
+pub async fn day_use_percent(pool: &DbPool) -> u32 {
+    SettingsRepository::get_value(pool, DAY_USE_PERCENT_SETTING)
         .await
-        .map_err(|e| ApiError::Database(e.to_string()))?
-        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+        .ok()
+        .flatten()
+        .and_then(|v| parse_day_use_percent(&v))
+        .unwrap_or(DEFAULT_DAY_USE_PERCENT)
+}
+
+/// Reject invalid values for the booking pricing settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == DAY_USE_PERCENT_SETTING && parse_day_use_percent(value).is_none() {
+        return Err(format!(
+            "{} must be a whole percentage between 1 and 100",
+            key
+        ));
+    }
+    Ok(())
+}
+
+/// Charge a same-day stay `percent` of its nightly rate. Nights priced by
+/// the request (an override or explicit daily rates) are charged as given.
+pub fn apply_day_use(price: StayPrice, percent: u32, tax_ctx: &TaxContext) -> StayPrice {
+    let manual = price
+        .nights
+        .iter()
+        .any(|n| n.rate_plan_code == MANUAL_RATE_CODE);
+    if manual || percent >= 100 {
+        return price;
+    }
+
+    let percent = Decimal::from(percent);
+    let share = |amount: Decimal| (amount * percent / Decimal::from(100)).round_dp(2);
+    let nights: Vec<NightlyRate> = price
+        .nights
+        .into_iter()
+        .map(|night| NightlyRate {
+            price: share(night.price),
+            ..night
+        })
+        .collect();
+    let subtotal = nights.iter().map(|n| n.price).sum();
+    StayPrice {
+        billable_nights: price.billable_nights,
+        room_rate: share(price.room_rate),
+        subtotal,
+        taxes: tax::compute_taxes(subtotal, tax_ctx),
+        daily_rates: price.daily_rates.map(|_| rates::daily_rates_json(&nights)),
+        nights,
+    }
+}
+
+/// Price a new booking's stay with the hotel's tax and day-use settings:
+/// [`price_stay`], with a same-day stay charged the day-use share. Booking
+/// creation, group bookings and quotes all price through here, so a quote
+/// matches the booking made from the same inputs.
+pub async fn price_new_booking(
+    pool: &DbPool,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    room_price: Decimal,
+    nightly_rates: Option<Vec<NightlyRate>>,
+    room_rate_override: Option<f64>,
+    daily_rates: Option<&serde_json::Value>,
+) -> StayPrice {
+    let tax_ctx = TaxContext::load(pool, TaxMode::Inclusive).await;
+    let price = price_stay(
+        check_in,
+        check_out,
+        room_price,
+        nightly_rates,
+        room_rate_override,
+        daily_rates,
+        &tax_ctx,
+    );
+    if check_out != check_in {
+        return price;
+    }
+    apply_day_use(price, day_use_percent(pool).await, &tax_ctx)
+}
+
+/// Price the nights of a stay that are still paid for once the rest are
+/// complimentary. Room rates include service tax, so comping nights never
+/// adds tax to the ones left.
+pub fn price_paid_nights(
+    room_rate: Decimal,
+    paid_nights: i32,
+    tax_ctx: &TaxContext,
+) -> TaxBreakdown {
+    tax::compute_taxes(room_rate * Decimal::from(paid_nights), tax_ctx)
+}
+
+/// Taxes and fees of a quote. Service tax is part of the subtotal; tourism
+/// tax (for tourists) and extra-bed charges are posted on top by the night
+/// audit, the extra bed once per billable night.
+pub fn quote_charges(
+    price: &StayPrice,
+    is_tourist: bool,
+    tourism_tax_amount: Option<f64>,
+    extra_bed_count: Option<i32>,
+    extra_bed_charge: Option<f64>,
+) -> (Vec<QuoteCharge>, Vec<QuoteCharge>) {
+    let positive = |amount: Option<f64>| {
+        amount
+            .and_then(Decimal::from_f64_retain)
+            .filter(|a| *a > Decimal::ZERO)
+    };
+
+    let mut taxes = vec![QuoteCharge {
+        code: "service_tax".to_string(),
+        description: "Service tax".to_string(),
+        amount: price.taxes.service_tax,
+        included: true,
+    }];
+    if is_tourist && let Some(amount) = positive(tourism_tax_amount) {
+        taxes.push(QuoteCharge {
+            code: "tourism_tax".to_string(),
+            description: "Tourism tax".to_string(),
+            amount,
+            included: false,
+        });
+    }
+
+    let mut fees = Vec::new();
+    if let Some(per_night) = positive(extra_bed_charge) {
+        let beds = extra_bed_count.unwrap_or(1).max(1);
+        fees.push(QuoteCharge {
+            code: "extra_bed".to_string(),
+            description: format!("Extra bed x{} ({} per night)", beds, per_night),
+            amount: per_night * Decimal::from(price.billable_nights),
+            included: false,
+        });
+    }
 
-    Ok(row_mappers::row_to_booking(&row))
+    (taxes, fees)
 }
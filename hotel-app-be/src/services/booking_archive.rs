@@ -0,0 +1,165 @@
+//! Booking retention and archiving
+//!
+//! Moves checked-out and cancelled bookings whose check-out is more than
+//! `older_than_days` behind the hotel's today from `bookings` into
+//! `bookings_archive`, taking their status history and modification log
+//! with them. Reports read the `bookings_all` view, which unions live and
+//! archived rows, so history is preserved.
+//!
+//! Deleting a booking cascades into its payments, invoices and other linked
+//! records, so a booking that still has any of them stays live; only the
+//! booking's own change log is moved alongside it.
+
+use chrono::{Duration, NaiveDate};
+
+use crate::core::db::{DbPool, DbTransaction};
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::hotel_time::hotel_today;
+
+/// System setting holding the retention in days; 0 leaves archiving off.
+pub const ARCHIVE_SETTING: &str = "booking_archive_after_days";
+
+/// Shortest retention accepted, so recent folios stay on hand for disputes.
+pub const MIN_ARCHIVE_DAYS: i64 = 30;
+pub const MAX_ARCHIVE_DAYS: i64 = 3650;
+
+/// Statuses a booking must be in to be archived.
+pub const ARCHIVABLE_STATUSES: &[&str] = &["checked_out", "cancelled"];
+
+/// Tables whose rows would be deleted or unlinked along with the booking.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const LINKED_TABLES: &[&str] = &[
+    "payments",
+    "invoices",
+    "customer_ledgers",
+    "deposit_forfeitures",
+    "booking_guests",
+    "booking_folio_routes",
+    "room_status_history",
+    "booking_fees",
+];
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const LINKED_TABLES: &[&str] = &[
+    "payments",
+    "invoices",
+    "customer_ledgers",
+    "deposit_forfeitures",
+    "booking_guests",
+    "booking_folio_routes",
+    "booking_services",
+    "night_audit_posted_nights",
+    "room_changes",
+    "guest_reviews",
+    "reward_redemptions",
+    "booking_fees",
+];
+
+fn parse_days(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|d| *d == 0 || (MIN_ARCHIVE_DAYS..=MAX_ARCHIVE_DAYS).contains(d))
+}
+
+/// Configured retention in days, or `None` when archiving is off or the
+/// setting is invalid.
+pub async fn configured_archive_days(pool: &DbPool) -> Option<i64> {
+    SettingsRepository::get_value(pool, ARCHIVE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_days(&v))
+        .filter(|d| *d > 0)
+}
+
+/// Reject invalid values for the archive setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == ARCHIVE_SETTING && parse_days(value).is_none() {
+        return Err(format!(
+            "{} must be 0 or a whole number of days between {} and {}",
+            key, MIN_ARCHIVE_DAYS, MAX_ARCHIVE_DAYS
+        ));
+    }
+    Ok(())
+}
+
+/// Bookings checking out before `cutoff` that can be archived. Read inside
+/// the archiving transaction so nothing links to them before they move.
+async fn archivable_booking_ids(
+    tx: &mut DbTransaction<'_>,
+    cutoff: NaiveDate,
+) -> Result<Vec<i64>, ApiError> {
+    let statuses = ARCHIVABLE_STATUSES
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let unlinked = LINKED_TABLES
+        .iter()
+        .map(|t| {
+            format!(
+                "AND NOT EXISTS (SELECT 1 FROM {} x WHERE x.booking_id = b.id)",
+                t
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n          ");
+
+    sqlx::query_scalar(&format!(
+        r#"
+        SELECT b.id FROM bookings b
+        WHERE b.status IN ({})
+          AND b.check_out_date < $1
+          {}
+        ORDER BY b.id
+        "#,
+        statuses, unlinked
+    ))
+    .bind(cutoff)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// Archive checked-out and cancelled bookings whose check-out date is more
+/// than `older_than_days` before today. Returns how many were moved.
+pub async fn archive_old_bookings(pool: &DbPool, older_than_days: i64) -> Result<u64, ApiError> {
+    if !(MIN_ARCHIVE_DAYS..=MAX_ARCHIVE_DAYS).contains(&older_than_days) {
+        return Err(ApiError::BadRequest(format!(
+            "older_than_days must be between {} and {}",
+            MIN_ARCHIVE_DAYS, MAX_ARCHIVE_DAYS
+        )));
+    }
+
+    let cutoff = hotel_today(pool).await? - Duration::days(older_than_days);
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let ids = archivable_booking_ids(&mut tx, cutoff).await?;
+
+    for id in &ids {
+        for statement in [
+            "INSERT INTO booking_history_archive SELECT * FROM booking_history WHERE booking_id = $1",
+            "INSERT INTO booking_modifications_archive SELECT * FROM booking_modifications WHERE booking_id = $1",
+            "INSERT INTO bookings_archive SELECT b.*, CURRENT_TIMESTAMP FROM bookings b WHERE b.id = $1",
+            "DELETE FROM bookings WHERE id = $1",
+        ] {
+            sqlx::query(statement)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(ids.len() as u64)
+}
@@ -0,0 +1,251 @@
+//! Booking attachments
+//!
+//! Staff attach scans to a booking, such as registration cards and signed
+//! forms. An attachment must be a JPEG, PNG or PDF of at most
+//! [`MAX_ATTACHMENT_BYTES`]. It is recorded in `booking_attachments` with the
+//! name it was uploaded under and stored as `attachments/<booking>/<id>`.
+//! That directory is outside the public upload root, so a file can only be
+//! fetched from [`download_url`], which checks the caller may read the
+//! booking. Deleting an attachment removes its file; the files of a deleted
+//! booking are left on disk.
+
+use std::path::{Path, PathBuf};
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::core::uploads::{JPEG, PDF, PNG, Upload, UploadPolicy};
+use crate::models::BookingAttachment;
+
+/// Directory attachments are stored in, one subdirectory per booking. It
+/// must not be served as static files.
+pub const ATTACHMENT_ROOT: &str = "attachments";
+
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+pub const ATTACHMENT_UPLOAD: UploadPolicy = UploadPolicy {
+    what: "Attachment",
+    max_bytes: MAX_ATTACHMENT_BYTES,
+    file_types: &[&JPEG, &PNG, &PDF],
+};
+
+/// Longest file name kept, in characters.
+pub const MAX_FILE_NAME_CHARS: usize = 255;
+
+const ATTACHMENT_COLUMNS: &str =
+    "id, booking_id, file_name, file_url, content_type, size_bytes, uploaded_by, created_at";
+
+/// The name to show for a file uploaded as `file_name`: its last path
+/// segment, trimmed and shortened to [`MAX_FILE_NAME_CHARS`], or
+/// `attachment.<extension>` when there is none.
+pub fn display_name(file_name: Option<&str>, extension: &str) -> String {
+    let name = file_name
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .map(|name| {
+            name.chars()
+                .filter(|c| !c.is_control())
+                .take(MAX_FILE_NAME_CHARS)
+                .collect::<String>()
+        })
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default();
+    if name.is_empty() {
+        format!("attachment.{}", extension)
+    } else {
+        name
+    }
+}
+
+async fn require_booking(pool: &DbPool, booking_id: i64) -> Result<(), ApiError> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM bookings WHERE id = $1")
+        .bind(booking_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))
+}
+
+/// Where attachment `attachment_id` of `booking_id` is stored under `root`.
+pub fn stored_file(root: &Path, booking_id: i64, attachment_id: i64) -> PathBuf {
+    root.join(booking_id.to_string()).join(attachment_id.to_string())
+}
+
+/// The authenticated route an attachment is downloaded from.
+pub fn download_url(booking_id: i64, attachment_id: i64) -> String {
+    format!("/bookings/{}/attachments/{}", booking_id, attachment_id)
+}
+
+/// Store `upload` as an attachment of `booking_id` under `root`. The row is
+/// only committed once the file is written.
+pub async fn save_attachment_in(
+    pool: &DbPool,
+    root: &Path,
+    booking_id: i64,
+    file_name: Option<&str>,
+    upload: &Upload,
+    user_id: i64,
+) -> Result<BookingAttachment, ApiError> {
+    require_booking(pool, booking_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let attachment_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO booking_attachments
+            (booking_id, file_name, file_url, content_type, size_bytes, uploaded_by)
+        VALUES ($1, $2, '', $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(booking_id)
+    .bind(display_name(file_name, upload.file_type.extension))
+    .bind(upload.file_type.content_types[0])
+    .bind(upload.data.len() as i64)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    let attachment = sqlx::query_as::<_, BookingAttachment>(&format!(
+        "UPDATE booking_attachments SET file_url = $1 WHERE id = $2 RETURNING {}",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(download_url(booking_id, attachment_id))
+    .bind(attachment_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let path = stored_file(root, booking_id, attachment_id);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            ApiError::Internal(format!("Failed to create attachment directory: {}", e))
+        })?;
+    }
+    std::fs::write(&path, &upload.data)
+        .map_err(|e| ApiError::Internal(format!("Failed to save attachment: {}", e)))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    log::info!(
+        "User {} attached {} to booking {}",
+        user_id,
+        attachment.file_name,
+        booking_id
+    );
+    Ok(attachment)
+}
+
+/// Store `upload` as an attachment of `booking_id`.
+pub async fn save_attachment(
+    pool: &DbPool,
+    booking_id: i64,
+    file_name: Option<&str>,
+    upload: &Upload,
+    user_id: i64,
+) -> Result<BookingAttachment, ApiError> {
+    save_attachment_in(
+        pool,
+        Path::new(ATTACHMENT_ROOT),
+        booking_id,
+        file_name,
+        upload,
+        user_id,
+    )
+    .await
+}
+
+/// The booking's attachments, oldest first.
+pub async fn list_attachments(
+    pool: &DbPool,
+    booking_id: i64,
+) -> Result<Vec<BookingAttachment>, ApiError> {
+    require_booking(pool, booking_id).await?;
+    sqlx::query_as::<_, BookingAttachment>(&format!(
+        "SELECT {} FROM booking_attachments WHERE booking_id = $1 ORDER BY created_at, id",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(booking_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// An attachment of `booking_id` and the contents of its file under `root`.
+pub async fn read_attachment_in(
+    pool: &DbPool,
+    root: &Path,
+    booking_id: i64,
+    attachment_id: i64,
+) -> Result<(BookingAttachment, Vec<u8>), ApiError> {
+    let attachment = sqlx::query_as::<_, BookingAttachment>(&format!(
+        "SELECT {} FROM booking_attachments WHERE id = $1 AND booking_id = $2",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(attachment_id)
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))?;
+
+    match std::fs::read(stored_file(root, booking_id, attachment_id)) {
+        Ok(data) => Ok((attachment, data)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(ApiError::NotFound("Attachment file not found".to_string()))
+        }
+        Err(e) => Err(ApiError::Internal(format!(
+            "Failed to read attachment: {}",
+            e
+        ))),
+    }
+}
+
+/// An attachment of `booking_id` and the contents of its file.
+pub async fn read_attachment(
+    pool: &DbPool,
+    booking_id: i64,
+    attachment_id: i64,
+) -> Result<(BookingAttachment, Vec<u8>), ApiError> {
+    read_attachment_in(pool, Path::new(ATTACHMENT_ROOT), booking_id, attachment_id).await
+}
+
+/// Remove an attachment of `booking_id` and its file under `root`. Returns
+/// the removed attachment.
+pub async fn delete_attachment_in(
+    pool: &DbPool,
+    root: &Path,
+    booking_id: i64,
+    attachment_id: i64,
+) -> Result<BookingAttachment, ApiError> {
+    let attachment = sqlx::query_as::<_, BookingAttachment>(&format!(
+        "DELETE FROM booking_attachments WHERE id = $1 AND booking_id = $2 RETURNING {}",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(attachment_id)
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))?;
+
+    let path = stored_file(root, booking_id, attachment_id);
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to remove attachment {}: {}", path.display(), e),
+    }
+    Ok(attachment)
+}
+
+/// Remove an attachment of `booking_id` and its file.
+pub async fn delete_attachment(
+    pool: &DbPool,
+    booking_id: i64,
+    attachment_id: i64,
+) -> Result<BookingAttachment, ApiError> {
+    delete_attachment_in(pool, Path::new(ATTACHMENT_ROOT), booking_id, attachment_id).await
+}
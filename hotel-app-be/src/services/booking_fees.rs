@@ -0,0 +1,360 @@
+//! Cancellation and no-show fees
+//!
+//! A late cancellation is charged the fee worked out by the cancellation
+//! policy; a no-show is charged `no_show_fee_nights` nights of the stay. Each
+//! fee is recorded once per booking in `booking_fees`, which the revenue and
+//! payment-status reports read, and audit-logged. On PostgreSQL it is also
+//! posted as a debit to the customer ledger: the company's city ledger for
+//! company-billed stays, otherwise the guest's folio with whatever the guest
+//! has already paid set against it. The SQLite `customer_ledgers` table has
+//! a different layout, so there the fee is recorded but not posted.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, DbTransaction, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::models::row_mappers;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+use crate::services::guest_balance::COMPANY_BILLING_METHOD;
+
+pub const CANCELLATION_FEE: &str = "cancellation";
+pub const NO_SHOW_FEE: &str = "no_show";
+
+pub const NO_SHOW_FEE_NIGHTS_SETTING: &str = "no_show_fee_nights";
+
+const DEFAULT_NO_SHOW_FEE_NIGHTS: i64 = 1;
+const MAX_NO_SHOW_FEE_NIGHTS: i64 = 30;
+
+/// A fee charged on a booking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookingFee {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub fee_type: String,
+    pub amount: Decimal,
+    /// `"guest"`, or `"company"` for company-billed stays
+    pub billed_to: String,
+    /// The customer ledger entry the fee was posted to, if any
+    pub ledger_id: Option<i64>,
+}
+
+fn parse_fee_nights(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|n| (0..=MAX_NO_SHOW_FEE_NIGHTS).contains(n))
+}
+
+/// Nights charged for a no-show; 0 means no-shows are not charged.
+pub async fn no_show_fee_nights(pool: &DbPool) -> i64 {
+    SettingsRepository::get_value(pool, NO_SHOW_FEE_NIGHTS_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_fee_nights(&v))
+        .unwrap_or(DEFAULT_NO_SHOW_FEE_NIGHTS)
+}
+
+/// Reject invalid values for the no-show fee setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == NO_SHOW_FEE_NIGHTS_SETTING && parse_fee_nights(value).is_none() {
+        return Err(format!(
+            "{} must be a whole number of nights between 0 and {}",
+            key, MAX_NO_SHOW_FEE_NIGHTS
+        ));
+    }
+    Ok(())
+}
+
+/// The no-show fee on a stay of `check_in` to `check_out` worth
+/// `total_amount`: `fee_nights` nights at the stay's average nightly rate,
+/// never more than the whole stay.
+pub fn no_show_fee(
+    total_amount: Decimal,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    fee_nights: i64,
+) -> Decimal {
+    let nights = (check_out - check_in).num_days().max(1);
+    let charged = fee_nights.clamp(0, nights);
+    (total_amount.max(Decimal::ZERO) * Decimal::from(charged) / Decimal::from(nights)).round_dp(2)
+}
+
+/// Charge `amount` as a `fee_type` fee on the booking, dated `fee_date`.
+/// Returns `None` when the amount is not positive or the booking already
+/// has a fee of that type.
+pub async fn charge_fee(
+    pool: &DbPool,
+    booking_id: i64,
+    fee_type: &str,
+    amount: Decimal,
+    fee_date: NaiveDate,
+    user_id: i64,
+) -> Result<Option<BookingFee>, ApiError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let fee = charge_fee_in(&mut tx, booking_id, fee_type, amount, fee_date, user_id).await?;
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if let Some(fee) = &fee {
+        record_fee_charged(pool, fee, fee_date, user_id).await;
+    }
+    Ok(fee)
+}
+
+/// [`charge_fee`] in the caller's transaction, for charging a fee together
+/// with the change that incurs it. Call [`record_fee_charged`] with the fee
+/// once the transaction is committed.
+pub async fn charge_fee_in(
+    tx: &mut DbTransaction<'_>,
+    booking_id: i64,
+    fee_type: &str,
+    amount: Decimal,
+    fee_date: NaiveDate,
+    user_id: i64,
+) -> Result<Option<BookingFee>, ApiError> {
+    if amount <= Decimal::ZERO {
+        return Ok(None);
+    }
+
+    let booking = sqlx::query(
+        r#"
+        SELECT b.booking_number, b.payment_method, b.company_name, r.room_number,
+               g.full_name AS guest_name,
+               COALESCE((SELECT SUM(CASE WHEN COALESCE(p.payment_type, 'booking') = 'refund'
+                                         THEN -p.amount ELSE p.amount END)
+                   FROM payments p
+                   WHERE p.booking_id = b.id AND p.status = 'completed'), 0) AS amount_paid
+        FROM bookings b
+        JOIN guests g ON b.guest_id = g.id
+        LEFT JOIN rooms r ON b.room_id = r.id
+        WHERE b.id = $1
+        "#,
+    )
+    .bind(booking_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+
+    let booking_number: Option<String> = booking.try_get("booking_number").ok().flatten();
+    let company_name: Option<String> = booking
+        .try_get::<Option<String>, _>("company_name")
+        .ok()
+        .flatten()
+        .filter(|c| !c.trim().is_empty());
+    let company_billed = booking
+        .try_get::<Option<String>, _>("payment_method")
+        .ok()
+        .flatten()
+        .as_deref()
+        == Some(COMPANY_BILLING_METHOD)
+        && company_name.is_some();
+    let billed_to = if company_billed { "company" } else { "guest" };
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO booking_fees (booking_id, fee_type, amount, billed_to, company_name, fee_date, charged_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (booking_id, fee_type) DO NOTHING
+        "#,
+    )
+    .bind(booking_id)
+    .bind(fee_type)
+    .bind(decimal_to_db(amount))
+    .bind(billed_to)
+    .bind(company_name.as_deref().filter(|_| company_billed))
+    .bind(fee_date)
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if inserted.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let ledger_id = {
+        let account: String = if company_billed {
+            company_name.clone().unwrap_or_default()
+        } else {
+            booking.get("guest_name")
+        };
+        // What the guest has paid on the booking already covers the fee.
+        let paid = if company_billed {
+            Decimal::ZERO
+        } else {
+            row_mappers::get_decimal(&booking, "amount_paid")
+                .max(Decimal::ZERO)
+                .min(amount)
+        };
+        let status = if paid == amount {
+            "paid"
+        } else if paid > Decimal::ZERO {
+            "partial"
+        } else {
+            "pending"
+        };
+        let room_number: Option<String> = booking.try_get("room_number").ok().flatten();
+        let description = format!(
+            "{} - {}",
+            fee_label(fee_type),
+            booking_number.as_deref().unwrap_or("booking")
+        );
+
+        let ledger_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO customer_ledgers (
+                company_name, description, expense_type, amount, paid_amount, status,
+                booking_id, post_type, posting_date, transaction_date, invoice_date,
+                due_date, room_number, folio_type, transaction_type,
+                created_by, updated_by, cashier_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6,
+                    $7, 'miscellaneous', $8, $8, $8,
+                    $8, $9, $10, 'debit',
+                    $11, $11, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(&account)
+        .bind(&description)
+        .bind(format!("{}_fee", fee_type))
+        .bind(amount)
+        .bind(paid)
+        .bind(status)
+        .bind(booking_id)
+        .bind(fee_date)
+        .bind(room_number)
+        .bind(if company_billed {
+            "city_ledger"
+        } else {
+            "guest_folio"
+        })
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        sqlx::query(
+            "UPDATE booking_fees SET ledger_id = $1 WHERE booking_id = $2 AND fee_type = $3",
+        )
+        .bind(ledger_id)
+        .bind(booking_id)
+        .bind(fee_type)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        Some(ledger_id)
+    };
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let ledger_id: Option<i64> = None;
+
+    Ok(Some(BookingFee {
+        booking_id,
+        booking_number,
+        fee_type: fee_type.to_string(),
+        amount,
+        billed_to: billed_to.to_string(),
+        ledger_id,
+    }))
+}
+
+/// Audit-log a fee charged with [`charge_fee_in`].
+pub async fn record_fee_charged(
+    pool: &DbPool,
+    fee: &BookingFee,
+    fee_date: NaiveDate,
+    user_id: i64,
+) {
+    let _ = AuditLog::log_event(
+        pool,
+        Some(user_id),
+        "booking_fee_charged",
+        "booking",
+        Some(fee.booking_id),
+        Some(serde_json::json!({
+            "booking_number": fee.booking_number,
+            "fee_type": fee.fee_type,
+            "amount": fee.amount.to_string(),
+            "billed_to": fee.billed_to,
+            "fee_date": fee_date.to_string(),
+            "ledger_id": fee.ledger_id,
+        })),
+        None,
+        None,
+    )
+    .await;
+    log::info!(
+        "Charged {} fee of {} on booking {} to the {}",
+        fee.fee_type,
+        fee.amount,
+        fee.booking_id,
+        fee.billed_to
+    );
+}
+
+/// Charge the no-show fee on no-show bookings arriving on or before
+/// `audit_date` that have not been charged yet. Nothing is charged while
+/// the policy is 0 nights.
+pub async fn charge_no_show_fees(
+    pool: &DbPool,
+    audit_date: NaiveDate,
+    user_id: i64,
+) -> Result<Vec<BookingFee>, ApiError> {
+    let fee_nights = no_show_fee_nights(pool).await;
+    if fee_nights == 0 {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT b.id, b.check_in_date, b.check_out_date, b.total_amount
+        FROM bookings b
+        WHERE b.status = 'no_show'
+          AND b.check_in_date <= $1
+          AND NOT EXISTS (SELECT 1 FROM booking_fees bf
+                          WHERE bf.booking_id = b.id AND bf.fee_type = $2)
+        ORDER BY b.check_in_date, b.id
+        "#,
+    )
+    .bind(audit_date)
+    .bind(NO_SHOW_FEE)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut charged = Vec::new();
+    for row in &rows {
+        let amount = no_show_fee(
+            row_mappers::get_decimal(row, "total_amount"),
+            row.get("check_in_date"),
+            row.get("check_out_date"),
+            fee_nights,
+        );
+        let booking_id: i64 = row.get("id");
+        if let Some(fee) =
+            charge_fee(pool, booking_id, NO_SHOW_FEE, amount, audit_date, user_id).await?
+        {
+            charged.push(fee);
+        }
+    }
+    Ok(charged)
+}
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+fn fee_label(fee_type: &str) -> &'static str {
+    match fee_type {
+        NO_SHOW_FEE => "No-show fee",
+        _ => "Cancellation fee",
+    }
+}
@@ -0,0 +1,302 @@
+//! Configurable booking number generation
+//!
+//! The `booking_number_format` setting holds a template such as
+//! `{property}-{year}-{seq:5}`. Supported tokens:
+//!
+//! - `{year}`, `{month}`, `{day}`: hotel-local date parts (`2026`, `04`, `18`)
+//! - `{date}`: `YYYYMMDD`
+//! - `{property}`: the `property_code` setting
+//! - `{seq}` / `{seq:N}`: per-prefix sequence, zero-padded to N digits (default 4)
+//!
+//! A sequence that outgrows its width keeps counting with more digits; a
+//! number that would no longer fit `bookings.booking_number` is refused.
+//! Every template must contain exactly one `{seq}`. Sequences are scoped to the
+//! rendered prefix (e.g. one counter per year for `BK-{year}-{seq}`) and are
+//! allocated atomically from `booking_number_sequences`; the unique constraint
+//! on `bookings.booking_number` remains the final guarantee. With
+//! `booking_number_check_digit` enabled a Luhn digit over the number's digits
+//! is appended. An empty template keeps the legacy `BK-YYYYMMDD-xxxxxxxx`
+//! random format. A group booking is numbered after its first booking, with a
+//! `GRP-` prefix.
+
+use chrono::NaiveDate;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::booking::generate_booking_number_for_date;
+
+pub const FORMAT_SETTING: &str = "booking_number_format";
+pub const CHECK_DIGIT_SETTING: &str = "booking_number_check_digit";
+pub const PROPERTY_CODE_SETTING: &str = "property_code";
+
+/// `bookings.booking_number` is VARCHAR(50).
+const MAX_LENGTH: usize = 50;
+/// Longest property code a template is validated against.
+const MAX_PROPERTY_CODE_LENGTH: usize = 10;
+const DEFAULT_SEQ_WIDTH: usize = 4;
+const MAX_SEQ_WIDTH: usize = 12;
+/// Attempts to skip sequence values already taken (e.g. after a counter reset).
+const MAX_ALLOCATION_ATTEMPTS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Year,
+    Month,
+    Day,
+    Date,
+    Property,
+    Seq(usize),
+}
+
+/// A validated booking number template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookingNumberFormat {
+    parts: Vec<Part>,
+    check_digit: bool,
+}
+
+impl BookingNumberFormat {
+    /// Parse and validate a template.
+    pub fn parse(template: &str, check_digit: bool) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut token = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => token.push(c),
+                            None => {
+                                return Err("Unclosed '{' in booking number format".to_string());
+                            }
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_token(&token)?);
+                }
+                '}' => return Err("Unmatched '}' in booking number format".to_string()),
+                c if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/') => literal.push(c),
+                c => {
+                    return Err(format!(
+                        "Character '{}' is not allowed in booking number format",
+                        c
+                    ));
+                }
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        let seq_count = parts.iter().filter(|p| matches!(p, Part::Seq(_))).count();
+        if seq_count != 1 {
+            return Err("Booking number format must contain exactly one {seq} token".to_string());
+        }
+
+        let format = Self { parts, check_digit };
+        let max_len = format.max_length();
+        if max_len > MAX_LENGTH {
+            return Err(format!(
+                "Booking number format can produce {} characters; the limit is {}",
+                max_len, MAX_LENGTH
+            ));
+        }
+        Ok(format)
+    }
+
+    fn max_length(&self) -> usize {
+        let body: usize = self
+            .parts
+            .iter()
+            .map(|p| match p {
+                Part::Literal(s) => s.len(),
+                Part::Year => 4,
+                Part::Month | Part::Day => 2,
+                Part::Date => 8,
+                Part::Property => MAX_PROPERTY_CODE_LENGTH,
+                Part::Seq(width) => *width,
+            })
+            .sum();
+        body + usize::from(self.check_digit)
+    }
+
+    fn render_with(&self, date: NaiveDate, property: &str, seq: &str) -> String {
+        self.parts
+            .iter()
+            .map(|p| match p {
+                Part::Literal(s) => s.clone(),
+                Part::Year => date.format("%Y").to_string(),
+                Part::Month => date.format("%m").to_string(),
+                Part::Day => date.format("%d").to_string(),
+                Part::Date => date.format("%Y%m%d").to_string(),
+                Part::Property => property.to_string(),
+                Part::Seq(_) => seq.to_string(),
+            })
+            .collect()
+    }
+
+    /// Render a booking number for `date` and sequence value `seq`, or
+    /// explain why it cannot be.
+    pub fn render(&self, date: NaiveDate, property: &str, seq: u64) -> Result<String, String> {
+        let width = self
+            .parts
+            .iter()
+            .find_map(|p| match p {
+                Part::Seq(w) => Some(*w),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_SEQ_WIDTH);
+        let mut number = self.render_with(date, property, &format!("{:0width$}", seq));
+        if self.check_digit {
+            number.push(luhn_check_digit(&number));
+        }
+        if number.len() > MAX_LENGTH {
+            return Err(format!(
+                "Booking number {} would be longer than {} characters; shorten the booking number format",
+                number, MAX_LENGTH
+            ));
+        }
+        Ok(number)
+    }
+
+    /// Key of the sequence counter this date and property draw from.
+    fn sequence_scope(&self, date: NaiveDate, property: &str) -> String {
+        self.render_with(date, property, "{seq}")
+    }
+}
+
+fn parse_token(token: &str) -> Result<Part, String> {
+    match token {
+        "year" => Ok(Part::Year),
+        "month" => Ok(Part::Month),
+        "day" => Ok(Part::Day),
+        "date" => Ok(Part::Date),
+        "property" => Ok(Part::Property),
+        "seq" => Ok(Part::Seq(DEFAULT_SEQ_WIDTH)),
+        _ => match token.strip_prefix("seq:").map(str::parse::<usize>) {
+            Some(Ok(width)) if (1..=MAX_SEQ_WIDTH).contains(&width) => Ok(Part::Seq(width)),
+            Some(_) => Err(format!(
+                "Sequence width in '{{{}}}' must be between 1 and {}",
+                token, MAX_SEQ_WIDTH
+            )),
+            None => Err(format!("Unknown booking number token '{{{}}}'", token)),
+        },
+    }
+}
+
+/// Luhn (mod 10) check digit over the ASCII digits of `s`.
+pub fn luhn_check_digit(s: &str) -> char {
+    let sum: u32 = s
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    char::from_digit((10 - sum % 10) % 10, 10).unwrap_or('0')
+}
+
+/// Load the configured format; `None` means the legacy random format.
+pub async fn configured_format(pool: &DbPool) -> Result<Option<BookingNumberFormat>, String> {
+    let template = SettingsRepository::get_value(pool, FORMAT_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    if template.trim().is_empty() {
+        return Ok(None);
+    }
+    let check_digit = SettingsRepository::get_bool(pool, CHECK_DIGIT_SETTING, false).await;
+    BookingNumberFormat::parse(template.trim(), check_digit).map(Some)
+}
+
+async fn property_code(pool: &DbPool) -> String {
+    SettingsRepository::get_value(pool, PROPERTY_CODE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v.trim().to_ascii_uppercase())
+        .unwrap_or_default()
+}
+
+/// Check the stored settings at startup so a bad template fails loudly.
+pub async fn validate_configured_format(pool: &DbPool) -> Result<(), String> {
+    if configured_format(pool).await?.is_some() {
+        let code = property_code(pool).await;
+        if code.len() > MAX_PROPERTY_CODE_LENGTH || !code.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(format!(
+                "Property code '{}' must be at most {} letters or digits",
+                code, MAX_PROPERTY_CODE_LENGTH
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn next_sequence(pool: &DbPool, scope: &str) -> Result<u64, ApiError> {
+    let value: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO booking_number_sequences (scope, last_value)
+        VALUES ($1, 1)
+        ON CONFLICT (scope) DO UPDATE
+            SET last_value = booking_number_sequences.last_value + 1
+        RETURNING last_value
+        "#,
+    )
+    .bind(scope)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(value.max(0) as u64)
+}
+
+/// Number shared by the bookings of a group, taken from the group's first
+/// booking. It is unique because booking numbers are.
+pub fn group_booking_number(lead_booking_number: &str) -> String {
+    format!("GRP-{}", lead_booking_number)
+}
+
+/// Generate the next booking number for a booking created on `date`.
+pub async fn next_booking_number(pool: &DbPool, date: NaiveDate) -> Result<String, ApiError> {
+    let format = match configured_format(pool).await.map_err(ApiError::Internal)? {
+        Some(format) => format,
+        None => return Ok(generate_booking_number_for_date(date)),
+    };
+    let property = property_code(pool).await;
+    let scope = format.sequence_scope(date, &property);
+
+    for _ in 0..MAX_ALLOCATION_ATTEMPTS {
+        let number = format
+            .render(date, &property, next_sequence(pool, &scope).await?)
+            .map_err(ApiError::Internal)?;
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM bookings WHERE booking_number = $1)")
+                .bind(&number)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+        if !taken {
+            return Ok(number);
+        }
+    }
+
+    Err(ApiError::Internal(
+        "Could not allocate a free booking number".to_string(),
+    ))
+}
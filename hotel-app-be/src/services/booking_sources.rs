@@ -0,0 +1,235 @@
+//! Booking sources (channels)
+//!
+//! `booking_sources` lists the channels a booking may come from: direct,
+//! walk-in, phone, corporate and the OTAs the hotel sells through, as a JSON
+//! array of codes like `market_codes`. A booking's `source` must be one of
+//! them. A booking made without one gets `default_booking_source`, which is
+//! always accepted, and older bookings with none are reported under it too.
+//! Sources the system sets itself, such as `complimentary_credits`, are not
+//! checked against the list.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+use crate::models::{BookingSourcesResponse, ChannelReport, ChannelSummary};
+use crate::repositories::settings::SettingsRepository;
+use crate::services::currency;
+
+pub const SOURCES_SETTING: &str = "booking_sources";
+pub const DEFAULT_SOURCE_SETTING: &str = "default_booking_source";
+
+const DEFAULT_SOURCES: &[&str] = &[
+    "walk_in",
+    "direct",
+    "phone",
+    "website",
+    "mobile",
+    "online",
+    "agent",
+    "corporate",
+    "booking_com",
+    "expedia",
+    "agoda",
+];
+const DEFAULT_SOURCE: &str = "walk_in";
+/// `bookings.source` is a VARCHAR(50).
+const MAX_SOURCE_LEN: usize = 50;
+
+/// The channels bookings may come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookingSources {
+    pub sources: Vec<String>,
+    /// Given to bookings made without a source
+    pub default_source: String,
+}
+
+impl Default for BookingSources {
+    fn default() -> Self {
+        Self {
+            sources: DEFAULT_SOURCES.iter().map(|s| s.to_string()).collect(),
+            default_source: DEFAULT_SOURCE.to_string(),
+        }
+    }
+}
+
+impl BookingSources {
+    /// The source to store for a booking given `source`: the default when
+    /// blank, otherwise a listed source.
+    pub fn resolve(&self, source: Option<&str>) -> Result<String, ApiError> {
+        let Some(source) = source.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(self.default_source.clone());
+        };
+        let source = source.to_ascii_lowercase();
+        if source == self.default_source || self.sources.contains(&source) {
+            Ok(source)
+        } else {
+            Err(ApiError::BadRequest(format!(
+                "source must be one of: {}",
+                self.sources.join(", ")
+            )))
+        }
+    }
+
+    pub fn response(&self) -> BookingSourcesResponse {
+        BookingSourcesResponse {
+            booking_sources: self.sources.clone(),
+            default_source: self.default_source.clone(),
+        }
+    }
+}
+
+/// A source code: lowercase letters, digits and underscores.
+fn parse_source(value: &str) -> Option<String> {
+    let source = value.trim().to_ascii_lowercase();
+    let valid = !source.is_empty()
+        && source.len() <= MAX_SOURCE_LEN
+        && source
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some(source)
+}
+
+/// A non-empty JSON array of source codes, without repeats.
+pub fn parse_sources(value: &str) -> Option<Vec<String>> {
+    let codes: Vec<String> = serde_json::from_str(value).ok()?;
+    let mut sources = Vec::with_capacity(codes.len());
+    for code in &codes {
+        let source = parse_source(code)?;
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    (!sources.is_empty()).then_some(sources)
+}
+
+/// Reject invalid values for the booking source settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        SOURCES_SETTING if parse_sources(value).is_none() => Err(format!(
+            "{} must be a JSON array of source codes such as [\"direct\", \"walk_in\"]",
+            key
+        )),
+        DEFAULT_SOURCE_SETTING if parse_source(value).is_none() => Err(format!(
+            "{} must be a source code of lowercase letters, digits and underscores",
+            key
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The sources from settings, falling back to the defaults for unset or
+/// invalid values.
+pub async fn configured(pool: &DbPool) -> BookingSources {
+    let value = |key: &'static str| async move {
+        SettingsRepository::get_value(pool, key)
+            .await
+            .ok()
+            .flatten()
+    };
+    let defaults = BookingSources::default();
+    BookingSources {
+        sources: value(SOURCES_SETTING)
+            .await
+            .and_then(|v| parse_sources(&v))
+            .unwrap_or(defaults.sources),
+        default_source: value(DEFAULT_SOURCE_SETTING)
+            .await
+            .and_then(|v| parse_source(&v))
+            .unwrap_or(defaults.default_source),
+    }
+}
+
+/// Average daily rate: revenue per room-night sold, zero when none were.
+pub fn average_daily_rate(revenue: Decimal, room_nights: i64) -> Decimal {
+    if room_nights > 0 {
+        (revenue / Decimal::from(room_nights)).round_dp(2)
+    } else {
+        Decimal::ZERO
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const STAY_NIGHTS: &str =
+    "MAX(CAST(julianday(check_out_date) - julianday(check_in_date) AS INTEGER), 1)";
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const STAY_NIGHTS: &str = "GREATEST(check_out_date - check_in_date, 1)";
+
+/// Bookings, room-nights and revenue per source for stays checking in from
+/// `start` to `end` inclusive, largest revenue first. A day-use stay counts
+/// as one room-night; bookings without a source count under
+/// `default_source`.
+pub async fn channel_breakdown(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+    default_source: &str,
+) -> Result<Vec<ChannelSummary>, ApiError> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT COALESCE(NULLIF(TRIM(source), ''), $3) AS channel,
+               COUNT(*) AS bookings,
+               CAST(SUM({}) AS BIGINT) AS room_nights,
+               CAST(SUM(total_amount) AS TEXT) AS revenue
+        FROM bookings_all
+        WHERE check_in_date >= $1 AND check_in_date <= $2
+          AND status NOT IN ('voided', 'cancelled', 'no_show')
+        GROUP BY 1
+        ORDER BY SUM(total_amount) DESC, 1
+        "#,
+        STAY_NIGHTS
+    ))
+    .bind(start)
+    .bind(end)
+    .bind(default_source)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let room_nights: i64 = row.get("room_nights");
+            let revenue: Option<String> = row.get("revenue");
+            let revenue = revenue.map(|r| parse_decimal(&r)).unwrap_or_default();
+            ChannelSummary {
+                source: row.get("channel"),
+                bookings: row.get("bookings"),
+                room_nights,
+                revenue,
+                adr: average_daily_rate(revenue, room_nights),
+            }
+        })
+        .collect())
+}
+
+/// The channel report for check-ins from `start` to `end` inclusive.
+pub async fn channel_report(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<ChannelReport, ApiError> {
+    if end < start {
+        return Err(ApiError::BadRequest(
+            "end must not be before start".to_string(),
+        ));
+    }
+
+    let sources = configured(pool).await;
+    let channels = channel_breakdown(pool, start, end, &sources.default_source).await?;
+    let total_room_nights = channels.iter().map(|c| c.room_nights).sum();
+    let total_revenue = channels.iter().map(|c| c.revenue).sum();
+    Ok(ChannelReport {
+        start,
+        end,
+        total_bookings: channels.iter().map(|c| c.bookings).sum(),
+        total_room_nights,
+        total_revenue,
+        adr: average_daily_rate(total_revenue, total_room_nights),
+        currency: currency::hotel_currency(pool).await,
+        channels,
+    })
+}
@@ -0,0 +1,102 @@
+//! Monthly booking trends
+//!
+//! Booking counts and revenue per calendar month of check-in, for the last
+//! [`TREND_MONTHS`] months up to and including the current one. Voided
+//! bookings are left out, as in the rest of the booking analytics. Months
+//! without bookings are reported as zero so the series has no gaps.
+
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+
+/// Months covered by the trend, the current one included.
+pub const TREND_MONTHS: u32 = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyTrend {
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub bookings: i64,
+    pub revenue: Decimal,
+}
+
+/// First day of the month containing `date`.
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// The trend months ending with the one containing `today`, oldest first.
+pub fn trend_months(today: NaiveDate) -> Vec<NaiveDate> {
+    let current = month_start(today);
+    (0..TREND_MONTHS)
+        .rev()
+        .filter_map(|back| current.checked_sub_months(Months::new(back)))
+        .collect()
+}
+
+/// One entry per trend month, taking counts and revenue from `totals`
+/// (keyed by first day of month) and zero for months it lacks.
+pub fn monthly_trends(today: NaiveDate, totals: &[MonthlyTrend]) -> Vec<MonthlyTrend> {
+    trend_months(today)
+        .into_iter()
+        .map(|month| {
+            totals
+                .iter()
+                .find(|t| t.month == month)
+                .cloned()
+                .unwrap_or(MonthlyTrend {
+                    month,
+                    bookings: 0,
+                    revenue: Decimal::ZERO,
+                })
+        })
+        .collect()
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const MONTH_OF_CHECK_IN: &str = "date(check_in_date, 'start of month')";
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const MONTH_OF_CHECK_IN: &str = "CAST(date_trunc('month', check_in_date) AS DATE)";
+
+/// Booking trends for the months up to the one containing `today`.
+pub async fn booking_trends(
+    pool: &DbPool,
+    today: NaiveDate,
+) -> Result<Vec<MonthlyTrend>, ApiError> {
+    let months = trend_months(today);
+    let (Some(&first), Some(&last)) = (months.first(), months.last()) else {
+        return Ok(Vec::new());
+    };
+    let end = last.checked_add_months(Months::new(1)).unwrap_or(last);
+
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {month} AS month, COUNT(*) AS bookings,
+               CAST(COALESCE(SUM(total_amount), 0) AS TEXT) AS revenue
+        FROM bookings
+        WHERE status NOT IN ('voided') AND check_in_date >= $1 AND check_in_date < $2
+        GROUP BY {month}
+        "#,
+        month = MONTH_OF_CHECK_IN
+    ))
+    .bind(first)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let totals: Vec<MonthlyTrend> = rows
+        .iter()
+        .map(|row| MonthlyTrend {
+            month: row.get("month"),
+            bookings: row.get("bookings"),
+            revenue: parse_decimal(&row.get::<String, _>("revenue")),
+        })
+        .collect();
+
+    Ok(monthly_trends(today, &totals))
+}
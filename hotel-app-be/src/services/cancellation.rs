@@ -0,0 +1,129 @@
+//! Booking cancellation policy
+//!
+//! Cancelling more than `cancellation_free_days` before check-in is free.
+//! Closer to arrival, or after it, the guest is charged
+//! `cancellation_fee_percent` of the booking total. Whatever the guest has
+//! paid beyond the fee is refundable.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::core::db::DbPool;
+use crate::repositories::settings::SettingsRepository;
+
+pub const FREE_DAYS_SETTING: &str = "cancellation_free_days";
+pub const FEE_PERCENT_SETTING: &str = "cancellation_fee_percent";
+
+const DEFAULT_FREE_DAYS: i64 = 2;
+const DEFAULT_FEE_PERCENT: u32 = 50;
+
+/// Upper bound accepted for the free cancellation window.
+const MAX_FREE_DAYS: i64 = 365;
+
+/// Statuses a booking can be cancelled from. Stays that have started or
+/// finished are settled at check-out instead.
+pub const CANCELLABLE_STATUSES: &[&str] = &["pending", "confirmed"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancellationPolicy {
+    pub free_days: i64,
+    pub fee_percent: u32,
+}
+
+impl Default for CancellationPolicy {
+    fn default() -> Self {
+        Self {
+            free_days: DEFAULT_FREE_DAYS,
+            fee_percent: DEFAULT_FEE_PERCENT,
+        }
+    }
+}
+
+/// What cancelling a booking costs the guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancellationCharge {
+    pub days_before_check_in: i64,
+    pub free_cancellation: bool,
+    pub cancellation_fee: Decimal,
+    pub amount_paid: Decimal,
+    pub refund_amount: Decimal,
+}
+
+fn parse_free_days(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|d| (0..=MAX_FREE_DAYS).contains(d))
+}
+
+fn parse_fee_percent(value: &str) -> Option<u32> {
+    value.trim().parse::<u32>().ok().filter(|p| *p <= 100)
+}
+
+async fn setting(pool: &DbPool, key: &str) -> Option<String> {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Load the cancellation policy, falling back to the defaults for missing
+/// or invalid values.
+pub async fn configured_policy(pool: &DbPool) -> CancellationPolicy {
+    CancellationPolicy {
+        free_days: setting(pool, FREE_DAYS_SETTING)
+            .await
+            .and_then(|v| parse_free_days(&v))
+            .unwrap_or(DEFAULT_FREE_DAYS),
+        fee_percent: setting(pool, FEE_PERCENT_SETTING)
+            .await
+            .and_then(|v| parse_fee_percent(&v))
+            .unwrap_or(DEFAULT_FEE_PERCENT),
+    }
+}
+
+/// Reject invalid values for the cancellation settings.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        FREE_DAYS_SETTING if parse_free_days(value).is_none() => Err(format!(
+            "{} must be a whole number of days between 0 and {}",
+            key, MAX_FREE_DAYS
+        )),
+        FEE_PERCENT_SETTING if parse_fee_percent(value).is_none() => Err(format!(
+            "{} must be a whole percentage between 0 and 100",
+            key
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Price cancelling a booking worth `total_amount`, of which `amount_paid`
+/// has been paid, for a stay starting `check_in` when the hotel's date is
+/// `today`.
+pub fn cancellation_charge(
+    policy: CancellationPolicy,
+    total_amount: Decimal,
+    amount_paid: Decimal,
+    check_in: NaiveDate,
+    today: NaiveDate,
+) -> CancellationCharge {
+    let days_before_check_in = (check_in - today).num_days();
+    let free_cancellation = days_before_check_in > policy.free_days;
+
+    let cancellation_fee = if free_cancellation {
+        Decimal::ZERO
+    } else {
+        (total_amount.max(Decimal::ZERO) * Decimal::from(policy.fee_percent) / Decimal::from(100))
+            .round_dp(2)
+    };
+    let amount_paid = amount_paid.max(Decimal::ZERO);
+
+    CancellationCharge {
+        days_before_check_in,
+        free_cancellation,
+        cancellation_fee,
+        amount_paid,
+        refund_amount: (amount_paid - cancellation_fee).max(Decimal::ZERO),
+    }
+}
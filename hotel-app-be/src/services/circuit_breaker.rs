@@ -0,0 +1,241 @@
+//! Retries and circuit breaking for outbound calls
+//!
+//! External endpoints (today the outbox webhook) are called through a
+//! [`CircuitBreaker`], which tracks each endpoint separately. After
+//! `failure_threshold` consecutive failures an endpoint's breaker opens and
+//! calls to it fail at once for `cooldown`, so a dead receiver can't hold up
+//! the caller. Once the cooldown has passed the breaker half-opens and lets a
+//! single probe through: success closes it, failure opens it for another
+//! cooldown. [`CircuitBreaker::call`] also retries a failed call up to
+//! `max_retries` times, stopping early if the breaker opens. The limits come
+//! from the `external_call_*` settings.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::core::db::DbPool;
+use crate::repositories::settings::SettingsRepository;
+
+pub const MAX_RETRIES_SETTING: &str = "external_call_max_retries";
+pub const FAILURE_THRESHOLD_SETTING: &str = "external_call_failure_threshold";
+pub const COOLDOWN_SETTING: &str = "external_call_cooldown_secs";
+
+const MAX_RETRIES_RANGE: (u32, u32) = (0, 5);
+const FAILURE_THRESHOLD_RANGE: (u32, u32) = (1, 100);
+const COOLDOWN_SECS_RANGE: (u32, u32) = (1, 3600);
+
+/// Base wait between retries; the nth retry waits n times this.
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerPolicy {
+    pub max_retries: u32,
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+    pub retry_backoff: Duration,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(60),
+            retry_backoff: RETRY_BACKOFF,
+        }
+    }
+}
+
+fn parse_in_range(value: &str, (min, max): (u32, u32)) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|n| (min..=max).contains(n))
+}
+
+async fn setting(pool: &DbPool, key: &str, range: (u32, u32), default: u32) -> u32 {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_in_range(&v, range))
+        .unwrap_or(default)
+}
+
+/// Load the configured policy, keeping the default for missing or invalid
+/// values.
+pub async fn configured_policy(pool: &DbPool) -> BreakerPolicy {
+    let defaults = BreakerPolicy::default();
+    BreakerPolicy {
+        max_retries: setting(
+            pool,
+            MAX_RETRIES_SETTING,
+            MAX_RETRIES_RANGE,
+            defaults.max_retries,
+        )
+        .await,
+        failure_threshold: setting(
+            pool,
+            FAILURE_THRESHOLD_SETTING,
+            FAILURE_THRESHOLD_RANGE,
+            defaults.failure_threshold,
+        )
+        .await,
+        cooldown: Duration::from_secs(
+            setting(
+                pool,
+                COOLDOWN_SETTING,
+                COOLDOWN_SECS_RANGE,
+                defaults.cooldown.as_secs() as u32,
+            )
+            .await
+            .into(),
+        ),
+        retry_backoff: defaults.retry_backoff,
+    }
+}
+
+/// Reject out-of-range values for the breaker settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    let range = match key {
+        MAX_RETRIES_SETTING => MAX_RETRIES_RANGE,
+        FAILURE_THRESHOLD_SETTING => FAILURE_THRESHOLD_RANGE,
+        COOLDOWN_SETTING => COOLDOWN_SECS_RANGE,
+        _ => return Ok(()),
+    };
+    match parse_in_range(value, range) {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "{} must be a whole number between {} and {}",
+            key, range.0, range.1
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct Endpoint {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+}
+
+/// Per-endpoint circuit breaker. State lives in memory, so every process
+/// starts with all breakers closed.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    policy: Mutex<BreakerPolicy>,
+    endpoints: Mutex<HashMap<String, Endpoint>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: BreakerPolicy) -> Self {
+        Self {
+            policy: Mutex::new(policy),
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn policy(&self) -> BreakerPolicy {
+        *self.policy.lock().unwrap()
+    }
+
+    /// Apply new limits; endpoint state is kept.
+    pub fn set_policy(&self, policy: BreakerPolicy) {
+        *self.policy.lock().unwrap() = policy;
+    }
+
+    pub fn state(&self, endpoint: &str, now: Instant) -> BreakerState {
+        let cooldown = self.policy().cooldown;
+        match self
+            .endpoints
+            .lock()
+            .unwrap()
+            .get(endpoint)
+            .and_then(|e| e.opened_at)
+        {
+            None => BreakerState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) < cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Whether a call to `endpoint` may go ahead at `now`. A half-open
+    /// breaker admits one probe at a time.
+    pub fn try_acquire(&self, endpoint: &str, now: Instant) -> bool {
+        let cooldown = self.policy().cooldown;
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let Some(state) = endpoints.get_mut(endpoint) else {
+            return true;
+        };
+        match state.opened_at {
+            None => true,
+            Some(opened_at) if now.duration_since(opened_at) < cooldown => false,
+            Some(_) if state.probe_in_flight => false,
+            Some(_) => {
+                state.probe_in_flight = true;
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&self, endpoint: &str) {
+        self.endpoints.lock().unwrap().remove(endpoint);
+    }
+
+    pub fn record_failure(&self, endpoint: &str, now: Instant) {
+        let threshold = self.policy().failure_threshold;
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints.entry(endpoint.to_string()).or_default();
+        state.consecutive_failures += 1;
+        if state.probe_in_flight || state.consecutive_failures >= threshold {
+            state.opened_at = Some(now);
+            state.probe_in_flight = false;
+        }
+    }
+
+    /// Run `call` against `endpoint`, retrying failures with a growing wait.
+    /// Fails without calling when the breaker is open.
+    pub async fn call<T, F, Fut>(&self, endpoint: &str, mut call: F) -> Result<T, String>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let policy = self.policy();
+        let mut last_error = None;
+
+        for attempt in 0..=policy.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(policy.retry_backoff * attempt).await;
+            }
+            if !self.try_acquire(endpoint, Instant::now()) {
+                break;
+            }
+            match call().await {
+                Ok(value) => {
+                    self.record_success(endpoint);
+                    return Ok(value);
+                }
+                Err(error) => {
+                    self.record_failure(endpoint, Instant::now());
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(match last_error {
+            Some(error) if self.state(endpoint, Instant::now()) == BreakerState::Closed => error,
+            Some(error) => format!("{} (circuit open)", error),
+            None => "circuit open; call skipped".to_string(),
+        })
+    }
+}
@@ -0,0 +1,321 @@
+//! Guest communication consent
+//!
+//! Guests carry `email_opt_in` and `sms_opt_in`. NULL means the guest has not
+//! chosen, and the `guest_email_opt_in_default` / `guest_sms_opt_in_default`
+//! setting applies. `marketing_opt_in` has no such setting: nobody receives
+//! promotions until they opt in. Anything that messages a guest asks
+//! [`may_contact`] first, which logs every message it suppresses; a
+//! promotional email needs both [`Channel::Marketing`] and [`Channel::Email`].
+//! The backend has no mail or SMS transport of its own: guest emails are
+//! queued on the outbox for the webhook receiver to send, so consent is
+//! checked when a message is queued.
+//!
+//! Every consent given or revoked is kept in `guest_consent_history` with who
+//! made the change, and `consent_timestamp` on the guest is the latest.
+
+use chrono::{DateTime, Utc};
+
+use crate::core::db::{DbConnection, DbPool, DbTransaction};
+use crate::core::error::ApiError;
+use crate::models::{
+    Booking, CommunicationPreferences, CommunicationPreferencesUpdate, ConsentChange, GroupBooking,
+};
+use crate::repositories::settings::SettingsRepository;
+use crate::services::outbox;
+
+pub const EMAIL_DEFAULT_SETTING: &str = "guest_email_opt_in_default";
+pub const SMS_DEFAULT_SETTING: &str = "guest_sms_opt_in_default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Email,
+    Sms,
+    /// Promotional messages, whatever they are sent by
+    Marketing,
+}
+
+impl Channel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Email => "email",
+            Channel::Sms => "sms",
+            Channel::Marketing => "marketing",
+        }
+    }
+}
+
+async fn default_opt_in(conn: &mut DbConnection, key: &str, fallback: bool) -> bool {
+    sqlx::query_scalar::<_, String>("SELECT value FROM system_settings WHERE key = $1")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| SettingsRepository::parse_bool(&v))
+        .unwrap_or(fallback)
+}
+
+/// A guest's preferences with the defaults applied; `None` if there is no
+/// such guest. Runs on `conn` so it can see a caller's uncommitted changes.
+pub async fn preferences(
+    conn: &mut DbConnection,
+    guest_id: i64,
+) -> Result<Option<CommunicationPreferences>, ApiError> {
+    let chosen: Option<(Option<bool>, Option<bool>, bool, Option<DateTime<Utc>>)> = sqlx::query_as(
+        "SELECT email_opt_in, sms_opt_in, marketing_opt_in, consent_timestamp
+             FROM guests WHERE id = $1",
+    )
+    .bind(guest_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    let Some((email, sms, marketing_opt_in, consent_timestamp)) = chosen else {
+        return Ok(None);
+    };
+
+    Ok(Some(CommunicationPreferences {
+        email_opt_in: match email {
+            Some(chosen) => chosen,
+            None => default_opt_in(conn, EMAIL_DEFAULT_SETTING, true).await,
+        },
+        sms_opt_in: match sms {
+            Some(chosen) => chosen,
+            None => default_opt_in(conn, SMS_DEFAULT_SETTING, false).await,
+        },
+        marketing_opt_in,
+        consent_timestamp,
+    }))
+}
+
+/// [`preferences`] on a pooled connection, with a missing guest as `NotFound`.
+pub async fn guest_preferences(
+    pool: &DbPool,
+    guest_id: i64,
+) -> Result<CommunicationPreferences, ApiError> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    preferences(&mut conn, guest_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Guest not found".to_string()))
+}
+
+/// The consents `update` gives or revokes against what is stored. Choosing
+/// a channel the guest has not chosen before counts, even if it matches the
+/// default.
+pub fn consent_changes(
+    stored: (Option<bool>, Option<bool>, bool),
+    update: CommunicationPreferencesUpdate,
+) -> Vec<(Channel, bool)> {
+    let (email, sms, marketing) = stored;
+    [
+        (Channel::Email, email, update.email_opt_in),
+        (Channel::Sms, sms, update.sms_opt_in),
+        (Channel::Marketing, Some(marketing), update.marketing_opt_in),
+    ]
+    .into_iter()
+    .filter_map(|(channel, stored, chosen)| {
+        chosen
+            .filter(|chosen| stored != Some(*chosen))
+            .map(|chosen| (channel, chosen))
+    })
+    .collect()
+}
+
+/// Record the channels a guest chose; omitted channels keep their value.
+/// Each consent given or revoked is logged to the guest's consent history
+/// as made by `changed_by`.
+pub async fn update_preferences(
+    pool: &DbPool,
+    guest_id: i64,
+    update: CommunicationPreferencesUpdate,
+    changed_by: Option<i64>,
+) -> Result<CommunicationPreferences, ApiError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let stored: Option<(Option<bool>, Option<bool>, bool)> = sqlx::query_as(
+        "SELECT email_opt_in, sms_opt_in, marketing_opt_in FROM guests WHERE id = $1",
+    )
+    .bind(guest_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    let stored = stored.ok_or_else(|| ApiError::NotFound("Guest not found".to_string()))?;
+
+    let changes = consent_changes(stored, update);
+    if changes.is_empty() {
+        tx.rollback().await.ok();
+        return guest_preferences(pool, guest_id).await;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE guests
+        SET email_opt_in = COALESCE($1, email_opt_in),
+            sms_opt_in = COALESCE($2, sms_opt_in),
+            marketing_opt_in = COALESCE($3, marketing_opt_in),
+            consent_timestamp = CURRENT_TIMESTAMP,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $4
+        "#,
+    )
+    .bind(update.email_opt_in)
+    .bind(update.sms_opt_in)
+    .bind(update.marketing_opt_in)
+    .bind(guest_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for (channel, opted_in) in changes {
+        sqlx::query(
+            "INSERT INTO guest_consent_history (guest_id, channel, opted_in, changed_by)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(guest_id)
+        .bind(channel.as_str())
+        .bind(opted_in)
+        .bind(changed_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    guest_preferences(pool, guest_id).await
+}
+
+/// Every consent `guest_id` has given or revoked, newest first.
+pub async fn consent_history(pool: &DbPool, guest_id: i64) -> Result<Vec<ConsentChange>, ApiError> {
+    guest_preferences(pool, guest_id).await?;
+    sqlx::query_as::<_, ConsentChange>(
+        r#"
+        SELECT id, channel, opted_in, changed_by, created_at
+        FROM guest_consent_history
+        WHERE guest_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(guest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// Whether a `purpose` message may be sent to `guest_id` on `channel`.
+/// Unknown guests may not be contacted. Suppressed messages are logged.
+pub async fn may_contact(
+    conn: &mut DbConnection,
+    guest_id: i64,
+    channel: Channel,
+    purpose: &str,
+) -> Result<bool, ApiError> {
+    let allowed = preferences(conn, guest_id)
+        .await?
+        .is_some_and(|prefs| match channel {
+            Channel::Email => prefs.email_opt_in,
+            Channel::Sms => prefs.sms_opt_in,
+            Channel::Marketing => prefs.marketing_opt_in,
+        });
+    if !allowed {
+        log::info!(
+            "Suppressed {} {} for guest {}: guest has not consented to {}",
+            purpose,
+            channel.as_str(),
+            guest_id,
+            channel.as_str()
+        );
+    }
+    Ok(allowed)
+}
+
+/// Email address to send `guest_id` a booking confirmation, or `None` when
+/// the guest has no address or has opted out of email.
+async fn confirmation_address(
+    tx: &mut DbTransaction<'_>,
+    guest_id: i64,
+) -> Result<Option<String>, ApiError> {
+    let email: Option<Option<String>> =
+        sqlx::query_scalar("SELECT email FROM guests WHERE id = $1")
+            .bind(guest_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    let Some(email) = email.flatten().filter(|e| !e.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let consented =
+        may_contact(&mut **tx, guest_id, Channel::Email, "booking confirmation").await?;
+    Ok(consented.then_some(email))
+}
+
+/// Queue the booking confirmation email in the booking's transaction.
+/// Returns `false`, queueing nothing, when the guest has no email address or
+/// has opted out of email.
+pub async fn queue_booking_confirmation(
+    tx: &mut DbTransaction<'_>,
+    booking: &Booking,
+) -> Result<bool, ApiError> {
+    let Some(email) = confirmation_address(tx, booking.guest_id).await? else {
+        return Ok(false);
+    };
+
+    outbox::enqueue(
+        tx,
+        outbox::GUEST_BOOKING_CONFIRMATION,
+        "booking",
+        booking.id,
+        &serde_json::json!({
+            "channel": Channel::Email.as_str(),
+            "to": email,
+            "guest_id": booking.guest_id,
+            "booking_id": booking.id,
+            "booking_number": &booking.booking_number,
+            "check_in_date": booking.check_in_date.to_string(),
+            "check_out_date": booking.check_out_date.to_string(),
+            "total_amount": booking.total_amount.to_string(),
+        }),
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Queue one confirmation email for a whole group booking, numbered with the
+/// group number and totalled over its rooms. Returns `false` as
+/// [`queue_booking_confirmation`] does.
+pub async fn queue_group_booking_confirmation(
+    tx: &mut DbTransaction<'_>,
+    group: &GroupBooking,
+) -> Result<bool, ApiError> {
+    let Some(lead) = group.bookings.first() else {
+        return Ok(false);
+    };
+    let Some(email) = confirmation_address(tx, lead.guest_id).await? else {
+        return Ok(false);
+    };
+
+    outbox::enqueue(
+        tx,
+        outbox::GUEST_BOOKING_CONFIRMATION,
+        "booking",
+        lead.id,
+        &serde_json::json!({
+            "channel": Channel::Email.as_str(),
+            "to": email,
+            "guest_id": lead.guest_id,
+            "booking_id": lead.id,
+            "booking_number": &group.group_booking_number,
+            "booking_numbers": group.bookings.iter().map(|b| &b.booking_number).collect::<Vec<_>>(),
+            "check_in_date": lead.check_in_date.to_string(),
+            "check_out_date": lead.check_out_date.to_string(),
+            "total_amount": group.total_amount.to_string(),
+        }),
+    )
+    .await?;
+    Ok(true)
+}
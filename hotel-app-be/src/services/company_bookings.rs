@@ -0,0 +1,153 @@
+//! Company booking activity
+//!
+//! A booking is attributed to a company through `bookings.company_id`, set
+//! when it is booked for the company or later on the booking. This is the
+//! booking side of a company's account; the city ledger holds what was
+//! billed to it. Archived bookings are included, as in the other reports,
+//! and voided, cancelled and no-show bookings earn no revenue.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+use crate::models::{CompanyBooking, CompanyRevenue};
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const COMPANY_BOOKINGS_QUERY: &str = r#"
+    SELECT b.id, b.booking_number, g.first_name || ' ' || g.last_name AS guest_name,
+           r.room_number, b.check_in_date, b.check_out_date, b.status,
+           CAST(b.total_amount AS TEXT) AS total_amount,
+           b.archived_at IS NOT NULL AS archived
+    FROM bookings_all b
+    LEFT JOIN guests g ON g.id = b.guest_id
+    LEFT JOIN rooms r ON r.id = b.room_id
+    WHERE b.company_id = $1
+      AND ($2 IS NULL OR b.check_in_date >= $2)
+      AND ($3 IS NULL OR b.check_in_date <= $3)
+    ORDER BY b.check_in_date DESC, b.id DESC
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const COMPANY_BOOKINGS_QUERY: &str = r#"
+    SELECT b.id, b.booking_number, g.first_name || ' ' || g.last_name AS guest_name,
+           r.room_number, b.check_in_date, b.check_out_date, b.status,
+           CAST(b.total_amount AS TEXT) AS total_amount,
+           b.archived_at IS NOT NULL AS archived
+    FROM bookings_all b
+    LEFT JOIN guests g ON g.id = b.guest_id
+    LEFT JOIN rooms r ON r.id = b.room_id
+    WHERE b.company_id = $1
+      AND ($2::date IS NULL OR b.check_in_date >= $2)
+      AND ($3::date IS NULL OR b.check_in_date <= $3)
+    ORDER BY b.check_in_date DESC, b.id DESC
+"#;
+
+/// Revenue per company from stays checking in between `$1` and `$2`.
+const REVENUE_BY_COMPANY_QUERY: &str = r#"
+    SELECT company_id, MAX(company_name) AS company_name, COUNT(*) AS bookings,
+           CAST(SUM(total_amount) AS TEXT) AS revenue
+    FROM bookings_all
+    WHERE company_id IS NOT NULL
+      AND check_in_date >= $1 AND check_in_date <= $2
+      AND status NOT IN ('voided', 'cancelled', 'no_show')
+    GROUP BY company_id
+    ORDER BY SUM(total_amount) DESC, company_id
+"#;
+
+/// Whether a booking in `status` earned revenue.
+fn is_stay(status: Option<&str>) -> bool {
+    !matches!(status, Some("voided" | "cancelled" | "no_show"))
+}
+
+/// The number of stays among `bookings` and their total.
+pub fn stay_totals(bookings: &[CompanyBooking]) -> (i64, Decimal) {
+    bookings
+        .iter()
+        .filter(|b| is_stay(b.status.as_deref()))
+        .fold((0, Decimal::ZERO), |(stays, revenue), b| {
+            (stays + 1, revenue + b.total_amount)
+        })
+}
+
+/// The name of active company `company_id`, for attributing a booking to it.
+pub async fn bookable_company_name(pool: &DbPool, company_id: i64) -> Result<String, ApiError> {
+    let company: Option<(String, bool)> =
+        sqlx::query_as("SELECT company_name, is_active FROM companies WHERE id = $1")
+            .bind(company_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    match company {
+        Some((name, true)) => Ok(name),
+        Some((name, false)) => Err(ApiError::BadRequest(format!(
+            "Company {} is inactive",
+            name
+        ))),
+        None => Err(ApiError::NotFound("Company not found".to_string())),
+    }
+}
+
+/// Bookings attributed to `company_id`, newest check-in first, optionally
+/// limited to check-ins from `start` to `end` inclusive.
+pub async fn company_bookings(
+    pool: &DbPool,
+    company_id: i64,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> Result<Vec<CompanyBooking>, ApiError> {
+    let rows = sqlx::query(COMPANY_BOOKINGS_QUERY)
+        .bind(company_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let total_amount: Option<String> = row.get("total_amount");
+            CompanyBooking {
+                id: row.get("id"),
+                booking_number: row.get("booking_number"),
+                guest_name: row.get("guest_name"),
+                room_number: row.get("room_number"),
+                check_in_date: row.get("check_in_date"),
+                check_out_date: row.get("check_out_date"),
+                status: row.get("status"),
+                total_amount: total_amount.map(|t| parse_decimal(&t)).unwrap_or_default(),
+                archived: row.get("archived"),
+            }
+        })
+        .collect())
+}
+
+/// Revenue from each company's stays checking in from `start` to `end`
+/// inclusive, largest first.
+pub async fn revenue_by_company(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<CompanyRevenue>, ApiError> {
+    let rows = sqlx::query(REVENUE_BY_COMPANY_QUERY)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let revenue: Option<String> = row.get("revenue");
+            CompanyRevenue {
+                company_id: row.get("company_id"),
+                company_name: row.get("company_name"),
+                bookings: row.get("bookings"),
+                revenue: revenue.map(|r| parse_decimal(&r)).unwrap_or_default(),
+            }
+        })
+        .collect())
+}
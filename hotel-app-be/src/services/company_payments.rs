@@ -0,0 +1,381 @@
+//! Batch payments against a company's city-ledger invoices
+//!
+//! Finance often receives one transfer covering several invoices. The amount
+//! is split across the company's open `customer_ledgers` debits, either
+//! oldest invoice first or per an explicit allocation list, and each share is
+//! recorded as a `customer_ledger_payments` row. Anything left over becomes a
+//! credit entry on the company's ledger. The whole posting, with its outbox
+//! event, is one transaction.
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::models::row_mappers::get_decimal;
+use crate::models::{AppliedLedgerPayment, CompanyPaymentRequest, CompanyPaymentResult};
+use crate::services::{hotel_time, outbox};
+
+/// An open debit on the company ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenInvoice {
+    pub ledger_id: i64,
+    pub invoice_number: Option<String>,
+    pub amount: Decimal,
+    pub paid_amount: Decimal,
+}
+
+impl OpenInvoice {
+    pub fn balance_due(&self) -> Decimal {
+        (self.amount - self.paid_amount).max(Decimal::ZERO)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAllocation {
+    pub ledger_id: i64,
+    pub amount: Decimal,
+}
+
+/// How a payment is split; `credit` is the unapplied remainder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationPlan {
+    pub allocations: Vec<PlannedAllocation>,
+    pub credit: Decimal,
+}
+
+/// Apply `amount` to `open` in order, settling each invoice before moving to
+/// the next. `open` must already be sorted oldest first.
+pub fn allocate_oldest_first(amount: Decimal, open: &[OpenInvoice]) -> AllocationPlan {
+    let mut remaining = amount;
+    let mut allocations = Vec::new();
+
+    for invoice in open {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        let share = remaining.min(invoice.balance_due());
+        if share > Decimal::ZERO {
+            allocations.push(PlannedAllocation {
+                ledger_id: invoice.ledger_id,
+                amount: share,
+            });
+            remaining -= share;
+        }
+    }
+
+    AllocationPlan {
+        allocations,
+        credit: remaining,
+    }
+}
+
+/// Check an explicit split against the open invoices. Each requested invoice
+/// must be open for the company and may not be overpaid; the shares together
+/// may not exceed the payment.
+pub fn allocate_explicit(
+    amount: Decimal,
+    open: &[OpenInvoice],
+    requested: &[PlannedAllocation],
+) -> Result<AllocationPlan, ApiError> {
+    if requested.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Allocations must list at least one invoice".to_string(),
+        ));
+    }
+
+    let mut allocated = Decimal::ZERO;
+    for (index, allocation) in requested.iter().enumerate() {
+        if allocation.amount <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(
+                "Allocation amounts must be positive".to_string(),
+            ));
+        }
+        if requested[..index]
+            .iter()
+            .any(|a| a.ledger_id == allocation.ledger_id)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Ledger {} is allocated more than once",
+                allocation.ledger_id
+            )));
+        }
+        let invoice = open
+            .iter()
+            .find(|i| i.ledger_id == allocation.ledger_id)
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Ledger {} is not an open invoice for this company",
+                    allocation.ledger_id
+                ))
+            })?;
+        if allocation.amount > invoice.balance_due() {
+            return Err(ApiError::BadRequest(format!(
+                "Allocation for ledger {} exceeds its balance due of {}",
+                allocation.ledger_id,
+                invoice.balance_due()
+            )));
+        }
+        allocated += allocation.amount;
+    }
+
+    if allocated > amount {
+        return Err(ApiError::BadRequest(
+            "Allocations exceed the payment amount".to_string(),
+        ));
+    }
+
+    Ok(AllocationPlan {
+        allocations: requested.to_vec(),
+        credit: amount - allocated,
+    })
+}
+
+fn money(value: f64, what: &str) -> Result<Decimal, ApiError> {
+    Decimal::from_f64_retain(value)
+        .map(|d| d.round_dp(2))
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid {}", what)))
+}
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const OPEN_INVOICES_QUERY: &str = r#"
+    SELECT id, invoice_number, amount, paid_amount
+    FROM customer_ledgers
+    WHERE company_name = $1
+      AND COALESCE(transaction_type, 'debit') = 'debit'
+      AND COALESCE(is_reversal, false) = false
+      AND void_at IS NULL
+      AND status NOT IN ('paid', 'cancelled')
+      AND amount > COALESCE(paid_amount, 0)
+    ORDER BY COALESCE(invoice_date, transaction_date, CAST(created_at AS DATE)), created_at, id
+    FOR UPDATE
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const OPEN_INVOICES_QUERY: &str = r#"
+    SELECT id, invoice_number, amount, paid_amount
+    FROM customer_ledgers
+    WHERE company_name = ?1
+      AND COALESCE(transaction_type, 'debit') = 'debit'
+      AND COALESCE(is_reversal, 0) = 0
+      AND void_at IS NULL
+      AND status NOT IN ('paid', 'cancelled')
+      AND CAST(amount AS REAL) > CAST(COALESCE(paid_amount, 0) AS REAL)
+    ORDER BY COALESCE(invoice_date, transaction_date, date(created_at)), created_at, id
+"#;
+
+/// Post `request` against company `company_id`'s open invoices.
+pub async fn post_company_payment(
+    pool: &DbPool,
+    company_id: i64,
+    user_id: i64,
+    request: &CompanyPaymentRequest,
+) -> Result<CompanyPaymentResult, ApiError> {
+    let amount = money(request.amount, "payment amount")?;
+    if amount <= Decimal::ZERO {
+        return Err(ApiError::BadRequest(
+            "Payment amount must be positive".to_string(),
+        ));
+    }
+    let payment_method = request.payment_method.trim();
+    if payment_method.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Payment method is required".to_string(),
+        ));
+    }
+    let payment_date: Option<NaiveDateTime> = match request.payment_date.as_deref() {
+        Some(d) => Some(
+            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map_err(|_| ApiError::BadRequest("payment_date must be YYYY-MM-DD".to_string()))?
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+        ),
+        None => None,
+    };
+    let requested = match &request.allocations {
+        Some(list) => Some(
+            list.iter()
+                .map(|a| {
+                    Ok(PlannedAllocation {
+                        ledger_id: a.ledger_id,
+                        amount: money(a.amount, "allocation amount")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, ApiError>>()?,
+        ),
+        None => None,
+    };
+
+    let company_name: String =
+        sqlx::query_scalar("SELECT company_name FROM companies WHERE id = $1")
+            .bind(company_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound("Company not found".to_string()))?;
+    let today = hotel_time::hotel_today(pool).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let open: Vec<OpenInvoice> = sqlx::query(OPEN_INVOICES_QUERY)
+        .bind(&company_name)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .iter()
+        .map(|row| OpenInvoice {
+            ledger_id: row.get("id"),
+            invoice_number: row.try_get("invoice_number").ok().flatten(),
+            amount: get_decimal(row, "amount"),
+            paid_amount: get_decimal(row, "paid_amount"),
+        })
+        .collect();
+
+    let plan = match &requested {
+        Some(list) => allocate_explicit(amount, &open, list)?,
+        None => allocate_oldest_first(amount, &open),
+    };
+
+    let mut applied = Vec::with_capacity(plan.allocations.len());
+    for allocation in &plan.allocations {
+        // The plan only names invoices taken from `open`.
+        let invoice = open
+            .iter()
+            .find(|i| i.ledger_id == allocation.ledger_id)
+            .expect("allocation refers to an open invoice");
+
+        let payment_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO customer_ledger_payments (
+                ledger_id, payment_amount, payment_method, payment_reference,
+                payment_date, notes, processed_by
+            )
+            VALUES ($1, $2, $3, $4, COALESCE($5, CURRENT_TIMESTAMP), $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(allocation.ledger_id)
+        .bind(decimal_to_db(allocation.amount))
+        .bind(payment_method)
+        .bind(&request.payment_reference)
+        .bind(payment_date)
+        .bind(&request.notes)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        let paid_amount = invoice.paid_amount + allocation.amount;
+        let status = if paid_amount >= invoice.amount {
+            "paid"
+        } else {
+            "partial"
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE customer_ledgers
+            SET paid_amount = $1,
+                status = $2,
+                payment_method = $3,
+                payment_reference = $4,
+                payment_date = COALESCE($5, CURRENT_TIMESTAMP),
+                updated_at = CURRENT_TIMESTAMP,
+                updated_by = $6
+            WHERE id = $7
+            "#,
+        )
+        .bind(decimal_to_db(paid_amount))
+        .bind(status)
+        .bind(payment_method)
+        .bind(&request.payment_reference)
+        .bind(payment_date)
+        .bind(user_id)
+        .bind(allocation.ledger_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        applied.push(AppliedLedgerPayment {
+            ledger_id: allocation.ledger_id,
+            payment_id,
+            invoice_number: invoice.invoice_number.clone(),
+            amount_applied: allocation.amount,
+            balance_due: invoice.amount - paid_amount,
+            status: status.to_string(),
+        });
+    }
+
+    // The credit is recorded settled, like a reversal, so it does not count
+    // towards the ledger's outstanding total.
+    let credit_ledger_id: Option<i64> = if plan.credit > Decimal::ZERO {
+        let id = sqlx::query_scalar(
+            r#"
+            INSERT INTO customer_ledgers (
+                company_name, description, expense_type, amount, status, paid_amount,
+                payment_method, payment_reference, payment_date, notes,
+                folio_type, transaction_type, post_type, posting_date, transaction_date,
+                created_by, updated_by, cashier_id
+            )
+            VALUES ($1, $2, 'payment', $3, 'paid', $3,
+                    $4, $5, COALESCE($6, CURRENT_TIMESTAMP), $7,
+                    'city_ledger', 'credit', 'advance_deposit', $9, $9,
+                    $8, $8, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(&company_name)
+        .bind(match request.payment_reference.as_deref() {
+            Some(reference) => format!("Unapplied payment credit ({})", reference),
+            None => "Unapplied payment credit".to_string(),
+        })
+        .bind(decimal_to_db(plan.credit))
+        .bind(payment_method)
+        .bind(&request.payment_reference)
+        .bind(payment_date)
+        .bind(&request.notes)
+        .bind(user_id)
+        .bind(today)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        Some(id)
+    } else {
+        None
+    };
+
+    outbox::enqueue(
+        &mut tx,
+        outbox::PAYMENT_POSTED,
+        "company",
+        company_id,
+        &serde_json::json!({
+            "company_id": company_id,
+            "company_name": &company_name,
+            "payment_method": payment_method,
+            "payment_reference": &request.payment_reference,
+            "amount": amount.to_string(),
+            "credit_amount": plan.credit.to_string(),
+            "ledger_payment_ids": applied.iter().map(|a| a.payment_id).collect::<Vec<_>>(),
+        }),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(CompanyPaymentResult {
+        company_id,
+        company_name,
+        amount,
+        amount_applied: amount - plan.credit,
+        credit_amount: plan.credit,
+        credit_ledger_id,
+        applied,
+    })
+}
@@ -0,0 +1,110 @@
+//! Hotel currency and money formatting
+//!
+//! Amounts are plain decimals everywhere; the currency they are in is the
+//! `currency` setting, an ISO 4217 code. Responses that carry amounts also
+//! carry the code, so clients format money themselves. [`format_money`] is
+//! for the few places the server writes an amount into text.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::core::db::DbPool;
+use crate::repositories::settings::SettingsRepository;
+
+pub const CURRENCY_SETTING: &str = "currency";
+
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// A three-letter currency code, upper-cased.
+pub fn parse_currency(value: &str) -> Option<String> {
+    let code = value.trim();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| code.to_ascii_uppercase())
+}
+
+/// The hotel's currency code; [`DEFAULT_CURRENCY`] when unset or invalid.
+pub async fn hotel_currency(pool: &DbPool) -> String {
+    SettingsRepository::get_value(pool, CURRENCY_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_currency(&v))
+        .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+}
+
+/// Reject invalid values for the currency setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == CURRENCY_SETTING && parse_currency(value).is_none() {
+        return Err(format!(
+            "{} must be a three-letter ISO 4217 code, such as USD or MYR",
+            key
+        ));
+    }
+    Ok(())
+}
+
+/// Digits after the decimal point in amounts of `currency` (ISO 4217 minor
+/// units); two for codes not listed.
+pub fn minor_units(currency: &str) -> u32 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// What goes before an amount of `currency`, for the currencies the web
+/// client knows a symbol for.
+fn prefix(currency: &str) -> Option<&'static str> {
+    Some(match currency {
+        "USD" => "$",
+        "MYR" => "RM ",
+        "EUR" => "€",
+        "GBP" => "£",
+        "SGD" => "S$",
+        "JPY" | "CNY" => "¥",
+        "AUD" => "A$",
+        "THB" => "฿",
+        "IDR" => "Rp ",
+        _ => return None,
+    })
+}
+
+/// `amount` as text in `currency`: rounded to the currency's minor units,
+/// with thousands separators and the currency's symbol, or its code when it
+/// has no well-known symbol. `-5` in USD is `-$5.00`, `1234.5` in MYR is
+/// `RM 1,234.50` and in CHF `CHF 1,234.50`.
+pub fn format_money(amount: Decimal, currency: &str) -> String {
+    let units = minor_units(currency);
+    let mut rounded = amount
+        .abs()
+        .round_dp_with_strategy(units, RoundingStrategy::MidpointAwayFromZero);
+    rounded.rescale(units);
+
+    let digits = rounded.to_string();
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    let sign = if amount.is_sign_negative() && !rounded.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    match prefix(currency) {
+        Some(prefix) => format!("{}{}{}", sign, prefix, grouped),
+        None => format!("{}{} {}", sign, currency, grouped),
+    }
+}
@@ -0,0 +1,139 @@
+//! Deposit forfeiture on no-show
+//!
+//! With `forfeit_deposit_on_no_show` enabled, the night audit records the
+//! deposit still held on each no-show booking arriving on or before the audit
+//! date as forfeited revenue under [`NO_SHOW_FORFEIT_CATEGORY`]. The deposit
+//! payment rows are left as they are; the forfeiture row is what moves the
+//! money to revenue and what stops the deposit being refunded. A booking is
+//! forfeited at most once.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::models::row_mappers;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+
+/// System setting that enables forfeiture in the night audit.
+pub const FORFEIT_SETTING: &str = "forfeit_deposit_on_no_show";
+
+/// Revenue category forfeited deposits are reported under.
+pub const NO_SHOW_FORFEIT_CATEGORY: &str = "no_show_forfeit";
+
+/// A deposit forfeited by the night audit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forfeiture {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub amount: Decimal,
+}
+
+pub async fn forfeiture_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, FORFEIT_SETTING, false).await
+}
+
+/// Deposit still held: collected less anything already refunded, never
+/// negative.
+pub fn held_deposit(collected: Decimal, refunded: Decimal) -> Decimal {
+    (collected - refunded).max(Decimal::ZERO)
+}
+
+/// Whether the booking's deposit has been forfeited.
+pub async fn is_forfeited(pool: &DbPool, booking_id: i64) -> Result<bool, ApiError> {
+    let id: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM deposit_forfeitures WHERE booking_id = $1")
+            .bind(booking_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(id.is_some())
+}
+
+/// Forfeit the held deposit on no-show bookings arriving on or before
+/// `audit_date` that have not been forfeited yet. Bookings without a deposit
+/// are skipped.
+pub async fn forfeit_no_show_deposits(
+    pool: &DbPool,
+    audit_date: NaiveDate,
+    user_id: i64,
+) -> Result<Vec<Forfeiture>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT b.id, b.booking_number,
+               COALESCE((SELECT SUM(p.amount) FROM payments p
+                   WHERE p.booking_id = b.id AND p.status = 'completed'
+                     AND p.payment_type = 'deposit'), 0) AS deposit_collected,
+               COALESCE((SELECT SUM(p.amount) FROM payments p
+                   WHERE p.booking_id = b.id
+                     AND (p.status = 'refunded' OR p.payment_type = 'refund')), 0) AS deposit_refunded
+        FROM bookings b
+        WHERE b.status = 'no_show'
+          AND b.check_in_date <= $1
+          AND NOT EXISTS (SELECT 1 FROM deposit_forfeitures df WHERE df.booking_id = b.id)
+        ORDER BY b.check_in_date, b.id
+        "#,
+    )
+    .bind(audit_date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut forfeited = Vec::new();
+    for row in &rows {
+        let amount = held_deposit(
+            row_mappers::get_decimal(row, "deposit_collected"),
+            row_mappers::get_decimal(row, "deposit_refunded"),
+        );
+        if amount <= Decimal::ZERO {
+            continue;
+        }
+        let booking_id: i64 = row.get("id");
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO deposit_forfeitures (booking_id, amount, category, audit_date, forfeited_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (booking_id) DO NOTHING
+            "#,
+        )
+        .bind(booking_id)
+        .bind(decimal_to_db(amount))
+        .bind(NO_SHOW_FORFEIT_CATEGORY)
+        .bind(audit_date)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        if inserted.rows_affected() == 0 {
+            continue;
+        }
+
+        let forfeiture = Forfeiture {
+            booking_id,
+            booking_number: row.try_get("booking_number").ok().flatten(),
+            amount,
+        };
+        let _ = AuditLog::log_event(
+            pool,
+            Some(user_id),
+            "deposit_forfeited",
+            "booking",
+            Some(booking_id),
+            Some(serde_json::json!({
+                "booking_number": forfeiture.booking_number,
+                "amount": amount.to_string(),
+                "category": NO_SHOW_FORFEIT_CATEGORY,
+                "audit_date": audit_date.to_string(),
+            })),
+            None,
+            None,
+        )
+        .await;
+        forfeited.push(forfeiture);
+    }
+
+    Ok(forfeited)
+}
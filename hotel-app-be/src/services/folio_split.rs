@@ -0,0 +1,274 @@
+//! Folio splits for shared bookings
+//!
+//! A booking billed partly to a company can route each charge category to
+//! the guest folio or the company's city ledger, e.g. the room to the company
+//! and incidentals to the guest. Routes are kept in `booking_folio_routes`;
+//! a category without a route stays on the guest folio. Checkout posts only
+//! the company-routed charges to `customer_ledgers`, the checkout invoice
+//! covers the guest folio, and the guest's balance counts only their share.
+//! A booking without routes is billed as before: the whole room charge goes
+//! to the company when it has company billing.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::row_mappers::get_decimal;
+use crate::models::{BookingFolioSplit, FolioLine};
+
+pub const ROOM: &str = "room";
+pub const EXTRA_BED: &str = "extra_bed";
+pub const LATE_CHECKOUT: &str = "late_checkout";
+pub const TOURISM_TAX: &str = "tourism_tax";
+pub const SERVICES: &str = "services";
+
+/// Every charge category a route can name, in folio order.
+pub const CHARGE_CATEGORIES: [&str; 5] = [ROOM, EXTRA_BED, LATE_CHECKOUT, TOURISM_TAX, SERVICES];
+
+pub const GUEST: &str = "guest";
+pub const COMPANY: &str = "company";
+
+/// `post_type` and `expense_type` of the city-ledger row for a category.
+pub fn ledger_codes(category: &str) -> (&'static str, &'static str) {
+    match category {
+        ROOM => ("room_charge", "accommodation"),
+        TOURISM_TAX => ("tourism_tax", "tourism_tax"),
+        EXTRA_BED => ("miscellaneous", "extra_bed"),
+        LATE_CHECKOUT => ("miscellaneous", "late_checkout"),
+        _ => ("miscellaneous", "services"),
+    }
+}
+
+/// Human-readable name of a category, for ledger descriptions.
+pub fn category_label(category: &str) -> &'static str {
+    match category {
+        ROOM => "Room",
+        EXTRA_BED => "Extra bed",
+        LATE_CHECKOUT => "Late checkout",
+        TOURISM_TAX => "Tourism tax",
+        _ => "Services",
+    }
+}
+
+/// Check that every route names a known category and payer, and that a
+/// booking routing anything to the company has a company to bill.
+pub fn validate_routes(
+    routes: &BTreeMap<String, String>,
+    company_name: Option<&str>,
+) -> Result<(), ApiError> {
+    for (category, payer) in routes {
+        if !CHARGE_CATEGORIES.contains(&category.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown charge category '{}'; expected one of: {}",
+                category,
+                CHARGE_CATEGORIES.join(", ")
+            )));
+        }
+        if payer != GUEST && payer != COMPANY {
+            return Err(ApiError::BadRequest(format!(
+                "Charges for '{}' must go to 'guest' or 'company'",
+                category
+            )));
+        }
+    }
+
+    let has_company = company_name.is_some_and(|name| !name.trim().is_empty());
+    if !has_company && routes.values().any(|payer| payer == COMPANY) {
+        return Err(ApiError::BadRequest(
+            "Set a company on the booking before routing charges to it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A booking's charges divided between the two payers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoutedCharges {
+    pub guest: Vec<FolioLine>,
+    pub company: Vec<FolioLine>,
+}
+
+impl RoutedCharges {
+    pub fn guest_total(&self) -> Decimal {
+        self.guest.iter().map(|line| line.amount).sum()
+    }
+
+    pub fn company_total(&self) -> Decimal {
+        self.company.iter().map(|line| line.amount).sum()
+    }
+}
+
+/// Send each charge to the payer its category is routed to, leaving
+/// unrouted categories on the guest folio. Zero charges are dropped.
+pub fn route_charges(routes: &BTreeMap<String, String>, charges: &[FolioLine]) -> RoutedCharges {
+    let mut routed = RoutedCharges::default();
+    for line in charges.iter().filter(|line| line.amount > Decimal::ZERO) {
+        match routes.get(&line.category).map(String::as_str) {
+            Some(COMPANY) => routed.company.push(line.clone()),
+            _ => routed.guest.push(line.clone()),
+        }
+    }
+    routed
+}
+
+/// The booking's folio routes; empty when it has no split.
+pub async fn load_routes(
+    pool: &DbPool,
+    booking_id: i64,
+) -> Result<BTreeMap<String, String>, ApiError> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT charge_category, payer FROM booking_folio_routes WHERE booking_id = $1",
+    )
+    .bind(booking_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows.into_iter().collect())
+}
+
+// The SQLite schema has no company columns or other incidental charges on
+// bookings and no booking_services table, so there the folio is the room
+// charge and any late-checkout penalty.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const BOOKING_CHARGES_QUERY: &str = r#"
+    SELECT
+        b.company_name,
+        b.total_amount AS room,
+        COALESCE(b.extra_bed_charge, 0) AS extra_bed,
+        COALESCE(b.late_checkout_penalty, 0) AS late_checkout,
+        COALESCE(b.tourism_tax_amount, 0) AS tourism_tax,
+        COALESCE((SELECT SUM(bs.total_price) FROM booking_services bs
+            WHERE bs.booking_id = b.id AND bs.status <> 'cancelled'), 0) AS services
+    FROM bookings b
+    WHERE b.id = $1
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const BOOKING_CHARGES_QUERY: &str = r#"
+    SELECT
+        NULL AS company_name,
+        b.total_amount AS room,
+        0 AS extra_bed,
+        COALESCE(b.late_checkout_penalty, 0) AS late_checkout,
+        0 AS tourism_tax,
+        0 AS services
+    FROM bookings b
+    WHERE b.id = ?1
+"#;
+
+/// The booking's company and its charges per category.
+async fn booking_charges(
+    pool: &DbPool,
+    booking_id: i64,
+) -> Result<(Option<String>, Vec<FolioLine>), ApiError> {
+    let row = sqlx::query(BOOKING_CHARGES_QUERY)
+        .bind(booking_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+
+    let charges = CHARGE_CATEGORIES
+        .iter()
+        .map(|category| FolioLine {
+            category: category.to_string(),
+            amount: get_decimal(&row, category),
+        })
+        .collect();
+    let company_name = row
+        .try_get::<Option<String>, _>("company_name")
+        .ok()
+        .flatten();
+    Ok((company_name, charges))
+}
+
+/// The booking's split and what each payer is charged under it.
+pub async fn booking_folio(pool: &DbPool, booking_id: i64) -> Result<BookingFolioSplit, ApiError> {
+    let (company_name, charges) = booking_charges(pool, booking_id).await?;
+    let routes = load_routes(pool, booking_id).await?;
+    let routed = route_charges(&routes, &charges);
+
+    Ok(BookingFolioSplit {
+        booking_id,
+        company_name,
+        guest_total: routed.guest_total(),
+        company_total: routed.company_total(),
+        routes,
+        guest_folio: routed.guest,
+        company_ledger: routed.company,
+    })
+}
+
+/// Replace the booking's routes; an empty map removes the split.
+pub async fn set_routes(
+    pool: &DbPool,
+    booking_id: i64,
+    routes: &BTreeMap<String, String>,
+    user_id: i64,
+) -> Result<BookingFolioSplit, ApiError> {
+    let (company_name, _) = booking_charges(pool, booking_id).await?;
+    validate_routes(routes, company_name.as_deref())?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    sqlx::query("DELETE FROM booking_folio_routes WHERE booking_id = $1")
+        .bind(booking_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for (category, payer) in routes {
+        sqlx::query(
+            "INSERT INTO booking_folio_routes (booking_id, charge_category, payer, updated_by) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(booking_id)
+        .bind(category)
+        .bind(payer)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    booking_folio(pool, booking_id).await
+}
+
+/// Charges to post to the company's city ledger at checkout: the routed
+/// ones when the booking has a split, otherwise the whole room charge.
+pub async fn company_ledger_charges(
+    pool: &DbPool,
+    booking_id: i64,
+    room_total: Decimal,
+) -> Result<Vec<FolioLine>, ApiError> {
+    if load_routes(pool, booking_id).await?.is_empty() {
+        return Ok(vec![FolioLine {
+            category: ROOM.to_string(),
+            amount: room_total,
+        }]);
+    }
+    Ok(booking_folio(pool, booking_id).await?.company_ledger)
+}
+
+/// The guest folio for the checkout invoice, or `None` when the booking has
+/// no split and the invoice covers the booking total.
+pub async fn guest_invoice_folio(
+    pool: &DbPool,
+    booking_id: i64,
+) -> Result<Option<BookingFolioSplit>, ApiError> {
+    if load_routes(pool, booking_id).await?.is_empty() {
+        return Ok(None);
+    }
+    booking_folio(pool, booking_id).await.map(Some)
+}
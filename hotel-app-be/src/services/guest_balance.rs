@@ -0,0 +1,250 @@
+//! Outstanding balances per guest
+//!
+//! A booking's balance is its total less completed, non-refund payments,
+//! floored at zero — the same figure the bookings list shows as
+//! `balance_due`. Company-billed stays are owed by the company: once checkout
+//! has posted them to the city ledger, the ledger's open balance is what is
+//! still due. A booking with a folio split owes each payer their share,
+//! with the guest's payments going against the guest's share. Voided and
+//! cancelled bookings owe nothing.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, DbRow};
+use crate::core::error::ApiError;
+use crate::models::row_mappers;
+use crate::models::{BookingBalance, GuestBalance};
+use crate::services::folio_split;
+
+/// Payment method marking a stay as billed to a company.
+pub const COMPANY_BILLING_METHOD: &str = "company_billing";
+
+/// One booking's figures as read from the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookingCharges {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub status: String,
+    pub check_in_date: NaiveDate,
+    pub check_out_date: NaiveDate,
+    pub total_amount: Decimal,
+    pub total_paid: Decimal,
+    pub company_billed: bool,
+    pub company_name: Option<String>,
+    /// Open city-ledger balance for the booking, once posted
+    pub ledger_balance: Option<Decimal>,
+    /// Each payer's share, when the booking has a folio split
+    pub split_shares: Option<SplitShares>,
+}
+
+/// What the guest and the company are charged under a folio split.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitShares {
+    pub guest: Decimal,
+    pub company: Decimal,
+}
+
+impl BookingCharges {
+    pub fn balance(&self) -> BookingBalance {
+        let unpaid = (self.total_amount - self.total_paid).max(Decimal::ZERO);
+        let balance_due = match (self.company_billed, self.ledger_balance) {
+            (true, Some(ledger)) => ledger.max(Decimal::ZERO),
+            _ => unpaid,
+        };
+
+        BookingBalance {
+            booking_id: self.booking_id,
+            booking_number: self.booking_number.clone(),
+            status: self.status.clone(),
+            check_in_date: self.check_in_date,
+            check_out_date: self.check_out_date,
+            total_amount: self.total_amount,
+            total_paid: self.total_paid,
+            billed_to: if self.company_billed {
+                "company"
+            } else {
+                "guest"
+            }
+            .to_string(),
+            company_name: self.company_name.clone(),
+            balance_due,
+        }
+    }
+
+    /// What the guest still owes on the booking, leaving out anything
+    /// billed to a company.
+    pub fn guest_balance_due(&self) -> Decimal {
+        self.balances()
+            .iter()
+            .filter(|b| b.billed_to == "guest")
+            .map(|b| b.balance_due)
+            .sum()
+    }
+
+    /// The booking's balance per payer: one entry, or one for the guest and
+    /// one for the company when the booking has a folio split.
+    pub fn balances(&self) -> Vec<BookingBalance> {
+        let Some(shares) = &self.split_shares else {
+            return vec![self.balance()];
+        };
+
+        let guest = BookingCharges {
+            total_amount: shares.guest,
+            company_billed: false,
+            ledger_balance: None,
+            split_shares: None,
+            ..self.clone()
+        };
+        let company = BookingCharges {
+            total_amount: shares.company,
+            total_paid: Decimal::ZERO,
+            company_billed: true,
+            split_shares: None,
+            ..self.clone()
+        };
+        vec![guest.balance(), company.balance()]
+    }
+}
+
+/// Sum the bookings into a guest balance, keeping only those with money due.
+pub fn summarize(guest_id: i64, charges: &[BookingCharges]) -> GuestBalance {
+    let bookings: Vec<BookingBalance> = charges
+        .iter()
+        .flat_map(BookingCharges::balances)
+        .filter(|b| b.balance_due > Decimal::ZERO)
+        .collect();
+
+    let owed_by = |billed_to: &str| -> Decimal {
+        bookings
+            .iter()
+            .filter(|b| b.billed_to == billed_to)
+            .map(|b| b.balance_due)
+            .sum()
+    };
+    let guest_outstanding = owed_by("guest");
+    let company_outstanding = owed_by("company");
+
+    GuestBalance {
+        guest_id,
+        guest_outstanding,
+        company_outstanding,
+        total_outstanding: guest_outstanding + company_outstanding,
+        bookings,
+    }
+}
+
+// The SQLite schema has no company columns on bookings and a different
+// customer_ledgers layout, so company billing is read from payment_method
+// alone and no ledger balance is available there.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const CHARGES_QUERY: &str = r#"
+    SELECT
+        b.id, b.booking_number, b.status, b.check_in_date, b.check_out_date,
+        b.total_amount, b.payment_method, b.company_name,
+        COALESCE((SELECT SUM(p.amount) FROM payments p
+            WHERE p.booking_id = b.id AND p.status = 'completed'
+              AND COALESCE(p.payment_type, 'booking') != 'refund'), 0) AS total_paid,
+        (SELECT SUM(cl.balance_due) FROM customer_ledgers cl
+            WHERE cl.booking_id = b.id
+              AND cl.transaction_type = 'debit'
+              AND COALESCE(cl.is_reversal, false) = false
+              AND cl.void_at IS NULL
+              AND cl.status <> 'cancelled') AS ledger_balance,
+        EXISTS(SELECT 1 FROM booking_folio_routes fr WHERE fr.booking_id = b.id) AS has_folio_split
+    FROM bookings b
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const CHARGES_QUERY: &str = r#"
+    SELECT
+        b.id, b.booking_number, b.status, b.check_in_date, b.check_out_date,
+        b.total_amount, b.payment_method, NULL AS company_name,
+        COALESCE((SELECT SUM(p.amount) FROM payments p
+            WHERE p.booking_id = b.id AND p.status = 'completed'
+              AND COALESCE(p.payment_type, 'booking') != 'refund'), 0) AS total_paid,
+        NULL AS ledger_balance,
+        EXISTS(SELECT 1 FROM booking_folio_routes fr WHERE fr.booking_id = b.id) AS has_folio_split
+    FROM bookings b
+"#;
+
+pub async fn guest_charges(pool: &DbPool, guest_id: i64) -> Result<Vec<BookingCharges>, ApiError> {
+    let sql = format!(
+        "{} WHERE b.guest_id = $1 AND b.status NOT IN ('voided', 'cancelled', 'comp_cancelled') \
+         ORDER BY b.check_in_date, b.id",
+        CHARGES_QUERY
+    );
+    let rows = sqlx::query(&sql)
+        .bind(guest_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    charges_from_rows(pool, &rows).await
+}
+
+/// One booking's figures, whatever its status.
+pub async fn booking_charges(
+    pool: &DbPool,
+    booking_id: i64,
+) -> Result<Option<BookingCharges>, ApiError> {
+    let sql = format!("{} WHERE b.id = $1", CHARGES_QUERY);
+    let rows = sqlx::query(&sql)
+        .bind(booking_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(charges_from_rows(pool, &rows).await?.pop())
+}
+
+async fn charges_from_rows(pool: &DbPool, rows: &[DbRow]) -> Result<Vec<BookingCharges>, ApiError> {
+    let mut charges = Vec::with_capacity(rows.len());
+    for row in rows {
+        let payment_method: Option<String> = row.try_get("payment_method").ok().flatten();
+        let booking_id: i64 = row.get("id");
+        let split_shares = if row.try_get::<bool, _>("has_folio_split").unwrap_or(false) {
+            let folio = folio_split::booking_folio(pool, booking_id).await?;
+            Some(SplitShares {
+                guest: folio.guest_total,
+                company: folio.company_total,
+            })
+        } else {
+            None
+        };
+
+        charges.push(BookingCharges {
+            booking_id,
+            booking_number: row.try_get("booking_number").ok().flatten(),
+            status: row
+                .try_get::<Option<String>, _>("status")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            check_in_date: row.get("check_in_date"),
+            check_out_date: row.get("check_out_date"),
+            total_amount: row_mappers::get_decimal(row, "total_amount"),
+            total_paid: row_mappers::get_decimal(row, "total_paid"),
+            company_billed: payment_method.as_deref() == Some(COMPANY_BILLING_METHOD),
+            company_name: row.try_get("company_name").ok().flatten(),
+            ledger_balance: row_mappers::get_opt_decimal(row, "ledger_balance"),
+            split_shares,
+        });
+    }
+    Ok(charges)
+}
+
+/// The guest's outstanding balance; `NotFound` for an unknown guest.
+pub async fn guest_balance(pool: &DbPool, guest_id: i64) -> Result<GuestBalance, ApiError> {
+    let exists = sqlx::query("SELECT id FROM guests WHERE id = $1")
+        .bind(guest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound("Guest not found".to_string()));
+    }
+
+    let charges = guest_charges(pool, guest_id).await?;
+    Ok(summarize(guest_id, &charges))
+}
@@ -0,0 +1,151 @@
+//! A guest's stay history
+//!
+//! Every booking the guest has made except voided ones, newest stay first,
+//! each with the reviews the guest left for it. This is the per-guest view
+//! of what the guest statistics report counts as returning guests.
+
+use std::collections::HashMap;
+
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::row_mappers;
+use crate::models::{GuestStay, GuestStayReview, PaginatedResponse};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// One page of the guest's stays; `NotFound` for an unknown guest.
+pub async fn guest_stays(
+    pool: &DbPool,
+    guest_id: i64,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<PaginatedResponse<Vec<GuestStay>>, ApiError> {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = (page - 1) * page_size;
+
+    let exists = sqlx::query("SELECT id FROM guests WHERE id = $1")
+        .bind(guest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound("Guest not found".to_string()));
+    }
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bookings WHERE guest_id = $1 AND status != 'voided'",
+    )
+    .bind(guest_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT b.id, b.booking_number, r.room_number, rt.name AS room_type,
+               b.check_in_date, b.check_out_date, b.total_amount, b.status
+        FROM bookings b
+        JOIN rooms r ON b.room_id = r.id
+        LEFT JOIN room_types rt ON r.room_type_id = rt.id
+        WHERE b.guest_id = $1 AND b.status != 'voided'
+        ORDER BY b.check_in_date DESC, b.id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(guest_id)
+    .bind(page_size)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let booking_ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+    let mut reviews = stay_reviews(pool, guest_id, &booking_ids).await?;
+
+    let stays = rows
+        .iter()
+        .map(|row| {
+            let booking_id: i64 = row.get("id");
+            let check_in_date: chrono::NaiveDate = row.get("check_in_date");
+            let check_out_date: chrono::NaiveDate = row.get("check_out_date");
+            GuestStay {
+                booking_id,
+                booking_number: row.try_get("booking_number").ok().flatten(),
+                room_number: row.get("room_number"),
+                room_type: row.try_get("room_type").ok().flatten(),
+                check_in_date,
+                check_out_date,
+                nights: (check_out_date - check_in_date).num_days(),
+                total_amount: row_mappers::get_decimal(row, "total_amount"),
+                status: row
+                    .try_get::<Option<String>, _>("status")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default(),
+                reviews: reviews.remove(&booking_id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(PaginatedResponse {
+        data: stays,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// The guest's reviews of `booking_ids`, oldest first, by booking.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+async fn stay_reviews(
+    pool: &DbPool,
+    guest_id: i64,
+    booking_ids: &[i64],
+) -> Result<HashMap<i64, Vec<GuestStayReview>>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, booking_id, overall_rating, title, content, response, created_at
+        FROM guest_reviews
+        WHERE guest_id = $1 AND booking_id = ANY($2)
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(guest_id)
+    .bind(booking_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut reviews: HashMap<i64, Vec<GuestStayReview>> = HashMap::new();
+    for row in &rows {
+        reviews
+            .entry(row.get("booking_id"))
+            .or_default()
+            .push(GuestStayReview {
+                id: row.get("id"),
+                overall_rating: row_mappers::get_decimal(row, "overall_rating"),
+                title: row.try_get("title").ok().flatten(),
+                content: row.try_get("content").ok().flatten(),
+                response: row.try_get("response").ok().flatten(),
+                created_at: row.get("created_at"),
+            });
+    }
+    Ok(reviews)
+}
+
+/// The SQLite schema has no reviews table, so no stay has reviews.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+async fn stay_reviews(
+    _pool: &DbPool,
+    _guest_id: i64,
+    _booking_ids: &[i64],
+) -> Result<HashMap<i64, Vec<GuestStayReview>>, ApiError> {
+    Ok(HashMap::new())
+}
@@ -0,0 +1,63 @@
+//! The hotel's local date
+//!
+//! "Today" is the date in the hotel's `timezone` setting, an IANA name such
+//! as `Asia/Kuala_Lumpur`, not the date of the database or the server. It is
+//! worked out here and bound into queries, so arrivals, departures, room
+//! status, occupancy and the night audit turn over at the hotel's midnight
+//! on PostgreSQL and SQLite alike, and a changed setting applies at once
+//! rather than when pooled connections are next opened.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const TIMEZONE_SETTING: &str = "timezone";
+
+pub const DEFAULT_TIMEZONE: Tz = Tz::UTC;
+
+/// An IANA timezone name.
+pub fn parse_timezone(value: &str) -> Option<Tz> {
+    value.trim().parse().ok()
+}
+
+/// Reject invalid values for the timezone setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == TIMEZONE_SETTING && parse_timezone(value).is_none() {
+        return Err(format!(
+            "{} must be an IANA timezone name, such as Asia/Kuala_Lumpur or UTC",
+            key
+        ));
+    }
+    Ok(())
+}
+
+/// The hotel's timezone; [`DEFAULT_TIMEZONE`] when unset or invalid.
+pub async fn configured(pool: &DbPool) -> Tz {
+    SettingsRepository::get_value(pool, TIMEZONE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_timezone(&v))
+        .unwrap_or(DEFAULT_TIMEZONE)
+}
+
+/// The date in `timezone` at the instant `now`.
+pub fn local_date(timezone: Tz, now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&timezone).date_naive()
+}
+
+/// The current date in the hotel's timezone.
+pub async fn hotel_today(pool: &DbPool) -> Result<NaiveDate, ApiError> {
+    Ok(local_date(configured(pool).await, Utc::now()))
+}
+
+/// The hotel's wall-clock time, for comparing with its check-in and
+/// check-out times.
+pub async fn hotel_now(pool: &DbPool) -> NaiveDateTime {
+    Utc::now()
+        .with_timezone(&configured(pool).await)
+        .naive_local()
+}
@@ -0,0 +1,160 @@
+//! Automatic release of cleaned rooms
+//!
+//! A room put into `cleaning` or `dirty` with a `cleaning_end_date` is
+//! returned to `available` once that time has passed, provided no guest is
+//! in house in it. Rooms without an end date stay put until cleared by
+//! staff. Runs from the auto check-in/checkout scheduler and the night audit
+//! when `auto_release_cleaned_rooms` is enabled.
+
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+
+/// System setting that enables the automatic release.
+pub const AUTO_RELEASE_SETTING: &str = "auto_release_cleaned_rooms";
+
+/// A room in a housekeeping status with its cleaning window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleaningRoom {
+    pub id: i64,
+    pub room_number: String,
+    pub status: String,
+    pub cleaning_end_date: Option<DateTime<Utc>>,
+    /// A guest is checked in (or overstaying) in the room.
+    pub has_active_booking: bool,
+}
+
+impl CleaningRoom {
+    /// Whether the room should be returned to `available` at `now`.
+    pub fn is_due_for_release(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.status.as_str(), "cleaning" | "dirty")
+            && self.cleaning_end_date.is_some_and(|end| end <= now)
+            && !self.has_active_booking
+    }
+}
+
+pub async fn auto_release_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, AUTO_RELEASE_SETTING, false).await
+}
+
+async fn cleaning_rooms(pool: &DbPool) -> Result<Vec<CleaningRoom>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.id, r.room_number, r.status, r.cleaning_end_date,
+               EXISTS(
+                   SELECT 1 FROM bookings b
+                   WHERE b.room_id = r.id
+                     AND b.status IN ('checked_in', 'auto_checked_in', 'late_checkout')
+               ) AS has_active_booking
+        FROM rooms r
+        WHERE r.status IN ('cleaning', 'dirty')
+          AND r.cleaning_end_date IS NOT NULL
+          AND r.is_active = true
+        ORDER BY r.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| CleaningRoom {
+            id: row.get("id"),
+            room_number: row.get("room_number"),
+            status: row
+                .try_get::<Option<String>, _>("status")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            cleaning_end_date: row.try_get("cleaning_end_date").ok().flatten(),
+            has_active_booking: row.try_get("has_active_booking").unwrap_or(true),
+        })
+        .collect())
+}
+
+/// Return every room whose cleaning window has ended to `available`.
+///
+/// Returns the IDs of the rooms released.
+pub async fn release_cleaned_rooms(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<i64>, ApiError> {
+    let mut released = Vec::new();
+
+    for room in cleaning_rooms(pool).await? {
+        if !room.is_due_for_release(now) {
+            continue;
+        }
+
+        // Guard on the status we read so a concurrent staff update wins.
+        let result = sqlx::query(
+            r#"
+            UPDATE rooms
+            SET status = 'available',
+                cleaning_start_date = NULL,
+                cleaning_end_date = NULL,
+                last_cleaned_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND status = $2
+            "#,
+        )
+        .bind(room.id)
+        .bind(&room.status)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+
+        let notes = format!(
+            "Cleaning window ended {}; room released automatically",
+            room.cleaning_end_date
+                .map(|d| d.to_rfc3339())
+                .unwrap_or_default()
+        );
+        let _ = sqlx::query(
+            r#"
+            INSERT INTO room_history (room_id, from_status, to_status, notes, is_auto_generated)
+            VALUES ($1, $2, 'available', $3, true)
+            "#,
+        )
+        .bind(room.id)
+        .bind(&room.status)
+        .bind(&notes)
+        .execute(pool)
+        .await;
+
+        let _ = AuditLog::log_event(
+            pool,
+            None,
+            "room_auto_released",
+            "room",
+            Some(room.id),
+            Some(serde_json::json!({
+                "room_number": room.room_number,
+                "from_status": room.status,
+                "to_status": "available",
+                "cleaning_end_date": room.cleaning_end_date,
+            })),
+            None,
+            None,
+        )
+        .await;
+
+        log::info!(
+            "Room {} released from {} to available (cleaning ended {:?})",
+            room.room_number,
+            room.status,
+            room.cleaning_end_date
+        );
+        released.push(room.id);
+    }
+
+    Ok(released)
+}
@@ -0,0 +1,202 @@
+//! Failed login tracking and lockout
+//!
+//! Failures are counted per username and client IP in `login_attempts`,
+//! whether or not the username exists, so a lockout says nothing about which
+//! accounts are real. After `max_login_attempts` consecutive failures the
+//! pair is locked for `login_lockout_minutes`; failures older than that
+//! window no longer count, and a successful password check clears the count.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const MAX_ATTEMPTS_SETTING: &str = "max_login_attempts";
+pub const LOCKOUT_MINUTES_SETTING: &str = "login_lockout_minutes";
+
+const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+const DEFAULT_LOCKOUT_MINUTES: i64 = 30;
+
+/// Upper bounds accepted for the settings.
+const MAX_CONFIGURABLE_ATTEMPTS: i32 = 100;
+const MAX_CONFIGURABLE_LOCKOUT_MINUTES: i64 = 1440;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    pub max_attempts: i32,
+    pub lockout_minutes: i64,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            lockout_minutes: DEFAULT_LOCKOUT_MINUTES,
+        }
+    }
+}
+
+fn parse_attempts(value: &str) -> Option<i32> {
+    value
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|n| (1..=MAX_CONFIGURABLE_ATTEMPTS).contains(n))
+}
+
+fn parse_minutes(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|n| (1..=MAX_CONFIGURABLE_LOCKOUT_MINUTES).contains(n))
+}
+
+async fn setting(pool: &DbPool, key: &str) -> Option<String> {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Load the lockout policy, falling back to the defaults for missing or
+/// invalid values.
+pub async fn configured_policy(pool: &DbPool) -> LockoutPolicy {
+    LockoutPolicy {
+        max_attempts: setting(pool, MAX_ATTEMPTS_SETTING)
+            .await
+            .and_then(|v| parse_attempts(&v))
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS),
+        lockout_minutes: setting(pool, LOCKOUT_MINUTES_SETTING)
+            .await
+            .and_then(|v| parse_minutes(&v))
+            .unwrap_or(DEFAULT_LOCKOUT_MINUTES),
+    }
+}
+
+/// Reject invalid values for the lockout settings.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        MAX_ATTEMPTS_SETTING if parse_attempts(value).is_none() => Err(format!(
+            "{} must be a whole number between 1 and {}",
+            key, MAX_CONFIGURABLE_ATTEMPTS
+        )),
+        LOCKOUT_MINUTES_SETTING if parse_minutes(value).is_none() => Err(format!(
+            "{} must be a whole number of minutes between 1 and {}",
+            key, MAX_CONFIGURABLE_LOCKOUT_MINUTES
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Usernames are matched case-insensitively, as are emails used to log in.
+fn attempt_key(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+/// Whole minutes left on a lock, rounded up.
+fn minutes_left(until: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (until - now).num_seconds().max(0).div_ceil(60).max(1)
+}
+
+/// Minutes left if `username` is locked out from `ip`.
+pub async fn locked_for(pool: &DbPool, username: &str, ip: &str) -> Result<Option<i64>, ApiError> {
+    let locked_until: Option<Option<DateTime<Utc>>> = sqlx::query_scalar(
+        "SELECT locked_until FROM login_attempts WHERE username = $1 AND ip_address = $2",
+    )
+    .bind(attempt_key(username))
+    .bind(ip)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let now = Utc::now();
+    Ok(locked_until
+        .flatten()
+        .filter(|until| *until > now)
+        .map(|until| minutes_left(until, now)))
+}
+
+/// Count a failed login. Returns the lock length in minutes when this
+/// failure locks the pair out.
+pub async fn record_failure(
+    pool: &DbPool,
+    username: &str,
+    ip: &str,
+    policy: &LockoutPolicy,
+) -> Result<Option<i64>, ApiError> {
+    let key = attempt_key(username);
+    let now = Utc::now();
+    let window = Duration::minutes(policy.lockout_minutes);
+
+    // Failures from before the window, or before an expired lock, start over.
+    let previous = sqlx::query(
+        "SELECT last_failed_at, locked_until FROM login_attempts WHERE username = $1 AND ip_address = $2",
+    )
+    .bind(&key)
+    .bind(ip)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if let Some(row) = previous {
+        let last_failed_at: Option<DateTime<Utc>> = row.get("last_failed_at");
+        let locked_until: Option<DateTime<Utc>> = row.get("locked_until");
+        let stale = last_failed_at.is_none_or(|at| at + window <= now);
+        if stale || locked_until.is_some_and(|until| until <= now) {
+            sqlx::query(
+                "UPDATE login_attempts SET failed_count = 0, locked_until = NULL
+                 WHERE username = $1 AND ip_address = $2",
+            )
+            .bind(&key)
+            .bind(ip)
+            .execute(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        }
+    }
+
+    let failed_count: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO login_attempts (username, ip_address, failed_count, last_failed_at)
+        VALUES ($1, $2, 1, $3)
+        ON CONFLICT (username, ip_address) DO UPDATE
+        SET failed_count = login_attempts.failed_count + 1, last_failed_at = $3
+        RETURNING failed_count
+        "#,
+    )
+    .bind(&key)
+    .bind(ip)
+    .bind(now)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if failed_count < policy.max_attempts {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        "UPDATE login_attempts SET failed_count = 0, locked_until = $3
+         WHERE username = $1 AND ip_address = $2",
+    )
+    .bind(&key)
+    .bind(ip)
+    .bind(now + window)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(Some(policy.lockout_minutes))
+}
+
+/// Forget the failures for `username` from `ip` after a correct password.
+pub async fn clear(pool: &DbPool, username: &str, ip: &str) -> Result<(), ApiError> {
+    sqlx::query("DELETE FROM login_attempts WHERE username = $1 AND ip_address = $2")
+        .bind(attempt_key(username))
+        .bind(ip)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Login history
+//!
+//! Each password or passkey login to an existing account is recorded in
+//! `login_history` with its outcome: a success, a failure, or a failure
+//! because the client or account was rate limited or locked out. Logins for
+//! unknown usernames are not recorded, as there is no account to show them
+//! to. Recording never fails a login; errors are only logged.
+//!
+//! Requests turned away by the per-IP rate limiter in the routes never reach
+//! the login handlers and are not recorded, so a flood of requests cannot
+//! flood this table. Lockouts from `login_attempts` are recorded as
+//! `rate_limited`.
+
+use chrono::Utc;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::{LoginHistoryEntry, PaginatedResponse};
+
+pub const PASSWORD_LOGIN: &str = "password";
+pub const PASSKEY_LOGIN: &str = "passkey";
+
+pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
+pub const RATE_LIMITED: &str = "rate_limited";
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// The outcome recorded for a login that ended in `result`.
+pub fn outcome<T>(result: &Result<T, ApiError>) -> &'static str {
+    match result {
+        Ok(_) => SUCCESS,
+        Err(ApiError::TooManyRequests(_) | ApiError::TooManyRequestsRetryAfter(..)) => RATE_LIMITED,
+        Err(_) => FAILURE,
+    }
+}
+
+/// Record a login as `username` (or an email address, for password logins)
+/// by `method`. `user_id` may be given when the account is already known.
+pub async fn record(
+    pool: &DbPool,
+    user_id: Option<i64>,
+    username: &str,
+    method: &str,
+    outcome: &str,
+    ip: &str,
+    user_agent: Option<&str>,
+) {
+    let user_id = match user_id {
+        Some(id) => Some(id),
+        None => sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM users WHERE (username = $1 OR email = $1) AND deleted_at IS NULL",
+        )
+        .bind(username)
+        .fetch_optional(pool)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to look up user for login history: {}", e);
+            None
+        }),
+    };
+    let Some(user_id) = user_id else {
+        return;
+    };
+
+    let recorded = sqlx::query(
+        r#"
+        INSERT INTO login_history (user_id, method, outcome, ip_address, user_agent, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(user_id)
+    .bind(method)
+    .bind(outcome)
+    .bind(ip)
+    .bind(user_agent)
+    .bind(Utc::now())
+    .execute(pool)
+    .await;
+    if let Err(e) = recorded {
+        log::warn!("Failed to record login for user {}: {}", user_id, e);
+    }
+}
+
+/// One page of the user's logins, newest first.
+pub async fn login_history(
+    pool: &DbPool,
+    user_id: i64,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<PaginatedResponse<Vec<LoginHistoryEntry>>, ApiError> {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM login_history WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let entries = sqlx::query_as::<_, LoginHistoryEntry>(
+        r#"
+        SELECT id, method, outcome, ip_address, user_agent, created_at
+        FROM login_history
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(page_size)
+    .bind((page - 1) * page_size)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(PaginatedResponse {
+        data: entries,
+        total,
+        page,
+        page_size,
+    })
+}
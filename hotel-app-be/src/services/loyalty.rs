@@ -1,8 +1,69 @@
 //! Loyalty program business logic
 
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
-use crate::models::PointsTransaction;
+use crate::models::{
+    Booking, BulkPointsInput, BulkPointsResult, LoyaltyStatistics, PaginatedResponse,
+    PointsRecomputeResult, PointsRule, PointsRuleInput, PointsRuleType, PointsTransaction,
+    PointsTransactionsParams, PointsTransferResult, TransferPointsInput,
+};
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+
+/// Largest single manual points adjustment staff may make.
+pub const MAX_ADJUSTMENT_SETTING: &str = "loyalty_max_points_adjustment";
+
+const DEFAULT_MAX_ADJUSTMENT: i32 = 100_000;
+const MAX_ADJUSTMENT_CEILING: i32 = 10_000_000;
+
+/// Highest multiplier a points rule may apply.
+const MAX_POINTS_MULTIPLIER: f64 = 10.0;
+
+fn parse_max_adjustment(value: &str) -> Option<i32> {
+    value
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|points| (1..=MAX_ADJUSTMENT_CEILING).contains(points))
+}
+
+pub async fn max_points_adjustment(pool: &DbPool) -> i32 {
+    SettingsRepository::get_value(pool, MAX_ADJUSTMENT_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_max_adjustment(&v))
+        .unwrap_or(DEFAULT_MAX_ADJUSTMENT)
+}
+
+/// Reject invalid values for the adjustment cap setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == MAX_ADJUSTMENT_SETTING && parse_max_adjustment(value).is_none() {
+        return Err(format!(
+            "{} must be a whole number of points between 1 and {}",
+            key, MAX_ADJUSTMENT_CEILING
+        ));
+    }
+    Ok(())
+}
+
+/// Check a manual adjustment of `points` against the cap of `max_points`.
+pub fn check_points_adjustment(points: i32, max_points: i32) -> Result<(), ApiError> {
+    if points <= 0 {
+        return Err(ApiError::BadRequest("Points must be positive".to_string()));
+    }
+    if points > max_points {
+        return Err(ApiError::BadRequest(format!(
+            "A single adjustment cannot exceed {} points",
+            max_points
+        )));
+    }
+    Ok(())
+}
 
 /// Resolve a user account to their linked guest ID via email matching.
 ///
@@ -32,17 +93,254 @@ pub async fn resolve_user_to_guest(pool: &DbPool, user_id: i64) -> Result<i64, A
     Ok(guest_id)
 }
 
-/// Add or deduct points on a membership, recording a points transaction.
+/// Transaction types a member can filter their points history by.
+pub const MEMBER_TRANSACTION_TYPES: [&str; 4] = ["earn", "redeem", "transfer", "expire"];
+
+pub const DEFAULT_TRANSACTIONS_PAGE_SIZE: i64 = 20;
+pub const MAX_TRANSACTIONS_PAGE_SIZE: i64 = 100;
+
+/// Validate a points history type filter; blank means all types.
+pub fn parse_transaction_type_filter(value: Option<&str>) -> Result<Option<&str>, ApiError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(t) if MEMBER_TRANSACTION_TYPES.contains(&t) => Ok(Some(t)),
+        Some(_) => Err(ApiError::BadRequest(format!(
+            "type must be one of: {}",
+            MEMBER_TRANSACTION_TYPES.join(", ")
+        ))),
+    }
+}
+
+/// The benefits listed in a tier's `benefits` JSON array, in order. Blank
+/// entries are dropped; anything that is not an array lists none.
+pub fn tier_benefits(benefits: Option<&serde_json::Value>) -> Vec<String> {
+    benefits
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| match item {
+                    serde_json::Value::String(s) => Some(s.trim().to_string()),
+                    serde_json::Value::Null => None,
+                    other => Some(other.to_string()),
+                })
+                .filter(|b| !b.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// How far a member has come from their tier's minimum toward the next
+/// tier's.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TierProgress {
+    /// Lifetime points still needed for the next tier; `None` at the top tier.
+    pub points_to_next_tier: Option<i32>,
+    /// Percent of the way from the current tier's minimum to the next tier's,
+    /// from 0 to 100. A member at the top tier has nowhere further to go and
+    /// is always at 100.
+    pub progress_percent: f64,
+}
+
+/// A member's progress within their tier, measured on lifetime points from
+/// `current_minimum` to `next_minimum` (`None` at the top tier).
+pub fn tier_progress(
+    lifetime_points: i32,
+    current_minimum: i32,
+    next_minimum: Option<i32>,
+) -> TierProgress {
+    let Some(next_minimum) = next_minimum else {
+        return TierProgress {
+            points_to_next_tier: None,
+            progress_percent: 100.0,
+        };
+    };
+
+    let remaining = (next_minimum - lifetime_points).max(0);
+    let span = next_minimum - current_minimum;
+    let progress_percent = if remaining == 0 {
+        100.0
+    } else if span <= 0 {
+        0.0
+    } else {
+        let percent = f64::from(lifetime_points - current_minimum) / f64::from(span) * 100.0;
+        (percent.clamp(0.0, 100.0) * 10.0).round() / 10.0
+    };
+
+    TierProgress {
+        points_to_next_tier: Some(remaining),
+        progress_percent,
+    }
+}
+
+/// The benefits of loyalty program (tier) `program_id`.
+pub async fn program_benefits(pool: &DbPool, program_id: i64) -> Result<Vec<String>, ApiError> {
+    let benefits: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT benefits FROM loyalty_programs WHERE id = $1")
+            .bind(program_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .flatten();
+    Ok(tier_benefits(benefits.as_ref()))
+}
+
+/// How the loyalty statistics are downloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatisticsExportFormat {
+    Json,
+    Csv,
+}
+
+/// Parse an export `format`; blank means JSON.
+pub fn parse_export_format(value: Option<&str>) -> Result<StatisticsExportFormat, ApiError> {
+    match value
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+        .as_deref()
+    {
+        None | Some("json") => Ok(StatisticsExportFormat::Json),
+        Some("csv") => Ok(StatisticsExportFormat::Csv),
+        Some(_) => Err(ApiError::BadRequest(
+            "format must be one of: json, csv".to_string(),
+        )),
+    }
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The statistics as CSV: members by tier, top members and points activity,
+/// each a titled section with its own header row, separated by blank lines.
+pub fn statistics_csv(stats: &LoyaltyStatistics) -> String {
+    let mut csv = String::from("Members by tier\nTier Level,Tier,Members,Percentage\n");
+    for tier in &stats.members_by_tier {
+        csv.push_str(&format!(
+            "{},{},{},{:.2}\n",
+            tier.tier_level,
+            csv_field(&tier.tier_name),
+            tier.count,
+            tier.percentage
+        ));
+    }
+
+    csv.push_str(
+        "\nTop members\nMembership Number,Guest,Email,Tier Level,Points Balance,Lifetime Points\n",
+    );
+    for member in &stats.top_members {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&member.membership_number),
+            csv_field(&member.guest_name),
+            csv_field(&member.guest_email),
+            member.tier_level,
+            member.points_balance,
+            member.lifetime_points
+        ));
+    }
+
+    csv.push_str("\nPoints activity\nDate,Points Earned,Points Redeemed\n");
+    for day in &stats.points_activity {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            csv_field(&day.date),
+            day.points_earned,
+            day.points_redeemed
+        ));
+    }
+    csv
+}
+
+/// One page of the user's points transactions, newest first, on the same
+/// membership the portal shows them: the active one, else the latest
+/// expired one.
+pub async fn user_points_transactions(
+    pool: &DbPool,
+    user_id: i64,
+    params: &PointsTransactionsParams,
+) -> Result<PaginatedResponse<Vec<PointsTransaction>>, ApiError> {
+    let transaction_type = parse_transaction_type_filter(params.transaction_type.as_deref())?;
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_TRANSACTIONS_PAGE_SIZE)
+        .clamp(1, MAX_TRANSACTIONS_PAGE_SIZE);
+
+    let guest_id = resolve_user_to_guest(pool, user_id).await?;
+    let membership_id: i64 = sqlx::query_scalar(
+        r#"
+        SELECT id FROM loyalty_memberships
+        WHERE guest_id = $1 AND status IN ('active', 'expired')
+        ORDER BY status = 'active' DESC, id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(guest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("No active loyalty membership found".to_string()))?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM points_transactions
+        WHERE membership_id = $1 AND ($2::text IS NULL OR transaction_type = $2)
+        "#,
+    )
+    .bind(membership_id)
+    .bind(transaction_type)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let transactions = sqlx::query_as::<_, PointsTransaction>(
+        r#"
+        SELECT id::text AS id, membership_id, transaction_type, points AS points_amount,
+               balance_after, reference_type, reference_id, description, created_at
+        FROM points_transactions
+        WHERE membership_id = $1 AND ($2::text IS NULL OR transaction_type = $2)
+        ORDER BY created_at DESC, id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(membership_id)
+    .bind(transaction_type)
+    .bind(page_size)
+    .bind((page - 1) * page_size)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(PaginatedResponse {
+        data: transactions,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// Add or deduct points on a membership, recording a points transaction
+/// performed by `performed_by`.
 ///
-/// `points` must be positive. `is_earn` controls whether lifetime_points is
-/// incremented (true) or not (false). The operation runs in its own transaction.
+/// `points` must be positive and within `loyalty_max_points_adjustment`.
+/// `is_earn` controls whether lifetime_points is incremented (true) or not
+/// (false). The operation runs in its own transaction.
 pub async fn adjust_membership_points(
     pool: &DbPool,
     membership_id: i64,
     points: i32,
     is_earn: bool,
     description: Option<String>,
+    performed_by: i64,
 ) -> Result<PointsTransaction, ApiError> {
+    check_points_adjustment(points, max_points_adjustment(pool).await)?;
+
     let membership = sqlx::query_as::<_, crate::models::LoyaltyMembership>(
         "SELECT * FROM loyalty_memberships WHERE id = $1",
     )
@@ -108,9 +406,10 @@ pub async fn adjust_membership_points(
     let transaction = sqlx::query_as::<_, PointsTransaction>(
         r#"
         INSERT INTO points_transactions (
-            membership_id, transaction_type, points_amount, balance_after, description
+            membership_id, transaction_type, points_amount, balance_after, description,
+            performed_by
         )
-        VALUES ($1, $2, $3, $4, $5)
+        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING id::text, membership_id, transaction_type, points_amount,
                   balance_after, reference_type, reference_id, description, created_at
         "#,
@@ -120,6 +419,7 @@ pub async fn adjust_membership_points(
     .bind(points_amount)
     .bind(new_balance)
     .bind(&description)
+    .bind(performed_by)
     .fetch_one(&mut *tx)
     .await
     .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -130,3 +430,633 @@ pub async fn adjust_membership_points(
 
     Ok(transaction)
 }
+
+/// Balances implied by a membership's transaction history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointsTotals {
+    /// Sum of every transaction
+    pub points_balance: i64,
+    /// Sum of positive earn transactions
+    pub lifetime_points: i64,
+}
+
+impl PointsTotals {
+    /// Totals for `(transaction_type, points)` pairs.
+    pub fn from_transactions<'a>(transactions: impl IntoIterator<Item = (&'a str, i64)>) -> Self {
+        transactions.into_iter().fold(
+            Self {
+                points_balance: 0,
+                lifetime_points: 0,
+            },
+            |mut totals, (transaction_type, points)| {
+                totals.points_balance += points;
+                if transaction_type == "earn" && points > 0 {
+                    totals.lifetime_points += points;
+                }
+                totals
+            },
+        )
+    }
+}
+
+/// Recompute `points_balance` and `lifetime_points` from the membership's
+/// transactions and correct the membership row if they disagree. A
+/// correction is recorded in the audit log against `admin_id`.
+pub async fn recompute_membership_points(
+    pool: &DbPool,
+    admin_id: i64,
+    membership_id: i64,
+) -> Result<PointsRecomputeResult, ApiError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let (previous_points_balance, previous_lifetime_points): (i32, i32) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(points_balance, 0), COALESCE(lifetime_points, 0)
+        FROM loyalty_memberships
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+    )
+    .bind(membership_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Membership not found".to_string()))?;
+
+    let transactions: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT transaction_type, points FROM points_transactions WHERE membership_id = $1",
+    )
+    .bind(membership_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let totals = PointsTotals::from_transactions(
+        transactions
+            .iter()
+            .map(|(transaction_type, points)| (transaction_type.as_str(), i64::from(*points))),
+    );
+    let to_points = |total: i64| {
+        i32::try_from(total).map_err(|_| {
+            ApiError::Internal(format!(
+                "Recomputed points for membership {} are out of range",
+                membership_id
+            ))
+        })
+    };
+    let points_balance = to_points(totals.points_balance)?;
+    let lifetime_points = to_points(totals.lifetime_points)?;
+    let corrected =
+        points_balance != previous_points_balance || lifetime_points != previous_lifetime_points;
+
+    if corrected {
+        sqlx::query(
+            r#"
+            UPDATE loyalty_memberships
+            SET points_balance = $1, lifetime_points = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $3
+            "#,
+        )
+        .bind(points_balance)
+        .bind(lifetime_points)
+        .bind(membership_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    if corrected {
+        log::warn!(
+            "Loyalty membership {} balances corrected: points {} -> {}, lifetime {} -> {}",
+            membership_id,
+            previous_points_balance,
+            points_balance,
+            previous_lifetime_points,
+            lifetime_points
+        );
+        let _ = AuditLog::log_event(
+            pool,
+            Some(admin_id),
+            "loyalty_points_recomputed",
+            "loyalty_membership",
+            Some(membership_id),
+            Some(serde_json::json!({
+                "previous_points_balance": previous_points_balance,
+                "previous_lifetime_points": previous_lifetime_points,
+                "points_balance": points_balance,
+                "lifetime_points": lifetime_points,
+            })),
+            None,
+            None,
+        )
+        .await;
+    }
+
+    Ok(PointsRecomputeResult {
+        membership_id,
+        previous_points_balance,
+        previous_lifetime_points,
+        points_balance,
+        lifetime_points,
+        corrected,
+    })
+}
+
+/// Validate a bulk adjustment against the per-adjustment cap of
+/// `max_points`, returning its trimmed description.
+pub fn check_bulk_points(input: &BulkPointsInput, max_points: i32) -> Result<&str, ApiError> {
+    check_points_adjustment(input.points, max_points)?;
+    if input.tier_level.is_some_and(|level| level < 1) {
+        return Err(ApiError::BadRequest(
+            "tier_level must be at least 1".to_string(),
+        ));
+    }
+    let description = input.description.trim();
+    if description.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A description is required for a bulk points adjustment".to_string(),
+        ));
+    }
+    Ok(description)
+}
+
+/// Add `input.points` to every active membership matching the filter, in one
+/// transaction. Each membership gets an `earn` transaction, so the points
+/// count toward its lifetime points like any other added points.
+pub async fn bulk_adjust_points(
+    pool: &DbPool,
+    performed_by: i64,
+    input: &BulkPointsInput,
+) -> Result<BulkPointsResult, ApiError> {
+    let description = check_bulk_points(input, max_points_adjustment(pool).await)?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let memberships_adjusted = sqlx::query(
+        r#"
+        WITH adjusted AS (
+            UPDATE loyalty_memberships
+            SET points_balance = COALESCE(points_balance, 0) + $3,
+                lifetime_points = COALESCE(lifetime_points, 0) + $3,
+                last_activity_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE status = 'active'
+              AND ($1::int IS NULL OR tier_level = $1)
+              AND ($2::date IS NULL OR enrolled_date < $2)
+            RETURNING id, points_balance
+        )
+        INSERT INTO points_transactions
+            (membership_id, transaction_type, points, balance_after, description, performed_by)
+        SELECT id, 'earn', $3, points_balance, $4, $5 FROM adjusted
+        "#,
+    )
+    .bind(input.tier_level)
+    .bind(input.enrolled_before)
+    .bind(input.points)
+    .bind(description)
+    .bind(performed_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .rows_affected() as i64;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let _ = AuditLog::log_event(
+        pool,
+        Some(performed_by),
+        "loyalty_points_bulk_adjusted",
+        "loyalty_membership",
+        None,
+        Some(serde_json::json!({
+            "tier_level": input.tier_level,
+            "enrolled_before": input.enrolled_before,
+            "points": input.points,
+            "description": description,
+            "memberships_adjusted": memberships_adjusted,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(BulkPointsResult {
+        points: input.points,
+        memberships_adjusted,
+    })
+}
+
+/// Balances after moving `points` from a membership holding `from_balance`
+/// to one holding `to_balance`.
+pub fn transfer_balances(
+    from_balance: i32,
+    to_balance: i32,
+    points: i32,
+) -> Result<(i32, i32), ApiError> {
+    if points <= 0 {
+        return Err(ApiError::BadRequest(
+            "Points to transfer must be greater than 0".to_string(),
+        ));
+    }
+    if from_balance < points {
+        return Err(ApiError::BadRequest(
+            "Insufficient points balance".to_string(),
+        ));
+    }
+    let to_balance = to_balance.checked_add(points).ok_or_else(|| {
+        ApiError::BadRequest("Transfer would overflow the destination balance".to_string())
+    })?;
+    Ok((from_balance - points, to_balance))
+}
+
+/// A serialization failure means a concurrent update touched the same
+/// memberships; the client can simply retry.
+fn transfer_db_error(e: sqlx::Error) -> ApiError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("40001") => {
+            ApiError::Conflict(
+                "The memberships changed during the transfer. Please try again.".to_string(),
+            )
+        }
+        _ => ApiError::Database(e.to_string()),
+    }
+}
+
+/// Move points between two active memberships in one SERIALIZABLE
+/// transaction. Each side gets a `transfer` transaction whose reference is
+/// the other side's transaction. Lifetime points are unchanged, since a
+/// transfer is not earned.
+pub async fn transfer_points(
+    pool: &DbPool,
+    staff_id: i64,
+    input: &TransferPointsInput,
+) -> Result<PointsTransferResult, ApiError> {
+    let reason = input.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A reason is required for a points transfer".to_string(),
+        ));
+    }
+    if input.from_membership_id == input.to_membership_id {
+        return Err(ApiError::BadRequest(
+            "Cannot transfer points to the same membership".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(transfer_db_error)?;
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .execute(&mut *tx)
+        .await
+        .map_err(transfer_db_error)?;
+
+    // Locked in id order so opposite transfers cannot deadlock.
+    let rows: Vec<(i64, i32, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT id, COALESCE(points_balance, 0), status
+        FROM loyalty_memberships
+        WHERE id IN ($1, $2)
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(input.from_membership_id)
+    .bind(input.to_membership_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(transfer_db_error)?;
+
+    let balance_of = |membership_id: i64| {
+        let (_, balance, status) = rows
+            .iter()
+            .find(|(id, _, _)| *id == membership_id)
+            .ok_or_else(|| ApiError::NotFound(format!("Membership {} not found", membership_id)))?;
+        if status.as_deref() != Some("active") {
+            return Err(ApiError::BadRequest(format!(
+                "Membership {} is not active",
+                membership_id
+            )));
+        }
+        Ok(*balance)
+    };
+    let (from_balance, to_balance) = transfer_balances(
+        balance_of(input.from_membership_id)?,
+        balance_of(input.to_membership_id)?,
+        input.points,
+    )?;
+
+    for (membership_id, balance) in [
+        (input.from_membership_id, from_balance),
+        (input.to_membership_id, to_balance),
+    ] {
+        sqlx::query(
+            r#"
+            UPDATE loyalty_memberships
+            SET points_balance = $1,
+                last_activity_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2
+            "#,
+        )
+        .bind(balance)
+        .bind(membership_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(transfer_db_error)?;
+    }
+
+    let insert_transfer = r#"
+        INSERT INTO points_transactions
+            (membership_id, transaction_type, points, balance_after,
+             reference_type, reference_id, description, created_by)
+        VALUES ($1, 'transfer', $2, $3, 'points_transaction', $4, $5, $6)
+        RETURNING id
+        "#;
+    let debit_transaction_id: i64 = sqlx::query_scalar(insert_transfer)
+        .bind(input.from_membership_id)
+        .bind(-input.points)
+        .bind(from_balance)
+        .bind(None::<i64>)
+        .bind(format!(
+            "Transfer to membership {}: {}",
+            input.to_membership_id, reason
+        ))
+        .bind(staff_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(transfer_db_error)?;
+    let credit_transaction_id: i64 = sqlx::query_scalar(insert_transfer)
+        .bind(input.to_membership_id)
+        .bind(input.points)
+        .bind(to_balance)
+        .bind(debit_transaction_id)
+        .bind(format!(
+            "Transfer from membership {}: {}",
+            input.from_membership_id, reason
+        ))
+        .bind(staff_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(transfer_db_error)?;
+    sqlx::query("UPDATE points_transactions SET reference_id = $1 WHERE id = $2")
+        .bind(credit_transaction_id)
+        .bind(debit_transaction_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(transfer_db_error)?;
+
+    tx.commit().await.map_err(transfer_db_error)?;
+
+    let _ = AuditLog::log_event(
+        pool,
+        Some(staff_id),
+        "loyalty_points_transferred",
+        "loyalty_membership",
+        Some(input.from_membership_id),
+        Some(serde_json::json!({
+            "to_membership_id": input.to_membership_id,
+            "points": input.points,
+            "reason": reason,
+            "debit_transaction_id": debit_transaction_id,
+            "credit_transaction_id": credit_transaction_id,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(PointsTransferResult {
+        from_membership_id: input.from_membership_id,
+        to_membership_id: input.to_membership_id,
+        points: input.points,
+        from_points_balance: from_balance,
+        to_points_balance: to_balance,
+        debit_transaction_id,
+        credit_transaction_id,
+    })
+}
+
+/// Check a points rule before it is saved and return its type.
+pub fn validate_points_rule(input: &PointsRuleInput) -> Result<PointsRuleType, ApiError> {
+    if input.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("name is required".to_string()));
+    }
+    let rule_type: PointsRuleType = input.rule_type.parse().map_err(ApiError::BadRequest)?;
+    match rule_type {
+        PointsRuleType::Bonus | PointsRuleType::Floor => {
+            if input.bonus_points.is_none_or(|points| points <= 0) {
+                return Err(ApiError::BadRequest(format!(
+                    "bonus_points must be greater than 0 for a {} rule",
+                    rule_type
+                )));
+            }
+        }
+        PointsRuleType::Multiplier => {
+            if input
+                .multiplier
+                .is_none_or(|m| !(1.0..=MAX_POINTS_MULTIPLIER).contains(&m))
+            {
+                return Err(ApiError::BadRequest(format!(
+                    "multiplier must be between 1 and {} for a multiplier rule",
+                    MAX_POINTS_MULTIPLIER
+                )));
+            }
+        }
+    }
+    if let (Some(starts_on), Some(ends_on)) = (input.starts_on, input.ends_on)
+        && ends_on < starts_on
+    {
+        return Err(ApiError::BadRequest(
+            "ends_on must not be before starts_on".to_string(),
+        ));
+    }
+    Ok(rule_type)
+}
+
+/// Whether `rule` is active and covers a stay in `room_type_id` checking in
+/// on `check_in`.
+fn rule_applies(rule: &PointsRule, room_type_id: Option<i64>, check_in: NaiveDate) -> bool {
+    rule.is_active
+        && rule.room_type_id.is_none_or(|id| Some(id) == room_type_id)
+        && rule.starts_on.is_none_or(|starts_on| starts_on <= check_in)
+        && rule.ends_on.is_none_or(|ends_on| check_in <= ends_on)
+}
+
+/// Points earned by a stay worth `amount` at `rate` points per unit of
+/// currency. Of the rules covering the stay, the highest multiplier scales
+/// the base points, every bonus is added, and the highest floor is the
+/// least the stay earns. A stay with nothing to pay earns nothing.
+pub fn stay_points(
+    amount: Decimal,
+    rate: Decimal,
+    room_type_id: Option<i64>,
+    check_in: NaiveDate,
+    rules: &[PointsRule],
+) -> i32 {
+    if amount <= Decimal::ZERO {
+        return 0;
+    }
+
+    let mut multiplier = Decimal::ONE;
+    let mut bonus: i64 = 0;
+    let mut floor: i64 = 0;
+    for rule in rules
+        .iter()
+        .filter(|rule| rule_applies(rule, room_type_id, check_in))
+    {
+        let points = i64::from(rule.bonus_points.unwrap_or(0).max(0));
+        match rule.rule_type.parse() {
+            Ok(PointsRuleType::Multiplier) => {
+                multiplier = multiplier.max(rule.multiplier.unwrap_or(Decimal::ONE));
+            }
+            Ok(PointsRuleType::Bonus) => bonus += points,
+            Ok(PointsRuleType::Floor) => floor = floor.max(points),
+            Err(_) => {}
+        }
+    }
+
+    let base = (amount * rate.max(Decimal::ZERO) * multiplier)
+        .floor()
+        .to_i64()
+        .unwrap_or(i64::MAX);
+    let points = base.saturating_add(bonus).max(floor);
+    i32::try_from(points).unwrap_or(i32::MAX)
+}
+
+/// Points a stay earns for a membership.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StayPoints {
+    pub membership_id: i64,
+    pub points: i32,
+}
+
+/// Points `booking` earns for the guest's active membership under the
+/// active points rules, or `None` when the guest has no active membership.
+/// The base is the stay's total before tax at the program's points per unit
+/// of currency times the member's tier multiplier.
+pub async fn points_for_booking(
+    pool: &DbPool,
+    booking: &Booking,
+) -> Result<Option<StayPoints>, ApiError> {
+    let membership: Option<(i64, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT lm.id,
+               COALESCE(lp.points_per_dollar, 1) * COALESCE(lt.points_multiplier, 1) AS rate
+        FROM loyalty_memberships lm
+        JOIN loyalty_programs lp ON lm.program_id = lp.id
+        LEFT JOIN loyalty_tiers lt ON lm.tier_id = lt.id
+        WHERE lm.guest_id = $1 AND lm.status = 'active'
+        ORDER BY lm.id
+        LIMIT 1
+        "#,
+    )
+    .bind(booking.guest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    let Some((membership_id, rate)) = membership else {
+        return Ok(None);
+    };
+
+    let room_type_id: Option<i64> =
+        sqlx::query_scalar("SELECT room_type_id FROM rooms WHERE id = $1")
+            .bind(booking.room_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let rules = sqlx::query_as::<_, PointsRule>(
+        "SELECT * FROM points_rules WHERE is_active = true ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let amount = booking.total_amount - booking.tax_amount.unwrap_or_default();
+    Ok(Some(StayPoints {
+        membership_id,
+        points: stay_points(amount, rate, room_type_id, booking.check_in_date, &rules),
+    }))
+}
+
+/// Award the points `booking` earns to the guest's active membership, for
+/// check-out to call. Recorded as an `earn` transaction with
+/// `reference_type = 'stay'` and the booking as `reference_id`; a unique
+/// index makes a repeat award a no-op. Returns the points awarded, or
+/// `None` when nothing was awarded.
+pub async fn award_stay_points(
+    pool: &DbPool,
+    booking: &Booking,
+    user_id: i64,
+) -> Result<Option<i32>, ApiError> {
+    let Some(earned) = points_for_booking(pool, booking).await? else {
+        return Ok(None);
+    };
+    if earned.points <= 0 {
+        return Ok(None);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let balance: i32 = sqlx::query_scalar(
+        r#"
+        UPDATE loyalty_memberships
+        SET points_balance = points_balance + $1,
+            lifetime_points = lifetime_points + $1,
+            last_activity_at = CURRENT_TIMESTAMP
+        WHERE id = $2
+        RETURNING points_balance
+        "#,
+    )
+    .bind(earned.points)
+    .bind(earned.membership_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let recorded: Option<i64> = sqlx::query_scalar(
+        r#"
+        INSERT INTO points_transactions
+            (membership_id, transaction_type, points, balance_after, reference_type, reference_id, description, created_by)
+        VALUES ($1, 'earn', $2, $3, 'stay', $4, $5, $6)
+        ON CONFLICT (reference_id) WHERE reference_type = 'stay' DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(earned.membership_id)
+    .bind(earned.points)
+    .bind(balance)
+    .bind(booking.id)
+    .bind(format!("Stay {}", booking.booking_number))
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    // Already awarded: dropping the transaction undoes the balance change.
+    if recorded.is_none() {
+        return Ok(None);
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Some(earned.points))
+}
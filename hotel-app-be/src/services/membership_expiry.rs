@@ -0,0 +1,253 @@
+//! Loyalty membership expiry and renewal
+//!
+//! The night audit moves active memberships whose `expires_at` has passed to
+//! `expired`, which takes them out of everything that works on active
+//! memberships: points awards, transfers and the tier review. With
+//! `loyalty_expiry_downgrade_enabled` on, an expiring member also drops to
+//! the lowest tier of their program, recorded as a tier change. Renewing
+//! reactivates a membership for `loyalty_membership_term_months` (or the
+//! term asked for) from its expiry, or from now if that has passed; a tier
+//! lost on expiry is not restored.
+
+use chrono::{DateTime, Months, Utc};
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::MembershipRenewal;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+
+pub const DOWNGRADE_SETTING: &str = "loyalty_expiry_downgrade_enabled";
+pub const TERM_SETTING: &str = "loyalty_membership_term_months";
+
+const DEFAULT_TERM_MONTHS: u32 = 12;
+const MAX_TERM_MONTHS: u32 = 120;
+
+/// A membership the night audit expired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiredMembership {
+    pub membership_id: i64,
+    pub guest_id: i64,
+    pub expires_at: DateTime<Utc>,
+    pub from_tier_id: Option<i64>,
+    /// Differs from `from_tier_id` when the member was downgraded
+    pub to_tier_id: Option<i64>,
+}
+
+fn parse_term_months(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|m| (1..=MAX_TERM_MONTHS).contains(m))
+}
+
+pub async fn downgrade_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, DOWNGRADE_SETTING, false).await
+}
+
+pub async fn configured_term_months(pool: &DbPool) -> u32 {
+    SettingsRepository::get_value(pool, TERM_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_term_months(&v))
+        .unwrap_or(DEFAULT_TERM_MONTHS)
+}
+
+/// Reject invalid values for the membership term setting; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key == TERM_SETTING && parse_term_months(value).is_none() {
+        return Err(format!(
+            "{} must be a whole number of months between 1 and {}",
+            key, MAX_TERM_MONTHS
+        ));
+    }
+    Ok(())
+}
+
+/// The expiry after renewing for `months`: the term runs on from the
+/// current expiry, or from `now` if there is none or it has passed.
+pub fn renewed_expiry(
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    months: u32,
+) -> Option<DateTime<Utc>> {
+    expires_at
+        .map_or(now, |expires_at| expires_at.max(now))
+        .checked_add_months(Months::new(months))
+}
+
+/// Expire every active membership whose `expires_at` is at or before
+/// `now`, downgrading it to its program's lowest tier if the setting is on.
+pub async fn expire_memberships(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<ExpiredMembership>, ApiError> {
+    let downgrade = downgrade_enabled(pool).await;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT m.id, m.guest_id, m.expires_at, m.tier_id,
+               (SELECT t.id FROM loyalty_tiers t
+                 WHERE t.program_id = m.program_id
+                 ORDER BY t.min_points, t.id
+                 LIMIT 1) AS lowest_tier_id
+        FROM loyalty_memberships m
+        WHERE m.status = 'active' AND m.expires_at <= $1
+        ORDER BY m.id
+        FOR UPDATE OF m
+        "#,
+    )
+    .bind(now)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut expired = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let from_tier_id: Option<i64> = row.get("tier_id");
+        let lowest_tier_id: Option<i64> = row.get("lowest_tier_id");
+        let to_tier_id = if downgrade {
+            lowest_tier_id.or(from_tier_id)
+        } else {
+            from_tier_id
+        };
+        let membership = ExpiredMembership {
+            membership_id: row.get("id"),
+            guest_id: row.get("guest_id"),
+            expires_at: row.get("expires_at"),
+            from_tier_id,
+            to_tier_id,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE loyalty_memberships
+            SET status = 'expired', tier_id = $1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2
+            "#,
+        )
+        .bind(membership.to_tier_id)
+        .bind(membership.membership_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        if membership.to_tier_id != membership.from_tier_id {
+            sqlx::query(
+                r#"
+                INSERT INTO loyalty_tier_changes (membership_id, from_tier_id, to_tier_id, reason)
+                VALUES ($1, $2, $3, 'membership_expired')
+                "#,
+            )
+            .bind(membership.membership_id)
+            .bind(membership.from_tier_id)
+            .bind(membership.to_tier_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        }
+
+        expired.push(membership);
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(expired)
+}
+
+/// Renew an active or expired membership for `months`, or the configured
+/// term, and record it in the audit log against `user_id`.
+pub async fn renew_membership(
+    pool: &DbPool,
+    user_id: i64,
+    membership_id: i64,
+    months: Option<u32>,
+) -> Result<MembershipRenewal, ApiError> {
+    let months = match months {
+        Some(months) if !(1..=MAX_TERM_MONTHS).contains(&months) => {
+            return Err(ApiError::BadRequest(format!(
+                "months must be between 1 and {}",
+                MAX_TERM_MONTHS
+            )));
+        }
+        Some(months) => months,
+        None => configured_term_months(pool).await,
+    };
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let (previous_status, previous_expires_at): (Option<String>, Option<DateTime<Utc>>) =
+        sqlx::query_as(
+            "SELECT status, expires_at FROM loyalty_memberships WHERE id = $1 FOR UPDATE",
+        )
+        .bind(membership_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Membership not found".to_string()))?;
+    let previous_status = previous_status.unwrap_or_default();
+    if previous_status != "active" && previous_status != "expired" {
+        return Err(ApiError::BadRequest(format!(
+            "A {} membership cannot be renewed",
+            previous_status
+        )));
+    }
+
+    let expires_at = renewed_expiry(previous_expires_at, Utc::now(), months)
+        .ok_or_else(|| ApiError::BadRequest("Invalid renewal term".to_string()))?;
+
+    sqlx::query(
+        r#"
+        UPDATE loyalty_memberships
+        SET status = 'active', expires_at = $1, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $2
+        "#,
+    )
+    .bind(expires_at)
+    .bind(membership_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let _ = AuditLog::log_event(
+        pool,
+        Some(user_id),
+        "loyalty_membership_renewed",
+        "loyalty_membership",
+        Some(membership_id),
+        Some(serde_json::json!({
+            "previous_status": previous_status,
+            "previous_expires_at": previous_expires_at,
+            "expires_at": expires_at,
+            "months": months,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(MembershipRenewal {
+        membership_id,
+        previous_status,
+        previous_expires_at,
+        status: "active".to_string(),
+        expires_at,
+    })
+}
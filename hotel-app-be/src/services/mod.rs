@@ -4,7 +4,48 @@
 
 #[allow(dead_code)]
 pub mod audit;
+pub mod avatars;
 pub mod booking;
+pub mod booking_archive;
+pub mod booking_attachments;
+pub mod booking_fees;
+pub mod booking_numbers;
+pub mod booking_sources;
+pub mod booking_trends;
+pub mod cancellation;
+pub mod circuit_breaker;
+pub mod communication;
+pub mod company_bookings;
+pub mod company_payments;
+pub mod currency;
+pub mod deposit_forfeiture;
+pub mod folio_split;
+pub mod guest_balance;
+pub mod guest_stays;
+pub mod hotel_time;
+pub mod housekeeping;
 pub mod invoice_numbers;
+pub mod login_attempts;
+pub mod login_history;
 pub mod loyalty;
+pub mod membership_expiry;
 pub mod night_audit;
+pub mod occupancy;
+pub mod occupancy_alerts;
+pub mod outbox;
+pub mod pace;
+pub mod password_policy;
+pub mod portal_guest;
+pub mod pre_arrival;
+pub mod rates;
+pub mod review_points;
+pub mod room_assignment;
+pub mod room_blocks;
+pub mod room_status;
+pub mod sessions;
+pub mod stay_policy;
+pub mod tax;
+pub mod tier_review;
+pub mod upload_cleanup;
+pub mod vip;
+pub mod walk_in;
@@ -8,6 +8,9 @@ use std::collections::HashMap;
 use crate::core::db::DbPool;
 use crate::core::error::ApiError;
 use crate::models::{JournalEntry, JournalSection, NightAuditRunWithUser, RevenueBreakdownItem};
+use crate::services::deposit_forfeiture::NO_SHOW_FORFEIT_CATEGORY;
+use crate::services::hotel_time;
+use crate::services::tax::{self, TaxContext, TaxMode};
 
 /// Backfill missing `night_audit_posted_nights` rows for a booking whose stay
 /// overlaps one or more already-completed audit dates.
@@ -56,15 +59,7 @@ pub async fn backfill_booking_posted_nights(
     let tourism_tax_amount: Decimal = row.get("tourism_tax_amount");
     let extra_bed_charge_full: Decimal = row.get("extra_bed_charge");
 
-    let tax_rate_pct: Decimal = sqlx::query_scalar::<_, String>(
-        "SELECT value FROM system_settings WHERE key = 'service_tax_rate'",
-    )
-    .fetch_optional(pool)
-    .await
-    .unwrap_or(None)
-    .and_then(|v| v.parse::<Decimal>().ok())
-    .unwrap_or(Decimal::new(8, 0));
-    let divisor = Decimal::ONE + tax_rate_pct / Decimal::new(100, 0);
+    let tax_ctx = TaxContext::load(pool, TaxMode::Inclusive).await;
 
     let is_hourly = check_in == check_out;
     let nights_total = (check_out - check_in).num_days().max(1);
@@ -79,8 +74,8 @@ pub async fn backfill_booking_posted_nights(
     };
 
     let (eb_charge_per_night, eb_tax_per_night) = if extra_bed_charge_full > Decimal::ZERO {
-        let c = (extra_bed_charge_full / divisor).round_dp(2);
-        (c, extra_bed_charge_full - c)
+        let eb = tax::compute_taxes(extra_bed_charge_full, &tax_ctx);
+        (eb.net, eb.service_tax)
     } else {
         (Decimal::ZERO, Decimal::ZERO)
     };
@@ -117,8 +112,9 @@ pub async fn backfill_booking_posted_nights(
                 })
                 .unwrap_or(room_rate);
 
-            let room_charge = (night_rate / divisor).round_dp(2);
-            let service_tax = night_rate - room_charge;
+            let taxes = tax::compute_taxes(night_rate, &tax_ctx);
+            let room_charge = taxes.net;
+            let service_tax = taxes.service_tax;
             let night_total = night_rate + extra_bed_charge_full + tourism_tax_per_night;
 
             let res = sqlx::query(
@@ -370,30 +366,9 @@ pub async fn generate_journal_sections(
 ) -> Vec<JournalSection> {
     let mut entries: Vec<JournalEntry> = Vec::new();
 
-    let tax_rate_pct: Decimal = {
-        let raw = sqlx::query_scalar::<_, String>(
-            "SELECT value FROM system_settings WHERE key = 'service_tax_rate'",
-        )
-        .fetch_optional(pool)
-        .await
-        .unwrap_or(None)
-        .and_then(|v| v.parse::<Decimal>().ok())
-        .unwrap_or(Decimal::ZERO);
+    let tax_ctx = TaxContext::load(pool, TaxMode::Inclusive).await;
 
-        if raw > Decimal::ZERO {
-            raw
-        } else {
-            Decimal::new(8, 0)
-        }
-    };
-    let divisor = Decimal::ONE + tax_rate_pct / Decimal::new(100, 0);
-
-    let hotel_timezone: String =
-        sqlx::query_scalar::<_, String>("SELECT value FROM system_settings WHERE key = 'timezone'")
-            .fetch_optional(pool)
-            .await
-            .unwrap_or(None)
-            .unwrap_or_else(|| "UTC".to_string());
+    let hotel_timezone = hotel_time::configured(pool).await.name();
 
     if is_posted {
         let query = r#"
@@ -540,8 +515,9 @@ pub async fn generate_journal_sections(
                     let is_tourist: bool = row.get("is_tourist");
                     let tourism_tax_amount: Decimal = row.get("tourism_tax_amount");
 
-                    let room_charge = (nightly_rate / divisor).round_dp(2);
-                    let service_tax = nightly_rate - room_charge;
+                    let taxes = tax::compute_taxes(nightly_rate, &tax_ctx);
+                    let room_charge = taxes.net;
+                    let service_tax = taxes.service_tax;
 
                     if room_charge > Decimal::ZERO {
                         entries.push(JournalEntry {
@@ -565,8 +541,9 @@ pub async fn generate_journal_sections(
                     }
 
                     if extra_bed_charge_raw > Decimal::ZERO {
-                        let extra_bed_charge = (extra_bed_charge_raw / divisor).round_dp(2);
-                        let extra_bed_tax = extra_bed_charge_raw - extra_bed_charge;
+                        let eb = tax::compute_taxes(extra_bed_charge_raw, &tax_ctx);
+                        let extra_bed_charge = eb.net;
+                        let extra_bed_tax = eb.service_tax;
 
                         entries.push(JournalEntry {
                             booking_number: booking_number.clone(),
@@ -648,7 +625,7 @@ pub async fn generate_journal_sections(
 
     match sqlx::query(payment_query)
         .bind(audit_date)
-        .bind(&hotel_timezone)
+        .bind(hotel_timezone)
         .fetch_all(pool)
         .await
     {
@@ -744,7 +721,7 @@ pub async fn generate_journal_sections(
 
     match sqlx::query(refund_query)
         .bind(audit_date)
-        .bind(&hotel_timezone)
+        .bind(hotel_timezone)
         .fetch_all(pool)
         .await
     {
@@ -774,6 +751,42 @@ pub async fn generate_journal_sections(
         }
     }
 
+    // Deposits forfeited on no-shows by this date's audit
+    let forfeit_query = r#"
+        SELECT b.booking_number, COALESCE(r.room_number, '') as room_number, df.amount
+        FROM deposit_forfeitures df
+        JOIN bookings b ON df.booking_id = b.id
+        LEFT JOIN rooms r ON b.room_id = r.id
+        WHERE df.audit_date = $1
+        ORDER BY b.booking_number
+    "#;
+
+    match sqlx::query(forfeit_query)
+        .bind(audit_date)
+        .fetch_all(pool)
+        .await
+    {
+        Ok(forfeit_rows) => {
+            for row in &forfeit_rows {
+                entries.push(JournalEntry {
+                    booking_number: row.get("booking_number"),
+                    room_number: row.get("room_number"),
+                    entry_type: NO_SHOW_FORFEIT_CATEGORY.to_string(),
+                    debit: row.get("amount"),
+                    credit: Decimal::ZERO,
+                    description: Some("No-Show Deposit Forfeit".to_string()),
+                });
+            }
+        }
+        Err(e) => {
+            log::error!(
+                "Failed to fetch deposit forfeitures for {}: {}",
+                audit_date,
+                e
+            );
+        }
+    }
+
     // City ledger payments received on the audit date
     let city_ledger_query = r#"
         SELECT
@@ -790,7 +803,7 @@ pub async fn generate_journal_sections(
 
     match sqlx::query(city_ledger_query)
         .bind(audit_date)
-        .bind(&hotel_timezone)
+        .bind(hotel_timezone)
         .fetch_all(pool)
         .await
     {
@@ -885,6 +898,7 @@ pub async fn generate_journal_sections(
     let trailing_types = [
         ("deposit", "Deposit"),
         ("deposit_refund", "Deposit Refund"),
+        (NO_SHOW_FORFEIT_CATEGORY, "No-Show Forfeit"),
         ("city_ledger", "City Ledger"),
     ];
 
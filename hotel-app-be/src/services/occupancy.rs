@@ -0,0 +1,103 @@
+//! Occupancy by night
+//!
+//! A room is occupied on the night of every date in its stay's
+//! `[check_in_date, check_out_date)`, so a five-night stay counts on all
+//! five nights, not just its arrival day. A day-use stay, which leaves the
+//! day it arrives, counts on that day. Voided, cancelled and no-show
+//! bookings never held a room and are left out. Archived stays count, as
+//! they do elsewhere in the reports.
+
+use chrono::NaiveDate;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+use crate::models::OccupancyNight;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const OCCUPANCY_BY_NIGHT_QUERY: &str = r#"
+    WITH RECURSIVE nights(night) AS (
+        SELECT date($1)
+        UNION ALL
+        SELECT date(night, '+1 day') FROM nights WHERE night < date($2)
+    )
+    SELECT n.night,
+           COUNT(DISTINCT b.room_id) AS rooms_occupied,
+           COALESCE(SUM(CASE WHEN b.check_in_date = n.night THEN 1 ELSE 0 END), 0) AS arrivals,
+           CAST(SUM(b.total_amount
+                    / MAX(julianday(b.check_out_date) - julianday(b.check_in_date), 1))
+                AS TEXT) AS revenue
+    FROM nights n
+    LEFT JOIN bookings_all b
+      ON b.check_in_date <= n.night
+     AND (b.check_out_date > n.night OR b.check_in_date = n.night)
+     AND b.status NOT IN ('voided', 'cancelled', 'no_show')
+    GROUP BY n.night
+    ORDER BY n.night
+"#;
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const OCCUPANCY_BY_NIGHT_QUERY: &str = r#"
+    SELECT n.night::date AS night,
+           COUNT(DISTINCT b.room_id) AS rooms_occupied,
+           COUNT(b.id) FILTER (WHERE b.check_in_date = n.night::date) AS arrivals,
+           CAST(SUM(b.total_amount / GREATEST(b.check_out_date - b.check_in_date, 1))
+                AS TEXT) AS revenue
+    FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS n(night)
+    LEFT JOIN bookings_all b
+      ON b.check_in_date <= n.night::date
+     AND (b.check_out_date > n.night::date OR b.check_in_date = n.night::date)
+     AND b.status NOT IN ('voided', 'cancelled', 'no_show')
+    GROUP BY n.night
+    ORDER BY n.night
+"#;
+
+/// Occupied room-nights as a percentage of available room-nights; zero when
+/// none were available.
+pub fn occupancy_rate(occupied_room_nights: i64, available_room_nights: i64) -> f64 {
+    if available_room_nights > 0 {
+        (occupied_room_nights as f64 / available_room_nights as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Room-nights occupied across `nights`.
+pub fn occupied_room_nights(nights: &[OccupancyNight]) -> i64 {
+    nights.iter().map(|n| n.rooms_occupied).sum()
+}
+
+/// Occupancy for each night from `start` to `end` inclusive, out of
+/// `total_rooms`. A night's revenue is each covering stay's total spread
+/// evenly over its nights.
+pub async fn occupancy_by_night(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+    total_rooms: i64,
+) -> Result<Vec<OccupancyNight>, ApiError> {
+    let rows = sqlx::query(OCCUPANCY_BY_NIGHT_QUERY)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let rooms_occupied: i64 = row.get("rooms_occupied");
+            let revenue: Option<String> = row.get("revenue");
+            OccupancyNight {
+                date: row.get("night"),
+                rooms_occupied,
+                arrivals: row.get("arrivals"),
+                revenue: revenue
+                    .map(|r| parse_decimal(&r))
+                    .unwrap_or_default()
+                    .round_dp(2),
+                occupancy_rate: occupancy_rate(rooms_occupied, total_rooms),
+            }
+        })
+        .collect())
+}
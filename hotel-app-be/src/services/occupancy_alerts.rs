@@ -0,0 +1,383 @@
+//! Forecast occupancy alerts
+//!
+//! With `occupancy_alerts_enabled` on, the night audit forecasts occupancy
+//! for each of the next `occupancy_alert_horizon_days` days from the stays
+//! already on the books and compares it with the high and low thresholds.
+//! A date whose level changes is recorded in `occupancy_alerts`; moving to
+//! high or low also enqueues an [`OCCUPANCY_THRESHOLD_CROSSED`] outbox event,
+//! so revenue managers hear about a date once per crossing rather than every
+//! night it stays busy.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::repositories::booking::BLOCKING_STATUSES;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::outbox::{self, OCCUPANCY_THRESHOLD_CROSSED};
+
+pub const ALERTS_SETTING: &str = "occupancy_alerts_enabled";
+pub const HIGH_SETTING: &str = "occupancy_alert_high_pct";
+pub const LOW_SETTING: &str = "occupancy_alert_low_pct";
+pub const HORIZON_SETTING: &str = "occupancy_alert_horizon_days";
+
+pub const DEFAULT_HIGH_PCT: i64 = 90;
+pub const DEFAULT_LOW_PCT: i64 = 30;
+pub const DEFAULT_HORIZON_DAYS: i64 = 14;
+const MAX_HORIZON_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OccupancyLevel {
+    Low,
+    Normal,
+    High,
+}
+
+impl OccupancyLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "low" => Some(Self::Low),
+            "normal" => Some(Self::Normal),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Occupancy percentages bounding the normal band. A date at or above
+/// `high_pct` is high; at or below `low_pct` it is low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub low_pct: Decimal,
+    pub high_pct: Decimal,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            low_pct: Decimal::from(DEFAULT_LOW_PCT),
+            high_pct: Decimal::from(DEFAULT_HIGH_PCT),
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn level(&self, occupancy_pct: Decimal) -> OccupancyLevel {
+        if occupancy_pct >= self.high_pct {
+            OccupancyLevel::High
+        } else if occupancy_pct <= self.low_pct {
+            OccupancyLevel::Low
+        } else {
+            OccupancyLevel::Normal
+        }
+    }
+}
+
+/// Forecast for one night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayOccupancy {
+    pub date: NaiveDate,
+    pub rooms_sold: i64,
+    pub rooms_available: i64,
+}
+
+impl DayOccupancy {
+    /// Rooms sold as a percentage of rooms available, to two places; zero
+    /// when the hotel has no rooms.
+    pub fn occupancy_pct(&self) -> Decimal {
+        if self.rooms_available <= 0 {
+            return Decimal::ZERO;
+        }
+        (Decimal::from(self.rooms_sold) * Decimal::from(100) / Decimal::from(self.rooms_available))
+            .round_dp(2)
+    }
+}
+
+/// A stay on the books: the room and its `[check_in, check_out)` nights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForecastStay {
+    pub room_id: i64,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+}
+
+impl ForecastStay {
+    /// Whether the room is occupied on the night of `date`.
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.check_in <= date && self.check_out > date
+    }
+}
+
+/// A date whose occupancy level changed since it was last evaluated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LevelChange {
+    pub date: NaiveDate,
+    pub from: OccupancyLevel,
+    pub to: OccupancyLevel,
+    pub occupancy_pct: Decimal,
+    pub rooms_sold: i64,
+    pub rooms_available: i64,
+}
+
+impl LevelChange {
+    /// Whether the change crossed into the high or low band.
+    pub fn is_alert(&self) -> bool {
+        self.to != OccupancyLevel::Normal
+    }
+}
+
+/// Occupancy for `days` nights from `start`, counting each room once per
+/// night however many stays overlap on it.
+pub fn forecast_occupancy(
+    stays: &[ForecastStay],
+    rooms_available: i64,
+    start: NaiveDate,
+    days: i64,
+) -> Vec<DayOccupancy> {
+    (0..days)
+        .map(|offset| {
+            let date = start + Duration::days(offset);
+            let mut rooms: Vec<i64> = stays
+                .iter()
+                .filter(|s| s.covers(date))
+                .map(|s| s.room_id)
+                .collect();
+            rooms.sort_unstable();
+            rooms.dedup();
+            DayOccupancy {
+                date,
+                rooms_sold: rooms.len() as i64,
+                rooms_available,
+            }
+        })
+        .collect()
+}
+
+/// Compare each forecast night with its last recorded level (normal when it
+/// has none) and return the nights whose level changed.
+pub fn evaluate_thresholds(
+    forecast: &[DayOccupancy],
+    previous: &HashMap<NaiveDate, OccupancyLevel>,
+    thresholds: &Thresholds,
+) -> Vec<LevelChange> {
+    forecast
+        .iter()
+        .filter_map(|day| {
+            let occupancy_pct = day.occupancy_pct();
+            let from = previous
+                .get(&day.date)
+                .copied()
+                .unwrap_or(OccupancyLevel::Normal);
+            let to = thresholds.level(occupancy_pct);
+            (from != to).then_some(LevelChange {
+                date: day.date,
+                from,
+                to,
+                occupancy_pct,
+                rooms_sold: day.rooms_sold,
+                rooms_available: day.rooms_available,
+            })
+        })
+        .collect()
+}
+
+fn parse_pct(value: &str) -> Option<Decimal> {
+    value
+        .trim()
+        .parse::<Decimal>()
+        .ok()
+        .filter(|p| *p >= Decimal::ZERO && *p <= Decimal::from(100))
+}
+
+fn parse_horizon(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|d| (1..=MAX_HORIZON_DAYS).contains(d))
+}
+
+async fn setting<T>(pool: &DbPool, key: &str, parse: fn(&str) -> Option<T>, default: T) -> T {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse(&v))
+        .unwrap_or(default)
+}
+
+pub async fn alerts_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, ALERTS_SETTING, false).await
+}
+
+/// Configured thresholds; the defaults are used when the pair is invalid.
+pub async fn configured_thresholds(pool: &DbPool) -> Thresholds {
+    let defaults = Thresholds::default();
+    let thresholds = Thresholds {
+        low_pct: setting(pool, LOW_SETTING, parse_pct, defaults.low_pct).await,
+        high_pct: setting(pool, HIGH_SETTING, parse_pct, defaults.high_pct).await,
+    };
+    if thresholds.low_pct < thresholds.high_pct {
+        thresholds
+    } else {
+        log::warn!(
+            "{} must be below {}; using the default occupancy thresholds",
+            LOW_SETTING,
+            HIGH_SETTING
+        );
+        defaults
+    }
+}
+
+pub async fn configured_horizon_days(pool: &DbPool) -> i64 {
+    setting(pool, HORIZON_SETTING, parse_horizon, DEFAULT_HORIZON_DAYS).await
+}
+
+/// Reject invalid values for the occupancy alert settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        HIGH_SETTING | LOW_SETTING if parse_pct(value).is_none() => {
+            Err(format!("{} must be a percentage between 0 and 100", key))
+        }
+        HORIZON_SETTING if parse_horizon(value).is_none() => Err(format!(
+            "{} must be a whole number of days between 1 and {}",
+            key, MAX_HORIZON_DAYS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Forecast the `days` nights from `start`, record the nights whose level
+/// changed and enqueue an event for each one that crossed a threshold.
+pub async fn check_occupancy_alerts(
+    pool: &DbPool,
+    start: NaiveDate,
+    days: i64,
+    thresholds: &Thresholds,
+) -> Result<Vec<LevelChange>, ApiError> {
+    let end = start + Duration::days(days);
+
+    let rooms_available: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rooms WHERE is_active")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let statuses = BLOCKING_STATUSES
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let stays: Vec<ForecastStay> = sqlx::query(&format!(
+        r#"
+        SELECT b.room_id, b.check_in_date, b.check_out_date
+        FROM bookings b
+        JOIN rooms r ON r.id = b.room_id AND r.is_active
+        WHERE b.status IN ({}) AND b.check_in_date < $2 AND b.check_out_date > $1
+        "#,
+        statuses
+    ))
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .iter()
+    .map(|row| ForecastStay {
+        room_id: row.get("room_id"),
+        check_in: row.get("check_in_date"),
+        check_out: row.get("check_out_date"),
+    })
+    .collect();
+
+    let previous: HashMap<NaiveDate, OccupancyLevel> = sqlx::query(
+        r#"
+        SELECT a.stay_date, a.level FROM occupancy_alerts a
+        WHERE a.stay_date >= $1 AND a.stay_date < $2
+          AND a.id = (SELECT MAX(id) FROM occupancy_alerts WHERE stay_date = a.stay_date)
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .iter()
+    .filter_map(|row| {
+        let date: NaiveDate = row.get("stay_date");
+        let level: String = row.get("level");
+        Some((date, OccupancyLevel::parse(&level)?))
+    })
+    .collect();
+
+    let forecast = forecast_occupancy(&stays, rooms_available, start, days);
+    let changes = evaluate_thresholds(&forecast, &previous, thresholds);
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for change in &changes {
+        let alert_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO occupancy_alerts (stay_date, level, occupancy_pct, rooms_sold, rooms_available)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(change.date)
+        .bind(change.to.as_str())
+        .bind(decimal_to_db(change.occupancy_pct))
+        .bind(change.rooms_sold)
+        .bind(change.rooms_available)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        if change.is_alert() {
+            let threshold_pct = match change.to {
+                OccupancyLevel::High => thresholds.high_pct,
+                _ => thresholds.low_pct,
+            };
+            outbox::enqueue(
+                &mut tx,
+                OCCUPANCY_THRESHOLD_CROSSED,
+                "occupancy_alert",
+                alert_id,
+                &serde_json::json!({
+                    "date": change.date,
+                    "level": change.to,
+                    "previous_level": change.from,
+                    "occupancy_pct": change.occupancy_pct.to_string(),
+                    "threshold_pct": threshold_pct.to_string(),
+                    "rooms_sold": change.rooms_sold,
+                    "rooms_available": change.rooms_available,
+                }),
+            )
+            .await?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(changes)
+}
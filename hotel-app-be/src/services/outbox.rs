@@ -0,0 +1,420 @@
+//! Transactional outbox for domain events
+//!
+//! Handlers call [`enqueue`] inside the transaction that makes the change, so
+//! an event is recorded exactly when the booking or payment commits. A
+//! background dispatcher ([`spawn_dispatcher`]) claims due rows, hands them to
+//! an [`OutboxSink`] and marks them delivered, or reschedules them with
+//! exponential backoff until [`MAX_ATTEMPTS`] is reached and the row is left
+//! `failed`. Delivery is at-least-once: a crash between sending and marking
+//! resends the event once its claim lapses, so receivers de-duplicate on the
+//! event id.
+//!
+//! Events go to the webhook in `outbox_webhook_url`; with no URL configured
+//! rows stay pending. Webhook calls go through a shared
+//! [`CircuitBreaker`], so a receiver that keeps failing is skipped for a
+//! cooldown instead of being retried on every poll. On shutdown the server
+//! runs [`flush_on_shutdown`] so events committed since the last poll go out
+//! before the process exits. There is no mail transport in the backend, so
+//! guest emails are events too ([`GUEST_BOOKING_CONFIRMATION`]) for the
+//! receiver to send; they are only queued for guests who consent (see
+//! `services::communication`).
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration as StdDuration, Instant};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, DbTransaction};
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::circuit_breaker::{self, BreakerPolicy, BreakerState, CircuitBreaker};
+
+pub const WEBHOOK_URL_SETTING: &str = "outbox_webhook_url";
+
+/// Deliveries tried before a row is left `failed`.
+pub const MAX_ATTEMPTS: i32 = 10;
+const BASE_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 3600;
+/// How long a claimed row is hidden from other dispatchers.
+const CLAIM_LEASE_SECS: i64 = 300;
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(10);
+const BATCH_SIZE: i64 = 50;
+const WEBHOOK_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+/// Longest the final dispatch pass may hold up shutdown.
+pub const SHUTDOWN_FLUSH_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+
+pub const BOOKING_CREATED: &str = "booking.created";
+pub const PAYMENT_POSTED: &str = "payment.posted";
+pub const OCCUPANCY_THRESHOLD_CROSSED: &str = "occupancy.threshold_crossed";
+/// A confirmation email to send to the booking's guest.
+pub const GUEST_BOOKING_CONFIRMATION: &str = "guest.booking_confirmation";
+
+/// A claimed outbox row, as sent to the sink.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: i64,
+    pub payload: serde_json::Value,
+    #[serde(skip)]
+    pub attempts: i32,
+}
+
+/// Record an event in the caller's transaction; it is only visible to the
+/// dispatcher once that transaction commits.
+pub async fn enqueue(
+    tx: &mut DbTransaction<'_>,
+    event_type: &str,
+    aggregate_type: &str,
+    aggregate_id: i64,
+    payload: &serde_json::Value,
+) -> Result<i64, ApiError> {
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let query = r#"
+        INSERT INTO outbox (event_type, aggregate_type, aggregate_id, payload, next_attempt_at)
+        VALUES ($1, $2, $3, CAST($4 AS JSONB), $5)
+        RETURNING id
+    "#;
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let query = r#"
+        INSERT INTO outbox (event_type, aggregate_type, aggregate_id, payload, next_attempt_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        RETURNING id
+    "#;
+
+    sqlx::query_scalar(query)
+        .bind(event_type)
+        .bind(aggregate_type)
+        .bind(aggregate_id)
+        .bind(payload.to_string())
+        .bind(Utc::now())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// Wait before the next try after `attempts` failed deliveries, or `None`
+/// once the row should be given up on.
+pub fn next_retry_delay(attempts: i32) -> Option<Duration> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let secs = BASE_RETRY_SECS
+        .saturating_mul(1_i64 << exponent)
+        .min(MAX_RETRY_SECS);
+    Some(Duration::seconds(secs))
+}
+
+/// Where outbox events are delivered.
+pub trait OutboxSink {
+    fn deliver(&self, event: &OutboxEvent) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+/// POSTs each event as JSON, with its id in `X-Outbox-Event-Id`.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(client: reqwest::Client, url: impl Into<String>) -> Self {
+        Self {
+            client,
+            url: url.into(),
+        }
+    }
+}
+
+/// Breaker shared by every webhook delivery in the process.
+fn webhook_breaker() -> &'static CircuitBreaker {
+    static BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+    BREAKER.get_or_init(|| CircuitBreaker::new(BreakerPolicy::default()))
+}
+
+impl WebhookSink {
+    async fn post(&self, event: &OutboxEvent) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("X-Outbox-Event-Id", event.id.to_string())
+            .json(event)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("webhook returned HTTP {}", response.status()))
+        }
+    }
+}
+
+impl OutboxSink for WebhookSink {
+    async fn deliver(&self, event: &OutboxEvent) -> Result<(), String> {
+        webhook_breaker().call(&self.url, || self.post(event)).await
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const CLAIM_QUERY: &str = r#"
+    UPDATE outbox SET next_attempt_at = $1
+    WHERE id IN (
+        SELECT id FROM outbox
+        WHERE status = 'pending' AND next_attempt_at <= $2
+        ORDER BY next_attempt_at, id
+        LIMIT $3
+        FOR UPDATE SKIP LOCKED
+    )
+    RETURNING id, event_type, aggregate_type, aggregate_id, payload::text AS payload, attempts
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const CLAIM_QUERY: &str = r#"
+    UPDATE outbox SET next_attempt_at = ?1
+    WHERE id IN (
+        SELECT id FROM outbox
+        WHERE status = 'pending' AND next_attempt_at <= ?2
+        ORDER BY next_attempt_at, id
+        LIMIT ?3
+    )
+    RETURNING id, event_type, aggregate_type, aggregate_id, payload, attempts
+"#;
+
+/// Claim up to `limit` rows due at `now` by pushing their next attempt past
+/// the claim lease.
+async fn claim_due(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<OutboxEvent>, ApiError> {
+    let rows = sqlx::query(CLAIM_QUERY)
+        .bind(now + Duration::seconds(CLAIM_LEASE_SECS))
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut events: Vec<OutboxEvent> = rows
+        .iter()
+        .map(|row| {
+            let payload: String = row.get("payload");
+            OutboxEvent {
+                id: row.get("id"),
+                event_type: row.get("event_type"),
+                aggregate_type: row.get("aggregate_type"),
+                aggregate_id: row.get("aggregate_id"),
+                payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                attempts: row.get("attempts"),
+            }
+        })
+        .collect();
+    events.sort_by_key(|e| e.id);
+    Ok(events)
+}
+
+/// Deliver the rows due at `now` to `sink`, recording each outcome.
+pub async fn dispatch_pending<S: OutboxSink + Sync>(
+    pool: &DbPool,
+    sink: &S,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<DispatchReport, ApiError> {
+    let mut report = DispatchReport::default();
+
+    for event in claim_due(pool, now, limit).await? {
+        let attempts = event.attempts + 1;
+        match sink.deliver(&event).await {
+            Ok(()) => {
+                sqlx::query(
+                    r#"
+                    UPDATE outbox
+                    SET status = 'delivered', attempts = $1, last_error = NULL, delivered_at = $2
+                    WHERE id = $3
+                    "#,
+                )
+                .bind(attempts)
+                .bind(Utc::now())
+                .bind(event.id)
+                .execute(pool)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+                report.delivered += 1;
+            }
+            Err(error) => {
+                let (status, next_attempt_at) = match next_retry_delay(attempts) {
+                    Some(delay) => {
+                        report.retrying += 1;
+                        ("pending", now + delay)
+                    }
+                    None => {
+                        report.failed += 1;
+                        ("failed", now)
+                    }
+                };
+                sqlx::query(
+                    r#"
+                    UPDATE outbox
+                    SET status = $1, attempts = $2, last_error = $3, next_attempt_at = $4
+                    WHERE id = $5
+                    "#,
+                )
+                .bind(status)
+                .bind(attempts)
+                .bind(&error)
+                .bind(next_attempt_at)
+                .bind(event.id)
+                .execute(pool)
+                .await
+                .map_err(|e| ApiError::Database(e.to_string()))?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Reject webhook URLs that are neither empty nor http(s).
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    if key == WEBHOOK_URL_SETTING
+        && !value.is_empty()
+        && !(value.starts_with("http://") || value.starts_with("https://"))
+    {
+        return Err(format!("{} must be an http:// or https:// URL", key));
+    }
+    Ok(())
+}
+
+/// Deliver everything due, batch by batch, until a pass comes back short or
+/// `timeout` runs out. Rows whose delivery fails are rescheduled as usual;
+/// rows still queued when time is up wait for the next start.
+pub async fn flush_pending<S: OutboxSink + Sync>(
+    pool: &DbPool,
+    sink: &S,
+    timeout: StdDuration,
+) -> Result<DispatchReport, ApiError> {
+    let mut report = DispatchReport::default();
+    let flushed = tokio::time::timeout(timeout, async {
+        loop {
+            let pass = dispatch_pending(pool, sink, Utc::now(), BATCH_SIZE).await?;
+            report.delivered += pass.delivered;
+            report.retrying += pass.retrying;
+            report.failed += pass.failed;
+            if ((pass.delivered + pass.retrying + pass.failed) as i64) < BATCH_SIZE {
+                return Ok::<(), ApiError>(());
+            }
+        }
+    })
+    .await;
+
+    match flushed {
+        Ok(result) => result.map(|()| report),
+        Err(_) => {
+            log::warn!(
+                "Outbox flush stopped after {:?}; remaining events wait for the next start",
+                timeout
+            );
+            Ok(report)
+        }
+    }
+}
+
+fn webhook_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build()
+}
+
+async fn configured_webhook_url(pool: &DbPool) -> Option<String> {
+    SettingsRepository::get_value(pool, WEBHOOK_URL_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+fn log_report(report: &DispatchReport) {
+    if *report != DispatchReport::default() {
+        log::info!(
+            "Outbox: {} delivered, {} retrying, {} failed",
+            report.delivered,
+            report.retrying,
+            report.failed
+        );
+    }
+}
+
+/// Poll the outbox for the life of the process. The webhook URL is re-read
+/// each round so changing the setting takes effect without a restart.
+/// Abort the returned task before [`flush_on_shutdown`] so the two don't
+/// race for the same rows.
+pub fn spawn_dispatcher(pool: DbPool) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = match webhook_client() {
+            Ok(client) => client,
+            Err(e) => {
+                log::error!("Outbox dispatcher disabled: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let Some(url) = configured_webhook_url(&pool).await else {
+                continue;
+            };
+            let breaker = webhook_breaker();
+            breaker.set_policy(circuit_breaker::configured_policy(&pool).await);
+            // Leave rows untouched while the receiver is known to be down.
+            if breaker.state(&url, Instant::now()) == BreakerState::Open {
+                continue;
+            }
+
+            let sink = WebhookSink::new(client.clone(), url);
+            match dispatch_pending(&pool, &sink, Utc::now(), BATCH_SIZE).await {
+                Ok(report) => log_report(&report),
+                Err(e) => log::warn!("Outbox dispatch failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Final dispatch pass at shutdown, bounded by [`SHUTDOWN_FLUSH_TIMEOUT`].
+pub async fn flush_on_shutdown(pool: &DbPool) {
+    let Some(url) = configured_webhook_url(pool).await else {
+        return;
+    };
+    let breaker = webhook_breaker();
+    breaker.set_policy(circuit_breaker::configured_policy(pool).await);
+    if breaker.state(&url, Instant::now()) == BreakerState::Open {
+        log::warn!("Outbox flush skipped: webhook circuit is open");
+        return;
+    }
+    let client = match webhook_client() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Outbox flush skipped: {}", e);
+            return;
+        }
+    };
+
+    let sink = WebhookSink::new(client, url);
+    match flush_pending(pool, &sink, SHUTDOWN_FLUSH_TIMEOUT).await {
+        Ok(report) => log_report(&report),
+        Err(e) => log::warn!("Outbox flush failed: {}", e),
+    }
+}
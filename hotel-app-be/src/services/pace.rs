@@ -0,0 +1,138 @@
+//! Booking pace
+//!
+//! Pace is the business already on the books for upcoming stay dates: for
+//! each night, the room-nights and room revenue of the stays that cover it.
+//! Stays are the ones the occupancy forecast counts (blocking statuses in
+//! active rooms), and a night's revenue is the booking's `daily_rates`
+//! price for that date, falling back to its room rate.
+
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+use crate::models::{PaceDay, PaceReport};
+use crate::repositories::booking::BLOCKING_STATUSES;
+use crate::services::currency;
+use crate::services::occupancy_alerts::ForecastStay;
+use crate::services::rates;
+
+/// Longest range one report covers.
+pub const MAX_PACE_DAYS: i64 = 366;
+
+/// A stay on the books with what it earns per night.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaceStay {
+    pub stay: ForecastStay,
+    pub room_rate: Decimal,
+    pub daily_rates: Option<serde_json::Value>,
+}
+
+impl PaceStay {
+    /// Room revenue for the night of `date`.
+    pub fn night_rate(&self, date: NaiveDate) -> Decimal {
+        self.daily_rates
+            .as_ref()
+            .and_then(|d| rates::daily_rate(d, date))
+            .unwrap_or(self.room_rate)
+    }
+}
+
+/// Pace for every date from `start` to `end` inclusive.
+pub fn pace(stays: &[PaceStay], start: NaiveDate, end: NaiveDate) -> Vec<PaceDay> {
+    let days = (end - start).num_days() + 1;
+    (0..days)
+        .map(|offset| {
+            let date = start + Duration::days(offset);
+            let covering: Vec<&PaceStay> = stays.iter().filter(|s| s.stay.covers(date)).collect();
+            PaceDay {
+                date,
+                room_nights: covering.len() as i64,
+                revenue: covering.iter().map(|s| s.night_rate(date)).sum(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const RATE_COLUMNS: &str =
+    "CAST(b.rate_per_night AS TEXT) AS room_rate, CAST(NULL AS TEXT) AS daily_rates";
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const RATE_COLUMNS: &str =
+    "CAST(b.room_rate AS TEXT) AS room_rate, CAST(b.daily_rates AS TEXT) AS daily_rates";
+
+/// Stays on the books with a night between `start` and `end` inclusive.
+pub async fn stays_on_books(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<PaceStay>, ApiError> {
+    let statuses = BLOCKING_STATUSES
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT b.room_id, b.check_in_date, b.check_out_date, {}
+        FROM bookings b
+        JOIN rooms r ON r.id = b.room_id AND r.is_active
+        WHERE b.status IN ({}) AND b.check_in_date <= $2 AND b.check_out_date > $1
+        "#,
+        RATE_COLUMNS, statuses
+    ))
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let room_rate: Option<String> = row.get("room_rate");
+            let daily_rates: Option<String> = row.get("daily_rates");
+            PaceStay {
+                stay: ForecastStay {
+                    room_id: row.get("room_id"),
+                    check_in: row.get("check_in_date"),
+                    check_out: row.get("check_out_date"),
+                },
+                room_rate: room_rate.map(|r| parse_decimal(&r)).unwrap_or_default(),
+                daily_rates: daily_rates.and_then(|d| serde_json::from_str(&d).ok()),
+            }
+        })
+        .collect())
+}
+
+/// Pace report for `start` to `end` inclusive.
+pub async fn pace_report(
+    pool: &DbPool,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<PaceReport, ApiError> {
+    if end < start {
+        return Err(ApiError::BadRequest(
+            "end must not be before start".to_string(),
+        ));
+    }
+    if (end - start).num_days() >= MAX_PACE_DAYS {
+        return Err(ApiError::BadRequest(format!(
+            "Pace covers at most {} days",
+            MAX_PACE_DAYS
+        )));
+    }
+
+    let stays = stays_on_books(pool, start, end).await?;
+    let days = pace(&stays, start, end);
+    Ok(PaceReport {
+        start,
+        end,
+        total_room_nights: days.iter().map(|d| d.room_nights).sum(),
+        total_revenue: days.iter().map(|d| d.revenue).sum(),
+        currency: currency::hotel_currency(pool).await,
+        days,
+    })
+}
@@ -0,0 +1,166 @@
+//! Configurable password policy
+//!
+//! Every path that sets a password (registration, user creation, password
+//! change, guest upgrade) checks it against [`configured_policy`], built from
+//! the `password_*` system settings. Length bounds and each character-class
+//! requirement can be changed per property; with `password_breached_check`
+//! on, passwords found in the file at `password_breached_list_path` (one per
+//! line) are rejected too. The built-in weak-password list always applies.
+//! A rejected password's error names every requirement it misses, both in
+//! its message and as `details` entries with a stable `code` each.
+
+use std::collections::HashSet;
+
+use crate::core::auth::{PasswordPolicy, PasswordRequirement};
+use crate::core::db::DbPool;
+use crate::core::error::{ApiError, ValidationIssue};
+use crate::repositories::settings::SettingsRepository;
+
+pub const MIN_LENGTH_SETTING: &str = "password_min_length";
+pub const MAX_LENGTH_SETTING: &str = "password_max_length";
+pub const REQUIRE_UPPERCASE_SETTING: &str = "password_require_uppercase";
+pub const REQUIRE_LOWERCASE_SETTING: &str = "password_require_lowercase";
+pub const REQUIRE_DIGIT_SETTING: &str = "password_require_digit";
+pub const REQUIRE_SYMBOL_SETTING: &str = "password_require_symbol";
+pub const BREACHED_CHECK_SETTING: &str = "password_breached_check";
+pub const BREACHED_LIST_SETTING: &str = "password_breached_list_path";
+
+/// Shortest minimum the settings accept.
+const MIN_CONFIGURABLE_LENGTH: usize = 8;
+/// Longest maximum the settings accept.
+const MAX_CONFIGURABLE_LENGTH: usize = 1024;
+
+fn parse_length(value: &str) -> Option<usize> {
+    value
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (MIN_CONFIGURABLE_LENGTH..=MAX_CONFIGURABLE_LENGTH).contains(n))
+}
+
+async fn length_setting(pool: &DbPool, key: &str, default: usize) -> usize {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_length(&v))
+        .unwrap_or(default)
+}
+
+/// Read a breached-password list: one password per line, blank lines
+/// ignored, lowercased for case-insensitive matching.
+pub fn load_breached_list(path: &str) -> std::io::Result<HashSet<String>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_lowercase)
+        .collect())
+}
+
+/// Load the configured policy, falling back to the built-in rules for
+/// missing or invalid values. The breached list is re-read on each call so
+/// an updated file takes effect without a restart; if it can't be read the
+/// check is skipped with a warning.
+pub async fn configured_policy(pool: &DbPool) -> PasswordPolicy {
+    let defaults = PasswordPolicy::default();
+    let min_length = length_setting(pool, MIN_LENGTH_SETTING, defaults.min_length).await;
+    let max_length = length_setting(pool, MAX_LENGTH_SETTING, defaults.max_length).await;
+    let (min_length, max_length) = if min_length <= max_length {
+        (min_length, max_length)
+    } else {
+        log::warn!(
+            "{} must not exceed {}; using the default password length bounds",
+            MIN_LENGTH_SETTING,
+            MAX_LENGTH_SETTING
+        );
+        (defaults.min_length, defaults.max_length)
+    };
+
+    let mut breached_passwords = HashSet::new();
+    if SettingsRepository::get_bool(pool, BREACHED_CHECK_SETTING, false).await {
+        let path = SettingsRepository::get_value(pool, BREACHED_LIST_SETTING)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        match load_breached_list(path.trim()) {
+            Ok(list) => breached_passwords = list,
+            Err(e) => log::warn!(
+                "Breached-password check skipped: cannot read {:?}: {}",
+                path,
+                e
+            ),
+        }
+    }
+
+    PasswordPolicy {
+        min_length,
+        max_length,
+        require_uppercase: SettingsRepository::get_bool(
+            pool,
+            REQUIRE_UPPERCASE_SETTING,
+            defaults.require_uppercase,
+        )
+        .await,
+        require_lowercase: SettingsRepository::get_bool(
+            pool,
+            REQUIRE_LOWERCASE_SETTING,
+            defaults.require_lowercase,
+        )
+        .await,
+        require_digit: SettingsRepository::get_bool(
+            pool,
+            REQUIRE_DIGIT_SETTING,
+            defaults.require_digit,
+        )
+        .await,
+        require_symbol: SettingsRepository::get_bool(
+            pool,
+            REQUIRE_SYMBOL_SETTING,
+            defaults.require_symbol,
+        )
+        .await,
+        breached_passwords,
+    }
+}
+
+/// Check `password` against the configured policy. The error lists every
+/// requirement it does not meet.
+pub async fn validate_password(pool: &DbPool, password: &str) -> Result<(), ApiError> {
+    configured_policy(pool)
+        .await
+        .validate(password)
+        .map_err(|unmet| policy_error(&unmet))
+}
+
+/// The error for a password missing the requirements in `unmet`.
+fn policy_error(unmet: &[PasswordRequirement]) -> ApiError {
+    ApiError::Validation(
+        PasswordRequirement::summary(unmet),
+        unmet
+            .iter()
+            .map(|r| ValidationIssue {
+                code: r.code(),
+                message: r.message(),
+            })
+            .collect(),
+    )
+}
+
+/// Reject invalid values for the password policy settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        MIN_LENGTH_SETTING | MAX_LENGTH_SETTING if parse_length(value).is_none() => Err(format!(
+            "{} must be a whole number between {} and {}",
+            key, MIN_CONFIGURABLE_LENGTH, MAX_CONFIGURABLE_LENGTH
+        )),
+        BREACHED_LIST_SETTING
+            if !value.trim().is_empty() && !std::path::Path::new(value.trim()).is_file() =>
+        {
+            Err(format!("{} must be the path of a readable file", key))
+        }
+        _ => Ok(()),
+    }
+}
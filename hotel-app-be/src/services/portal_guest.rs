@@ -0,0 +1,209 @@
+//! Guest records for portal accounts
+//!
+//! Portal bookings are made for a guest the account is linked to through
+//! `user_guests`. With `portal_auto_create_guest` on (the default), signup
+//! creates a guest from the new user's details and links it as the account's
+//! own profile: relationship `self`, allowed to book, view and modify. It
+//! happens in the signup transaction, so there is never a user without its
+//! guest. Guest accounts that have no link yet (signed up while the setting
+//! was off, or before links were made) get one the first time the portal
+//! looks up their guests.
+
+use crate::core::db::{DbPool, DbTransaction};
+use crate::core::error::{ApiError, RepoError};
+use crate::repositories::settings::SettingsRepository;
+
+pub const AUTO_CREATE_SETTING: &str = "portal_auto_create_guest";
+
+pub async fn auto_create_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, AUTO_CREATE_SETTING, true).await
+}
+
+/// Contact details copied from the account onto its new guest.
+#[derive(Debug, Clone)]
+pub struct PortalGuestDetails {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub phone: Option<String>,
+}
+
+impl PortalGuestDetails {
+    /// Split an account's display name into first and last name, falling
+    /// back to the username when the account has no name.
+    pub fn from_account(
+        full_name: Option<&str>,
+        username: &str,
+        email: &str,
+        phone: Option<&str>,
+    ) -> Self {
+        let name = full_name
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(username);
+        let (first_name, last_name) = match name.split_once(char::is_whitespace) {
+            Some((first, rest)) => (first.to_string(), rest.trim().to_string()),
+            None => (name.to_string(), String::new()),
+        };
+        Self {
+            first_name,
+            last_name,
+            email: email.to_string(),
+            phone: phone.map(str::to_string),
+        }
+    }
+
+    fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+            .trim()
+            .to_string()
+    }
+}
+
+/// Link `guest_id` to `user_id` as the account's own profile.
+async fn link_own_guest(
+    tx: &mut DbTransaction<'_>,
+    user_id: i64,
+    guest_id: i64,
+) -> Result<(), ApiError> {
+    sqlx::query("UPDATE users SET guest_id = $1 WHERE id = $2")
+        .bind(guest_id)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_guests
+            (user_id, guest_id, relationship_type, can_book_for, can_view_bookings, can_modify, linked_by)
+        VALUES ($1, $2, 'self', true, true, true, $1)
+        ON CONFLICT (user_id, guest_id) DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(guest_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Create a guest from `details` and link it to `user_id`, in the caller's
+/// transaction. Returns the new guest's id. Guest names are unique, so a
+/// name already on file is a `Conflict`.
+pub async fn create_linked_guest(
+    tx: &mut DbTransaction<'_>,
+    user_id: i64,
+    details: &PortalGuestDetails,
+) -> Result<i64, ApiError> {
+    let guest_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO guests (first_name, last_name, full_name, email, phone, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(&details.first_name)
+    .bind(&details.last_name)
+    .bind(details.full_name())
+    .bind(&details.email)
+    .bind(&details.phone)
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(RepoError::from)?;
+
+    link_own_guest(tx, user_id, guest_id).await?;
+    Ok(guest_id)
+}
+
+const BOOKABLE_GUEST_QUERY: &str = r#"
+    SELECT guest_id FROM user_guests
+    WHERE user_id = $1 AND can_book_for = true
+    ORDER BY id
+    LIMIT 1
+"#;
+
+// Lock the account row so two first requests don't both create a guest.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const PORTAL_ACCOUNT_QUERY: &str = r#"
+    SELECT u.username, u.email, u.full_name, u.phone, g.id
+    FROM users u
+    LEFT JOIN guests g ON g.id = u.guest_id AND g.deleted_at IS NULL
+    WHERE u.id = $1 AND u.user_type = 'guest'
+    FOR UPDATE OF u
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const PORTAL_ACCOUNT_QUERY: &str = r#"
+    SELECT u.username, u.email, u.full_name, u.phone, g.id
+    FROM users u
+    LEFT JOIN guests g ON g.id = u.guest_id
+    WHERE u.id = ?1 AND u.user_type = 'guest'
+"#;
+
+/// The guest a portal account books for, linking or creating it on first
+/// use. `None` for staff accounts, and for unlinked accounts while
+/// automatic creation is off.
+pub async fn ensure_portal_guest(pool: &DbPool, user_id: i64) -> Result<Option<i64>, ApiError> {
+    let linked: Option<i64> = sqlx::query_scalar(BOOKABLE_GUEST_QUERY)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if let Some(guest_id) = linked {
+        return Ok(Some(guest_id));
+    }
+    if !auto_create_enabled(pool).await {
+        return Ok(None);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let account: Option<(String, String, Option<String>, Option<String>, Option<i64>)> =
+        sqlx::query_as(PORTAL_ACCOUNT_QUERY)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    let Some((username, email, full_name, phone, existing_guest)) = account else {
+        return Ok(None);
+    };
+
+    // A concurrent request may have linked a guest while we waited.
+    let linked: Option<i64> = sqlx::query_scalar(BOOKABLE_GUEST_QUERY)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if linked.is_some() {
+        return Ok(linked);
+    }
+
+    let guest_id = match existing_guest {
+        Some(guest_id) => {
+            link_own_guest(&mut tx, user_id, guest_id).await?;
+            guest_id
+        }
+        None => {
+            let details = PortalGuestDetails::from_account(
+                full_name.as_deref(),
+                &username,
+                &email,
+                phone.as_deref(),
+            );
+            create_linked_guest(&mut tx, user_id, &details).await?
+        }
+    };
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Some(guest_id))
+}
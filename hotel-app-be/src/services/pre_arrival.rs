@@ -0,0 +1,81 @@
+//! Upcoming arrivals for pre-arrival outreach
+//!
+//! Lists guests with a confirmed stay starting between the hotel's today and
+//! `within_days` days later, one entry per guest at their earliest arrival.
+//! "Today" is the date in the hotel's `timezone` setting.
+
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDate};
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::ArrivingGuest;
+
+pub const DEFAULT_WITHIN_DAYS: i64 = 7;
+pub const MAX_WITHIN_DAYS: i64 = 90;
+
+/// Keep each guest's first entry; `arrivals` must be in arrival order.
+pub fn first_arrival_per_guest(arrivals: Vec<ArrivingGuest>) -> Vec<ArrivingGuest> {
+    let mut seen = HashSet::new();
+    arrivals
+        .into_iter()
+        .filter(|a| seen.insert(a.guest_id))
+        .collect()
+}
+
+/// Guests arriving on `today` through `today + within_days`, inclusive.
+pub async fn arriving_guests(
+    pool: &DbPool,
+    today: NaiveDate,
+    within_days: i64,
+) -> Result<Vec<ArrivingGuest>, ApiError> {
+    if !(0..=MAX_WITHIN_DAYS).contains(&within_days) {
+        return Err(ApiError::BadRequest(format!(
+            "within_days must be between 0 and {}",
+            MAX_WITHIN_DAYS
+        )));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT g.id AS guest_id,
+               COALESCE(NULLIF(g.full_name, ''), g.first_name || ' ' || g.last_name) AS full_name,
+               g.email, g.phone, COALESCE(g.is_vip, false) AS is_vip,
+               b.id AS booking_id, b.booking_number, b.check_in_date, r.room_number
+        FROM bookings b
+        JOIN guests g ON g.id = b.guest_id
+        LEFT JOIN rooms r ON r.id = b.room_id
+        WHERE b.status = 'confirmed'
+          AND b.check_in_date >= $1 AND b.check_in_date <= $2
+        ORDER BY b.check_in_date, b.id
+        "#,
+    )
+    .bind(today)
+    .bind(today + Duration::days(within_days))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let arrivals = rows
+        .iter()
+        .map(|row| ArrivingGuest {
+            guest_id: row.get("guest_id"),
+            full_name: row
+                .try_get::<Option<String>, _>("full_name")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            email: row.try_get("email").ok().flatten(),
+            phone: row.try_get("phone").ok().flatten(),
+            booking_id: row.get("booking_id"),
+            booking_number: row.try_get("booking_number").ok().flatten(),
+            arrival_date: row.get("check_in_date"),
+            room_number: row.try_get("room_number").ok().flatten(),
+            is_vip: row.try_get("is_vip").unwrap_or(false),
+        })
+        .collect();
+
+    Ok(first_arrival_per_guest(arrivals))
+}
@@ -0,0 +1,290 @@
+//! Nightly rate resolution
+//!
+//! `resolve_rates` is the single answer to "what does each night of this room
+//! type cost across these dates". The highest-priority active rate plan whose
+//! validity window, room-rate effective dates, and weekday flags cover the
+//! date wins (seasonal plans are just plans with a validity window); otherwise
+//! the room type's base price applies. Booking creation and the rate calendar
+//! both price nights through here.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rust_decimal::Decimal;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::{NightlyRate, RoomRateWithDetails};
+
+/// Rate plan code reported when no plan applies.
+pub const BASE_RATE_CODE: &str = "BASE";
+
+/// The room rate of the highest-priority plan covering `date`, if any.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+pub async fn applicable_room_rate(
+    pool: &DbPool,
+    room_type_id: i64,
+    date: NaiveDate,
+) -> Result<Option<RoomRateWithDetails>, sqlx::Error> {
+    let day_of_week = date.weekday().num_days_from_monday();
+
+    sqlx::query_as::<_, RoomRateWithDetails>(
+        r#"
+        SELECT
+            rr.id,
+            rr.rate_plan_id,
+            rp.name as rate_plan_name,
+            rp.code as rate_plan_code,
+            rp.description as rate_plan_description,
+            rr.room_type_id,
+            rt.name as room_type_name,
+            rt.code as room_type_code,
+            rr.price,
+            rr.effective_from,
+            rr.effective_to,
+            rr.created_at
+        FROM room_rates rr
+        JOIN rate_plans rp ON rr.rate_plan_id = rp.id
+        JOIN room_types rt ON rr.room_type_id = rt.id
+        WHERE rr.room_type_id = $1
+          AND rp.is_active = true
+          AND rr.effective_from <= $2
+          AND (rr.effective_to IS NULL OR rr.effective_to >= $2)
+          AND (rp.valid_from IS NULL OR rp.valid_from <= $2)
+          AND (rp.valid_to IS NULL OR rp.valid_to >= $2)
+          AND (
+              ($3 = 0 AND rp.applies_monday = true) OR
+              ($3 = 1 AND rp.applies_tuesday = true) OR
+              ($3 = 2 AND rp.applies_wednesday = true) OR
+              ($3 = 3 AND rp.applies_thursday = true) OR
+              ($3 = 4 AND rp.applies_friday = true) OR
+              ($3 = 5 AND rp.applies_saturday = true) OR
+              ($3 = 6 AND rp.applies_sunday = true)
+          )
+        ORDER BY rp.priority DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(room_type_id)
+    .bind(date)
+    .bind(day_of_week as i32)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The SQLite schema has no rate plans, so only base prices apply.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub async fn applicable_room_rate(
+    _pool: &DbPool,
+    _room_type_id: i64,
+    _date: NaiveDate,
+) -> Result<Option<RoomRateWithDetails>, sqlx::Error> {
+    Ok(None)
+}
+
+async fn base_price(pool: &DbPool, room_type_id: i64) -> Result<Decimal, ApiError> {
+    let price: String =
+        sqlx::query_scalar("SELECT CAST(base_price AS TEXT) FROM room_types WHERE id = $1")
+            .bind(room_type_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound("Room type not found".to_string()))?;
+
+    price.parse().map_err(|_| {
+        ApiError::Internal(format!("Invalid base price for room type {}", room_type_id))
+    })
+}
+
+/// A room rate with the plan terms that decide which dates it covers.
+#[derive(Debug, sqlx::FromRow)]
+struct PlanRate {
+    rate_plan_code: String,
+    price: Decimal,
+    effective_from: NaiveDate,
+    effective_to: Option<NaiveDate>,
+    valid_from: Option<NaiveDate>,
+    valid_to: Option<NaiveDate>,
+    applies_monday: bool,
+    applies_tuesday: bool,
+    applies_wednesday: bool,
+    applies_thursday: bool,
+    applies_friday: bool,
+    applies_saturday: bool,
+    applies_sunday: bool,
+}
+
+impl PlanRate {
+    /// Whether this rate is the one [`applicable_room_rate`] would consider
+    /// for `date`.
+    fn covers(&self, date: NaiveDate) -> bool {
+        let applies_on_weekday = match date.weekday() {
+            Weekday::Mon => self.applies_monday,
+            Weekday::Tue => self.applies_tuesday,
+            Weekday::Wed => self.applies_wednesday,
+            Weekday::Thu => self.applies_thursday,
+            Weekday::Fri => self.applies_friday,
+            Weekday::Sat => self.applies_saturday,
+            Weekday::Sun => self.applies_sunday,
+        };
+
+        self.effective_from <= date
+            && self.effective_to.is_none_or(|to| to >= date)
+            && self.valid_from.is_none_or(|from| from <= date)
+            && self.valid_to.is_none_or(|to| to >= date)
+            && applies_on_weekday
+    }
+}
+
+/// Every active room rate covering some date from `start` to `end`,
+/// highest priority first.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+async fn plan_rates_between(
+    pool: &DbPool,
+    room_type_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<PlanRate>, ApiError> {
+    sqlx::query_as::<_, PlanRate>(
+        r#"
+        SELECT
+            rp.code as rate_plan_code,
+            rr.price,
+            rr.effective_from,
+            rr.effective_to,
+            rp.valid_from,
+            rp.valid_to,
+            rp.applies_monday,
+            rp.applies_tuesday,
+            rp.applies_wednesday,
+            rp.applies_thursday,
+            rp.applies_friday,
+            rp.applies_saturday,
+            rp.applies_sunday
+        FROM room_rates rr
+        JOIN rate_plans rp ON rr.rate_plan_id = rp.id
+        WHERE rr.room_type_id = $1
+          AND rp.is_active = true
+          AND rr.effective_from <= $3
+          AND (rr.effective_to IS NULL OR rr.effective_to >= $2)
+          AND (rp.valid_from IS NULL OR rp.valid_from <= $3)
+          AND (rp.valid_to IS NULL OR rp.valid_to >= $2)
+        ORDER BY rp.priority DESC
+        "#,
+    )
+    .bind(room_type_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// The SQLite schema has no rate plans, so only base prices apply.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+async fn plan_rates_between(
+    _pool: &DbPool,
+    _room_type_id: i64,
+    _start: NaiveDate,
+    _end: NaiveDate,
+) -> Result<Vec<PlanRate>, ApiError> {
+    Ok(Vec::new())
+}
+
+/// Resolve the nightly rate for `room_type_id` on `date`.
+pub async fn resolve_rate(
+    pool: &DbPool,
+    room_type_id: i64,
+    date: NaiveDate,
+) -> Result<NightlyRate, ApiError> {
+    let mut rates = resolve_rates(pool, room_type_id, date, date).await?;
+    Ok(rates.remove(0))
+}
+
+/// Resolve every date from `start` to `end`, both inclusive.
+///
+/// The plan rates for the whole range are loaded at once and the base price
+/// only if some date has no plan.
+pub async fn resolve_rates(
+    pool: &DbPool,
+    room_type_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<NightlyRate>, ApiError> {
+    let plan_rates = plan_rates_between(pool, room_type_id, start, end).await?;
+    let mut base = None;
+
+    let mut rates = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let rate = match plan_rates.iter().find(|rate| rate.covers(date)) {
+            Some(rate) => NightlyRate {
+                date,
+                price: rate.price,
+                rate_plan_code: rate.rate_plan_code.clone(),
+                is_base_rate: false,
+            },
+            None => {
+                let price = match base {
+                    Some(price) => price,
+                    None => *base.insert(base_price(pool, room_type_id).await?),
+                };
+                NightlyRate {
+                    date,
+                    price,
+                    rate_plan_code: BASE_RATE_CODE.to_string(),
+                    is_base_rate: true,
+                }
+            }
+        };
+        rates.push(rate);
+        date += Duration::days(1);
+    }
+    Ok(rates)
+}
+
+/// The nights billed for a stay, first to last inclusive.
+///
+/// A same-day (hourly) stay is billed as its check-in night.
+pub fn billable_nights(check_in: NaiveDate, check_out: NaiveDate) -> (NaiveDate, NaiveDate) {
+    if check_out > check_in {
+        (check_in, check_out.pred_opt().unwrap_or(check_in))
+    } else {
+        (check_in, check_in)
+    }
+}
+
+/// Resolve the rate of every billable night of a stay.
+pub async fn resolve_stay(
+    pool: &DbPool,
+    room_type_id: i64,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> Result<Vec<NightlyRate>, ApiError> {
+    let (first, last) = billable_nights(check_in, check_out);
+    resolve_rates(pool, room_type_id, first, last).await
+}
+
+/// The price stored for `date` in a `bookings.daily_rates` object, written
+/// either as a number or as a numeric string.
+pub fn daily_rate(daily_rates: &serde_json::Value, date: NaiveDate) -> Option<Decimal> {
+    let value = daily_rates.get(date.format("%Y-%m-%d").to_string())?;
+    value
+        .as_str()
+        .and_then(|s| s.parse::<Decimal>().ok())
+        .or_else(|| value.as_f64().and_then(Decimal::from_f64_retain))
+}
+
+/// Per-night rates as stored in `bookings.daily_rates` (`{"YYYY-MM-DD": price}`).
+pub fn daily_rates_json(rates: &[NightlyRate]) -> serde_json::Value {
+    use rust_decimal::prelude::ToPrimitive;
+
+    rates
+        .iter()
+        .map(|r| {
+            (
+                r.date.format("%Y-%m-%d").to_string(),
+                serde_json::json!(r.price.to_f64().unwrap_or(0.0)),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
@@ -0,0 +1,147 @@
+//! Loyalty points for guest reviews
+//!
+//! With `review_points_enabled` on, a verified review earns the guest's
+//! active membership `review_points_bonus` points, recorded as an `earn`
+//! transaction with `reference_type = 'review'` and the booking as
+//! `reference_id`. A review counts as verified when it is for a checked-out
+//! stay of the reviewing guest. Each booking pays out at most once: a unique
+//! index on review transactions makes a repeat award (an edited or
+//! resubmitted review) a no-op, even when two arrive together. The guest
+//! portal's review submission awards the bonus in the review's transaction.
+
+use crate::core::db::{DbPool, DbTransaction};
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const ENABLED_SETTING: &str = "review_points_enabled";
+pub const BONUS_SETTING: &str = "review_points_bonus";
+
+pub const DEFAULT_BONUS: i32 = 100;
+const MAX_BONUS: i32 = 100_000;
+
+fn parse_bonus(value: &str) -> Option<i32> {
+    value
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|points| (1..=MAX_BONUS).contains(points))
+}
+
+pub async fn review_points_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, ENABLED_SETTING, false).await
+}
+
+pub async fn configured_bonus(pool: &DbPool) -> i32 {
+    SettingsRepository::get_value(pool, BONUS_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_bonus(&v))
+        .unwrap_or(DEFAULT_BONUS)
+}
+
+/// Reject invalid values for the review points settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        BONUS_SETTING if parse_bonus(value).is_none() => Err(format!(
+            "{} must be a whole number of points between 1 and {}",
+            key, MAX_BONUS
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Lock the membership so the balance recorded with the award is current.
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const ACTIVE_MEMBERSHIP_QUERY: &str = r#"
+    SELECT id, points_balance FROM loyalty_memberships
+    WHERE guest_id = $1 AND status = 'active'
+    ORDER BY id
+    LIMIT 1
+    FOR UPDATE
+"#;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const ACTIVE_MEMBERSHIP_QUERY: &str = r#"
+    SELECT id, points_balance FROM loyalty_memberships
+    WHERE guest_id = ?1 AND status = 'active'
+    ORDER BY id
+    LIMIT 1
+"#;
+
+/// Award the review bonus for `guest_id`'s review of `booking_id` in the
+/// transaction that saves the review. Returns the points awarded, or `None`
+/// when the feature is off, the review isn't verified, the guest has no
+/// active membership or the booking was already rewarded.
+pub async fn award_review_points(
+    pool: &DbPool,
+    tx: &mut DbTransaction<'_>,
+    guest_id: i64,
+    booking_id: i64,
+) -> Result<Option<i32>, ApiError> {
+    if !review_points_enabled(pool).await {
+        return Ok(None);
+    }
+
+    let verified: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM bookings WHERE id = $1 AND guest_id = $2 AND status = 'checked_out')",
+    )
+    .bind(booking_id)
+    .bind(guest_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if !verified {
+        return Ok(None);
+    }
+
+    let membership: Option<(i64, i32)> = sqlx::query_as(ACTIVE_MEMBERSHIP_QUERY)
+        .bind(guest_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let Some((membership_id, balance)) = membership else {
+        return Ok(None);
+    };
+
+    let bonus = configured_bonus(pool).await;
+
+    // Record the award first: when the booking was already rewarded this
+    // does nothing and the balance is left alone.
+    let recorded: Option<i64> = sqlx::query_scalar(
+        r#"
+        INSERT INTO points_transactions
+            (membership_id, transaction_type, points, balance_after, reference_type, reference_id, description)
+        VALUES ($1, 'earn', $2, $3, 'review', $4, 'Review bonus')
+        ON CONFLICT (reference_id) WHERE reference_type = 'review' DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(membership_id)
+    .bind(bonus)
+    .bind(balance + bonus)
+    .bind(booking_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if recorded.is_none() {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE loyalty_memberships
+        SET points_balance = points_balance + $1,
+            lifetime_points = lifetime_points + $1,
+            last_activity_at = CURRENT_TIMESTAMP
+        WHERE id = $2
+        "#,
+    )
+    .bind(bonus)
+    .bind(membership_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Some(bonus))
+}
@@ -0,0 +1,352 @@
+//! Room auto-assignment for type-level bookings
+//!
+//! A type-level booking (`bookings.room_assigned = false`) reserves a room type
+//! and holds a provisional `room_id`. `auto_assign_room` picks a concrete room
+//! of that type for the stay, preferring clean rooms and then rooms matching
+//! the guest's stored room preferences. Availability is re-checked under a row
+//! lock inside the assigning transaction, so two concurrent assignments can
+//! never land on the same room.
+
+use chrono::NaiveDate;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+/// System setting that enables auto-assignment at check-in and night audit.
+pub const AUTO_ASSIGN_SETTING: &str = "auto_assign_rooms";
+
+/// A room that could host the booking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomCandidate {
+    pub id: i64,
+    pub room_number: String,
+    pub status: String,
+    pub floor: Option<i32>,
+    pub is_smoking: bool,
+    pub is_accessible: bool,
+}
+
+/// `(id, room_number, status, floor, is_smoking, is_accessible)` as read from `rooms`.
+type CandidateRow = (
+    i64,
+    String,
+    Option<String>,
+    Option<i32>,
+    Option<bool>,
+    Option<bool>,
+);
+
+/// Room preferences recorded for a guest (`guest_preferences`, category `room`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomPreferences {
+    pub floor: Option<i32>,
+    pub smoking: Option<bool>,
+    pub accessible: Option<bool>,
+}
+
+impl RoomPreferences {
+    /// Build preferences from `(preference_key, preference_value)` pairs.
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let parse_bool = |v: &str| match v.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(true),
+            "false" | "no" | "0" => Some(false),
+            _ => None,
+        };
+
+        let mut prefs = Self::default();
+        for (key, value) in pairs {
+            match key {
+                "floor" => prefs.floor = value.trim().parse().ok(),
+                "smoking" => prefs.smoking = parse_bool(value),
+                "accessible" => prefs.accessible = parse_bool(value),
+                _ => {}
+            }
+        }
+        prefs
+    }
+
+    fn score(&self, room: &RoomCandidate) -> u8 {
+        let mut score = 0;
+        if self.floor.is_some() && self.floor == room.floor {
+            score += 1;
+        }
+        if self.smoking == Some(room.is_smoking) {
+            score += 1;
+        }
+        if self.accessible == Some(room.is_accessible) {
+            score += 1;
+        }
+        score
+    }
+}
+
+/// Lower is cleaner; rooms still being turned over sort last.
+fn cleanliness_rank(status: &str) -> u8 {
+    match status {
+        "available" => 0,
+        "reserved" => 1,
+        "cleaning" => 2,
+        "dirty" => 3,
+        _ => 4,
+    }
+}
+
+/// Order candidates best-first: clean rooms, then preference matches, then room number.
+pub fn rank_candidates(
+    mut candidates: Vec<RoomCandidate>,
+    prefs: &RoomPreferences,
+) -> Vec<RoomCandidate> {
+    candidates.sort_by(|a, b| {
+        cleanliness_rank(&a.status)
+            .cmp(&cleanliness_rank(&b.status))
+            .then_with(|| prefs.score(b).cmp(&prefs.score(a)))
+            .then_with(|| a.room_number.cmp(&b.room_number))
+    });
+    candidates
+}
+
+/// Whether auto-assignment is enabled in system settings.
+pub async fn auto_assign_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, AUTO_ASSIGN_SETTING, false).await
+}
+
+/// Whether the booking is still waiting for a concrete room.
+pub async fn needs_assignment(pool: &DbPool, booking_id: i64) -> Result<bool, ApiError> {
+    let assigned: bool = sqlx::query_scalar("SELECT room_assigned FROM bookings WHERE id = $1")
+        .bind(booking_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+    Ok(!assigned)
+}
+
+async fn load_room_preferences(pool: &DbPool, guest_id: i64) -> RoomPreferences {
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT preference_key, preference_value FROM guest_preferences
+             WHERE guest_id = $1 AND category = 'room'",
+        )
+        .bind(guest_id)
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default();
+        RoomPreferences::from_pairs(rows.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    }
+
+    // The SQLite schema has no guest_preferences table.
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    {
+        let _ = (pool, guest_id);
+        RoomPreferences::default()
+    }
+}
+
+/// Assign a concrete room to a type-level booking and return the room ID.
+///
+/// Other bookings' provisional holds do not block a room; only bookings with
+/// a concrete room do.
+///
+/// Fails with `Conflict` when no room of the booking's type is free for the
+/// whole stay.
+pub async fn auto_assign_room(pool: &DbPool, booking_id: i64) -> Result<i64, ApiError> {
+    // Preferences are read before the transaction so it only holds one connection.
+    let guest_id: i64 = sqlx::query_scalar("SELECT guest_id FROM bookings WHERE id = $1")
+        .bind(booking_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+    let prefs = load_room_preferences(pool, guest_id).await;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let booking_query = r#"
+        SELECT COALESCE(b.room_type_id, r.room_type_id), b.status,
+               b.check_in_date, b.check_out_date
+        FROM bookings b
+        INNER JOIN rooms r ON r.id = b.room_id
+        WHERE b.id = ?1
+    "#;
+
+    #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+    let booking_query = r#"
+        SELECT COALESCE(b.room_type_id, r.room_type_id), b.status,
+               b.check_in_date, b.check_out_date
+        FROM bookings b
+        INNER JOIN rooms r ON r.id = b.room_id
+        WHERE b.id = $1
+        FOR UPDATE OF b
+    "#;
+
+    let (room_type_id, status, check_in, check_out): (i64, String, NaiveDate, NaiveDate) =
+        sqlx::query_as(booking_query)
+            .bind(booking_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+
+    if status != "confirmed" && status != "pending" {
+        return Err(ApiError::BadRequest(format!(
+            "Cannot assign a room to a booking with status: {}",
+            status
+        )));
+    }
+
+    // Same-day bookings still occupy the room for their check-in night.
+    let stay_end = if check_out > check_in {
+        check_out
+    } else {
+        check_in.succ_opt().unwrap_or(check_in)
+    };
+
+    let candidate_rows: Vec<CandidateRow> =
+        sqlx::query_as(
+            r#"
+            SELECT r.id, r.room_number, r.status, r.floor, r.is_smoking, r.is_accessible
+            FROM rooms r
+            WHERE r.room_type_id = $1
+              AND r.is_active = true
+              AND COALESCE(r.status, 'available') NOT IN ('maintenance', 'out_of_order')
+              AND NOT EXISTS (
+                  SELECT 1 FROM bookings b
+                  WHERE b.room_id = r.id
+                    AND b.id <> $2
+                    AND b.room_assigned = true
+                    AND b.status IN ('reserved', 'confirmed', 'checked_in', 'auto_checked_in', 'pending')
+                    AND b.check_out_date > $3
+                    AND b.check_in_date < $4
+              )
+            "#,
+        )
+        .bind(room_type_id)
+        .bind(booking_id)
+        .bind(check_in)
+        .bind(stay_end)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let candidates = candidate_rows
+        .into_iter()
+        .map(
+            |(id, room_number, status, floor, is_smoking, is_accessible)| RoomCandidate {
+                id,
+                room_number,
+                status: status.unwrap_or_else(|| "available".to_string()),
+                floor,
+                is_smoking: is_smoking.unwrap_or(false),
+                is_accessible: is_accessible.unwrap_or(false),
+            },
+        )
+        .collect();
+
+    for candidate in rank_candidates(candidates, &prefs) {
+        // Lock the room and re-verify nothing claimed it since the candidate scan.
+        #[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+        sqlx::query("SELECT id FROM rooms WHERE id = $1 FOR UPDATE")
+            .bind(candidate.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        let still_free: bool = sqlx::query_scalar(
+            r#"
+            SELECT NOT EXISTS(
+                SELECT 1 FROM bookings
+                WHERE room_id = $1
+                  AND id <> $2
+                  AND room_assigned = true
+                  AND status IN ('reserved', 'confirmed', 'checked_in', 'auto_checked_in', 'pending')
+                  AND check_out_date > $3
+                  AND check_in_date < $4
+            )
+            "#,
+        )
+        .bind(candidate.id)
+        .bind(booking_id)
+        .bind(check_in)
+        .bind(stay_end)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        if !still_free {
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE bookings
+            SET room_id = $1,
+                room_type_id = COALESCE(room_type_id, $2),
+                room_assigned = true,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $3
+            "#,
+        )
+        .bind(candidate.id)
+        .bind(room_type_id)
+        .bind(booking_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        log::info!(
+            "Auto-assigned room {} ({}) to booking {}",
+            candidate.room_number,
+            candidate.id,
+            booking_id
+        );
+        return Ok(candidate.id);
+    }
+
+    Err(ApiError::Conflict(
+        "No room of the booked type is available for these dates".to_string(),
+    ))
+}
+
+/// Assign rooms to every unassigned arrival on `arrival_date`.
+///
+/// Used by the night audit to pre-assign the next day's type-level bookings.
+/// Bookings that cannot be placed are logged and left for the front desk.
+/// Returns the number of bookings assigned.
+pub async fn pre_assign_arrivals(pool: &DbPool, arrival_date: NaiveDate) -> Result<u32, ApiError> {
+    let booking_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM bookings
+        WHERE room_assigned = false
+          AND check_in_date = $1
+          AND status IN ('confirmed', 'pending')
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(arrival_date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut assigned = 0;
+    for booking_id in booking_ids {
+        match auto_assign_room(pool, booking_id).await {
+            Ok(_) => assigned += 1,
+            Err(e) => log::warn!(
+                "Could not pre-assign a room to booking {}: {}",
+                booking_id,
+                e
+            ),
+        }
+    }
+    Ok(assigned)
+}
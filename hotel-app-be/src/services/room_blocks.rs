@@ -0,0 +1,167 @@
+//! Room blocks
+//!
+//! A block keeps a room off sale for a date range for a reason other than
+//! maintenance or cleaning, such as owner use, staff housing or renovation
+//! staging. Blocks live in `room_blocks` rather than in `rooms.status`, which
+//! describes the room today, and cover the nights `start_date` up to, but
+//! not including, `end_date`, the way a stay does. A room cannot be blocked
+//! over a booking that holds it or over another block. Dated room searches
+//! leave blocked rooms out.
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::{RoomBlock, RoomBlockInput};
+use crate::repositories::booking::{CONFLICTING_BOOKINGS_QUERY, lock_room};
+
+/// Why a room can be blocked.
+pub const BLOCK_REASONS: &[&str] = &["owner_use", "staff_housing", "renovation", "other"];
+
+/// Longest block accepted, in nights.
+pub const MAX_BLOCK_NIGHTS: i64 = 366;
+
+const BLOCK_COLUMNS: &str =
+    "id, room_id, start_date, end_date, reason, notes, created_by, created_at";
+
+/// Check a block's dates and reason. Returns the reason as stored.
+pub fn check_block(input: &RoomBlockInput) -> Result<&'static str, ApiError> {
+    if input.end_date <= input.start_date {
+        return Err(ApiError::BadRequest(
+            "Block end date must be after its start date".to_string(),
+        ));
+    }
+    if (input.end_date - input.start_date).num_days() > MAX_BLOCK_NIGHTS {
+        return Err(ApiError::BadRequest(format!(
+            "A block cannot exceed {} nights",
+            MAX_BLOCK_NIGHTS
+        )));
+    }
+    let reason = input.reason.trim().to_ascii_lowercase();
+    BLOCK_REASONS
+        .iter()
+        .copied()
+        .find(|r| *r == reason)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Invalid block reason '{}'. Must be one of: {}",
+                input.reason.trim(),
+                BLOCK_REASONS.join(", ")
+            ))
+        })
+}
+
+async fn require_room(pool: &DbPool, room_id: i64) -> Result<(), ApiError> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::NotFound("Room not found".to_string()))
+}
+
+/// Block `room_id` for the input's dates.
+pub async fn create_block(
+    pool: &DbPool,
+    room_id: i64,
+    input: &RoomBlockInput,
+    user_id: i64,
+) -> Result<RoomBlock, ApiError> {
+    let reason = check_block(input)?;
+    require_room(pool, room_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    lock_room(&mut tx, room_id).await?;
+
+    let bookings: Vec<Option<String>> = sqlx::query_scalar(CONFLICTING_BOOKINGS_QUERY)
+        .bind(room_id)
+        .bind(input.start_date)
+        .bind(input.end_date)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if !bookings.is_empty() {
+        let numbers: Vec<String> = bookings.into_iter().flatten().collect();
+        return Err(ApiError::Conflict(format!(
+            "Room is booked for these dates: {}",
+            numbers.join(", ")
+        )));
+    }
+
+    let overlapping: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM room_blocks WHERE room_id = $1 AND start_date < $3 AND end_date > $2",
+    )
+    .bind(room_id)
+    .bind(input.start_date)
+    .bind(input.end_date)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    if overlapping.is_some() {
+        return Err(ApiError::Conflict(
+            "Room is already blocked for some of these dates".to_string(),
+        ));
+    }
+
+    let block = sqlx::query_as::<_, RoomBlock>(&format!(
+        r#"
+        INSERT INTO room_blocks (room_id, start_date, end_date, reason, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING {}
+        "#,
+        BLOCK_COLUMNS
+    ))
+    .bind(room_id)
+    .bind(input.start_date)
+    .bind(input.end_date)
+    .bind(reason)
+    .bind(
+        input
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty()),
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(block)
+}
+
+/// The room's blocks, by start date.
+pub async fn list_blocks(pool: &DbPool, room_id: i64) -> Result<Vec<RoomBlock>, ApiError> {
+    require_room(pool, room_id).await?;
+    sqlx::query_as::<_, RoomBlock>(&format!(
+        "SELECT {} FROM room_blocks WHERE room_id = $1 ORDER BY start_date, id",
+        BLOCK_COLUMNS
+    ))
+    .bind(room_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))
+}
+
+/// Remove a block from `room_id`. Returns the removed block.
+pub async fn delete_block(
+    pool: &DbPool,
+    room_id: i64,
+    block_id: i64,
+) -> Result<RoomBlock, ApiError> {
+    sqlx::query_as::<_, RoomBlock>(&format!(
+        "DELETE FROM room_blocks WHERE id = $1 AND room_id = $2 RETURNING {}",
+        BLOCK_COLUMNS
+    ))
+    .bind(block_id)
+    .bind(room_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?
+    .ok_or_else(|| ApiError::NotFound("Room block not found".to_string()))
+}
@@ -0,0 +1,226 @@
+//! Dynamic room status
+//!
+//! A room's displayed status is derived from its stored `rooms.status` and the
+//! booking that currently "owns" it (in-house first, then today's arrivals,
+//! then future reservations). Both the rooms list and the reservation timeline
+//! read status from `current_room_status` so the two views cannot drift.
+//! [`reconcile_room_statuses`] writes the derived status back to rooms whose
+//! stored one has drifted, e.g. a checked-out room still stored `occupied`.
+
+use std::collections::HashMap;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::models::RoomStatusCorrection;
+use crate::services::hotel_time;
+
+/// Every status a room can be in, stored or derived.
+pub const ROOM_STATUSES: &[&str] = &[
+    "available",
+    "occupied",
+    "reserved",
+    "cleaning",
+    "dirty",
+    "maintenance",
+    "out_of_order",
+];
+
+/// Parse a comma-separated status filter such as `dirty,cleaning`. A blank
+/// filter parses to no statuses, meaning rooms of any status.
+pub fn parse_status_filter(filter: &str) -> Result<Vec<&'static str>, ApiError> {
+    let mut statuses = Vec::new();
+    for part in filter.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let status = ROOM_STATUSES
+            .iter()
+            .copied()
+            .find(|s| s.eq_ignore_ascii_case(part))
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid room status '{}'. Must be one of: {}",
+                    part,
+                    ROOM_STATUSES.join(", ")
+                ))
+            })?;
+        if !statuses.contains(&status) {
+            statuses.push(status);
+        }
+    }
+    Ok(statuses)
+}
+
+/// Current status per active room - PostgreSQL version
+#[cfg(any(
+    all(feature = "postgres", not(feature = "sqlite")),
+    all(feature = "sqlite", feature = "postgres")
+))]
+const CURRENT_ROOM_STATUS_QUERY: &str = r#"
+WITH current_bookings AS (
+    SELECT DISTINCT ON (room_id)
+        room_id,
+        status as booking_status,
+        check_in_date
+    FROM bookings
+    WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
+      AND check_out_date >= $1
+    ORDER BY room_id,
+        CASE
+            WHEN status IN ('checked_in', 'auto_checked_in') THEN 1
+            WHEN status = 'confirmed' AND check_in_date <= $1 THEN 2
+            WHEN status = 'confirmed' THEN 3
+            WHEN status = 'pending' AND check_in_date <= $1 THEN 4
+            ELSE 5
+        END,
+        check_in_date
+)
+SELECT
+    r.id,
+    CASE
+        WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN 'occupied'
+        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty', 'cleaning') THEN r.status
+        WHEN cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= $1 THEN 'reserved'
+        ELSE 'available'
+    END as status
+FROM rooms r
+LEFT JOIN current_bookings cb ON cb.room_id = r.id
+WHERE r.is_active = true
+"#;
+
+/// Current status per active room - SQLite version
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const CURRENT_ROOM_STATUS_QUERY: &str = r#"
+WITH current_bookings AS (
+    SELECT
+        room_id,
+        status as booking_status,
+        check_in_date
+    FROM bookings b1
+    WHERE status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
+      AND check_out_date >= ?1
+      AND b1.id = (
+          SELECT b2.id FROM bookings b2
+          WHERE b2.room_id = b1.room_id
+            AND b2.status IN ('checked_in', 'auto_checked_in', 'confirmed', 'pending')
+            AND b2.check_out_date >= ?1
+          ORDER BY
+              CASE
+                  WHEN b2.status IN ('checked_in', 'auto_checked_in') THEN 1
+                  WHEN b2.status = 'confirmed' AND b2.check_in_date <= ?1 THEN 2
+                  WHEN b2.status = 'confirmed' THEN 3
+                  WHEN b2.status = 'pending' AND b2.check_in_date <= ?1 THEN 4
+                  ELSE 5
+              END,
+              b2.check_in_date
+          LIMIT 1
+      )
+)
+SELECT
+    r.id,
+    CASE
+        WHEN cb.booking_status IN ('checked_in', 'auto_checked_in') THEN 'occupied'
+        WHEN r.status IN ('maintenance', 'out_of_order', 'dirty', 'cleaning') THEN r.status
+        WHEN cb.booking_status IN ('confirmed', 'pending') AND cb.check_in_date <= ?1 THEN 'reserved'
+        ELSE 'available'
+    END as status
+FROM rooms r
+LEFT JOIN current_bookings cb ON cb.room_id = r.id
+WHERE r.is_active = 1
+"#;
+
+/// Compute today's dynamic status for every active room, keyed by room ID.
+/// Today is the hotel's date, not the database's.
+pub async fn current_room_status(pool: &DbPool) -> Result<HashMap<i64, String>, ApiError> {
+    let today = hotel_time::hotel_today(pool).await?;
+    let rows: Vec<(i64, String)> = sqlx::query_as(CURRENT_ROOM_STATUS_QUERY)
+        .bind(today)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Store today's dynamic status for every active room whose stored status
+/// differs, logging each correction to `room_history` as auto-generated.
+/// Only rooms in `property_id` are touched when one is given. Rooms being
+/// cleaned are left for housekeeping to release when their cleaning window
+/// ends.
+pub async fn reconcile_room_statuses(
+    pool: &DbPool,
+    property_id: Option<i64>,
+    changed_by: Option<i64>,
+) -> Result<Vec<RoomStatusCorrection>, ApiError> {
+    let current = current_room_status(pool).await?;
+    let rooms: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT id, room_number, status FROM rooms
+        WHERE is_active = true AND (CAST($1 AS BIGINT) IS NULL OR property_id = $1)
+        ORDER BY id
+        "#,
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut corrections = Vec::new();
+    for (room_id, room_number, stored) in rooms {
+        let Some(derived) = current.get(&room_id) else {
+            continue;
+        };
+        if stored.as_deref() == Some(derived.as_str()) || stored.as_deref() == Some("cleaning") {
+            continue;
+        }
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        // Guard on the status we read so a concurrent staff update wins.
+        let updated = sqlx::query(
+            r#"
+            UPDATE rooms SET status = $1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2 AND COALESCE(status, '') = $3
+            "#,
+        )
+        .bind(derived)
+        .bind(room_id)
+        .bind(stored.as_deref().unwrap_or_default())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        if updated.rows_affected() == 0 {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO room_history (room_id, from_status, to_status, changed_by, notes, is_auto_generated)
+            VALUES ($1, $2, $3, $4, 'Status reconciled with current bookings', true)
+            "#,
+        )
+        .bind(room_id)
+        .bind(stored.as_deref())
+        .bind(derived)
+        .bind(changed_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        log::info!(
+            "Room {} status reconciled from {} to {}",
+            room_number,
+            stored.as_deref().unwrap_or("none"),
+            derived
+        );
+        corrections.push(RoomStatusCorrection {
+            room_id,
+            room_number,
+            from_status: stored,
+            to_status: derived.clone(),
+        });
+    }
+
+    Ok(corrections)
+}
@@ -0,0 +1,165 @@
+//! Refresh-token lifetime policy
+//!
+//! Every login starts a session; each refresh rotates the token but keeps
+//! the session's start time. How long the new token lives depends on
+//! `refresh_token_expiry_mode`:
+//!
+//! - `sliding`: each refresh grants another `refresh_token_lifetime_days`,
+//!   but never past `refresh_token_max_lifetime_days` from the session start.
+//! - `fixed`: the session ends `refresh_token_lifetime_days` after it started,
+//!   however often it is refreshed.
+//!
+//! A deployment can pin the lifetime with `REFRESH_TOKEN_TTL_DAYS`, which
+//! then takes the place of `refresh_token_lifetime_days`.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::auth::AuthService;
+use crate::core::config;
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const LIFETIME_SETTING: &str = "refresh_token_lifetime_days";
+pub const MODE_SETTING: &str = "refresh_token_expiry_mode";
+pub const MAX_LIFETIME_SETTING: &str = "refresh_token_max_lifetime_days";
+
+const DEFAULT_LIFETIME_DAYS: i64 = 30;
+const DEFAULT_MAX_LIFETIME_DAYS: i64 = 90;
+/// Upper bound accepted for either lifetime setting.
+const MAX_CONFIGURABLE_DAYS: i64 = config::MAX_REFRESH_TOKEN_TTL_DAYS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshExpiryMode {
+    Sliding,
+    Fixed,
+}
+
+impl RefreshExpiryMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sliding" => Some(Self::Sliding),
+            "fixed" => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshTokenPolicy {
+    pub mode: RefreshExpiryMode,
+    pub lifetime_days: i64,
+    /// Absolute cap on a sliding session; ignored in fixed mode.
+    pub max_lifetime_days: i64,
+}
+
+impl Default for RefreshTokenPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RefreshExpiryMode::Sliding,
+            lifetime_days: DEFAULT_LIFETIME_DAYS,
+            max_lifetime_days: DEFAULT_MAX_LIFETIME_DAYS,
+        }
+    }
+}
+
+impl RefreshTokenPolicy {
+    /// Expiry for a token issued at `now` in a session started at
+    /// `session_started_at`.
+    pub fn expires_at(
+        &self,
+        session_started_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        match self.mode {
+            RefreshExpiryMode::Fixed => session_started_at + Duration::days(self.lifetime_days),
+            RefreshExpiryMode::Sliding => {
+                // A cap shorter than one window would make the first token
+                // outlive the session; treat it as one window.
+                let cap = self.max_lifetime_days.max(self.lifetime_days);
+                (now + Duration::days(self.lifetime_days))
+                    .min(session_started_at + Duration::days(cap))
+            }
+        }
+    }
+}
+
+fn parse_days(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|days| (1..=MAX_CONFIGURABLE_DAYS).contains(days))
+}
+
+async fn days_setting(pool: &DbPool, key: &str, default: i64) -> i64 {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_days(&v))
+        .unwrap_or(default)
+}
+
+/// Load the configured policy, falling back to defaults for missing or
+/// invalid values. `REFRESH_TOKEN_TTL_DAYS`, when set, wins over the
+/// lifetime setting.
+pub async fn configured_policy(pool: &DbPool) -> RefreshTokenPolicy {
+    let mode = SettingsRepository::get_value(pool, MODE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| RefreshExpiryMode::parse(&v))
+        .unwrap_or(RefreshExpiryMode::Sliding);
+
+    RefreshTokenPolicy {
+        mode,
+        lifetime_days: match config::get().refresh_token_ttl_days {
+            Some(days) => days,
+            None => days_setting(pool, LIFETIME_SETTING, DEFAULT_LIFETIME_DAYS).await,
+        },
+        max_lifetime_days: days_setting(pool, MAX_LIFETIME_SETTING, DEFAULT_MAX_LIFETIME_DAYS)
+            .await,
+    }
+}
+
+/// Reject invalid values for the session settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        MODE_SETTING if RefreshExpiryMode::parse(value).is_none() => {
+            Err("refresh_token_expiry_mode must be 'sliding' or 'fixed'".to_string())
+        }
+        LIFETIME_SETTING | MAX_LIFETIME_SETTING if parse_days(value).is_none() => Err(format!(
+            "{} must be a whole number of days between 1 and {}",
+            key, MAX_CONFIGURABLE_DAYS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Issue and store a refresh token for `user_id`. Pass the session start of
+/// the token being rotated, or `None` to start a new session.
+pub async fn issue_refresh_token(
+    pool: &DbPool,
+    user_id: i64,
+    session_started_at: Option<DateTime<Utc>>,
+) -> Result<String, ApiError> {
+    let now = Utc::now();
+    let session_started_at = session_started_at.unwrap_or(now);
+    let expires_at = configured_policy(pool)
+        .await
+        .expires_at(session_started_at, now);
+
+    let refresh_token = AuthService::generate_refresh_token();
+    AuthService::store_refresh_token(
+        pool,
+        user_id,
+        &refresh_token,
+        session_started_at,
+        expires_at,
+    )
+    .await
+    .map_err(|e| ApiError::Database(format!("Failed to store refresh token: {}", e)))?;
+
+    Ok(refresh_token)
+}
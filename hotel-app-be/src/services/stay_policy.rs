@@ -0,0 +1,230 @@
+//! Check-in and check-out times and late-checkout fees
+//!
+//! Stays are booked by date; `check_in_time` and `check_out_time` are the
+//! hotel's standard times on those dates. The auto check-in/checkout run
+//! checks arrivals in from the check-in time, and marks an in-house guest
+//! still there after the check-out time on their departure day as
+//! `late_checkout`. A late guest is charged `late_checkout_fee_per_hour` for
+//! every hour or part hour past the check-out time, never more than one
+//! night of the stay. The fee is kept as the booking's
+//! `late_checkout_penalty`, which the folio bills at checkout; a penalty
+//! staff have set higher is left alone.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, decimal_to_db};
+use crate::core::error::ApiError;
+use crate::models::StayPolicyResponse;
+use crate::models::row_mappers::get_decimal;
+use crate::repositories::settings::SettingsRepository;
+
+pub const CHECK_IN_TIME_SETTING: &str = "check_in_time";
+pub const CHECK_OUT_TIME_SETTING: &str = "check_out_time";
+pub const LATE_FEE_SETTING: &str = "late_checkout_fee_per_hour";
+
+const DEFAULT_CHECK_IN: (u32, u32) = (15, 0);
+const DEFAULT_CHECK_OUT: (u32, u32) = (11, 0);
+
+/// The hotel's standard times and late-checkout charge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StayPolicy {
+    pub check_in_time: NaiveTime,
+    pub check_out_time: NaiveTime,
+    /// Charged per hour or part hour past the check-out time; zero charges
+    /// nothing.
+    pub late_checkout_fee_per_hour: Decimal,
+}
+
+impl Default for StayPolicy {
+    fn default() -> Self {
+        let time = |(h, m)| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        Self {
+            check_in_time: time(DEFAULT_CHECK_IN),
+            check_out_time: time(DEFAULT_CHECK_OUT),
+            late_checkout_fee_per_hour: Decimal::ZERO,
+        }
+    }
+}
+
+impl StayPolicy {
+    /// Hours, counting a part hour as whole, that `now` is past the
+    /// check-out time on `check_out_date`; 0 before it.
+    pub fn hours_late(&self, check_out_date: NaiveDate, now: NaiveDateTime) -> i64 {
+        let late = now - check_out_date.and_time(self.check_out_time);
+        if late <= TimeDelta::zero() {
+            return 0;
+        }
+        (late.num_seconds() + 3599) / 3600
+    }
+
+    /// The fee for leaving `hours_late` hours late from a stay of
+    /// `check_in` to `check_out` worth `total_amount`: the hourly fee for
+    /// each hour, never more than the stay's average nightly rate.
+    pub fn late_checkout_fee(
+        &self,
+        hours_late: i64,
+        total_amount: Decimal,
+        check_in: NaiveDate,
+        check_out: NaiveDate,
+    ) -> Decimal {
+        let nights = (check_out - check_in).num_days().max(1);
+        let one_night = total_amount.max(Decimal::ZERO) / Decimal::from(nights);
+        (self.late_checkout_fee_per_hour * Decimal::from(hours_late.max(0)))
+            .min(one_night)
+            .round_dp(2)
+    }
+
+    /// The policy as shown to clients, with times as `HH:MM`.
+    pub fn response(&self) -> StayPolicyResponse {
+        StayPolicyResponse {
+            check_in_time: self.check_in_time.format("%H:%M").to_string(),
+            check_out_time: self.check_out_time.format("%H:%M").to_string(),
+            late_checkout_fee_per_hour: self.late_checkout_fee_per_hour,
+        }
+    }
+}
+
+/// A time of day written `HH:MM` or `HH:MM:SS`.
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .ok()
+}
+
+fn parse_fee(value: &str) -> Option<Decimal> {
+    value
+        .trim()
+        .parse::<Decimal>()
+        .ok()
+        .filter(|f| !f.is_sign_negative())
+}
+
+/// Reject invalid values for the stay policy settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        CHECK_IN_TIME_SETTING | CHECK_OUT_TIME_SETTING if parse_time(value).is_none() => {
+            Err(format!("{} must be a time of day such as 14:00", key))
+        }
+        LATE_FEE_SETTING if parse_fee(value).is_none() => {
+            Err(format!("{} must be an amount of 0 or more", key))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The policy from settings, falling back to the defaults for unset or
+/// invalid values.
+pub async fn configured(pool: &DbPool) -> StayPolicy {
+    let value = |key: &'static str| async move {
+        SettingsRepository::get_value(pool, key)
+            .await
+            .ok()
+            .flatten()
+    };
+    let defaults = StayPolicy::default();
+    StayPolicy {
+        check_in_time: value(CHECK_IN_TIME_SETTING)
+            .await
+            .and_then(|v| parse_time(&v))
+            .unwrap_or(defaults.check_in_time),
+        check_out_time: value(CHECK_OUT_TIME_SETTING)
+            .await
+            .and_then(|v| parse_time(&v))
+            .unwrap_or(defaults.check_out_time),
+        late_checkout_fee_per_hour: value(LATE_FEE_SETTING)
+            .await
+            .and_then(|v| parse_fee(&v))
+            .unwrap_or(defaults.late_checkout_fee_per_hour),
+    }
+}
+
+/// A booking found in house past its check-out time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LateCheckout {
+    pub booking_id: i64,
+    pub booking_number: Option<String>,
+    pub hours_late: i64,
+    /// The booking's late-checkout penalty after this run
+    pub fee: Decimal,
+    /// Whether this run marked the booking late, rather than it already
+    /// being so
+    pub newly_marked: bool,
+}
+
+/// Mark bookings due out today that are still in house past the check-out
+/// time at `now` as `late_checkout`, and raise their late-checkout penalty
+/// to the fee so far. Returns the bookings marked or charged more.
+pub async fn mark_late_checkouts(
+    pool: &DbPool,
+    policy: &StayPolicy,
+    now: NaiveDateTime,
+) -> Result<Vec<LateCheckout>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, booking_number, status, check_in_date, check_out_date, total_amount,
+               COALESCE(late_checkout_penalty, 0) AS late_checkout_penalty
+        FROM bookings
+        WHERE status IN ('checked_in', 'auto_checked_in', 'late_checkout')
+          AND check_out_date = $1
+        ORDER BY id
+        "#,
+    )
+    .bind(now.date())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut late = Vec::new();
+    for row in rows {
+        let check_out: NaiveDate = row.get("check_out_date");
+        let hours_late = policy.hours_late(check_out, now);
+        if hours_late == 0 {
+            continue;
+        }
+        let newly_marked = row.get::<String, _>("status") != "late_checkout";
+        let penalty = get_decimal(&row, "late_checkout_penalty");
+        let fee = policy
+            .late_checkout_fee(
+                hours_late,
+                get_decimal(&row, "total_amount"),
+                row.get("check_in_date"),
+                check_out,
+            )
+            .max(penalty);
+        if !newly_marked && fee == penalty {
+            continue;
+        }
+
+        let booking_id: i64 = row.get("id");
+        sqlx::query(
+            r#"
+            UPDATE bookings
+            SET status = 'late_checkout', late_checkout_penalty = $1,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2
+            "#,
+        )
+        .bind(decimal_to_db(fee))
+        .bind(booking_id)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        late.push(LateCheckout {
+            booking_id,
+            booking_number: row.try_get("booking_number").ok().flatten(),
+            hours_late,
+            fee,
+            newly_marked,
+        });
+    }
+
+    if !late.is_empty() {
+        log::info!("Late checkout: {} booking(s) marked or charged", late.len());
+    }
+    Ok(late)
+}
@@ -0,0 +1,95 @@
+//! Service tax calculation
+//!
+//! Single source of truth for service-tax maths. Room prices are configured
+//! tax-inclusive, so callers (booking creation, night audit) *extract* the
+//! tax from a gross amount; reports read the tax stored on each booking.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::core::db::DbPool;
+
+/// Service tax rate (percent) used when `system_settings.service_tax_rate`
+/// is missing, unparseable, or negative.
+pub const DEFAULT_SERVICE_TAX_RATE: Decimal = Decimal::from_parts(8, 0, 0, false, 0);
+
+/// Whether the base amount already includes service tax. Room prices always
+/// do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxMode {
+    /// Base is the gross (tax-inclusive) price; tax is extracted from it.
+    Inclusive,
+}
+
+/// Inputs that determine how a base amount is taxed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxContext {
+    /// Service tax rate as a percentage (e.g. `8` for 8%).
+    pub service_tax_rate: Decimal,
+    pub mode: TaxMode,
+}
+
+impl TaxContext {
+    pub fn inclusive(service_tax_rate: Decimal) -> Self {
+        Self {
+            service_tax_rate,
+            mode: TaxMode::Inclusive,
+        }
+    }
+
+    /// Build a context from the configured service tax rate.
+    pub async fn load(pool: &DbPool, mode: TaxMode) -> Self {
+        Self {
+            service_tax_rate: service_tax_rate(pool).await,
+            mode,
+        }
+    }
+}
+
+/// Result of taxing a single amount. `net + service_tax == gross` always holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TaxBreakdown {
+    pub net: Decimal,
+    pub service_tax: Decimal,
+    pub gross: Decimal,
+}
+
+/// Split `base` into net, service tax, and gross according to `ctx`.
+///
+/// Amounts are rounded to 2 decimal places. The net is rounded and the tax
+/// takes the remainder, so the gross is never altered.
+pub fn compute_taxes(base: Decimal, ctx: &TaxContext) -> TaxBreakdown {
+    let rate = ctx.service_tax_rate / Decimal::ONE_HUNDRED;
+
+    match ctx.mode {
+        TaxMode::Inclusive => {
+            let net = (base / (Decimal::ONE + rate)).round_dp(2);
+            TaxBreakdown {
+                net,
+                service_tax: base - net,
+                gross: base,
+            }
+        }
+    }
+}
+
+/// Read the configured service tax rate (percent), falling back to
+/// [`DEFAULT_SERVICE_TAX_RATE`].
+pub async fn service_tax_rate(pool: &DbPool) -> Decimal {
+    let configured = sqlx::query_scalar::<_, String>(
+        "SELECT value FROM system_settings WHERE key = 'service_tax_rate'",
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None);
+
+    parse_service_tax_rate(configured.as_deref())
+}
+
+/// Interpret a raw `service_tax_rate` setting value. 0% is a valid rate;
+/// negative or unparsable values fall back to the default.
+pub fn parse_service_tax_rate(raw: Option<&str>) -> Decimal {
+    raw.and_then(|v| v.trim().parse::<Decimal>().ok())
+        .filter(|rate| *rate >= Decimal::ZERO)
+        .unwrap_or(DEFAULT_SERVICE_TAX_RATE)
+}
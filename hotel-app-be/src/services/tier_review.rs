@@ -0,0 +1,364 @@
+//! Loyalty tier downgrade review
+//!
+//! Tiers are earned on lifetime activity but, when
+//! `loyalty_tier_demotion_enabled` is on, kept only on recent activity. A
+//! review compares each active member's qualifying points (earned points)
+//! and nights (checked-out stays) within a rolling window against their
+//! tier's `min_points` / `min_nights`; meeting either keeps the tier. Members
+//! who fall short drop to the highest lower tier they do meet, unless they
+//! reached their tier within `loyalty_tier_grace_days`. The night audit runs
+//! a review every `loyalty_tier_review_interval_months`.
+
+use chrono::{DateTime, Duration, Months, Utc};
+use sqlx::Row;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const DEMOTION_SETTING: &str = "loyalty_tier_demotion_enabled";
+pub const WINDOW_SETTING: &str = "loyalty_tier_review_window_months";
+pub const INTERVAL_SETTING: &str = "loyalty_tier_review_interval_months";
+pub const GRACE_SETTING: &str = "loyalty_tier_grace_days";
+
+const DEFAULT_WINDOW_MONTHS: u32 = 12;
+const DEFAULT_INTERVAL_MONTHS: u32 = 12;
+const DEFAULT_GRACE_DAYS: i64 = 90;
+const MAX_MONTHS: u32 = 120;
+const MAX_GRACE_DAYS: i64 = 3650;
+
+/// A tier's qualification thresholds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tier {
+    pub id: i64,
+    pub program_id: i64,
+    pub min_points: i64,
+    /// Nights that qualify on their own; `None` means points only.
+    pub min_nights: Option<i64>,
+}
+
+impl Tier {
+    pub fn is_met_by(&self, points: i64, nights: i64) -> bool {
+        points >= self.min_points || self.min_nights.is_some_and(|n| nights >= n)
+    }
+}
+
+/// A member's tier and activity within the review window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberActivity {
+    pub membership_id: i64,
+    pub program_id: i64,
+    pub tier_id: i64,
+    /// When the member reached the current tier (last change or enrolment).
+    pub tier_since: Option<DateTime<Utc>>,
+    pub qualifying_points: i64,
+    pub qualifying_nights: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TierOutcome {
+    Retained,
+    /// Short of the tier but still within the grace period.
+    InGrace,
+    Downgraded {
+        to_tier_id: i64,
+    },
+}
+
+/// Decide one member's outcome. `tiers` may hold several programs; only
+/// tiers of the member's program are considered, ranked by `min_points`.
+pub fn review_member(
+    tiers: &[Tier],
+    member: &MemberActivity,
+    grace: Duration,
+    now: DateTime<Utc>,
+) -> TierOutcome {
+    let mut ladder: Vec<&Tier> = tiers
+        .iter()
+        .filter(|t| t.program_id == member.program_id)
+        .collect();
+    ladder.sort_by_key(|t| (t.min_points, t.id));
+
+    let Some(current) = ladder.iter().position(|t| t.id == member.tier_id) else {
+        return TierOutcome::Retained;
+    };
+    let (points, nights) = (member.qualifying_points, member.qualifying_nights);
+    if ladder[current].is_met_by(points, nights) {
+        return TierOutcome::Retained;
+    }
+    if member.tier_since.is_some_and(|since| since + grace > now) {
+        return TierOutcome::InGrace;
+    }
+
+    // The lowest tier is the floor even if its own threshold isn't met.
+    let target = ladder[..current]
+        .iter()
+        .rposition(|t| t.is_met_by(points, nights))
+        .unwrap_or(0);
+    if target == current {
+        TierOutcome::Retained
+    } else {
+        TierOutcome::Downgraded {
+            to_tier_id: ladder[target].id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierChange {
+    pub membership_id: i64,
+    pub from_tier_id: i64,
+    pub to_tier_id: i64,
+    pub qualifying_points: i64,
+    pub qualifying_nights: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierReviewSummary {
+    pub review_id: i64,
+    pub reviewed: usize,
+    pub in_grace: usize,
+    pub downgraded: Vec<TierChange>,
+}
+
+fn parse_months(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|m| (1..=MAX_MONTHS).contains(m))
+}
+
+fn parse_grace_days(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|d| (0..=MAX_GRACE_DAYS).contains(d))
+}
+
+async fn setting<T>(pool: &DbPool, key: &str, parse: fn(&str) -> Option<T>, default: T) -> T {
+    SettingsRepository::get_value(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse(&v))
+        .unwrap_or(default)
+}
+
+pub async fn demotion_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, DEMOTION_SETTING, false).await
+}
+
+pub async fn configured_window_months(pool: &DbPool) -> u32 {
+    setting(pool, WINDOW_SETTING, parse_months, DEFAULT_WINDOW_MONTHS).await
+}
+
+/// Reject invalid values for the tier review settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        WINDOW_SETTING | INTERVAL_SETTING if parse_months(value).is_none() => Err(format!(
+            "{} must be a whole number of months between 1 and {}",
+            key, MAX_MONTHS
+        )),
+        GRACE_SETTING if parse_grace_days(value).is_none() => Err(format!(
+            "{} must be a whole number of days between 0 and {}",
+            key, MAX_GRACE_DAYS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether the last review is at least the configured interval before `now`.
+pub async fn review_due(pool: &DbPool, now: DateTime<Utc>) -> Result<bool, ApiError> {
+    let interval = setting(
+        pool,
+        INTERVAL_SETTING,
+        parse_months,
+        DEFAULT_INTERVAL_MONTHS,
+    )
+    .await;
+    let last: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT MAX(created_at) FROM loyalty_tier_reviews")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(match last {
+        Some(last) => last
+            .checked_add_months(Months::new(interval))
+            .is_none_or(|next| next <= now),
+        None => true,
+    })
+}
+
+async fn load_tiers(pool: &DbPool) -> Result<Vec<Tier>, ApiError> {
+    let rows =
+        sqlx::query("SELECT id, program_id, min_points, min_nights FROM loyalty_tiers ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Tier {
+            id: row.get("id"),
+            program_id: row.get("program_id"),
+            min_points: row.get::<i32, _>("min_points") as i64,
+            min_nights: row
+                .try_get::<Option<i32>, _>("min_nights")
+                .ok()
+                .flatten()
+                .map(i64::from),
+        })
+        .collect())
+}
+
+async fn load_members(
+    pool: &DbPool,
+    window_start: DateTime<Utc>,
+) -> Result<Vec<MemberActivity>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT m.id, m.program_id, m.tier_id,
+               COALESCE(
+                   (SELECT MAX(c.created_at) FROM loyalty_tier_changes c
+                     WHERE c.membership_id = m.id),
+                   m.enrolled_at, m.created_at
+               ) AS tier_since,
+               COALESCE(
+                   (SELECT SUM(t.points) FROM points_transactions t
+                     WHERE t.membership_id = m.id
+                       AND t.transaction_type = 'earn'
+                       AND t.created_at >= $1),
+                   0
+               )::BIGINT AS qualifying_points,
+               COALESCE(
+                   (SELECT SUM(b.check_out_date - b.check_in_date) FROM bookings b
+                     WHERE b.guest_id = m.guest_id
+                       AND b.status = 'checked_out'
+                       AND b.check_out_date >= $2),
+                   0
+               )::BIGINT AS qualifying_nights
+        FROM loyalty_memberships m
+        WHERE m.status = 'active' AND m.tier_id IS NOT NULL
+        ORDER BY m.id
+        "#,
+    )
+    .bind(window_start)
+    .bind(window_start.date_naive())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| MemberActivity {
+            membership_id: row.get("id"),
+            program_id: row.get("program_id"),
+            tier_id: row.get("tier_id"),
+            tier_since: row.try_get("tier_since").ok().flatten(),
+            qualifying_points: row.try_get("qualifying_points").unwrap_or(0),
+            qualifying_nights: row.try_get("qualifying_nights").unwrap_or(0),
+        })
+        .collect())
+}
+
+/// Review every active member against activity in the last `window_months`
+/// and apply the downgrades. The review and its tier changes are written in
+/// one transaction. Callers check [`demotion_enabled`] first.
+pub async fn review_tiers(
+    pool: &DbPool,
+    window_months: u32,
+) -> Result<TierReviewSummary, ApiError> {
+    let window_end = Utc::now();
+    let window_start = window_end
+        .checked_sub_months(Months::new(window_months))
+        .ok_or_else(|| ApiError::BadRequest("Invalid review window".to_string()))?;
+    let grace =
+        Duration::days(setting(pool, GRACE_SETTING, parse_grace_days, DEFAULT_GRACE_DAYS).await);
+
+    let tiers = load_tiers(pool).await?;
+    let members = load_members(pool, window_start).await?;
+
+    let mut in_grace = 0;
+    let mut downgraded = Vec::new();
+    for member in &members {
+        match review_member(&tiers, member, grace, window_end) {
+            TierOutcome::Retained => {}
+            TierOutcome::InGrace => in_grace += 1,
+            TierOutcome::Downgraded { to_tier_id } => downgraded.push(TierChange {
+                membership_id: member.membership_id,
+                from_tier_id: member.tier_id,
+                to_tier_id,
+                qualifying_points: member.qualifying_points,
+                qualifying_nights: member.qualifying_nights,
+            }),
+        }
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let review_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO loyalty_tier_reviews (window_start, window_end, reviewed_count, downgraded_count)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(window_start)
+    .bind(window_end)
+    .bind(members.len() as i32)
+    .bind(downgraded.len() as i32)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    for change in &downgraded {
+        sqlx::query(
+            r#"
+            UPDATE loyalty_memberships
+            SET tier_id = $1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2
+            "#,
+        )
+        .bind(change.to_tier_id)
+        .bind(change.membership_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO loyalty_tier_changes (
+                membership_id, review_id, from_tier_id, to_tier_id, reason,
+                qualifying_points, qualifying_nights
+            )
+            VALUES ($1, $2, $3, $4, 'review_downgrade', $5, $6)
+            "#,
+        )
+        .bind(change.membership_id)
+        .bind(review_id)
+        .bind(change.from_tier_id)
+        .bind(change.to_tier_id)
+        .bind(change.qualifying_points as i32)
+        .bind(change.qualifying_nights as i32)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(TierReviewSummary {
+        review_id,
+        reviewed: members.len(),
+        in_grace,
+        downgraded,
+    })
+}
@@ -0,0 +1,271 @@
+//! Orphaned upload cleanup
+//!
+//! Uploaded files are served from `uploads/` and referenced by path or URL
+//! from database rows: user avatars, eKYC documents and catalogue images.
+//! When a row is deleted, or an upload is never attached to one, the file
+//! stays on disk. [`cleanup_orphaned_uploads`] removes files no row refers
+//! to, leaving anything modified within `upload_cleanup_grace_hours` alone
+//! so an upload can still be attached by the request that follows it. The night audit runs it when
+//! `upload_cleanup_enabled` is on; admins can also trigger it.
+//!
+//! If any reference cannot be read, nothing is removed.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::core::db::DbPool;
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+
+pub const ENABLED_SETTING: &str = "upload_cleanup_enabled";
+pub const GRACE_SETTING: &str = "upload_cleanup_grace_hours";
+
+pub const DEFAULT_GRACE_HOURS: u64 = 24;
+/// Shortest grace accepted, so an upload is never removed before the form
+/// that sent it has been submitted.
+pub const MIN_GRACE_HOURS: u64 = 1;
+pub const MAX_GRACE_HOURS: u64 = 24 * 365;
+
+/// Directory uploads are written to and served from.
+pub const UPLOAD_ROOT: &str = "uploads";
+
+/// Columns that hold upload paths or URLs. Tables or columns missing from a
+/// schema are skipped.
+pub const REFERENCE_COLUMNS: &[(&str, &str)] = &[
+    ("users", "avatar_url"),
+    ("users", "avatar_thumbnail_url"),
+    ("ekyc_verifications", "id_front_image_path"),
+    ("ekyc_verifications", "id_back_image_path"),
+    ("ekyc_verifications", "selfie_image_path"),
+    ("ekyc_verifications", "proof_of_address_path"),
+    ("reward_catalog", "image_url"),
+    ("services", "image_url"),
+];
+
+/// A file found under the upload root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadFile {
+    /// Path relative to the upload root, `/`-separated.
+    pub path: String,
+    pub modified: SystemTime,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UploadCleanupSummary {
+    pub scanned: usize,
+    pub referenced: usize,
+    /// Unreferenced files still within the grace period.
+    pub recent: usize,
+    pub removed: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+fn parse_grace_hours(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|h| (MIN_GRACE_HOURS..=MAX_GRACE_HOURS).contains(h))
+}
+
+pub async fn cleanup_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, ENABLED_SETTING, false).await
+}
+
+pub async fn configured_grace(pool: &DbPool) -> Duration {
+    let hours = SettingsRepository::get_value(pool, GRACE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| parse_grace_hours(&v))
+        .unwrap_or(DEFAULT_GRACE_HOURS);
+    Duration::from_secs(hours * 3600)
+}
+
+/// Reject invalid values for the upload cleanup settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        ENABLED_SETTING if SettingsRepository::parse_bool(value).is_none() => {
+            Err(format!("{} must be true or false", key))
+        }
+        GRACE_SETTING if parse_grace_hours(value).is_none() => Err(format!(
+            "{} must be a whole number of hours between {} and {}",
+            key, MIN_GRACE_HOURS, MAX_GRACE_HOURS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The upload a stored reference points at, relative to the upload root.
+///
+/// Accepts `uploads/a/b.jpg`, `/uploads/a/b.jpg` and absolute URLs whose
+/// path starts with `/uploads/`, ignoring any query or fragment. Returns
+/// `None` for anything outside the upload root.
+pub fn upload_path(reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    let reference = reference.split(['?', '#']).next().unwrap_or_default();
+    let path = match reference.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map(|(_, path)| path)?,
+        None => reference,
+    };
+    let relative = path
+        .trim_start_matches('/')
+        .strip_prefix(UPLOAD_ROOT)?
+        .strip_prefix('/')?;
+
+    let segments: Vec<&str> = relative
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    if segments.is_empty() || segments.contains(&"..") {
+        return None;
+    }
+    Some(segments.join("/"))
+}
+
+/// Split `files` into those to remove: neither referenced nor modified
+/// within `grace` of `now`. Files dated in the future count as recent.
+pub fn find_orphans<'a>(
+    files: &'a [UploadFile],
+    references: &HashSet<String>,
+    grace: Duration,
+    now: SystemTime,
+    summary: &mut UploadCleanupSummary,
+) -> Vec<&'a UploadFile> {
+    summary.scanned = files.len();
+    let mut orphans = Vec::new();
+    for file in files {
+        if references.contains(&file.path) {
+            summary.referenced += 1;
+        } else if now
+            .duration_since(file.modified)
+            .is_ok_and(|age| age >= grace)
+        {
+            orphans.push(file);
+        } else {
+            summary.recent += 1;
+        }
+    }
+    orphans
+}
+
+/// Every file under `root`, with its path relative to `root`. A missing
+/// root has no files.
+pub fn list_uploads(root: &Path) -> std::io::Result<Vec<UploadFile>> {
+    let mut files = Vec::new();
+    if !root.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(root.join(&dir))? {
+            let entry = entry?;
+            let relative = dir.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(relative);
+            } else if file_type.is_file() {
+                let path = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(UploadFile {
+                    path,
+                    modified: entry.metadata()?.modified()?,
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+async fn column_exists(pool: &DbPool, table: &str, column: &str) -> Result<bool, ApiError> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info($1) WHERE name = $2")
+            .bind(table)
+            .bind(column)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(count > 0)
+}
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+async fn column_exists(pool: &DbPool, table: &str, column: &str) -> Result<bool, ApiError> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM information_schema.columns
+         WHERE table_schema = current_schema() AND table_name = $1 AND column_name = $2",
+    )
+    .bind(table)
+    .bind(column)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+    Ok(count > 0)
+}
+
+/// Uploads referenced by any row in [`REFERENCE_COLUMNS`], as
+/// [`upload_path`]s.
+pub async fn referenced_uploads(pool: &DbPool) -> Result<HashSet<String>, ApiError> {
+    let mut references = HashSet::new();
+    for (table, column) in REFERENCE_COLUMNS {
+        if !column_exists(pool, table, column).await? {
+            continue;
+        }
+        let values: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT {column} FROM {table} WHERE {column} IS NOT NULL AND {column} <> ''"
+        ))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+        references.extend(values.iter().filter_map(|v| upload_path(v)));
+    }
+    Ok(references)
+}
+
+/// Remove unreferenced files under `root` older than `grace`.
+pub async fn cleanup_uploads_in(
+    pool: &DbPool,
+    root: &Path,
+    grace: Duration,
+    now: SystemTime,
+) -> Result<UploadCleanupSummary, ApiError> {
+    // Read references before listing, so a file attached meanwhile is
+    // either listed as recent or not listed at all.
+    let references = referenced_uploads(pool).await?;
+    let files = list_uploads(root)
+        .map_err(|e| ApiError::Internal(format!("Failed to list uploads: {}", e)))?;
+
+    let mut summary = UploadCleanupSummary::default();
+    for file in find_orphans(&files, &references, grace, now, &mut summary) {
+        match std::fs::remove_file(root.join(&file.path)) {
+            Ok(()) => {
+                log::info!("Removed orphaned upload {}/{}", UPLOAD_ROOT, file.path);
+                summary.removed.push(file.path.clone());
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to remove orphaned upload {}/{}: {}",
+                    UPLOAD_ROOT,
+                    file.path,
+                    e
+                );
+                summary.failed.push(file.path.clone());
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Remove unreferenced files under `uploads/` older than the configured
+/// grace period.
+pub async fn cleanup_orphaned_uploads(pool: &DbPool) -> Result<UploadCleanupSummary, ApiError> {
+    let grace = configured_grace(pool).await;
+    cleanup_uploads_in(pool, Path::new(UPLOAD_ROOT), grace, SystemTime::now()).await
+}
@@ -0,0 +1,194 @@
+//! Automatic VIP flagging
+//!
+//! Guests whose stay history reaches `vip_lifetime_nights` nights or
+//! `vip_lifetime_spend` in booking totals are flagged `is_vip`, so arrivals
+//! lists can point them out to staff. Only checked-out stays count. A
+//! threshold of 0 turns that criterion off. Flags are only ever set here;
+//! removing one is a manual decision. The night audit runs the flagging when
+//! `vip_auto_flag_enabled` is on.
+
+use rust_decimal::Decimal;
+use sqlx::Row;
+
+use crate::core::db::{DbPool, parse_decimal};
+use crate::core::error::ApiError;
+use crate::repositories::settings::SettingsRepository;
+use crate::services::audit::AuditLog;
+
+pub const ENABLED_SETTING: &str = "vip_auto_flag_enabled";
+pub const NIGHTS_SETTING: &str = "vip_lifetime_nights";
+pub const SPEND_SETTING: &str = "vip_lifetime_spend";
+
+pub const DEFAULT_NIGHTS: i64 = 30;
+pub const DEFAULT_SPEND: i64 = 10_000;
+const MAX_NIGHTS: i64 = 3650;
+
+/// Lifetime thresholds; reaching either one makes a guest VIP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VipConfig {
+    /// `None` when nights don't qualify on their own
+    pub min_nights: Option<i64>,
+    /// `None` when spend doesn't qualify on its own
+    pub min_spend: Option<Decimal>,
+}
+
+impl VipConfig {
+    pub fn qualifies(&self, nights: i64, spend: Decimal) -> bool {
+        self.min_nights.is_some_and(|n| nights >= n) || self.min_spend.is_some_and(|s| spend >= s)
+    }
+}
+
+/// A guest's checked-out stay history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StayHistory {
+    pub guest_id: i64,
+    pub nights: i64,
+    pub spend: Decimal,
+}
+
+fn parse_nights(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|n| (0..=MAX_NIGHTS).contains(n))
+}
+
+fn parse_spend(value: &str) -> Option<Decimal> {
+    value
+        .trim()
+        .parse::<Decimal>()
+        .ok()
+        .filter(|s| !s.is_sign_negative())
+}
+
+pub async fn auto_flag_enabled(pool: &DbPool) -> bool {
+    SettingsRepository::get_bool(pool, ENABLED_SETTING, false).await
+}
+
+/// Thresholds from settings, falling back to the defaults for unset or
+/// invalid values.
+pub async fn configured(pool: &DbPool) -> VipConfig {
+    let value = |key: &'static str| async move {
+        SettingsRepository::get_value(pool, key)
+            .await
+            .ok()
+            .flatten()
+    };
+    let nights = value(NIGHTS_SETTING)
+        .await
+        .and_then(|v| parse_nights(&v))
+        .unwrap_or(DEFAULT_NIGHTS);
+    let spend = value(SPEND_SETTING)
+        .await
+        .and_then(|v| parse_spend(&v))
+        .unwrap_or(Decimal::from(DEFAULT_SPEND));
+    VipConfig {
+        min_nights: Some(nights).filter(|n| *n > 0),
+        min_spend: Some(spend).filter(|s| !s.is_zero()),
+    }
+}
+
+/// Reject invalid values for the VIP settings; other keys pass.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        NIGHTS_SETTING if parse_nights(value).is_none() => Err(format!(
+            "{} must be a whole number of nights between 0 and {}",
+            key, MAX_NIGHTS
+        )),
+        SPEND_SETTING if parse_spend(value).is_none() => {
+            Err(format!("{} must be an amount of 0 or more", key))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const STAY_NIGHTS: &str =
+    "CAST(julianday(b.check_out_date) - julianday(b.check_in_date) AS INTEGER)";
+/// SQLite guests are never soft-deleted.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+const ACTIVE_GUEST: &str = "1 = 1";
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const STAY_NIGHTS: &str = "(b.check_out_date - b.check_in_date)";
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+const ACTIVE_GUEST: &str = "g.deleted_at IS NULL";
+
+/// Stay history of guests who are not VIP yet.
+pub async fn unflagged_stay_history(pool: &DbPool) -> Result<Vec<StayHistory>, ApiError> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT g.id AS guest_id,
+               CAST(COALESCE(SUM({}), 0) AS BIGINT) AS nights,
+               CAST(COALESCE(SUM(b.total_amount), 0) AS TEXT) AS spend
+        FROM guests g
+        JOIN bookings b ON b.guest_id = g.id AND b.status = 'checked_out'
+        WHERE {} AND NOT COALESCE(g.is_vip, false)
+        GROUP BY g.id
+        ORDER BY g.id
+        "#,
+        STAY_NIGHTS, ACTIVE_GUEST
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| StayHistory {
+            guest_id: row.get("guest_id"),
+            nights: row.get("nights"),
+            spend: parse_decimal(&row.get::<String, _>("spend")),
+        })
+        .collect())
+}
+
+/// Flag every guest whose stay history meets `config`. Returns the ids of
+/// the newly flagged guests.
+pub async fn flag_vip_guests(pool: &DbPool, config: &VipConfig) -> Result<Vec<i64>, ApiError> {
+    let flagged: Vec<i64> = unflagged_stay_history(pool)
+        .await?
+        .into_iter()
+        .filter(|h| config.qualifies(h.nights, h.spend))
+        .map(|h| h.guest_id)
+        .collect();
+    if flagged.is_empty() {
+        return Ok(flagged);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    for guest_id in &flagged {
+        sqlx::query(
+            "UPDATE guests SET is_vip = true, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(guest_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let _ = AuditLog::log_event(
+        pool,
+        None,
+        "guests_flagged_vip",
+        "guest",
+        None,
+        Some(serde_json::json!({
+            "guest_ids": flagged,
+            "min_nights": config.min_nights,
+            "min_spend": config.min_spend,
+        })),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(flagged)
+}
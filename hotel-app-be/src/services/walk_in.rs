@@ -0,0 +1,44 @@
+//! Walk-in check-in
+//!
+//! A walk-in arrives without a booking and is checked straight in: the guest
+//! is found or created, the room is booked from today and the booking starts
+//! out `checked_in` with the room `occupied`, all in one transaction (see
+//! `handlers::bookings::walk_in_checkin_handler`). The checks here run before
+//! anything is written.
+
+use chrono::NaiveDate;
+
+use crate::core::error::ApiError;
+use crate::models::{GuestInput, WalkInInput};
+
+/// Who a walk-in is for.
+#[derive(Debug, Clone, Copy)]
+pub enum WalkInGuest<'a> {
+    Existing(i64),
+    New(&'a GuestInput),
+}
+
+/// The guest a walk-in names: exactly one of `guest_id` or a new `guest`.
+pub fn walk_in_guest(input: &WalkInInput) -> Result<WalkInGuest<'_>, ApiError> {
+    match (input.guest_id, input.guest.as_ref()) {
+        (Some(guest_id), None) => Ok(WalkInGuest::Existing(guest_id)),
+        (None, Some(guest)) => Ok(WalkInGuest::New(guest)),
+        (Some(_), Some(_)) => Err(ApiError::BadRequest(
+            "Give either guest_id or a new guest for a walk-in, not both".to_string(),
+        )),
+        (None, None) => Err(ApiError::BadRequest(
+            "A walk-in needs a guest_id or a new guest".to_string(),
+        )),
+    }
+}
+
+/// Check a walk-in leaving on `check_out` against `hotel_today`, when it
+/// arrives. Leaving the same day is a day-use stay.
+pub fn check_walk_in_stay(check_out: NaiveDate, hotel_today: NaiveDate) -> Result<(), ApiError> {
+    if check_out < hotel_today {
+        return Err(ApiError::BadRequest(
+            "A walk-in's check-out date cannot be before today".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -2,7 +2,10 @@
 //!
 //! Common utilities used across the application.
 
+pub mod pagination;
 #[allow(dead_code)]
 pub mod sanitization;
+pub mod sort;
+pub mod user_agent;
 #[allow(dead_code)]
 pub mod validation;
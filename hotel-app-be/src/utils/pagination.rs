@@ -0,0 +1,45 @@
+//! Validated paging for list endpoints
+//!
+//! Bounds are checked here; the order comes from the endpoint's
+//! [`SortSpec`].
+
+use crate::core::error::ApiError;
+use crate::models::ListParams;
+use crate::utils::sort::SortSpec;
+
+pub const DEFAULT_PER_PAGE: i64 = 50;
+pub const MAX_PER_PAGE: i64 = 200;
+
+/// A validated page: bounds plus the `ORDER BY` body built from the whitelist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListPage {
+    pub page: i64,
+    pub per_page: i64,
+    pub order_by: String,
+}
+
+impl ListPage {
+    pub fn resolve(params: &ListParams, spec: &SortSpec) -> Result<Self, ApiError> {
+        let page = params.page.unwrap_or(1);
+        if page < 1 {
+            return Err(ApiError::BadRequest("page must be at least 1".to_string()));
+        }
+        let per_page = params.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::BadRequest(format!(
+                "per_page must be between 1 and {}",
+                MAX_PER_PAGE
+            )));
+        }
+
+        Ok(Self {
+            page,
+            per_page,
+            order_by: spec.order_by(params.sort.as_deref(), params.order.as_deref())?,
+        })
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
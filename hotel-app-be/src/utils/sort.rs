@@ -0,0 +1,60 @@
+//! Whitelisted `ORDER BY` for list endpoints
+//!
+//! Each endpoint declares a [`SortSpec`]: the `sort` values it accepts and
+//! the SQL expression each maps to. Request input is only ever matched
+//! against that table, never copied into the query, and an unknown column or
+//! direction is rejected rather than silently replaced.
+
+use crate::core::error::ApiError;
+
+/// Sortable columns for one endpoint.
+pub struct SortSpec {
+    /// `(sort value, SQL expression)` pairs.
+    pub columns: &'static [(&'static str, &'static str)],
+    pub default_sort: &'static str,
+    pub default_descending: bool,
+    /// Unique expression appended to the order so pages are stable.
+    pub tiebreaker: &'static str,
+}
+
+impl SortSpec {
+    /// The `ORDER BY` body (without the keyword) for a requested column and
+    /// direction; either falls back to the endpoint default when absent.
+    pub fn order_by(&self, sort: Option<&str>, order: Option<&str>) -> Result<String, ApiError> {
+        let sort = sort
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(self.default_sort);
+        let column = self
+            .columns
+            .iter()
+            .find(|(name, _)| *name == sort)
+            .map(|(_, expr)| *expr)
+            .ok_or_else(|| {
+                let allowed: Vec<&str> = self.columns.iter().map(|(name, _)| *name).collect();
+                ApiError::BadRequest(format!(
+                    "Cannot sort by '{}'; expected one of: {}",
+                    sort,
+                    allowed.join(", ")
+                ))
+            })?;
+
+        let descending = match order.map(|o| o.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") => self.default_descending,
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Sort order must be 'asc' or 'desc', not '{}'",
+                    other
+                )));
+            }
+        };
+        let direction = if descending { "DESC" } else { "ASC" };
+
+        Ok(format!(
+            "{} {}, {} {}",
+            column, direction, self.tiebreaker, direction
+        ))
+    }
+}
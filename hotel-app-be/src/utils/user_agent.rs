@@ -0,0 +1,47 @@
+//! Readable device names from `User-Agent` headers
+//!
+//! Only the browser family and operating system are picked out, enough to
+//! tell a user's devices apart in a list. Versions are left out.
+
+/// Browser markers, most specific first: Edge, Opera and Samsung Internet
+/// also announce Chrome, and every Chromium browser announces Safari.
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg", "Edge"),
+    ("OPR/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("Firefox/", "Firefox"),
+    ("FxiOS/", "Firefox"),
+    ("Chrome/", "Chrome"),
+    ("CriOS/", "Chrome"),
+    ("Safari/", "Safari"),
+];
+
+/// Operating system markers, most specific first: Android announces Linux
+/// and iOS announces "like Mac OS X".
+const SYSTEMS: &[(&str, &str)] = &[
+    ("iPhone", "iOS"),
+    ("iPad", "iPadOS"),
+    ("Android", "Android"),
+    ("CrOS", "ChromeOS"),
+    ("Windows", "Windows"),
+    ("Macintosh", "macOS"),
+    ("Linux", "Linux"),
+];
+
+fn find(user_agent: &str, markers: &[(&str, &'static str)]) -> Option<&'static str> {
+    markers
+        .iter()
+        .find(|(marker, _)| user_agent.contains(marker))
+        .map(|(_, name)| *name)
+}
+
+/// A device name such as "Chrome on macOS", or `None` when neither the
+/// browser nor the operating system is recognised.
+pub fn device_name(user_agent: &str) -> Option<String> {
+    match (find(user_agent, BROWSERS), find(user_agent, SYSTEMS)) {
+        (Some(browser), Some(system)) => Some(format!("{} on {}", browser, system)),
+        (Some(browser), None) => Some(browser.to_string()),
+        (None, Some(system)) => Some(system.to_string()),
+        (None, None) => None,
+    }
+}
@@ -0,0 +1,149 @@
+//! Tests for the analytics report handlers.
+//!
+//! The reports are run against the real SQLite schema so a query naming a
+//! column the bookings table does not have fails here rather than at
+//! runtime. SQLite-backed tests are gated so the default PostgreSQL build is
+//! not forced to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Query, State};
+    use axum::http::{HeaderMap, HeaderValue};
+    use hotel_app_be::AuthService;
+    use hotel_app_be::handlers::analytics::{
+        generate_report_handler, get_booking_analytics_handler, get_occupancy_report_handler,
+    };
+    use hotel_app_be::models::ReportQuery;
+    use rust_decimal::Decimal;
+
+    const ANALYST: i64 = 9950;
+
+    /// An analyst, two rooms and three stays: one in house today, one
+    /// checked out and one voided.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9950, 'u-9950', 'analyst', 'analyst@example.com', 1)",
+            "INSERT INTO roles (id, name, display_name) VALUES (9950, 'analyst', 'Analyst')",
+            "INSERT INTO permissions (id, name, resource, action)
+             VALUES (9950, 'analytics:read', 'analytics', 'read')",
+            "INSERT INTO role_permissions (role_id, permission_id) VALUES (9950, 9950)",
+            "INSERT INTO user_roles (user_id, role_id) VALUES (9950, 9950)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (995, 'Report Deluxe', 'RDX', 200.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9951, 'R951', 995, 'occupied', 1), (9952, 'R952', 995, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9950, 'Mei', 'Tan', 'Mei Tan')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9951, 'BK-RPT-1', 9950, 9951, date('now', '-1 day'), date('now', '+1 day'), 200.0, 400.0, 'checked_in'),
+             (9952, 'BK-RPT-2', 9950, 9952, date('now', '-9 day'), date('now', '-7 day'), 200.0, 400.0, 'checked_out'),
+             (9953, 'BK-RPT-3', 9950, 9952, date('now', '-1 day'), date('now', '+1 day'), 200.0, 400.0, 'voided')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    fn analyst_headers() -> HeaderMap {
+        common::install_config(&[]);
+        let token = AuthService::generate_jwt(ANALYST, "analyst".to_string(), vec![]).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn occupancy_report_reads_the_bookings_schema() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let report = get_occupancy_report_handler(State(pool.clone()), analyst_headers())
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(report["occupiedRooms"], 1);
+        assert_eq!(report["revenue"], 400.0);
+    }
+
+    #[tokio::test]
+    async fn booking_analytics_reads_the_bookings_schema() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let report = get_booking_analytics_handler(State(pool.clone()), analyst_headers())
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(report["totalBookings"], 2);
+        assert_eq!(report["totalRevenue"], 800.0);
+        assert_eq!(report["averageBookingValue"], 400.0);
+        assert_eq!(report["bookingsByRoomType"]["Report Deluxe"], 2);
+        assert_eq!(report["monthlyTrends"].as_array().map(Vec::len), Some(12));
+    }
+
+    /// Run the `report_type` report over the last 30 days.
+    async fn report(pool: &sqlx::SqlitePool, report_type: &str) -> serde_json::Value {
+        let today = chrono::Utc::now().date_naive();
+        generate_report_handler(
+            State(pool.clone()),
+            Query(ReportQuery {
+                report_type: report_type.to_string(),
+                start_date: (today - chrono::Duration::days(30)).to_string(),
+                end_date: today.to_string(),
+                shift: None,
+                drawer: None,
+                company_name: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    fn amount(value: &serde_json::Value) -> Decimal {
+        value.as_str().unwrap().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn accounting_reports_use_the_tax_stored_on_bookings() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        // 400 at 8% service tax, included in the price
+        sqlx::query("UPDATE bookings SET tax_amount = 29.63")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // The in-house and checked-out stays: 800 including 59.26 tax.
+        let balance = report(&pool, "balance_sheet").await;
+        let accounts = balance["accounts"].as_array().unwrap();
+        assert_eq!(accounts[0]["name"], "Guest Ledger");
+        assert_eq!(amount(&accounts[0]["debit"]), Decimal::new(800, 0));
+        assert_eq!(accounts[2]["name"], "Room Revenue");
+        assert_eq!(amount(&accounts[2]["credit"]), Decimal::new(74074, 2));
+        assert_eq!(accounts[3]["name"], "Sales Tax Payable");
+        assert_eq!(amount(&accounts[3]["credit"]), Decimal::new(5926, 2));
+
+        // One service-tax line per booking, at the stored amount.
+        let journal = report(&pool, "journal_by_type").await;
+        let taxes: Vec<Decimal> = journal["transactions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|t| t["description"] == "[Service Tax]")
+            .map(|t| amount(&t["credit"]))
+            .collect();
+        assert_eq!(taxes, vec![Decimal::new(2963, 2); 3]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Tests for per-user API keys sent as `X-Api-Key`.
+//!
+//! Database tests are gated on SQLite like the other integration tests.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, HeaderValue};
+    use chrono::{Duration, Utc};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::api_keys::{
+        API_KEY_HEADER, create_api_key, list_api_keys, revoke_api_key,
+    };
+    use hotel_app_be::handlers::rooms::get_all_room_types_handler;
+    use hotel_app_be::handlers::settings::get_system_settings_handler;
+
+    /// An admin (role 1 holds every permission), so any rejection comes from
+    /// the key's scopes rather than RBAC.
+    async fn seed_admin(pool: &sqlx::SqlitePool) -> i64 {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9001, 'api-key-user', 'integration', 'integration@example.com', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO user_roles (user_id, role_id) VALUES (9001, 1)")
+            .execute(pool)
+            .await
+            .unwrap();
+        9001
+    }
+
+    fn key_headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_str(key).unwrap());
+        headers
+    }
+
+    fn scopes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn scoped_key_reaches_permitted_endpoint() {
+        let pool = common::setup_test_db().await;
+        let user_id = seed_admin(&pool).await;
+        let (key, _) = create_api_key(&pool, user_id, "PMS sync", &scopes(&["rooms:read"]), None)
+            .await
+            .unwrap();
+
+        let result = get_all_room_types_handler(State(pool.clone()), key_headers(&key)).await;
+        assert!(result.is_ok(), "{:?}", result.err());
+
+        let listed = list_api_keys(&pool, user_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_used_at.is_some());
+        assert!(key.starts_with(&listed[0].key_prefix));
+    }
+
+    #[tokio::test]
+    async fn scoped_key_is_rejected_out_of_scope() {
+        let pool = common::setup_test_db().await;
+        let user_id = seed_admin(&pool).await;
+        let (key, _) = create_api_key(&pool, user_id, "PMS sync", &scopes(&["rooms:read"]), None)
+            .await
+            .unwrap();
+
+        let result = get_system_settings_handler(State(pool.clone()), key_headers(&key)).await;
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn revoked_expired_and_unknown_keys_are_unauthorized() {
+        let pool = common::setup_test_db().await;
+        let user_id = seed_admin(&pool).await;
+        let read_rooms = scopes(&["rooms:read"]);
+
+        let (revoked, record) = create_api_key(&pool, user_id, "old", &read_rooms, None)
+            .await
+            .unwrap();
+        revoke_api_key(&pool, user_id, record.id).await.unwrap();
+
+        let (expired, _) = create_api_key(
+            &pool,
+            user_id,
+            "expired",
+            &read_rooms,
+            Some(Utc::now() - Duration::minutes(1)),
+        )
+        .await
+        .unwrap();
+
+        for key in [revoked.as_str(), expired.as_str(), "hak_not-a-real-key"] {
+            let result = get_all_room_types_handler(State(pool.clone()), key_headers(key)).await;
+            assert!(
+                matches!(result, Err(ApiError::Unauthorized(_))),
+                "{key} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Tests for loading and validating the startup configuration.
+
+mod common;
+
+use std::collections::HashMap;
+
+use hotel_app_be::core::config::{
+    AppConfig, ConfigError, DEFAULT_ACCESS_TOKEN_TTL_MINUTES, DEFAULT_BACKEND_PORT,
+    DEFAULT_MAX_UPLOAD_MB, DEFAULT_PASSKEY_ORIGIN, DEFAULT_PASSKEY_RP_ID,
+};
+use hotel_app_be::core::security_headers::DeploymentMode;
+
+fn load(vars: &[(&str, &str)]) -> Result<AppConfig, ConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    AppConfig::from_lookup(|key| vars.get(key).cloned())
+}
+
+fn with(extra: &[(&'static str, &'static str)]) -> Vec<(&'static str, &'static str)> {
+    common::REQUIRED_ENV.iter().chain(extra).copied().collect()
+}
+
+#[test]
+fn missing_jwt_secret_fails_with_a_clear_message() {
+    let err = load(&[("DATABASE_URL", "postgres://hotel@localhost/hotel")]).unwrap_err();
+    assert_eq!(err, ConfigError::Missing("JWT_SECRET"));
+    assert_eq!(err.to_string(), "JWT_SECRET must be set");
+
+    // A blank value is as good as unset.
+    let blank = load(&[
+        ("JWT_SECRET", "  "),
+        ("DATABASE_URL", "postgres://hotel@localhost/hotel"),
+    ]);
+    assert_eq!(blank.unwrap_err(), ConfigError::Missing("JWT_SECRET"));
+}
+
+#[cfg(any(feature = "postgres", not(feature = "sqlite")))]
+#[test]
+fn postgres_builds_require_a_database_url() {
+    let err = load(&[("JWT_SECRET", "test-secret")]).unwrap_err();
+    assert_eq!(err.to_string(), "DATABASE_URL must be set");
+}
+
+#[test]
+fn defaults_apply_when_optional_vars_are_unset() {
+    let config = load(common::REQUIRED_ENV).unwrap();
+
+    assert_eq!(config.backend_port, DEFAULT_BACKEND_PORT);
+    assert_eq!(config.passkey_rp_id, DEFAULT_PASSKEY_RP_ID);
+    assert_eq!(config.passkey_origin, DEFAULT_PASSKEY_ORIGIN);
+    assert_eq!(config.deployment_mode, DeploymentMode::Web);
+    assert_eq!(
+        config.allowed_origins,
+        DeploymentMode::Web.default_allowed_origins()
+    );
+    assert!(!config.desktop_mode);
+    assert!(!config.skip_email_verification);
+    assert!(config.max_concurrent_requests > 0);
+    assert_eq!(config.log_dir, None);
+    assert_eq!(
+        config.access_token_ttl_minutes,
+        DEFAULT_ACCESS_TOKEN_TTL_MINUTES
+    );
+    assert_eq!(config.refresh_token_ttl_days, None);
+    assert_eq!(config.max_upload_mb, DEFAULT_MAX_UPLOAD_MB);
+}
+
+#[test]
+fn desktop_mode_picks_the_desktop_profile() {
+    let config = load(&with(&[("HOTEL_DESKTOP_MODE", "1")])).unwrap();
+    assert!(config.desktop_mode);
+    assert_eq!(config.deployment_mode, DeploymentMode::Desktop);
+    assert_eq!(config.allowed_origins, "*");
+
+    // An explicit DEPLOYMENT_MODE wins.
+    let web = load(&with(&[
+        ("HOTEL_DESKTOP_MODE", "1"),
+        ("DEPLOYMENT_MODE", "web"),
+    ]))
+    .unwrap();
+    assert_eq!(web.deployment_mode, DeploymentMode::Web);
+}
+
+#[test]
+fn malformed_values_are_rejected() {
+    let port = load(&with(&[("BACKEND_PORT", "eighty")])).unwrap_err();
+    assert_eq!(
+        port.to_string(),
+        "BACKEND_PORT has invalid value \"eighty\": expected a port number"
+    );
+
+    assert!(matches!(
+        load(&with(&[("MAX_CONCURRENT_REQUESTS", "0")])),
+        Err(ConfigError::Invalid {
+            var: "MAX_CONCURRENT_REQUESTS",
+            ..
+        })
+    ));
+    assert!(matches!(
+        load(&with(&[("MAX_UPLOAD_MB", "101")])),
+        Err(ConfigError::Invalid {
+            var: "MAX_UPLOAD_MB",
+            ..
+        })
+    ));
+    assert!(matches!(
+        load(&with(&[("DEPLOYMENT_MODE", "kiosk")])),
+        Err(ConfigError::Invalid {
+            var: "DEPLOYMENT_MODE",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn explicit_values_override_defaults() {
+    let config = load(&with(&[
+        ("BACKEND_PORT", "8080"),
+        ("PASSKEY_RP_ID", "hotel.example.com"),
+        ("SKIP_EMAIL_VERIFICATION", "TRUE"),
+        ("ALLOWED_ORIGINS", "https://hotel.example.com"),
+    ]))
+    .unwrap();
+
+    assert_eq!(config.backend_port, 8080);
+    assert_eq!(config.passkey_rp_id, "hotel.example.com");
+    // The origin follows the relying party unless set.
+    assert_eq!(config.passkey_origin, "https://hotel.example.com");
+    assert!(config.skip_email_verification);
+    assert_eq!(config.allowed_origins, "https://hotel.example.com");
+}
+
+#[test]
+fn passkey_origin_can_be_set() {
+    let config = load(&with(&[
+        ("PASSKEY_RP_ID", "hotel.example.com"),
+        ("PASSKEY_ORIGIN", " https://app.hotel.example.com:8443/ "),
+    ]))
+    .unwrap();
+    assert_eq!(config.passkey_origin, "https://app.hotel.example.com:8443");
+}
+
+#[test]
+fn token_lifetimes_are_configurable_within_bounds() {
+    let config = load(&with(&[
+        ("ACCESS_TOKEN_TTL_MINUTES", "15"),
+        ("REFRESH_TOKEN_TTL_DAYS", "1"),
+    ]))
+    .unwrap();
+    assert_eq!(config.access_token_ttl_minutes, 15);
+    assert_eq!(config.refresh_token_ttl_days, Some(1));
+
+    for (var, value) in [
+        ("ACCESS_TOKEN_TTL_MINUTES", "0"),
+        ("ACCESS_TOKEN_TTL_MINUTES", "43201"),
+        ("ACCESS_TOKEN_TTL_MINUTES", "1h"),
+        ("REFRESH_TOKEN_TTL_DAYS", "0"),
+        ("REFRESH_TOKEN_TTL_DAYS", "3651"),
+    ] {
+        let err = load(&with(&[(var, value)])).unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::Invalid { var: v, .. } if *v == var),
+            "{}={} gave {}",
+            var,
+            value,
+            err
+        );
+    }
+}
+
+#[test]
+fn web_deployments_need_a_valid_origin() {
+    let err = load(&with(&[("ALLOWED_ORIGINS", "hotel.example.com/")])).unwrap_err();
+    assert!(matches!(
+        err,
+        ConfigError::Invalid {
+            var: "ALLOWED_ORIGINS",
+            ..
+        }
+    ));
+
+    // One good origin is enough; the bad one is only warned about.
+    assert!(
+        load(&with(&[(
+            "ALLOWED_ORIGINS",
+            "hotel.example.com, https://hotel.example.com"
+        )]))
+        .is_ok()
+    );
+    // Desktop builds may run without cross-origin access.
+    assert!(
+        load(&with(&[
+            ("DEPLOYMENT_MODE", "desktop"),
+            ("ALLOWED_ORIGINS", "hotel.example.com")
+        ]))
+        .is_ok()
+    );
+}
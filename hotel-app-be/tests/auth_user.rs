@@ -0,0 +1,56 @@
+//! Tests for the `AuthUser` request extractor.
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    routing::get,
+};
+use hotel_app_be::core::auth::AuthService;
+use hotel_app_be::core::middleware::AuthUser;
+use tower::ServiceExt;
+
+async fn call(authorization: Option<&str>) -> (StatusCode, String) {
+    common::install_config(&[]);
+    let app = Router::new().route(
+        "/me",
+        get(|user: AuthUser| async move {
+            format!("{} admin={}", user.user_id, user.has_role("admin"))
+        }),
+    );
+
+    let mut request = Request::get("/me");
+    if let Some(value) = authorization {
+        request = request.header(header::AUTHORIZATION, value);
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn valid_token_yields_the_user() {
+    common::install_config(&[]);
+    let token =
+        AuthService::generate_jwt(7, "frontdesk".to_string(), vec!["admin".to_string()]).unwrap();
+
+    let (status, body) = call(Some(&format!("Bearer {}", token))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "7 admin=true");
+}
+
+#[tokio::test]
+async fn missing_or_invalid_token_is_unauthorized() {
+    for authorization in [None, Some("Basic dXNlcjpwYXNz"), Some("Bearer not-a-jwt")] {
+        let (status, _) = call(authorization).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{:?}", authorization);
+    }
+}
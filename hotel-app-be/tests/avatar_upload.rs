@@ -0,0 +1,149 @@
+//! Tests for avatar uploads.
+//!
+//! Checking and thumbnailing an upload is pure and runs under any feature.
+//! Storing avatars is SQLite-backed and gated accordingly.
+
+mod common;
+
+use std::io::Cursor;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::avatars::{
+    MAX_AVATAR_BYTES, THUMBNAIL_SIZE, avatar_format, make_thumbnail, stored_avatar_path,
+};
+use image::{ImageFormat, RgbImage};
+
+/// A `width` by `height` image encoded as `format`.
+fn encoded(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let mut data = Vec::new();
+    RgbImage::from_pixel(width, height, image::Rgb([200, 80, 40]))
+        .write_to(&mut Cursor::new(&mut data), format)
+        .unwrap();
+    data
+}
+
+#[test]
+fn avatars_must_be_images_of_the_declared_type() {
+    let png = encoded(4, 4, ImageFormat::Png);
+    let jpeg = encoded(4, 4, ImageFormat::Jpeg);
+
+    assert_eq!(
+        avatar_format("image/png", &png).unwrap(),
+        (ImageFormat::Png, "png")
+    );
+    assert_eq!(
+        avatar_format("image/JPEG", &jpeg).unwrap(),
+        (ImageFormat::Jpeg, "jpg")
+    );
+
+    for (content_type, data) in [
+        ("image/jpeg", &png[..]),
+        ("image/gif", &png[..]),
+        ("application/pdf", &png[..]),
+        ("image/png", b"not an image"),
+        ("image/png", b""),
+    ] {
+        assert!(
+            matches!(
+                avatar_format(content_type, data),
+                Err(ApiError::BadRequest(_))
+            ),
+            "{} accepted",
+            content_type
+        );
+    }
+}
+
+#[test]
+fn oversized_avatars_are_rejected() {
+    let mut data = encoded(4, 4, ImageFormat::Png);
+    data.resize(MAX_AVATAR_BYTES + 1, 0);
+
+    assert!(matches!(
+        avatar_format("image/png", &data),
+        Err(ApiError::BadRequest(msg)) if msg.contains("5 MB")
+    ));
+}
+
+#[test]
+fn thumbnails_are_square_pngs() {
+    let thumbnail = make_thumbnail(&encoded(300, 200, ImageFormat::Png), ImageFormat::Png).unwrap();
+
+    let image = image::load_from_memory_with_format(&thumbnail, ImageFormat::Png).unwrap();
+    assert_eq!(
+        (image.width(), image.height()),
+        (THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    );
+}
+
+#[test]
+fn only_the_users_own_stored_avatars_are_replaced() {
+    assert_eq!(
+        stored_avatar_path("/uploads/avatars/7_abc.png", 7).as_deref(),
+        Some("avatars/7_abc.png")
+    );
+    for reference in [
+        "/uploads/avatars/8_abc.png",
+        "/uploads/avatars/7/abc.png",
+        "/uploads/ekyc/7_front.jpg",
+        "https://cdn.example.com/avatars/7_abc.png",
+    ] {
+        assert_eq!(stored_avatar_path(reference, 7), None, "{}", reference);
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, encoded};
+    use hotel_app_be::services::avatars::save_avatar_in;
+    use image::ImageFormat;
+
+    #[tokio::test]
+    async fn replacing_an_avatar_removes_the_old_files() {
+        let pool = common::setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9950, 'u-9950', 'avatar_user', 'avatar@example.com', 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let root = std::env::temp_dir().join(format!("hotel-avatars-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let first = save_avatar_in(
+            &pool,
+            &root,
+            9950,
+            "image/png",
+            &encoded(64, 48, ImageFormat::Png),
+        )
+        .await
+        .unwrap();
+        assert!(first.avatar_url.starts_with("/uploads/avatars/9950_"));
+        let first_files = [&first.avatar_url, &first.thumbnail_url]
+            .map(|url| root.join(url.trim_start_matches("/uploads/")));
+        assert!(first_files.iter().all(|f| f.is_file()));
+
+        let second = save_avatar_in(
+            &pool,
+            &root,
+            9950,
+            "image/jpeg",
+            &encoded(64, 48, ImageFormat::Jpeg),
+        )
+        .await
+        .unwrap();
+        assert!(second.avatar_url.ends_with(".jpg"));
+        assert!(first_files.iter().all(|f| !f.exists()));
+
+        let stored: (String, String) =
+            sqlx::query_as("SELECT avatar_url, avatar_thumbnail_url FROM users WHERE id = 9950")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, (second.avatar_url, second.thumbnail_url));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+//! Tests for archiving long-past bookings.
+//!
+//! Setting validation is pure and runs under any feature; the archive run is
+//! checked against SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::services::booking_archive::{ARCHIVE_SETTING, validate_setting};
+
+#[test]
+fn archive_setting_accepts_off_or_a_retention_in_range() {
+    assert!(validate_setting(ARCHIVE_SETTING, "0").is_ok());
+    assert!(validate_setting(ARCHIVE_SETTING, " 365 ").is_ok());
+    assert!(validate_setting(ARCHIVE_SETTING, "7").is_err());
+    assert!(validate_setting(ARCHIVE_SETTING, "99999").is_err());
+    assert!(validate_setting(ARCHIVE_SETTING, "a year").is_err());
+    assert!(validate_setting("timezone", "7").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::{Duration, Local, NaiveDate};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::services::booking_archive::archive_old_bookings;
+
+    fn days_ago(days: i64) -> NaiveDate {
+        Local::now().date_naive() - Duration::days(days)
+    }
+
+    async fn booking(pool: &sqlx::SqlitePool, id: i64, status: &str, check_out: NaiveDate) {
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES ($1, $2, 9721, 9721, $3, $4, 150.0, 300.0, $5)",
+        )
+        .bind(id)
+        .bind(format!("BK-ARC-{}", id))
+        .bind(check_out - Duration::days(2))
+        .bind(check_out)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (972, 'Archive Twin', 'ATW', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9721, 'A721', 972, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9721, 'Hana', 'Lim', 'Hana Lim')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // 9721 and 9722 are eligible; 9723 checked out recently, 9724 is
+        // still confirmed and 9725 has a payment against it.
+        booking(pool, 9721, "checked_out", days_ago(400)).await;
+        booking(pool, 9722, "cancelled", days_ago(100)).await;
+        booking(pool, 9723, "checked_out", days_ago(5)).await;
+        booking(pool, 9724, "confirmed", days_ago(400)).await;
+        booking(pool, 9725, "checked_out", days_ago(400)).await;
+
+        sqlx::query(
+            "INSERT INTO booking_history (booking_id, previous_status, new_status)
+             VALUES (9721, 'checked_in', 'checked_out')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type, status)
+             VALUES (9725, 9721, 300.0, 'card', 'room_charge', 'completed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn ids(pool: &sqlx::SqlitePool, table: &str) -> Vec<i64> {
+        sqlx::query_scalar(&format!(
+            "SELECT id FROM {} WHERE id BETWEEN 9721 AND 9725 ORDER BY id",
+            table
+        ))
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn archiving_moves_only_eligible_bookings() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert_eq!(archive_old_bookings(&pool, 90).await.unwrap(), 2);
+
+        assert_eq!(ids(&pool, "bookings").await, vec![9723, 9724, 9725]);
+        assert_eq!(ids(&pool, "bookings_archive").await, vec![9721, 9722]);
+
+        let history: Vec<String> = sqlx::query_scalar(
+            "SELECT new_status FROM booking_history_archive WHERE booking_id = 9721",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(history, vec!["checked_out"]);
+
+        let payments: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM payments WHERE booking_id = 9725")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(payments, 1);
+
+        // A second run has nothing left to move.
+        assert_eq!(archive_old_bookings(&pool, 90).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn reports_see_archived_bookings_through_the_view() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        archive_old_bookings(&pool, 90).await.unwrap();
+
+        assert_eq!(
+            ids(&pool, "bookings_all").await,
+            vec![9721, 9722, 9723, 9724, 9725]
+        );
+
+        let checked_out_revenue: f64 = sqlx::query_scalar(
+            "SELECT SUM(total_amount) FROM bookings_all
+             WHERE status = 'checked_out' AND id BETWEEN 9721 AND 9725",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(checked_out_revenue, 900.0);
+
+        let archived: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM bookings_all
+             WHERE archived_at IS NOT NULL AND id BETWEEN 9721 AND 9725 ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(archived, vec![9721, 9722]);
+    }
+
+    #[tokio::test]
+    async fn retention_below_the_minimum_is_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            archive_old_bookings(&pool, 7).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        assert_eq!(ids(&pool, "bookings").await.len(), 5);
+    }
+}
@@ -0,0 +1,189 @@
+//! Tests for files attached to bookings.
+//!
+//! Checking an upload and naming it are pure and run under any feature.
+//! Storing, listing, reading and removing attachments is SQLite-backed and
+//! gated accordingly.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::booking_attachments::{
+    ATTACHMENT_UPLOAD, MAX_ATTACHMENT_BYTES, MAX_FILE_NAME_CHARS, display_name,
+};
+
+const PDF_SCAN: &[u8] = b"%PDF-1.7\n% registration card\n";
+
+#[test]
+fn attachments_are_scans_or_pdfs_of_the_declared_type() {
+    assert_eq!(
+        ATTACHMENT_UPLOAD
+            .check("application/pdf", PDF_SCAN)
+            .unwrap()
+            .extension,
+        "pdf"
+    );
+    assert_eq!(
+        ATTACHMENT_UPLOAD
+            .check("image/png", b"\x89PNG\r\n\x1a\n....")
+            .unwrap()
+            .extension,
+        "png"
+    );
+
+    for (content_type, data) in [
+        ("image/png", PDF_SCAN),
+        ("application/msword", PDF_SCAN),
+        ("image/webp", b"RIFF....WEBPVP8 ".as_slice()),
+        ("application/pdf", b""),
+    ] {
+        assert!(
+            matches!(
+                ATTACHMENT_UPLOAD.check(content_type, data),
+                Err(ApiError::BadRequest(_))
+            ),
+            "{} accepted",
+            content_type
+        );
+    }
+
+    let mut oversized = PDF_SCAN.to_vec();
+    oversized.resize(MAX_ATTACHMENT_BYTES + 1, 0);
+    assert!(matches!(
+        ATTACHMENT_UPLOAD.check("application/pdf", &oversized),
+        Err(ApiError::BadRequest(msg)) if msg.contains("10 MB")
+    ));
+}
+
+#[test]
+fn attachments_keep_the_name_they_were_uploaded_under() {
+    assert_eq!(display_name(Some("reg card.pdf"), "pdf"), "reg card.pdf");
+    assert_eq!(
+        display_name(Some("C:\\scans\\../signed form.jpg"), "jpg"),
+        "signed form.jpg"
+    );
+    assert_eq!(display_name(Some(" \t"), "pdf"), "attachment.pdf");
+    assert_eq!(display_name(None, "png"), "attachment.png");
+    assert_eq!(
+        display_name(Some(&"a".repeat(400)), "pdf").chars().count(),
+        MAX_FILE_NAME_CHARS
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{PDF_SCAN, common};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::uploads::{PDF, Upload};
+    use hotel_app_be::services::booking_attachments::{
+        delete_attachment_in, list_attachments, read_attachment_in, save_attachment_in, stored_file,
+    };
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (997, 'Attach Double', 'ADB', 90.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9971, 'A01', 997, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9970, 'Noor', 'Aziz')",
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9970, 'u-9970', 'front_desk', 'desk@example.com', 1)",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9971, 'BK-AT-1', 9970, 9971, date('now'), date('now', '+1 day'), 90.0, 90.0, 'confirmed'),
+             (9972, 'BK-AT-2', 9970, 9971, date('now', '+3 day'), date('now', '+4 day'), 90.0, 90.0, 'confirmed')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    fn scan() -> Upload {
+        Upload {
+            content_type: "application/pdf".to_string(),
+            file_type: &PDF,
+            data: PDF_SCAN.to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn attachments_are_stored_listed_and_removed_with_their_files() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let root =
+            std::env::temp_dir().join(format!("hotel-booking-attachments-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let card = save_attachment_in(&pool, &root, 9971, Some("card.pdf"), &scan(), 9970)
+            .await
+            .unwrap();
+        let form = save_attachment_in(&pool, &root, 9971, None, &scan(), 9970)
+            .await
+            .unwrap();
+        // Files are only reachable through the authenticated download route.
+        assert_eq!(
+            card.file_url,
+            format!("/bookings/9971/attachments/{}", card.id)
+        );
+        assert_eq!(
+            (card.file_name.as_str(), form.file_name.as_str()),
+            ("card.pdf", "attachment.pdf")
+        );
+        assert_eq!(card.size_bytes, PDF_SCAN.len() as i64);
+        assert_eq!(card.uploaded_by, Some(9970));
+        let card_file = stored_file(&root, 9971, card.id);
+        assert_eq!(std::fs::read(&card_file).unwrap(), PDF_SCAN);
+        assert_eq!(
+            read_attachment_in(&pool, &root, 9971, card.id)
+                .await
+                .unwrap(),
+            (card.clone(), PDF_SCAN.to_vec())
+        );
+        // An attachment is only read through its own booking.
+        assert!(matches!(
+            read_attachment_in(&pool, &root, 9972, card.id).await,
+            Err(ApiError::NotFound(_))
+        ));
+
+        assert_eq!(
+            list_attachments(&pool, 9971).await.unwrap(),
+            [card.clone(), form.clone()]
+        );
+        assert!(list_attachments(&pool, 9972).await.unwrap().is_empty());
+
+        // Nor removed through another.
+        assert!(matches!(
+            delete_attachment_in(&pool, &root, 9972, card.id).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert_eq!(
+            delete_attachment_in(&pool, &root, 9971, card.id)
+                .await
+                .unwrap(),
+            card
+        );
+        assert!(!card_file.exists());
+        assert_eq!(list_attachments(&pool, 9971).await.unwrap(), [form]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn unknown_bookings_take_no_attachments() {
+        let pool = common::setup_test_db().await;
+        let root = std::env::temp_dir().join(format!(
+            "hotel-booking-attachments-missing-{}",
+            std::process::id()
+        ));
+
+        assert!(matches!(
+            save_attachment_in(&pool, &root, 404, None, &scan(), 1).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            list_attachments(&pool, 404).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(!root.exists());
+    }
+}
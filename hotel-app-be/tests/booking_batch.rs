@@ -0,0 +1,26 @@
+//! Tests for fetching several bookings in one request.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::booking::{MAX_BOOKING_BATCH, batch_ids, in_request_order};
+
+#[test]
+fn batch_ids_keep_request_order_without_repeats() {
+    assert_eq!(batch_ids(&[7, 3, 7, 9, 3]).unwrap(), [7, 3, 9]);
+    assert!(batch_ids(&[]).unwrap().is_empty());
+
+    let at_cap: Vec<i64> = (1..=MAX_BOOKING_BATCH as i64).collect();
+    assert_eq!(batch_ids(&at_cap).unwrap().len(), MAX_BOOKING_BATCH);
+    let over_cap: Vec<i64> = (0..=MAX_BOOKING_BATCH as i64).collect();
+    assert!(matches!(batch_ids(&over_cap), Err(ApiError::BadRequest(_))));
+}
+
+#[test]
+fn found_bookings_follow_the_request_and_the_rest_are_reported() {
+    // As the database returns them: in its own order.
+    let found = vec![(3, "BK-3"), (7, "BK-7"), (12, "BK-12")];
+
+    let (ordered, missing) = in_request_order(&[7, 99, 3, 12, 41], found, |b| b.0);
+
+    assert_eq!(ordered, [(7, "BK-7"), (3, "BK-3"), (12, "BK-12")]);
+    assert_eq!(missing, [99, 41]);
+}
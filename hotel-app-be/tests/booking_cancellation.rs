@@ -0,0 +1,254 @@
+//! Tests for booking cancellation and its fee policy.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::cancellation::{
+    CancellationPolicy, FEE_PERCENT_SETTING, FREE_DAYS_SETTING, cancellation_charge,
+    validate_setting,
+};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn money(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+const POLICY: CancellationPolicy = CancellationPolicy {
+    free_days: 2,
+    fee_percent: 50,
+};
+
+#[test]
+fn early_cancellations_are_free_and_fully_refunded() {
+    let charge = cancellation_charge(
+        POLICY,
+        money("300"),
+        money("100"),
+        date("2026-09-10"),
+        date("2026-09-07"),
+    );
+
+    assert_eq!(charge.days_before_check_in, 3);
+    assert!(charge.free_cancellation);
+    assert_eq!(charge.cancellation_fee, Decimal::ZERO);
+    assert_eq!(charge.refund_amount, money("100"));
+}
+
+#[test]
+fn late_cancellations_are_charged_the_fee_percentage() {
+    // Exactly at the window's edge is no longer free.
+    let charge = cancellation_charge(
+        POLICY,
+        money("333.34"),
+        money("200"),
+        date("2026-09-10"),
+        date("2026-09-08"),
+    );
+    assert!(!charge.free_cancellation);
+    assert_eq!(charge.cancellation_fee, money("166.67"));
+    assert_eq!(charge.refund_amount, money("33.33"));
+
+    // A fee above what was paid leaves nothing to refund.
+    let charge = cancellation_charge(
+        POLICY,
+        money("300"),
+        money("100"),
+        date("2026-09-10"),
+        date("2026-09-12"),
+    );
+    assert_eq!(charge.days_before_check_in, -2);
+    assert_eq!(charge.cancellation_fee, money("150"));
+    assert_eq!(charge.refund_amount, Decimal::ZERO);
+}
+
+#[test]
+fn cancellation_settings_are_validated() {
+    assert!(validate_setting(FREE_DAYS_SETTING, "0").is_ok());
+    assert!(validate_setting(FREE_DAYS_SETTING, "-1").is_err());
+    assert!(validate_setting(FREE_DAYS_SETTING, "366").is_err());
+    assert!(validate_setting(FEE_PERCENT_SETTING, "100").is_ok());
+    assert!(validate_setting(FEE_PERCENT_SETTING, "101").is_err());
+    assert!(validate_setting(FEE_PERCENT_SETTING, "half").is_err());
+    assert!(validate_setting("hotel_name", "anything").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, money};
+    use axum::extract::{Extension, Json, Path, State};
+    use chrono::{Duration, Local};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::cancel_booking_handler;
+    use hotel_app_be::models::CancelBookingInput;
+    use rust_decimal::Decimal;
+
+    const CLERK: i64 = 9900;
+
+    /// A booking for 300 in its own reserved room, checking in `days_ahead`
+    /// days from today, with 200 paid and 50 of it refunded.
+    async fn seed(pool: &sqlx::SqlitePool, id: i64, days_ahead: i64, status: &str) {
+        let check_in = Local::now().date_naive() + Duration::days(days_ahead);
+        for sql in [
+            "INSERT OR IGNORE INTO users (id, uuid, username, email, is_active)
+             VALUES (9900, 'u-9900', 'front_desk', 'desk@example.com', 1)",
+            "INSERT OR IGNORE INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (990, 'Cancel Double', 'CDB', 150.0, 2)",
+            "INSERT OR IGNORE INTO guests (id, first_name, last_name, full_name)
+             VALUES (9900, 'Farid', 'Ismail', 'Farid Ismail')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES ($1, $2, 990, 'reserved', 1)",
+        )
+        .bind(id)
+        .bind(format!("C{}", id))
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES ($1, $2, 9900, $1, $3, $4, 150.0, 300.0, $5)",
+        )
+        .bind(id)
+        .bind(format!("BK-CXL-{}", id))
+        .bind(check_in.to_string())
+        .bind((check_in + Duration::days(2)).to_string())
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type)
+             VALUES ($1, 9900, 200.0, 'card', 'booking'), ($1, 9900, 50.0, 'card', 'refund')",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn reason(text: &str) -> Json<CancelBookingInput> {
+        Json(CancelBookingInput {
+            reason: Some(text.to_string()),
+        })
+    }
+
+    #[tokio::test]
+    async fn early_cancellation_is_free_and_releases_the_room() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9901, 30, "confirmed").await;
+
+        let cancelled = cancel_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Path(9901),
+            reason("Change of plans"),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(cancelled.status, "cancelled");
+        assert!(cancelled.free_cancellation);
+        assert_eq!(cancelled.cancellation_fee, Decimal::ZERO);
+        assert_eq!(cancelled.amount_paid, money("150"));
+        assert_eq!(cancelled.refund_amount, money("150"));
+
+        let (status, cancelled_by, why): (String, i64, String) = sqlx::query_as(
+            "SELECT status, cancelled_by, cancellation_reason FROM bookings WHERE id = 9901",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            (status.as_str(), cancelled_by, why.as_str()),
+            ("cancelled", CLERK, "Change of plans")
+        );
+
+        let room: String = sqlx::query_scalar("SELECT status FROM rooms WHERE id = 9901")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(room, "available");
+    }
+
+    #[tokio::test]
+    async fn late_cancellation_records_the_fee() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9902, 1, "confirmed").await;
+
+        let cancelled = cancel_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Path(9902),
+            Json(CancelBookingInput::default()),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(!cancelled.free_cancellation);
+        assert_eq!(cancelled.cancellation_fee, money("150"));
+        assert_eq!(cancelled.refund_amount, Decimal::ZERO);
+
+        let fee: f64 = sqlx::query_scalar("SELECT cancellation_fee FROM bookings WHERE id = 9902")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fee, 150.0);
+    }
+
+    #[tokio::test]
+    async fn started_and_cancelled_stays_cannot_be_cancelled() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9903, 0, "checked_in").await;
+        seed(&pool, 9904, -3, "checked_out").await;
+        seed(&pool, 9905, 10, "confirmed").await;
+
+        cancel_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Path(9905),
+            Json(CancelBookingInput::default()),
+        )
+        .await
+        .unwrap();
+
+        for id in [9903, 9904, 9905] {
+            assert!(
+                matches!(
+                    cancel_booking_handler(
+                        State(pool.clone()),
+                        Extension(CLERK),
+                        Extension(PropertyScope::default()),
+                        Path(id),
+                        Json(CancelBookingInput::default()),
+                    )
+                    .await,
+                    Err(ApiError::BadRequest(_))
+                ),
+                "booking {}",
+                id
+            );
+        }
+
+        let room: String = sqlx::query_scalar("SELECT status FROM rooms WHERE id = 9903")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(room, "reserved");
+    }
+}
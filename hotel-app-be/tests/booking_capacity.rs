@@ -0,0 +1,40 @@
+//! Tests for checking a booking's party against room capacity.
+//!
+//! Booking creation locks the room row with PostgreSQL-only SQL, so the
+//! capacity rule is tested directly; capacity-based room search is covered
+//! in `rooms_search.rs`.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::booking::check_guest_capacity;
+
+#[test]
+fn over_capacity_booking_is_rejected() {
+    // Six guests in a room for two.
+    let result = check_guest_capacity(2, 4, 0, 2, false);
+    match result {
+        Err(ApiError::BadRequest(message)) => {
+            assert!(message.contains("at most 2 guests"), "{message}");
+            assert!(message.contains("6 were requested"), "{message}");
+        }
+        other => panic!("expected a capacity error, got {other:?}"),
+    }
+
+    assert!(check_guest_capacity(2, 0, 0, 2, false).is_ok());
+    assert!(check_guest_capacity(1, 1, 0, 2, false).is_ok());
+}
+
+#[test]
+fn infants_only_count_when_configured() {
+    assert!(check_guest_capacity(2, 0, 1, 2, false).is_ok());
+    assert!(matches!(
+        check_guest_capacity(2, 0, 1, 2, true),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn party_needs_an_adult_and_no_negative_counts() {
+    assert!(check_guest_capacity(0, 2, 0, 4, false).is_err());
+    assert!(check_guest_capacity(2, -1, 0, 4, false).is_err());
+    assert!(check_guest_capacity(2, 0, -1, 4, false).is_err());
+}
@@ -0,0 +1,170 @@
+//! Tests for checking guests out.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database. Check-in returns the booking through PostgreSQL-only
+//! columns, so only check-out runs here.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::checkout_booking_handler;
+    use hotel_app_be::models::CheckOutRequest;
+
+    const CLERK: i64 = 9910;
+
+    /// A 300 stay in its own occupied room with `paid` settled so far.
+    async fn seed(pool: &sqlx::SqlitePool, id: i64, status: &str, paid: f64) {
+        for sql in [
+            "INSERT OR IGNORE INTO users (id, uuid, username, email, is_active)
+             VALUES (9910, 'u-9910', 'night_desk', 'night@example.com', 1)",
+            "INSERT OR IGNORE INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (991, 'Checkout King', 'CKK', 150.0, 2)",
+            "INSERT OR IGNORE INTO guests (id, first_name, last_name, full_name)
+             VALUES (9910, 'Siti', 'Aminah', 'Siti Aminah')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES ($1, $2, 991, 'occupied', 1)",
+        )
+        .bind(id)
+        .bind(format!("K{}", id))
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES ($1, $2, 9910, $1, '2026-09-01', '2026-09-03', 150.0, 300.0, $3)",
+        )
+        .bind(id)
+        .bind(format!("BK-OUT-{}", id))
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type)
+             VALUES ($1, 9910, $2, 'card', 'booking')",
+        )
+        .bind(id)
+        .bind(paid)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn check_out(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        override_balance: bool,
+    ) -> Result<String, ApiError> {
+        checkout_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Path(id),
+            Json(Some(CheckOutRequest {
+                override_balance,
+                notes: None,
+            })),
+        )
+        .await
+        .map(|json| json.0.status)
+    }
+
+    async fn status_of(pool: &sqlx::SqlitePool, id: i64) -> (String, String) {
+        let booking: String = sqlx::query_scalar("SELECT status FROM bookings WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let room: String = sqlx::query_scalar("SELECT status FROM rooms WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        (booking, room)
+    }
+
+    #[tokio::test]
+    async fn settled_stay_checks_out_and_leaves_the_room_dirty() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9911, "checked_in", 300.0).await;
+
+        assert_eq!(check_out(&pool, 9911, false).await.unwrap(), "checked_out");
+        assert_eq!(
+            status_of(&pool, 9911).await,
+            ("checked_out".to_string(), "dirty".to_string())
+        );
+
+        let stamped: Option<String> =
+            sqlx::query_scalar("SELECT actual_check_out FROM bookings WHERE id = 9911")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(stamped.is_some());
+
+        let history: (String, String, i64) = sqlx::query_as(
+            "SELECT from_status, to_status, changed_by FROM room_history WHERE room_id = 9911",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            history,
+            ("occupied".to_string(), "dirty".to_string(), CLERK)
+        );
+
+        let events: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM room_events WHERE room_id = 9911")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(events, 1);
+    }
+
+    #[tokio::test]
+    async fn outstanding_balance_blocks_check_out_unless_overridden() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9912, "checked_in", 100.0).await;
+
+        let blocked = check_out(&pool, 9912, false).await;
+        assert!(
+            matches!(&blocked, Err(ApiError::BadRequest(msg)) if msg.contains("outstanding balance of 200")),
+            "{:?}",
+            blocked
+        );
+        assert_eq!(
+            status_of(&pool, 9912).await,
+            ("checked_in".to_string(), "occupied".to_string())
+        );
+
+        assert_eq!(check_out(&pool, 9912, true).await.unwrap(), "checked_out");
+    }
+
+    #[tokio::test]
+    async fn only_checked_in_stays_can_check_out() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, 9913, "confirmed", 300.0).await;
+        seed(&pool, 9914, "checked_out", 300.0).await;
+
+        for id in [9913, 9914] {
+            assert!(
+                matches!(
+                    check_out(&pool, id, true).await,
+                    Err(ApiError::BadRequest(_))
+                ),
+                "booking {}",
+                id
+            );
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Integration tests for the booking conflict preview.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::check_booking_conflict_handler;
+    use hotel_app_be::models::{BookingConflictCheck, BookingConflictParams};
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (941, 'Conflict King', 'CKNG', 180.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9401, 'C941', 941, 'available', 1), (9402, 'C942', 941, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9401, 'Farid', 'Osman', 'Farid Osman')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // Room 9401 is held 10–13 July; the voided stay on 20–22 July no longer blocks it.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9401, 'BK-CNF-9401', 9401, 9401, '2026-07-10', '2026-07-13', 180.0, 540.0, 'confirmed'),
+             (9402, 'BK-CNF-9402', 9401, 9401, '2026-07-20', '2026-07-22', 180.0, 360.0, 'voided')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn check(
+        pool: &sqlx::SqlitePool,
+        room_id: i64,
+        check_in: &str,
+        check_out: &str,
+    ) -> Result<BookingConflictCheck, ApiError> {
+        check_booking_conflict_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(BookingConflictParams {
+                room_id,
+                check_in: check_in.to_string(),
+                check_out: check_out.to_string(),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    #[tokio::test]
+    async fn free_range_is_available() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // Arriving on the existing check-out day does not overlap.
+        let result = check(&pool, 9401, "2026-07-13", "2026-07-15")
+            .await
+            .unwrap();
+        assert!(result.available);
+        assert!(result.conflicting_booking_numbers.is_empty());
+
+        let voided = check(&pool, 9401, "2026-07-20", "2026-07-22")
+            .await
+            .unwrap();
+        assert!(voided.available);
+
+        let other_room = check(&pool, 9402, "2026-07-11", "2026-07-12")
+            .await
+            .unwrap();
+        assert!(other_room.available);
+    }
+
+    #[tokio::test]
+    async fn overlapping_range_lists_the_conflicting_booking() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let result = check(&pool, 9401, "2026-07-12", "2026-07-14")
+            .await
+            .unwrap();
+        assert!(!result.available);
+        assert_eq!(result.conflicting_booking_numbers, vec!["BK-CNF-9401"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_rooms_and_reversed_dates() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            check(&pool, 424242, "2026-07-12", "2026-07-14").await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            check(&pool, 9401, "2026-07-14", "2026-07-12").await,
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Tests for cancellation and no-show fees.
+//!
+//! The fee policy is pure and runs under any feature. Charging fees is
+//! SQLite-backed and gated accordingly.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::booking_fees::{
+    NO_SHOW_FEE_NIGHTS_SETTING, no_show_fee, validate_setting,
+};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn money(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn no_shows_are_charged_nights_at_the_average_rate() {
+    let (check_in, check_out) = (date("2026-11-01"), date("2026-11-04"));
+
+    assert_eq!(
+        no_show_fee(money("500"), check_in, check_out, 1),
+        money("166.67")
+    );
+    assert_eq!(
+        no_show_fee(money("500"), check_in, check_out, 2),
+        money("333.33")
+    );
+    // Never more than the stay, and nothing when the policy is off.
+    assert_eq!(
+        no_show_fee(money("500"), check_in, check_out, 7),
+        money("500")
+    );
+    assert_eq!(
+        no_show_fee(money("500"), check_in, check_out, 0),
+        Decimal::ZERO
+    );
+    // A day-use stay counts as one night.
+    assert_eq!(no_show_fee(money("80"), check_in, check_in, 1), money("80"));
+}
+
+#[test]
+fn no_show_fee_setting_is_validated() {
+    assert!(validate_setting(NO_SHOW_FEE_NIGHTS_SETTING, "0").is_ok());
+    assert!(validate_setting(NO_SHOW_FEE_NIGHTS_SETTING, "30").is_ok());
+    assert!(validate_setting(NO_SHOW_FEE_NIGHTS_SETTING, "31").is_err());
+    assert!(validate_setting(NO_SHOW_FEE_NIGHTS_SETTING, "-1").is_err());
+    assert!(validate_setting(NO_SHOW_FEE_NIGHTS_SETTING, "one").is_err());
+    assert!(validate_setting("hotel_name", "one").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date, money};
+    use axum::extract::{Extension, Json, Path, State};
+    use chrono::{Duration, Local};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::cancel_booking_handler;
+    use hotel_app_be::models::CancelBookingInput;
+    use hotel_app_be::services::booking_fees::charge_no_show_fees;
+
+    const CLERK: i64 = 9990;
+
+    /// Bookings 9991 to 9995 for 300 over two nights. 9991 checks in
+    /// tomorrow and 9992 in a month; 9993 and 9994 were no-shows on the
+    /// 2nd of November, 9994 billed to a company; 9995 is a no-show
+    /// arriving on the 9th.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        let tomorrow = Local::now().date_naive() + Duration::days(1);
+        let next_month = Local::now().date_naive() + Duration::days(30);
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9990, 'u-9990', 'fee_desk', 'fees@example.com', 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (999, 'Fee Double', 'FDB', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9991, 'F991', 999, 'reserved', 1), (9992, 'F992', 999, 'reserved', 1),
+                    (9993, 'F993', 999, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9990, 'Ines', 'Costa', 'Ines Costa')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, payment_method, company_name)
+             VALUES
+             (9991, 'BK-FEE-1', 9990, 9991, $1, $2, 150.0, 300.0, 'confirmed', 'card', NULL),
+             (9992, 'BK-FEE-2', 9990, 9992, $3, $4, 150.0, 300.0, 'confirmed', 'card', NULL),
+             (9993, 'BK-FEE-3', 9990, 9993, '2026-11-02', '2026-11-04', 150.0, 300.0, 'no_show', 'card', NULL),
+             (9994, 'BK-FEE-4', 9990, 9993, '2026-11-02', '2026-11-04', 150.0, 300.0, 'no_show', 'company_billing', 'Acme Travel'),
+             (9995, 'BK-FEE-5', 9990, 9993, '2026-11-09', '2026-11-11', 150.0, 300.0, 'no_show', 'card', NULL)",
+        )
+        .bind(tomorrow.to_string())
+        .bind((tomorrow + Duration::days(2)).to_string())
+        .bind(next_month.to_string())
+        .bind((next_month + Duration::days(2)).to_string())
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn fees(pool: &sqlx::SqlitePool) -> Vec<(i64, String, f64, String)> {
+        sqlx::query_as(
+            "SELECT booking_id, fee_type, amount, COALESCE(company_name, billed_to)
+             FROM booking_fees ORDER BY booking_id",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn late_cancellations_charge_the_fee_once() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for booking_id in [9991, 9992] {
+            cancel_booking_handler(
+                State(pool.clone()),
+                Extension(CLERK),
+                Extension(PropertyScope::default()),
+                Path(booking_id),
+                Json(CancelBookingInput::default()),
+            )
+            .await
+            .unwrap();
+        }
+
+        // Only the late cancellation is charged.
+        assert_eq!(
+            fees(&pool).await,
+            vec![(9991, "cancellation".into(), 150.0, "guest".into())]
+        );
+        let charged_by: i64 =
+            sqlx::query_scalar("SELECT charged_by FROM booking_fees WHERE booking_id = 9991")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(charged_by, CLERK);
+    }
+
+    #[tokio::test]
+    async fn no_shows_are_charged_by_the_night_audit_once() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let charged = charge_no_show_fees(&pool, date("2026-11-03"), CLERK)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            charged
+                .iter()
+                .map(|f| (f.booking_id, f.amount, f.billed_to.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (9993, money("150"), "guest"),
+                (9994, money("150"), "company")
+            ]
+        );
+        assert_eq!(
+            fees(&pool).await,
+            vec![
+                (9993, "no_show".into(), 150.0, "guest".into()),
+                (9994, "no_show".into(), 150.0, "Acme Travel".into()),
+            ]
+        );
+
+        // A later audit charges only the later arrival.
+        let charged = charge_no_show_fees(&pool, date("2026-11-10"), CLERK)
+            .await
+            .unwrap();
+        assert_eq!(
+            charged.iter().map(|f| f.booking_id).collect::<Vec<_>>(),
+            vec![9995]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_show_fees_follow_the_setting() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        sqlx::query("UPDATE system_settings SET value = '0' WHERE key = 'no_show_fee_nights'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(
+            charge_no_show_fees(&pool, date("2026-11-03"), CLERK)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        sqlx::query("UPDATE system_settings SET value = '2' WHERE key = 'no_show_fee_nights'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let charged = charge_no_show_fees(&pool, date("2026-11-03"), CLERK)
+            .await
+            .unwrap();
+        assert_eq!(charged[0].amount, money("300"));
+    }
+}
@@ -0,0 +1,143 @@
+//! Tests for changing a booking's dates or room.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::update_booking_handler;
+    use hotel_app_be::models::{Booking, BookingUpdateInput};
+    use hotel_app_be::services::tax::{self, TaxContext, TaxMode};
+    use rust_decimal::Decimal;
+
+    const ADMIN: i64 = 9920;
+
+    /// A two-night stay at 150 in room 9921 from 1 to 3 September. Another
+    /// guest, in the given status, follows it in the room from the 5th and
+    /// has room 9922 for the same two nights.
+    async fn seed(pool: &sqlx::SqlitePool, neighbour_status: &str) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9920, 'u-9920', 'reservations', 'reservations@example.com', 1)",
+            "INSERT INTO user_roles (user_id, role_id) VALUES (9920, 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (992, 'Modify Queen', 'MQN', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9921, 'M921', 992, 'reserved', 1),
+                    (9922, 'M922', 992, 'reserved', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9920, 'Hana', 'Lim', 'Hana Lim')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, room_rate, subtotal, total_amount, status)
+             VALUES
+             (9921, 'BK-MOD-1', 9920, 9921, '2026-09-01', '2026-09-03', 150.0, 150.0, 300.0, 300.0, 'confirmed'),
+             (9922, 'BK-MOD-2', 9920, 9921, '2026-09-05', '2026-09-07', 150.0, 150.0, 300.0, 300.0, $1),
+             (9923, 'BK-MOD-3', 9920, 9922, '2026-09-01', '2026-09-03', 150.0, 150.0, 300.0, 300.0, $1)",
+        )
+        .bind(neighbour_status)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn modify(
+        pool: &sqlx::SqlitePool,
+        changes: serde_json::Value,
+    ) -> Result<Booking, ApiError> {
+        let input: BookingUpdateInput = serde_json::from_value(changes).unwrap();
+        update_booking_handler(
+            State(pool.clone()),
+            Extension(ADMIN),
+            Extension(PropertyScope::default()),
+            Path(9921),
+            Json(input),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    #[tokio::test]
+    async fn moving_dates_on_the_same_room_reprices_the_stay() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, "confirmed").await;
+
+        // The new dates overlap the booking's own old ones.
+        let booking = modify(
+            &pool,
+            serde_json::json!({ "check_in_date": "2026-09-02", "check_out_date": "2026-09-05" }),
+        )
+        .await
+        .unwrap();
+
+        let tax_ctx = TaxContext::load(&pool, TaxMode::Inclusive).await;
+        let expected = tax::compute_taxes(Decimal::from(450), &tax_ctx);
+        assert_eq!(booking.room_id, 9921);
+        assert_eq!(booking.subtotal, Decimal::from(450));
+        assert_eq!(booking.total_amount, expected.gross);
+        // SQLite stores money as REAL.
+        assert_eq!(
+            booking.tax_amount.map(|t| t.round_dp(2)),
+            Some(expected.service_tax)
+        );
+        assert!(expected.service_tax > Decimal::ZERO);
+
+        let logged: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM booking_modifications WHERE booking_id = 9921",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(logged > 0);
+    }
+
+    #[tokio::test]
+    async fn changes_that_collide_with_another_stay_are_rejected() {
+        for status in ["confirmed", "auto_checked_in"] {
+            let pool = common::setup_test_db().await;
+            seed(&pool, status).await;
+
+            let extended =
+                modify(&pool, serde_json::json!({ "check_out_date": "2026-09-06" })).await;
+            let moved = modify(&pool, serde_json::json!({ "room_id": "9922" })).await;
+
+            for result in [extended, moved] {
+                assert!(
+                    matches!(&result, Err(ApiError::BadRequest(msg)) if msg.contains("already booked")),
+                    "{}: {:?}",
+                    status,
+                    result.map(|b| b.id)
+                );
+            }
+
+            let unchanged: (i64, String, f64) = sqlx::query_as(
+                "SELECT room_id, check_out_date, total_amount FROM bookings WHERE id = 9921",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(unchanged, (9921, "2026-09-03".to_string(), 300.0));
+        }
+    }
+
+    #[tokio::test]
+    async fn back_to_back_stays_do_not_collide() {
+        let pool = common::setup_test_db().await;
+        seed(&pool, "confirmed").await;
+
+        let booking = modify(&pool, serde_json::json!({ "check_out_date": "2026-09-05" }))
+            .await
+            .unwrap();
+        assert_eq!(booking.subtotal, Decimal::from(600));
+    }
+}
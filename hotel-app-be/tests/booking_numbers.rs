@@ -0,0 +1,150 @@
+//! Tests for `services::booking_numbers`
+//!
+//! Template rendering and validation are pure and run under any feature.
+//! Sequence allocation is exercised against SQLite and gated accordingly.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::booking_numbers::{BookingNumberFormat, luhn_check_digit};
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 4, 18).unwrap()
+}
+
+#[test]
+fn renders_template_tokens() {
+    let cases = [
+        // (template, seq, expected)
+        ("BK-{year}-{seq}", 7, "BK-2026-0007"),
+        ("{property}{date}{seq:6}", 42, "KLC20260418000042"),
+        ("R/{year}{month}{day}/{seq:2}", 3, "R/20260418/03"),
+        ("{seq:3}", 12345, "12345"),
+    ];
+
+    for (template, seq, expected) in cases {
+        let format = BookingNumberFormat::parse(template, false).unwrap();
+        assert_eq!(
+            format.render(date(), "KLC", seq).unwrap(),
+            expected,
+            "{template}"
+        );
+    }
+}
+
+#[test]
+fn appends_luhn_check_digit() {
+    let format = BookingNumberFormat::parse("BK-{year}-{seq}", true).unwrap();
+    let number = format.render(date(), "", 7).unwrap();
+
+    // Digits 2026 0007 -> Luhn check digit 8.
+    assert_eq!(number, "BK-2026-00078");
+    assert_eq!(luhn_check_digit("BK-2026-0007"), '8');
+    assert_eq!(luhn_check_digit("7992739871"), '3');
+}
+
+#[test]
+fn sequences_widen_until_the_number_no_longer_fits() {
+    let format = BookingNumberFormat::parse("BK-{date}-{seq}", false).unwrap();
+    assert_eq!(
+        format.render(date(), "", 10_000).unwrap(),
+        "BK-20260418-10000"
+    );
+
+    // 44 characters at the template's width, over 50 once the sequence
+    // outgrows it.
+    let format = BookingNumberFormat::parse("{property}-{date}-{date}-X-{seq:12}", true).unwrap();
+    assert_eq!(format.render(date(), "KLCENTRAL1", 1).unwrap().len(), 44);
+    assert!(format.render(date(), "KLCENTRAL1", u64::MAX).is_err());
+}
+
+#[test]
+fn rejects_invalid_templates() {
+    let invalid = [
+        // no sequence
+        "BK-{year}",
+        // two sequences
+        "BK-{seq}-{seq}",
+        // unknown token
+        "BK-{yaer}-{seq}",
+        // token swallowed into an unknown one
+        "BK-{year-{seq}",
+        // unclosed brace
+        "BK-{seq",
+        // stray closing brace
+        "BK-}{seq}",
+        // whitespace
+        "BK {seq}",
+        // sequence width out of range
+        "BK-{seq:0}",
+        "BK-{seq:13}",
+        // can exceed the 50-character column
+        "{property}{property}{property}{property}{property}{seq}",
+    ];
+
+    for template in invalid {
+        assert!(
+            BookingNumberFormat::parse(template, false).is_err(),
+            "template should be rejected: {template}"
+        );
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date};
+    use hotel_app_be::services::booking_numbers::next_booking_number;
+
+    async fn set(pool: &sqlx::SqlitePool, key: &str, value: &str) {
+        sqlx::query("UPDATE system_settings SET value = ?1 WHERE key = ?2")
+            .bind(value)
+            .bind(key)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn empty_template_keeps_legacy_format() {
+        let pool = common::setup_test_db().await;
+
+        let number = next_booking_number(&pool, date()).await.unwrap();
+        assert!(number.starts_with("BK-20260418-"), "{number}");
+    }
+
+    #[tokio::test]
+    async fn sequence_increments_per_prefix() {
+        let pool = common::setup_test_db().await;
+        set(&pool, "booking_number_format", "{property}-{year}-{seq}").await;
+        set(&pool, "property_code", "klc").await;
+
+        let first = next_booking_number(&pool, date()).await.unwrap();
+        let second = next_booking_number(&pool, date()).await.unwrap();
+        assert_eq!(first, "KLC-2026-0001");
+        assert_eq!(second, "KLC-2026-0002");
+
+        let next_year = chrono::NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        assert_eq!(
+            next_booking_number(&pool, next_year).await.unwrap(),
+            "KLC-2027-0001"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_bookings_get_distinct_numbers() {
+        let pool = common::setup_test_db().await;
+        set(&pool, "booking_number_format", "BK-{date}-{seq}").await;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..40 {
+            let pool = pool.clone();
+            tasks.spawn(async move { next_booking_number(&pool, date()).await.unwrap() });
+        }
+        let mut numbers = tasks.join_all().await;
+        numbers.sort();
+        numbers.dedup();
+        assert_eq!(numbers.len(), 40);
+        assert_eq!(numbers.first().unwrap(), "BK-20260418-0001");
+        assert_eq!(numbers.last().unwrap(), "BK-20260418-0040");
+    }
+}
@@ -0,0 +1,182 @@
+//! Tests for the booking pace report.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::occupancy_alerts::ForecastStay;
+use hotel_app_be::services::pace::{PaceStay, pace};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn stay(room_id: i64, check_in: &str, check_out: &str, rate: i64) -> PaceStay {
+    PaceStay {
+        stay: ForecastStay {
+            room_id,
+            check_in: date(check_in),
+            check_out: date(check_out),
+        },
+        room_rate: Decimal::from(rate),
+        daily_rates: None,
+    }
+}
+
+#[test]
+fn each_night_counts_the_stays_covering_it() {
+    let stays = vec![
+        stay(1, "2026-12-01", "2026-12-03", 100),
+        stay(2, "2026-12-02", "2026-12-04", 150),
+        // Same-day use covers no night.
+        stay(3, "2026-12-02", "2026-12-02", 60),
+    ];
+
+    let days = pace(&stays, date("2026-12-01"), date("2026-12-04"));
+    let summary: Vec<(NaiveDate, i64, Decimal)> = days
+        .iter()
+        .map(|d| (d.date, d.room_nights, d.revenue))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (date("2026-12-01"), 1, Decimal::from(100)),
+            (date("2026-12-02"), 2, Decimal::from(250)),
+            (date("2026-12-03"), 1, Decimal::from(150)),
+            (date("2026-12-04"), 0, Decimal::ZERO),
+        ]
+    );
+}
+
+#[test]
+fn daily_rates_override_the_room_rate_per_night() {
+    let mut weekend = stay(1, "2026-12-04", "2026-12-07", 100);
+    weekend.daily_rates = Some(serde_json::json!({
+        "2026-12-04": 180.0,
+        "2026-12-05": "175.50",
+    }));
+
+    let days = pace(&[weekend], date("2026-12-04"), date("2026-12-06"));
+    let revenue: Vec<Decimal> = days.iter().map(|d| d.revenue).collect();
+    assert_eq!(
+        revenue,
+        vec![
+            Decimal::from(180),
+            "175.50".parse().unwrap(),
+            Decimal::from(100)
+        ]
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date};
+    use axum::extract::{Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::analytics::get_pace_handler;
+    use hotel_app_be::models::{PaceQuery, PaceReport};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (841, 'Pace Queen', 'PQN', 100.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (8401, 'P101', 841, 'available', 1),
+                    (8402, 'P102', 841, 'available', 1),
+                    (8403, 'P103', 841, 'available', 0)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (8401, 'Farah', 'Ismail', 'Farah Ismail')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+                (8401, 'BK-PACE-1', 8401, 8401, '2026-12-10', '2026-12-13', 100.0, 300.0, 'confirmed'),
+                (8402, 'BK-PACE-2', 8401, 8402, '2026-12-11', '2026-12-12', 120.5, 120.5, 'confirmed'),
+                (8403, 'BK-PACE-3', 8401, 8402, '2026-12-12', '2026-12-14', 90.0, 180.0, 'cancelled'),
+                (8404, 'BK-PACE-4', 8401, 8403, '2026-12-10', '2026-12-12', 80.0, 160.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn report(
+        pool: &sqlx::SqlitePool,
+        start: &str,
+        end: &str,
+    ) -> Result<PaceReport, ApiError> {
+        get_pace_handler(
+            State(pool.clone()),
+            Query(PaceQuery {
+                start: start.to_string(),
+                end: end.to_string(),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    #[tokio::test]
+    async fn pace_matches_confirmed_bookings_per_date() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let pace = report(&pool, "2026-12-09", "2026-12-13").await.unwrap();
+        let days: Vec<(chrono::NaiveDate, i64, Decimal)> = pace
+            .days
+            .iter()
+            .map(|d| (d.date, d.room_nights, d.revenue))
+            .collect();
+
+        // Cancelled bookings and inactive rooms are not on the books.
+        assert_eq!(
+            days,
+            vec![
+                (date("2026-12-09"), 0, Decimal::ZERO),
+                (date("2026-12-10"), 1, Decimal::from(100)),
+                (date("2026-12-11"), 2, "220.5".parse().unwrap()),
+                (date("2026-12-12"), 1, Decimal::from(100)),
+                (date("2026-12-13"), 0, Decimal::ZERO),
+            ]
+        );
+        assert_eq!(pace.total_room_nights, 4);
+        assert_eq!(pace.total_revenue, "420.5".parse::<Decimal>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn range_is_validated() {
+        let pool = common::setup_test_db().await;
+
+        for (start, end) in [
+            ("2026-12-10", "2026-12-09"),
+            ("2026-12-10", "2027-12-11"),
+            ("12/10/2026", "2026-12-11"),
+        ] {
+            assert!(matches!(
+                report(&pool, start, end).await,
+                Err(ApiError::BadRequest(_))
+            ));
+        }
+        let single = report(&pool, "2026-12-10", "2026-12-10").await.unwrap();
+        assert_eq!(single.days.len(), 1);
+    }
+}
@@ -0,0 +1,335 @@
+//! Tests for booking quotes.
+//!
+//! Booking creation and quotes both price a stay with
+//! `booking_svc::price_new_booking`, which wraps `price_stay`; the pure tests
+//! pin down that pricing and the quote's taxes and fees. Booking creation
+//! itself runs PostgreSQL-only SQL, so the SQLite tests check quotes against
+//! amounts worked out by hand.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::models::NightlyRate;
+use hotel_app_be::services::booking::{MANUAL_RATE_CODE, price_stay, quote_charges};
+use hotel_app_be::services::tax::TaxContext;
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+fn eight_percent() -> TaxContext {
+    TaxContext::inclusive(Decimal::from(8))
+}
+
+#[test]
+fn room_price_is_charged_per_night_with_tax_included() {
+    let price = price_stay(
+        date("2026-09-01"),
+        date("2026-09-03"),
+        dec("120"),
+        None,
+        None,
+        None,
+        &eight_percent(),
+    );
+
+    assert_eq!(price.billable_nights, 2);
+    assert_eq!(price.nights.len(), 2);
+    assert_eq!(price.nights[1].date, date("2026-09-02"));
+    assert_eq!(price.subtotal, dec("240"));
+    assert_eq!(price.taxes.service_tax, dec("17.78"));
+    assert_eq!(price.taxes.gross, dec("240"));
+    assert_eq!(price.daily_rates, None);
+}
+
+#[test]
+fn plan_rates_win_and_are_stored_per_night() {
+    let plan_night = |day: &str, price: &str| NightlyRate {
+        date: date(day),
+        price: dec(price),
+        rate_plan_code: "WKND".to_string(),
+        is_base_rate: false,
+    };
+    let price = price_stay(
+        date("2026-09-04"),
+        date("2026-09-06"),
+        dec("120"),
+        Some(vec![
+            plan_night("2026-09-04", "150"),
+            plan_night("2026-09-05", "160"),
+        ]),
+        None,
+        None,
+        &eight_percent(),
+    );
+
+    assert_eq!(price.room_rate, dec("150"));
+    assert_eq!(price.taxes.gross, dec("310"));
+    let stored = price.daily_rates.expect("plan rates are stored");
+    assert_eq!(stored["2026-09-05"], serde_json::json!(160.0));
+}
+
+#[test]
+fn overrides_and_daily_rates_price_manually() {
+    let overridden = price_stay(
+        date("2026-09-01"),
+        date("2026-09-03"),
+        dec("120"),
+        None,
+        Some(99.5),
+        None,
+        &eight_percent(),
+    );
+    assert_eq!(overridden.taxes.gross, dec("199"));
+    assert!(
+        overridden
+            .nights
+            .iter()
+            .all(|n| n.rate_plan_code == MANUAL_RATE_CODE)
+    );
+
+    let daily = serde_json::json!({ "2026-09-02": 110.0, "2026-09-01": 100.0 });
+    let priced = price_stay(
+        date("2026-09-01"),
+        date("2026-09-03"),
+        dec("120"),
+        None,
+        None,
+        Some(&daily),
+        &eight_percent(),
+    );
+    assert_eq!(priced.subtotal, dec("210"));
+    assert_eq!(priced.nights[0].date, date("2026-09-01"));
+    assert_eq!(priced.daily_rates, Some(daily));
+}
+
+#[test]
+fn same_day_stay_is_charged_one_night() {
+    let price = price_stay(
+        date("2026-09-01"),
+        date("2026-09-01"),
+        dec("80"),
+        None,
+        None,
+        None,
+        &eight_percent(),
+    );
+    assert_eq!(price.billable_nights, 1);
+    assert_eq!(price.nights.len(), 1);
+    assert_eq!(price.taxes.gross, dec("80"));
+}
+
+#[test]
+fn tourism_tax_and_extra_beds_are_charged_on_top() {
+    let price = price_stay(
+        date("2026-09-01"),
+        date("2026-09-04"),
+        dec("120"),
+        None,
+        None,
+        None,
+        &eight_percent(),
+    );
+
+    let (taxes, fees) = quote_charges(&price, true, Some(30.0), Some(1), Some(25.0));
+    assert_eq!(taxes.len(), 2);
+    assert!(taxes[0].included);
+    assert_eq!(taxes[0].amount, price.taxes.service_tax);
+    assert_eq!(
+        (taxes[1].code.as_str(), taxes[1].amount),
+        ("tourism_tax", dec("30"))
+    );
+    assert!(!taxes[1].included);
+    assert_eq!(fees.len(), 1);
+    assert_eq!(fees[0].amount, dec("75"));
+
+    // Tourism tax only applies to tourists.
+    let (taxes, fees) = quote_charges(&price, false, Some(30.0), None, None);
+    assert_eq!(taxes.len(), 1);
+    assert!(fees.is_empty());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date, dec};
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::quote_booking_handler;
+    use hotel_app_be::models::{BookingQuote, BookingQuoteInput};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (821, 'Quote Double', 'QDBL', 120.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, custom_price, status, is_active)
+             VALUES (8201, 'Q101', 821, NULL, 'available', 1),
+                    (8202, 'Q102', 821, 150.0, 'available', 1),
+                    (8203, 'Q103', 821, NULL, 'maintenance', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (8201, 'Nadia', 'Aziz', 'Nadia Aziz')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (8201, 'BK-Q-1', 8201, 8201, '2026-10-05', '2026-10-07', 120.0, 240.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn input(room_id: i64, check_in: &str, check_out: &str) -> BookingQuoteInput {
+        BookingQuoteInput {
+            guest_id: Some(8201),
+            room_id,
+            check_in_date: check_in.to_string(),
+            check_out_date: check_out.to_string(),
+            adults: Some(2),
+            children: None,
+            infants: None,
+            is_tourist: None,
+            tourism_tax_amount: None,
+            extra_bed_count: None,
+            extra_bed_charge: None,
+            room_rate_override: None,
+            daily_rates: None,
+        }
+    }
+
+    async fn quote(
+        pool: &sqlx::SqlitePool,
+        input: BookingQuoteInput,
+    ) -> Result<BookingQuote, ApiError> {
+        quote_booking_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Json(input),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    async fn booking_count(pool: &sqlx::SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM bookings")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn quote_total_matches_the_booking_price() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        sqlx::query("UPDATE system_settings SET value = '50' WHERE key = 'day_use_rate_percent'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Room prices include the default 8% service tax.
+        for (room_id, rate_override, check_out, nightly, total, service_tax) in [
+            (8201, None, "2026-10-13", "120", "360", "26.67"),
+            // Q102's custom price wins over the room type's.
+            (8202, None, "2026-10-13", "150", "450", "33.33"),
+            (8201, Some(99.5), "2026-10-13", "99.5", "298.5", "22.11"),
+            // Day use at half the room price.
+            (8202, None, "2026-10-10", "75", "75", "5.56"),
+        ] {
+            let mut request = input(room_id, "2026-10-10", check_out);
+            request.room_rate_override = rate_override;
+            let quoted = quote(&pool, request).await.unwrap();
+
+            assert!(
+                quoted.nightly_rates.iter().all(|n| n.price == dec(nightly)),
+                "room {} until {}: {:?}",
+                room_id,
+                check_out,
+                quoted.nightly_rates
+            );
+            assert_eq!(quoted.subtotal, dec(total));
+            assert_eq!(quoted.total_amount, dec(total));
+            assert_eq!(quoted.taxes.len(), 1);
+            assert_eq!(quoted.taxes[0].amount, dec(service_tax));
+            assert!(quoted.taxes[0].included);
+            assert!(quoted.fees.is_empty());
+            assert_eq!(quoted.amount_due, dec(total));
+            assert!(quoted.available);
+        }
+    }
+
+    #[tokio::test]
+    async fn quote_reports_availability_and_writes_nothing() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let before = booking_count(&pool).await;
+
+        let booked = quote(&pool, input(8201, "2026-10-06", "2026-10-08"))
+            .await
+            .unwrap();
+        assert!(!booked.available);
+        assert_eq!(booked.conflicting_booking_numbers, vec!["BK-Q-1"]);
+        // Still priced, so the guest can compare other dates.
+        assert_eq!(booked.total_amount, Decimal::from(240));
+
+        let maintenance = quote(&pool, input(8203, "2026-10-10", "2026-10-11"))
+            .await
+            .unwrap();
+        assert!(!maintenance.available);
+        assert!(
+            maintenance
+                .unavailable_reason
+                .unwrap()
+                .contains("maintenance")
+        );
+
+        // Arriving on the day the existing stay leaves is fine.
+        let free = quote(&pool, input(8201, "2026-10-07", "2026-10-09"))
+            .await
+            .unwrap();
+        assert!(free.available);
+
+        assert_eq!(booking_count(&pool).await, before);
+    }
+
+    #[tokio::test]
+    async fn invalid_quotes_are_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            quote(&pool, input(9999, "2026-10-10", "2026-10-11")).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            quote(&pool, input(8201, "2026-10-11", "2026-10-10")).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        let mut crowded = input(8201, "2026-10-10", "2026-10-11");
+        crowded.adults = Some(3);
+        assert!(matches!(
+            quote(&pool, crowded).await,
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! Tests for the booking repository trait.
+//!
+//! The conflict rules run against an in-memory repository under any feature;
+//! the SQL implementation is checked against SQLite like the other
+//! integration tests.
+
+mod common;
+
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use hotel_app_be::ApiError;
+use hotel_app_be::models::Booking;
+use hotel_app_be::repositories::booking::{
+    BookingFilter, BookingRepository, NewBooking, blocks_stay, stays_overlap,
+};
+use hotel_app_be::services::booking::check_conflict;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[derive(Default)]
+struct InMemoryBookingRepository {
+    bookings: Mutex<Vec<Booking>>,
+}
+
+impl BookingRepository for InMemoryBookingRepository {
+    async fn create(&self, booking: &NewBooking) -> Result<Booking, ApiError> {
+        let mut bookings = self.bookings.lock().unwrap();
+        let created = Booking {
+            id: bookings.len() as i64 + 1,
+            booking_number: booking.booking_number.clone(),
+            guest_id: booking.guest_id,
+            room_id: booking.room_id,
+            check_in_date: booking.check_in_date,
+            check_out_date: booking.check_out_date,
+            room_rate: booking.room_rate,
+            subtotal: booking.total_amount,
+            total_amount: booking.total_amount,
+            status: booking.status.clone(),
+            created_by: booking.created_by,
+            ..Booking::default()
+        };
+        bookings.push(created.clone());
+        Ok(created)
+    }
+
+    async fn find(&self, id: i64) -> Result<Option<Booking>, ApiError> {
+        let bookings = self.bookings.lock().unwrap();
+        Ok(bookings.iter().find(|b| b.id == id).cloned())
+    }
+
+    async fn list(&self, filter: &BookingFilter) -> Result<Vec<Booking>, ApiError> {
+        let bookings = self.bookings.lock().unwrap();
+        Ok(bookings
+            .iter()
+            .filter(|b| filter.guest_id.is_none_or(|id| b.guest_id == id))
+            .filter(|b| filter.room_id.is_none_or(|id| b.room_id == id))
+            .filter(|b| filter.status.as_deref().is_none_or(|s| b.status == s))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_status(&self, id: i64, status: &str) -> Result<(), ApiError> {
+        let mut bookings = self.bookings.lock().unwrap();
+        let booking = bookings
+            .iter_mut()
+            .find(|b| b.id == id)
+            .ok_or_else(|| ApiError::NotFound("Booking not found".to_string()))?;
+        booking.status = status.to_string();
+        Ok(())
+    }
+
+    async fn check_conflict(
+        &self,
+        room_id: i64,
+        check_in: NaiveDate,
+        check_out: NaiveDate,
+    ) -> Result<Vec<String>, ApiError> {
+        let bookings = self.bookings.lock().unwrap();
+        Ok(bookings
+            .iter()
+            .filter(|b| blocks_stay(b, room_id, check_in, check_out))
+            .map(|b| b.booking_number.clone())
+            .collect())
+    }
+}
+
+fn new_booking(number: &str, room_id: i64, check_in: &str, check_out: &str) -> NewBooking {
+    NewBooking {
+        booking_number: number.to_string(),
+        guest_id: 1,
+        room_id,
+        check_in_date: date(check_in),
+        check_out_date: date(check_out),
+        room_rate: 150.into(),
+        total_amount: 300.into(),
+        status: "confirmed".to_string(),
+        created_by: None,
+    }
+}
+
+async fn repo_with_stay() -> InMemoryBookingRepository {
+    let repo = InMemoryBookingRepository::default();
+    repo.create(&new_booking("BK-1", 101, "2026-08-10", "2026-08-12"))
+        .await
+        .unwrap();
+    repo
+}
+
+#[test]
+fn overlap_rule_allows_back_to_back_stays() {
+    // Against an existing stay of 10-12 August.
+    let overlaps = |check_in, check_out| {
+        stays_overlap(
+            date("2026-08-10"),
+            date("2026-08-12"),
+            date(check_in),
+            date(check_out),
+        )
+    };
+
+    assert!(!overlaps("2026-08-12", "2026-08-14"));
+    assert!(!overlaps("2026-08-08", "2026-08-10"));
+    assert!(overlaps("2026-08-11", "2026-08-13"));
+    assert!(overlaps("2026-08-09", "2026-08-11"));
+    assert!(overlaps("2026-08-08", "2026-08-14"));
+    assert!(overlaps("2026-08-10", "2026-08-11"));
+}
+
+#[tokio::test]
+async fn overlapping_stay_on_the_same_room_conflicts() {
+    let repo = repo_with_stay().await;
+
+    let check = check_conflict(&repo, 101, date("2026-08-11"), date("2026-08-13"))
+        .await
+        .unwrap();
+    assert!(!check.available);
+    assert_eq!(check.conflicting_booking_numbers, vec!["BK-1"]);
+
+    let other_room = check_conflict(&repo, 102, date("2026-08-11"), date("2026-08-13"))
+        .await
+        .unwrap();
+    assert!(other_room.available);
+}
+
+#[tokio::test]
+async fn inactive_bookings_do_not_block_the_room() {
+    let repo = repo_with_stay().await;
+
+    for status in ["voided", "checked_out", "completed"] {
+        repo.update_status(1, status).await.unwrap();
+        let check = check_conflict(&repo, 101, date("2026-08-10"), date("2026-08-12"))
+            .await
+            .unwrap();
+        assert!(check.available, "{status} should not block the room");
+    }
+
+    repo.update_status(1, "checked_in").await.unwrap();
+    let check = check_conflict(&repo, 101, date("2026-08-10"), date("2026-08-12"))
+        .await
+        .unwrap();
+    assert!(!check.available);
+}
+
+#[tokio::test]
+async fn reversed_dates_are_rejected() {
+    let repo = repo_with_stay().await;
+
+    assert!(matches!(
+        check_conflict(&repo, 101, date("2026-08-12"), date("2026-08-10")).await,
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date, new_booking};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::repositories::booking::{
+        BookingFilter, BookingRepository, SqlBookingRepository,
+    };
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (951, 'Repo Double', 'RDBL', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (101, 'R101', 951, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (1, 'Mei', 'Tan', 'Mei Tan')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sql_repository_round_trips_and_detects_conflicts() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let repo = SqlBookingRepository::new(&pool);
+
+        let created = repo
+            .create(&new_booking("BK-REPO-1", 101, "2026-08-10", "2026-08-12"))
+            .await
+            .unwrap();
+        assert_eq!(created.booking_number, "BK-REPO-1");
+        assert_eq!(created.check_in_date, date("2026-08-10"));
+
+        let conflicts = repo
+            .check_conflict(101, date("2026-08-11"), date("2026-08-13"))
+            .await
+            .unwrap();
+        assert_eq!(conflicts, vec!["BK-REPO-1"]);
+
+        repo.update_status(created.id, "voided").await.unwrap();
+        assert!(
+            repo.check_conflict(101, date("2026-08-11"), date("2026-08-13"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let voided = repo
+            .list(&BookingFilter {
+                status: Some("voided".to_string()),
+                ..BookingFilter::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(voided.len(), 1);
+        assert_eq!(voided[0].id, created.id);
+
+        assert!(matches!(
+            repo.update_status(424242, "confirmed").await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
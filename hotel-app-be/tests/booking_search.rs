@@ -0,0 +1,112 @@
+//! Integration tests for the front-desk booking search.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::search_bookings_handler;
+    use hotel_app_be::models::BookingSearchParams;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (601, 'Search Twin', 'STWN', 90.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (6001, '612', 601, 'available', 1), (6002, '1612', 601, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name, email, phone)
+             VALUES
+             (6001, 'Aisha', 'Rahman', 'Aisha Rahman', 'aisha@example.com', '+60 12-345 6789'),
+             (6002, 'Ben', 'Lim', 'Ben Lim', 'ben@example.com', '+60 19-888 1212')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (6001, 'BK-SR-0001', 6001, 6001, '2026-06-01', '2026-06-03', 90.0, 180.0, 'confirmed'),
+             (6002, 'BK-SR-0002', 6002, 6002, '2026-06-02', '2026-06-04', 90.0, 180.0, 'confirmed'),
+             (6003, 'BK-SR-0003', 6001, 6002, '2026-05-01', '2026-05-02', 90.0, 90.0, 'voided')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn search(pool: &sqlx::SqlitePool, q: &str) -> Vec<String> {
+        search_bookings_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(BookingSearchParams {
+                q: Some(q.to_string()),
+                page: None,
+                page_size: None,
+            }),
+        )
+        .await
+        .expect("search should succeed")
+        .0
+        .data
+        .into_iter()
+        .map(|b| b.booking_number)
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn matches_partial_phone_ignoring_separators() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert_eq!(search(&pool, "3456789").await, vec!["BK-SR-0001"]);
+        assert_eq!(search(&pool, "12-345").await, vec!["BK-SR-0001"]);
+    }
+
+    #[tokio::test]
+    async fn exact_room_number_ranks_first() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // "612" is an exact match for room 612 and a partial one for room 1612;
+        // the voided booking in 1612 is excluded.
+        assert_eq!(search(&pool, "612").await, vec!["BK-SR-0001", "BK-SR-0002"]);
+    }
+
+    #[tokio::test]
+    async fn short_queries_return_nothing() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(search(&pool, "6").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn wildcards_in_the_query_match_literally() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // As patterns these would match every booking number.
+        assert!(search(&pool, "0_1").await.is_empty());
+        assert!(search(&pool, "%%").await.is_empty());
+    }
+}
@@ -16,7 +16,8 @@ use hotel_app_be::services::booking;
 
 #[test]
 fn booking_number_has_correct_format() {
-    let n = booking::generate_booking_number();
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 4, 18).unwrap();
+    let n = booking::generate_booking_number_for_date(date);
 
     // Expected: "BK-YYYYMMDD-XXXXXXXX"
     let parts: Vec<&str> = n.splitn(3, '-').collect();
@@ -31,9 +32,9 @@ fn booking_number_has_correct_format() {
         8,
         "date segment should be 8 digits (YYYYMMDD): {n}"
     );
-    assert!(
-        parts[1].chars().all(|c| c.is_ascii_digit()),
-        "date segment must be all digits: {n}"
+    assert_eq!(
+        parts[1], "20260418",
+        "date segment should match the input date: {n}"
     );
     assert_eq!(parts[2].len(), 8, "UUID suffix should be 8 hex chars: {n}");
     assert!(
@@ -44,8 +45,9 @@ fn booking_number_has_correct_format() {
 
 #[test]
 fn booking_numbers_are_unique() {
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 4, 18).unwrap();
     let numbers: std::collections::HashSet<String> = (0..200)
-        .map(|_| booking::generate_booking_number())
+        .map(|_| booking::generate_booking_number_for_date(date))
         .collect();
     assert_eq!(
         numbers.len(),
@@ -0,0 +1,132 @@
+//! Tests for the booking sources and the channel report.
+//!
+//! Resolving a booking's source, validating the source settings and the
+//! average daily rate are pure and run under any feature. Reading the
+//! configured sources and the per-channel breakdown are SQLite-backed and
+//! gated accordingly.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::booking_sources::{
+    BookingSources, average_daily_rate, parse_sources, validate_setting,
+};
+use rust_decimal::Decimal;
+
+#[test]
+fn a_booking_without_a_source_gets_the_default() {
+    let sources = BookingSources::default();
+    assert_eq!(sources.resolve(None).unwrap(), "walk_in");
+    assert_eq!(sources.resolve(Some("  ")).unwrap(), "walk_in");
+}
+
+#[test]
+fn only_listed_sources_are_accepted() {
+    let sources = BookingSources {
+        sources: vec!["direct".to_string(), "expedia".to_string()],
+        default_source: "walk_in".to_string(),
+    };
+    assert_eq!(sources.resolve(Some(" Expedia ")).unwrap(), "expedia");
+    // The default is accepted even when it is not listed.
+    assert_eq!(sources.resolve(Some("walk_in")).unwrap(), "walk_in");
+    assert!(matches!(
+        sources.resolve(Some("agoda")),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn source_settings_are_validated() {
+    assert_eq!(
+        parse_sources(r#"["direct", " Expedia", "direct"]"#),
+        Some(vec!["direct".to_string(), "expedia".to_string()])
+    );
+    assert_eq!(parse_sources("[]"), None);
+    assert_eq!(parse_sources(r#"["booking.com"]"#), None);
+    assert_eq!(parse_sources("direct"), None);
+
+    assert!(validate_setting("booking_sources", r#"["direct"]"#).is_ok());
+    assert!(validate_setting("booking_sources", r#"["walk in"]"#).is_err());
+    assert!(validate_setting("default_booking_source", "phone").is_ok());
+    assert!(validate_setting("default_booking_source", "").is_err());
+    assert!(validate_setting("market_codes", "anything").is_ok());
+}
+
+#[test]
+fn average_daily_rate_is_revenue_per_room_night() {
+    assert_eq!(
+        average_daily_rate(Decimal::from(1000), 3),
+        Decimal::new(33333, 2)
+    );
+    assert_eq!(average_daily_rate(Decimal::from(500), 0), Decimal::ZERO);
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::NaiveDate;
+    use hotel_app_be::services::booking_sources::{BookingSources, channel_breakdown, configured};
+    use rust_decimal::Decimal;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn migrated_settings_match_the_defaults() {
+        let pool = common::setup_test_db().await;
+        assert_eq!(configured(&pool).await, BookingSources::default());
+
+        sqlx::query(
+            "UPDATE system_settings SET value = 'direct' WHERE key = 'default_booking_source'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(configured(&pool).await.default_source, "direct");
+    }
+
+    #[tokio::test]
+    async fn bookings_are_grouped_by_channel() {
+        let pool = common::setup_test_db().await;
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (996, 'Channel Queen', 'CHQ', 100.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9961, 'S01', 996, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9960, 'Omar', 'Haddad')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, source)
+             VALUES
+             (9961, 'BK-CH-1', 9960, 9961, '2026-07-01', '2026-07-04', 100.0, 300.0, 'checked_out', 'expedia'),
+             (9962, 'BK-CH-2', 9960, 9961, '2026-07-05', '2026-07-05', 60.0, 60.0, 'checked_out', 'expedia'),
+             (9963, 'BK-CH-3', 9960, 9961, '2026-07-06', '2026-07-08', 90.0, 180.0, 'confirmed', NULL),
+             (9964, 'BK-CH-4', 9960, 9961, '2026-07-10', '2026-07-12', 90.0, 180.0, 'cancelled', 'direct'),
+             (9965, 'BK-CH-5', 9960, 9961, '2026-08-01', '2026-08-02', 90.0, 90.0, 'confirmed', 'direct')",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        let channels = channel_breakdown(&pool, date("2026-07-01"), date("2026-07-31"), "walk_in")
+            .await
+            .unwrap();
+        assert_eq!(
+            channels
+                .iter()
+                .map(|c| (
+                    c.source.as_str(),
+                    c.bookings,
+                    c.room_nights,
+                    c.revenue,
+                    c.adr
+                ))
+                .collect::<Vec<_>>(),
+            [
+                // The day-use stay counts as one room-night.
+                ("expedia", 2, 4, Decimal::from(360), Decimal::from(90)),
+                ("walk_in", 1, 2, Decimal::from(180), Decimal::from(90)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Tests for `services::booking_trends`
+//!
+//! Month bucketing is pure and runs under any feature. Totals are read
+//! against SQLite and gated accordingly.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::booking_trends::{MonthlyTrend, monthly_trends, trend_months};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn trend_covers_twelve_months_across_the_year_end() {
+    let months = trend_months(date("2026-02-17"));
+
+    assert_eq!(months.len(), 12);
+    assert_eq!(months.first(), Some(&date("2025-03-01")));
+    assert_eq!(months.last(), Some(&date("2026-02-01")));
+    assert!(months.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn months_without_bookings_are_zero() {
+    let totals = [MonthlyTrend {
+        month: date("2026-08-01"),
+        bookings: 3,
+        revenue: Decimal::from(900),
+    }];
+
+    let trends = monthly_trends(date("2026-10-16"), &totals);
+
+    assert_eq!(trends.len(), 12);
+    assert_eq!(trends[9], totals[0]);
+    let others: Vec<&MonthlyTrend> = trends
+        .iter()
+        .filter(|t| t.month != totals[0].month)
+        .collect();
+    assert!(
+        others
+            .iter()
+            .all(|t| t.bookings == 0 && t.revenue == Decimal::ZERO)
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date};
+    use hotel_app_be::services::booking_trends::booking_trends;
+    use rust_decimal::Decimal;
+
+    #[tokio::test]
+    async fn totals_are_grouped_by_month_of_check_in() {
+        let pool = common::setup_test_db().await;
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (994, 'Trend Single', 'TSG', 100.0, 1)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9941, 'T941', 994, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9940, 'Ravi', 'Kumar', 'Ravi Kumar')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9941, 'BK-TR-1', 9940, 9941, '2026-08-03', '2026-08-05', 100.0, 200.0, 'checked_out'),
+             (9942, 'BK-TR-2', 9940, 9941, '2026-08-30', '2026-09-02', 100.0, 300.5, 'checked_out'),
+             (9943, 'BK-TR-3', 9940, 9941, '2026-10-01', '2026-10-02', 100.0, 100.0, 'confirmed'),
+             (9944, 'BK-TR-4', 9940, 9941, '2026-10-05', '2026-10-06', 100.0, 100.0, 'voided'),
+             (9945, 'BK-TR-5', 9940, 9941, '2025-10-20', '2025-10-21', 100.0, 100.0, 'checked_out')",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        let trends = booking_trends(&pool, date("2026-10-16")).await.unwrap();
+
+        assert_eq!(trends.len(), 12);
+        assert_eq!(trends[0].month, date("2025-11-01"));
+        let by_month: Vec<(i64, Decimal)> =
+            trends.iter().map(|t| (t.bookings, t.revenue)).collect();
+        assert_eq!(by_month[9], (2, "500.5".parse().unwrap()));
+        assert_eq!(by_month[10], (0, Decimal::ZERO));
+        assert_eq!(by_month[11], (1, Decimal::from(100)));
+        assert_eq!(trends.iter().map(|t| t.bookings).sum::<i64>(), 3);
+    }
+}
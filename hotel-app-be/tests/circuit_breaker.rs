@@ -0,0 +1,145 @@
+//! Tests for the outbound-call circuit breaker and retries.
+//!
+//! The breaker keeps its state in memory and takes the current instant as
+//! an argument, so these run under any feature without a database.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use hotel_app_be::services::circuit_breaker::{
+    BreakerPolicy, BreakerState, COOLDOWN_SETTING, CircuitBreaker, FAILURE_THRESHOLD_SETTING,
+    MAX_RETRIES_SETTING, validate_setting,
+};
+
+const WEBHOOK: &str = "https://hooks.example.com/pms";
+
+fn policy(failure_threshold: u32, max_retries: u32) -> BreakerPolicy {
+    BreakerPolicy {
+        max_retries,
+        failure_threshold,
+        cooldown: Duration::from_secs(60),
+        retry_backoff: Duration::ZERO,
+    }
+}
+
+#[test]
+fn consecutive_failures_open_the_breaker() {
+    let breaker = CircuitBreaker::new(policy(3, 0));
+    let now = Instant::now();
+
+    breaker.record_failure(WEBHOOK, now);
+    breaker.record_failure(WEBHOOK, now);
+    assert_eq!(breaker.state(WEBHOOK, now), BreakerState::Closed);
+    assert!(breaker.try_acquire(WEBHOOK, now));
+
+    breaker.record_failure(WEBHOOK, now);
+    assert_eq!(breaker.state(WEBHOOK, now), BreakerState::Open);
+    assert!(!breaker.try_acquire(WEBHOOK, now + Duration::from_secs(30)));
+
+    // Each endpoint has its own breaker.
+    assert_eq!(
+        breaker.state("https://other.example.com", now),
+        BreakerState::Closed
+    );
+}
+
+#[test]
+fn a_success_resets_the_failure_count() {
+    let breaker = CircuitBreaker::new(policy(3, 0));
+    let now = Instant::now();
+
+    breaker.record_failure(WEBHOOK, now);
+    breaker.record_failure(WEBHOOK, now);
+    breaker.record_success(WEBHOOK);
+    breaker.record_failure(WEBHOOK, now);
+    assert_eq!(breaker.state(WEBHOOK, now), BreakerState::Closed);
+}
+
+#[test]
+fn cooldown_half_opens_for_a_single_probe() {
+    let breaker = CircuitBreaker::new(policy(2, 0));
+    let opened = Instant::now();
+    breaker.record_failure(WEBHOOK, opened);
+    breaker.record_failure(WEBHOOK, opened);
+
+    let after_cooldown = opened + Duration::from_secs(60);
+    assert_eq!(
+        breaker.state(WEBHOOK, after_cooldown),
+        BreakerState::HalfOpen
+    );
+    assert!(breaker.try_acquire(WEBHOOK, after_cooldown));
+    // Only one probe at a time.
+    assert!(!breaker.try_acquire(WEBHOOK, after_cooldown));
+
+    // A failed probe opens the breaker for another cooldown.
+    breaker.record_failure(WEBHOOK, after_cooldown);
+    assert_eq!(
+        breaker.state(WEBHOOK, after_cooldown + Duration::from_secs(59)),
+        BreakerState::Open
+    );
+
+    // A successful probe closes it.
+    let later = after_cooldown + Duration::from_secs(60);
+    assert!(breaker.try_acquire(WEBHOOK, later));
+    breaker.record_success(WEBHOOK);
+    assert_eq!(breaker.state(WEBHOOK, later), BreakerState::Closed);
+}
+
+#[tokio::test]
+async fn failed_calls_are_retried_a_bounded_number_of_times() {
+    let breaker = CircuitBreaker::new(policy(10, 2));
+    let attempts = AtomicU32::new(0);
+
+    let result: Result<(), String> = breaker
+        .call(WEBHOOK, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err("connection refused".to_string())
+        })
+        .await;
+
+    assert_eq!(result.unwrap_err(), "connection refused");
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+    // A call that recovers on retry succeeds and clears the failures.
+    let attempts = AtomicU32::new(0);
+    let result = breaker
+        .call(WEBHOOK, || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("timed out".to_string()),
+                _ => Ok("sent"),
+            }
+        })
+        .await;
+    assert_eq!(result, Ok("sent"));
+    assert_eq!(breaker.state(WEBHOOK, Instant::now()), BreakerState::Closed);
+}
+
+#[tokio::test]
+async fn an_open_breaker_stops_retries_and_short_circuits() {
+    let breaker = CircuitBreaker::new(policy(2, 5));
+    let attempts = AtomicU32::new(0);
+    let failing = || async {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Err::<(), _>("HTTP 503".to_string())
+    };
+
+    let result = breaker.call(WEBHOOK, failing).await;
+    assert!(result.unwrap_err().contains("circuit open"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    // While open, the call is not made at all.
+    let result = breaker.call(WEBHOOK, failing).await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn settings_must_be_in_range() {
+    assert!(validate_setting(MAX_RETRIES_SETTING, "0").is_ok());
+    assert!(validate_setting(MAX_RETRIES_SETTING, "6").is_err());
+    assert!(validate_setting(FAILURE_THRESHOLD_SETTING, "0").is_err());
+    assert!(validate_setting(FAILURE_THRESHOLD_SETTING, " 5 ").is_ok());
+    assert!(validate_setting(COOLDOWN_SETTING, "3601").is_err());
+    assert!(validate_setting(COOLDOWN_SETTING, "soon").is_err());
+    assert!(validate_setting("outbox_webhook_url", "anything").is_ok());
+}
@@ -1,4 +1,31 @@
 //! Shared test helpers
+//!
+//! Each test binary uses only some of these.
+#![allow(dead_code)]
+
+use hotel_app_be::core::config::{self, AppConfig};
+
+/// The variables a configuration cannot load without.
+pub const REQUIRED_ENV: &[(&str, &str)] = &[
+    ("JWT_SECRET", "test-secret"),
+    ("DATABASE_URL", "postgres://hotel@localhost/hotel"),
+];
+
+/// Install the process-wide configuration from [`REQUIRED_ENV`] and
+/// `extra`, which wins where both set a variable. Only the first call in a
+/// test binary has an effect.
+pub fn install_config(extra: &[(&str, &str)]) {
+    config::install(
+        AppConfig::from_lookup(|key| {
+            extra
+                .iter()
+                .chain(REQUIRED_ENV)
+                .find(|(var, _)| *var == key)
+                .map(|(_, value)| value.to_string())
+        })
+        .unwrap(),
+    );
+}
 
 /// Create a fresh in-memory SQLite pool with all migrations applied.
 ///
@@ -22,3 +49,32 @@ pub async fn setup_test_db() -> sqlx::SqlitePool {
 
     pool
 }
+
+/// Create a SQLite pool over a WAL database file in `dir` with all
+/// migrations applied, for tests that need several connections at once.
+///
+/// The caller owns `dir` and removes it when done.
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+pub async fn setup_file_test_db(dir: &std::path::Path) -> sqlx::SqlitePool {
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+
+    std::fs::create_dir_all(dir).expect("Failed to create test database directory");
+    let options = SqliteConnectOptions::new()
+        .filename(dir.join("hotel.db"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(std::time::Duration::from_secs(5));
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .expect("Failed to create file-backed SQLite pool");
+
+    sqlx::migrate!("./database/sqlite_migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run SQLite migrations on file-backed database");
+
+    pool
+}
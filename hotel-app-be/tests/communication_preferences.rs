@@ -0,0 +1,258 @@
+//! Tests for guest communication consent.
+//!
+//! Working out which consents an update changes is pure and runs under any
+//! feature. SQLite-backed tests are gated so the default PostgreSQL build is
+//! not forced to create a database.
+
+mod common;
+
+use hotel_app_be::models::CommunicationPreferencesUpdate;
+use hotel_app_be::services::communication::{Channel, consent_changes};
+
+#[test]
+fn only_choices_that_differ_from_the_stored_ones_are_changes() {
+    let update = CommunicationPreferencesUpdate {
+        email_opt_in: Some(true),
+        sms_opt_in: Some(false),
+        marketing_opt_in: Some(true),
+    };
+
+    // Email was never chosen, so choosing it counts even though it matches
+    // the default; SMS was already off.
+    assert_eq!(
+        consent_changes((None, Some(false), false), update),
+        [(Channel::Email, true), (Channel::Marketing, true)]
+    );
+    assert!(consent_changes((Some(true), Some(false), true), update).is_empty());
+    assert!(
+        consent_changes(
+            (None, None, false),
+            CommunicationPreferencesUpdate::default()
+        )
+        .is_empty()
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::handlers::guest_portal::{
+        get_own_communication_preferences, update_own_communication_preferences,
+    };
+    use hotel_app_be::handlers::guests::{
+        get_guest_consent_history_handler, update_guest_communication_preferences_handler,
+    };
+    use hotel_app_be::models::{Booking, CommunicationPreferencesUpdate};
+    use hotel_app_be::services::communication::{
+        Channel, EMAIL_DEFAULT_SETTING, may_contact, queue_booking_confirmation,
+    };
+    use hotel_app_be::services::outbox::GUEST_BOOKING_CONFIRMATION;
+
+    const PORTAL_USER: i64 = 9801;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name, email, email_opt_in)
+             VALUES (9801, 'Aina', 'Salleh', 'Aina Salleh', 'aina@example.com', NULL),
+                    (9802, 'Ravi', 'Kumar', 'Ravi Kumar', 'ravi@example.com', 0),
+                    (9803, 'Tan', 'Mei Ling', 'Tan Mei Ling', NULL, 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, user_type)
+             VALUES (9801, 'u-9801', 'aina', 'aina@example.com', 'guest')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO user_guests
+                 (user_id, guest_id, relationship_type, can_book_for, can_view_bookings, can_modify)
+             VALUES (9801, 9801, 'self', 1, 1, 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn booking(id: i64, guest_id: i64) -> Booking {
+        Booking {
+            id,
+            guest_id,
+            booking_number: format!("BK-CP-{id}"),
+            status: "confirmed".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Queue a confirmation for `guest_id` and commit, returning whether it
+    /// was queued.
+    async fn confirm(pool: &sqlx::SqlitePool, booking_id: i64, guest_id: i64) -> bool {
+        let mut tx = pool.begin().await.unwrap();
+        let queued = queue_booking_confirmation(&mut tx, &booking(booking_id, guest_id))
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        queued
+    }
+
+    async fn confirmations(pool: &sqlx::SqlitePool) -> Vec<i64> {
+        sqlx::query_scalar("SELECT aggregate_id FROM outbox WHERE event_type = $1 ORDER BY id")
+            .bind(GUEST_BOOKING_CONFIRMATION)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn confirmation_email_is_suppressed_for_opted_out_guest() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // No preference yet: the default (email on) applies.
+        assert!(confirm(&pool, 1, 9801).await);
+        // Opted out.
+        assert!(!confirm(&pool, 2, 9802).await);
+        // Opted in but no address to send to.
+        assert!(!confirm(&pool, 3, 9803).await);
+
+        assert_eq!(confirmations(&pool).await, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn default_setting_applies_until_the_guest_chooses() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        sqlx::query("UPDATE system_settings SET value = 'false' WHERE key = $1")
+            .bind(EMAIL_DEFAULT_SETTING)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(!confirm(&pool, 1, 9801).await);
+
+        sqlx::query("UPDATE guests SET email_opt_in = 1 WHERE id = 9801")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(confirm(&pool, 2, 9801).await);
+        assert_eq!(confirmations(&pool).await, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn portal_user_can_opt_out_and_back_in() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let prefs = get_own_communication_preferences(State(pool.clone()), Extension(PORTAL_USER))
+            .await
+            .unwrap()
+            .0;
+        assert!(prefs.email_opt_in);
+        assert!(!prefs.sms_opt_in);
+
+        let prefs = update_own_communication_preferences(
+            State(pool.clone()),
+            Extension(PORTAL_USER),
+            Json(CommunicationPreferencesUpdate {
+                email_opt_in: Some(false),
+                sms_opt_in: None,
+                marketing_opt_in: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(!prefs.email_opt_in);
+        assert!(!prefs.sms_opt_in);
+        assert!(!confirm(&pool, 1, 9801).await);
+
+        let prefs = update_own_communication_preferences(
+            State(pool.clone()),
+            Extension(PORTAL_USER),
+            Json(CommunicationPreferencesUpdate {
+                email_opt_in: Some(true),
+                sms_opt_in: Some(true),
+                marketing_opt_in: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(prefs.email_opt_in && prefs.sms_opt_in);
+        assert!(confirm(&pool, 2, 9801).await);
+        assert_eq!(confirmations(&pool).await, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn marketing_consent_is_off_until_given_and_every_change_is_kept() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, user_type)
+             VALUES (9810, 'u-9810', 'front_desk', 'desk@example.com', 'staff')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(
+            !may_contact(&mut conn, 9801, Channel::Marketing, "newsletter")
+                .await
+                .unwrap()
+        );
+        drop(conn);
+
+        // The front desk records the guest's answers.
+        let staff_update = |marketing_opt_in| {
+            update_guest_communication_preferences_handler(
+                State(pool.clone()),
+                Extension(9810),
+                Path(9801),
+                Json(CommunicationPreferencesUpdate {
+                    marketing_opt_in: Some(marketing_opt_in),
+                    ..Default::default()
+                }),
+            )
+        };
+        let prefs = staff_update(true).await.unwrap().0;
+        assert!(prefs.marketing_opt_in);
+        let given_at = prefs.consent_timestamp.expect("consent time is recorded");
+        // Repeating a choice changes nothing.
+        let prefs = staff_update(true).await.unwrap().0;
+        assert_eq!(prefs.consent_timestamp, Some(given_at));
+        assert!(!staff_update(false).await.unwrap().0.marketing_opt_in);
+
+        // The guest confirms email through the portal.
+        update_own_communication_preferences(
+            State(pool.clone()),
+            Extension(PORTAL_USER),
+            Json(CommunicationPreferencesUpdate {
+                email_opt_in: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        let history = get_guest_consent_history_handler(State(pool.clone()), Path(9801))
+            .await
+            .unwrap()
+            .0;
+        let changes: Vec<_> = history
+            .iter()
+            .map(|c| (c.channel.as_str(), c.opted_in, c.changed_by))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("email", true, Some(PORTAL_USER)),
+                ("marketing", false, Some(9810)),
+                ("marketing", true, Some(9810)),
+            ]
+        );
+    }
+}
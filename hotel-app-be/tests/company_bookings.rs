@@ -0,0 +1,129 @@
+//! Tests for the bookings attributed to a company.
+//!
+//! Totalling a company's stays is pure and runs under any feature. Listing a
+//! company's bookings and the revenue report's company section are
+//! SQLite-backed and gated accordingly.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::models::CompanyBooking;
+use hotel_app_be::services::company_bookings::stay_totals;
+use rust_decimal::Decimal;
+
+fn booking(id: i64, status: &str, total: i64) -> CompanyBooking {
+    CompanyBooking {
+        id,
+        booking_number: format!("BK-{}", id),
+        guest_name: None,
+        room_number: None,
+        check_in_date: NaiveDate::from_ymd_opt(2026, 6, 1).unwrap(),
+        check_out_date: NaiveDate::from_ymd_opt(2026, 6, 3).unwrap(),
+        status: Some(status.to_string()),
+        total_amount: Decimal::from(total),
+        archived: false,
+    }
+}
+
+#[test]
+fn only_stays_count_toward_a_company_revenue() {
+    let bookings = [
+        booking(1, "checked_out", 400),
+        booking(2, "confirmed", 250),
+        booking(3, "cancelled", 300),
+        booking(4, "no_show", 200),
+    ];
+    assert_eq!(stay_totals(&bookings), (2, Decimal::from(650)));
+    assert_eq!(stay_totals(&[]), (0, Decimal::ZERO));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::NaiveDate;
+    use hotel_app_be::services::company_bookings::{company_bookings, revenue_by_company};
+    use rust_decimal::Decimal;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// Acme has two stays and a cancellation, one stay archived; Globex has
+    /// one stay; one booking is the guest's own.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (995, 'Company King', 'CKG', 100.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9951, 'C01', 995, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9950, 'Lena', 'Park')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, company_id, company_name)
+             VALUES
+             (9951, 'BK-CO-1', 9950, 9951, '2026-06-01', '2026-06-03', 100.0, 200.0, 'checked_out', 71, 'Acme'),
+             (9952, 'BK-CO-2', 9950, 9951, '2026-06-10', '2026-06-11', 150.0, 150.0, 'cancelled', 71, 'Acme'),
+             (9953, 'BK-CO-3', 9950, 9951, '2026-06-12', '2026-06-14', 120.0, 240.0, 'confirmed', 72, 'Globex'),
+             (9954, 'BK-CO-4', 9950, 9951, '2026-06-20', '2026-06-21', 90.0, 90.0, 'confirmed', NULL, NULL),
+             (9955, 'BK-CO-5', 9950, 9951, '2026-05-01', '2026-05-05', 100.0, 400.0, 'checked_out', 71, 'Acme')",
+            "INSERT INTO bookings_archive SELECT b.*, CURRENT_TIMESTAMP FROM bookings b WHERE b.id = 9955",
+            "DELETE FROM bookings WHERE id = 9955",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn a_company_lists_its_bookings_newest_first() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let bookings = company_bookings(&pool, 71, None, None).await.unwrap();
+        assert_eq!(
+            bookings
+                .iter()
+                .map(|b| (b.booking_number.as_str(), b.archived))
+                .collect::<Vec<_>>(),
+            [("BK-CO-2", false), ("BK-CO-1", false), ("BK-CO-5", true)]
+        );
+        assert_eq!(bookings[1].guest_name.as_deref(), Some("Lena Park"));
+        assert_eq!(bookings[1].room_number.as_deref(), Some("C01"));
+        assert_eq!(bookings[1].total_amount, Decimal::from(200));
+
+        // Check-ins are limited to the requested range.
+        let june = company_bookings(
+            &pool,
+            71,
+            Some(date("2026-06-01")),
+            Some(date("2026-06-30")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(june.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn revenue_is_grouped_by_company() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let by_company = revenue_by_company(&pool, date("2026-05-01"), date("2026-06-30"))
+            .await
+            .unwrap();
+        assert_eq!(
+            by_company
+                .iter()
+                .map(|c| (
+                    c.company_id,
+                    c.company_name.as_deref(),
+                    c.bookings,
+                    c.revenue
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (71, Some("Acme"), 2, Decimal::from(600)),
+                (72, Some("Globex"), 1, Decimal::from(240)),
+            ]
+        );
+    }
+}
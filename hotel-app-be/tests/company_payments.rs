@@ -0,0 +1,83 @@
+//! Tests for splitting one company payment across its open invoices.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::company_payments::{
+    OpenInvoice, PlannedAllocation, allocate_explicit, allocate_oldest_first,
+};
+use rust_decimal::Decimal;
+
+fn invoice(id: i64, amount: i64, paid: i64) -> OpenInvoice {
+    OpenInvoice {
+        ledger_id: id,
+        invoice_number: Some(format!("INV-202604-{id:04}")),
+        amount: Decimal::from(amount),
+        paid_amount: Decimal::from(paid),
+    }
+}
+
+fn share(id: i64, amount: i64) -> PlannedAllocation {
+    PlannedAllocation {
+        ledger_id: id,
+        amount: Decimal::from(amount),
+    }
+}
+
+#[test]
+fn oldest_first_settles_the_first_invoice_before_the_second() {
+    // Invoice 1 is older and already part-paid.
+    let open = [invoice(1, 500, 100), invoice(2, 300, 0)];
+
+    let plan = allocate_oldest_first(Decimal::from(550), &open);
+
+    assert_eq!(plan.allocations, vec![share(1, 400), share(2, 150)]);
+    assert_eq!(plan.credit, Decimal::ZERO);
+}
+
+#[test]
+fn oldest_first_overpayment_becomes_credit() {
+    let open = [invoice(1, 200, 0), invoice(2, 300, 0)];
+
+    let plan = allocate_oldest_first(Decimal::from(600), &open);
+
+    assert_eq!(plan.allocations, vec![share(1, 200), share(2, 300)]);
+    assert_eq!(plan.credit, Decimal::from(100));
+}
+
+#[test]
+fn explicit_allocation_is_applied_as_given() {
+    let open = [invoice(1, 500, 0), invoice(2, 300, 0)];
+
+    // Pay the newer invoice in full and part of the older one.
+    let plan =
+        allocate_explicit(Decimal::from(400), &open, &[share(2, 300), share(1, 50)]).unwrap();
+
+    assert_eq!(plan.allocations, vec![share(2, 300), share(1, 50)]);
+    assert_eq!(plan.credit, Decimal::from(50));
+}
+
+#[test]
+fn explicit_allocation_is_validated() {
+    let open = [invoice(1, 500, 400), invoice(2, 300, 0)];
+    let amount = Decimal::from(1000);
+
+    for requested in [
+        vec![],
+        vec![share(1, 150)],
+        vec![share(3, 10)],
+        vec![share(2, 100), share(2, 100)],
+        vec![share(2, 0)],
+    ] {
+        assert!(
+            matches!(
+                allocate_explicit(amount, &open, &requested),
+                Err(ApiError::BadRequest(_))
+            ),
+            "{requested:?} should be rejected"
+        );
+    }
+
+    assert!(matches!(
+        allocate_explicit(Decimal::from(200), &open, &[share(1, 100), share(2, 150)]),
+        Err(ApiError::BadRequest(_))
+    ));
+}
@@ -0,0 +1,87 @@
+//! Tests for the in-flight request cap.
+//!
+//! Runs under any feature: the middleware is exercised on a small router
+//! whose handler blocks until the test releases it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::{Router, body::Body, http::Request, http::StatusCode, routing::get};
+use hotel_app_be::core::concurrency_limit::{ConcurrencyLimit, limit_concurrency};
+use tokio::sync::Semaphore;
+use tower::ServiceExt;
+
+/// A router capped at `max` whose `/slow` handler waits on `gate`.
+fn router(max: usize, gate: Arc<Semaphore>, entered: Arc<AtomicUsize>) -> Router {
+    Router::new()
+        .route(
+            "/slow",
+            get(move || {
+                let gate = gate.clone();
+                let entered = entered.clone();
+                async move {
+                    entered.fetch_add(1, Ordering::SeqCst);
+                    gate.acquire().await.unwrap().forget();
+                    "done"
+                }
+            }),
+        )
+        .route("/health", get(|| async { "ok" }))
+        .layer(axum::middleware::from_fn_with_state(
+            ConcurrencyLimit::new(max),
+            limit_concurrency,
+        ))
+}
+
+async fn status(app: &Router, path: &str) -> StatusCode {
+    app.clone()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn requests_beyond_the_limit_get_503() {
+    let gate = Arc::new(Semaphore::new(0));
+    let entered = Arc::new(AtomicUsize::new(0));
+    let app = router(2, gate.clone(), entered.clone());
+
+    let in_flight: Vec<_> = (0..2)
+        .map(|_| {
+            let app = app.clone();
+            tokio::spawn(async move { status(&app, "/slow").await })
+        })
+        .collect();
+    while entered.load(Ordering::SeqCst) < 2 {
+        tokio::task::yield_now().await;
+    }
+
+    assert_eq!(status(&app, "/slow").await, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(status(&app, "/health").await, StatusCode::OK);
+
+    gate.add_permits(2);
+    for request in in_flight {
+        assert_eq!(request.await.unwrap(), StatusCode::OK);
+    }
+
+    // Capacity is back once the in-flight requests finish.
+    gate.add_permits(1);
+    assert_eq!(status(&app, "/slow").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn requests_within_the_limit_succeed() {
+    let gate = Arc::new(Semaphore::new(3));
+    let app = router(3, gate, Arc::new(AtomicUsize::new(0)));
+
+    let requests: Vec<_> = (0..3)
+        .map(|_| {
+            let app = app.clone();
+            tokio::spawn(async move { status(&app, "/slow").await })
+        })
+        .collect();
+    for request in requests {
+        assert_eq!(request.await.unwrap(), StatusCode::OK);
+    }
+}
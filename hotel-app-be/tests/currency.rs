@@ -0,0 +1,62 @@
+//! Tests for `services::currency`
+//!
+//! Formatting and validation are pure and run under any feature. Reading
+//! the setting is SQLite-backed and gated accordingly.
+
+mod common;
+
+use hotel_app_be::services::currency::{format_money, parse_currency, validate_setting};
+use rust_decimal::Decimal;
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn amounts_are_formatted_in_the_currency() {
+    assert_eq!(format_money(dec("1234.5"), "USD"), "$1,234.50");
+    assert_eq!(format_money(dec("1234567.891"), "MYR"), "RM 1,234,567.89");
+    assert_eq!(format_money(dec("-5"), "EUR"), "-€5.00");
+    assert_eq!(format_money(dec("950"), "CHF"), "CHF 950.00");
+}
+
+#[test]
+fn amounts_are_rounded_to_the_currency_minor_units() {
+    assert_eq!(format_money(dec("12500.5"), "JPY"), "¥12,501");
+    assert_eq!(format_money(dec("1.2345"), "KWD"), "KWD 1.235");
+    assert_eq!(format_money(dec("-0.001"), "USD"), "$0.00");
+}
+
+#[test]
+fn currency_setting_takes_three_letter_codes() {
+    assert_eq!(parse_currency(" myr "), Some("MYR".to_string()));
+    for value in ["", "US", "USDT", "U$D", "RM"] {
+        assert!(validate_setting("currency", value).is_err(), "{:?}", value);
+    }
+    assert!(validate_setting("currency", "SGD").is_ok());
+    assert!(validate_setting("hotel_name", "RM").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use hotel_app_be::services::currency::hotel_currency;
+
+    #[tokio::test]
+    async fn hotel_currency_follows_the_setting() {
+        let pool = common::setup_test_db().await;
+        assert_eq!(hotel_currency(&pool).await, "USD");
+
+        sqlx::query("UPDATE system_settings SET value = 'myr' WHERE key = 'currency'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(hotel_currency(&pool).await, "MYR");
+
+        sqlx::query("UPDATE system_settings SET value = 'ringgit' WHERE key = 'currency'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(hotel_currency(&pool).await, "USD");
+    }
+}
@@ -0,0 +1,151 @@
+//! Tests for day-use (same-day) booking prices.
+//!
+//! Booking creation runs PostgreSQL-only SQL, so the SQLite tests go through
+//! a booking quote, which applies the day-use rate the same way
+//! `create_booking_handler` does.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::booking::{apply_day_use, price_stay, validate_setting};
+use hotel_app_be::services::tax::TaxContext;
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn eight_percent() -> TaxContext {
+    TaxContext::inclusive(Decimal::from(8))
+}
+
+#[test]
+fn same_day_stay_is_charged_the_day_use_share() {
+    let full = price_stay(
+        date("2026-09-01"),
+        date("2026-09-01"),
+        Decimal::from(120),
+        None,
+        None,
+        None,
+        &eight_percent(),
+    );
+    let day_use = apply_day_use(full.clone(), 50, &eight_percent());
+
+    assert_eq!(day_use.billable_nights, 1);
+    assert_eq!(day_use.room_rate, Decimal::from(60));
+    assert_eq!(day_use.nights[0].price, Decimal::from(60));
+    assert_eq!(day_use.subtotal, Decimal::from(60));
+    assert_eq!(day_use.taxes.gross, Decimal::from(60));
+    assert_eq!(day_use.taxes.service_tax, "4.44".parse().unwrap());
+
+    // 100% keeps the full night.
+    assert_eq!(apply_day_use(full.clone(), 100, &eight_percent()), full);
+}
+
+#[test]
+fn rates_set_on_the_booking_are_charged_as_given() {
+    let overridden = price_stay(
+        date("2026-09-01"),
+        date("2026-09-01"),
+        Decimal::from(120),
+        None,
+        Some(45.0),
+        None,
+        &eight_percent(),
+    );
+    assert_eq!(
+        apply_day_use(overridden.clone(), 50, &eight_percent()),
+        overridden
+    );
+}
+
+#[test]
+fn day_use_percent_is_validated() {
+    assert!(validate_setting("day_use_rate_percent", "50").is_ok());
+    assert!(validate_setting("day_use_rate_percent", "100").is_ok());
+    for bad in ["0", "101", "-5", "half"] {
+        assert!(validate_setting("day_use_rate_percent", bad).is_err());
+    }
+    assert!(validate_setting("some_other_setting", "0").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::quote_booking_handler;
+    use hotel_app_be::models::{BookingQuote, BookingQuoteInput};
+    use hotel_app_be::services::booking::DAY_USE_PERCENT_SETTING;
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (851, 'Day Room', 'DAYR', 120.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (8501, 'D101', 851, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE system_settings SET value = '40' WHERE key = $1")
+            .bind(DAY_USE_PERCENT_SETTING)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn quote(pool: &sqlx::SqlitePool, check_in: &str, check_out: &str) -> BookingQuote {
+        quote_booking_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Json(BookingQuoteInput {
+                guest_id: None,
+                room_id: 8501,
+                check_in_date: check_in.to_string(),
+                check_out_date: check_out.to_string(),
+                adults: Some(1),
+                children: None,
+                infants: None,
+                is_tourist: None,
+                tourism_tax_amount: None,
+                extra_bed_count: None,
+                extra_bed_charge: None,
+                room_rate_override: None,
+                daily_rates: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    #[tokio::test]
+    async fn same_day_booking_gets_the_day_use_charge() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let day_use = quote(&pool, "2026-11-20", "2026-11-20").await;
+        assert!(day_use.is_hourly);
+        assert_eq!(day_use.total_amount, Decimal::from(48));
+        assert_eq!(day_use.nightly_rates.len(), 1);
+        assert_eq!(day_use.nightly_rates[0].price, Decimal::from(48));
+    }
+
+    #[tokio::test]
+    async fn normal_stay_is_unaffected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let stay = quote(&pool, "2026-11-20", "2026-11-22").await;
+        assert!(!stay.is_hourly);
+        assert_eq!(stay.total_amount, Decimal::from(240));
+    }
+}
@@ -0,0 +1,166 @@
+//! Tests for forfeiting no-show deposits in the night audit.
+//!
+//! The held-deposit rule is pure and runs under any feature; the forfeiture
+//! run is checked against SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::services::deposit_forfeiture::held_deposit;
+use rust_decimal::Decimal;
+
+#[test]
+fn held_deposit_nets_off_refunds() {
+    assert_eq!(
+        held_deposit(Decimal::from(100), Decimal::ZERO),
+        Decimal::from(100)
+    );
+    assert_eq!(
+        held_deposit(Decimal::from(100), Decimal::from(40)),
+        Decimal::from(60)
+    );
+    assert_eq!(
+        held_deposit(Decimal::from(100), Decimal::from(150)),
+        Decimal::ZERO
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::NaiveDate;
+    use hotel_app_be::services::deposit_forfeiture::{
+        NO_SHOW_FORFEIT_CATEGORY, forfeit_no_show_deposits, is_forfeited,
+    };
+    use rust_decimal::Decimal;
+
+    const AUDITOR: i64 = 9710;
+
+    fn audit_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 5).unwrap()
+    }
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9710, 'forfeit-auditor', 'auditor', 'auditor@example.com', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (971, 'Forfeit Queen', 'FQN', 200.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9711, 'F711', 971, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9711, 'Nur', 'Aziz', 'Nur Aziz')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // 9711: no-show with a deposit; 9712: no-show without one;
+        // 9713: confirmed with a deposit, not a no-show.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9711, 'BK-NS-9711', 9711, 9711, '2026-10-05', '2026-10-07', 200.0, 400.0, 'no_show'),
+             (9712, 'BK-NS-9712', 9711, 9711, '2026-10-04', '2026-10-05', 200.0, 200.0, 'no_show'),
+             (9713, 'BK-NS-9713', 9711, 9711, '2026-10-05', '2026-10-06', 200.0, 200.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type, status)
+             VALUES
+             (9711, 9711, 100.0, 'card', 'deposit', 'completed'),
+             (9712, 9711, 200.0, 'card', 'room_charge', 'completed'),
+             (9713, 9711, 50.0, 'card', 'deposit', 'completed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn no_show_with_deposit_is_forfeited() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let forfeited = forfeit_no_show_deposits(&pool, audit_date(), AUDITOR)
+            .await
+            .unwrap();
+
+        assert_eq!(forfeited.len(), 1);
+        assert_eq!(forfeited[0].booking_id, 9711);
+        assert_eq!(forfeited[0].amount, Decimal::from(100));
+
+        let (amount, category): (f64, String) = sqlx::query_as(
+            "SELECT amount, category FROM deposit_forfeitures WHERE booking_id = 9711",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(amount, 100.0);
+        assert_eq!(category, NO_SHOW_FORFEIT_CATEGORY);
+        assert!(is_forfeited(&pool, 9711).await.unwrap());
+        assert!(!is_forfeited(&pool, 9713).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn no_show_without_deposit_records_nothing() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        forfeit_no_show_deposits(&pool, audit_date(), AUDITOR)
+            .await
+            .unwrap();
+
+        assert!(!is_forfeited(&pool, 9712).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rerunning_the_audit_does_not_forfeit_twice() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        forfeit_no_show_deposits(&pool, audit_date(), AUDITOR)
+            .await
+            .unwrap();
+        let again = forfeit_no_show_deposits(&pool, audit_date(), AUDITOR)
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM deposit_forfeitures")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn later_no_shows_wait_for_their_audit_date() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let earlier = audit_date().pred_opt().unwrap();
+        let forfeited = forfeit_no_show_deposits(&pool, earlier, AUDITOR)
+            .await
+            .unwrap();
+        assert!(forfeited.is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+//! Integration tests for concurrent moves into the same room.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::update_booking_handler;
+    use hotel_app_be::models::BookingUpdateInput;
+
+    const ADMIN: i64 = 9890;
+    const TARGET_ROOM: i64 = 9893;
+
+    /// Two confirmed stays on the same dates in rooms 9891 and 9892, and an
+    /// empty room 9893 both are about to be moved into.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9890, 'u-9890', 'duty_manager', 'duty@example.com', 1)",
+            "INSERT INTO user_roles (user_id, role_id) VALUES (9890, 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (989, 'Race Twin', 'RTW', 120.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9891, 'R891', 989, 'reserved', 1),
+                    (9892, 'R892', 989, 'reserved', 1),
+                    (9893, 'R893', 989, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9890, 'Mei', 'Tan', 'Mei Tan')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9891, 'BK-RACE-1', 9890, 9891, '2026-09-01', '2026-09-03', 120.0, 240.0, 'confirmed'),
+             (9892, 'BK-RACE-2', 9890, 9892, '2026-09-01', '2026-09-03', 120.0, 240.0, 'confirmed')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    fn move_to(room_id: i64) -> BookingUpdateInput {
+        serde_json::from_value(serde_json::json!({ "room_id": room_id.to_string() })).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrent_moves_into_one_room_let_exactly_one_through() {
+        // Each move needs its own connection for the two to overlap.
+        let root =
+            std::env::temp_dir().join(format!("hotel-double-booking-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let pool = common::setup_file_test_db(&root).await;
+        seed(&pool).await;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for booking_id in [9891_i64, 9892] {
+            let pool = pool.clone();
+            tasks.spawn(async move {
+                update_booking_handler(
+                    State(pool),
+                    Extension(ADMIN),
+                    Extension(PropertyScope::default()),
+                    Path(booking_id),
+                    Json(move_to(TARGET_ROOM)),
+                )
+                .await
+                .map(|json| json.0.id)
+            });
+        }
+        let results = tasks.join_all().await;
+
+        let moved: Vec<i64> = results
+            .iter()
+            .filter_map(|r| r.as_ref().ok())
+            .copied()
+            .collect();
+        assert_eq!(moved.len(), 1, "{:?}", results);
+        assert!(
+            results.iter().any(
+                |r| matches!(r, Err(ApiError::BadRequest(msg)) if msg.contains("already booked"))
+            ),
+            "{:?}",
+            results
+        );
+
+        let in_target: Vec<i64> =
+            sqlx::query_scalar("SELECT id FROM bookings WHERE room_id = $1 ORDER BY id")
+                .bind(TARGET_ROOM)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(in_target, moved);
+
+        pool.close().await;
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+//! Tests for splitting a booking's charges between guest and company.
+//!
+//! Routing and the resulting balances are pure and run under any feature.
+//! Company billing columns only exist in the PostgreSQL schema, so the
+//! SQLite test covers storing routes for the guest side only.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use hotel_app_be::ApiError;
+use hotel_app_be::models::FolioLine;
+use hotel_app_be::services::folio_split::{ledger_codes, route_charges, validate_routes};
+use hotel_app_be::services::guest_balance::{BookingCharges, SplitShares, summarize};
+use rust_decimal::Decimal;
+
+fn line(category: &str, amount: i64) -> FolioLine {
+    FolioLine {
+        category: category.to_string(),
+        amount: Decimal::from(amount),
+    }
+}
+
+fn routes(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(category, payer)| (category.to_string(), payer.to_string()))
+        .collect()
+}
+
+fn stay_charges() -> Vec<FolioLine> {
+    vec![
+        line("room", 450),
+        line("extra_bed", 60),
+        line("late_checkout", 40),
+        line("tourism_tax", 20),
+        line("services", 0),
+    ]
+}
+
+#[test]
+fn room_charges_go_to_the_company_and_incidentals_stay_with_the_guest() {
+    let routed = route_charges(&routes(&[("room", "company")]), &stay_charges());
+
+    assert_eq!(routed.company, vec![line("room", 450)]);
+    assert_eq!(
+        routed.guest,
+        vec![
+            line("extra_bed", 60),
+            line("late_checkout", 40),
+            line("tourism_tax", 20)
+        ]
+    );
+    assert_eq!(routed.company_total(), Decimal::from(450));
+    assert_eq!(routed.guest_total(), Decimal::from(120));
+
+    // The company's room charge lands on the city ledger as a room charge.
+    assert_eq!(ledger_codes("room"), ("room_charge", "accommodation"));
+}
+
+#[test]
+fn charges_can_be_routed_per_category() {
+    let split = routes(&[
+        ("room", "company"),
+        ("tourism_tax", "company"),
+        ("late_checkout", "guest"),
+    ]);
+    let routed = route_charges(&split, &stay_charges());
+
+    assert_eq!(routed.company_total(), Decimal::from(470));
+    assert_eq!(routed.guest_total(), Decimal::from(100));
+}
+
+#[test]
+fn without_routes_everything_stays_on_the_guest_folio() {
+    let routed = route_charges(&BTreeMap::new(), &stay_charges());
+    assert!(routed.company.is_empty());
+    assert_eq!(routed.guest_total(), Decimal::from(570));
+}
+
+#[test]
+fn routes_are_validated() {
+    let acme = Some("Acme Sdn Bhd");
+    assert!(validate_routes(&routes(&[("room", "company")]), acme).is_ok());
+    assert!(validate_routes(&routes(&[("minibar", "guest")]), acme).is_err());
+    assert!(validate_routes(&routes(&[("room", "travel_agent")]), acme).is_err());
+    assert!(matches!(
+        validate_routes(&routes(&[("room", "company")]), None),
+        Err(ApiError::BadRequest(_))
+    ));
+    assert!(validate_routes(&routes(&[("room", "company")]), Some("  ")).is_err());
+    assert!(validate_routes(&routes(&[("room", "guest")]), None).is_ok());
+}
+
+#[test]
+fn split_booking_owes_each_payer_their_share() {
+    let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let split = BookingCharges {
+        booking_id: 1,
+        booking_number: Some("BK-1".to_string()),
+        status: "checked_out".to_string(),
+        check_in_date: date("2026-04-01"),
+        check_out_date: date("2026-04-04"),
+        total_amount: Decimal::from(450),
+        total_paid: Decimal::from(50),
+        company_billed: true,
+        company_name: Some("Acme Sdn Bhd".to_string()),
+        ledger_balance: None,
+        split_shares: Some(SplitShares {
+            guest: Decimal::from(120),
+            company: Decimal::from(450),
+        }),
+    };
+
+    let balance = summarize(7, std::slice::from_ref(&split));
+    assert_eq!(balance.guest_outstanding, Decimal::from(70));
+    assert_eq!(balance.company_outstanding, Decimal::from(450));
+    assert_eq!(balance.bookings.len(), 2);
+
+    // Once posted, the company owes what is left open on its ledger.
+    let posted = BookingCharges {
+        ledger_balance: Some(Decimal::from(200)),
+        ..split
+    };
+    let balance = summarize(7, &[posted]);
+    assert_eq!(balance.company_outstanding, Decimal::from(200));
+    assert_eq!(balance.guest_outstanding, Decimal::from(70));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, routes};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::services::folio_split::{booking_folio, load_routes, set_routes};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (931, 'Split Twin', 'STW', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9311, 'F931', 931, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9311, 'Mei', 'Ling', 'Mei Ling')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (9311, 'BK-SPLIT-9311', 9311, 9311, '2026-07-01', '2026-07-03',
+                     150.0, 300.0, 'checked_in')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn routes_are_stored_and_replaced() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let folio = set_routes(&pool, 9311, &routes(&[("room", "guest")]), 1)
+            .await
+            .unwrap();
+        assert_eq!(folio.routes, routes(&[("room", "guest")]));
+        assert_eq!(folio.guest_total, Decimal::from(300));
+        assert!(folio.company_ledger.is_empty());
+
+        set_routes(&pool, 9311, &routes(&[]), 1).await.unwrap();
+        assert!(load_routes(&pool, 9311).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn company_routes_need_a_company_on_the_booking() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let result = set_routes(&pool, 9311, &routes(&[("room", "company")]), 1).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+        assert!(load_routes(&pool, 9311).await.unwrap().is_empty());
+
+        assert!(matches!(
+            booking_folio(&pool, 9999).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,276 @@
+//! Tests for booking several rooms in one request and cancelling them
+//! together.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use hotel_app_be::services::booking_numbers::group_booking_number;
+
+#[test]
+fn group_numbers_follow_the_first_booking() {
+    assert_eq!(group_booking_number("BK-2026-0042"), "GRP-BK-2026-0042");
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::{
+        cancel_group_booking_handler, create_group_booking_handler,
+    };
+    use hotel_app_be::models::{
+        CancelBookingInput, GroupBooking, GroupBookingInput, GroupCancellation,
+    };
+    use rust_decimal::Decimal;
+
+    const CLERK: i64 = 9930;
+
+    /// Three available rooms at 150 a night, 9931 to 9933.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9930, 'u-9930', 'groups_desk', 'groups@example.com', 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (993, 'Group Twin', 'GTW', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9931, 'G931', 993, 'available', 1),
+                    (9932, 'G932', 993, 'available', 1),
+                    (9933, 'G933', 993, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9930, 'Tour', 'Leader', 'Tour Leader')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn book(pool: &sqlx::SqlitePool, room_ids: &[i64]) -> Result<GroupBooking, ApiError> {
+        let input: GroupBookingInput = serde_json::from_value(serde_json::json!({
+            "guest_id": 9930,
+            "room_ids": room_ids,
+            "check_in_date": "2026-12-01",
+            "check_out_date": "2026-12-03",
+        }))
+        .unwrap();
+        create_group_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Json(input),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    async fn cancel(
+        pool: &sqlx::SqlitePool,
+        reference: &str,
+    ) -> Result<GroupCancellation, ApiError> {
+        cancel_group_booking_handler(
+            State(pool.clone()),
+            Extension(CLERK),
+            Extension(PropertyScope::default()),
+            Path(reference.to_string()),
+            Json(CancelBookingInput {
+                reason: Some("Event called off".to_string()),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    async fn statuses(pool: &sqlx::SqlitePool, reference: &str) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT status FROM bookings WHERE group_booking_number = $1 ORDER BY id",
+        )
+        .bind(reference)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn room_status(pool: &sqlx::SqlitePool, id: i64) -> String {
+        sqlx::query_scalar("SELECT status FROM rooms WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn books_every_room_under_one_group_number() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let group = book(&pool, &[9933, 9931]).await.unwrap();
+
+        let rooms: Vec<i64> = group.bookings.iter().map(|b| b.room_id).collect();
+        assert_eq!(rooms, vec![9931, 9933]);
+        assert_eq!(
+            group.group_booking_number,
+            format!("GRP-{}", group.bookings[0].booking_number)
+        );
+        assert_ne!(
+            group.bookings[0].booking_number,
+            group.bookings[1].booking_number
+        );
+        for booking in &group.bookings {
+            assert_eq!(booking.status, "confirmed");
+            assert_eq!(booking.total_amount, Decimal::from(300));
+        }
+        assert_eq!(group.total_amount, Decimal::from(600));
+
+        let stored: Vec<String> = sqlx::query_scalar(
+            "SELECT group_booking_number FROM bookings WHERE guest_id = 9930 ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, vec![group.group_booking_number.clone(); 2]);
+        assert_eq!(room_status(&pool, 9931).await, "reserved");
+        assert_eq!(room_status(&pool, 9932).await, "available");
+    }
+
+    #[tokio::test]
+    async fn a_conflict_on_any_room_books_none() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (9939, 'BK-GRP-TAKEN', 9930, 9932, '2026-12-02', '2026-12-04', 150.0, 300.0, 'confirmed')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = book(&pool, &[9931, 9932, 9933]).await;
+        assert!(
+            matches!(&result, Err(ApiError::BadRequest(msg)) if msg.contains("G932")),
+            "{:?}",
+            result.map(|g| g.group_booking_number)
+        );
+
+        let grouped: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM bookings WHERE group_booking_number IS NOT NULL",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(grouped, 0);
+        assert_eq!(room_status(&pool, 9931).await, "available");
+    }
+
+    #[tokio::test]
+    async fn empty_or_repeated_room_lists_are_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for room_ids in [&[][..], &[9931, 9931][..]] {
+            assert!(
+                matches!(book(&pool, room_ids).await, Err(ApiError::BadRequest(_))),
+                "{:?}",
+                room_ids
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_group_cancels_each_booking_once() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        // Every stay is inside the fee window, whenever the test runs.
+        sqlx::query(
+            "UPDATE system_settings SET value = '365' WHERE key = 'cancellation_free_days'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let group = book(&pool, &[9931, 9932, 9933]).await.unwrap();
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type)
+             VALUES ($1, 9930, 200.0, 'card', 'booking')",
+        )
+        .bind(group.bookings[0].id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let cancelled = cancel(&pool, &group.group_booking_number).await.unwrap();
+        // Half of each 300 stay is charged; only the first room paid, 200.
+        assert_eq!(
+            cancelled
+                .cancelled
+                .iter()
+                .map(|c| (c.booking_id, c.cancellation_fee, c.refund_amount))
+                .collect::<Vec<_>>(),
+            vec![
+                (group.bookings[0].id, Decimal::from(150), Decimal::from(50)),
+                (group.bookings[1].id, Decimal::from(150), Decimal::ZERO),
+                (group.bookings[2].id, Decimal::from(150), Decimal::ZERO),
+            ]
+        );
+        assert_eq!(cancelled.total_cancellation_fee, Decimal::from(450));
+        assert_eq!(cancelled.total_refund_amount, Decimal::from(50));
+        assert!(cancelled.already_cancelled.is_empty());
+        assert_eq!(
+            statuses(&pool, &group.group_booking_number).await,
+            vec!["cancelled"; 3]
+        );
+        for room_id in [9931, 9932, 9933] {
+            assert_eq!(room_status(&pool, room_id).await, "available");
+        }
+        // Each fee is recorded with its cancellation.
+        let fees: Vec<(i64, f64)> = sqlx::query_as(
+            "SELECT booking_id, amount FROM booking_fees WHERE fee_type = 'cancellation'
+             ORDER BY booking_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            fees,
+            group
+                .bookings
+                .iter()
+                .map(|b| (b.id, 150.0))
+                .collect::<Vec<_>>()
+        );
+
+        // Repeating the request skips the bookings already cancelled.
+        let again = cancel(&pool, &group.group_booking_number).await.unwrap();
+        assert!(again.cancelled.is_empty());
+        assert_eq!(again.already_cancelled.len(), 3);
+        assert_eq!(again.total_cancellation_fee, Decimal::ZERO);
+        assert_eq!(again.total_refund_amount, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn a_started_stay_leaves_the_group_untouched() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let group = book(&pool, &[9931, 9932]).await.unwrap();
+        sqlx::query("UPDATE bookings SET status = 'checked_in' WHERE id = $1")
+            .bind(group.bookings[1].id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            cancel(&pool, &group.group_booking_number).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        assert_eq!(
+            statuses(&pool, &group.group_booking_number).await,
+            vec!["confirmed", "checked_in"]
+        );
+        assert!(matches!(
+            cancel(&pool, "GRP-UNKNOWN").await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
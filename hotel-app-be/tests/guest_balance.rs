@@ -0,0 +1,168 @@
+//! Tests for per-guest outstanding balances.
+//!
+//! The summary maths is pure and runs under any feature; the database test
+//! is gated on SQLite like the other integration tests.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::services::guest_balance::{BookingCharges, SplitShares, summarize};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn charges(id: i64, total: i64, paid: i64) -> BookingCharges {
+    BookingCharges {
+        booking_id: id,
+        booking_number: Some(format!("BK-{id}")),
+        status: "checked_out".to_string(),
+        check_in_date: date("2026-04-01"),
+        check_out_date: date("2026-04-03"),
+        total_amount: Decimal::from(total),
+        total_paid: Decimal::from(paid),
+        company_billed: false,
+        company_name: None,
+        ledger_balance: None,
+        split_shares: None,
+    }
+}
+
+#[test]
+fn sums_unpaid_remainders_and_skips_settled_bookings() {
+    let balance = summarize(7, &[charges(1, 200, 200), charges(2, 300, 120)]);
+
+    assert_eq!(balance.guest_id, 7);
+    assert_eq!(balance.total_outstanding, Decimal::from(180));
+    assert_eq!(balance.guest_outstanding, Decimal::from(180));
+    assert_eq!(balance.company_outstanding, Decimal::ZERO);
+    assert_eq!(balance.bookings.len(), 1);
+    assert_eq!(balance.bookings[0].booking_id, 2);
+}
+
+#[test]
+fn overpayment_does_not_offset_other_bookings() {
+    let balance = summarize(7, &[charges(1, 100, 150), charges(2, 80, 0)]);
+
+    assert_eq!(balance.total_outstanding, Decimal::from(80));
+}
+
+#[test]
+fn company_billed_stays_use_the_open_ledger_balance() {
+    let mut posted = charges(1, 400, 0);
+    posted.company_billed = true;
+    posted.company_name = Some("Acme Sdn Bhd".to_string());
+    posted.ledger_balance = Some(Decimal::from(150));
+
+    let mut unposted = charges(2, 250, 0);
+    unposted.company_billed = true;
+
+    let balance = summarize(7, &[posted, unposted, charges(3, 90, 40)]);
+
+    assert_eq!(balance.company_outstanding, Decimal::from(400));
+    assert_eq!(balance.guest_outstanding, Decimal::from(50));
+    assert_eq!(balance.total_outstanding, Decimal::from(450));
+    assert_eq!(balance.bookings[0].billed_to, "company");
+    assert_eq!(balance.bookings[2].billed_to, "guest");
+}
+
+#[test]
+fn guest_balance_due_leaves_out_company_charges() {
+    assert_eq!(charges(1, 300, 120).guest_balance_due(), Decimal::from(180));
+    assert_eq!(charges(1, 300, 400).guest_balance_due(), Decimal::ZERO);
+
+    let mut billed = charges(2, 300, 0);
+    billed.company_billed = true;
+    assert_eq!(billed.guest_balance_due(), Decimal::ZERO);
+
+    let mut split = charges(3, 300, 50);
+    split.split_shares = Some(SplitShares {
+        guest: Decimal::from(100),
+        company: Decimal::from(200),
+    });
+    assert_eq!(split.guest_balance_due(), Decimal::from(50));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::guests::get_guest_balance_handler;
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (901, 'Balance Queen', 'BQN', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9001, 'B901', 901, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9001, 'Nur', 'Hakim', 'Nur Hakim')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // 9001 is paid in full, 9002 is partially paid, 9003 is voided.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9001, 'BK-BAL-9001', 9001, 9001, '2026-04-01', '2026-04-03', 150.0, 300.0, 'checked_out'),
+             (9002, 'BK-BAL-9002', 9001, 9001, '2026-05-01', '2026-05-04', 150.0, 450.0, 'checked_out'),
+             (9003, 'BK-BAL-9003', 9001, 9001, '2026-06-01', '2026-06-02', 150.0, 150.0, 'voided')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO payments (booking_id, guest_id, amount, payment_method, payment_type, status)
+             VALUES
+             (9001, 9001, 300.0, 'cash', 'booking', 'completed'),
+             (9002, 9001, 200.0, 'card', 'booking', 'completed'),
+             (9002, 9001, 100.0, 'card', 'booking', 'failed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn totals_a_paid_and_a_partially_paid_booking() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let balance = get_guest_balance_handler(State(pool.clone()), Path(9001))
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(balance.total_outstanding, Decimal::from(250));
+        assert_eq!(balance.guest_outstanding, Decimal::from(250));
+        assert_eq!(balance.bookings.len(), 1);
+        assert_eq!(balance.bookings[0].booking_id, 9002);
+        assert_eq!(balance.bookings[0].total_paid, Decimal::from(200));
+    }
+
+    #[tokio::test]
+    async fn unknown_guest_is_not_found() {
+        let pool = common::setup_test_db().await;
+
+        let result = get_guest_balance_handler(State(pool), Path(424242)).await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+}
@@ -0,0 +1,179 @@
+//! Tests for the signed-in guest's portal profile.
+//!
+//! Update sanitizing is pure and runs under any feature; the handlers run
+//! against SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::handlers::guest_portal::sanitize_profile_update;
+use hotel_app_be::models::GuestPortalProfileUpdate;
+
+#[test]
+fn updates_are_trimmed_and_normalised() {
+    let update = sanitize_profile_update(GuestPortalProfileUpdate {
+        email: Some("  Mei.Ling@Example.COM ".to_string()),
+        phone: Some("+60 12-345 6789".to_string()),
+        address_line1: Some("  12 Jalan Ampang\u{7}  ".to_string()),
+        city: Some("".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(update.email.as_deref(), Some("mei.ling@example.com"));
+    assert_eq!(update.phone.as_deref(), Some("+60123456789"));
+    assert_eq!(update.address_line1.as_deref(), Some("12 Jalan Ampang"));
+    // Blank means clear; omitted stays omitted.
+    assert_eq!(update.city.as_deref(), Some(""));
+    assert_eq!(update.country, None);
+}
+
+#[test]
+fn malformed_contact_details_are_rejected() {
+    let bad = [
+        GuestPortalProfileUpdate {
+            email: Some("not-an-email".to_string()),
+            ..Default::default()
+        },
+        GuestPortalProfileUpdate {
+            phone: Some("12".to_string()),
+            ..Default::default()
+        },
+        GuestPortalProfileUpdate {
+            postal_code: Some("9".repeat(21)),
+            ..Default::default()
+        },
+    ];
+    for update in bad {
+        assert!(matches!(
+            sanitize_profile_update(update),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::guest_portal::{get_own_guest_profile, update_own_guest_profile};
+    use hotel_app_be::models::GuestPortalProfileUpdate;
+
+    const LINKED_USER: i64 = 9750;
+    const UNLINKED_USER: i64 = 9751;
+    const GUEST: i64 = 9750;
+    const OTHER_GUEST: i64 = 9751;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, user_type)
+             VALUES (9750, 'u-9750', 'meiling', 'meiling@example.com', 'guest'),
+                    (9751, 'u-9751', 'drifter', 'drifter@example.com', 'guest')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name, email, phone, city)
+             VALUES (9750, 'Mei', 'Ling', 'Mei Ling', 'meiling@example.com', '+60123456789', 'Ipoh'),
+                    (9751, 'Tan', 'Ah Kow', 'Tan Ah Kow', 'tan@example.com', '+60198765432', 'Penang')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        // The first user may edit their own record and only view a relative's.
+        sqlx::query(
+            "INSERT INTO user_guests (user_id, guest_id, relationship_type, can_modify)
+             VALUES (9750, 9750, 'self', 1), (9750, 9751, 'family', 0), (9751, 9751, 'family', 0)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn user_views_and_updates_their_own_guest_record() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let profile = get_own_guest_profile(State(pool.clone()), Extension(LINKED_USER))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(profile.guest_id, GUEST);
+        assert_eq!(profile.full_name, "Mei Ling");
+        assert_eq!(profile.city.as_deref(), Some("Ipoh"));
+
+        let updated = update_own_guest_profile(
+            State(pool.clone()),
+            Extension(LINKED_USER),
+            Json(GuestPortalProfileUpdate {
+                phone: Some("+60 11 2233 4455".to_string()),
+                address_line1: Some(" 8 Jalan Sultan Idris ".to_string()),
+                state_province: Some("Perak".to_string()),
+                city: Some("  ".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(updated.guest_id, GUEST);
+        assert_eq!(updated.phone.as_deref(), Some("+601122334455"));
+        assert_eq!(
+            updated.address_line1.as_deref(),
+            Some("8 Jalan Sultan Idris")
+        );
+        assert_eq!(updated.state_province.as_deref(), Some("Perak"));
+        assert_eq!(updated.city, None);
+        assert_eq!(updated.email.as_deref(), Some("meiling@example.com"));
+
+        // The relative's record is untouched.
+        let other_phone: String = sqlx::query_scalar("SELECT phone FROM guests WHERE id = $1")
+            .bind(OTHER_GUEST)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(other_phone, "+60198765432");
+    }
+
+    #[tokio::test]
+    async fn user_without_a_modifiable_link_is_refused() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let viewed = get_own_guest_profile(State(pool.clone()), Extension(UNLINKED_USER)).await;
+        assert!(matches!(viewed, Err(ApiError::Forbidden(_))));
+
+        let updated = update_own_guest_profile(
+            State(pool.clone()),
+            Extension(UNLINKED_USER),
+            Json(GuestPortalProfileUpdate {
+                phone: Some("+60111111111".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await;
+        assert!(matches!(updated, Err(ApiError::Forbidden(_))));
+
+        // An invalid update from a linked user changes nothing either.
+        let invalid = update_own_guest_profile(
+            State(pool.clone()),
+            Extension(LINKED_USER),
+            Json(GuestPortalProfileUpdate {
+                email: Some("nope".to_string()),
+                city: Some("Kampar".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await;
+        assert!(matches!(invalid, Err(ApiError::BadRequest(_))));
+        let city: String = sqlx::query_scalar("SELECT city FROM guests WHERE id = $1")
+            .bind(GUEST)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(city, "Ipoh");
+    }
+}
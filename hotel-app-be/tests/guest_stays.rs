@@ -0,0 +1,153 @@
+//! Tests for a guest's stay history.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Path, Query, State};
+    use axum::http::{HeaderMap, HeaderValue};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::AuthService;
+    use hotel_app_be::handlers::guests::get_guest_stays_handler;
+    use hotel_app_be::models::GuestStaysParams;
+    use hotel_app_be::services::guest_stays::guest_stays;
+    use rust_decimal::Decimal;
+
+    const FRONT_DESK: i64 = 9981;
+    const LINKED_USER: i64 = 9982;
+    const OTHER_USER: i64 = 9983;
+
+    /// Guest 9980 with three stays and a voided booking, guest 9989 with one
+    /// stay. User 9981 has `guests:read`; 9982 is linked to guest 9980 and
+    /// 9983 to nobody.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9981, 'u-9981', 'front_desk', 'desk@example.com', 1),
+                    (9982, 'u-9982', 'family', 'family@example.com', 1),
+                    (9983, 'u-9983', 'stranger', 'stranger@example.com', 1)",
+            "INSERT INTO roles (id, name, display_name) VALUES (9980, 'stay_desk', 'Stay Desk')",
+            "INSERT INTO role_permissions (role_id, permission_id)
+             SELECT 9980, id FROM permissions WHERE name = 'guests:read'",
+            "INSERT INTO user_roles (user_id, role_id) VALUES (9981, 9980)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (998, 'History Suite', 'HST', 250.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9981, 'H981', 998, 'available', 1), (9982, 'H982', 998, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9980, 'Lena', 'Vogel', 'Lena Vogel'), (9989, 'Raj', 'Patel', 'Raj Patel')",
+            "INSERT INTO user_guests (user_id, guest_id) VALUES (9982, 9980)",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9981, 'BK-HST-1', 9980, 9981, '2025-03-01', '2025-03-04', 250.0, 750.0, 'checked_out'),
+             (9982, 'BK-HST-2', 9980, 9982, '2026-01-10', '2026-01-12', 250.0, 500.0, 'checked_out'),
+             (9983, 'BK-HST-3', 9980, 9981, '2026-12-20', '2026-12-21', 250.0, 250.0, 'confirmed'),
+             (9984, 'BK-HST-4', 9980, 9982, '2026-06-01', '2026-06-02', 250.0, 250.0, 'voided'),
+             (9985, 'BK-HST-5', 9989, 9981, '2026-02-01', '2026-02-03', 250.0, 500.0, 'checked_out')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    fn headers_for(user_id: i64) -> HeaderMap {
+        common::install_config(&[]);
+        let token = AuthService::generate_jwt(user_id, "user".to_string(), vec![]).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    async fn stays_as(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+        guest_id: i64,
+    ) -> Result<Vec<String>, ApiError> {
+        get_guest_stays_handler(
+            State(pool.clone()),
+            headers_for(user_id),
+            Path(guest_id),
+            Query(GuestStaysParams {
+                page: None,
+                page_size: None,
+            }),
+        )
+        .await
+        .map(|json| {
+            json.0
+                .data
+                .into_iter()
+                .filter_map(|stay| stay.booking_number)
+                .collect()
+        })
+    }
+
+    #[tokio::test]
+    async fn stays_are_listed_newest_first_without_voided_bookings() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let page = guest_stays(&pool, 9980, None, None).await.unwrap();
+
+        assert_eq!(page.total, 3);
+        let numbers: Vec<_> = page
+            .data
+            .iter()
+            .map(|s| s.booking_number.as_deref().unwrap())
+            .collect();
+        assert_eq!(numbers, vec!["BK-HST-3", "BK-HST-2", "BK-HST-1"]);
+
+        let oldest = &page.data[2];
+        assert_eq!(oldest.room_number, "H981");
+        assert_eq!(oldest.room_type.as_deref(), Some("History Suite"));
+        assert_eq!(oldest.nights, 3);
+        assert_eq!(oldest.total_amount, Decimal::from(750));
+        assert_eq!(oldest.status, "checked_out");
+        assert!(oldest.reviews.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stays_are_paginated() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let page = guest_stays(&pool, 9980, Some(2), Some(2)).await.unwrap();
+
+        assert_eq!((page.total, page.page, page.page_size), (3, 2, 2));
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.data[0].booking_number.as_deref(), Some("BK-HST-1"));
+
+        assert!(matches!(
+            guest_stays(&pool, 9999, None, None).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_staff_and_linked_users_see_a_guests_stays() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert_eq!(
+            stays_as(&pool, FRONT_DESK, 9989).await.unwrap(),
+            vec!["BK-HST-5"]
+        );
+        assert_eq!(stays_as(&pool, LINKED_USER, 9980).await.unwrap().len(), 3);
+        assert!(matches!(
+            stays_as(&pool, LINKED_USER, 9989).await,
+            Err(ApiError::Forbidden(_))
+        ));
+        assert!(matches!(
+            stays_as(&pool, OTHER_USER, 9980).await,
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+//! Tests for the hotel's local date.
+//!
+//! Parsing the timezone setting and the date at an instant in a timezone are
+//! pure and run under any feature. Room status following the hotel's day
+//! rather than the database's is SQLite-backed and gated accordingly.
+
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use hotel_app_be::services::hotel_time::{local_date, parse_timezone, validate_setting};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn timezone_setting_takes_iana_names() {
+    assert_eq!(
+        parse_timezone(" Asia/Kuala_Lumpur "),
+        Some(Tz::Asia__Kuala_Lumpur)
+    );
+    assert_eq!(parse_timezone("UTC"), Some(Tz::UTC));
+    assert_eq!(parse_timezone("GMT+8"), None);
+    assert_eq!(parse_timezone(""), None);
+
+    assert!(validate_setting("timezone", "America/New_York").is_ok());
+    assert!(validate_setting("timezone", "Mars/Olympus_Mons").is_err());
+    assert!(validate_setting("currency", "Mars/Olympus_Mons").is_ok());
+}
+
+#[test]
+fn the_hotel_day_turns_over_at_local_midnight() {
+    // 20:00 UTC is already the next morning in Kuala Lumpur (UTC+8).
+    let evening_utc = Utc.with_ymd_and_hms(2026, 10, 15, 20, 0, 0).unwrap();
+    assert_eq!(local_date(Tz::UTC, evening_utc), date("2026-10-15"));
+    assert_eq!(
+        local_date(Tz::Asia__Kuala_Lumpur, evening_utc),
+        date("2026-10-16")
+    );
+
+    // 02:00 UTC is still the previous evening in New York (UTC-4 in October).
+    let small_hours_utc = Utc.with_ymd_and_hms(2026, 10, 16, 2, 0, 0).unwrap();
+    assert_eq!(
+        local_date(Tz::America__New_York, small_hours_utc),
+        date("2026-10-15")
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::{Duration, Utc};
+    use hotel_app_be::services::hotel_time::hotel_today;
+    use hotel_app_be::services::room_status::current_room_status;
+
+    async fn set_timezone(pool: &sqlx::SqlitePool, timezone: &str) {
+        sqlx::query("UPDATE system_settings SET value = $1 WHERE key = 'timezone'")
+            .bind(timezone)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn today_is_the_date_at_the_hotel() {
+        let pool = common::setup_test_db().await;
+        assert_eq!(hotel_today(&pool).await.unwrap(), Utc::now().date_naive());
+
+        // Kiritimati is UTC+14 all year.
+        set_timezone(&pool, "Pacific/Kiritimati").await;
+        assert_eq!(
+            hotel_today(&pool).await.unwrap(),
+            (Utc::now() + Duration::hours(14)).date_naive()
+        );
+    }
+
+    #[tokio::test]
+    async fn room_status_follows_the_hotel_day() {
+        let pool = common::setup_test_db().await;
+        set_timezone(&pool, "Pacific/Kiritimati").await;
+        let today = hotel_today(&pool).await.unwrap();
+
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (993, 'Island Suite', 'ISU', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9931, 'K01', 993, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9930, 'Tui', 'Kaitu')",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        // Arriving on the hotel's today, which may still be tomorrow in UTC.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (9931, 'BK-TZ-1', 9930, 9931, $1, $2, 150.0, 150.0, 'confirmed')",
+        )
+        .bind(today)
+        .bind(today + Duration::days(1))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let statuses = current_room_status(&pool).await.unwrap();
+        assert_eq!(statuses.get(&9931).map(String::as_str), Some("reserved"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Tests for the automatic release of cleaned rooms.
+//!
+//! The release predicate is pure and runs under any feature; the database
+//! test is gated on SQLite like the other integration tests.
+
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hotel_app_be::services::housekeeping::CleaningRoom;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 6, 1, 14, 0, 0).unwrap()
+}
+
+fn room(status: &str, end: Option<DateTime<Utc>>, occupied: bool) -> CleaningRoom {
+    CleaningRoom {
+        id: 1,
+        room_number: "101".to_string(),
+        status: status.to_string(),
+        cleaning_end_date: end,
+        has_active_booking: occupied,
+    }
+}
+
+#[test]
+fn releases_once_the_cleaning_window_has_passed() {
+    let ended = Some(now() - Duration::minutes(5));
+
+    assert!(room("cleaning", ended, false).is_due_for_release(now()));
+    assert!(room("dirty", ended, false).is_due_for_release(now()));
+    // The end instant itself counts as passed.
+    assert!(room("cleaning", Some(now()), false).is_due_for_release(now()));
+}
+
+#[test]
+fn keeps_rooms_that_are_not_due() {
+    let ended = Some(now() - Duration::minutes(5));
+    let cases = [
+        // window still open
+        room("cleaning", Some(now() + Duration::minutes(5)), false),
+        // no end date: cleared manually only
+        room("cleaning", None, false),
+        room("dirty", None, false),
+        // guest in house
+        room("dirty", ended, true),
+        room("cleaning", ended, true),
+        // not a housekeeping status
+        room("maintenance", ended, false),
+        room("available", ended, false),
+    ];
+
+    for case in cases {
+        assert!(!case.is_due_for_release(now()), "{:?}", case);
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use chrono::{Duration, Utc};
+    use hotel_app_be::services::housekeeping::release_cleaned_rooms;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        let past = (Utc::now() - Duration::hours(1)).to_rfc3339();
+        let future = (Utc::now() + Duration::hours(1)).to_rfc3339();
+
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (801, 'Release Twin', 'RTWN', 120.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, cleaning_end_date, is_active)
+             VALUES
+             (8001, 'H101', 801, 'cleaning', ?1, 1),
+             (8002, 'H102', 801, 'dirty', ?1, 1),
+             (8003, 'H103', 801, 'cleaning', ?2, 1),
+             (8004, 'H104', 801, 'dirty', NULL, 1)",
+        )
+        .bind(&past)
+        .bind(&future)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // H102 has a guest in house.
+        sqlx::query("INSERT INTO guests (id, first_name, last_name) VALUES (8001, 'In', 'House')")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (8001, 'BK-HK-8001', 8001, 8002, date('now'), date('now', '+2 day'),
+                     120.0, 240.0, 'checked_in')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn releases_only_rooms_past_their_window_without_guests() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let released = release_cleaned_rooms(&pool, Utc::now()).await.unwrap();
+        assert_eq!(released, vec![8001]);
+
+        let statuses: Vec<(i64, String)> =
+            sqlx::query_as("SELECT id, status FROM rooms WHERE id >= 8001 ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                (8001, "available".to_string()),
+                (8002, "dirty".to_string()),
+                (8003, "cleaning".to_string()),
+                (8004, "dirty".to_string()),
+            ]
+        );
+
+        // Nothing left to release.
+        assert!(
+            release_cleaned_rooms(&pool, Utc::now())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
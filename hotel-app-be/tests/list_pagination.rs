@@ -0,0 +1,83 @@
+//! Tests for validated paging and sorting on the loyalty lists.
+//!
+//! The memberships and redemptions tables only exist on PostgreSQL, so these
+//! check the parameter handling the handlers build their queries from.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::handlers::loyalty::{MEMBERSHIP_SORT, REDEMPTION_SORT};
+use hotel_app_be::models::ListParams;
+use hotel_app_be::utils::pagination::{DEFAULT_PER_PAGE, ListPage, MAX_PER_PAGE};
+
+fn params(page: Option<i64>, per_page: Option<i64>, sort: &str, order: &str) -> ListParams {
+    ListParams {
+        page,
+        per_page,
+        sort: (!sort.is_empty()).then(|| sort.to_string()),
+        order: (!order.is_empty()).then(|| order.to_string()),
+    }
+}
+
+#[test]
+fn defaults_to_the_first_page_in_the_endpoint_order() {
+    let page = ListPage::resolve(&ListParams::default(), &MEMBERSHIP_SORT).unwrap();
+
+    assert_eq!(page.page, 1);
+    assert_eq!(page.per_page, DEFAULT_PER_PAGE);
+    assert_eq!(page.offset(), 0);
+    assert_eq!(page.order_by, "lm.lifetime_points DESC, lm.id DESC");
+}
+
+#[test]
+fn later_pages_skip_earlier_rows() {
+    let page = ListPage::resolve(&params(Some(3), Some(20), "", ""), &REDEMPTION_SORT).unwrap();
+
+    assert_eq!(page.offset(), 40);
+    assert_eq!(page.per_page, 20);
+}
+
+#[test]
+fn whitelisted_sort_maps_to_its_column() {
+    let page =
+        ListPage::resolve(&params(None, None, "guest_name", "ASC"), &MEMBERSHIP_SORT).unwrap();
+    assert_eq!(page.order_by, "g.full_name ASC, lm.id ASC");
+
+    let page = ListPage::resolve(
+        &params(None, None, "points_spent", "desc"),
+        &REDEMPTION_SORT,
+    )
+    .unwrap();
+    assert_eq!(page.order_by, "rr.points_spent DESC, rr.id DESC");
+}
+
+#[test]
+fn rejects_sort_columns_outside_the_whitelist() {
+    for sort in ["lm.id; DROP TABLE guests", "password_hash", "g.full_name"] {
+        assert!(
+            matches!(
+                ListPage::resolve(&params(None, None, sort, ""), &MEMBERSHIP_SORT),
+                Err(ApiError::BadRequest(_))
+            ),
+            "{sort} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn rejects_bad_page_bounds_and_order() {
+    let bad = [
+        params(Some(0), None, "", ""),
+        params(None, Some(0), "", ""),
+        params(None, Some(MAX_PER_PAGE + 1), "", ""),
+        params(None, None, "", "sideways"),
+    ];
+
+    for case in bad {
+        assert!(
+            matches!(
+                ListPage::resolve(&case, &REDEMPTION_SORT),
+                Err(ApiError::BadRequest(_))
+            ),
+            "{case:?} should be rejected"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Tests for the login history.
+//!
+//! Classifying outcomes is pure and runs under any feature. Recording and
+//! listing logins is SQLite-backed and gated accordingly.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::login_history::{FAILURE, RATE_LIMITED, SUCCESS, outcome};
+
+#[test]
+fn lockouts_and_rate_limits_are_told_apart_from_failures() {
+    assert_eq!(outcome(&Ok::<_, ApiError>(())), SUCCESS);
+    assert_eq!(
+        outcome::<()>(&Err(ApiError::Unauthorized("bad password".into()))),
+        FAILURE
+    );
+    assert_eq!(
+        outcome::<()>(&Err(ApiError::TooManyRequests("locked".into()))),
+        RATE_LIMITED
+    );
+    assert_eq!(
+        outcome::<()>(&Err(ApiError::TooManyRequestsRetryAfter(
+            "slow down".into(),
+            30
+        ))),
+        RATE_LIMITED
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Json, State};
+    use hotel_app_be::handlers::auth::login_handler;
+    use hotel_app_be::models::LoginRequest;
+    use hotel_app_be::services::login_history::{self, FAILURE, PASSWORD_LOGIN, SUCCESS};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    const PASSWORD: &str = "Correct-Horse-9";
+    const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) Firefox/127.0";
+
+    async fn login(pool: &sqlx::SqlitePool, username: &str, password: &str) -> bool {
+        login_handler(
+            State(pool.clone()),
+            IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)),
+            Some(USER_AGENT.to_string()),
+            Json(LoginRequest {
+                username: username.to_string(),
+                password: password.to_string(),
+                totp_code: None,
+            }),
+        )
+        .await
+        .is_ok()
+    }
+
+    #[tokio::test]
+    async fn logins_are_listed_newest_first() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, password_hash, is_active, is_verified)
+             VALUES (9960, 'u-9960', 'day_clerk', 'day@example.com', $1, 1, 1)",
+        )
+        .bind(bcrypt::hash(PASSWORD, 4).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(!login(&pool, "day_clerk", "wrong").await);
+        assert!(login(&pool, "day@example.com", PASSWORD).await);
+        // Nobody to show a login for an unknown username to.
+        assert!(!login(&pool, "no_such_clerk", PASSWORD).await);
+
+        let history = login_history::login_history(&pool, 9960, None, None)
+            .await
+            .unwrap();
+        assert_eq!(history.total, 2);
+        let outcomes: Vec<_> = history.data.iter().map(|e| e.outcome.as_str()).collect();
+        assert_eq!(outcomes, [SUCCESS, FAILURE]);
+        let latest = &history.data[0];
+        assert_eq!(latest.method, PASSWORD_LOGIN);
+        assert_eq!(latest.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(latest.user_agent.as_deref(), Some(USER_AGENT));
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM login_history")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let second_page = login_history::login_history(&pool, 9960, Some(2), Some(1))
+            .await
+            .unwrap();
+        assert_eq!((second_page.page, second_page.page_size), (2, 1));
+        assert_eq!(second_page.data[0].outcome, FAILURE);
+    }
+}
@@ -0,0 +1,232 @@
+//! Tests for failed-login lockout.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use hotel_app_be::services::login_attempts::{
+    LOCKOUT_MINUTES_SETTING, MAX_ATTEMPTS_SETTING, validate_setting,
+};
+
+#[test]
+fn lockout_settings_are_validated() {
+    assert!(validate_setting(MAX_ATTEMPTS_SETTING, "5").is_ok());
+    assert!(validate_setting(LOCKOUT_MINUTES_SETTING, "1440").is_ok());
+    for bad in ["0", "-1", "101", "five"] {
+        assert!(validate_setting(MAX_ATTEMPTS_SETTING, bad).is_err());
+    }
+    for bad in ["0", "1441", "half an hour"] {
+        assert!(validate_setting(LOCKOUT_MINUTES_SETTING, bad).is_err());
+    }
+    assert!(validate_setting("some_other_setting", "0").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::auth::login_handler;
+    use hotel_app_be::models::{AuthResponse, LoginRequest};
+    use hotel_app_be::services::login_attempts::{self, LockoutPolicy, MAX_ATTEMPTS_SETTING};
+    use std::net::IpAddr;
+
+    const PASSWORD: &str = "Correct-Horse-9";
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, password_hash, is_active, is_verified)
+             VALUES (9810, 'u-9810', 'night_clerk', 'night@example.com', $1, 1, 1)",
+        )
+        .bind(bcrypt::hash(PASSWORD, 4).unwrap())
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE system_settings SET value = '3' WHERE key = $1")
+            .bind(MAX_ATTEMPTS_SETTING)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::from([203, 0, 113, last])
+    }
+
+    async fn login(
+        pool: &sqlx::SqlitePool,
+        from: IpAddr,
+        username: &str,
+        password: &str,
+    ) -> Result<AuthResponse, ApiError> {
+        login_with_code(pool, from, username, password, None).await
+    }
+
+    async fn login_with_code(
+        pool: &sqlx::SqlitePool,
+        from: IpAddr,
+        username: &str,
+        password: &str,
+        totp_code: Option<&str>,
+    ) -> Result<AuthResponse, ApiError> {
+        login_handler(
+            State(pool.clone()),
+            from,
+            None,
+            Json(LoginRequest {
+                username: username.to_string(),
+                password: password.to_string(),
+                totp_code: totp_code.map(str::to_string),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    fn describe(result: Result<AuthResponse, ApiError>) -> String {
+        match result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_and_real_usernames_lock_out_alike() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for username in ["night_clerk", "no_such_user"] {
+            let mut responses = Vec::new();
+            for _ in 0..4 {
+                responses.push(describe(login(&pool, ip(1), username, "wrong").await));
+            }
+            assert_eq!(
+                responses,
+                vec![
+                    "Unauthorized: Invalid credentials".to_string(),
+                    "Unauthorized: Invalid credentials".to_string(),
+                    "Too many requests: Too many failed login attempts. Try again in 30 minute(s)."
+                        .to_string(),
+                    "Too many requests: Too many failed login attempts. Try again in 30 minute(s)."
+                        .to_string(),
+                ],
+                "responses for {}",
+                username
+            );
+        }
+
+        // The right password does not get through a lock either.
+        assert!(matches!(
+            login(&pool, ip(1), "night_clerk", PASSWORD).await,
+            Err(ApiError::TooManyRequests(_))
+        ));
+        // Other addresses are unaffected.
+        assert!(matches!(
+            login(&pool, ip(2), "NIGHT_CLERK", "wrong").await,
+            Err(ApiError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn successful_login_resets_the_count() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for _ in 0..2 {
+            assert!(login(&pool, ip(3), "night_clerk", "wrong").await.is_err());
+        }
+        let response = login(&pool, ip(3), "night_clerk", PASSWORD).await.unwrap();
+        assert_eq!(response.user.username, "night_clerk");
+
+        // Two more failures stay under the limit of three.
+        for _ in 0..2 {
+            assert!(matches!(
+                login(&pool, ip(3), "night_clerk", "wrong").await,
+                Err(ApiError::Unauthorized(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_two_factor_does_not_reset_the_count() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        sqlx::query(
+            "UPDATE users SET two_factor_enabled = 1,
+                              two_factor_secret = 'JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP'
+             WHERE id = 9810",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for _ in 0..2 {
+            assert!(login(&pool, ip(5), "night_clerk", "wrong").await.is_err());
+        }
+        // The right password without a valid code is not a successful login.
+        for code in [None, Some("12345x")] {
+            assert!(matches!(
+                login_with_code(&pool, ip(5), "night_clerk", PASSWORD, code).await,
+                Err(ApiError::Unauthorized(_))
+            ));
+        }
+
+        // So the third wrong password still locks the account.
+        assert!(matches!(
+            login(&pool, ip(5), "night_clerk", "wrong").await,
+            Err(ApiError::TooManyRequests(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn expired_locks_and_stale_failures_start_over() {
+        let pool = common::setup_test_db().await;
+        let policy = LockoutPolicy {
+            max_attempts: 2,
+            lockout_minutes: 10,
+        };
+        let addr = ip(4).to_string();
+
+        assert_eq!(
+            login_attempts::record_failure(&pool, "ghost", &addr, &policy)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            login_attempts::record_failure(&pool, "ghost", &addr, &policy)
+                .await
+                .unwrap(),
+            Some(10)
+        );
+        assert_eq!(
+            login_attempts::locked_for(&pool, "Ghost", &addr)
+                .await
+                .unwrap(),
+            Some(10)
+        );
+
+        // Once the lock has run out, one failure is not enough to lock again.
+        let past = chrono::Utc::now() - chrono::Duration::minutes(1);
+        sqlx::query("UPDATE login_attempts SET locked_until = $1, last_failed_at = $1")
+            .bind(past)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            login_attempts::locked_for(&pool, "ghost", &addr)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            login_attempts::record_failure(&pool, "ghost", &addr, &policy)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Tests for adding points to many memberships at once.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so only checking
+//! a bulk adjustment before it is applied is covered here.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::models::BulkPointsInput;
+use hotel_app_be::services::loyalty::check_bulk_points;
+
+fn promotion(body: serde_json::Value) -> BulkPointsInput {
+    let mut input = serde_json::json!({"points": 500, "description": " Gold weekend bonus "});
+    input
+        .as_object_mut()
+        .unwrap()
+        .extend(body.as_object().unwrap().clone());
+    serde_json::from_value(input).unwrap()
+}
+
+#[test]
+fn a_promotion_needs_only_points_and_a_description() {
+    let input = promotion(serde_json::json!({"tier_level": 3, "enrolled_before": "2026-01-01"}));
+    assert_eq!(
+        check_bulk_points(&input, 1_000).unwrap(),
+        "Gold weekend bonus"
+    );
+
+    // Without a filter every active membership is credited.
+    assert!(check_bulk_points(&promotion(serde_json::json!({})), 1_000).is_ok());
+}
+
+#[test]
+fn invalid_promotions_are_rejected() {
+    for body in [
+        serde_json::json!({"points": 0}),
+        serde_json::json!({"points": -500}),
+        // Over the per-adjustment cap.
+        serde_json::json!({"points": 5_000}),
+        serde_json::json!({"tier_level": 0}),
+        serde_json::json!({"description": "  "}),
+    ] {
+        assert!(matches!(
+            check_bulk_points(&promotion(body), 1_000),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
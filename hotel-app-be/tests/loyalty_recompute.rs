@@ -0,0 +1,57 @@
+//! Tests for recomputing loyalty balances from transaction history.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so the repair is
+//! checked through the totals it writes back rather than against SQLite.
+
+use hotel_app_be::services::loyalty::PointsTotals;
+
+/// History of a member who earned, redeemed and had a manual adjustment.
+const HISTORY: &[(&str, i64)] = &[
+    ("earn", 500),
+    ("earn", 350),
+    ("redeem", -400),
+    ("adjust", 50),
+    ("adjust", -20),
+    ("earn", 120),
+];
+
+#[test]
+fn corrupted_balances_are_repaired_to_match_history() {
+    // Balances as a bad manual edit left them.
+    let stored = PointsTotals {
+        points_balance: 9_999,
+        lifetime_points: 0,
+    };
+
+    let recomputed = PointsTotals::from_transactions(HISTORY.iter().copied());
+
+    assert_ne!(recomputed, stored);
+    assert_eq!(
+        recomputed,
+        PointsTotals {
+            points_balance: 600,
+            lifetime_points: 970,
+        }
+    );
+}
+
+#[test]
+fn only_positive_earns_count_towards_lifetime_points() {
+    let totals = PointsTotals::from_transactions([
+        ("earn", 200),
+        ("adjust", 300),
+        ("transfer", 100),
+        ("expire", -150),
+        ("earn", -50),
+    ]);
+
+    assert_eq!(totals.lifetime_points, 200);
+    assert_eq!(totals.points_balance, 400);
+}
+
+#[test]
+fn a_membership_without_transactions_has_nothing() {
+    let totals = PointsTotals::from_transactions(std::iter::empty());
+    assert_eq!(totals.points_balance, 0);
+    assert_eq!(totals.lifetime_points, 0);
+}
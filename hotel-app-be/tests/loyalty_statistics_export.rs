@@ -0,0 +1,89 @@
+//! Tests for downloading the loyalty statistics.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so only the
+//! format choice and the CSV layout are covered here.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::models::{LoyaltyStatistics, PointsActivity, TierStatistics, TopMember};
+use hotel_app_be::services::loyalty::{
+    StatisticsExportFormat, parse_export_format, statistics_csv,
+};
+
+fn statistics() -> LoyaltyStatistics {
+    LoyaltyStatistics {
+        total_members: 3,
+        active_members: 3,
+        members_by_tier: vec![
+            TierStatistics {
+                tier_level: 1,
+                tier_name: "Bronze".to_string(),
+                count: 2,
+                percentage: 66.666,
+            },
+            TierStatistics {
+                tier_level: 2,
+                tier_name: "Silver, Plus".to_string(),
+                count: 1,
+                percentage: 33.333,
+            },
+        ],
+        total_points_issued: 5200,
+        total_points_redeemed: 700,
+        total_points_active: 4500,
+        average_points_per_member: 1500.0,
+        top_members: vec![TopMember {
+            guest_name: "Aiko \"Kit\" Mori".to_string(),
+            guest_email: "aiko@example.com".to_string(),
+            points_balance: 3000,
+            lifetime_points: 3400,
+            tier_level: 2,
+            membership_number: "LM-0001".to_string(),
+        }],
+        recent_transactions: vec![],
+        membership_growth: vec![],
+        points_activity: vec![PointsActivity {
+            date: "2026-10-15".to_string(),
+            points_earned: 250,
+            points_redeemed: 100,
+        }],
+    }
+}
+
+#[test]
+fn statistics_download_as_json_unless_csv_is_asked_for() {
+    assert_eq!(
+        parse_export_format(None).unwrap(),
+        StatisticsExportFormat::Json
+    );
+    assert_eq!(
+        parse_export_format(Some(" ")).unwrap(),
+        StatisticsExportFormat::Json
+    );
+    assert_eq!(
+        parse_export_format(Some("CSV")).unwrap(),
+        StatisticsExportFormat::Csv
+    );
+    assert!(matches!(
+        parse_export_format(Some("xlsx")),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn csv_has_a_section_for_tiers_top_members_and_points_activity() {
+    assert_eq!(
+        statistics_csv(&statistics()),
+        "Members by tier\n\
+         Tier Level,Tier,Members,Percentage\n\
+         1,Bronze,2,66.67\n\
+         2,\"Silver, Plus\",1,33.33\n\
+         \n\
+         Top members\n\
+         Membership Number,Guest,Email,Tier Level,Points Balance,Lifetime Points\n\
+         LM-0001,\"Aiko \"\"Kit\"\" Mori\",aiko@example.com,2,3000,3400\n\
+         \n\
+         Points activity\n\
+         Date,Points Earned,Points Redeemed\n\
+         2026-10-15,250,100\n"
+    );
+}
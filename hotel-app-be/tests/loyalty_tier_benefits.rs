@@ -0,0 +1,23 @@
+//! Tests for the benefits shown for a member's current and next tier.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so only reading
+//! a tier's configured benefits is covered here.
+
+use hotel_app_be::services::loyalty::tier_benefits;
+use serde_json::json;
+
+#[test]
+fn configured_next_tier_benefits_are_listed() {
+    let gold = json!(["Late checkout until 2pm", " Free breakfast ", "", null, 2]);
+    let benefits = tier_benefits(Some(&gold));
+
+    assert!(!benefits.is_empty());
+    assert_eq!(benefits, ["Late checkout until 2pm", "Free breakfast", "2"]);
+}
+
+#[test]
+fn tiers_without_a_benefit_list_have_none() {
+    assert!(tier_benefits(None).is_empty());
+    assert!(tier_benefits(Some(&json!(null))).is_empty());
+    assert!(tier_benefits(Some(&json!({"late_checkout": true}))).is_empty());
+}
@@ -0,0 +1,41 @@
+//! Tests for a member's progress toward their next loyalty tier.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so only measuring
+//! progress between tier minimums is covered here.
+
+use hotel_app_be::services::loyalty::tier_progress;
+
+#[test]
+fn progress_is_measured_from_the_current_tier_minimum() {
+    // Silver starts at 1,000 and Gold at 5,000.
+    let progress = tier_progress(3_000, 1_000, Some(5_000));
+    assert_eq!(progress.points_to_next_tier, Some(2_000));
+    assert_eq!(progress.progress_percent, 50.0);
+
+    assert_eq!(
+        tier_progress(1_000, 1_000, Some(5_000)).progress_percent,
+        0.0
+    );
+    assert_eq!(
+        tier_progress(1_333, 1_000, Some(4_000)).progress_percent,
+        11.1
+    );
+}
+
+#[test]
+fn progress_stays_between_zero_and_one_hundred() {
+    // Already past the next tier's minimum, awaiting promotion.
+    let ahead = tier_progress(6_000, 1_000, Some(5_000));
+    assert_eq!(ahead.points_to_next_tier, Some(0));
+    assert_eq!(ahead.progress_percent, 100.0);
+
+    // Placed in the tier by hand without its minimum points.
+    assert_eq!(tier_progress(200, 1_000, Some(5_000)).progress_percent, 0.0);
+}
+
+#[test]
+fn the_top_tier_has_no_next_tier_and_is_complete() {
+    let top = tier_progress(25_000, 10_000, None);
+    assert_eq!(top.points_to_next_tier, None);
+    assert_eq!(top.progress_percent, 100.0);
+}
@@ -0,0 +1,33 @@
+//! Tests for a member's own points history.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so only the type
+//! filter is covered here.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::loyalty::{MEMBER_TRANSACTION_TYPES, parse_transaction_type_filter};
+
+#[test]
+fn history_can_be_filtered_by_each_member_transaction_type() {
+    for transaction_type in MEMBER_TRANSACTION_TYPES {
+        assert_eq!(
+            parse_transaction_type_filter(Some(transaction_type)).unwrap(),
+            Some(transaction_type)
+        );
+    }
+    assert_eq!(
+        parse_transaction_type_filter(Some(" redeem ")).unwrap(),
+        Some("redeem")
+    );
+}
+
+#[test]
+fn blank_filters_show_everything_and_unknown_types_are_rejected() {
+    assert_eq!(parse_transaction_type_filter(None).unwrap(), None);
+    assert_eq!(parse_transaction_type_filter(Some("  ")).unwrap(), None);
+    for transaction_type in ["adjust", "EARN", "refund"] {
+        assert!(matches!(
+            parse_transaction_type_filter(Some(transaction_type)),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+//! Tests for transferring loyalty points between memberships.
+//!
+//! The loyalty tables only exist in the PostgreSQL schema, so the transfer is
+//! checked through the balances it computes rather than against SQLite.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::loyalty::{PointsTotals, transfer_balances};
+
+#[test]
+fn transfer_moves_points_between_balances() {
+    assert_eq!(transfer_balances(1_200, 300, 500).unwrap(), (700, 800));
+    // The whole balance can be moved.
+    assert_eq!(transfer_balances(500, 0, 500).unwrap(), (0, 500));
+}
+
+#[test]
+fn insufficient_balance_is_rejected() {
+    let err = transfer_balances(400, 300, 500).unwrap_err();
+    assert!(matches!(err, ApiError::BadRequest(ref msg) if msg == "Insufficient points balance"));
+}
+
+#[test]
+fn points_must_be_positive_and_fit() {
+    for points in [0, -50] {
+        assert!(matches!(
+            transfer_balances(1_000, 0, points),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+    assert!(matches!(
+        transfer_balances(10, i32::MAX - 5, 10),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn paired_transfers_keep_history_totals_in_step() {
+    // Source earned 1 200 and sent 500; destination earned 300 and got 500.
+    let source = PointsTotals::from_transactions([("earn", 1_200), ("transfer", -500)]);
+    let destination = PointsTotals::from_transactions([("earn", 300), ("transfer", 500)]);
+    let (from_balance, to_balance) = transfer_balances(1_200, 300, 500).unwrap();
+
+    assert_eq!(source.points_balance, i64::from(from_balance));
+    assert_eq!(destination.points_balance, i64::from(to_balance));
+    // A transfer is not earned, so lifetime points stay put.
+    assert_eq!(destination.lifetime_points, 300);
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use hotel_app_be::ApiError;
+    use hotel_app_be::models::TransferPointsInput;
+    use hotel_app_be::services::loyalty::transfer_points;
+
+    #[tokio::test]
+    async fn request_is_validated_before_touching_balances() {
+        let pool = common::setup_test_db().await;
+
+        for (to_membership_id, reason) in [(1, "Household merge"), (2, "  ")] {
+            let input = TransferPointsInput {
+                from_membership_id: 1,
+                to_membership_id,
+                points: 100,
+                reason: reason.to_string(),
+            };
+            assert!(matches!(
+                transfer_points(&pool, 1, &input).await,
+                Err(ApiError::BadRequest(_))
+            ));
+        }
+    }
+}
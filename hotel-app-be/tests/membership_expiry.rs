@@ -0,0 +1,48 @@
+//! Tests for loyalty membership renewal terms and settings.
+//!
+//! Expiring and renewing memberships needs the loyalty tables, which only
+//! exist in the PostgreSQL schema, so it isn't exercised by the SQLite test
+//! database.
+
+use chrono::{DateTime, Utc};
+use hotel_app_be::services::membership_expiry::{
+    DOWNGRADE_SETTING, TERM_SETTING, renewed_expiry, validate_setting,
+};
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn renewal_runs_on_from_a_current_expiry() {
+    let now = at("2026-10-16T08:00:00Z");
+
+    assert_eq!(
+        renewed_expiry(Some(at("2026-12-31T00:00:00Z")), now, 12),
+        Some(at("2027-12-31T00:00:00Z"))
+    );
+}
+
+#[test]
+fn lapsed_or_open_memberships_renew_from_now() {
+    let now = at("2026-10-16T08:00:00Z");
+
+    assert_eq!(
+        renewed_expiry(Some(at("2026-03-31T00:00:00Z")), now, 6),
+        Some(at("2027-04-16T08:00:00Z"))
+    );
+    assert_eq!(
+        renewed_expiry(None, now, 1),
+        Some(at("2026-11-16T08:00:00Z"))
+    );
+}
+
+#[test]
+fn term_setting_is_validated() {
+    assert!(validate_setting(TERM_SETTING, "12").is_ok());
+    assert!(validate_setting(TERM_SETTING, "120").is_ok());
+    assert!(validate_setting(TERM_SETTING, "0").is_err());
+    assert!(validate_setting(TERM_SETTING, "121").is_err());
+    assert!(validate_setting(TERM_SETTING, "a year").is_err());
+    assert!(validate_setting(DOWNGRADE_SETTING, "true").is_ok());
+}
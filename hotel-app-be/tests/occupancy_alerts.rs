@@ -0,0 +1,221 @@
+//! Tests for forecast occupancy alerts.
+//!
+//! The forecast and threshold evaluation are pure and run under any feature;
+//! the night-audit check is run against SQLite like the other integration
+//! tests.
+
+mod common;
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+use hotel_app_be::services::occupancy_alerts::{
+    DayOccupancy, ForecastStay, HIGH_SETTING, HORIZON_SETTING, LevelChange, OccupancyLevel,
+    Thresholds, evaluate_thresholds, forecast_occupancy, validate_setting,
+};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+/// Ten rooms, with the given number sold on consecutive nights from 1 Nov.
+fn series(sold: &[i64]) -> Vec<DayOccupancy> {
+    sold.iter()
+        .enumerate()
+        .map(|(i, &rooms_sold)| DayOccupancy {
+            date: date("2026-11-01") + chrono::Duration::days(i as i64),
+            rooms_sold,
+            rooms_available: 10,
+        })
+        .collect()
+}
+
+fn levels(changes: &[LevelChange]) -> Vec<(u32, OccupancyLevel)> {
+    changes.iter().map(|c| (c.date.day(), c.to)).collect()
+}
+
+#[test]
+fn nights_outside_the_normal_band_are_alerted() {
+    let changes = evaluate_thresholds(
+        &series(&[9, 8, 3, 5, 10, 2]),
+        &HashMap::new(),
+        &Thresholds::default(),
+    );
+
+    // 90% and 30% sit on the thresholds and count as crossed.
+    assert_eq!(
+        levels(&changes),
+        vec![
+            (1, OccupancyLevel::High),
+            (3, OccupancyLevel::Low),
+            (5, OccupancyLevel::High),
+            (6, OccupancyLevel::Low),
+        ]
+    );
+    assert!(changes.iter().all(|c| c.is_alert()));
+    assert_eq!(changes[0].occupancy_pct, Decimal::from(90));
+}
+
+#[test]
+fn a_night_is_alerted_once_per_crossing() {
+    let previous = HashMap::from([
+        (date("2026-11-01"), OccupancyLevel::High),
+        (date("2026-11-02"), OccupancyLevel::High),
+        (date("2026-11-03"), OccupancyLevel::Low),
+    ]);
+
+    let changes = evaluate_thresholds(&series(&[10, 6, 7]), &previous, &Thresholds::default());
+
+    // 1 Nov is still high; 2 Nov and 3 Nov went back to normal and are
+    // recorded without an alert.
+    assert_eq!(
+        levels(&changes),
+        vec![(2, OccupancyLevel::Normal), (3, OccupancyLevel::Normal)]
+    );
+    assert!(changes.iter().all(|c| !c.is_alert()));
+}
+
+#[test]
+fn custom_thresholds_move_the_band() {
+    let thresholds = Thresholds {
+        low_pct: Decimal::from(50),
+        high_pct: Decimal::from(75),
+    };
+    let changes = evaluate_thresholds(&series(&[8, 6, 5]), &HashMap::new(), &thresholds);
+
+    assert_eq!(
+        levels(&changes),
+        vec![(1, OccupancyLevel::High), (3, OccupancyLevel::Low)]
+    );
+}
+
+#[test]
+fn forecast_counts_each_room_once_per_night() {
+    let stays = [
+        ForecastStay {
+            room_id: 1,
+            check_in: date("2026-11-01"),
+            check_out: date("2026-11-03"),
+        },
+        // Back-to-back stay on the same room.
+        ForecastStay {
+            room_id: 1,
+            check_in: date("2026-11-03"),
+            check_out: date("2026-11-04"),
+        },
+        ForecastStay {
+            room_id: 2,
+            check_in: date("2026-11-02"),
+            check_out: date("2026-11-03"),
+        },
+    ];
+
+    let sold: Vec<i64> = forecast_occupancy(&stays, 4, date("2026-11-01"), 4)
+        .iter()
+        .map(|d| d.rooms_sold)
+        .collect();
+    assert_eq!(sold, vec![1, 2, 1, 0]);
+
+    let empty_hotel = DayOccupancy {
+        date: date("2026-11-01"),
+        rooms_sold: 0,
+        rooms_available: 0,
+    };
+    assert_eq!(empty_hotel.occupancy_pct(), Decimal::ZERO);
+}
+
+#[test]
+fn alert_settings_are_validated() {
+    assert!(validate_setting(HIGH_SETTING, "92.5").is_ok());
+    assert!(validate_setting(HIGH_SETTING, "120").is_err());
+    assert!(validate_setting(HORIZON_SETTING, "30").is_ok());
+    assert!(validate_setting(HORIZON_SETTING, "0").is_err());
+    assert!(validate_setting("timezone", "120").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date};
+    use hotel_app_be::services::occupancy_alerts::{
+        OccupancyLevel, Thresholds, check_occupancy_alerts,
+    };
+    use hotel_app_be::services::outbox::OCCUPANCY_THRESHOLD_CROSSED;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        // Take any other rooms out of the forecast so only these two count.
+        sqlx::query("UPDATE rooms SET is_active = 0")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (973, 'Alert Suite', 'ALS', 300.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9731, 'O731', 973, 'available', 1), (9732, 'O732', 973, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9731, 'Ravi', 'Kumar', 'Ravi Kumar')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // Both rooms are sold on 1 Nov; one room on 2 Nov.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9731, 'BK-OCC-9731', 9731, 9731, '2026-11-01', '2026-11-03', 300.0, 600.0, 'confirmed'),
+             (9732, 'BK-OCC-9732', 9731, 9732, '2026-11-01', '2026-11-02', 300.0, 300.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn alert_events(pool: &sqlx::SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM outbox WHERE event_type = $1")
+            .bind(OCCUPANCY_THRESHOLD_CROSSED)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn crossing_enqueues_one_event_per_night() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // 1 Nov is full; 3 Nov is empty; 2 Nov at 50% is normal.
+        let changes = check_occupancy_alerts(&pool, date("2026-11-01"), 3, &Thresholds::default())
+            .await
+            .unwrap();
+        let alerted: Vec<_> = changes.iter().map(|c| (c.date, c.to)).collect();
+        assert_eq!(
+            alerted,
+            vec![
+                (date("2026-11-01"), OccupancyLevel::High),
+                (date("2026-11-03"), OccupancyLevel::Low),
+            ]
+        );
+        assert_eq!(alert_events(&pool).await, 2);
+
+        // Nothing changed, so the next night's run stays quiet.
+        let again = check_occupancy_alerts(&pool, date("2026-11-01"), 3, &Thresholds::default())
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+        assert_eq!(alert_events(&pool).await, 2);
+    }
+}
@@ -0,0 +1,108 @@
+//! Tests for the occupancy report's nightly breakdown and rate.
+//!
+//! The occupancy rate is pure and runs under any feature. Counting rooms by
+//! night is SQLite-backed and gated accordingly.
+
+mod common;
+
+use hotel_app_be::services::occupancy::occupancy_rate;
+
+#[test]
+fn occupancy_compares_room_nights_with_room_nights() {
+    // One room taken for all ten nights of a ten-night range is full.
+    assert_eq!(occupancy_rate(10, 10), 100.0);
+    assert_eq!(occupancy_rate(15, 60), 25.0);
+    assert_eq!(occupancy_rate(0, 0), 0.0);
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::NaiveDate;
+    use hotel_app_be::services::occupancy::{
+        occupancy_by_night, occupancy_rate, occupied_room_nights,
+    };
+    use rust_decimal::Decimal;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// Four rooms: a five-night stay from 10 May, a night arriving on 12 May,
+    /// a day-use on 13 May, and a cancelled stay over the whole range.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (996, 'Night Queen', 'NQN', 100.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9961, 'N01', 996, 'available', 1), (9962, 'N02', 996, 'available', 1),
+                    (9963, 'N03', 996, 'available', 1), (9964, 'N04', 996, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9960, 'Ines', 'Varga')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9961, 'BK-NT-1', 9960, 9961, '2026-05-10', '2026-05-15', 100.0, 500.0, 'checked_out'),
+             (9962, 'BK-NT-2', 9960, 9962, '2026-05-12', '2026-05-13', 120.0, 120.0, 'checked_out'),
+             (9963, 'BK-NT-3', 9960, 9963, '2026-05-13', '2026-05-13', 60.0, 60.0, 'checked_out'),
+             (9964, 'BK-NT-4', 9960, 9964, '2026-05-09', '2026-05-17', 100.0, 800.0, 'cancelled')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn a_multi_night_stay_counts_on_every_night() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let nights = occupancy_by_night(&pool, date("2026-05-09"), date("2026-05-15"), 4)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            nights
+                .iter()
+                .map(|n| (n.date, n.rooms_occupied, n.arrivals))
+                .collect::<Vec<_>>(),
+            [
+                (date("2026-05-09"), 0, 0),
+                (date("2026-05-10"), 1, 1),
+                (date("2026-05-11"), 1, 0),
+                (date("2026-05-12"), 2, 1),
+                (date("2026-05-13"), 2, 1),
+                // The five-night stay leaves on the 15th.
+                (date("2026-05-14"), 1, 0),
+                (date("2026-05-15"), 0, 0),
+            ]
+        );
+        assert_eq!(nights[3].occupancy_rate, 50.0);
+        // Each night earns its share of the stays covering it.
+        assert_eq!(nights[3].revenue, Decimal::from(220));
+        assert_eq!(nights[4].revenue, Decimal::from(160));
+        assert_eq!(nights[6].revenue, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn a_fully_booked_day_is_fully_occupied() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        // Rooms 1 and 2 are taken on the 12th; fill the other two.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9965, 'BK-NT-5', 9960, 9963, '2026-05-11', '2026-05-13', 100.0, 200.0, 'checked_out'),
+             (9966, 'BK-NT-6', 9960, 9964, '2026-05-12', '2026-05-14', 100.0, 200.0, 'checked_out')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let day = date("2026-05-12");
+        let nights = occupancy_by_night(&pool, day, day, 4).await.unwrap();
+        assert_eq!(occupied_room_nights(&nights), 4);
+        assert_eq!(occupancy_rate(occupied_room_nights(&nights), 4), 100.0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Tests for the transactional outbox.
+//!
+//! Backoff and setting validation are pure and run under any feature; the
+//! database tests are gated on SQLite like the other integration tests.
+
+mod common;
+
+use chrono::Duration;
+use hotel_app_be::services::outbox::{
+    MAX_ATTEMPTS, WEBHOOK_URL_SETTING, next_retry_delay, validate_setting,
+};
+
+#[test]
+fn retry_delay_doubles_up_to_the_cap() {
+    assert_eq!(next_retry_delay(1), Some(Duration::seconds(30)));
+    assert_eq!(next_retry_delay(2), Some(Duration::seconds(60)));
+    assert_eq!(next_retry_delay(3), Some(Duration::seconds(120)));
+    assert_eq!(next_retry_delay(9), Some(Duration::hours(1)));
+}
+
+#[test]
+fn retries_stop_after_the_last_attempt() {
+    assert!(next_retry_delay(MAX_ATTEMPTS - 1).is_some());
+    assert_eq!(next_retry_delay(MAX_ATTEMPTS), None);
+}
+
+#[test]
+fn webhook_url_must_be_http_or_empty() {
+    assert!(validate_setting(WEBHOOK_URL_SETTING, "").is_ok());
+    assert!(validate_setting(WEBHOOK_URL_SETTING, "https://hooks.example.com/pms").is_ok());
+    assert!(validate_setting(WEBHOOK_URL_SETTING, "ftp://example.com").is_err());
+    assert!(validate_setting("check_in_time", "anything").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::Utc;
+    use hotel_app_be::services::outbox::{
+        BOOKING_CREATED, OutboxEvent, OutboxSink, dispatch_pending, enqueue, flush_pending,
+    };
+    use std::sync::Mutex;
+    use std::time::Duration as StdDuration;
+
+    struct RecordingSink {
+        delivered: Mutex<Vec<i64>>,
+        fail: bool,
+    }
+
+    impl RecordingSink {
+        fn new(fail: bool) -> Self {
+            Self {
+                delivered: Mutex::new(Vec::new()),
+                fail,
+            }
+        }
+    }
+
+    impl OutboxSink for RecordingSink {
+        async fn deliver(&self, event: &OutboxEvent) -> Result<(), String> {
+            if self.fail {
+                return Err("connection refused".to_string());
+            }
+            self.delivered.lock().unwrap().push(event.aggregate_id);
+            Ok(())
+        }
+    }
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (931, 'Outbox Queen', 'OBQ', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9301, 'O931', 931, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9301, 'Aina', 'Rahman', 'Aina Rahman')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Insert a booking and its event in one transaction, committing or not.
+    async fn create_booking(pool: &sqlx::SqlitePool, id: i64, commit: bool) {
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (?1, ?2, 9301, 9301, '2026-07-01', '2026-07-03', 150.0, 300.0, 'confirmed')",
+        )
+        .bind(id)
+        .bind(format!("BK-OBX-{id}"))
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        enqueue(
+            &mut tx,
+            BOOKING_CREATED,
+            "booking",
+            id,
+            &serde_json::json!({ "booking_id": id, "total_amount": "300" }),
+        )
+        .await
+        .unwrap();
+
+        if commit {
+            tx.commit().await.unwrap();
+        } else {
+            tx.rollback().await.unwrap();
+        }
+    }
+
+    async fn outbox_row(pool: &sqlx::SqlitePool, aggregate_id: i64) -> Option<(String, i32)> {
+        sqlx::query_as(
+            "SELECT status, attempts FROM outbox
+             WHERE aggregate_type = 'booking' AND aggregate_id = ?1",
+        )
+        .bind(aggregate_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn committed_booking_writes_an_outbox_row() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        create_booking(&pool, 9301, true).await;
+        create_booking(&pool, 9302, false).await;
+
+        assert_eq!(
+            outbox_row(&pool, 9301).await,
+            Some(("pending".to_string(), 0))
+        );
+        assert_eq!(outbox_row(&pool, 9302).await, None);
+
+        let payload: String =
+            sqlx::query_scalar("SELECT payload FROM outbox WHERE aggregate_id = 9301")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["booking_id"], 9301);
+    }
+
+    #[tokio::test]
+    async fn dispatcher_marks_delivered_events() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        create_booking(&pool, 9301, true).await;
+
+        let sink = RecordingSink::new(false);
+        let report = dispatch_pending(&pool, &sink, Utc::now(), 10)
+            .await
+            .unwrap();
+
+        assert_eq!(report.delivered, 1);
+        assert_eq!(*sink.delivered.lock().unwrap(), vec![9301]);
+        assert_eq!(
+            outbox_row(&pool, 9301).await,
+            Some(("delivered".to_string(), 1))
+        );
+        let delivered_at: Option<String> =
+            sqlx::query_scalar("SELECT delivered_at FROM outbox WHERE aggregate_id = 9301")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(delivered_at.is_some());
+
+        // Delivered rows are not sent again.
+        let again = dispatch_pending(&pool, &sink, Utc::now(), 10)
+            .await
+            .unwrap();
+        assert_eq!(again.delivered, 0);
+        assert_eq!(sink.delivered.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_is_rescheduled() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        create_booking(&pool, 9301, true).await;
+
+        let now = Utc::now();
+        let report = dispatch_pending(&pool, &RecordingSink::new(true), now, 10)
+            .await
+            .unwrap();
+
+        assert_eq!(report.retrying, 1);
+        assert_eq!(
+            outbox_row(&pool, 9301).await,
+            Some(("pending".to_string(), 1))
+        );
+        let last_error: Option<String> =
+            sqlx::query_scalar("SELECT last_error FROM outbox WHERE aggregate_id = 9301")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(last_error.as_deref(), Some("connection refused"));
+
+        // Not due again until the backoff has passed.
+        let sink = RecordingSink::new(false);
+        let early = dispatch_pending(&pool, &sink, now, 10).await.unwrap();
+        assert_eq!(early.delivered, 0);
+
+        let later = now + chrono::Duration::minutes(1);
+        let retried = dispatch_pending(&pool, &sink, later, 10).await.unwrap();
+        assert_eq!(retried.delivered, 1);
+        assert_eq!(
+            outbox_row(&pool, 9301).await,
+            Some(("delivered".to_string(), 2))
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_flush_attempts_every_pending_row() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        create_booking(&pool, 9301, true).await;
+        create_booking(&pool, 9302, true).await;
+
+        let sink = RecordingSink::new(false);
+        let report = flush_pending(&pool, &sink, StdDuration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(report.delivered, 2);
+        assert_eq!(*sink.delivered.lock().unwrap(), vec![9301, 9302]);
+        assert_eq!(
+            outbox_row(&pool, 9302).await,
+            Some(("delivered".to_string(), 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_shutdown_delivery_stays_pending_for_the_next_start() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        create_booking(&pool, 9301, true).await;
+
+        let report = flush_pending(&pool, &RecordingSink::new(true), StdDuration::from_secs(5))
+            .await
+            .unwrap();
+
+        // Tried once, then left for the dispatcher after a restart.
+        assert_eq!(report.retrying, 1);
+        assert_eq!(
+            outbox_row(&pool, 9301).await,
+            Some(("pending".to_string(), 1))
+        );
+    }
+}
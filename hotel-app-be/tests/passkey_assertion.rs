@@ -0,0 +1,265 @@
+//! Tests for passkey attestation parsing and assertion verification.
+//!
+//! Assertions are signed here with fixed P-256 test keys, the way an
+//! authenticator would, and checked with `verify_assertion`.
+
+mod common;
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use hotel_app_be::ApiError;
+use hotel_app_be::core::webauthn::{Assertion, parse_attestation_object, verify_assertion};
+use jsonwebtoken::{Algorithm, EncodingKey};
+use sha2::{Digest, Sha256};
+
+const RP_ID: &str = "hotel.example.com";
+const ORIGIN: &str = "https://hotel.example.com";
+const CHALLENGE: [u8; 4] = [7, 1, 2, 9];
+const CREDENTIAL_ID: [u8; 3] = [0xca, 0xfe, 0x01];
+
+/// PKCS#8 private key whose public half is `KEY_X`/`KEY_Y`.
+const KEY_PKCS8: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgCzsis0SInBk+ndYLcodnfwpA0p7ol3cX0YLPVf4UKyyhRANCAARFGNG0kSwq0vKaChDpbTiI2T9bdytAPkJa+1VdtXOclGrA1b+bbbX1Eo7ag5H9ut8HE41oUXpwvuu8QwOGJvwd";
+const KEY_X: &str = "4518d1b4912c2ad2f29a0a10e96d3888d93f5b772b403e425afb555db5739c94";
+const KEY_Y: &str = "6ac0d5bf9b6db5f5128eda8391fdbadf07138d68517a70beebbc43038626fc1d";
+
+/// An unrelated P-256 key, used to forge signatures.
+const OTHER_PKCS8: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgba+Z0RawAjbF36z5FFk9OpAUJqxk920e21ctJtUw1vihRANCAASE2sTpHxPWLo9T+mudp7AZmPRwo4H0q0PnRfxjzWbBYl1bdqyakAOALptXZlCWbwK5jxRdaMRzqHMLYMcsaBxO";
+
+/// COSE_Key for the test key: {1: 2, 3: -7, -1: 1, -2: x, -3: y}.
+fn cose_key() -> Vec<u8> {
+    let mut key = vec![0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20];
+    key.extend(hex::decode(KEY_X).unwrap());
+    key.extend([0x22, 0x58, 0x20]);
+    key.extend(hex::decode(KEY_Y).unwrap());
+    key
+}
+
+fn authenticator_data(rp_id: &str, flags: u8, counter: u32) -> Vec<u8> {
+    let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+    data.push(flags);
+    data.extend(counter.to_be_bytes());
+    data
+}
+
+fn client_data(kind: &str, challenge: &[u8]) -> Vec<u8> {
+    client_data_from(ORIGIN, kind, challenge)
+}
+
+fn client_data_from(origin: &str, kind: &str, challenge: &[u8]) -> Vec<u8> {
+    serde_json::json!({
+        "type": kind,
+        "challenge": URL_SAFE_NO_PAD.encode(challenge),
+        "origin": origin,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Signs like an authenticator: ECDSA over authData || SHA-256(clientData),
+/// returned in ASN.1 DER form.
+fn sign(pkcs8: &str, auth_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+    let mut message = auth_data.to_vec();
+    message.extend(Sha256::digest(client_data));
+    let key = EncodingKey::from_ec_der(&STANDARD.decode(pkcs8).unwrap());
+    let fixed = URL_SAFE_NO_PAD
+        .decode(jsonwebtoken::crypto::sign(&message, &key, Algorithm::ES256).unwrap())
+        .unwrap();
+
+    let mut body = Vec::new();
+    for half in fixed.chunks(32) {
+        let start = half.iter().position(|&b| b != 0).unwrap_or(31);
+        let mut int = half[start..].to_vec();
+        if int[0] & 0x80 != 0 {
+            int.insert(0, 0);
+        }
+        body.extend([0x02, int.len() as u8]);
+        body.extend(int);
+    }
+    let mut der = vec![0x30, body.len() as u8];
+    der.extend(body);
+    der
+}
+
+fn verify(
+    auth_data: &[u8],
+    client_data: &[u8],
+    signature: &[u8],
+    stored_counter: u32,
+) -> Result<u32, ApiError> {
+    verify_assertion(
+        &Assertion {
+            authenticator_data: auth_data,
+            client_data_json: client_data,
+            signature,
+        },
+        &cose_key(),
+        stored_counter,
+        RP_ID,
+        ORIGIN,
+        &CHALLENGE,
+    )
+}
+
+#[test]
+fn genuine_assertion_verifies_and_returns_the_counter() {
+    let auth_data = authenticator_data(RP_ID, 0x05, 12);
+    let client = client_data("webauthn.get", &CHALLENGE);
+    let signature = sign(KEY_PKCS8, &auth_data, &client);
+
+    assert_eq!(verify(&auth_data, &client, &signature, 11).unwrap(), 12);
+}
+
+#[test]
+fn forged_signature_is_rejected() {
+    let auth_data = authenticator_data(RP_ID, 0x05, 3);
+    let client = client_data("webauthn.get", &CHALLENGE);
+
+    // Signed with a key other than the registered one.
+    let forged = sign(OTHER_PKCS8, &auth_data, &client);
+    assert!(matches!(
+        verify(&auth_data, &client, &forged, 0),
+        Err(ApiError::Unauthorized(_))
+    ));
+
+    // A genuine signature does not cover altered authenticator data.
+    let signature = sign(KEY_PKCS8, &auth_data, &client);
+    let tampered = authenticator_data(RP_ID, 0x05, 4);
+    assert!(matches!(
+        verify(&tampered, &client, &signature, 0),
+        Err(ApiError::Unauthorized(_))
+    ));
+}
+
+#[test]
+fn replayed_counter_is_rejected() {
+    let client = client_data("webauthn.get", &CHALLENGE);
+    for counter in [5, 4] {
+        let auth_data = authenticator_data(RP_ID, 0x05, counter);
+        let signature = sign(KEY_PKCS8, &auth_data, &client);
+        assert!(matches!(
+            verify(&auth_data, &client, &signature, 5),
+            Err(ApiError::Unauthorized(_))
+        ));
+    }
+
+    // Authenticators without a counter always report 0.
+    let auth_data = authenticator_data(RP_ID, 0x05, 0);
+    let signature = sign(KEY_PKCS8, &auth_data, &client);
+    assert_eq!(verify(&auth_data, &client, &signature, 0).unwrap(), 0);
+}
+
+#[test]
+fn client_data_and_relying_party_must_match() {
+    let cases = [
+        (
+            authenticator_data(RP_ID, 0x05, 1),
+            client_data("webauthn.create", &CHALLENGE),
+        ),
+        (
+            authenticator_data(RP_ID, 0x05, 1),
+            client_data("webauthn.get", &[1, 2, 3]),
+        ),
+        (
+            authenticator_data("evil.example.com", 0x05, 1),
+            client_data("webauthn.get", &CHALLENGE),
+        ),
+        // User present flag missing.
+        (
+            authenticator_data(RP_ID, 0x04, 1),
+            client_data("webauthn.get", &CHALLENGE),
+        ),
+    ];
+    for (auth_data, client) in cases {
+        let signature = sign(KEY_PKCS8, &auth_data, &client);
+        assert!(matches!(
+            verify(&auth_data, &client, &signature, 0),
+            Err(ApiError::Unauthorized(_))
+        ));
+    }
+}
+
+#[test]
+fn assertion_from_another_origin_is_rejected() {
+    let auth_data = authenticator_data(RP_ID, 0x05, 1);
+    for origin in [
+        "https://evil.example.com",
+        "http://hotel.example.com",
+        "https://hotel.example.com:8443",
+    ] {
+        let client = client_data_from(origin, "webauthn.get", &CHALLENGE);
+        let signature = sign(KEY_PKCS8, &auth_data, &client);
+        assert!(
+            matches!(
+                verify(&auth_data, &client, &signature, 0),
+                Err(ApiError::Unauthorized(_))
+            ),
+            "{}",
+            origin
+        );
+    }
+
+    // Client data without an origin is rejected too.
+    let client = serde_json::json!({
+        "type": "webauthn.get",
+        "challenge": URL_SAFE_NO_PAD.encode(CHALLENGE),
+    })
+    .to_string()
+    .into_bytes();
+    let signature = sign(KEY_PKCS8, &auth_data, &client);
+    assert!(matches!(
+        verify(&auth_data, &client, &signature, 0),
+        Err(ApiError::Unauthorized(_))
+    ));
+}
+
+#[test]
+fn attestation_yields_the_credential_key() {
+    let mut auth_data = authenticator_data(RP_ID, 0x45, 2);
+    auth_data.extend([0u8; 16]);
+    auth_data.extend((CREDENTIAL_ID.len() as u16).to_be_bytes());
+    auth_data.extend(CREDENTIAL_ID);
+    auth_data.extend(cose_key());
+
+    // {"fmt": "none", "attStmt": {}, "authData": <bytes>}
+    let mut attestation = vec![0xa3, 0x63];
+    attestation.extend(b"fmt");
+    attestation.push(0x64);
+    attestation.extend(b"none");
+    attestation.push(0x67);
+    attestation.extend(b"attStmt");
+    attestation.extend([0xa0, 0x68]);
+    attestation.extend(b"authData");
+    attestation.extend([0x59, 0x00, auth_data.len() as u8]);
+    attestation.extend(&auth_data);
+
+    let parsed = parse_attestation_object(&attestation).unwrap();
+    assert_eq!(parsed.sign_count, 2);
+    let credential = parsed.attested_credential.unwrap();
+    assert_eq!(credential.credential_id, CREDENTIAL_ID);
+    assert_eq!(credential.public_key, cose_key());
+
+    assert!(matches!(
+        parse_attestation_object(&attestation[..attestation.len() - 10]),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn placeholder_keys_cannot_log_in() {
+    let auth_data = authenticator_data(RP_ID, 0x05, 1);
+    let client = client_data("webauthn.get", &CHALLENGE);
+    let signature = sign(KEY_PKCS8, &auth_data, &client);
+    let result = verify_assertion(
+        &Assertion {
+            authenticator_data: &auth_data,
+            client_data_json: &client,
+            signature: &signature,
+        },
+        &[0u8; 64],
+        0,
+        RP_ID,
+        ORIGIN,
+        &CHALLENGE,
+    );
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+}
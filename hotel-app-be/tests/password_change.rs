@@ -0,0 +1,111 @@
+//! Tests for the forced password change after an account is created for
+//! someone.
+//!
+//! Which requests stay open is pure and runs under any feature. Refusing and
+//! then allowing a flagged user is SQLite-backed and gated accordingly.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum::response::IntoResponse;
+use hotel_app_be::ApiError;
+use hotel_app_be::core::password_change::is_exempt;
+
+#[test]
+fn only_the_password_change_and_session_requests_stay_open() {
+    assert!(is_exempt(&Method::POST, "/profile/password"));
+    assert!(is_exempt(&Method::POST, "/auth/logout"));
+    assert!(is_exempt(&Method::POST, "/auth/refresh"));
+
+    assert!(!is_exempt(&Method::GET, "/profile"));
+    assert!(!is_exempt(&Method::GET, "/profile/password"));
+    assert!(!is_exempt(&Method::GET, "/bookings"));
+}
+
+#[tokio::test]
+async fn refusals_carry_a_code_the_client_can_check() {
+    let response = ApiError::PasswordChangeRequired.into_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["code"], "password_change_required");
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::{
+        Router,
+        body::Body,
+        extract::{Extension, Json, State},
+        http::{Request, StatusCode, header},
+        routing::{get, post},
+    };
+    use hotel_app_be::core::auth::AuthService;
+    use hotel_app_be::core::password_change::{is_required, require_password_change};
+    use hotel_app_be::handlers::profile::update_password_handler;
+    use hotel_app_be::models::PasswordUpdateInput;
+    use tower::ServiceExt;
+
+    const ISSUED_PASSWORD: &str = "Issued-By-Admin-1";
+
+    /// Status of `method path` through the middleware, as user 9950.
+    async fn status(pool: &sqlx::SqlitePool, method: &str, path: &str) -> StatusCode {
+        let app = Router::new()
+            .route("/profile", get(|| async { "profile" }))
+            .route("/profile/password", post(|| async { "changed" }))
+            .layer(axum::middleware::from_fn_with_state(
+                pool.clone(),
+                require_password_change,
+            ));
+        let token = AuthService::generate_jwt(9950, "new_clerk".to_string(), vec![]).unwrap();
+        let request = Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn flagged_users_can_only_change_their_password() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, password_hash, is_active, is_verified,
+                                must_change_password)
+             VALUES (9950, 'u-9950', 'new_clerk', 'new@example.com', $1, 1, 1, 1)",
+        )
+        .bind(bcrypt::hash(ISSUED_PASSWORD, 4).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            status(&pool, "GET", "/profile").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&pool, "POST", "/profile/password").await,
+            StatusCode::OK
+        );
+
+        update_password_handler(
+            State(pool.clone()),
+            Extension(9950),
+            Json(PasswordUpdateInput {
+                current_password: ISSUED_PASSWORD.to_string(),
+                new_password: "Chosen-By-Me-42".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert!(!is_required(&pool, 9950).await.unwrap());
+        assert_eq!(status(&pool, "GET", "/profile").await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,181 @@
+//! Tests for the configurable password policy.
+//!
+//! The policy rules are pure and run under any feature; loading them from
+//! settings and enforcing them on a password change runs against SQLite.
+
+mod common;
+
+use std::collections::HashSet;
+
+use hotel_app_be::core::auth::{PasswordPolicy, PasswordRequirement};
+use hotel_app_be::services::password_policy::{
+    BREACHED_LIST_SETTING, MIN_LENGTH_SETTING, load_breached_list, validate_setting,
+};
+
+const ACCEPTED_BY_DEFAULT: &str = "S3cure_Rooms!";
+
+#[test]
+fn tightening_the_policy_rejects_a_previously_accepted_password() {
+    assert!(
+        PasswordPolicy::default()
+            .validate(ACCEPTED_BY_DEFAULT)
+            .is_ok()
+    );
+
+    let longer = PasswordPolicy {
+        min_length: 16,
+        ..Default::default()
+    };
+    assert_eq!(
+        longer.validate(ACCEPTED_BY_DEFAULT),
+        Err(vec![PasswordRequirement::MinLength(16)])
+    );
+
+    let breached = PasswordPolicy {
+        breached_passwords: HashSet::from(["s3cure_rooms!".to_string()]),
+        ..Default::default()
+    };
+    assert_eq!(
+        breached.validate(ACCEPTED_BY_DEFAULT),
+        Err(vec![PasswordRequirement::NotBreached])
+    );
+}
+
+#[test]
+fn relaxed_character_classes_are_not_required() {
+    let relaxed = PasswordPolicy {
+        min_length: 12,
+        require_uppercase: false,
+        require_digit: false,
+        require_symbol: false,
+        ..Default::default()
+    };
+    assert!(relaxed.validate("quiet harbour view").is_ok());
+    // The built-in weak-password list still applies.
+    assert!(relaxed.validate("mypassword is long").is_err());
+    assert!(
+        PasswordPolicy::default()
+            .validate("quiet harbour view")
+            .is_err()
+    );
+}
+
+#[test]
+fn breached_list_is_read_one_password_per_line() {
+    let path = std::env::temp_dir().join(format!("breached-{}.txt", std::process::id()));
+    std::fs::write(&path, "Summer2024!\n\n  Hunter2!  \n").unwrap();
+
+    let list = load_breached_list(path.to_str().unwrap()).unwrap();
+    assert_eq!(
+        list,
+        HashSet::from(["summer2024!".to_string(), "hunter2!".to_string()])
+    );
+    assert!(validate_setting(BREACHED_LIST_SETTING, path.to_str().unwrap()).is_ok());
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(validate_setting(BREACHED_LIST_SETTING, path.to_str().unwrap()).is_err());
+    assert!(validate_setting(BREACHED_LIST_SETTING, "").is_ok());
+}
+
+#[test]
+fn length_settings_are_validated() {
+    assert!(validate_setting(MIN_LENGTH_SETTING, "12").is_ok());
+    assert!(validate_setting(MIN_LENGTH_SETTING, "4").is_err());
+    assert!(validate_setting(MIN_LENGTH_SETTING, "twelve").is_err());
+    assert!(validate_setting("timezone", "4").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{ACCEPTED_BY_DEFAULT, common};
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::auth::AuthService;
+    use hotel_app_be::handlers::profile::update_password_handler;
+    use hotel_app_be::models::PasswordUpdateInput;
+    use hotel_app_be::services::password_policy::{
+        BREACHED_CHECK_SETTING, BREACHED_LIST_SETTING, MIN_LENGTH_SETTING, REQUIRE_SYMBOL_SETTING,
+        validate_password,
+    };
+
+    const USER: i64 = 9760;
+    const CURRENT: &str = "Old_Passw0rd!x";
+
+    async fn set(pool: &sqlx::SqlitePool, key: &str, value: &str) {
+        sqlx::query("UPDATE system_settings SET value = $1 WHERE key = $2")
+            .bind(value)
+            .bind(key)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn change_password(pool: &sqlx::SqlitePool, new_password: &str) -> Result<(), ApiError> {
+        update_password_handler(
+            State(pool.clone()),
+            Extension(USER),
+            Json(PasswordUpdateInput {
+                current_password: CURRENT.to_string(),
+                new_password: new_password.to_string(),
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn settings_tighten_the_policy() {
+        let pool = common::setup_test_db().await;
+        assert!(validate_password(&pool, ACCEPTED_BY_DEFAULT).await.is_ok());
+
+        set(&pool, MIN_LENGTH_SETTING, "16").await;
+        match validate_password(&pool, "short1").await {
+            Err(ApiError::Validation(message, details)) => {
+                let codes: Vec<_> = details.iter().map(|d| d.code).collect();
+                assert_eq!(codes, ["min_length", "uppercase", "symbol"]);
+                assert_eq!(
+                    message,
+                    "Password must be at least 16 characters long, contain at least one \
+                     uppercase letter and contain at least one special character"
+                );
+            }
+            other => panic!("expected the unmet requirements, got {:?}", other),
+        }
+
+        set(&pool, MIN_LENGTH_SETTING, "8").await;
+        let path = std::env::temp_dir().join(format!("breached-db-{}.txt", std::process::id()));
+        std::fs::write(&path, format!("{}\n", ACCEPTED_BY_DEFAULT)).unwrap();
+        set(&pool, BREACHED_LIST_SETTING, path.to_str().unwrap()).await;
+        // The list is only consulted once the check is switched on.
+        assert!(validate_password(&pool, ACCEPTED_BY_DEFAULT).await.is_ok());
+        set(&pool, BREACHED_CHECK_SETTING, "true").await;
+        let result = validate_password(&pool, ACCEPTED_BY_DEFAULT).await;
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(ApiError::Validation(..))));
+    }
+
+    #[tokio::test]
+    async fn password_change_enforces_the_configured_policy() {
+        let pool = common::setup_test_db().await;
+        let hash = AuthService::hash_password(CURRENT).await.unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, password_hash)
+             VALUES ($1, 'u-9760', 'frontdesk', 'frontdesk@example.com', $2)",
+        )
+        .bind(USER)
+        .bind(&hash)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        set(&pool, MIN_LENGTH_SETTING, "16").await;
+        assert!(matches!(
+            change_password(&pool, ACCEPTED_BY_DEFAULT).await,
+            Err(ApiError::Validation(..))
+        ));
+
+        // A relaxed rule lets through a password the built-in policy refuses.
+        set(&pool, REQUIRE_SYMBOL_SETTING, "false").await;
+        change_password(&pool, "Harbour View 2026").await.unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests for the cap on manual loyalty points adjustments.
+//!
+//! Applying an adjustment needs the loyalty tables, which only exist in the
+//! PostgreSQL schema, so the SQLite-backed tests only cover rejections,
+//! which happen before any membership is read.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::loyalty::{
+    MAX_ADJUSTMENT_SETTING, check_points_adjustment, validate_setting,
+};
+
+#[test]
+fn adjustments_must_be_positive_and_within_the_cap() {
+    assert!(check_points_adjustment(1, 1_000).is_ok());
+    assert!(check_points_adjustment(1_000, 1_000).is_ok());
+    for points in [0, -5, 1_001, i32::MAX] {
+        assert!(matches!(
+            check_points_adjustment(points, 1_000),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
+
+#[test]
+fn cap_setting_is_validated() {
+    assert!(validate_setting(MAX_ADJUSTMENT_SETTING, "100000").is_ok());
+    assert!(validate_setting(MAX_ADJUSTMENT_SETTING, "0").is_err());
+    assert!(validate_setting(MAX_ADJUSTMENT_SETTING, "10000001").is_err());
+    assert!(validate_setting(MAX_ADJUSTMENT_SETTING, "lots").is_err());
+    assert!(validate_setting("hotel_name", "lots").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::loyalty::{add_points_handler, redeem_points_handler};
+    use hotel_app_be::models::AddPointsInput;
+
+    const STAFF: i64 = 9960;
+
+    fn points(points: i32) -> Json<AddPointsInput> {
+        Json(AddPointsInput {
+            points,
+            description: Some("Goodwill".to_string()),
+        })
+    }
+
+    #[tokio::test]
+    async fn huge_adjustments_are_rejected() {
+        let pool = common::setup_test_db().await;
+
+        let added = add_points_handler(
+            State(pool.clone()),
+            Extension(STAFF),
+            Path(1),
+            points(i32::MAX),
+        )
+        .await;
+        assert!(
+            matches!(added, Err(ApiError::BadRequest(ref msg)) if msg.contains("100000")),
+            "{:?}",
+            added.err()
+        );
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO system_settings (key, value) VALUES ('loyalty_max_points_adjustment', '500')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let redeemed =
+            redeem_points_handler(State(pool.clone()), Extension(STAFF), Path(1), points(501))
+                .await;
+        assert!(matches!(redeemed, Err(ApiError::BadRequest(ref msg)) if msg.contains("500")));
+    }
+}
@@ -0,0 +1,148 @@
+//! Tests for the loyalty points-earning rules.
+//!
+//! Awarding itself needs the loyalty tables, which only exist in the
+//! PostgreSQL schema, so it isn't exercised by the SQLite test database.
+
+use chrono::{NaiveDate, Utc};
+use hotel_app_be::ApiError;
+use hotel_app_be::models::{PointsRule, PointsRuleInput, PointsRuleType};
+use hotel_app_be::services::loyalty::{stay_points, validate_points_rule};
+use rust_decimal::Decimal;
+
+const SUITE: i64 = 7;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn rule(rule_type: &str, bonus_points: Option<i32>, multiplier: Option<&str>) -> PointsRule {
+    PointsRule {
+        id: 0,
+        name: rule_type.to_string(),
+        rule_type: rule_type.to_string(),
+        room_type_id: None,
+        bonus_points,
+        multiplier: multiplier.map(|m| m.parse().unwrap()),
+        starts_on: None,
+        ends_on: None,
+        is_active: true,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn points(amount: i64, room_type_id: i64, check_in: &str, rules: &[PointsRule]) -> i32 {
+    stay_points(
+        Decimal::from(amount),
+        Decimal::ONE,
+        Some(room_type_id),
+        date(check_in),
+        rules,
+    )
+}
+
+#[test]
+fn without_rules_a_stay_earns_its_amount_at_the_rate() {
+    assert_eq!(points(250, SUITE, "2026-11-01", &[]), 250);
+    assert_eq!(
+        stay_points(
+            "199.99".parse().unwrap(),
+            "1.5".parse().unwrap(),
+            None,
+            date("2026-11-01"),
+            &[]
+        ),
+        299
+    );
+}
+
+#[test]
+fn promotions_multiply_within_their_window() {
+    let double = PointsRule {
+        starts_on: Some(date("2026-12-01")),
+        ends_on: Some(date("2026-12-31")),
+        ..rule("multiplier", None, Some("2"))
+    };
+    let triple_off = PointsRule {
+        is_active: false,
+        ..rule("multiplier", None, Some("3"))
+    };
+    let rules = [double, triple_off, rule("multiplier", None, Some("1.5"))];
+
+    // The highest applicable multiplier wins; they don't stack.
+    assert_eq!(points(100, SUITE, "2026-12-31", &rules), 200);
+    assert_eq!(points(100, SUITE, "2027-01-01", &rules), 150);
+}
+
+#[test]
+fn bonuses_add_for_their_room_type_and_floors_set_the_minimum() {
+    let suite_bonus = PointsRule {
+        room_type_id: Some(SUITE),
+        ..rule("bonus", Some(500), None)
+    };
+    let rules = [
+        suite_bonus,
+        rule("bonus", Some(50), None),
+        rule("floor", Some(100), None),
+        rule("floor", Some(300), None),
+    ];
+
+    assert_eq!(points(1000, SUITE, "2026-11-01", &rules), 1550);
+    assert_eq!(points(1000, 8, "2026-11-01", &rules), 1050);
+    assert_eq!(points(20, 8, "2026-11-01", &rules), 300);
+    // A stay with nothing to pay earns nothing.
+    assert_eq!(points(0, SUITE, "2026-11-01", &rules), 0);
+}
+
+fn input(rule_type: &str, bonus_points: Option<i32>, multiplier: Option<f64>) -> PointsRuleInput {
+    PointsRuleInput {
+        name: "Winter promotion".to_string(),
+        rule_type: rule_type.to_string(),
+        room_type_id: None,
+        bonus_points,
+        multiplier,
+        starts_on: None,
+        ends_on: None,
+        is_active: None,
+    }
+}
+
+fn rejection(input: &PointsRuleInput) -> String {
+    match validate_points_rule(input) {
+        Err(ApiError::BadRequest(msg)) => msg,
+        other => panic!("expected a bad request, got {:?}", other),
+    }
+}
+
+#[test]
+fn rules_are_validated_by_type() {
+    assert_eq!(
+        validate_points_rule(&input("multiplier", None, Some(2.0))).unwrap(),
+        PointsRuleType::Multiplier
+    );
+    assert_eq!(
+        validate_points_rule(&input("floor", Some(100), None)).unwrap(),
+        PointsRuleType::Floor
+    );
+
+    assert_eq!(
+        rejection(&input("double", None, Some(2.0))),
+        "rule_type must be one of: bonus, multiplier, floor"
+    );
+    assert!(rejection(&input("bonus", None, None)).starts_with("bonus_points"));
+    assert!(rejection(&input("floor", Some(0), None)).starts_with("bonus_points"));
+    assert!(rejection(&input("multiplier", None, Some(0.5))).starts_with("multiplier"));
+    assert!(rejection(&input("multiplier", None, Some(11.0))).starts_with("multiplier"));
+
+    let backwards = PointsRuleInput {
+        starts_on: Some(date("2026-12-31")),
+        ends_on: Some(date("2026-12-01")),
+        ..input("multiplier", None, Some(2.0))
+    };
+    assert!(rejection(&backwards).starts_with("ends_on"));
+    let unnamed = PointsRuleInput {
+        name: " ".to_string(),
+        ..input("bonus", Some(10), None)
+    };
+    assert!(rejection(&unnamed).starts_with("name"));
+}
@@ -0,0 +1,165 @@
+//! Tests for linking portal accounts to their guest record.
+//!
+//! Name splitting is pure and runs under any feature; creating and linking
+//! guests runs against SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::services::portal_guest::PortalGuestDetails;
+
+#[test]
+fn account_name_is_split_into_first_and_last() {
+    let details = PortalGuestDetails::from_account(
+        Some("  Siti Nur Aisyah "),
+        "siti",
+        "siti@example.com",
+        None,
+    );
+    assert_eq!(details.first_name, "Siti");
+    assert_eq!(details.last_name, "Nur Aisyah");
+
+    let unnamed = PortalGuestDetails::from_account(Some(" "), "kumar88", "k@example.com", None);
+    assert_eq!(unnamed.first_name, "kumar88");
+    assert_eq!(unnamed.last_name, "");
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::guest_portal::get_own_guest_profile;
+    use hotel_app_be::services::portal_guest::{
+        AUTO_CREATE_SETTING, PortalGuestDetails, create_linked_guest, ensure_portal_guest,
+    };
+
+    const NEW_USER: i64 = 9790;
+    const STAFF_USER: i64 = 9791;
+
+    async fn insert_user(pool: &sqlx::SqlitePool, id: i64, user_type: &str, full_name: &str) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, full_name, user_type)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(id)
+        .bind(format!("u-{id}"))
+        .bind(format!("user{id}"))
+        .bind(format!("user{id}@example.com"))
+        .bind(full_name)
+        .bind(user_type)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Relationship and booking permission of each of the user's links.
+    async fn links(pool: &sqlx::SqlitePool, user_id: i64) -> Vec<(i64, String, bool, bool)> {
+        sqlx::query_as(
+            "SELECT guest_id, relationship_type, can_book_for, can_modify
+             FROM user_guests WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn signup_links_a_guest_the_portal_can_book_for() {
+        let pool = common::setup_test_db().await;
+
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, user_type)
+             VALUES (9790, 'u-9790', 'hafiz', 'hafiz@example.com', 'guest')",
+        )
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        let details = PortalGuestDetails {
+            first_name: "Hafiz".to_string(),
+            last_name: "Rahman".to_string(),
+            email: "hafiz@example.com".to_string(),
+            phone: Some("+60123000111".to_string()),
+        };
+        let guest_id = create_linked_guest(&mut tx, NEW_USER, &details)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            links(&pool, NEW_USER).await,
+            vec![(guest_id, "self".to_string(), true, true)]
+        );
+        let users_guest: Option<i64> =
+            sqlx::query_scalar("SELECT guest_id FROM users WHERE id = $1")
+                .bind(NEW_USER)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(users_guest, Some(guest_id));
+
+        // The portal finds the guest straight away, without staff linking it.
+        assert_eq!(
+            ensure_portal_guest(&pool, NEW_USER).await.unwrap(),
+            Some(guest_id)
+        );
+        let profile = get_own_guest_profile(State(pool.clone()), Extension(NEW_USER))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(profile.guest_id, guest_id);
+        assert_eq!(profile.full_name, "Hafiz Rahman");
+    }
+
+    #[tokio::test]
+    async fn unlinked_account_gets_a_guest_on_first_use() {
+        let pool = common::setup_test_db().await;
+        insert_user(&pool, NEW_USER, "guest", "Lim Wei Jie").await;
+
+        let guest_id = ensure_portal_guest(&pool, NEW_USER)
+            .await
+            .unwrap()
+            .expect("guest created");
+        let name: (String, String) =
+            sqlx::query_as("SELECT first_name, last_name FROM guests WHERE id = $1")
+                .bind(guest_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(name, ("Lim".to_string(), "Wei Jie".to_string()));
+
+        // Later calls reuse the link rather than creating another guest.
+        assert_eq!(
+            ensure_portal_guest(&pool, NEW_USER).await.unwrap(),
+            Some(guest_id)
+        );
+        assert_eq!(links(&pool, NEW_USER).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn nothing_is_created_when_disabled_or_for_staff() {
+        let pool = common::setup_test_db().await;
+        insert_user(&pool, NEW_USER, "guest", "Lim Wei Jie").await;
+        insert_user(&pool, STAFF_USER, "staff", "Front Desk").await;
+
+        assert_eq!(ensure_portal_guest(&pool, STAFF_USER).await.unwrap(), None);
+
+        sqlx::query("UPDATE system_settings SET value = 'false' WHERE key = $1")
+            .bind(AUTO_CREATE_SETTING)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(ensure_portal_guest(&pool, NEW_USER).await.unwrap(), None);
+        assert!(matches!(
+            get_own_guest_profile(State(pool.clone()), Extension(NEW_USER)).await,
+            Err(ApiError::Forbidden(_))
+        ));
+
+        let guests: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM guests")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(guests, 0);
+    }
+}
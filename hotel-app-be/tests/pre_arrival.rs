@@ -0,0 +1,137 @@
+//! Tests for the upcoming-arrivals list used for pre-arrival outreach.
+//!
+//! Deduplication is pure and runs under any feature; the window query is
+//! checked against SQLite like the other integration tests.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::models::ArrivingGuest;
+use hotel_app_be::services::pre_arrival::first_arrival_per_guest;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn arrival(guest_id: i64, booking_id: i64, arrival_date: &str) -> ArrivingGuest {
+    ArrivingGuest {
+        guest_id,
+        full_name: format!("Guest {guest_id}"),
+        email: None,
+        phone: None,
+        booking_id,
+        booking_number: Some(format!("BK-{booking_id}")),
+        arrival_date: date(arrival_date),
+        room_number: None,
+        is_vip: false,
+    }
+}
+
+#[test]
+fn keeps_each_guests_earliest_arrival() {
+    let guests = first_arrival_per_guest(vec![
+        arrival(1, 10, "2026-09-01"),
+        arrival(2, 11, "2026-09-02"),
+        arrival(1, 12, "2026-09-04"),
+    ]);
+
+    assert_eq!(guests.len(), 2);
+    assert_eq!((guests[0].guest_id, guests[0].booking_id), (1, 10));
+    assert_eq!((guests[1].guest_id, guests[1].booking_id), (2, 11));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, date};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::services::pre_arrival::arriving_guests;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (961, 'Arrival Twin', 'ATWN', 140.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9611, 'A611', 961, 'available', 1), (9612, 'A612', 961, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name, email, phone)
+             VALUES (9611, 'Aina', 'Rahman', 'Aina Rahman', 'aina@example.com', '+60123456789'),
+                    (9612, 'Ben', 'Lee', 'Ben Lee', 'ben@example.com', NULL),
+                    (9613, 'Chen', 'Wei', 'Chen Wei', NULL, NULL)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // Today is 1 September. Aina arrives twice inside the window; Ben's
+        // confirmed stay is outside it and his pending one doesn't count;
+        // Chen arrived yesterday.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9611, 'BK-ARR-9611', 9611, 9611, '2026-09-03', '2026-09-05', 140.0, 280.0, 'confirmed'),
+             (9612, 'BK-ARR-9612', 9611, 9612, '2026-09-06', '2026-09-07', 140.0, 140.0, 'confirmed'),
+             (9613, 'BK-ARR-9613', 9612, 9612, '2026-09-12', '2026-09-14', 140.0, 280.0, 'confirmed'),
+             (9614, 'BK-ARR-9614', 9612, 9611, '2026-09-02', '2026-09-03', 140.0, 140.0, 'pending'),
+             (9615, 'BK-ARR-9615', 9613, 9611, '2026-08-31', '2026-09-02', 140.0, 280.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_confirmed_arrivals_inside_the_window() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let guests = arriving_guests(&pool, date("2026-09-01"), 7).await.unwrap();
+
+        assert_eq!(guests.len(), 1);
+        let aina = &guests[0];
+        assert_eq!(aina.guest_id, 9611);
+        assert_eq!(aina.full_name, "Aina Rahman");
+        assert_eq!(aina.email.as_deref(), Some("aina@example.com"));
+        assert_eq!(aina.phone.as_deref(), Some("+60123456789"));
+        assert_eq!(aina.arrival_date, date("2026-09-03"));
+        assert_eq!(aina.room_number.as_deref(), Some("A611"));
+    }
+
+    #[tokio::test]
+    async fn window_end_is_inclusive() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let guests = arriving_guests(&pool, date("2026-09-01"), 11)
+            .await
+            .unwrap();
+        let ids: Vec<i64> = guests.iter().map(|g| g.guest_id).collect();
+        assert_eq!(ids, vec![9611, 9612]);
+
+        let today_only = arriving_guests(&pool, date("2026-09-03"), 0).await.unwrap();
+        assert_eq!(today_only.len(), 1);
+        assert_eq!(today_only[0].booking_number.as_deref(), Some("BK-ARR-9611"));
+    }
+
+    #[tokio::test]
+    async fn rejects_out_of_range_windows() {
+        let pool = common::setup_test_db().await;
+
+        for within_days in [-1, 91] {
+            assert!(matches!(
+                arriving_guests(&pool, date("2026-09-01"), within_days).await,
+                Err(ApiError::BadRequest(_))
+            ));
+        }
+    }
+}
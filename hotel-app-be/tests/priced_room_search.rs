@@ -0,0 +1,164 @@
+//! Integration tests for the priced room search.
+//!
+//! Booking creation runs PostgreSQL-only SQL, so these compare the search
+//! against a booking quote, which prices a stay through the same path as
+//! `create_booking_handler`. SQLite-backed tests are gated so the default
+//! PostgreSQL build is not forced to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::quote_booking_handler;
+    use hotel_app_be::handlers::rooms::search_priced_rooms_handler;
+    use hotel_app_be::models::{BookingQuoteInput, PricedRoom, PricedRoomSearchQuery};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (831, 'Funnel Twin', 'FTWN', 120.0, 2),
+                    (832, 'Funnel Family', 'FFAM', 200.0, 4)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, custom_price, status, is_active)
+             VALUES (8301, 'F101', 831, NULL, 'available', 1),
+                    (8302, 'F102', 831, 135.0, 'available', 1),
+                    (8303, 'F201', 832, NULL, 'available', 1),
+                    (8304, 'F202', 832, NULL, 'maintenance', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (8301, 'Hana', 'Yusof', 'Hana Yusof')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (8301, 'BK-PS-1', 8301, 8301, '2026-11-02', '2026-11-04', 120.0, 240.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn search(
+        pool: &sqlx::SqlitePool,
+        check_in: &str,
+        check_out: &str,
+        guests: Option<i32>,
+    ) -> Result<Vec<PricedRoom>, ApiError> {
+        search_priced_rooms_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(PricedRoomSearchQuery {
+                check_in: check_in.to_string(),
+                check_out: check_out.to_string(),
+                guests,
+                room_type: None,
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    fn numbers(rooms: &[PricedRoom]) -> Vec<&str> {
+        rooms.iter().map(|r| r.room.room_number.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn search_total_matches_the_booking_price() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let rooms = search(&pool, "2026-11-10", "2026-11-13", Some(2))
+            .await
+            .unwrap();
+        assert_eq!(numbers(&rooms), vec!["F101", "F102", "F201"]);
+
+        for room in &rooms {
+            assert_eq!(room.nights, 3);
+            assert_eq!(room.nightly_breakdown.len(), 3);
+
+            let quote = quote_booking_handler(
+                State(pool.clone()),
+                Extension(PropertyScope::default()),
+                Json(BookingQuoteInput {
+                    guest_id: Some(8301),
+                    room_id: room.room.id,
+                    check_in_date: "2026-11-10".to_string(),
+                    check_out_date: "2026-11-13".to_string(),
+                    adults: Some(2),
+                    children: None,
+                    infants: None,
+                    is_tourist: None,
+                    tourism_tax_amount: None,
+                    extra_bed_count: None,
+                    extra_bed_charge: None,
+                    room_rate_override: None,
+                    daily_rates: None,
+                }),
+            )
+            .await
+            .unwrap()
+            .0;
+            assert_eq!(room.total_price, quote.total_amount);
+            assert_eq!(room.nightly_breakdown, quote.nightly_rates);
+        }
+
+        // The custom room price applies to every night.
+        assert_eq!(rooms[1].total_price, Decimal::from(405));
+    }
+
+    #[tokio::test]
+    async fn booked_rooms_and_small_rooms_are_left_out() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let rooms = search(&pool, "2026-11-03", "2026-11-05", None)
+            .await
+            .unwrap();
+        assert_eq!(numbers(&rooms), vec!["F102", "F201"]);
+
+        let rooms = search(&pool, "2026-11-10", "2026-11-11", Some(3))
+            .await
+            .unwrap();
+        assert_eq!(numbers(&rooms), vec!["F201"]);
+    }
+
+    #[tokio::test]
+    async fn stay_must_be_valid_and_capped() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for (check_in, check_out) in [
+            ("2026-11-10", "2026-11-10"),
+            ("2026-11-10", "2026-11-09"),
+            ("2026-11-10", "2026-12-11"),
+            ("10/11/2026", "2026-11-12"),
+        ] {
+            assert!(matches!(
+                search(&pool, check_in, check_out, None).await,
+                Err(ApiError::BadRequest(_))
+            ));
+        }
+        assert!(
+            search(&pool, "2026-11-10", "2026-12-10", None)
+                .await
+                .is_ok()
+        );
+    }
+}
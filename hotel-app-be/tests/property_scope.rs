@@ -0,0 +1,429 @@
+//! Tests for per-property scoping of rooms and bookings.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use axum::http::{HeaderMap, HeaderValue};
+use hotel_app_be::ApiError;
+use hotel_app_be::core::property_scope::{
+    DEFAULT_PROPERTY_ID, PROPERTY_HEADER, requested_property, select_property,
+};
+
+#[test]
+fn header_selects_an_accessible_property() {
+    assert_eq!(select_property(&[3, 2], Some(2)).unwrap().property_id, 2);
+    assert_eq!(select_property(&[3, 2], None).unwrap().property_id, 3);
+    assert_eq!(
+        select_property(&[], None).unwrap().property_id,
+        DEFAULT_PROPERTY_ID
+    );
+    assert!(matches!(
+        select_property(&[3, 2], Some(1)),
+        Err(ApiError::Forbidden(_))
+    ));
+}
+
+#[test]
+fn property_header_is_parsed() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(requested_property(&headers).unwrap(), None);
+
+    headers.insert(PROPERTY_HEADER, HeaderValue::from_static(" 7 "));
+    assert_eq!(requested_property(&headers).unwrap(), Some(7));
+
+    headers.insert(PROPERTY_HEADER, HeaderValue::from_static("main"));
+    assert!(matches!(
+        requested_property(&headers),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, Query, State};
+    use axum::http::{HeaderMap, HeaderValue};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::{
+        PROPERTY_HEADER, PropertyScope, require_property_scope,
+    };
+    use hotel_app_be::handlers::bookings::{
+        cancel_booking_handler, checkout_booking_handler, delete_booking_handler,
+        get_booking_handler, get_booking_stats_handler, get_bookings_handler,
+        manual_checkin_handler, search_bookings_handler, update_booking_handler,
+    };
+    use hotel_app_be::handlers::rooms::{
+        delete_room_handler, get_reservation_timeline_handler, get_room_bookings_handler,
+        get_rooms_handler, search_rooms_handler, update_room_handler,
+    };
+    use hotel_app_be::models::{
+        BookingPaginationParams, BookingSearchParams, CancelBookingInput, ReservationTimelineQuery,
+        RoomBookingsQuery, RoomListParams, SearchQuery,
+    };
+
+    const STAFF_A: i64 = 9870;
+    const STAFF_B: i64 = 9871;
+
+    /// Property 2 next to the default one, each with a room and a booking.
+    /// Staff A works in property 1 only; staff B in both, property 2 first.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO properties (id, code, name) VALUES (2, 'BEACH', 'Beach Annex')",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (987, 'Scope Queen', 'SQN', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active, property_id)
+             VALUES (9870, 'P1-01', 987, 'available', 1, 1),
+                    (9871, 'P2-01', 987, 'available', 1, 2)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9870, 'Aina', 'Rahman', 'Aina Rahman')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9870, 'BK-PS-1', 9870, 9870, '2026-08-01', '2026-08-03', 150.0, 300.0, 'confirmed'),
+             (9871, 'BK-PS-2', 9870, 9871, '2026-08-01', '2026-08-03', 150.0, 300.0, 'confirmed')",
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9870, 'u-9870', 'staff_a', 'a@example.com', 1),
+                    (9871, 'u-9871', 'staff_b', 'b@example.com', 1)",
+            "INSERT INTO user_roles (user_id, role_id) VALUES (9870, 1), (9871, 1)",
+            "INSERT INTO user_properties (user_id, property_id, is_default)
+             VALUES (9870, 1, 1), (9871, 1, 0), (9871, 2, 1)",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    fn booking_params() -> BookingPaginationParams {
+        serde_json::from_value(serde_json::json!({})).unwrap()
+    }
+
+    fn scope(property_id: i64) -> Extension<PropertyScope> {
+        Extension(PropertyScope { property_id })
+    }
+
+    fn not_found<T>(result: Result<T, ApiError>) -> bool {
+        matches!(result, Err(ApiError::NotFound(_)))
+    }
+
+    async fn booking_row(pool: &sqlx::SqlitePool, id: i64) -> (i64, String, String) {
+        sqlx::query_as("SELECT room_id, status, check_out_date FROM bookings WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn bookings_take_the_property_of_their_room() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let property_of: Vec<i64> = sqlx::query_scalar(
+            "SELECT property_id FROM bookings WHERE id IN (9870, 9871) ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(property_of, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn lists_only_show_the_selected_property() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for (property_id, room, other_room, booking) in [
+            (1, "P1-01", "P2-01", "BK-PS-1"),
+            (2, "P2-01", "P1-01", "BK-PS-2"),
+        ] {
+            let rooms = get_rooms_handler(
+                State(pool.clone()),
+                scope(property_id),
+                Query(RoomListParams::default()),
+            )
+            .await
+            .unwrap()
+            .0;
+            let numbers: Vec<&str> = rooms.iter().map(|r| r.room_number.as_str()).collect();
+            assert!(numbers.contains(&room), "property {}", property_id);
+            assert!(!numbers.contains(&other_room), "property {}", property_id);
+
+            let bookings = get_bookings_handler(
+                State(pool.clone()),
+                scope(property_id),
+                Query(booking_params()),
+            )
+            .await
+            .unwrap()
+            .0;
+            let numbers: Vec<&str> = bookings
+                .data
+                .iter()
+                .map(|b| b.booking_number.as_str())
+                .collect();
+            assert_eq!(numbers, vec![booking], "property {}", property_id);
+            assert_eq!(bookings.total, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn bookings_in_other_properties_are_not_found() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let own = get_booking_handler(
+            State(pool.clone()),
+            Extension(STAFF_A),
+            scope(1),
+            Path(9870),
+        )
+        .await
+        .unwrap();
+        assert_eq!(own.0.booking_number, "BK-PS-1");
+
+        assert!(matches!(
+            get_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871)
+            )
+            .await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn users_only_select_their_own_properties() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            require_property_scope(&pool, &headers, STAFF_A)
+                .await
+                .unwrap()
+                .property_id,
+            1
+        );
+        assert_eq!(
+            require_property_scope(&pool, &headers, STAFF_B)
+                .await
+                .unwrap()
+                .property_id,
+            2
+        );
+
+        headers.insert(PROPERTY_HEADER, HeaderValue::from_static("2"));
+        assert!(matches!(
+            require_property_scope(&pool, &headers, STAFF_A).await,
+            Err(ApiError::Forbidden(_))
+        ));
+        assert_eq!(
+            require_property_scope(&pool, &headers, STAFF_B)
+                .await
+                .unwrap()
+                .property_id,
+            2
+        );
+
+        // Users without assignments work in the default property.
+        headers.remove(PROPERTY_HEADER);
+        assert_eq!(
+            require_property_scope(&pool, &headers, 424242)
+                .await
+                .unwrap()
+                .property_id,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn changes_to_other_properties_are_not_found() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let before = booking_row(&pool, 9871).await;
+
+        let update = serde_json::from_value(serde_json::json!({
+            "room_id": "9870",
+            "check_out_date": "2026-08-05",
+        }))
+        .unwrap();
+        assert!(not_found(
+            update_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871),
+                Json(update),
+            )
+            .await
+        ));
+        assert!(not_found(
+            cancel_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871),
+                Json(CancelBookingInput::default()),
+            )
+            .await
+        ));
+        assert!(not_found(
+            manual_checkin_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871),
+                Json(None),
+            )
+            .await
+        ));
+        assert!(not_found(
+            checkout_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871),
+                Json(None),
+            )
+            .await
+        ));
+        assert!(not_found(
+            delete_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9871),
+            )
+            .await
+        ));
+        assert_eq!(booking_row(&pool, 9871).await, before);
+
+        // A booking in this property cannot be moved into another one's room.
+        let update = serde_json::from_value(serde_json::json!({ "room_id": "9871" })).unwrap();
+        assert!(not_found(
+            update_booking_handler(
+                State(pool.clone()),
+                Extension(STAFF_A),
+                scope(1),
+                Path(9870),
+                Json(update),
+            )
+            .await
+        ));
+        assert_eq!(booking_row(&pool, 9870).await.0, 9870);
+
+        let rename = serde_json::from_value(serde_json::json!({ "room_number": "X-01" })).unwrap();
+        assert!(not_found(
+            update_room_handler(State(pool.clone()), scope(1), Path(9871), Json(rename)).await
+        ));
+        assert!(not_found(
+            delete_room_handler(State(pool.clone()), scope(1), Path(9871)).await
+        ));
+        let room: String = sqlx::query_scalar("SELECT room_number FROM rooms WHERE id = 9871")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(room, "P2-01");
+    }
+
+    #[tokio::test]
+    async fn searches_only_cover_the_selected_property() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for (property_id, room_id, room, booking) in
+            [(1, 9870, "P1-01", "BK-PS-1"), (2, 9871, "P2-01", "BK-PS-2")]
+        {
+            let found = search_bookings_handler(
+                State(pool.clone()),
+                scope(property_id),
+                Query(BookingSearchParams {
+                    q: Some("BK-PS".to_string()),
+                    page: None,
+                    page_size: None,
+                }),
+            )
+            .await
+            .unwrap()
+            .0;
+            let numbers: Vec<&str> = found
+                .data
+                .iter()
+                .map(|b| b.booking_number.as_str())
+                .collect();
+            assert_eq!(numbers, vec![booking], "property {}", property_id);
+            assert_eq!(found.total, 1);
+
+            let free = search_rooms_handler(
+                State(pool.clone()),
+                scope(property_id),
+                Query(SearchQuery {
+                    room_type: Some("SQN".to_string()),
+                    max_price: None,
+                    check_in_date: Some("2026-09-01".to_string()),
+                    check_out_date: Some("2026-09-02".to_string()),
+                    exclude_booking_id: None,
+                    guests: None,
+                }),
+            )
+            .await
+            .unwrap()
+            .0;
+            let ids: Vec<i64> = free.iter().map(|r| r.id).collect();
+            assert_eq!(ids, vec![room_id], "property {}", property_id);
+
+            let timeline = get_reservation_timeline_handler(
+                State(pool.clone()),
+                scope(property_id),
+                Query(ReservationTimelineQuery {
+                    start: "2026-08-01".to_string(),
+                    end: "2026-08-02".to_string(),
+                }),
+            )
+            .await
+            .unwrap()
+            .0;
+            let rows: Vec<(&str, Vec<&str>)> = timeline
+                .rooms
+                .iter()
+                .filter(|r| r.room_type == "Scope Queen")
+                .map(|r| {
+                    (
+                        r.room_number.as_str(),
+                        r.bookings
+                            .iter()
+                            .map(|b| b.booking_number.as_str())
+                            .collect(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                rows,
+                vec![(room, vec![booking])],
+                "property {}",
+                property_id
+            );
+
+            let stats = get_booking_stats_handler(State(pool.clone()), scope(property_id))
+                .await
+                .unwrap()
+                .0;
+            assert_eq!(stats.total, 1);
+            assert_eq!(stats.confirmed, 1);
+        }
+
+        assert!(not_found(
+            get_room_bookings_handler(
+                State(pool.clone()),
+                scope(1),
+                Path(9871),
+                Query(RoomBookingsQuery::default()),
+            )
+            .await
+        ));
+    }
+}
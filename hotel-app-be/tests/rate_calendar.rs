@@ -0,0 +1,122 @@
+//! Tests for the rate calendar and shared nightly rate resolution.
+//!
+//! Booking creation prices a stay with `rates::resolve_stay`; the calendar
+//! uses `rates::resolve_rates`. These tests pin the two to the same answer.
+//! Database tests are gated on SQLite like the other integration tests.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::models::NightlyRate;
+use hotel_app_be::services::rates::{billable_nights, daily_rates_json};
+use rust_decimal::Decimal;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn billable_nights_exclude_checkout_day() {
+    assert_eq!(
+        billable_nights(date("2026-03-01"), date("2026-03-04")),
+        (date("2026-03-01"), date("2026-03-03"))
+    );
+    // Same-day stays are billed as their check-in night.
+    assert_eq!(
+        billable_nights(date("2026-03-01"), date("2026-03-01")),
+        (date("2026-03-01"), date("2026-03-01"))
+    );
+}
+
+#[test]
+fn daily_rates_json_is_keyed_by_date() {
+    let rates = vec![
+        NightlyRate {
+            date: date("2026-03-06"),
+            price: Decimal::new(12000, 2),
+            rate_plan_code: "BASE".to_string(),
+            is_base_rate: true,
+        },
+        NightlyRate {
+            date: date("2026-03-07"),
+            price: Decimal::new(15050, 2),
+            rate_plan_code: "WKND".to_string(),
+            is_base_rate: false,
+        },
+    ];
+
+    assert_eq!(
+        daily_rates_json(&rates),
+        serde_json::json!({"2026-03-06": 120.0, "2026-03-07": 150.5})
+    );
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::date;
+    use axum::extract::{Query, State};
+    use hotel_app_be::handlers::rates::{RateError, get_rate_calendar};
+    use hotel_app_be::models::RateCalendarQuery;
+    use hotel_app_be::services::rates::resolve_stay;
+    use rust_decimal::Decimal;
+
+    async fn seed_room_type(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (701, 'Calendar Suite', 'CSTE', 180.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn query(start: &str, end: &str) -> Query<RateCalendarQuery> {
+        Query(RateCalendarQuery {
+            room_type_id: 701,
+            start: start.to_string(),
+            end: end.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn calendar_matches_booking_nightly_charges() {
+        let pool = common::setup_test_db().await;
+        seed_room_type(&pool).await;
+
+        let calendar = get_rate_calendar(State(pool.clone()), query("2026-05-01", "2026-05-31"))
+            .await
+            .ok()
+            .expect("calendar should succeed")
+            .0;
+        assert_eq!(calendar.days.len(), 31);
+
+        // A 4-night stay is charged exactly the calendar's prices for its nights.
+        let stay = resolve_stay(&pool, 701, date("2026-05-10"), date("2026-05-14"))
+            .await
+            .unwrap();
+        let from_calendar: Vec<_> = calendar
+            .days
+            .iter()
+            .filter(|d| d.date >= date("2026-05-10") && d.date < date("2026-05-14"))
+            .cloned()
+            .collect();
+        assert_eq!(stay, from_calendar);
+
+        let charged: Decimal = stay.iter().map(|r| r.price).sum();
+        assert_eq!(charged, Decimal::from(720));
+        assert!(stay.iter().all(|r| r.is_base_rate));
+    }
+
+    #[tokio::test]
+    async fn calendar_rejects_oversized_and_inverted_ranges() {
+        let pool = common::setup_test_db().await;
+        seed_room_type(&pool).await;
+
+        let too_long =
+            get_rate_calendar(State(pool.clone()), query("2026-01-01", "2026-12-31")).await;
+        assert!(matches!(too_long, Err(RateError::BadRequest(_))));
+
+        let inverted = get_rate_calendar(State(pool), query("2026-02-10", "2026-02-01")).await;
+        assert!(matches!(inverted, Err(RateError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,121 @@
+//! Integration tests for batch role-permission grants.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::rbac::batch_grant_permissions_handler;
+    use hotel_app_be::models::{RoleGrantInput, RoleGrantResult};
+
+    const ADMIN: i64 = 9740;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO roles (id, name, display_name)
+             VALUES (9741, 'night_manager', 'Night Manager'), (9742, 'auditor', 'Auditor')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO permissions (id, name, resource, action)
+             VALUES (9741, 'grants:read', 'grants', 'read'),
+                    (9742, 'grants:write', 'grants', 'write'),
+                    (9743, 'grants:audit', 'grants', 'audit')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        // The night manager already holds read access.
+        sqlx::query("INSERT INTO role_permissions (role_id, permission_id) VALUES (9741, 9741)")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn grant(
+        pool: &sqlx::SqlitePool,
+        grants: Vec<(i64, Vec<i64>)>,
+    ) -> Result<Vec<RoleGrantResult>, ApiError> {
+        let input = grants
+            .into_iter()
+            .map(|(role_id, permission_ids)| RoleGrantInput {
+                role_id,
+                permission_ids,
+            })
+            .collect();
+        batch_grant_permissions_handler(State(pool.clone()), Extension(ADMIN), Json(input))
+            .await
+            .map(|json| json.0)
+    }
+
+    async fn held(pool: &sqlx::SqlitePool, role_id: i64) -> Vec<i64> {
+        sqlx::query_scalar(
+            "SELECT permission_id FROM role_permissions WHERE role_id = $1 ORDER BY permission_id",
+        )
+        .bind(role_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn grants_permissions_across_several_roles() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let results = grant(
+            &pool,
+            vec![(9741, vec![9741, 9742]), (9742, vec![9741, 9743])],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].role_id, 9741);
+        assert_eq!(results[0].permission_ids, vec![9741, 9742]);
+        // 9741 was already held, so only 9742 is new.
+        assert_eq!(results[0].granted, vec![9742]);
+        assert_eq!(results[1].permission_ids, vec![9741, 9743]);
+        assert_eq!(results[1].granted, vec![9741, 9743]);
+
+        let granted_by: Option<i64> = sqlx::query_scalar(
+            "SELECT granted_by FROM role_permissions WHERE role_id = 9742 AND permission_id = 9743",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(granted_by, Some(ADMIN));
+    }
+
+    #[tokio::test]
+    async fn an_unknown_id_rejects_the_whole_batch() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let unknown_permission =
+            grant(&pool, vec![(9741, vec![9742]), (9742, vec![9743, 424242])]).await;
+        match unknown_permission {
+            Err(ApiError::BadRequest(message)) => assert!(message.contains("424242")),
+            other => panic!("expected BadRequest, got {:?}", other.map(|_| ())),
+        }
+
+        let unknown_role = grant(&pool, vec![(9742, vec![9743]), (424242, vec![9741])]).await;
+        assert!(matches!(unknown_role, Err(ApiError::BadRequest(_))));
+
+        // Nothing from either batch was applied.
+        assert_eq!(held(&pool, 9741).await, vec![9741]);
+        assert!(held(&pool, 9742).await.is_empty());
+
+        assert!(matches!(
+            grant(&pool, vec![]).await,
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+//! Integration tests for assigning roles to users.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::rbac::assign_role_to_user_handler;
+    use hotel_app_be::models::{AssignRoleInput, RoleAssignmentResult};
+
+    const ADMIN: i64 = 9750;
+    const SUPER_ADMIN: i64 = 9751;
+    const CLERK: i64 = 9752;
+
+    const ADMIN_ROLE: i64 = 1;
+    const RECEPTIONIST_ROLE: i64 = 3;
+    const SUPER_ADMIN_ROLE: i64 = 9750;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO roles (id, name, display_name)
+             VALUES (9750, 'super_admin', 'Super Administrator')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email)
+             VALUES (9750, 'u-9750', 'ops_admin', 'ops@example.com'),
+                    (9751, 'u-9751', 'owner', 'owner@example.com'),
+                    (9752, 'u-9752', 'clerk', 'clerk@example.com')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO user_roles (user_id, role_id) VALUES (9750, 1), (9751, 9750)")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn assign(
+        pool: &sqlx::SqlitePool,
+        caller: i64,
+        user_id: i64,
+        role_id: i64,
+    ) -> Result<RoleAssignmentResult, ApiError> {
+        assign_role_to_user_handler(
+            State(pool.clone()),
+            Extension(caller),
+            Json(AssignRoleInput { user_id, role_id }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    async fn roles_of(pool: &sqlx::SqlitePool, user_id: i64) -> Vec<i64> {
+        sqlx::query_scalar("SELECT role_id FROM user_roles WHERE user_id = $1 ORDER BY role_id")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reports_new_and_existing_assignments() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let first = assign(&pool, ADMIN, CLERK, RECEPTIONIST_ROLE)
+            .await
+            .unwrap();
+        assert!(first.created);
+        assert_eq!(first.role_name, "receptionist");
+
+        let again = assign(&pool, ADMIN, CLERK, RECEPTIONIST_ROLE)
+            .await
+            .unwrap();
+        assert!(!again.created);
+        assert_eq!(again.message, "Role already assigned");
+
+        assert_eq!(roles_of(&pool, CLERK).await, vec![RECEPTIONIST_ROLE]);
+        let assigned_by: Option<i64> =
+            sqlx::query_scalar("SELECT assigned_by FROM user_roles WHERE user_id = $1")
+                .bind(CLERK)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(assigned_by, Some(ADMIN));
+    }
+
+    #[tokio::test]
+    async fn unknown_user_or_role_is_not_found() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            assign(&pool, ADMIN, 99999, RECEPTIONIST_ROLE).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            assign(&pool, ADMIN, CLERK, 99999).await,
+            Err(ApiError::NotFound(_))
+        ));
+
+        sqlx::query("UPDATE users SET deleted_at = datetime('now') WHERE id = $1")
+            .bind(CLERK)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(matches!(
+            assign(&pool, ADMIN, CLERK, RECEPTIONIST_ROLE).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_super_admins_grant_admin_roles() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for role_id in [ADMIN_ROLE, SUPER_ADMIN_ROLE] {
+            assert!(matches!(
+                assign(&pool, ADMIN, CLERK, role_id).await,
+                Err(ApiError::Forbidden(_))
+            ));
+        }
+        assert!(roles_of(&pool, CLERK).await.is_empty());
+
+        assert!(
+            assign(&pool, SUPER_ADMIN, CLERK, ADMIN_ROLE)
+                .await
+                .unwrap()
+                .created
+        );
+        assert_eq!(roles_of(&pool, CLERK).await, vec![ADMIN_ROLE]);
+    }
+}
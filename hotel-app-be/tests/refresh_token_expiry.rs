@@ -0,0 +1,155 @@
+//! Tests for the sliding and fixed refresh-token expiry modes.
+//!
+//! The policy is pure and runs under any feature; the database test is
+//! gated on SQLite like the other integration tests.
+
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hotel_app_be::services::sessions::{
+    LIFETIME_SETTING, MODE_SETTING, RefreshExpiryMode, RefreshTokenPolicy, validate_setting,
+};
+
+fn login() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()
+}
+
+fn policy(mode: RefreshExpiryMode) -> RefreshTokenPolicy {
+    RefreshTokenPolicy {
+        mode,
+        lifetime_days: 30,
+        max_lifetime_days: 90,
+    }
+}
+
+#[test]
+fn sliding_refresh_extends_expiry() {
+    let sliding = policy(RefreshExpiryMode::Sliding);
+    let first = sliding.expires_at(login(), login());
+    let refreshed = sliding.expires_at(login(), login() + Duration::days(20));
+
+    assert_eq!(first, login() + Duration::days(30));
+    assert_eq!(refreshed, login() + Duration::days(50));
+    assert!(refreshed > first);
+}
+
+#[test]
+fn sliding_refresh_stops_at_the_absolute_maximum() {
+    let sliding = policy(RefreshExpiryMode::Sliding);
+
+    assert_eq!(
+        sliding.expires_at(login(), login() + Duration::days(80)),
+        login() + Duration::days(90)
+    );
+}
+
+#[test]
+fn fixed_refresh_does_not_extend_past_the_cap() {
+    let fixed = policy(RefreshExpiryMode::Fixed);
+    let cap = login() + Duration::days(30);
+
+    for refreshed_after in [0, 1, 15, 29] {
+        assert_eq!(
+            fixed.expires_at(login(), login() + Duration::days(refreshed_after)),
+            cap
+        );
+    }
+}
+
+#[test]
+fn settings_are_validated_on_update() {
+    assert!(validate_setting(MODE_SETTING, "fixed").is_ok());
+    assert!(validate_setting(MODE_SETTING, "Sliding").is_ok());
+    assert!(validate_setting(MODE_SETTING, "forever").is_err());
+    assert!(validate_setting(LIFETIME_SETTING, "14").is_ok());
+    assert!(validate_setting(LIFETIME_SETTING, "0").is_err());
+    assert!(validate_setting(LIFETIME_SETTING, "thirty").is_err());
+    assert!(validate_setting("check_in_time", "anything").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::{Duration, Utc};
+    use hotel_app_be::AuthService;
+    use hotel_app_be::services::sessions::{MODE_SETTING, issue_refresh_token};
+
+    async fn seed_user(pool: &sqlx::SqlitePool) -> i64 {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9101, 'refresh-user', 'refresher', 'refresher@example.com', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        9101
+    }
+
+    async fn expires_at(pool: &sqlx::SqlitePool, token: &str) -> chrono::DateTime<Utc> {
+        sqlx::query_scalar("SELECT expires_at FROM refresh_tokens WHERE token_hash = ?1")
+            .bind(AuthService::hash_refresh_token(token))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rotated_tokens_keep_the_session_start() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        let user_id = seed_user(&pool).await;
+        sqlx::query("UPDATE system_settings SET value = 'fixed' WHERE key = ?1")
+            .bind(MODE_SETTING)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let started = Utc::now() - Duration::days(10);
+        let token = issue_refresh_token(&pool, user_id, Some(started))
+            .await
+            .unwrap();
+
+        let session = AuthService::validate_refresh_token(&pool, &token)
+            .await
+            .unwrap()
+            .expect("token should be valid");
+        assert_eq!(session.user_id, user_id);
+        assert_eq!(session.session_started_at, started);
+
+        let rotated = issue_refresh_token(&pool, user_id, Some(session.session_started_at))
+            .await
+            .unwrap();
+        assert_eq!(
+            expires_at(&pool, &rotated).await,
+            expires_at(&pool, &token).await
+        );
+        assert_eq!(
+            expires_at(&pool, &token).await,
+            started + Duration::days(30)
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_tokens_are_rejected() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        let user_id = seed_user(&pool).await;
+
+        // A fixed 30-day session that started 31 days ago is already over.
+        sqlx::query("UPDATE system_settings SET value = 'fixed' WHERE key = ?1")
+            .bind(MODE_SETTING)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let token = issue_refresh_token(&pool, user_id, Some(Utc::now() - Duration::days(31)))
+            .await
+            .unwrap();
+
+        assert!(
+            AuthService::validate_refresh_token(&pool, &token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
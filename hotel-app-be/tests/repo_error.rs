@@ -0,0 +1,63 @@
+//! Tests for mapping repository errors onto API errors.
+//!
+//! Driver errors without a database behind them are mapped under any
+//! feature; the unique violation comes from a real insert against SQLite.
+
+mod common;
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use hotel_app_be::ApiError;
+use hotel_app_be::core::error::RepoError;
+
+fn status(err: RepoError) -> StatusCode {
+    ApiError::from(err).into_response().status()
+}
+
+#[test]
+fn missing_row_is_not_found() {
+    let err = RepoError::from(sqlx::Error::RowNotFound);
+    assert!(matches!(err, RepoError::NotFound(_)));
+    assert_eq!(status(err), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn other_driver_errors_stay_database_errors() {
+    let err = RepoError::from(sqlx::Error::PoolTimedOut);
+    assert!(matches!(err, RepoError::Database(_)));
+    assert_eq!(status(err), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn conflict_maps_to_409() {
+    let err = RepoError::Conflict("UNIQUE constraint failed: users.email".to_string());
+    assert_eq!(status(err), StatusCode::CONFLICT);
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, status};
+    use axum::http::StatusCode;
+    use hotel_app_be::core::error::RepoError;
+
+    async fn insert_user(pool: &sqlx::SqlitePool, uuid: &str) -> Result<(), RepoError> {
+        sqlx::query(
+            "INSERT INTO users (uuid, username, email)
+             VALUES ($1, 'nightaudit', 'nightaudit@example.com')",
+        )
+        .bind(uuid)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unique_violation_is_a_conflict() {
+        let pool = common::setup_test_db().await;
+        insert_user(&pool, "u-9780").await.unwrap();
+
+        let err = insert_user(&pool, "u-9781").await.unwrap_err();
+        assert!(matches!(err, RepoError::Conflict(_)), "{err}");
+        assert_eq!(status(err), StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,178 @@
+//! Integration tests for the reservation timeline.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::{get_reservation_timeline_handler, get_rooms_handler};
+    use hotel_app_be::models::{ReservationTimelineQuery, RoomListParams};
+    use hotel_app_be::services::room_status::current_room_status;
+
+    async fn today(pool: &sqlx::SqlitePool) -> chrono::NaiveDate {
+        let s: String = sqlx::query_scalar("SELECT date('now')")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").unwrap()
+    }
+
+    async fn seed_rooms_with_bookings(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (801, 'Timeline Standard', 'TSTD', 100.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES
+             (8001, 'T101', 801, 'available', 1),
+             (8002, 'T102', 801, 'available', 1),
+             (8003, 'T103', 801, 'available', 1),
+             (8004, 'T104', 801, 'maintenance', 1),
+             (8005, 'T105', 801, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO guests (id, first_name, last_name) VALUES (8001, 'Time', 'Line')")
+            .execute(pool)
+            .await
+            .unwrap();
+
+        // T101 in-house, T102 arriving today, T103 reserved for next week,
+        // T105 has only a past stay.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (8001, 'BK-TL-1', 8001, 8001, date('now', '-1 day'), date('now', '+2 day'), 100.0, 300.0, 'checked_in'),
+             (8002, 'BK-TL-2', 8001, 8002, date('now'), date('now', '+1 day'), 100.0, 100.0, 'confirmed'),
+             (8003, 'BK-TL-3', 8001, 8003, date('now', '+7 day'), date('now', '+9 day'), 100.0, 200.0, 'confirmed'),
+             (8004, 'BK-TL-4', 8001, 8005, date('now', '-5 day'), date('now', '-3 day'), 100.0, 200.0, 'checked_out')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn timeline_status_matches_rooms_list_for_today() {
+        let pool = common::setup_test_db().await;
+        seed_rooms_with_bookings(&pool).await;
+        let today = today(&pool).await;
+
+        let rooms = get_rooms_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(RoomListParams::default()),
+        )
+        .await
+        .expect("rooms list should succeed")
+        .0;
+        let timeline = get_reservation_timeline_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(ReservationTimelineQuery {
+                start: today.format("%Y-%m-%d").to_string(),
+                end: (today + chrono::Duration::days(13))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            }),
+        )
+        .await
+        .expect("timeline should succeed")
+        .0;
+
+        assert_eq!(rooms.len(), timeline.rooms.len());
+        for room in &rooms {
+            let row = timeline
+                .rooms
+                .iter()
+                .find(|t| t.room_id == room.id)
+                .expect("every listed room appears on the timeline");
+            assert_eq!(
+                room.status.as_deref(),
+                Some(row.current_status.as_str()),
+                "status drift for room {}",
+                room.room_number
+            );
+        }
+
+        let status_of = |n: &str| {
+            timeline
+                .rooms
+                .iter()
+                .find(|t| t.room_number == n)
+                .unwrap()
+                .current_status
+                .clone()
+        };
+        assert_eq!(status_of("T101"), "occupied");
+        assert_eq!(status_of("T102"), "reserved");
+        assert_eq!(status_of("T103"), "available");
+        assert_eq!(status_of("T104"), "maintenance");
+        assert_eq!(status_of("T105"), "available");
+
+        let shared = current_room_status(&pool).await.unwrap();
+        assert_eq!(shared.get(&8001).map(String::as_str), Some("occupied"));
+    }
+
+    #[tokio::test]
+    async fn timeline_returns_bookings_overlapping_range() {
+        let pool = common::setup_test_db().await;
+        seed_rooms_with_bookings(&pool).await;
+        let today = today(&pool).await;
+
+        let timeline = get_reservation_timeline_handler(
+            State(pool),
+            Extension(PropertyScope::default()),
+            Query(ReservationTimelineQuery {
+                start: today.format("%Y-%m-%d").to_string(),
+                end: (today + chrono::Duration::days(3))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let numbers: Vec<&str> = timeline
+            .rooms
+            .iter()
+            .flat_map(|r| r.bookings.iter().map(|b| b.booking_number.as_str()))
+            .collect();
+        assert_eq!(numbers, vec!["BK-TL-1", "BK-TL-2"]);
+    }
+
+    #[tokio::test]
+    async fn timeline_rejects_oversized_range() {
+        let pool = common::setup_test_db().await;
+
+        let result = get_reservation_timeline_handler(
+            State(pool),
+            Extension(PropertyScope::default()),
+            Query(ReservationTimelineQuery {
+                start: "2030-01-01".to_string(),
+                end: "2030-12-31".to_string(),
+            }),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(hotel_app_be::core::error::ApiError::BadRequest(_))
+        ));
+    }
+}
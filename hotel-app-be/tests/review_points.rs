@@ -0,0 +1,208 @@
+//! Tests for review points.
+//!
+//! The settings and review validation are pure. Awarding needs the loyalty
+//! and review tables, which only exist in the PostgreSQL schema, so the
+//! SQLite-backed test creates just the columns the review submission uses.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::handlers::guest_portal::validate_review;
+use hotel_app_be::models::GuestReviewInput;
+use hotel_app_be::services::review_points::{
+    BONUS_SETTING, DEFAULT_BONUS, ENABLED_SETTING, validate_setting,
+};
+
+#[test]
+fn bonus_must_be_a_positive_whole_number_of_points() {
+    assert!(validate_setting(BONUS_SETTING, &DEFAULT_BONUS.to_string()).is_ok());
+    assert!(validate_setting(BONUS_SETTING, " 250 ").is_ok());
+    assert!(validate_setting(BONUS_SETTING, "0").is_err());
+    assert!(validate_setting(BONUS_SETTING, "-50").is_err());
+    assert!(validate_setting(BONUS_SETTING, "12.5").is_err());
+    assert!(validate_setting(BONUS_SETTING, "1000000").is_err());
+}
+
+#[test]
+fn other_settings_pass_through() {
+    assert!(validate_setting(ENABLED_SETTING, "true").is_ok());
+    assert!(validate_setting("timezone", "0").is_ok());
+}
+
+#[test]
+fn reviews_need_ratings_between_one_and_five() {
+    let review = validate_review(GuestReviewInput {
+        overall_rating: 4.5,
+        service_rating: Some(5.0),
+        title: Some("  Lovely stay ".to_string()),
+        content: Some("   ".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(review.title.as_deref(), Some("Lovely stay"));
+    assert_eq!(review.content, None);
+
+    for bad in [
+        GuestReviewInput {
+            overall_rating: 0.0,
+            ..Default::default()
+        },
+        GuestReviewInput {
+            overall_rating: 4.0,
+            value_rating: Some(6.0),
+            ..Default::default()
+        },
+        GuestReviewInput {
+            overall_rating: 4.0,
+            title: Some("x".repeat(256)),
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(validate_review(bad), Err(ApiError::BadRequest(_))));
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::handlers::guest_portal::submit_own_review;
+    use hotel_app_be::models::GuestReviewInput;
+
+    const GUEST_USER: i64 = 9790;
+    const STAYED: i64 = 9791;
+    const UPCOMING: i64 = 9792;
+    const SOMEONE_ELSES: i64 = 9793;
+
+    /// User 9790 is linked to guest 9790, who has an active membership, a
+    /// checked-out stay and a confirmed one. Guest 9791 has a stay of their
+    /// own.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "CREATE TABLE loyalty_memberships (
+                id INTEGER PRIMARY KEY,
+                guest_id INTEGER NOT NULL,
+                points_balance INTEGER NOT NULL DEFAULT 0,
+                lifetime_points INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'active',
+                last_activity_at TEXT
+            )",
+            "CREATE TABLE points_transactions (
+                id INTEGER PRIMARY KEY,
+                membership_id INTEGER NOT NULL,
+                transaction_type TEXT NOT NULL,
+                points INTEGER NOT NULL,
+                balance_after INTEGER NOT NULL,
+                reference_type TEXT,
+                reference_id INTEGER,
+                description TEXT
+            )",
+            "CREATE UNIQUE INDEX idx_points_transactions_review_once
+                ON points_transactions(reference_id) WHERE reference_type = 'review'",
+            "CREATE TABLE guest_reviews (
+                id INTEGER PRIMARY KEY,
+                guest_id INTEGER NOT NULL,
+                booking_id INTEGER,
+                overall_rating REAL NOT NULL,
+                cleanliness_rating REAL,
+                service_rating REAL,
+                comfort_rating REAL,
+                location_rating REAL,
+                value_rating REAL,
+                title TEXT,
+                content TEXT,
+                pros TEXT,
+                cons TEXT
+            )",
+            "INSERT INTO users (id, uuid, username, email, user_type)
+             VALUES (9790, 'u-9790', 'reviewer', 'reviewer@example.com', 'guest')",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9790, 'Aina', 'Rahman', 'Aina Rahman'), (9791, 'Raj', 'Patel', 'Raj Patel')",
+            "INSERT INTO user_guests (user_id, guest_id, relationship_type, can_modify)
+             VALUES (9790, 9790, 'self', 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (979, 'Review Suite', 'RVW', 200.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9791, 'R791', 979, 'available', 1)",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9791, 'BK-RVW-1', 9790, 9791, '2026-03-01', '2026-03-03', 200.0, 400.0, 'checked_out'),
+             (9792, 'BK-RVW-2', 9790, 9791, '2026-12-20', '2026-12-21', 200.0, 200.0, 'confirmed'),
+             (9793, 'BK-RVW-3', 9791, 9791, '2026-04-01', '2026-04-02', 200.0, 200.0, 'checked_out')",
+            "INSERT INTO loyalty_memberships (id, guest_id) VALUES (9790, 9790)",
+            "INSERT OR REPLACE INTO system_settings (key, value) VALUES ('review_points_enabled', 'true')",
+            "INSERT OR REPLACE INTO system_settings (key, value) VALUES ('review_points_bonus', '150')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn review(pool: &sqlx::SqlitePool, booking_id: i64) -> Result<Option<i32>, ApiError> {
+        submit_own_review(
+            State(pool.clone()),
+            Extension(GUEST_USER),
+            Path(booking_id),
+            Json(GuestReviewInput {
+                overall_rating: 5.0,
+                title: Some("Would stay again".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map(|submitted| submitted.0.points_awarded)
+    }
+
+    #[tokio::test]
+    async fn a_stay_earns_review_points_once() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert_eq!(review(&pool, STAYED).await.unwrap(), Some(150));
+        // A second review of the same stay is saved but earns nothing.
+        assert_eq!(review(&pool, STAYED).await.unwrap(), None);
+        // Nor does a stay that hasn't happened yet.
+        assert_eq!(review(&pool, UPCOMING).await.unwrap(), None);
+
+        let (balance, lifetime): (i64, i64) = sqlx::query_as(
+            "SELECT points_balance, lifetime_points FROM loyalty_memberships WHERE id = 9790",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((balance, lifetime), (150, 150));
+
+        let awards: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT reference_id, balance_after FROM points_transactions WHERE reference_type = 'review'",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(awards, vec![(STAYED, 150)]);
+
+        let reviews: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM guest_reviews WHERE guest_id = 9790")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reviews, 3);
+    }
+
+    #[tokio::test]
+    async fn guests_cannot_review_someone_elses_stay() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            review(&pool, SOMEONE_ELSES).await,
+            Err(ApiError::NotFound(_))
+        ));
+        let reviews: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM guest_reviews")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(reviews, 0);
+    }
+}
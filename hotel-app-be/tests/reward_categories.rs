@@ -0,0 +1,27 @@
+//! Tests for reward catalog categories.
+
+use hotel_app_be::models::RewardCategory;
+
+#[test]
+fn categories_round_trip_through_their_names() {
+    for category in RewardCategory::ALL {
+        assert_eq!(category.to_string().parse(), Ok(category));
+        assert_eq!(
+            serde_json::to_value(category).unwrap(),
+            serde_json::json!(category.as_str())
+        );
+    }
+    assert_eq!("room_upgrade".parse(), Ok(RewardCategory::RoomUpgrade));
+}
+
+#[test]
+fn unknown_categories_name_the_field_and_the_valid_values() {
+    let err = "massage".parse::<RewardCategory>().unwrap_err();
+    assert_eq!(
+        err,
+        "category must be one of: room_upgrade, service, discount, gift, dining, spa, experience"
+    );
+    // Matching is exact, as the stored values are.
+    assert!("Spa".parse::<RewardCategory>().is_err());
+    assert!("".parse::<RewardCategory>().is_err());
+}
@@ -0,0 +1,165 @@
+//! Tests for room auto-assignment.
+//!
+//! Ranking tests are pure and run under any feature; the database tests are
+//! gated on SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::services::room_assignment::{RoomCandidate, RoomPreferences, rank_candidates};
+
+fn room(id: i64, number: &str, status: &str, floor: i32, smoking: bool) -> RoomCandidate {
+    RoomCandidate {
+        id,
+        room_number: number.to_string(),
+        status: status.to_string(),
+        floor: Some(floor),
+        is_smoking: smoking,
+        is_accessible: false,
+    }
+}
+
+#[test]
+fn clean_rooms_rank_before_dirty_ones() {
+    let ranked = rank_candidates(
+        vec![
+            room(1, "101", "dirty", 1, false),
+            room(2, "102", "cleaning", 1, false),
+            room(3, "103", "available", 1, false),
+        ],
+        &RoomPreferences::default(),
+    );
+    let ids: Vec<i64> = ranked.iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+}
+
+#[test]
+fn preferences_break_ties_between_clean_rooms() {
+    let prefs = RoomPreferences::from_pairs([("floor", "3"), ("smoking", "no")]);
+    assert_eq!(prefs.floor, Some(3));
+    assert_eq!(prefs.smoking, Some(false));
+
+    let ranked = rank_candidates(
+        vec![
+            room(1, "101", "available", 1, false),
+            room(2, "301", "available", 3, true),
+            room(3, "302", "available", 3, false),
+            room(4, "303", "dirty", 3, false),
+        ],
+        &prefs,
+    );
+    let ids: Vec<i64> = ranked.iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![3, 1, 2, 4]);
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use hotel_app_be::core::error::ApiError;
+    use hotel_app_be::services::room_assignment::{auto_assign_room, pre_assign_arrivals};
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (901, 'Assign Deluxe', 'ADLX', 150.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES
+             (9001, 'A101', 901, 'dirty', 1),
+             (9002, 'A102', 901, 'available', 1),
+             (9003, 'A103', 901, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO guests (id, first_name, last_name) VALUES (9001, 'Assign', 'Me')")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn book(pool: &sqlx::SqlitePool, id: i64, room_id: i64, assigned: bool) {
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, room_type_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, room_assigned)
+             VALUES (?1, ?2, 9001, ?3, 901, date('now', '+1 day'), date('now', '+3 day'),
+                     150.0, 300.0, 'confirmed', ?4)",
+        )
+        .bind(id)
+        .bind(format!("BK-AS-{}", id))
+        .bind(room_id)
+        .bind(assigned)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn assignment(pool: &sqlx::SqlitePool, booking_id: i64) -> (i64, bool) {
+        sqlx::query_as("SELECT room_id, room_assigned FROM bookings WHERE id = ?1")
+            .bind(booking_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn assigns_a_clean_free_room_of_the_booked_type() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        // A102 is taken; the provisional hold sits on the dirty A101.
+        book(&pool, 9001, 9002, true).await;
+        book(&pool, 9002, 9001, false).await;
+
+        let room_id = auto_assign_room(&pool, 9002).await.unwrap();
+
+        assert_eq!(room_id, 9003);
+        assert_eq!(assignment(&pool, 9002).await, (9003, true));
+    }
+
+    #[tokio::test]
+    async fn conflicts_when_every_room_is_taken() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        book(&pool, 9001, 9001, true).await;
+        book(&pool, 9002, 9002, true).await;
+        book(&pool, 9003, 9003, true).await;
+        book(&pool, 9004, 9001, false).await;
+
+        let result = auto_assign_room(&pool, 9004).await;
+
+        assert!(matches!(result, Err(ApiError::Conflict(_))));
+        assert_eq!(assignment(&pool, 9004).await, (9001, false));
+    }
+
+    #[tokio::test]
+    async fn pre_assignment_never_double_books_a_room() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        book(&pool, 9001, 9001, false).await;
+        book(&pool, 9002, 9001, false).await;
+        book(&pool, 9003, 9001, false).await;
+        book(&pool, 9004, 9001, false).await;
+
+        let tomorrow: String = sqlx::query_scalar("SELECT date('now', '+1 day')")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let tomorrow = chrono::NaiveDate::parse_from_str(&tomorrow, "%Y-%m-%d").unwrap();
+
+        let assigned = pre_assign_arrivals(&pool, tomorrow).await.unwrap();
+        assert_eq!(assigned, 3);
+
+        let rooms: Vec<i64> = sqlx::query_scalar(
+            "SELECT room_id FROM bookings WHERE room_assigned = 1 ORDER BY room_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rooms, vec![9001, 9002, 9003]);
+    }
+}
@@ -0,0 +1,161 @@
+//! Tests for blocking rooms from sale for a date range.
+//!
+//! Checking a block is pure and runs under any feature. Blocking rooms and
+//! searching around blocks is SQLite-backed and gated accordingly.
+
+mod common;
+
+use chrono::NaiveDate;
+use hotel_app_be::ApiError;
+use hotel_app_be::models::RoomBlockInput;
+use hotel_app_be::services::room_blocks::{MAX_BLOCK_NIGHTS, check_block};
+
+fn block(start: &str, end: &str, reason: &str) -> RoomBlockInput {
+    RoomBlockInput {
+        start_date: NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+        end_date: NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+        reason: reason.to_string(),
+        notes: None,
+    }
+}
+
+#[test]
+fn blocks_need_a_known_reason_and_at_least_one_night() {
+    assert_eq!(
+        check_block(&block("2026-09-01", "2026-09-04", " Owner_Use ")).unwrap(),
+        "owner_use"
+    );
+    assert!(matches!(
+        check_block(&block("2026-09-01", "2026-09-04", "maintenance")),
+        Err(ApiError::BadRequest(msg)) if msg.contains("'maintenance'")
+    ));
+    assert!(matches!(
+        check_block(&block("2026-09-04", "2026-09-04", "renovation")),
+        Err(ApiError::BadRequest(_))
+    ));
+
+    let mut too_long = block("2026-01-01", "2026-01-02", "staff_housing");
+    too_long.end_date = too_long.start_date + chrono::Days::new(MAX_BLOCK_NIGHTS as u64 + 1);
+    assert!(matches!(
+        check_block(&too_long),
+        Err(ApiError::BadRequest(_))
+    ));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{block, common};
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::search_rooms_handler;
+    use hotel_app_be::models::{RoomBlock, SearchQuery};
+    use hotel_app_be::services::room_blocks::{create_block, delete_block, list_blocks};
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (999, 'Block Double', 'BDB', 110.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9991, 'B01', 999, 'available', 1),
+                    (9992, 'B02', 999, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9990, 'Rhea', 'Solis')",
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9990, 'u-9990', 'ops_manager', 'ops@example.com', 1)",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES (9991, 'BK-BL-1', 9990, 9992, '2026-09-02', '2026-09-05', 110.0, 330.0, 'confirmed')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn block_room(
+        pool: &sqlx::SqlitePool,
+        room_id: i64,
+        start: &str,
+        end: &str,
+        reason: &str,
+    ) -> Result<RoomBlock, ApiError> {
+        create_block(pool, room_id, &block(start, end, reason), 9990).await
+    }
+
+    async fn free_rooms(pool: &sqlx::SqlitePool, check_in: &str, check_out: &str) -> Vec<String> {
+        search_rooms_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(SearchQuery {
+                room_type: Some("BDB".to_string()),
+                max_price: None,
+                check_in_date: Some(check_in.to_string()),
+                check_out_date: Some(check_out.to_string()),
+                exclude_booking_id: None,
+                guests: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|r| r.room_number)
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn blocked_rooms_drop_out_of_dated_searches_until_unblocked() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let owner_stay = block_room(&pool, 9991, "2026-09-10", "2026-09-13", "owner_use")
+            .await
+            .unwrap();
+        assert_eq!(owner_stay.reason, "owner_use");
+        assert_eq!(owner_stay.created_by, Some(9990));
+        let listed = list_blocks(&pool, 9991).await.unwrap();
+        assert_eq!(
+            listed.iter().map(|b| b.id).collect::<Vec<_>>(),
+            [owner_stay.id]
+        );
+
+        assert_eq!(free_rooms(&pool, "2026-09-12", "2026-09-14").await, ["B02"]);
+        // Blocks end like stays: the end date is free again.
+        assert_eq!(
+            free_rooms(&pool, "2026-09-13", "2026-09-14").await,
+            ["B01", "B02"]
+        );
+
+        delete_block(&pool, 9991, owner_stay.id).await.unwrap();
+        assert_eq!(
+            free_rooms(&pool, "2026-09-12", "2026-09-14").await,
+            ["B01", "B02"]
+        );
+        assert!(matches!(
+            delete_block(&pool, 9991, owner_stay.id).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rooms_are_not_blocked_over_bookings_or_other_blocks() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            block_room(&pool, 9992, "2026-09-04", "2026-09-08", "renovation").await,
+            Err(ApiError::Conflict(msg)) if msg.contains("BK-BL-1")
+        ));
+        // Starting on the guest's departure day is fine.
+        block_room(&pool, 9992, "2026-09-05", "2026-09-08", "renovation")
+            .await
+            .unwrap();
+        assert!(matches!(
+            block_room(&pool, 9992, "2026-09-07", "2026-09-09", "other").await,
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            block_room(&pool, 404, "2026-09-07", "2026-09-09", "other").await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
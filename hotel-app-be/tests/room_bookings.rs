@@ -0,0 +1,127 @@
+//! Integration tests for a single room's booking schedule.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Path, Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::get_room_bookings_handler;
+    use hotel_app_be::models::{RoomBookingsQuery, RoomSchedule};
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (811, 'Schedule Twin', 'STWN', 120.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (8101, 'S201', 811, 'available', 1),
+                    (8102, 'S202', 811, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (8101, 'Farah', 'Idris', 'Farah Idris'),
+                    (8102, 'Chong', 'Wei', 'Chong Wei')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        // S201: one stay before the range, one spanning its start, one
+        // inside it, a cancelled one and one after it. S202 has a stay in
+        // range that must not show up.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (8101, 'BK-RS-1', 8101, 8101, '2026-08-01', '2026-08-03', 120.0, 240.0, 'checked_out'),
+             (8102, 'BK-RS-2', 8101, 8101, '2026-08-09', '2026-08-11', 120.0, 240.0, 'checked_in'),
+             (8103, 'BK-RS-3', 8102, 8101, '2026-08-14', '2026-08-16', 120.0, 240.0, 'confirmed'),
+             (8104, 'BK-RS-4', 8102, 8101, '2026-08-12', '2026-08-13', 120.0, 120.0, 'cancelled'),
+             (8105, 'BK-RS-5', 8101, 8101, '2026-08-25', '2026-08-27', 120.0, 240.0, 'confirmed'),
+             (8106, 'BK-RS-6', 8102, 8102, '2026-08-12', '2026-08-14', 120.0, 240.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn schedule(
+        pool: &sqlx::SqlitePool,
+        room_id: i64,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<RoomSchedule, ApiError> {
+        get_room_bookings_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Path(room_id),
+            Query(RoomBookingsQuery {
+                from: from.map(str::to_string),
+                to: to.map(str::to_string),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    #[tokio::test]
+    async fn only_the_rooms_active_bookings_in_range_are_listed() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let result = schedule(&pool, 8101, Some("2026-08-10"), Some("2026-08-20"))
+            .await
+            .unwrap();
+
+        assert_eq!(result.room_number, "S201");
+        let numbers: Vec<&str> = result
+            .bookings
+            .iter()
+            .map(|b| b.booking_number.as_str())
+            .collect();
+        assert_eq!(numbers, vec!["BK-RS-2", "BK-RS-3"]);
+        assert_eq!(result.bookings[0].status, "checked_in");
+        assert_eq!(result.bookings[1].guest_name.as_deref(), Some("Chong Wei"));
+    }
+
+    #[tokio::test]
+    async fn unknown_room_and_bad_ranges_are_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert!(matches!(
+            schedule(&pool, 9999, None, None).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            schedule(&pool, 8101, Some("2026-08-20"), Some("2026-08-10")).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(matches!(
+            schedule(&pool, 8101, Some("10/08/2026"), None).await,
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn range_defaults_to_the_next_thirty_days() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let result = schedule(&pool, 8102, None, None).await.unwrap();
+        assert_eq!((result.to_date - result.from_date).num_days(), 30);
+    }
+}
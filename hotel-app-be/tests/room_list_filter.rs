@@ -0,0 +1,108 @@
+//! Tests for filtering the rooms list by current status.
+//!
+//! Parsing the filter is pure and runs under any feature. Listing rooms is
+//! SQLite-backed and gated accordingly.
+
+mod common;
+
+use hotel_app_be::ApiError;
+use hotel_app_be::services::room_status::parse_status_filter;
+
+#[test]
+fn status_filters_use_the_room_status_vocabulary() {
+    assert_eq!(
+        parse_status_filter(" dirty, Cleaning ,dirty").unwrap(),
+        ["dirty", "cleaning"]
+    );
+    assert!(parse_status_filter("").unwrap().is_empty());
+    assert!(parse_status_filter(" , ").unwrap().is_empty());
+    assert!(matches!(
+        parse_status_filter("available,vacant"),
+        Err(ApiError::BadRequest(msg)) if msg.contains("'vacant'")
+    ));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::get_rooms_handler;
+    use hotel_app_be::models::RoomListParams;
+
+    /// Rooms F01 to F05: one in-house, one arriving today, one being cleaned,
+    /// one dirty and one free.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (996, 'Filter Double', 'FDB', 90.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9961, 'F01', 996, 'available', 1),
+                    (9962, 'F02', 996, 'available', 1),
+                    (9963, 'F03', 996, 'cleaning', 1),
+                    (9964, 'F04', 996, 'dirty', 1),
+                    (9965, 'F05', 996, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name)
+             VALUES (9960, 'Ines', 'Moreau')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9961, 'BK-FL-1', 9960, 9961, date('now', '-1 day'), date('now', '+1 day'), 90.0, 180.0, 'checked_in'),
+             (9962, 'BK-FL-2', 9960, 9962, date('now'), date('now', '+2 day'), 90.0, 180.0, 'confirmed')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn rooms_in(pool: &sqlx::SqlitePool, status: Option<&str>) -> Vec<(String, String)> {
+        get_rooms_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Query(RoomListParams {
+                status: status.map(str::to_string),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|r| (r.room_number, r.status.unwrap_or_default()))
+        .collect()
+    }
+
+    fn listed(rooms: &[(&str, &str)]) -> Vec<(String, String)> {
+        rooms
+            .iter()
+            .map(|(n, s)| (n.to_string(), s.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rooms_are_filtered_by_their_current_status() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        assert_eq!(
+            rooms_in(&pool, None).await,
+            listed(&[
+                ("F01", "occupied"),
+                ("F02", "reserved"),
+                ("F03", "cleaning"),
+                ("F04", "dirty"),
+                ("F05", "available"),
+            ])
+        );
+        assert_eq!(
+            rooms_in(&pool, Some("dirty,cleaning")).await,
+            listed(&[("F03", "cleaning"), ("F04", "dirty")])
+        );
+        // F02 is stored available, but today's arrival has reserved it.
+        assert_eq!(
+            rooms_in(&pool, Some("available")).await,
+            listed(&[("F05", "available")])
+        );
+        assert!(rooms_in(&pool, Some("out_of_order")).await.is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+//! Tests for reconciling stored room statuses with bookings.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::reconcile_room_statuses_handler;
+    use hotel_app_be::services::room_status::reconcile_room_statuses;
+
+    /// Rooms 9971 to 9975, each stored with a status its bookings may not
+    /// explain.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO users (id, uuid, username, email, is_active)
+             VALUES (9970, 'u-9970', 'duty_manager', 'duty@example.com', 1)",
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (997, 'Drift Twin', 'DTW', 120.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9971, 'D971', 997, 'occupied', 1),
+                    (9972, 'D972', 997, 'available', 1),
+                    (9973, 'D973', 997, 'maintenance', 1),
+                    (9974, 'D974', 997, 'cleaning', 1),
+                    (9975, 'D975', 997, 'available', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9970, 'Omar', 'Haddad', 'Omar Haddad')",
+            // 9971's guest has left; 9972's is in house; 9975's arrives today.
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (9971, 'BK-DR-1', 9970, 9971, date('now', '-3 day'), date('now', '-1 day'), 120.0, 240.0, 'checked_out'),
+             (9972, 'BK-DR-2', 9970, 9972, date('now', '-1 day'), date('now', '+1 day'), 120.0, 240.0, 'checked_in'),
+             (9975, 'BK-DR-5', 9970, 9975, date('now'), date('now', '+2 day'), 120.0, 240.0, 'confirmed')",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn stored_statuses(pool: &sqlx::SqlitePool) -> Vec<(i64, String)> {
+        sqlx::query_as("SELECT id, status FROM rooms WHERE id BETWEEN 9971 AND 9975 ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn drifted_statuses_are_corrected_and_logged() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let corrections = reconcile_room_statuses(&pool, None, None).await.unwrap();
+
+        let changed: Vec<(i64, Option<&str>, &str)> = corrections
+            .iter()
+            .map(|c| (c.room_id, c.from_status.as_deref(), c.to_status.as_str()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (9971, Some("occupied"), "available"),
+                (9972, Some("available"), "occupied"),
+                (9975, Some("available"), "reserved"),
+            ]
+        );
+        assert_eq!(
+            stored_statuses(&pool).await,
+            vec![
+                (9971, "available".to_string()),
+                (9972, "occupied".to_string()),
+                (9973, "maintenance".to_string()),
+                (9974, "cleaning".to_string()),
+                (9975, "reserved".to_string()),
+            ]
+        );
+
+        let logged: Vec<(i64, String, String, bool)> = sqlx::query_as(
+            "SELECT room_id, from_status, to_status, is_auto_generated
+             FROM room_history ORDER BY room_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            logged,
+            vec![
+                (9971, "occupied".to_string(), "available".to_string(), true),
+                (9972, "available".to_string(), "occupied".to_string(), true),
+                (9975, "available".to_string(), "reserved".to_string(), true),
+            ]
+        );
+
+        assert!(
+            reconcile_room_statuses(&pool, None, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn on_demand_reconciliation_records_who_ran_it() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let corrections = reconcile_room_statuses_handler(
+            State(pool.clone()),
+            Extension(9970),
+            Extension(PropertyScope::default()),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(corrections.len(), 3);
+
+        let changed_by: Vec<Option<i64>> =
+            sqlx::query_scalar("SELECT changed_by FROM room_history")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(changed_by, vec![Some(9970); 3]);
+    }
+}
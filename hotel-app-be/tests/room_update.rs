@@ -0,0 +1,175 @@
+//! Tests for partial room and reward updates.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use hotel_app_be::models::{PartialUpdate, RewardUpdateInput, RoomUpdateInput};
+
+fn empty_room_update() -> RoomUpdateInput {
+    RoomUpdateInput {
+        room_number: None,
+        room_type: None,
+        price_per_night: None,
+        available: None,
+        description: None,
+        max_occupancy: None,
+        notes: None,
+    }
+}
+
+#[test]
+fn update_is_empty_only_when_no_field_is_set() {
+    assert!(empty_room_update().is_empty());
+    assert!(
+        !RoomUpdateInput {
+            max_occupancy: Some(3),
+            ..empty_room_update()
+        }
+        .is_empty()
+    );
+
+    let reward: RewardUpdateInput = serde_json::from_str("{}").unwrap();
+    assert!(reward.is_empty());
+    let reward: RewardUpdateInput = serde_json::from_str(r#"{"is_active": false}"#).unwrap();
+    assert!(!reward.is_empty());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{common, empty_room_update};
+    use axum::extract::{Extension, Json, Path, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::rooms::update_room_handler;
+    use hotel_app_be::models::{Room, RoomUpdateInput};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, description, base_price, max_occupancy)
+             VALUES (861, 'Garden Queen', 'GQN', 'Queen bed, garden view', 100.0, 2),
+                    (862, 'Garden Family', 'GFM', 'Two queens', 180.0, 4)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, custom_price, status, is_active)
+             VALUES (8601, 'G101', 861, 95.0, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn update(pool: &sqlx::SqlitePool, input: RoomUpdateInput) -> Result<Room, ApiError> {
+        update_room_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Path(8601),
+            Json(input),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    async fn updated_at(pool: &sqlx::SqlitePool) -> String {
+        sqlx::query_scalar("SELECT updated_at FROM rooms WHERE id = 8601")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn empty_update_returns_the_room_unchanged() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        sqlx::query("UPDATE rooms SET updated_at = '2026-01-01 00:00:00' WHERE id = 8601")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let room = update(&pool, empty_room_update()).await.unwrap();
+        assert_eq!(room.room_number, "G101");
+        assert_eq!(room.room_type, "Garden Queen");
+        assert_eq!(room.price_per_night, Decimal::from(95));
+        assert_eq!(updated_at(&pool).await, "2026-01-01 00:00:00");
+
+        assert!(matches!(
+            update_room_handler(
+                State(pool.clone()),
+                Extension(PropertyScope::default()),
+                Path(99999),
+                Json(empty_room_update())
+            )
+            .await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn room_type_description_and_capacity_are_applied() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let room = update(
+            &pool,
+            RoomUpdateInput {
+                room_type: Some("gfm".to_string()),
+                description: Some("Two queens, patio".to_string()),
+                max_occupancy: Some(5),
+                ..empty_room_update()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(room.room_type, "Garden Family");
+        assert_eq!(room.description.as_deref(), Some("Two queens, patio"));
+        assert_eq!(room.max_occupancy, 5);
+        // The room's own price override is kept.
+        assert_eq!(room.price_per_night, Decimal::from(95));
+    }
+
+    #[tokio::test]
+    async fn notes_update_keeps_the_price_override() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let room = update(
+            &pool,
+            RoomUpdateInput {
+                notes: Some("Squeaky door".to_string()),
+                ..empty_room_update()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(room.notes.as_deref(), Some("Squeaky door"));
+        assert_eq!(room.price_per_night, Decimal::from(95));
+    }
+
+    #[tokio::test]
+    async fn invalid_room_type_or_capacity_is_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for input in [
+            RoomUpdateInput {
+                room_type: Some("Penthouse".to_string()),
+                ..empty_room_update()
+            },
+            RoomUpdateInput {
+                max_occupancy: Some(0),
+                ..empty_room_update()
+            },
+        ] {
+            assert!(matches!(
+                update(&pool, input).await,
+                Err(ApiError::BadRequest(_))
+            ));
+        }
+    }
+}
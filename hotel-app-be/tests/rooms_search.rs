@@ -7,7 +7,8 @@ mod common;
 
 #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
 mod sqlite_tests {
-    use axum::extract::{Query, State};
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::core::property_scope::PropertyScope;
     use hotel_app_be::handlers::rooms::search_rooms_handler;
     use hotel_app_be::models::SearchQuery;
 
@@ -49,12 +50,14 @@ mod sqlite_tests {
 
         let response = search_rooms_handler(
             State(pool),
+            Extension(PropertyScope::default()),
             Query(SearchQuery {
                 room_type: Some("search deluxe".to_string()),
                 max_price: None,
                 check_in_date: None,
                 check_out_date: None,
                 exclude_booking_id: None,
+                guests: None,
             }),
         )
         .await
@@ -70,12 +73,14 @@ mod sqlite_tests {
 
         let response = search_rooms_handler(
             State(pool),
+            Extension(PropertyScope::default()),
             Query(SearchQuery {
                 room_type: Some("sdlx".to_string()),
                 max_price: Some(250.0),
                 check_in_date: None,
                 check_out_date: None,
                 exclude_booking_id: None,
+                guests: None,
             }),
         )
         .await
@@ -109,12 +114,14 @@ mod sqlite_tests {
 
         let response = search_rooms_handler(
             State(pool),
+            Extension(PropertyScope::default()),
             Query(SearchQuery {
                 room_type: Some("Search Deluxe".to_string()),
                 max_price: Some(300.0),
                 check_in_date: Some("2030-01-10".to_string()),
                 check_out_date: Some("2030-01-12".to_string()),
                 exclude_booking_id: None,
+                guests: None,
             }),
         )
         .await
@@ -122,4 +129,37 @@ mod sqlite_tests {
 
         assert_eq!(room_numbers(&response.0), vec!["S202"]);
     }
+
+    #[tokio::test]
+    async fn search_rooms_filters_to_rooms_that_fit_the_party() {
+        let pool = common::setup_test_db().await;
+        seed_search_rooms(&pool).await;
+
+        let search = |guests| {
+            search_rooms_handler(
+                State(pool.clone()),
+                Extension(PropertyScope::default()),
+                Query(SearchQuery {
+                    room_type: None,
+                    max_price: None,
+                    check_in_date: None,
+                    check_out_date: None,
+                    exclude_booking_id: None,
+                    guests: Some(guests),
+                }),
+            )
+        };
+
+        let three = search(3).await.expect("room search should succeed");
+        assert_eq!(room_numbers(&three.0), vec!["S201", "S202", "S301"]);
+
+        let four = search(4).await.expect("room search should succeed");
+        assert_eq!(room_numbers(&four.0), vec!["S301"]);
+
+        assert!(search(5).await.unwrap().0.is_empty());
+        assert!(matches!(
+            search(0).await,
+            Err(hotel_app_be::ApiError::BadRequest(_))
+        ));
+    }
 }
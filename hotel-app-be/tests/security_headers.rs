@@ -0,0 +1,85 @@
+//! Tests for the per-deployment security-header profiles.
+
+use axum::{Router, body::Body, http::HeaderMap, http::Request, http::header, routing::get};
+use hotel_app_be::core::security_headers::{
+    AllowedOrigins, DeploymentMode, security_header_layers,
+};
+use tower::ServiceExt;
+
+async fn response_headers(mode: DeploymentMode) -> HeaderMap {
+    let app = security_header_layers(mode).into_iter().fold(
+        Router::new().route("/ping", get(|| async { "pong" })),
+        |app, layer| app.layer(layer),
+    );
+
+    app.oneshot(Request::get("/ping").body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .headers()
+        .clone()
+}
+
+#[tokio::test]
+async fn desktop_mode_omits_hsts() {
+    let headers = response_headers(DeploymentMode::Desktop).await;
+
+    assert!(!headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+    assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    let csp = headers[header::CONTENT_SECURITY_POLICY].to_str().unwrap();
+    assert!(csp.contains("connect-src 'self' http://127.0.0.1:*"));
+}
+
+#[tokio::test]
+async fn web_mode_keeps_the_strict_set() {
+    let headers = response_headers(DeploymentMode::Web).await;
+
+    assert_eq!(
+        headers[header::STRICT_TRANSPORT_SECURITY],
+        "max-age=31536000; includeSubDomains"
+    );
+    assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+    let csp = headers[header::CONTENT_SECURITY_POLICY].to_str().unwrap();
+    assert!(csp.contains("connect-src 'self';"));
+}
+
+#[test]
+fn deployment_mode_parsing() {
+    assert_eq!(
+        DeploymentMode::parse("desktop"),
+        Some(DeploymentMode::Desktop)
+    );
+    assert_eq!(DeploymentMode::parse(" Web "), Some(DeploymentMode::Web));
+    assert_eq!(DeploymentMode::parse("kiosk"), None);
+    assert_eq!(DeploymentMode::Desktop.default_allowed_origins(), "*");
+}
+
+#[test]
+fn malformed_origins_are_reported() {
+    let AllowedOrigins::List { origins, rejected } = AllowedOrigins::parse(
+        "https://hotel.example.com, http://localhost:5173,,http://[::1]:3000, \
+         hotel.example.com, https://hotel.example.com/, ftp://files.example.com, \
+         http://localhost:99999, https://",
+    ) else {
+        panic!("expected an origin list");
+    };
+
+    assert_eq!(
+        origins,
+        [
+            "https://hotel.example.com",
+            "http://localhost:5173",
+            "http://[::1]:3000"
+        ]
+    );
+    assert_eq!(
+        rejected,
+        [
+            "hotel.example.com",
+            "https://hotel.example.com/",
+            "ftp://files.example.com",
+            "http://localhost:99999",
+            "https://",
+        ]
+    );
+    assert_eq!(AllowedOrigins::parse(" * "), AllowedOrigins::Any);
+}
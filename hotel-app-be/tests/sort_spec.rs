@@ -0,0 +1,83 @@
+//! Tests for the whitelisted sort helper and the list endpoints using it.
+
+use hotel_app_be::ApiError;
+use hotel_app_be::handlers::bookings::BOOKING_SORT;
+use hotel_app_be::handlers::guests::GUEST_SORT;
+use hotel_app_be::handlers::rooms::ROOM_SORT;
+use hotel_app_be::utils::sort::SortSpec;
+
+const SPEC: SortSpec = SortSpec {
+    columns: &[("name", "t.full_name"), ("created_at", "t.created_at")],
+    default_sort: "created_at",
+    default_descending: true,
+    tiebreaker: "t.id",
+};
+
+#[test]
+fn allowed_column_maps_to_its_expression() {
+    assert_eq!(
+        SPEC.order_by(Some("name"), Some("asc")).unwrap(),
+        "t.full_name ASC, t.id ASC"
+    );
+    assert_eq!(
+        SPEC.order_by(Some("name"), Some(" DESC ")).unwrap(),
+        "t.full_name DESC, t.id DESC"
+    );
+}
+
+#[test]
+fn missing_values_use_the_default_sort() {
+    assert_eq!(
+        SPEC.order_by(None, None).unwrap(),
+        "t.created_at DESC, t.id DESC"
+    );
+    assert_eq!(
+        SPEC.order_by(Some(""), Some("")).unwrap(),
+        "t.created_at DESC, t.id DESC"
+    );
+    assert_eq!(
+        SPEC.order_by(None, Some("asc")).unwrap(),
+        "t.created_at ASC, t.id ASC"
+    );
+}
+
+#[test]
+fn disallowed_columns_and_directions_are_rejected() {
+    let cases = [
+        (Some("t.full_name"), None),
+        (Some("name; DROP TABLE guests"), None),
+        (Some("password_hash"), None),
+        (Some("name"), Some("asc; DELETE FROM users")),
+        (Some("name"), Some("upward")),
+    ];
+
+    for (sort, order) in cases {
+        assert!(
+            matches!(SPEC.order_by(sort, order), Err(ApiError::BadRequest(_))),
+            "{sort:?} {order:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn list_endpoint_defaults() {
+    assert_eq!(
+        BOOKING_SORT.order_by(None, None).unwrap(),
+        "b.created_at DESC, b.id DESC"
+    );
+    assert_eq!(
+        GUEST_SORT.order_by(None, None).unwrap(),
+        "full_name ASC, id ASC"
+    );
+    assert_eq!(
+        ROOM_SORT.order_by(None, None).unwrap(),
+        "r.room_number ASC, r.id ASC"
+    );
+    assert_eq!(
+        BOOKING_SORT
+            .order_by(Some("folio_number"), Some("asc"))
+            .unwrap(),
+        "b.booking_number ASC, b.id ASC"
+    );
+    assert!(ROOM_SORT.order_by(Some("guest_name"), None).is_err());
+}
@@ -0,0 +1,132 @@
+//! Tests for checking whether a stay can be extended.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Json, Path, Query, State};
+    use hotel_app_be::ApiError;
+    use hotel_app_be::core::property_scope::PropertyScope;
+    use hotel_app_be::handlers::bookings::can_extend_booking_handler;
+    use hotel_app_be::models::{StayExtensionCheck, StayExtensionParams};
+    use rust_decimal::Decimal;
+
+    /// Room 9961 at 180 a night, booked 1 to 3 November (9961) and again
+    /// from the 5th (9962). Booking 9963 is at an override of 99.5 in room
+    /// 9962, and 9964 there was voided.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (996, 'Extend King', 'EKG', 180.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9961, 'E961', 996, 'occupied', 1), (9962, 'E962', 996, 'occupied', 1)",
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (9960, 'Aiko', 'Sato', 'Aiko Sato')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, rate_override_weekday)
+             VALUES
+             (9961, 'BK-EXT-1', 9960, 9961, '2026-11-01', '2026-11-03', 180.0, 360.0, 'checked_in', NULL),
+             (9962, 'BK-EXT-2', 9960, 9961, '2026-11-05', '2026-11-07', 180.0, 360.0, 'confirmed', NULL),
+             (9963, 'BK-EXT-3', 9960, 9962, '2026-11-01', '2026-11-03', 99.5, 199.0, 'checked_in', 99.5),
+             (9964, 'BK-EXT-4', 9960, 9962, '2026-10-20', '2026-10-22', 180.0, 360.0, 'voided', NULL)",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn can_extend(
+        pool: &sqlx::SqlitePool,
+        booking_id: i64,
+        check_out_date: &str,
+    ) -> Result<StayExtensionCheck, ApiError> {
+        can_extend_booking_handler(
+            State(pool.clone()),
+            Extension(PropertyScope::default()),
+            Path(booking_id),
+            Query(StayExtensionParams {
+                check_out_date: check_out_date.to_string(),
+            }),
+        )
+        .await
+        .map(|json| json.0)
+    }
+
+    #[tokio::test]
+    async fn nights_up_to_the_next_stay_are_free_and_priced() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        // Leaving on the day the next guest arrives is fine.
+        let check = can_extend(&pool, 9961, "2026-11-05").await.unwrap();
+
+        assert!(check.available);
+        assert!(check.conflicting_booking_numbers.is_empty());
+        assert_eq!(check.nightly_rates.len(), 2);
+        assert_eq!(check.additional_amount, Decimal::from(360));
+        assert!(check.service_tax > Decimal::ZERO);
+        assert_eq!(check.currency, "USD");
+
+        let booked: String =
+            sqlx::query_scalar("SELECT check_out_date FROM bookings WHERE id = 9961")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(booked, "2026-11-03");
+    }
+
+    #[tokio::test]
+    async fn an_extension_into_another_stay_is_not_available() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let check = can_extend(&pool, 9961, "2026-11-06").await.unwrap();
+
+        assert!(!check.available);
+        assert_eq!(check.conflicting_booking_numbers, vec!["BK-EXT-2"]);
+        // Still priced, so staff can offer fewer nights.
+        assert_eq!(check.additional_amount, Decimal::from(540));
+    }
+
+    #[tokio::test]
+    async fn the_booking_rate_override_prices_the_added_nights() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let check = can_extend(&pool, 9963, "2026-11-04").await.unwrap();
+
+        assert!(check.available);
+        assert_eq!(check.additional_amount, "99.5".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn invalid_extensions_are_rejected() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        for (booking_id, check_out_date) in [
+            (9961, "2026-11-03"),
+            (9961, "2026-11-02"),
+            (9961, "not-a-date"),
+            (9964, "2026-10-25"),
+        ] {
+            assert!(
+                matches!(
+                    can_extend(&pool, booking_id, check_out_date).await,
+                    Err(ApiError::BadRequest(_))
+                ),
+                "{} {}",
+                booking_id,
+                check_out_date
+            );
+        }
+        assert!(matches!(
+            can_extend(&pool, 9999, "2026-11-05").await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+//! Tests for check-in/check-out times and late-checkout fees.
+//!
+//! Working out how late a guest is and what that costs is pure and runs
+//! under any feature. Marking late checkouts is SQLite-backed and gated
+//! accordingly.
+
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+
+use hotel_app_be::services::stay_policy::{StayPolicy, parse_time, validate_setting};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn at(s: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+}
+
+fn policy(fee_per_hour: i64) -> StayPolicy {
+    StayPolicy {
+        late_checkout_fee_per_hour: Decimal::from(fee_per_hour),
+        ..StayPolicy::default()
+    }
+}
+
+#[test]
+fn guests_are_late_by_each_hour_started_past_check_out() {
+    let policy = policy(20);
+    let departure = date("2026-03-10");
+
+    assert_eq!(policy.hours_late(departure, at("2026-03-10 10:59")), 0);
+    assert_eq!(policy.hours_late(departure, at("2026-03-10 11:00")), 0);
+    assert_eq!(policy.hours_late(departure, at("2026-03-10 11:01")), 1);
+    assert_eq!(policy.hours_late(departure, at("2026-03-10 13:00")), 2);
+    assert_eq!(policy.hours_late(departure, at("2026-03-10 13:30")), 3);
+}
+
+#[test]
+fn late_checkout_fees_never_exceed_a_night() {
+    let policy = policy(20);
+    let (check_in, check_out) = (date("2026-03-08"), date("2026-03-10"));
+    let total = Decimal::from(300);
+
+    assert_eq!(
+        policy.late_checkout_fee(3, total, check_in, check_out),
+        Decimal::from(60)
+    );
+    assert_eq!(
+        policy.late_checkout_fee(12, total, check_in, check_out),
+        Decimal::from(150)
+    );
+    assert_eq!(
+        StayPolicy::default().late_checkout_fee(3, total, check_in, check_out),
+        Decimal::ZERO
+    );
+}
+
+#[test]
+fn policy_times_are_shown_as_hours_and_minutes() {
+    assert_eq!(parse_time(" 14:30:00 "), NaiveTime::from_hms_opt(14, 30, 0));
+    assert_eq!(parse_time("2pm"), None);
+
+    let response = StayPolicy {
+        check_in_time: parse_time("14:30").unwrap(),
+        ..policy(25)
+    }
+    .response();
+    assert_eq!(
+        (
+            response.check_in_time.as_str(),
+            response.check_out_time.as_str()
+        ),
+        ("14:30", "11:00")
+    );
+    assert_eq!(response.late_checkout_fee_per_hour, Decimal::from(25));
+}
+
+#[test]
+fn policy_settings_are_validated() {
+    assert!(validate_setting("check_in_time", "14:00").is_ok());
+    assert!(validate_setting("check_out_time", "25:00").is_err());
+    assert!(validate_setting("late_checkout_fee_per_hour", "12.50").is_ok());
+    assert!(validate_setting("late_checkout_fee_per_hour", "-1").is_err());
+    assert!(validate_setting("hotel_name", "anything").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{at, common};
+    use hotel_app_be::services::stay_policy::{configured, mark_late_checkouts};
+    use rust_decimal::Decimal;
+
+    /// Three in-house bookings: two due out on 10 March, one of them already
+    /// late with a penalty staff set, and one due out the day after.
+    async fn seed(pool: &sqlx::SqlitePool) {
+        for sql in [
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (998, 'Late Double', 'LDB', 150.0, 2)",
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (9981, 'L01', 998, 'occupied', 1),
+                    (9982, 'L02', 998, 'occupied', 1),
+                    (9983, 'L03', 998, 'occupied', 1)",
+            "INSERT INTO guests (id, first_name, last_name) VALUES (9980, 'Tomas', 'Lind')",
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status, late_checkout_penalty)
+             VALUES
+             (9981, 'BK-LC-1', 9980, 9981, '2026-03-08', '2026-03-10', 150.0, 300.0, 'checked_in', 0),
+             (9982, 'BK-LC-2', 9980, 9982, '2026-03-08', '2026-03-10', 150.0, 300.0, 'late_checkout', 120),
+             (9983, 'BK-LC-3', 9980, 9983, '2026-03-08', '2026-03-11', 150.0, 450.0, 'checked_in', 0)",
+            "UPDATE system_settings SET value = '20' WHERE key = 'late_checkout_fee_per_hour'",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn booking(pool: &sqlx::SqlitePool, id: i64) -> (String, f64) {
+        sqlx::query_as("SELECT status, late_checkout_penalty FROM bookings WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn late_guests_are_marked_and_charged_as_the_hours_pass() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let policy = configured(&pool).await;
+        assert_eq!(policy.late_checkout_fee_per_hour, Decimal::from(20));
+
+        assert!(
+            mark_late_checkouts(&pool, &policy, at("2026-03-10 10:30"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let late = mark_late_checkouts(&pool, &policy, at("2026-03-10 13:30"))
+            .await
+            .unwrap();
+        assert_eq!(late.len(), 1);
+        assert_eq!(
+            (late[0].booking_id, late[0].hours_late, late[0].newly_marked),
+            (9981, 3, true)
+        );
+        assert_eq!(late[0].fee, Decimal::from(60));
+        assert_eq!(
+            booking(&pool, 9981).await,
+            ("late_checkout".to_string(), 60.0)
+        );
+        // Staff's higher penalty stands; tomorrow's departure is untouched.
+        assert_eq!(
+            booking(&pool, 9982).await,
+            ("late_checkout".to_string(), 120.0)
+        );
+        assert_eq!(booking(&pool, 9983).await, ("checked_in".to_string(), 0.0));
+
+        // Later runs raise the fee up to one night, without re-marking.
+        let later = mark_late_checkouts(&pool, &policy, at("2026-03-10 19:00"))
+            .await
+            .unwrap();
+        assert_eq!(
+            later
+                .iter()
+                .map(|l| (l.booking_id, l.fee, l.newly_marked))
+                .collect::<Vec<_>>(),
+            [
+                (9981, Decimal::from(150), false),
+                (9982, Decimal::from(150), false)
+            ]
+        );
+        assert!(
+            mark_late_checkouts(&pool, &policy, at("2026-03-10 19:00"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
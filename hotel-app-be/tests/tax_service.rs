@@ -0,0 +1,67 @@
+//! Unit tests for `services::tax`
+//!
+//! All tests here are pure calculations and run under any feature.
+
+use hotel_app_be::services::booking::price_paid_nights;
+use hotel_app_be::services::tax::{
+    DEFAULT_SERVICE_TAX_RATE, TaxContext, compute_taxes, parse_service_tax_rate,
+};
+use rust_decimal::Decimal;
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn inclusive_extracts_tax_from_gross() {
+    let cases = [
+        // (gross, rate %, expected net, expected tax)
+        ("108.00", "8", "100.00", "8.00"),
+        ("110.00", "10", "100.00", "10.00"),
+        ("250.00", "8", "231.48", "18.52"),
+        ("99.99", "6", "94.33", "5.66"),
+        ("0", "8", "0", "0"),
+    ];
+
+    for (gross, rate, net, tax) in cases {
+        let b = compute_taxes(dec(gross), &TaxContext::inclusive(dec(rate)));
+        assert_eq!(b.net, dec(net), "net for {gross} @ {rate}%");
+        assert_eq!(b.service_tax, dec(tax), "tax for {gross} @ {rate}%");
+        assert_eq!(b.gross, dec(gross), "gross must be unchanged");
+        assert_eq!(b.net + b.service_tax, b.gross);
+    }
+}
+
+#[test]
+fn zero_rate_yields_no_tax() {
+    let inclusive = compute_taxes(dec("120.00"), &TaxContext::inclusive(Decimal::ZERO));
+    assert_eq!(inclusive.service_tax, Decimal::ZERO);
+    assert_eq!(inclusive.net, dec("120.00"));
+}
+
+#[test]
+fn service_tax_rate_setting_accepts_zero_and_falls_back_to_default() {
+    assert_eq!(DEFAULT_SERVICE_TAX_RATE, dec("8"));
+    assert_eq!(parse_service_tax_rate(Some("6")), dec("6"));
+    assert_eq!(parse_service_tax_rate(Some(" 10.5 ")), dec("10.5"));
+    assert_eq!(parse_service_tax_rate(Some("0")), Decimal::ZERO);
+    assert_eq!(parse_service_tax_rate(Some("-3")), DEFAULT_SERVICE_TAX_RATE);
+    assert_eq!(
+        parse_service_tax_rate(Some("abc")),
+        DEFAULT_SERVICE_TAX_RATE
+    );
+    assert_eq!(parse_service_tax_rate(None), DEFAULT_SERVICE_TAX_RATE);
+}
+
+#[test]
+fn comping_nights_does_not_add_tax_to_the_rest() {
+    // Three nights at 100 with one comped: the two paid nights still cost 200.
+    let price = price_paid_nights(dec("100"), 2, &TaxContext::inclusive(dec("8")));
+    assert_eq!(price.gross, dec("200"));
+    assert_eq!(price.service_tax, dec("14.81"));
+    assert_eq!(price.net, dec("185.19"));
+
+    let fully_comped = price_paid_nights(dec("100"), 0, &TaxContext::inclusive(dec("8")));
+    assert_eq!(fully_comped.gross, Decimal::ZERO);
+    assert_eq!(fully_comped.service_tax, Decimal::ZERO);
+}
@@ -0,0 +1,102 @@
+//! Tests for the loyalty tier downgrade review.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hotel_app_be::services::tier_review::{
+    GRACE_SETTING, MemberActivity, Tier, TierOutcome, WINDOW_SETTING, review_member,
+    validate_setting,
+};
+
+const BRONZE: i64 = 1;
+const SILVER: i64 = 2;
+const GOLD: i64 = 3;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 6, 1, 3, 0, 0).unwrap()
+}
+
+fn tiers() -> Vec<Tier> {
+    vec![
+        Tier {
+            id: GOLD,
+            program_id: 1,
+            min_points: 5000,
+            min_nights: Some(20),
+        },
+        Tier {
+            id: BRONZE,
+            program_id: 1,
+            min_points: 0,
+            min_nights: None,
+        },
+        Tier {
+            id: SILVER,
+            program_id: 1,
+            min_points: 1000,
+            min_nights: Some(8),
+        },
+    ]
+}
+
+fn member(tier_id: i64, points: i64, nights: i64) -> MemberActivity {
+    MemberActivity {
+        membership_id: 10,
+        program_id: 1,
+        tier_id,
+        tier_since: Some(now() - Duration::days(400)),
+        qualifying_points: points,
+        qualifying_nights: nights,
+    }
+}
+
+fn review(member: &MemberActivity) -> TierOutcome {
+    review_member(&tiers(), member, Duration::days(90), now())
+}
+
+#[test]
+fn member_below_the_window_threshold_is_downgraded() {
+    assert_eq!(
+        review(&member(GOLD, 1200, 3)),
+        TierOutcome::Downgraded { to_tier_id: SILVER }
+    );
+}
+
+#[test]
+fn member_above_the_window_threshold_is_retained() {
+    assert_eq!(review(&member(GOLD, 6000, 0)), TierOutcome::Retained);
+}
+
+#[test]
+fn qualifying_nights_alone_keep_the_tier() {
+    assert_eq!(review(&member(GOLD, 100, 25)), TierOutcome::Retained);
+}
+
+#[test]
+fn downgrade_lands_on_the_highest_tier_still_met() {
+    assert_eq!(
+        review(&member(GOLD, 40, 2)),
+        TierOutcome::Downgraded { to_tier_id: BRONZE }
+    );
+    assert_eq!(review(&member(BRONZE, 0, 0)), TierOutcome::Retained);
+}
+
+#[test]
+fn recently_promoted_members_are_in_grace() {
+    let mut promoted = member(GOLD, 0, 0);
+    promoted.tier_since = Some(now() - Duration::days(30));
+    assert_eq!(review(&promoted), TierOutcome::InGrace);
+
+    promoted.tier_since = Some(now() - Duration::days(91));
+    assert_eq!(
+        review(&promoted),
+        TierOutcome::Downgraded { to_tier_id: BRONZE }
+    );
+}
+
+#[test]
+fn settings_are_validated_on_update() {
+    assert!(validate_setting(WINDOW_SETTING, "12").is_ok());
+    assert!(validate_setting(WINDOW_SETTING, "0").is_err());
+    assert!(validate_setting(GRACE_SETTING, "0").is_ok());
+    assert!(validate_setting(GRACE_SETTING, "-5").is_err());
+    assert!(validate_setting("check_in_time", "anything").is_ok());
+}
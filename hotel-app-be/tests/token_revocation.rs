@@ -0,0 +1,134 @@
+//! Tests for access token revocation.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database. The denylist is shared by the whole test binary, so
+//! each test works with its own tokens.
+
+mod common;
+
+use axum::http::{HeaderMap, HeaderValue};
+use hotel_app_be::AuthService;
+use hotel_app_be::core::middleware::extract_claims;
+use hotel_app_be::core::revoked_tokens;
+
+fn bearer(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "authorization",
+        HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+    );
+    headers
+}
+
+#[tokio::test]
+async fn each_token_gets_its_own_id() {
+    common::install_config(&[]);
+    let first = AuthService::generate_jwt(1, "admin".to_string(), vec![]).unwrap();
+    let second = AuthService::generate_jwt(1, "admin".to_string(), vec![]).unwrap();
+
+    let first = extract_claims(&bearer(&first)).await.unwrap();
+    let second = extract_claims(&bearer(&second)).await.unwrap();
+    assert!(!first.jti.is_empty());
+    assert_ne!(first.jti, second.jti);
+    assert!(!revoked_tokens::is_revoked(&first.jti));
+    assert!(!revoked_tokens::is_revoked(""));
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{bearer, common};
+    use chrono::{Duration, Utc};
+    use hotel_app_be::core::middleware::extract_claims;
+    use hotel_app_be::core::revoked_tokens;
+    use hotel_app_be::{ApiError, AuthService};
+
+    async fn seed_user(pool: &sqlx::SqlitePool, id: i64) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, is_active) VALUES ($1, $2, $3, $4, 1)",
+        )
+        .bind(id)
+        .bind(format!("u-{}", id))
+        .bind(format!("clerk_{}", id))
+        .bind(format!("clerk{}@example.com", id))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn revoked_tokens_are_rejected_and_others_are_not() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        seed_user(&pool, 9880).await;
+
+        let kept = AuthService::generate_jwt(9880, "clerk_9880".to_string(), vec![]).unwrap();
+        let logged_out = AuthService::generate_jwt(9880, "clerk_9880".to_string(), vec![]).unwrap();
+        let claims = extract_claims(&bearer(&logged_out)).await.unwrap();
+
+        revoked_tokens::revoke(&pool, &claims.jti, 9880, Utc::now() + Duration::hours(24))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            extract_claims(&bearer(&logged_out)).await,
+            Err(ApiError::Unauthorized(_))
+        ));
+        assert!(extract_claims(&bearer(&kept)).await.is_ok());
+
+        // Revoking twice is harmless.
+        revoked_tokens::revoke(&pool, &claims.jti, 9880, Utc::now() + Duration::hours(24))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reload_picks_up_revocations_from_the_table() {
+        common::install_config(&[]);
+        let pool = common::setup_test_db().await;
+        seed_user(&pool, 9881).await;
+
+        let token = AuthService::generate_jwt(9881, "clerk_9881".to_string(), vec![]).unwrap();
+        let claims = extract_claims(&bearer(&token)).await.unwrap();
+
+        // As another server instance would record it.
+        sqlx::query("INSERT INTO revoked_tokens (jti, user_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(&claims.jti)
+            .bind(9881_i64)
+            .bind(Utc::now() + Duration::hours(24))
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!revoked_tokens::is_revoked(&claims.jti));
+
+        assert_eq!(revoked_tokens::reload(&pool).await.unwrap(), 1);
+        assert!(revoked_tokens::is_revoked(&claims.jti));
+    }
+
+    #[tokio::test]
+    async fn sweep_purges_only_expired_revocations() {
+        let pool = common::setup_test_db().await;
+        seed_user(&pool, 9882).await;
+        let now = Utc::now();
+
+        revoked_tokens::revoke(&pool, "sweep-expired", 9882, now - Duration::hours(1))
+            .await
+            .unwrap();
+        revoked_tokens::revoke(&pool, "sweep-grace", 9882, now - Duration::minutes(1))
+            .await
+            .unwrap();
+        revoked_tokens::revoke(&pool, "sweep-live", 9882, now + Duration::hours(1))
+            .await
+            .unwrap();
+
+        assert_eq!(revoked_tokens::purge_expired(&pool, now).await.unwrap(), 1);
+        assert!(!revoked_tokens::is_revoked("sweep-expired"));
+        assert!(revoked_tokens::is_revoked("sweep-grace"));
+        assert!(revoked_tokens::is_revoked("sweep-live"));
+
+        let left: Vec<String> = sqlx::query_scalar("SELECT jti FROM revoked_tokens ORDER BY jti")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(left, vec!["sweep-grace", "sweep-live"]);
+    }
+}
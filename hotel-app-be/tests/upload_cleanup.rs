@@ -0,0 +1,197 @@
+//! Tests for orphaned upload cleanup.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use hotel_app_be::services::upload_cleanup::{
+    UploadCleanupSummary, UploadFile, find_orphans, upload_path, validate_setting,
+};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+fn file(path: &str, age: Duration, now: SystemTime) -> UploadFile {
+    UploadFile {
+        path: path.to_string(),
+        modified: now - age,
+    }
+}
+
+#[test]
+fn references_resolve_to_paths_under_the_upload_root() {
+    for reference in [
+        "uploads/ekyc/front.jpg",
+        "/uploads/ekyc/front.jpg",
+        " uploads//ekyc/./front.jpg ",
+        "https://hotel.example.com/uploads/ekyc/front.jpg?v=2",
+        "http://localhost:3030/uploads/ekyc/front.jpg#top",
+    ] {
+        assert_eq!(
+            upload_path(reference).as_deref(),
+            Some("ekyc/front.jpg"),
+            "{reference}"
+        );
+    }
+
+    for reference in [
+        "",
+        "uploads/",
+        "uploads",
+        "uploadsx/a.jpg",
+        "static/uploads/a.jpg",
+        "https://cdn.example.com/avatars/a.png",
+        "https://cdn.example.com",
+        "uploads/../secrets.env",
+        "data:image/png;base64,AAAA",
+    ] {
+        assert_eq!(upload_path(reference), None, "{reference}");
+    }
+}
+
+#[test]
+fn only_old_unreferenced_files_are_orphans() {
+    let now = SystemTime::now();
+    let files = vec![
+        file("avatars/kept.png", 100 * HOUR, now),
+        file("ekyc/front.jpg", 100 * HOUR, now),
+        file("ekyc/abandoned.jpg", 25 * HOUR, now),
+        file("ekyc/just-uploaded.jpg", HOUR, now),
+        file("ekyc/clock-skew.jpg", Duration::ZERO, now + HOUR),
+    ];
+    let references: HashSet<String> = ["/uploads/avatars/kept.png", "uploads/ekyc/front.jpg"]
+        .iter()
+        .filter_map(|r| upload_path(r))
+        .collect();
+
+    let mut summary = UploadCleanupSummary::default();
+    let orphans = find_orphans(&files, &references, 24 * HOUR, now, &mut summary);
+
+    let orphans: Vec<&str> = orphans.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(orphans, vec!["ekyc/abandoned.jpg"]);
+    assert_eq!(summary.scanned, 5);
+    assert_eq!(summary.referenced, 2);
+    assert_eq!(summary.recent, 2);
+}
+
+#[test]
+fn settings_are_validated() {
+    assert!(validate_setting("upload_cleanup_enabled", "true").is_ok());
+    assert!(validate_setting("upload_cleanup_enabled", "sometimes").is_err());
+    assert!(validate_setting("upload_cleanup_grace_hours", "48").is_ok());
+    assert!(validate_setting("upload_cleanup_grace_hours", "0").is_err());
+    assert!(validate_setting("upload_cleanup_grace_hours", "-1").is_err());
+    assert!(validate_setting("upload_cleanup_grace_hours", "100000").is_err());
+    assert!(validate_setting("some_other_setting", "anything").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::{HOUR, common};
+    use hotel_app_be::services::upload_cleanup::{
+        cleanup_uploads_in, list_uploads, referenced_uploads,
+    };
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    /// A fresh upload root with `files` written into it.
+    fn upload_root(name: &str, files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "hotel-upload-cleanup-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        for path in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"test").unwrap();
+        }
+        root
+    }
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email, user_type, avatar_url)
+             VALUES (9901, 'u-9901', 'amir', 'amir@example.com', 'staff', '/uploads/avatars/amir.png'),
+                    (9902, 'u-9902', 'siti', 'siti@example.com', 'staff',
+                     'https://hotel.example.com/uploads/avatars/siti.png'),
+                    (9903, 'u-9903', 'lee', 'lee@example.com', 'staff',
+                     'https://cdn.example.com/lee.png'),
+                    (9904, 'u-9904', 'raj', 'raj@example.com', 'staff', NULL)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn references_are_read_from_the_database() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let mut references: Vec<String> = referenced_uploads(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+        references.sort();
+        assert_eq!(references, vec!["avatars/amir.png", "avatars/siti.png"]);
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_only_old_orphans() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let root = upload_root(
+            "cleanup",
+            &[
+                "avatars/amir.png",
+                "avatars/siti.png",
+                "avatars/former-user.png",
+                "ekyc/abandoned.jpg",
+            ],
+        );
+
+        // Just written, so everything is within the grace period.
+        let summary = cleanup_uploads_in(&pool, &root, 24 * HOUR, SystemTime::now())
+            .await
+            .unwrap();
+        assert!(summary.removed.is_empty());
+        assert_eq!(summary.recent, 2);
+
+        let later = SystemTime::now() + 25 * HOUR;
+        let summary = cleanup_uploads_in(&pool, &root, 24 * HOUR, later)
+            .await
+            .unwrap();
+        assert_eq!(
+            summary.removed,
+            vec!["avatars/former-user.png", "ekyc/abandoned.jpg"]
+        );
+        assert_eq!(summary.referenced, 2);
+        assert!(summary.failed.is_empty());
+
+        let left: Vec<String> = list_uploads(&root)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.path)
+            .collect();
+        assert_eq!(left, vec!["avatars/amir.png", "avatars/siti.png"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_upload_root_is_empty() {
+        let pool = common::setup_test_db().await;
+        let root = upload_root("missing", &[]);
+
+        let summary = cleanup_uploads_in(&pool, &root, HOUR, SystemTime::now())
+            .await
+            .unwrap();
+        assert_eq!(summary.scanned, 0);
+    }
+}
@@ -0,0 +1,118 @@
+//! Tests for the shared upload size and file-type limits.
+
+mod common;
+
+use axum::body::Body;
+use axum::extract::{FromRequest, Multipart};
+use axum::http::{Request, header};
+use hotel_app_be::ApiError;
+use hotel_app_be::core::uploads::{JPEG, PNG, UploadPolicy, WEBP, read_file};
+
+const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+const PHOTO: UploadPolicy = UploadPolicy {
+    what: "Photo",
+    max_bytes: 4 * 1024 * 1024,
+    file_types: &[&JPEG, &PNG, &WEBP],
+};
+
+fn bad_request(result: Result<impl std::fmt::Debug, ApiError>) -> String {
+    match result {
+        Err(ApiError::BadRequest(msg)) => msg,
+        other => panic!("expected a bad request, got {:?}", other),
+    }
+}
+
+/// A multipart request with one `file` field declared as `content_type`.
+async fn multipart(content_type: &str, data: &[u8]) -> Multipart {
+    let mut body = format!(
+        "--X\r\nContent-Disposition: form-data; name=\"file\"; filename=\"f\"\r\n\
+         Content-Type: {}\r\n\r\n",
+        content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n--X--\r\n");
+
+    let request = Request::post("/upload")
+        .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+        .body(Body::from(body))
+        .unwrap();
+    Multipart::from_request(request, &()).await.unwrap()
+}
+
+#[test]
+fn contents_must_match_the_declared_type() {
+    let png = [PNG_HEADER, b"rest of the image"].concat();
+
+    assert_eq!(PHOTO.check("image/PNG", &png).unwrap().extension, "png");
+    assert_eq!(
+        PHOTO
+            .check("image/jpg", b"\xFF\xD8\xFF\xE0")
+            .unwrap()
+            .extension,
+        "jpg"
+    );
+    assert_eq!(
+        PHOTO
+            .check("image/webp", b"RIFF\0\0\0\0WEBPVP8 ")
+            .unwrap()
+            .name,
+        "WebP"
+    );
+
+    assert_eq!(
+        bad_request(PHOTO.check("image/jpeg", &png)),
+        "Photo contents are not a valid JPEG file"
+    );
+    assert_eq!(
+        bad_request(PHOTO.check("image/svg+xml", b"<svg/>")),
+        "Photo must be a JPEG, PNG or WebP file"
+    );
+    assert_eq!(
+        bad_request(PHOTO.check("image/png", b"")),
+        "Photo file is empty"
+    );
+}
+
+#[test]
+fn undeclared_files_are_recognised_by_their_contents() {
+    assert_eq!(PHOTO.sniff(PNG_HEADER).unwrap().name, "PNG");
+    assert!(PHOTO.sniff(b"%PDF-1.7").is_err());
+
+    let mut oversized = PNG_HEADER.to_vec();
+    oversized.resize(PHOTO.max_bytes + 1, 0);
+    assert_eq!(
+        bad_request(PHOTO.sniff(&oversized)),
+        "Photo must be at most 4 MB"
+    );
+    assert!(PHOTO.body_limit() > PHOTO.max_bytes);
+}
+
+#[tokio::test]
+async fn multipart_files_are_limited_while_read() {
+    common::install_config(&[("MAX_UPLOAD_MB", "1")]);
+
+    let mut form = multipart("image/png", PNG_HEADER).await;
+    let field = form.next_field().await.unwrap().unwrap();
+    let upload = read_file(field, &PHOTO).await.unwrap();
+    assert_eq!(upload.content_type, "image/png");
+    assert_eq!(upload.data, PNG_HEADER);
+
+    // MAX_UPLOAD_MB=1 caps the photo's own 4 MB limit.
+    let mut large = PNG_HEADER.to_vec();
+    large.resize(1024 * 1024 + 1, 0);
+    let mut form = multipart("image/png", &large).await;
+    let field = form.next_field().await.unwrap().unwrap();
+    assert_eq!(
+        bad_request(read_file(field, &PHOTO).await),
+        "Photo must be at most 1 MB"
+    );
+
+    let mut form = multipart("application/x-msdownload", b"MZ").await;
+    let field = form.next_field().await.unwrap().unwrap();
+    assert_eq!(
+        bad_request(read_file(field, &PHOTO).await),
+        "Photo must be a JPEG, PNG or WebP file"
+    );
+}
@@ -0,0 +1,114 @@
+//! Tests for the per-user activity feed on the profile page.
+//!
+//! Feed queries run against SQLite like the other integration tests.
+
+mod common;
+
+use hotel_app_be::services::audit::USER_ACTIVITY_ACTIONS;
+
+#[test]
+fn security_and_staff_actions_are_not_user_activity() {
+    for action in ["login_success", "booking_created", "profile_updated"] {
+        assert!(USER_ACTIVITY_ACTIONS.contains(&action), "{action}");
+    }
+    for action in [
+        "login_failure",
+        "role_assigned",
+        "settings_changed",
+        "user_created",
+    ] {
+        assert!(!USER_ACTIVITY_ACTIONS.contains(&action), "{action}");
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use axum::extract::{Extension, Query, State};
+    use hotel_app_be::handlers::profile::get_user_activity_handler;
+    use hotel_app_be::models::{UserActivityQuery, UserActivityResponse};
+
+    const USER: i64 = 9770;
+    const OTHER_USER: i64 = 9771;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO users (id, uuid, username, email)
+             VALUES (9770, 'u-9770', 'aisyah', 'aisyah@example.com'),
+                    (9771, 'u-9771', 'bala', 'bala@example.com')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO audit_logs (user_id, action, entity_type, entity_id, created_at)
+             VALUES
+             (9770, 'login_success', 'user', '9770', '2026-10-01 08:00:00'),
+             (9770, 'login_failure', 'user', '9770', '2026-10-01 08:05:00'),
+             (9770, 'booking_created', 'booking', '4410', '2026-10-01 09:00:00'),
+             (9770, 'settings_changed', 'system_setting', NULL, '2026-10-01 10:00:00'),
+             (9770, 'role_assigned', 'user_role', '9771', '2026-10-01 11:00:00'),
+             (9770, 'profile_updated', 'user', '9770', '2026-10-02 08:00:00'),
+             (9771, 'login_success', 'user', '9771', '2026-10-02 09:00:00'),
+             (9771, 'booking_cancelled', 'booking', '4411', '2026-10-02 10:00:00')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn activity(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> UserActivityResponse {
+        get_user_activity_handler(
+            State(pool.clone()),
+            Extension(user_id),
+            Query(UserActivityQuery {
+                page: Some(page),
+                page_size: Some(page_size),
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    fn actions(response: &UserActivityResponse) -> Vec<&str> {
+        response.data.iter().map(|e| e.action.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn user_sees_only_their_own_user_facing_activity() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let feed = activity(&pool, USER, 1, 25).await;
+        assert_eq!(feed.total, 3);
+        assert_eq!(
+            actions(&feed),
+            vec!["profile_updated", "booking_created", "login_success"]
+        );
+        assert_eq!(feed.data[1].resource_type, "booking");
+        assert_eq!(feed.data[1].resource_id, Some(4410));
+
+        let other = activity(&pool, OTHER_USER, 1, 25).await;
+        assert_eq!(actions(&other), vec!["booking_cancelled", "login_success"]);
+    }
+
+    #[tokio::test]
+    async fn activity_is_paginated() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let first = activity(&pool, USER, 1, 2).await;
+        assert_eq!(actions(&first), vec!["profile_updated", "booking_created"]);
+        assert_eq!(first.total_pages, 2);
+
+        let second = activity(&pool, USER, 2, 2).await;
+        assert_eq!(actions(&second), vec!["login_success"]);
+        assert_eq!(second.total, 3);
+    }
+}
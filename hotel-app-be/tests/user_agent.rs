@@ -0,0 +1,45 @@
+//! Tests for device names derived from `User-Agent` headers.
+
+use hotel_app_be::utils::user_agent::device_name;
+
+#[test]
+fn common_browsers_are_named_with_their_os() {
+    for (user_agent, expected) in [
+        (
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36",
+            "Chrome on macOS",
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.2592.87",
+            "Edge on Windows",
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:127.0) Gecko/20100101 Firefox/127.0",
+            "Firefox on Windows",
+        ),
+        (
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1",
+            "Safari on iOS",
+        ),
+        (
+            "Mozilla/5.0 (Linux; Android 14; SM-S918B) AppleWebKit/537.36 (KHTML, like Gecko) SamsungBrowser/25.0 Chrome/121.0.0.0 Mobile Safari/537.36",
+            "Samsung Internet on Android",
+        ),
+        (
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 OPR/111.0.0.0",
+            "Opera on Linux",
+        ),
+    ] {
+        assert_eq!(device_name(user_agent).as_deref(), Some(expected));
+    }
+}
+
+#[test]
+fn unknown_agents_have_no_name() {
+    assert_eq!(device_name("curl/8.5.0"), None);
+    assert_eq!(device_name(""), None);
+    assert_eq!(
+        device_name("HotelKiosk/2.1 (Windows NT 10.0)").as_deref(),
+        Some("Windows")
+    );
+}
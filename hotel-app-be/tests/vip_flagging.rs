@@ -0,0 +1,133 @@
+//! Tests for flagging VIP guests from their stay history.
+//!
+//! SQLite-backed tests are gated so the default PostgreSQL build is not forced
+//! to create a database.
+
+mod common;
+
+use hotel_app_be::services::vip::{NIGHTS_SETTING, SPEND_SETTING, VipConfig, validate_setting};
+use rust_decimal::Decimal;
+
+#[test]
+fn either_threshold_qualifies() {
+    let config = VipConfig {
+        min_nights: Some(20),
+        min_spend: Some(Decimal::from(5_000)),
+    };
+    assert!(config.qualifies(20, Decimal::ZERO));
+    assert!(config.qualifies(2, Decimal::from(5_000)));
+    assert!(!config.qualifies(19, "4999.99".parse().unwrap()));
+
+    let nights_only = VipConfig {
+        min_spend: None,
+        ..config
+    };
+    assert!(!nights_only.qualifies(5, Decimal::from(1_000_000)));
+}
+
+#[test]
+fn thresholds_are_validated() {
+    assert!(validate_setting(NIGHTS_SETTING, "0").is_ok());
+    assert!(validate_setting(NIGHTS_SETTING, "45").is_ok());
+    assert!(validate_setting(SPEND_SETTING, "7500.50").is_ok());
+    for bad in ["-1", "ten", "4000"] {
+        assert!(validate_setting(NIGHTS_SETTING, bad).is_err());
+    }
+    assert!(validate_setting(SPEND_SETTING, "-100").is_err());
+    assert!(validate_setting("timezone", "-1").is_ok());
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+mod sqlite_tests {
+    use super::common;
+    use chrono::NaiveDate;
+    use hotel_app_be::services::pre_arrival::arriving_guests;
+    use hotel_app_be::services::vip::{VipConfig, configured, flag_vip_guests};
+    use rust_decimal::Decimal;
+
+    async fn seed(pool: &sqlx::SqlitePool) {
+        sqlx::query(
+            "INSERT INTO room_types (id, name, code, base_price, max_occupancy)
+             VALUES (871, 'VIP Suite', 'VSU', 400.0, 2)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO rooms (id, room_number, room_type_id, status, is_active)
+             VALUES (8701, 'V701', 871, 'available', 1)",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO guests (id, first_name, last_name, full_name)
+             VALUES (8701, 'Mei', 'Tan', 'Mei Tan'),
+                    (8702, 'Raj', 'Kumar', 'Raj Kumar'),
+                    (8703, 'Siti', 'Aziz', 'Siti Aziz')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        // Mei: 10 + 6 checked-out nights. Raj: 4 nights, plus a cancelled
+        // long stay that doesn't count. Siti: one expensive stay.
+        sqlx::query(
+            "INSERT INTO bookings
+             (id, booking_number, guest_id, room_id, check_in_date, check_out_date,
+              rate_per_night, total_amount, status)
+             VALUES
+             (8701, 'BK-VIP-1', 8701, 8701, '2026-01-01', '2026-01-11', 100.0, 1000.0, 'checked_out'),
+             (8702, 'BK-VIP-2', 8701, 8701, '2026-03-01', '2026-03-07', 100.0, 600.0, 'checked_out'),
+             (8703, 'BK-VIP-3', 8702, 8701, '2026-04-01', '2026-04-05', 100.0, 400.0, 'checked_out'),
+             (8704, 'BK-VIP-4', 8702, 8701, '2026-05-01', '2026-05-21', 100.0, 2000.0, 'cancelled'),
+             (8705, 'BK-VIP-5', 8703, 8701, '2026-06-01', '2026-06-03', 2600.0, 5200.0, 'checked_out'),
+             (8706, 'BK-VIP-6', 8701, 8701, '2026-12-20', '2026-12-22', 100.0, 200.0, 'confirmed')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn vip_guests(pool: &sqlx::SqlitePool) -> Vec<i64> {
+        sqlx::query_scalar("SELECT id FROM guests WHERE is_vip = 1 ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn guests_crossing_a_threshold_are_flagged() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+        let config = VipConfig {
+            min_nights: Some(15),
+            min_spend: Some(Decimal::from(5_000)),
+        };
+
+        let flagged = flag_vip_guests(&pool, &config).await.unwrap();
+        assert_eq!(flagged, vec![8701, 8703]);
+        assert_eq!(vip_guests(&pool).await, vec![8701, 8703]);
+
+        // Already flagged guests are not flagged again.
+        assert!(flag_vip_guests(&pool, &config).await.unwrap().is_empty());
+
+        let arrivals = arriving_guests(&pool, NaiveDate::from_ymd_opt(2026, 12, 18).unwrap(), 7)
+            .await
+            .unwrap();
+        assert_eq!(arrivals.len(), 1);
+        assert!(arrivals[0].is_vip);
+    }
+
+    #[tokio::test]
+    async fn default_thresholds_leave_short_histories_alone() {
+        let pool = common::setup_test_db().await;
+        seed(&pool).await;
+
+        let config = configured(&pool).await;
+        assert_eq!(config.min_nights, Some(30));
+        assert_eq!(config.min_spend, Some(Decimal::from(10_000)));
+        assert!(flag_vip_guests(&pool, &config).await.unwrap().is_empty());
+        assert!(vip_guests(&pool).await.is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+//! Tests for checking in guests who arrive without a booking.
+//!
+//! Checking who a walk-in is for and how long they stay is pure and runs
+//! under any feature.
+
+use chrono::NaiveDate;
+use hotel_app_be::ApiError;
+use hotel_app_be::models::WalkInInput;
+use hotel_app_be::services::walk_in::{WalkInGuest, check_walk_in_stay, walk_in_guest};
+
+fn walk_in(body: serde_json::Value) -> WalkInInput {
+    let mut input = serde_json::json!({"room_id": 101, "check_out_date": "2026-10-18"});
+    input
+        .as_object_mut()
+        .unwrap()
+        .extend(body.as_object().unwrap().clone());
+    serde_json::from_value(input).unwrap()
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn walk_ins_are_for_a_known_guest_or_a_new_one() {
+    assert!(matches!(
+        walk_in_guest(&walk_in(serde_json::json!({"guest_id": 7}))),
+        Ok(WalkInGuest::Existing(7))
+    ));
+
+    let new_guest = walk_in(serde_json::json!({
+        "guest": {"first_name": "Ada", "last_name": "Okafor", "phone": "+60 12-345 6789"}
+    }));
+    match walk_in_guest(&new_guest).unwrap() {
+        WalkInGuest::New(guest) => {
+            assert_eq!(
+                (guest.first_name.as_str(), guest.last_name.as_str()),
+                ("Ada", "Okafor")
+            )
+        }
+        other => panic!("expected a new guest, got {:?}", other),
+    }
+
+    for body in [
+        serde_json::json!({}),
+        serde_json::json!({"guest_id": 7, "guest": {"first_name": "Ada", "last_name": "Okafor"}}),
+    ] {
+        assert!(matches!(
+            walk_in_guest(&walk_in(body)),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
+
+#[test]
+fn walk_ins_stay_from_today() {
+    let today = date("2026-10-16");
+    assert!(check_walk_in_stay(date("2026-10-18"), today).is_ok());
+    // Leaving the same day is a day-use stay.
+    assert!(check_walk_in_stay(today, today).is_ok());
+    assert!(matches!(
+        check_walk_in_stay(date("2026-10-15"), today),
+        Err(ApiError::BadRequest(_))
+    ));
+}
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- MIGRATION 016: CONFIGURABLE BOOKING NUMBER FORMAT
+-- ============================================================================
+-- Booking numbers can follow a template (`booking_number_format`) with an
+-- optional Luhn check digit. `{seq}` values are allocated per rendered prefix
+-- from booking_number_sequences. An empty template keeps the legacy random
+-- BK-YYYYMMDD-xxxxxxxx format. See services::booking_numbers.
+
+CREATE TABLE IF NOT EXISTS booking_number_sequences (
+    scope VARCHAR(100) PRIMARY KEY,
+    last_value BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('booking_number_format', '', 'string', 'booking',
+     'Booking number template using {year}, {month}, {day}, {date}, {property} and {seq} or {seq:N}; empty keeps the default random format'),
+    ('booking_number_check_digit', 'false', 'boolean', 'booking',
+     'Append a Luhn check digit to templated booking numbers'),
+    ('property_code', '', 'string', 'general',
+     'Short property code (letters/digits, max 10) used by the {property} booking number token')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,14 @@
+-- ============================================================================
+-- MIGRATION 017: AUTOMATIC RELEASE OF CLEANED ROOMS
+-- ============================================================================
+-- Rooms in `cleaning`/`dirty` whose cleaning_end_date has passed are returned
+-- to `available` by the scheduler and night audit when enabled.
+-- See services::housekeeping.
+
+CREATE INDEX IF NOT EXISTS idx_rooms_cleaning_end_date
+    ON rooms(cleaning_end_date) WHERE status IN ('cleaning', 'dirty');
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('auto_release_cleaned_rooms', 'false', 'boolean', 'housekeeping',
+        'Return cleaning/dirty rooms to available once their cleaning end date passes and no guest is in house')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,24 @@
+-- ============================================================================
+-- MIGRATION 018: PER-USER API KEYS
+-- ============================================================================
+-- Long-lived keys for server-to-server integrations, sent as `X-Api-Key`.
+-- Only the SHA-256 of the key is stored; the plaintext is returned once at
+-- creation. A key acts as its owner, limited to its `scopes` (permission
+-- names such as `rooms:read`). See core::api_keys.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user_id ON api_keys(user_id) WHERE revoked_at IS NULL;
+
+COMMENT ON TABLE api_keys IS 'Hashed per-user API keys for integrations';
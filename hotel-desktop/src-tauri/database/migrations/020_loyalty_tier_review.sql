@@ -0,0 +1,45 @@
+-- ============================================================================
+-- MIGRATION 020: LOYALTY TIER DOWNGRADE REVIEW
+-- ============================================================================
+-- When demotion is enabled, the night audit periodically reviews each
+-- member's tier against qualifying points and nights earned in a rolling
+-- window, downgrading members who no longer meet it. Every review and tier
+-- change is recorded. See services::tier_review.
+
+ALTER TABLE loyalty_tiers ADD COLUMN IF NOT EXISTS min_nights INTEGER;
+
+CREATE TABLE IF NOT EXISTS loyalty_tier_reviews (
+    id BIGSERIAL PRIMARY KEY,
+    window_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    window_end TIMESTAMP WITH TIME ZONE NOT NULL,
+    reviewed_count INTEGER NOT NULL DEFAULT 0,
+    downgraded_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS loyalty_tier_changes (
+    id BIGSERIAL PRIMARY KEY,
+    membership_id BIGINT NOT NULL REFERENCES loyalty_memberships(id) ON DELETE CASCADE,
+    review_id BIGINT REFERENCES loyalty_tier_reviews(id) ON DELETE SET NULL,
+    from_tier_id BIGINT REFERENCES loyalty_tiers(id) ON DELETE SET NULL,
+    to_tier_id BIGINT REFERENCES loyalty_tiers(id) ON DELETE SET NULL,
+    reason VARCHAR(50) NOT NULL,
+    qualifying_points INTEGER,
+    qualifying_nights INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_tier_changes_membership
+    ON loyalty_tier_changes(membership_id, created_at);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('loyalty_tier_demotion_enabled', 'false', 'boolean', 'loyalty',
+     'Periodically downgrade members whose recent activity no longer meets their tier'),
+    ('loyalty_tier_review_window_months', '12', 'number', 'loyalty',
+     'Rolling window of qualifying points and nights considered by the tier review'),
+    ('loyalty_tier_review_interval_months', '12', 'number', 'loyalty',
+     'Months between tier reviews; the night audit runs one when due'),
+    ('loyalty_tier_grace_days', '90', 'number', 'loyalty',
+     'Members who reached their tier within this many days are not downgraded')
+ON CONFLICT (key) DO NOTHING;
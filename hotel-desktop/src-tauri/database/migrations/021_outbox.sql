@@ -0,0 +1,33 @@
+-- ============================================================================
+-- MIGRATION 021: TRANSACTIONAL OUTBOX
+-- ============================================================================
+-- Domain events (booking created, payment posted) are written to `outbox` in
+-- the same transaction as the change that raised them. A background
+-- dispatcher delivers pending rows to the configured webhook and marks them
+-- delivered, retrying with backoff. Delivery is at-least-once: receivers
+-- should de-duplicate on the event id. See services::outbox.
+
+CREATE TABLE IF NOT EXISTS outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(100) NOT NULL,
+    aggregate_type VARCHAR(50) NOT NULL,
+    aggregate_id BIGINT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_pending
+    ON outbox(next_attempt_at, id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_outbox_aggregate ON outbox(aggregate_type, aggregate_id);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('outbox_webhook_url', '', 'string', 'integrations',
+     'URL that receives booking and payment events as JSON POSTs; empty disables delivery')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,25 @@
+-- ============================================================================
+-- MIGRATION 022: NO-SHOW DEPOSIT FORFEITURE
+-- ============================================================================
+-- When enabled, the night audit forfeits the deposit collected on a no-show
+-- booking to revenue. Each forfeiture is recorded once per booking under the
+-- `no_show_forfeit` category, and a forfeited deposit is no longer
+-- refundable. See services::deposit_forfeiture.
+
+CREATE TABLE IF NOT EXISTS deposit_forfeitures (
+    id BIGSERIAL PRIMARY KEY,
+    booking_id BIGINT NOT NULL UNIQUE REFERENCES bookings(id) ON DELETE CASCADE,
+    amount DECIMAL(12,2) NOT NULL CHECK (amount > 0),
+    category VARCHAR(30) NOT NULL DEFAULT 'no_show_forfeit',
+    audit_date DATE NOT NULL,
+    forfeited_by BIGINT REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_deposit_forfeitures_audit_date
+    ON deposit_forfeitures(audit_date);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('forfeit_deposit_on_no_show', 'false', 'boolean', 'night_audit',
+        'Night audit forfeits the deposit on no-show bookings to revenue instead of leaving it refundable')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,27 @@
+-- ============================================================================
+-- MIGRATION 025: PASSWORD POLICY
+-- ============================================================================
+-- Makes the password rules configurable per property. The defaults match the
+-- previously built-in policy. With `password_breached_check` on, passwords
+-- listed in the file at `password_breached_list_path` are rejected as well.
+-- See services::password_policy.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('password_min_length', '8', 'number', 'security',
+     'Minimum password length'),
+    ('password_max_length', '128', 'number', 'security',
+     'Maximum password length'),
+    ('password_require_uppercase', 'true', 'boolean', 'security',
+     'Passwords must contain an uppercase letter'),
+    ('password_require_lowercase', 'true', 'boolean', 'security',
+     'Passwords must contain a lowercase letter'),
+    ('password_require_digit', 'true', 'boolean', 'security',
+     'Passwords must contain a digit'),
+    ('password_require_symbol', 'true', 'boolean', 'security',
+     'Passwords must contain a special character'),
+    ('password_breached_check', 'false', 'boolean', 'security',
+     'Reject passwords found in the breached-password list'),
+    ('password_breached_list_path', '', 'string', 'security',
+     'Path to a local file of breached passwords, one per line')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- MIGRATION 029: PORTAL GUEST LINK
+-- ============================================================================
+-- Portal signup creates a guest from the new account and links it through
+-- user_guests so the first booking can find it. See services::portal_guest.
+-- Accounts that got a guest at signup before the link existed are linked
+-- here.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('portal_auto_create_guest', 'true', 'boolean', 'guests',
+        'Create and link a guest profile when a portal user signs up')
+ON CONFLICT (key) DO NOTHING;
+
+INSERT INTO user_guests
+    (user_id, guest_id, relationship_type, can_book_for, can_view_bookings, can_modify, linked_by)
+SELECT u.id, u.guest_id, 'self', true, true, true, u.id
+FROM users u
+JOIN guests g ON g.id = u.guest_id AND g.deleted_at IS NULL
+WHERE u.user_type = 'guest'
+ON CONFLICT (user_id, guest_id) DO NOTHING;
@@ -0,0 +1,14 @@
+-- ============================================================================
+-- MIGRATION 032: ORPHANED UPLOAD CLEANUP
+-- ============================================================================
+-- Files under uploads/ that no avatar, eKYC or catalogue row refers to are
+-- removed by the night audit when `upload_cleanup_enabled` is on, once they
+-- are older than `upload_cleanup_grace_hours`. See services::upload_cleanup.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('upload_cleanup_enabled', 'false', 'boolean', 'maintenance',
+     'Night audit removes uploaded files that no record refers to'),
+    ('upload_cleanup_grace_hours', '24', 'number', 'maintenance',
+     'Unreferenced uploads newer than this many hours are kept')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- MIGRATION 033: DAY-USE RATE
+-- ============================================================================
+-- Same-day (hourly) bookings are charged `day_use_rate_percent` of the
+-- night's rate. 100 keeps charging a full night. Rates set on the booking
+-- itself are charged as given. See services::booking::apply_day_use.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('day_use_rate_percent', '100', 'number', 'booking',
+        'Percentage of the nightly rate charged for same-day (day-use) bookings')
+ON CONFLICT (key) DO NOTHING;
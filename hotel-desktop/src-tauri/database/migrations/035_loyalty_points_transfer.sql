@@ -0,0 +1,16 @@
+-- ============================================================================
+-- MIGRATION 035: LOYALTY POINTS TRANSFER
+-- ============================================================================
+-- POST /loyalty/transfer moves points between memberships and requires
+-- `loyalty:manage`. Version 034 is taken by a desktop-only migration.
+
+INSERT INTO permissions (name, resource, action, description, is_system_permission)
+VALUES ('loyalty:manage', 'loyalty', 'manage', 'Transfer and adjust loyalty points', true)
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.name IN ('admin', 'super_admin', 'manager')
+AND p.name = 'loyalty:manage'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,23 @@
+-- ============================================================================
+-- MIGRATION 037: LOGIN ATTEMPT LOCKOUT
+-- ============================================================================
+-- Failed logins are counted per username and client IP, including usernames
+-- that do not exist, so lockouts do not reveal which accounts are real.
+-- After `max_login_attempts` consecutive failures the pair is locked for
+-- `login_lockout_minutes`. This replaces the per-user counters on `users`.
+-- See services::login_attempts.
+
+CREATE TABLE IF NOT EXISTS login_attempts (
+    username VARCHAR(255) NOT NULL,
+    ip_address VARCHAR(45) NOT NULL,
+    failed_count INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP WITH TIME ZONE,
+    locked_until TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (username, ip_address)
+);
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('login_lockout_minutes', '30', 'number', 'security',
+     'Minutes a username is locked out from an IP after too many failed logins')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,15 @@
+-- ============================================================================
+-- MIGRATION 039: ACCESS TOKEN REVOCATION
+-- ============================================================================
+-- Logging out puts the access token's `jti` here so it stops working before
+-- it expires. Rows are purged once the token would have expired anyway.
+-- See core::revoked_tokens.
+
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti VARCHAR(64) PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- MIGRATION 040: BOOKING CANCELLATION POLICY
+-- ============================================================================
+-- Bookings can now be cancelled, not only voided. Cancelling more than
+-- `cancellation_free_days` before check-in is free; later cancellations are
+-- charged `cancellation_fee_percent` of the booking total, recorded in
+-- `bookings.cancellation_fee`. See services::cancellation.
+
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_status_check;
+ALTER TABLE bookings ADD CONSTRAINT bookings_status_check CHECK (status IN (
+    'pending', 'confirmed', 'checked_in', 'auto_checked_in', 'checked_out',
+    'no_show', 'completed', 'cancelled', 'comp_cancelled',
+    'partial_complimentary', 'fully_complimentary', 'voided'
+));
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('cancellation_free_days', '2', 'number', 'booking',
+     'Bookings cancelled more than this many days before check-in are not charged'),
+    ('cancellation_fee_percent', '50', 'number', 'booking',
+     'Percentage of the booking total charged for later cancellations')
+ON CONFLICT (key) DO NOTHING;
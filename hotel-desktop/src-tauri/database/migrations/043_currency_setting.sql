@@ -0,0 +1,12 @@
+-- ============================================================================
+-- MIGRATION 043: HOTEL CURRENCY
+-- ============================================================================
+-- Amounts are stored without a currency; `currency` (ISO 4217) names the
+-- one they are in and is returned alongside amounts. Only the seed data set
+-- it before, so databases created without it get the default here. See
+-- services::currency.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('currency', 'USD', 'string', 'general', 'Default currency code')
+ON CONFLICT (key) DO NOTHING;
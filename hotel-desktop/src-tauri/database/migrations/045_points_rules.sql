@@ -0,0 +1,30 @@
+-- ============================================================================
+-- MIGRATION 045: POINTS-EARNING RULES
+-- ============================================================================
+-- A checked-out stay earns its total before tax at the program's points per
+-- unit of currency times the member's tier multiplier, adjusted by the
+-- active rules: `multiplier` rules (e.g. double points) scale that base,
+-- `bonus` rules add points and `floor` rules set the least a paid stay
+-- earns. A rule may be limited to a room type and a window of check-in
+-- dates. The award is an `earn` transaction referencing the booking; the
+-- unique index allows one per stay. See services::loyalty.
+
+CREATE TABLE IF NOT EXISTS points_rules (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    rule_type VARCHAR(20) NOT NULL CHECK (rule_type IN ('bonus', 'multiplier', 'floor')),
+    room_type_id BIGINT REFERENCES room_types(id) ON DELETE CASCADE,
+    bonus_points INTEGER CHECK (bonus_points > 0),
+    multiplier DECIMAL(4,2) CHECK (multiplier >= 1),
+    starts_on DATE,
+    ends_on DATE,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_on IS NULL OR starts_on IS NULL OR ends_on >= starts_on)
+);
+
+CREATE INDEX IF NOT EXISTS idx_points_rules_active ON points_rules(is_active) WHERE is_active = true;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_points_transactions_stay_once
+    ON points_transactions(reference_id) WHERE reference_type = 'stay';
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- MIGRATION 046: LOYALTY MEMBERSHIP EXPIRY
+-- ============================================================================
+-- The night audit moves active memberships past `expires_at` to `expired`,
+-- optionally dropping them to their program's lowest tier (recorded in
+-- loyalty_tier_changes). Renewing reactivates a membership for the
+-- configured term. See services::membership_expiry.
+
+ALTER TABLE loyalty_memberships DROP CONSTRAINT IF EXISTS loyalty_memberships_status_check;
+ALTER TABLE loyalty_memberships ADD CONSTRAINT loyalty_memberships_status_check
+    CHECK (status IN ('active', 'inactive', 'suspended', 'expired'));
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_memberships_expires_at
+    ON loyalty_memberships(expires_at) WHERE status = 'active';
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('loyalty_expiry_downgrade_enabled', 'false', 'boolean', 'loyalty',
+     'Drop expiring memberships to the lowest tier of their program'),
+    ('loyalty_membership_term_months', '12', 'number', 'loyalty',
+     'Months a membership is extended by when it is renewed')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,8 @@
+-- ============================================================================
+-- MIGRATION 048: AVATAR THUMBNAILS
+-- ============================================================================
+-- Avatars uploaded through POST /profile/avatar are stored under
+-- uploads/avatars with a thumbnail beside them. The thumbnail is referenced
+-- here so the upload cleanup keeps it. See services::avatars.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_thumbnail_url TEXT;
@@ -0,0 +1,8 @@
+-- ============================================================================
+-- MIGRATION 050: FORCED PASSWORD CHANGE
+-- ============================================================================
+-- Accounts created by an administrator start with a password someone else
+-- chose. Until the owner sets their own through POST /profile/password,
+-- every other authenticated request is refused. See core::password_change.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS must_change_password BOOLEAN NOT NULL DEFAULT false;
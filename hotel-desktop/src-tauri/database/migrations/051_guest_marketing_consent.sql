@@ -0,0 +1,23 @@
+-- ============================================================================
+-- MIGRATION 051: GUEST MARKETING CONSENT
+-- ============================================================================
+-- Marketing consent sits beside the email and SMS preferences of migration
+-- 031 but has no default setting: guests are opted out until they opt in.
+-- consent_timestamp is when any of the three last changed, and every change
+-- is kept in guest_consent_history. See services::communication.
+
+ALTER TABLE guests ADD COLUMN IF NOT EXISTS marketing_opt_in BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE guests ADD COLUMN IF NOT EXISTS consent_timestamp TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS guest_consent_history (
+    id BIGSERIAL PRIMARY KEY,
+    guest_id BIGINT NOT NULL REFERENCES guests(id) ON DELETE CASCADE,
+    channel VARCHAR(20) NOT NULL CHECK (channel IN ('email', 'sms', 'marketing')),
+    opted_in BOOLEAN NOT NULL,
+    -- The staff member, or the guest's own portal account
+    changed_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_guest_consent_history_guest
+    ON guest_consent_history(guest_id, created_at DESC);
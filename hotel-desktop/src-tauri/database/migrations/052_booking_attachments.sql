@@ -0,0 +1,22 @@
+-- ============================================================================
+-- MIGRATION 052: BOOKING ATTACHMENTS
+-- ============================================================================
+-- Scans staff attach to a booking, such as registration cards and signed
+-- forms. Files are stored under uploads/bookings/<booking_id>; file_url is
+-- the path they are served from. Removing a booking removes its rows and
+-- leaves the files to the upload cleanup. See services::booking_attachments.
+
+CREATE TABLE IF NOT EXISTS booking_attachments (
+    id BIGSERIAL PRIMARY KEY,
+    booking_id BIGINT NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    -- Name of the file as uploaded, for display
+    file_name VARCHAR(255) NOT NULL,
+    file_url VARCHAR(500) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_attachments_booking
+    ON booking_attachments(booking_id, created_at);
@@ -0,0 +1,37 @@
+-- ============================================================================
+-- MIGRATION 055: BOOKING COMPANY ATTRIBUTION
+-- ============================================================================
+-- Bookings made for a company before bookings could name one by id only
+-- carry its name. Link them to the company of that name so they show in the
+-- company's bookings and the revenue report's company section. A name that
+-- more than one company goes by can't say which one was meant, so those
+-- bookings stay unattributed. See services::company_bookings.
+
+WITH named_once AS (
+    SELECT MIN(id) AS id, LOWER(company_name) AS name
+    FROM companies
+    GROUP BY LOWER(company_name)
+    HAVING COUNT(*) = 1
+)
+UPDATE bookings b
+SET company_id = c.id
+FROM named_once c
+WHERE b.company_id IS NULL
+  AND b.company_name IS NOT NULL
+  AND LOWER(b.company_name) = c.name;
+
+WITH named_once AS (
+    SELECT MIN(id) AS id, LOWER(company_name) AS name
+    FROM companies
+    GROUP BY LOWER(company_name)
+    HAVING COUNT(*) = 1
+)
+UPDATE bookings_archive b
+SET company_id = c.id
+FROM named_once c
+WHERE b.company_id IS NULL
+  AND b.company_name IS NOT NULL
+  AND LOWER(b.company_name) = c.name;
+
+CREATE INDEX IF NOT EXISTS idx_bookings_archive_company_id
+    ON bookings_archive(company_id) WHERE company_id IS NOT NULL;
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- MIGRATION 056: BOOKING SOURCES
+-- ============================================================================
+-- The channels a booking's source may be, and the source given to bookings
+-- made without one. Bookings that have none yet get that default. See
+-- services::booking_sources.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES
+    ('booking_sources',
+     '["walk_in","direct","phone","website","mobile","online","agent","corporate","booking_com","expedia","agoda"]',
+     'json', 'booking', 'Channels a booking may come from'),
+    ('default_booking_source', 'walk_in', 'string', 'booking',
+     'Source given to bookings made without one')
+ON CONFLICT (key) DO NOTHING;
+
+UPDATE bookings SET source = 'walk_in' WHERE source IS NULL OR TRIM(source) = '';
+UPDATE bookings_archive SET source = 'walk_in' WHERE source IS NULL OR TRIM(source) = '';
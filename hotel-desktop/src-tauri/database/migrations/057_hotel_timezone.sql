@@ -0,0 +1,9 @@
+-- ============================================================================
+-- MIGRATION 057: HOTEL TIMEZONE
+-- ============================================================================
+-- The IANA timezone the hotel's business day follows. Seeded installs
+-- already have it; this adds it where they do not. See services::hotel_time.
+
+INSERT INTO system_settings (key, value, value_type, category, description)
+VALUES ('timezone', 'UTC', 'string', 'general', 'Hotel timezone (IANA name, e.g. Asia/Kuala_Lumpur)')
+ON CONFLICT (key) DO NOTHING;
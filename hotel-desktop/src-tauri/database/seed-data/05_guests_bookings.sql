@@ -1,5 +1,5 @@
 -- ============================================================================
--- SEED 04: GUESTS, BOOKINGS, COMPANIES, ROOM CHANGES - ALL SCENARIOS
+-- SEED 05: GUESTS, BOOKINGS, COMPANIES, ROOM CHANGES - ALL SCENARIOS
 -- ============================================================================
 -- Description: Comprehensive test data covering ALL possibilities
 -- ============================================================================
@@ -1,5 +1,5 @@
 -- ============================================================================
--- SEED 05: LOYALTY PROGRAMS & MEMBERSHIPS
+-- SEED 06: LOYALTY PROGRAMS & MEMBERSHIPS
 -- ============================================================================
 -- Description: Loyalty programs, tiers, memberships, and points transactions
 -- ============================================================================
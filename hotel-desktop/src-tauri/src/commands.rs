@@ -45,6 +45,16 @@ pub async fn get_status(app_handle: AppHandle) -> Result<AppStatus, String> {
     })
 }
 
+/// Get the bundled PostgreSQL server's process and database status
+#[tauri::command]
+pub async fn get_database_status(
+    app_handle: AppHandle,
+) -> Result<crate::postgres::DatabaseStatus, String> {
+    Ok(crate::postgres::EmbeddedDatabase::new(&app_handle)
+        .status()
+        .await)
+}
+
 fn find_available_backend_port(preferred: u16) -> u16 {
     if TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
         return preferred;
@@ -99,6 +109,7 @@ pub async fn start_backend_sidecar(app_handle: &AppHandle) -> Result<(), String>
         .env("BACKEND_PORT", backend_port.to_string())
         .env("JWT_SECRET", "super-secret-jwt-key-for-hotel-desktop-app")
         .env("HOTEL_DESKTOP_MODE", "1")
+        .env("DEPLOYMENT_MODE", "desktop")
         .env("ALLOWED_ORIGINS", "*")
         .env("SKIP_EMAIL_VERIFICATION", "true")
         .env("RUST_LOG", "info");
@@ -191,12 +202,56 @@ async fn wait_for_backend_ready(port: u16) -> Result<(), String> {
     ))
 }
 
-/// Stop the backend sidecar process
+/// How long the backend gets to flush its outbox and exit after SIGTERM
+/// before it is killed. Covers the backend's own 5 second flush timeout.
+const BACKEND_STOP_GRACE: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Ask the backend to shut down gracefully and wait for it to exit. Returns
+/// false when it is still running after the grace period.
+#[cfg(unix)]
+async fn request_backend_shutdown(pid: u32) -> bool {
+    let signalled = tokio::process::Command::new("kill")
+        .arg("-TERM")
+        .arg(pid.to_string())
+        .status()
+        .await
+        .map(|status| status.success())
+        .unwrap_or(false);
+    if !signalled {
+        return false;
+    }
+
+    let deadline = tokio::time::Instant::now() + BACKEND_STOP_GRACE;
+    while BACKEND_RUNNING.load(Ordering::SeqCst) {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    true
+}
+
+/// Windows has no SIGTERM for console processes, so the backend is killed.
+#[cfg(not(unix))]
+async fn request_backend_shutdown(_pid: u32) -> bool {
+    false
+}
+
+/// Stop the backend sidecar process, letting it flush pending outbox events
+/// first where the platform allows.
 pub async fn stop_backend_sidecar() -> Result<(), String> {
     log::info!("Stopping backend sidecar...");
 
     let mut process = BACKEND_PROCESS.lock().await;
     if let Some(child) = process.take() {
+        // BACKEND_RUNNING is only set once the backend is serving, so one
+        // that is still starting up is killed outright.
+        if BACKEND_RUNNING.load(Ordering::SeqCst) && request_backend_shutdown(child.pid()).await {
+            BACKEND_STARTING.store(false, Ordering::SeqCst);
+            log::info!("Backend sidecar stopped");
+            return Ok(());
+        }
+        log::warn!("Backend did not exit gracefully, killing it");
         child
             .kill()
             .map_err(|e| format!("Failed to kill backend process: {}", e))?;
@@ -39,6 +39,7 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_status,
+            commands::get_database_status,
             commands::restart_backend,
             commands::backup_database,
             commands::get_logs,
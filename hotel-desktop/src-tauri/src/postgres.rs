@@ -6,7 +6,7 @@
 //! - Running migrations
 //! - Health checks
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
@@ -32,6 +32,48 @@ const POSTGRES_USER: &str = "hotel_admin";
 const POSTGRES_DB: &str = "hotel_management";
 const MAX_STARTUP_WAIT_SECS: u64 = 30;
 
+/// Seed files loaded by the minimal profile: roles, permissions, the admin
+/// accounts and the room-type catalogue.
+const MINIMAL_SEED_FILES: &[&str] = &["01_system_roles_admin.sql", "03_room_types.sql"];
+
+/// Which seed dataset a fresh database gets, from `SEED_PROFILE`
+///
+/// `minimal` (the default) is for real use; `demo` also loads sample staff,
+/// rooms, rates, guests, bookings and loyalty members for evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedProfile {
+    Minimal,
+    Demo,
+}
+
+impl SeedProfile {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "minimal" => Some(Self::Minimal),
+            "demo" => Some(Self::Demo),
+            _ => None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("SEED_PROFILE") {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|| {
+                log::warn!("Unknown SEED_PROFILE {:?}; using minimal", value);
+                Self::Minimal
+            }),
+            Err(_) => Self::Minimal,
+        }
+    }
+
+    /// Whether the seed file `file_name` belongs to this profile.
+    pub fn includes(self, file_name: &str) -> bool {
+        match self {
+            Self::Minimal => MINIMAL_SEED_FILES.contains(&file_name),
+            Self::Demo => true,
+        }
+    }
+}
+
 /// Error types for PostgreSQL operations
 #[derive(Debug, thiserror::Error)]
 pub enum PostgresError {
@@ -77,6 +119,11 @@ fn get_pgdata_dir() -> PathBuf {
     get_data_directory().join("pgdata")
 }
 
+/// File recording the last migration applied to the bundled database
+fn get_schema_version_file() -> PathBuf {
+    get_data_directory().join("schema_version")
+}
+
 /// Check if PostgreSQL data directory is initialized
 fn is_pgdata_initialized() -> bool {
     get_pgdata_dir().join("PG_VERSION").exists()
@@ -453,12 +500,20 @@ pub async fn run_migrations_if_needed(app_handle: &AppHandle) -> Result<(), Post
     // Always run migrations - they use IF NOT EXISTS patterns and are idempotent
     // This ensures new migrations are applied even if the database was initialized before
     log::info!("Running database migrations...");
-    run_sql_files(app_handle, "database/migrations").await?;
+    if let Some(latest) = run_sql_files(app_handle, "database/migrations", |_| true).await? {
+        if let Err(e) = std::fs::write(get_schema_version_file(), &latest) {
+            log::warn!("Failed to record schema version {}: {}", latest, e);
+        }
+    }
 
     // Only run seed data if database was not previously initialized
     if !already_initialized {
-        log::info!("Running seed data...");
-        run_sql_files(app_handle, "database/seed-data").await?;
+        let profile = SeedProfile::from_env();
+        log::info!("Running {:?} seed data...", profile);
+        run_sql_files(app_handle, "database/seed-data", |name| {
+            profile.includes(name)
+        })
+        .await?;
     }
 
     log::info!("Database migrations completed successfully");
@@ -504,8 +559,13 @@ async fn is_database_initialized(app_handle: &AppHandle) -> Result<bool, Postgre
     Ok(result)
 }
 
-/// Run SQL files from a directory
-async fn run_sql_files(app_handle: &AppHandle, dir_name: &str) -> Result<(), PostgresError> {
+/// Run the SQL files in a directory accepted by `include`, returning the name
+/// (without extension) of the last file run
+async fn run_sql_files(
+    app_handle: &AppHandle,
+    dir_name: &str,
+    include: impl Fn(&str) -> bool,
+) -> Result<Option<String>, PostgresError> {
     let resource_dir = app_handle
         .path()
         .resource_dir()
@@ -523,13 +583,14 @@ async fn run_sql_files(app_handle: &AppHandle, dir_name: &str) -> Result<(), Pos
 
     if !sql_dir.exists() {
         log::warn!("SQL directory not found: {:?}", sql_dir);
-        return Ok(());
+        return Ok(None);
     }
 
     // Get all .sql files and sort them
     let mut sql_files: Vec<_> = std::fs::read_dir(&sql_dir)?
         .filter_map(|e| e.ok())
         .filter(|e| e.path().extension().map_or(false, |ext| ext == "sql"))
+        .filter(|e| include(&e.file_name().to_string_lossy()))
         .collect();
 
     sql_files.sort_by_key(|e| e.file_name());
@@ -544,6 +605,7 @@ async fn run_sql_files(app_handle: &AppHandle, dir_name: &str) -> Result<(), Pos
         current_path
     );
 
+    let mut last_run = None;
     for entry in sql_files {
         let file_path = entry.path();
         log::info!("Running SQL file: {:?}", file_path.file_name());
@@ -584,9 +646,13 @@ async fn run_sql_files(app_handle: &AppHandle, dir_name: &str) -> Result<(), Pos
                 stderr
             )));
         }
+
+        last_run = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
     }
 
-    Ok(())
+    Ok(last_run)
 }
 
 /// Get the DATABASE_URL for the backend
@@ -612,3 +678,242 @@ pub async fn get_postgres_status(app_handle: &AppHandle) -> serde_json::Value {
         "data_directory": pgdata.to_string_lossy(),
     })
 }
+
+/// Snapshot of the bundled PostgreSQL server
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DatabaseStatus {
+    /// The postmaster process recorded in the data directory is alive
+    pub process_alive: bool,
+    pub pid: Option<u32>,
+    /// `pg_isready` reports the server accepting connections
+    pub accepting_connections: bool,
+    pub initialized: bool,
+    pub data_directory: String,
+    pub port: u16,
+    pub database: String,
+    /// Last migration applied, e.g. `020_loyalty_tier_review`
+    pub schema_version: Option<String>,
+    /// Approximate on-disk size of the database, when it can be queried
+    pub size_bytes: Option<i64>,
+}
+
+impl DatabaseStatus {
+    fn new(
+        pgdata: &Path,
+        pid: Option<u32>,
+        accepting_connections: bool,
+        schema_version: Option<String>,
+        size_bytes: Option<i64>,
+    ) -> Self {
+        Self {
+            process_alive: pid.is_some(),
+            pid,
+            accepting_connections,
+            initialized: pgdata.join("PG_VERSION").exists(),
+            data_directory: pgdata.to_string_lossy().to_string(),
+            port: POSTGRES_PORT,
+            database: POSTGRES_DB.to_string(),
+            schema_version,
+            size_bytes,
+        }
+    }
+}
+
+/// Extract the postmaster PID from `pg_ctl status` output, which reads
+/// `pg_ctl: server is running (PID: 1234)` while the server is up.
+fn parse_pg_ctl_pid(output: &str) -> Option<u32> {
+    let start = output.find("(PID: ")? + "(PID: ".len();
+    let digits: String = output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// The bundled PostgreSQL server as seen from the desktop shell
+pub struct EmbeddedDatabase {
+    app_handle: AppHandle,
+}
+
+impl EmbeddedDatabase {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+        }
+    }
+
+    /// Report process liveness, data directory, schema version and size
+    pub async fn status(&self) -> DatabaseStatus {
+        let pid = self.postmaster_pid().await;
+        let accepting_connections = is_postgres_running(&self.app_handle).await;
+        let size_bytes = if accepting_connections {
+            self.database_size().await
+        } else {
+            None
+        };
+        let schema_version = std::fs::read_to_string(get_schema_version_file())
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        DatabaseStatus::new(
+            &get_pgdata_dir(),
+            pid,
+            accepting_connections,
+            schema_version,
+            size_bytes,
+        )
+    }
+
+    /// PID of the running postmaster, checked with `pg_ctl status`
+    async fn postmaster_pid(&self) -> Option<u32> {
+        let pgsql_bin = get_pgsql_bin_dir(&self.app_handle);
+        let pg_ctl_path = pgsql_bin.join(format!("pg_ctl{}", EXE_SUFFIX));
+
+        if !pg_ctl_path.exists() || !is_pgdata_initialized() {
+            return None;
+        }
+
+        let mut cmd = tokio::process::Command::new(&pg_ctl_path);
+        cmd.args(["status", "-D", &get_pgdata_dir().to_string_lossy()])
+            .current_dir(&pgsql_bin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let output = cmd.output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_pg_ctl_pid(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Size of the application database in bytes
+    async fn database_size(&self) -> Option<i64> {
+        let pgsql_bin = get_pgsql_bin_dir(&self.app_handle);
+        let psql_path = pgsql_bin.join(format!("psql{}", EXE_SUFFIX));
+
+        if !psql_path.exists() {
+            return None;
+        }
+
+        let mut cmd = tokio::process::Command::new(&psql_path);
+        cmd.args([
+            "-h",
+            "localhost",
+            "-p",
+            &POSTGRES_PORT.to_string(),
+            "-U",
+            POSTGRES_USER,
+            "-d",
+            POSTGRES_DB,
+            "-tAc",
+            &format!("SELECT pg_database_size('{}')", POSTGRES_DB),
+        ])
+        .current_dir(&pgsql_bin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let output = cmd.output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pid_from_pg_ctl_status() {
+        assert_eq!(
+            parse_pg_ctl_pid(
+                "pg_ctl: server is running (PID: 48213)\n/usr/bin/postgres \"-D\" ..."
+            ),
+            Some(48213)
+        );
+        assert_eq!(parse_pg_ctl_pid("pg_ctl: no server running"), None);
+    }
+
+    #[test]
+    fn status_serializes_a_live_server() {
+        let status = DatabaseStatus::new(
+            Path::new("/tmp/HotelApp/pgdata"),
+            Some(48213),
+            true,
+            Some("020_loyalty_tier_review".to_string()),
+            Some(9_437_184),
+        );
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["process_alive"], true);
+        assert_eq!(json["pid"], 48213);
+        assert_eq!(json["accepting_connections"], true);
+        assert_eq!(json["data_directory"], "/tmp/HotelApp/pgdata");
+        assert_eq!(json["port"], POSTGRES_PORT);
+        assert_eq!(json["database"], POSTGRES_DB);
+        assert_eq!(json["schema_version"], "020_loyalty_tier_review");
+        assert_eq!(json["size_bytes"], 9_437_184);
+    }
+
+    #[test]
+    fn status_serializes_a_stopped_server() {
+        let status = DatabaseStatus::new(Path::new("/nonexistent/pgdata"), None, false, None, None);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["process_alive"], false);
+        assert!(json["pid"].is_null());
+        assert_eq!(json["initialized"], false);
+        assert!(json["schema_version"].is_null());
+        assert!(json["size_bytes"].is_null());
+    }
+
+    fn seeded_sql(profile: SeedProfile) -> String {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("database/seed-data");
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".sql") && profile.includes(name))
+            .collect();
+        files.sort();
+        files
+            .iter()
+            .map(|name| std::fs::read_to_string(dir.join(name)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn minimal_seed_has_no_sample_bookings() {
+        let sql = seeded_sql(SeedProfile::Minimal);
+
+        assert!(sql.contains("INSERT INTO roles"));
+        assert!(sql.contains("INSERT INTO room_types"));
+        assert!(!sql.contains("INSERT INTO rooms"));
+        assert!(!sql.contains("INSERT INTO guests"));
+        assert!(!sql.contains("INSERT INTO bookings"));
+    }
+
+    #[test]
+    fn demo_seed_loads_sample_bookings() {
+        let sql = seeded_sql(SeedProfile::Demo);
+
+        assert!(sql.contains("INSERT INTO room_types"));
+        assert!(sql.contains("INSERT INTO rooms"));
+        assert!(sql.contains("INSERT INTO guests"));
+        assert!(sql.contains("INSERT INTO bookings"));
+    }
+
+    #[test]
+    fn seed_profile_parsing() {
+        assert_eq!(SeedProfile::parse(" Demo "), Some(SeedProfile::Demo));
+        assert_eq!(SeedProfile::parse("minimal"), Some(SeedProfile::Minimal));
+        assert_eq!(SeedProfile::parse("full"), None);
+    }
+}
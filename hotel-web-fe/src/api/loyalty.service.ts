@@ -14,6 +14,28 @@ import {
   RewardRedemption,
 } from '../types';
 
+interface LoyaltyPage<T> {
+  data: T[];
+  total: number;
+  page: number;
+  page_size: number;
+}
+
+// Loyalty lists are paginated server-side (max 200 per page); fetch every page.
+async function fetchAllPages<T>(path: string): Promise<T[]> {
+  const perPage = 200;
+  const first = await api.get(path, { searchParams: { page: 1, per_page: perPage } }).json<LoyaltyPage<T>>();
+  if (first.total <= perPage) return first.data;
+
+  const totalPages = Math.ceil(first.total / perPage);
+  const rest = await Promise.all(
+    Array.from({ length: totalPages - 1 }, (_, i) =>
+      api.get(path, { searchParams: { page: i + 2, per_page: perPage } }).json<LoyaltyPage<T>>()
+    )
+  );
+  return rest.reduce((acc, res) => acc.concat(res.data), first.data);
+}
+
 export class LoyaltyService {
   // Loyalty Program Operations
   static async getAllLoyaltyPrograms(): Promise<LoyaltyProgram[]> {
@@ -21,7 +43,7 @@ export class LoyaltyService {
   }
 
   static async getAllLoyaltyMemberships(): Promise<LoyaltyMembershipWithDetails[]> {
-    return await api.get('loyalty/memberships').json<LoyaltyMembershipWithDetails[]>();
+    return await fetchAllPages<LoyaltyMembershipWithDetails>('loyalty/memberships');
   }
 
   static async getLoyaltyMembershipsByGuest(guestId: string): Promise<LoyaltyMembership[]> {
@@ -123,7 +145,7 @@ export class LoyaltyService {
 
   static async getRewardRedemptions(): Promise<RewardRedemption[]> {
     try {
-      return await api.get('rewards/redemptions').json<RewardRedemption[]>();
+      return await fetchAllPages<RewardRedemption>('rewards/redemptions');
     } catch (error) {
       if (error instanceof HTTPError) {
         const errorData = await error.response.json().catch(() => ({}));
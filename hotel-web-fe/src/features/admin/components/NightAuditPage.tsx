@@ -49,6 +49,7 @@ import { NightAuditService, NightAuditPreview, NightAuditRun, UnpostedBooking, J
 import { TabPanel, getTabA11yProps } from '../../../components/common/TabPanel';
 import { formatCurrency } from '../../../utils/currency';
 import { getHotelSettings } from '../../../utils/hotelSettings';
+import { formatDateInTimeZone } from '../../../utils/date';
 
 // Journal Sections Display Component
 interface JournalSectionsDisplayProps {
@@ -185,10 +186,10 @@ function JournalSectionsDisplay({ sections }: JournalSectionsDisplayProps) {
 const NightAuditPage: React.FC = () => {
   // State
   const [tabValue, setTabValue] = useState(0);
-  const [auditDate, setAuditDate] = useState(() => {
-    const today = new Date();
-    return today.toISOString().split('T')[0];
-  });
+  // Default to the hotel's business day, not the browser's UTC date.
+  const [auditDate, setAuditDate] = useState(() =>
+    formatDateInTimeZone(getHotelSettings().timezone)
+  );
   const [preview, setPreview] = useState<NightAuditPreview | null>(null);
   const [auditHistory, setAuditHistory] = useState<NightAuditRun[]>([]);
   const [loading, setLoading] = useState(false);
@@ -142,6 +142,9 @@ interface UserLoyaltyMembership {
   };
   current_tier_benefits: string[];
   points_to_next_tier?: number;
+  current_tier_minimum: number;
+  progress_percent: number;
+  is_top_tier: boolean;
   recent_transactions: Array<{
     id: string;
     transaction_type: string;
@@ -376,16 +379,8 @@ const LoyaltyDashboard: React.FC = () => {
   };
 
   const getTierProgress = () => {
-    if (!membership || !membership.next_tier) return 100;
-
-    const currentPoints = membership.lifetime_points;
-    const nextTierPoints = membership.next_tier.minimum_points;
-    const currentTierMin = membership.tier_level === 1 ? 0 :
-      (membership.tier_level === 2 ? 1000 :
-       membership.tier_level === 3 ? 5000 : 10000);
-
-    const progress = ((currentPoints - currentTierMin) / (nextTierPoints - currentTierMin)) * 100;
-    return Math.min(Math.max(progress, 0), 100);
+    if (!membership || membership.is_top_tier) return 100;
+    return membership.progress_percent;
   };
 
   const filteredRewards = filterCategory === 'all'
@@ -1030,6 +1030,33 @@ const ModernReportsPage: React.FC = () => {
               </Table>
             </TableContainer>
           </Grid>
+
+          {/* By Company */}
+          {reportData.by_company?.length > 0 && (
+            <Grid size={12}>
+              <Typography variant="h6" gutterBottom>By Company</Typography>
+              <TableContainer component={Paper} variant="outlined">
+                <Table size="small">
+                  <TableHead>
+                    <TableRow sx={{ bgcolor: 'grey.100' }}>
+                      <TableCell>Company</TableCell>
+                      <TableCell align="right">Bookings</TableCell>
+                      <TableCell align="right">Revenue</TableCell>
+                    </TableRow>
+                  </TableHead>
+                  <TableBody>
+                    {reportData.by_company.map((c: any) => (
+                      <TableRow key={c.company_id}>
+                        <TableCell>{c.company_name}</TableCell>
+                        <TableCell align="right">{c.bookings}</TableCell>
+                        <TableCell align="right">{currencySymbol}{c.revenue?.toFixed(2)}</TableCell>
+                      </TableRow>
+                    ))}
+                  </TableBody>
+                </Table>
+              </TableContainer>
+            </Grid>
+          )}
         </Grid>
       </Box>
     );
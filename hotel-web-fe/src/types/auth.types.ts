@@ -17,6 +17,7 @@ export interface AuthResponse {
   roles: string[];
   permissions: string[];
   is_first_login: boolean;
+  must_change_password: boolean;
 }
 
 export interface UserProfile {
@@ -134,6 +134,7 @@ export interface BookingCreateRequest {
   deposit_amount?: number;
   room_rate_override?: number;
   daily_rates?: Record<string, number>;
+  company_id?: number;
 }
 
 export interface BookingUpdateRequest {
@@ -113,6 +113,9 @@ export interface UserLoyaltyMembership {
   next_tier?: TierInfo;
   current_tier_benefits: string[];
   points_to_next_tier?: number;
+  current_tier_minimum: number;
+  progress_percent: number;
+  is_top_tier: boolean;
   recent_transactions: PointsTransaction[];
 }
 
@@ -6,6 +6,21 @@ export const formatLocalDate = (date: Date = new Date()): string => {
   return `${year}-${month}-${day}`;
 };
 
+// The date in `timeZone` (an IANA name such as the hotel's timezone setting),
+// falling back to the browser's local date when the name is not recognised.
+export const formatDateInTimeZone = (timeZone: string, date: Date = new Date()): string => {
+  try {
+    return new Intl.DateTimeFormat('en-CA', {
+      timeZone,
+      year: 'numeric',
+      month: '2-digit',
+      day: '2-digit',
+    }).format(date);
+  } catch {
+    return formatLocalDate(date);
+  }
+};
+
 export const parseLocalDate = (dateString: string): Date => {
   const [datePart] = dateString.split('T');
   const [year, month, day] = datePart.split('-').map(Number);
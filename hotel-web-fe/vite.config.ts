@@ -10,6 +10,7 @@ const PROXY_PREFIXES = [
   '/loyalty', '/ledgers', '/companies', '/complimentary', '/roles',
   '/users', '/audit-logs', '/uploads', '/data-transfer', '/guest-portal',
   '/ekyc', '/reports', '/health', '/ws', '/system', '/search',
+  '/reservations',
 ];
 
 export default defineConfig(({ mode }) => {